@@ -0,0 +1,63 @@
+/**
+ * Load test against a running backend
+ * Drives /api/stream-chat on an already running server (npm start, the desktop sidecar, a
+ * deployment) instead of an in-process app, so numbers include the real process, its env, and
+ * its network path. By default the streams are answered by a local mock LLM server; pass
+ * --upstream to point at a real OpenAI-compatible API (key from QURIO_LOADTEST_API_KEY).
+ *
+ * npm run loadtest -- --url=http://127.0.0.1:3001 --streams=200 --concurrency=50 [--words=1000]
+ *   [--upstream=https://api.example.com/v1 --model=name] [--json]
+ * The mock listens on 127.0.0.1, so without --upstream the backend must run on this machine.
+ */
+
+import { startMockLlmServer } from '../test/support/mockLlmServer.js'
+import { numericArg, percentile, report } from './support/harness.js'
+import { buildAnswer, buildStreamChatBody, runStreamLoad } from './support/streams.js'
+
+const stringArg = (name, fallback) =>
+  process.argv.find(item => item.startsWith(`--${name}=`))?.slice(name.length + 3) || fallback
+
+const url = stringArg('url', `http://127.0.0.1:${process.env.PORT || 3001}`).replace(/\/$/, '')
+const upstream = stringArg('upstream', null)
+const streams = numericArg('streams', 100)
+const concurrency = numericArg('concurrency', 20)
+
+const health = await fetch(`${url}/api/health`).catch(error => error)
+if (!health.ok) {
+  console.error(`Backend not reachable at ${url}:`, health.message || `HTTP ${health.status}`)
+  process.exit(1)
+}
+
+const mock = upstream
+  ? null
+  : await startMockLlmServer({
+      defaultFixture: { content: buildAnswer(numericArg('words', 1000)), chunkSize: 16 },
+      record: false,
+    })
+const buildBody = index => ({
+  ...buildStreamChatBody(upstream || mock.baseUrl, index),
+  ...(upstream
+    ? { apiKey: process.env.QURIO_LOADTEST_API_KEY, model: stringArg('model', 'gpt-4o-mini') }
+    : {}),
+})
+
+try {
+  const startedAt = performance.now()
+  const load = await runStreamLoad({ baseUrl: url, buildBody, streams, concurrency })
+  const elapsedMs = performance.now() - startedAt
+  report([
+    {
+      name: `load ${url}`,
+      streams: load.streams,
+      concurrency,
+      failed: load.failed,
+      streams_per_sec: (load.streams / elapsedMs) * 1000,
+      events_per_sec: Math.round((load.events / elapsedMs) * 1000),
+      first_event_p50_ms: percentile(load.firstEventMs, 50),
+      first_event_p95_ms: percentile(load.firstEventMs, 95),
+      stream_p95_ms: percentile(load.totalMs, 95),
+    },
+  ])
+} finally {
+  await mock?.close()
+}
@@ -0,0 +1,121 @@
+/**
+ * Parser benchmarks: the custom provider stream decoders (readServerSentEvents for Anthropic,
+ * readJsonLines for Ollama's NDJSON) and <think> tag splitting (createTaggedTextParser, run on
+ * every streamed text chunk), fed synthetic provider output in realistic network-sized chunks.
+ *
+ * npm run bench:parsers [-- --events=20000 --iterations=30 --json]
+ */
+
+import { readJsonLines } from '../src/services/providers/OllamaChatModel.js'
+import { readServerSentEvents } from '../src/utils/sse.js'
+import { createTaggedTextParser } from '../src/utils/taggedText.js'
+import { bench, numericArg, report } from './support/harness.js'
+
+const EVENTS = numericArg('events', 20000)
+const ITERATIONS = numericArg('iterations', 30)
+const NETWORK_CHUNK_BYTES = 1400
+
+// Anthropic-style stream: content_block_delta events with short text deltas, some non-ASCII
+const buildSseBytes = events => {
+  const words = ['token', 'stream', 'latency', '吞吐量', 'résumé', 'parser', '😀', 'delta']
+  let text = 'event: message_start\ndata: {"type":"message_start","message":{"usage":{}}}\n\n'
+  for (let i = 0; i < events; i += 1) {
+    const delta = { type: 'text_delta', text: ` ${words[i % words.length]}` }
+    text += `event: content_block_delta\ndata: ${JSON.stringify({
+      type: 'content_block_delta',
+      index: 0,
+      delta,
+    })}\n\n`
+    if (i % 500 === 0) text += 'event: ping\ndata: {"type":"ping"}\n\n'
+  }
+  return new TextEncoder().encode(text)
+}
+
+// Ollama-style stream: one JSON object per line
+const buildNdjsonBytes = events => {
+  let text = ''
+  for (let i = 0; i < events; i += 1) {
+    text += `${JSON.stringify({
+      model: 'mock-model',
+      message: { role: 'assistant', content: ` word${i % 100}` },
+      done: false,
+    })}\n`
+  }
+  text += `${JSON.stringify({ model: 'mock-model', done: true, eval_count: events })}\n`
+  return new TextEncoder().encode(text)
+}
+
+const toChunks = (bytes, size) => {
+  const chunks = []
+  for (let offset = 0; offset < bytes.length; offset += size) {
+    chunks.push(bytes.subarray(offset, offset + size))
+  }
+  return chunks
+}
+
+async function* toBody(chunks) {
+  for (const chunk of chunks) yield chunk
+}
+
+// Reasoning-model output: a think block, then the answer, split into small deltas
+const buildTaggedChunks = events => {
+  const chunks = ['<think>']
+  for (let i = 0; i < events; i += 1) {
+    if (i === Math.floor(events / 3)) chunks.push('</thi', 'nk>')
+    chunks.push(i % 7 === 0 ? ' a <b> c' : ' word')
+  }
+  return chunks
+}
+
+const sseChunks = toChunks(buildSseBytes(EVENTS), NETWORK_CHUNK_BYTES)
+const sseBytes = sseChunks.reduce((sum, chunk) => sum + chunk.length, 0)
+const ndjsonChunks = toChunks(buildNdjsonBytes(EVENTS), NETWORK_CHUNK_BYTES)
+const taggedChunks = buildTaggedChunks(EVENTS)
+
+const results = [
+  await bench(
+    'readServerSentEvents',
+    async () => {
+      let events = 0
+      for await (const message of readServerSentEvents(toBody(sseChunks))) {
+        JSON.parse(message.data)
+        events += 1
+      }
+      return events
+    },
+    { iterations: ITERATIONS },
+  ),
+  await bench(
+    'readServerSentEvents (bytes)',
+    async () => {
+      for await (const message of readServerSentEvents(toBody(sseChunks))) void message
+      return sseBytes
+    },
+    { iterations: ITERATIONS, unit: 'bytes' },
+  ),
+  await bench(
+    'readJsonLines',
+    async () => {
+      let events = 0
+      for await (const line of readJsonLines(toBody(ndjsonChunks))) events += line ? 1 : 0
+      return events
+    },
+    { iterations: ITERATIONS },
+  ),
+  await bench(
+    'createTaggedTextParser',
+    () => {
+      let emitted = 0
+      const handle = createTaggedTextParser({
+        emitText: () => (emitted += 1),
+        emitThought: () => (emitted += 1),
+      })
+      for (const chunk of taggedChunks) handle(chunk)
+      handle.flush()
+      return taggedChunks.length
+    },
+    { iterations: ITERATIONS },
+  ),
+]
+
+report(results)
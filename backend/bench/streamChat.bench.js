@@ -0,0 +1,57 @@
+/**
+ * Stream chat benchmark: the full /api/stream-chat pipeline (agent loop, provider streaming,
+ * think-tag parsing, SSE writes) in-process against the mock LLM server, at increasing
+ * concurrency. Reports delivered events/sec, time to first event, and peak heap per open stream.
+ *
+ * npm run bench:stream [-- --words=2000 --streams=40 --json]
+ * Set SSE_FLUSH_MS to compare write coalescing settings (default 50, as in .env.example).
+ */
+
+import { startMockLlmServer } from '../test/support/mockLlmServer.js'
+import { startTestApp } from '../test/support/testApp.js'
+import { numericArg, percentile, report, startMemorySampler } from './support/harness.js'
+import { buildAnswer, buildStreamChatBody, runStreamLoad } from './support/streams.js'
+
+const WORDS = numericArg('words', 2000)
+const STREAMS = numericArg('streams', 40)
+const CONCURRENCY_LEVELS = [1, 10, 40]
+
+const mock = await startMockLlmServer({
+  defaultFixture: { content: buildAnswer(WORDS), chunkSize: 16 },
+  record: false,
+})
+const app = await startTestApp({ env: { SSE_FLUSH_MS: process.env.SSE_FLUSH_MS || '50' } })
+const buildBody = index => buildStreamChatBody(mock.baseUrl, index)
+
+try {
+  // Warm up module loading, the agent graph, and the HTTP connection pool
+  await runStreamLoad({ baseUrl: app.baseUrl, buildBody, streams: 3, concurrency: 3 })
+
+  const results = []
+  for (const concurrency of CONCURRENCY_LEVELS) {
+    const sampler = startMemorySampler(10)
+    const startedAt = performance.now()
+    const load = await runStreamLoad({
+      baseUrl: app.baseUrl,
+      buildBody,
+      streams: Math.max(STREAMS, concurrency),
+      concurrency,
+    })
+    const elapsedMs = performance.now() - startedAt
+    const memory = sampler.stop()
+    results.push({
+      name: `stream-chat x${concurrency}`,
+      streams: load.streams,
+      failed: load.failed,
+      events_per_sec: Math.round((load.events / elapsedMs) * 1000),
+      first_event_p50_ms: percentile(load.firstEventMs, 50),
+      stream_p95_ms: percentile(load.totalMs, 95),
+      heap_per_stream_mb: memory.heap_peak_mb / concurrency,
+      rss_peak_mb: memory.rss_peak_mb,
+    })
+  }
+  report(results)
+} finally {
+  await app.close()
+  await mock.close()
+}
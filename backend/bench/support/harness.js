@@ -0,0 +1,108 @@
+/**
+ * Benchmark helpers
+ * Timed runs with warmup, throughput and latency percentiles, and heap/RSS sampling. Run with
+ * --expose-gc (npm run bench does) so memory is measured from a collected baseline.
+ */
+
+import { performance } from 'perf_hooks'
+
+const MB = 1024 * 1024
+
+export const collectGarbage = () => globalThis.gc?.()
+
+/**
+ * Nearest-rank percentile of unsorted values
+ */
+export const percentile = (values, p) => {
+  const sorted = [...values].sort((a, b) => a - b)
+  return sorted[Math.min(sorted.length - 1, Math.floor((p / 100) * sorted.length))] ?? 0
+}
+
+/**
+ * Sample heap and RSS every intervalMs until stopped; reports peaks above the starting baseline
+ */
+export const startMemorySampler = (intervalMs = 20) => {
+  collectGarbage()
+  const baseline = process.memoryUsage()
+  let peakHeap = baseline.heapUsed
+  let peakRss = baseline.rss
+  const sample = () => {
+    const { heapUsed, rss } = process.memoryUsage()
+    peakHeap = Math.max(peakHeap, heapUsed)
+    peakRss = Math.max(peakRss, rss)
+  }
+  const timer = setInterval(sample, intervalMs)
+  return {
+    stop: () => {
+      clearInterval(timer)
+      sample()
+      return {
+        heap_peak_mb: (peakHeap - baseline.heapUsed) / MB,
+        rss_peak_mb: (peakRss - baseline.rss) / MB,
+      }
+    },
+  }
+}
+
+/**
+ * Run fn repeatedly and report throughput
+ * @param {string} name
+ * @param {() => Promise<number>|number} fn - One iteration; returns how many units it processed
+ *   (events, bytes, ...)
+ * @param {Object} [options]
+ * @param {number} [options.iterations] - Default 50
+ * @param {number} [options.warmup] - Untimed iterations first (default 5)
+ * @param {string} [options.unit] - Name of what fn counts (default "events")
+ */
+export const bench = async (name, fn, { iterations = 50, warmup = 5, unit = 'events' } = {}) => {
+  for (let i = 0; i < warmup; i += 1) await fn()
+
+  const sampler = startMemorySampler()
+  const durations = []
+  let units = 0
+  const startedAt = performance.now()
+  for (let i = 0; i < iterations; i += 1) {
+    const iterationStartedAt = performance.now()
+    units += await fn()
+    durations.push(performance.now() - iterationStartedAt)
+  }
+  const totalMs = performance.now() - startedAt
+  const memory = sampler.stop()
+
+  return {
+    name,
+    iterations,
+    [`${unit}_per_sec`]: Math.round((units / totalMs) * 1000),
+    mean_ms: totalMs / iterations,
+    p50_ms: percentile(durations, 50),
+    p95_ms: percentile(durations, 95),
+    ...memory,
+  }
+}
+
+const formatValue = value =>
+  typeof value === 'number' && !Number.isInteger(value) ? value.toFixed(2) : String(value)
+
+/**
+ * Print results as a table, or as JSON lines with --json (for comparing runs)
+ */
+export const report = results => {
+  if (process.argv.includes('--json')) {
+    for (const result of results) console.log(JSON.stringify(result))
+    return
+  }
+  console.table(
+    results.map(result =>
+      Object.fromEntries(Object.entries(result).map(([key, value]) => [key, formatValue(value)])),
+    ),
+  )
+}
+
+/**
+ * Read a numeric --name=value argument
+ */
+export const numericArg = (name, fallback) => {
+  const arg = process.argv.find(item => item.startsWith(`--${name}=`))
+  const value = Number.parseInt(arg?.slice(name.length + 3), 10)
+  return Number.isFinite(value) && value > 0 ? value : fallback
+}
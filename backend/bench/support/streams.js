@@ -0,0 +1,72 @@
+/**
+ * Streaming load generator
+ * Opens many concurrent /api/stream-chat requests against a backend and measures delivered
+ * events, time to first event, and total time per stream.
+ */
+
+import { performance } from 'perf_hooks'
+import { readServerSentEvents } from '../../src/utils/sse.js'
+
+// A long answer split into small deltas, the shape that stresses per-event overhead
+export const buildAnswer = words =>
+  Array.from({ length: words }, (_, i) => `word${i % 100}`).join(' ')
+
+export const buildStreamChatBody = (mockBaseUrl, index) => ({
+  provider: 'openai',
+  apiKey: 'bench-key',
+  baseUrl: mockBaseUrl,
+  model: 'mock-model',
+  messages: [{ role: 'user', content: `Benchmark stream ${index}` }],
+})
+
+const runOneStream = async (url, body) => {
+  const startedAt = performance.now()
+  const response = await fetch(url, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(body),
+  })
+  if (!response.ok) throw new Error(`HTTP ${response.status}: ${await response.text()}`)
+
+  let events = 0
+  let firstEventMs = null
+  let failed = false
+  for await (const message of readServerSentEvents(response.body)) {
+    if (!message.data || message.data === '[DONE]') continue
+    events += 1
+    firstEventMs ??= performance.now() - startedAt
+    if (JSON.parse(message.data).type === 'error') failed = true
+  }
+  return { events, firstEventMs, totalMs: performance.now() - startedAt, failed }
+}
+
+/**
+ * Run `streams` stream-chat requests, at most `concurrency` at a time
+ * @param {Object} args
+ * @param {string} args.baseUrl - Backend root, e.g. http://127.0.0.1:3001
+ * @param {(index: number) => Object} args.buildBody
+ * @param {number} args.streams
+ * @param {number} args.concurrency
+ * @returns {Promise<Object>} { events, streams, failed, firstEventMs[], totalMs[] }
+ */
+export const runStreamLoad = async ({ baseUrl, buildBody, streams, concurrency }) => {
+  const url = `${baseUrl}/api/stream-chat`
+  const results = []
+  let next = 0
+  const worker = async () => {
+    while (next < streams) {
+      const index = next
+      next += 1
+      results.push(await runOneStream(url, buildBody(index)))
+    }
+  }
+  await Promise.all(Array.from({ length: Math.min(concurrency, streams) }, worker))
+
+  return {
+    streams: results.length,
+    events: results.reduce((sum, result) => sum + result.events, 0),
+    failed: results.filter(result => result.failed).length,
+    firstEventMs: results.map(result => result.firstEventMs ?? result.totalMs),
+    totalMs: results.map(result => result.totalMs),
+  }
+}
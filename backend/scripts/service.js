@@ -0,0 +1,64 @@
+/**
+ * Register the Qurio backend as an OS service (launchd / systemd user unit / Windows logon task)
+ *
+ * Usage:
+ *   node scripts/service.js print [platform]
+ *   node scripts/service.js install [platform]
+ *   node scripts/service.js uninstall [platform]
+ */
+
+import { execSync } from 'child_process'
+import fs from 'fs'
+import path from 'path'
+import { buildServiceDefinition } from '../src/services/serviceRegistration.js'
+
+const [command = 'print', platform] = process.argv.slice(2)
+
+const run = commands => {
+  for (const cmd of commands) {
+    console.log(`> ${cmd}`)
+    execSync(cmd, { stdio: 'inherit' })
+  }
+}
+
+const main = () => {
+  const definition = buildServiceDefinition(platform)
+
+  if (command === 'print') {
+    if (definition.path) console.log(`# ${definition.path}\n${definition.content}`)
+    console.log('# install')
+    definition.install.forEach(cmd => console.log(cmd))
+    console.log('# uninstall')
+    definition.uninstall.forEach(cmd => console.log(cmd))
+    return
+  }
+
+  if (command === 'install') {
+    if (definition.path) {
+      fs.mkdirSync(path.dirname(definition.path), { recursive: true })
+      fs.writeFileSync(definition.path, definition.content)
+      console.log(`Wrote ${definition.path}`)
+    }
+    run(definition.install)
+    return
+  }
+
+  if (command === 'uninstall') {
+    run(definition.uninstall)
+    if (definition.path && fs.existsSync(definition.path)) {
+      fs.unlinkSync(definition.path)
+      console.log(`Removed ${definition.path}`)
+    }
+    return
+  }
+
+  console.error(`Unknown command: ${command}. Use print, install, or uninstall.`)
+  process.exitCode = 1
+}
+
+try {
+  main()
+} catch (error) {
+  console.error(`[Service] ${error.message}`)
+  process.exitCode = 1
+}
@@ -0,0 +1,184 @@
+/**
+ * Qurio Backend App
+ * Builds the Express app (middleware and routes); server.js listens on it, and the integration
+ * tests mount it on an ephemeral port (see test/support/testApp.js)
+ */
+
+import express from 'express'
+import cors from 'cors'
+import { LLM_ROUTES, rateLimitProviders } from './middleware/rateLimit.js'
+import { applySpaceCredentials } from './middleware/spaceCredentials.js'
+import { TRACED_ROUTES, traceRequests } from './middleware/tracing.js'
+import titleSpaceAgentRoutes from './routes/titleSpaceAgent.js'
+import titleRoutes from './routes/title.js'
+import researchPlanRoutes from './routes/researchPlan.js'
+import dailyTipRoutes from './routes/dailyTip.js'
+import titleAndSpaceRoutes from './routes/titleAndSpace.js'
+import agentForAutoRoutes from './routes/agentForAuto.js'
+import agentsRoutes from './routes/agents.js'
+import relatedQuestionsRoutes from './routes/relatedQuestions.js'
+import streamChatRoutes from './routes/streamChat.js'
+import deepResearchChatRoutes from './routes/deepResearchChat.js'
+import toolsRoutes from './routes/tools.js'
+import mcpToolsRoutes from './routes/mcpTools.js'
+import backgroundRoutes from './routes/background.js'
+import quickAskRoutes from './routes/quickAsk.js'
+import warmupRoutes from './routes/warmup.js'
+import researchRunsRoutes from './routes/researchRuns.js'
+import spaceCredentialsRoutes from './routes/spaceCredentials.js'
+import systemPromptsRoutes from './routes/systemPrompts.js'
+import savedPromptsRoutes from './routes/savedPrompts.js'
+import textEditRoutes from './routes/textEdit.js'
+import proofreadRoutes from './routes/proofread.js'
+import preferencesRoutes from './routes/preferences.js'
+import snippetsRoutes from './routes/snippets.js'
+import codeBlocksRoutes from './routes/codeBlocks.js'
+import codeIndexRoutes from './routes/codeIndex.js'
+import sqlConnectionsRoutes from './routes/sqlConnections.js'
+import httpToolRoutes from './routes/httpTool.js'
+import modelsRoutes from './routes/models.js'
+import conversationsRoutes from './routes/conversations.js'
+import streamsRoutes from './routes/streams.js'
+import sessionsRoutes from './routes/sessions.js'
+import ragRoutes from './routes/rag.js'
+import usageRoutes from './routes/usage.js'
+import sourceChecksRoutes from './routes/sourceChecks.js'
+import screenshotsRoutes from './routes/screenshots.js'
+import titleProposalsRoutes from './routes/titleProposals.js'
+import embeddingsRoutes from './routes/embeddings.js'
+import watchlistRoutes from './routes/watchlist.js'
+import spaceGlossariesRoutes from './routes/spaceGlossaries.js'
+import uploadsRoutes from './routes/uploads.js'
+import tasksRoutes from './routes/tasks.js'
+import storageRoutes from './routes/storage.js'
+import researchExportRoutes from './routes/researchExport.js'
+import providerHealthRoutes from './routes/providerHealth.js'
+import transcribeRoutes from './routes/transcribe.js'
+import imagesRoutes from './routes/images.js'
+import tracesRoutes from './routes/traces.js'
+import kimiFilesRoutes from './routes/kimiFiles.js'
+import { createRateLimiter } from './services/providers/rateLimiter.js'
+import { detectRuntimes, redetectRuntimes } from './services/runtimeCapabilities.js'
+import { sendError } from './utils/errors.js'
+
+/**
+ * Create the Express app
+ * @param {Object} [options]
+ * @param {string} [options.frontendUrls] - Comma-separated CORS origins (default FRONTEND_URLS)
+ * @param {Object} [options.rateLimiter] - Per-provider rate limiter (default from RATE_LIMIT_* env)
+ */
+export const createApp = ({
+  frontendUrls = process.env.FRONTEND_URLS,
+  rateLimiter = createRateLimiter(),
+} = {}) => {
+  const app = express()
+  const allowedOrigins = new Set(
+    (frontendUrls || 'http://localhost:3000')
+      .split(',')
+      .map(origin => origin.trim())
+      .filter(Boolean),
+  )
+
+  // Read by the WebSocket routes, which the HTTP server attaches outside Express (routes/wsChat.js)
+  app.locals.allowedOrigins = allowedOrigins
+  app.locals.rateLimiter = rateLimiter
+
+  // Middleware
+  app.use(
+    cors({
+      origin(origin, callback) {
+        if (!origin || allowedOrigins.has(origin)) {
+          return callback(null, true)
+        }
+        return callback(new Error(`CORS blocked origin: ${origin}`))
+      },
+      credentials: true,
+      exposedHeaders: ['X-Trace-Id'],
+    }),
+  )
+  app.use(express.json())
+  // Space-pinned provider credentials override request-level ones
+  app.use('/api', applySpaceCredentials)
+  // Before rate limiting, so time spent queued shows up in the trace
+  app.use(TRACED_ROUTES, traceRequests)
+  // After space credentials, so requests are metered against the key actually used
+  app.use(LLM_ROUTES, rateLimitProviders(rateLimiter))
+
+  // Health check endpoint; mode is "degraded" when a program some features need is missing
+  // (?recheck=1 probes again, e.g. after installing it)
+  app.get('/api/health', async (req, res) => {
+    const { mode, runtimes, unavailable_features } = await (req.query.recheck
+      ? redetectRuntimes()
+      : detectRuntimes())
+    res.json({
+      status: 'ok',
+      message: 'Qurio backend is running',
+      mode,
+      runtimes,
+      unavailable_features,
+    })
+  })
+
+  app.use('/api', titleSpaceAgentRoutes)
+  app.use('/api', titleRoutes)
+  app.use('/api', researchPlanRoutes)
+  app.use('/api', dailyTipRoutes)
+  app.use('/api', titleAndSpaceRoutes)
+  app.use('/api', agentForAutoRoutes)
+  app.use('/api', relatedQuestionsRoutes)
+  app.use('/api', streamChatRoutes)
+  app.use('/api', deepResearchChatRoutes)
+  app.use('/api', toolsRoutes)
+  app.use('/api', quickAskRoutes)
+  app.use('/api', warmupRoutes)
+  app.use('/api', researchRunsRoutes)
+  app.use('/api', spaceCredentialsRoutes)
+  // Before saved prompts: both live under /api/prompts and unknown names fall through
+  app.use('/api', systemPromptsRoutes)
+  app.use('/api', savedPromptsRoutes)
+  app.use('/api', textEditRoutes)
+  app.use('/api', proofreadRoutes)
+  app.use('/api', preferencesRoutes)
+  app.use('/api', snippetsRoutes)
+  app.use('/api', codeBlocksRoutes)
+  app.use('/api', codeIndexRoutes)
+  app.use('/api', sqlConnectionsRoutes)
+  app.use('/api', httpToolRoutes)
+  app.use('/api', modelsRoutes)
+  app.use('/api', conversationsRoutes)
+  app.use('/api', agentsRoutes)
+  app.use('/api', streamsRoutes)
+  app.use('/api', sessionsRoutes)
+  app.use('/api', ragRoutes)
+  app.use('/api', usageRoutes)
+  app.use('/api', sourceChecksRoutes)
+  app.use('/api', screenshotsRoutes)
+  app.use('/api', titleProposalsRoutes)
+  app.use('/api', embeddingsRoutes)
+  app.use('/api', watchlistRoutes)
+  app.use('/api', spaceGlossariesRoutes)
+  app.use('/api', uploadsRoutes)
+  app.use('/api', tasksRoutes)
+  app.use('/api', storageRoutes)
+  app.use('/api', researchExportRoutes)
+  app.use('/api', providerHealthRoutes)
+  app.use('/api', transcribeRoutes)
+  app.use('/api', imagesRoutes)
+  app.use('/api', tracesRoutes)
+  app.use('/api', kimiFilesRoutes)
+  app.use('/api/mcp-tools', mcpToolsRoutes)
+  app.use('/api/background', backgroundRoutes)
+
+  // 404 handler
+  app.use((req, res) => {
+    res.status(404).json({ error: 'Not found' })
+  })
+
+  // Error handler
+  app.use((err, req, res, next) => {
+    console.error(err.stack)
+    sendError(res, err, 'Internal server error')
+  })
+
+  return app
+}
@@ -0,0 +1,90 @@
+/**
+ * Provider rate limit middleware
+ * Holds requests to the LLM routes against the app's rate limiter (services/providers/
+ * rateLimiter.js), keyed by the request's provider and API key after space credentials are
+ * applied. Many tabs sharing a key then queue here instead of tripping the provider's own 429s;
+ * a request that may not wait gets 429 with a Retry-After header. The share taken here covers
+ * the request's first model call; the route runs in a rate limit context, so every further model
+ * call it makes (a deep research run makes dozens) takes its own share.
+ */
+
+import { estimateRequestTokens, runWithRateLimiter } from '../services/providers/rateLimiter.js'
+import { getResearchRun } from '../services/researchRunStore.js'
+import { addSpanEvent } from '../services/tracing.js'
+import { sendError } from '../utils/errors.js'
+
+// Routes that call a provider model; image generation, transcription and embeddings are metered
+// by the same key
+export const LLM_ROUTES = [
+  '/api/title',
+  '/api/title-and-space',
+  '/api/title-space-agent',
+  '/api/research-plan',
+  '/api/research-plan-stream',
+  '/api/daily-tip',
+  '/api/agent-for-auto',
+  '/api/related-questions',
+  '/api/stream-chat',
+  '/api/stream-deep-research',
+  '/api/research-runs/:id/resume',
+  '/api/deep-research/:id/followup',
+  '/api/quick-ask',
+  '/api/edit-text',
+  '/api/proofread',
+  '/api/prompts/:id/run',
+  '/api/images/generate',
+  '/api/transcribe',
+  '/api/embeddings',
+]
+
+// Resume and follow-up bodies carry only the key; the provider is the stored run's
+const RUN_ROUTE = /^\/api\/(?:research-runs|deep-research)\/([^/]+)\/(?:resume|followup)$/
+
+/**
+ * Provider, key and estimated tokens a request is metered by
+ * @returns {{ provider?: string, apiKey?: string, tokens: number }}
+ */
+const resolveMeteredRequest = req => {
+  // Raw audio uploads (POST /api/transcribe) name the provider in the query
+  const body =
+    req.body && typeof req.body === 'object' && !Buffer.isBuffer(req.body) ? req.body : {}
+  const apiKey = body.apiKey || req.get('x-api-key')
+  const tokens = estimateRequestTokens(body)
+  if (body.provider) return { provider: body.provider, apiKey, tokens }
+  const runId = req.originalUrl.split('?')[0].match(RUN_ROUTE)?.[1]
+  if (runId) return { provider: getResearchRun(runId)?.request?.provider, apiKey, tokens }
+  return { provider: req.query?.provider, apiKey, tokens: 0 }
+}
+
+/**
+ * @param {{ acquire: Function }} rateLimiter - From createRateLimiter()
+ */
+export const rateLimitProviders = rateLimiter => async (req, res, next) => {
+  if (req.method !== 'POST') return next()
+  const { provider, apiKey, tokens } = resolveMeteredRequest(req)
+  if (!provider) return next()
+
+  // A client that gives up while queued releases its place
+  const controller = new AbortController()
+  const onClose = () => controller.abort()
+  res.once('close', onClose)
+  try {
+    const { waitedMs } = await rateLimiter.acquire({
+      provider,
+      apiKey,
+      tokens,
+      signal: controller.signal,
+    })
+    if (waitedMs) {
+      console.log(`[RateLimit] ${provider} request queued for ${waitedMs}ms`)
+      addSpanEvent('rate_limit_queued', { provider, waited_ms: waitedMs })
+    }
+  } catch (error) {
+    if (controller.signal.aborted) return
+    if (error.retryAfterMs) res.set('Retry-After', String(Math.ceil(error.retryAfterMs / 1000)))
+    return sendError(res, error, 'Rate limit reached')
+  } finally {
+    res.off('close', onClose)
+  }
+  runWithRateLimiter({ rateLimiter, provider, apiKey }, next)
+}
@@ -0,0 +1,28 @@
+/**
+ * Space credential middleware
+ * When a request names a space with pinned credentials, those override the request-level
+ * provider, apiKey, baseUrl (and model when pinned) before the route handler runs.
+ */
+
+import { resolveSpaceCredentials } from '../services/keyVault.js'
+
+export const applySpaceCredentials = (req, res, next) => {
+  const body = req.body
+  if (!body || typeof body !== 'object' || Array.isArray(body)) return next()
+  const spaceId = body.spaceId ?? body.space_id
+  if (!spaceId) return next()
+
+  const pinned = resolveSpaceCredentials(spaceId)
+  if (!pinned) return next()
+
+  const providerChanged = body.provider && body.provider !== pinned.provider
+  body.provider = pinned.provider
+  body.apiKey = pinned.apiKey
+  body.baseUrl = pinned.baseUrl
+  // A model picked for another provider would not exist on the pinned one
+  if (pinned.model) body.model = pinned.model
+  else if (providerChanged) body.model = undefined
+
+  req.spaceCredentials = { spaceId: String(spaceId), provider: pinned.provider }
+  next()
+}
@@ -0,0 +1,37 @@
+/**
+ * Tracing middleware
+ * Runs each request to the LLM routes inside a trace (services/tracing.js). The trace id is the
+ * request's X-Trace-Id header when it is a valid id, otherwise generated, and is returned in the
+ * X-Trace-Id response header (and in stream_started events) for GET /api/traces/:id.
+ */
+
+import { LLM_ROUTES } from './rateLimit.js'
+import { finishTrace, runInTrace, startTrace } from '../services/tracing.js'
+
+export const TRACED_ROUTES = LLM_ROUTES
+
+const traceKind = urlPath => {
+  if (/deep-research|research-runs/.test(urlPath)) return 'research'
+  return /chat/.test(urlPath) ? 'chat' : 'request'
+}
+
+export const traceRequests = (req, res, next) => {
+  const urlPath = req.originalUrl.split('?')[0]
+  const body = req.body && typeof req.body === 'object' ? req.body : {}
+  const started = startTrace({
+    id: req.get('x-trace-id'),
+    name: `${req.method} ${urlPath}`,
+    kind: traceKind(urlPath),
+    attributes: { provider: body.provider, model: body.model, request: body },
+  })
+  if (!started) return next()
+
+  res.setHeader('X-Trace-Id', started.trace.id)
+  res.once('close', () => {
+    finishTrace(started.trace, {
+      error: res.statusCode >= 400 ? `HTTP ${res.statusCode}` : null,
+      attributes: { status_code: res.statusCode, completed: res.writableFinished },
+    })
+  })
+  runInTrace(started.context, next)
+}
@@ -0,0 +1,152 @@
+/**
+ * Prompt store
+ * Resolves the system prompts in systemPrompts.js against user overrides saved as plain text
+ * files in the data dir (<data dir>/prompts/<name>.md), and fills in {{variable}} placeholders.
+ * Overrides are read on every render, so a hand-edited file applies to the next request without
+ * a restart; deleting the file restores the built-in prompt.
+ */
+
+import fs from 'fs'
+import path from 'path'
+import { resolveCollectionDir } from '../utils/dataStore.js'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+import { SYSTEM_PROMPTS } from './systemPrompts.js'
+
+const COLLECTION = 'prompts'
+const PLACEHOLDER_PATTERN = /\{\{\s*([a-zA-Z_]\w*)\s*\}\}/g
+const MAX_TEMPLATE_LENGTH = 200000
+
+export const isSystemPromptName = name => Object.hasOwn(SYSTEM_PROMPTS, name)
+
+const requirePrompt = name => {
+  if (!isSystemPromptName(name)) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Unknown prompt: ${name}`, { status: 404 })
+  }
+  return SYSTEM_PROMPTS[name]
+}
+
+// Names are fixed keys of SYSTEM_PROMPTS, so they are safe as file names
+const resolveOverridePath = name => path.join(resolveCollectionDir(COLLECTION), `${name}.md`)
+
+const readOverride = name => {
+  const filePath = resolveOverridePath(name)
+  if (!fs.existsSync(filePath)) return null
+  try {
+    const stat = fs.statSync(filePath)
+    return { template: fs.readFileSync(filePath, 'utf8'), updatedAt: stat.mtime.toISOString() }
+  } catch (error) {
+    console.warn(`[Prompts] Failed to read override ${name}:`, error.message)
+    return null
+  }
+}
+
+const describePrompt = (name, { includeTemplates = true } = {}) => {
+  const { description, variables, template: defaultTemplate } = SYSTEM_PROMPTS[name]
+  const override = readOverride(name)
+  return {
+    name,
+    description,
+    variables: Object.entries(variables).map(([variable, spec]) => ({ name: variable, ...spec })),
+    customized: Boolean(override),
+    updated_at: override?.updatedAt || null,
+    ...(includeTemplates
+      ? { template: override?.template ?? defaultTemplate, default_template: defaultTemplate }
+      : {}),
+  }
+}
+
+/**
+ * List every overridable prompt (without template bodies)
+ */
+export const listSystemPrompts = () =>
+  Object.keys(SYSTEM_PROMPTS).map(name => describePrompt(name, { includeTemplates: false }))
+
+/**
+ * Get one prompt with its effective and default template
+ */
+export const getSystemPrompt = name => {
+  requirePrompt(name)
+  return describePrompt(name)
+}
+
+/**
+ * Check a template against the prompt's variables
+ * @returns {string[]} Problems found (empty when valid)
+ */
+export const validatePromptOverride = (name, template) => {
+  const { variables } = requirePrompt(name)
+  if (typeof template !== 'string' || !template.trim()) {
+    return ['template must be a non-empty string']
+  }
+  if (template.length > MAX_TEMPLATE_LENGTH) {
+    return [`template must be at most ${MAX_TEMPLATE_LENGTH} characters`]
+  }
+
+  const errors = []
+  const used = new Set([...template.matchAll(PLACEHOLDER_PATTERN)].map(([, variable]) => variable))
+  for (const variable of used) {
+    if (!Object.hasOwn(variables, variable)) {
+      const known = Object.keys(variables)
+      const allowed = known.length
+        ? `allowed: ${known.join(', ')}`
+        : 'this prompt takes no variables'
+      errors.push(`Unknown variable {{${variable}}}; ${allowed}`)
+    }
+  }
+  for (const [variable, spec] of Object.entries(variables)) {
+    if (spec.required && !used.has(variable)) {
+      errors.push(`Missing required variable {{${variable}}}`)
+    }
+  }
+  return errors
+}
+
+/**
+ * Save an override
+ * @throws {QurioError} invalid_request with the problems as details
+ */
+export const saveSystemPrompt = (name, template) => {
+  const errors = validatePromptOverride(name, template)
+  if (errors.length) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Invalid template for ${name}`, {
+      details: errors,
+    })
+  }
+  const filePath = resolveOverridePath(name)
+  const tempPath = `${filePath}.${process.pid}.tmp`
+  fs.writeFileSync(tempPath, template)
+  fs.renameSync(tempPath, filePath)
+  return describePrompt(name)
+}
+
+/**
+ * Drop an override and go back to the built-in template
+ * @returns {boolean} Whether an override existed
+ */
+export const resetSystemPrompt = name => {
+  requirePrompt(name)
+  const filePath = resolveOverridePath(name)
+  if (!fs.existsSync(filePath)) return false
+  fs.unlinkSync(filePath)
+  return true
+}
+
+/**
+ * Fill {{variable}} placeholders; unknown placeholders are left as written
+ * @param {string} template
+ * @param {Object<string, *>} values - null/undefined render as ''
+ */
+export const interpolatePrompt = (template, values = {}) =>
+  template.replace(PLACEHOLDER_PATTERN, (placeholder, variable) =>
+    Object.hasOwn(values, variable) ? String(values[variable] ?? '') : placeholder,
+  )
+
+/**
+ * Render a system prompt: the user's override if there is one, else the built-in template
+ * @param {string} name - Key of SYSTEM_PROMPTS
+ * @param {Object<string, *>} [values]
+ */
+export const renderSystemPrompt = (name, values = {}) => {
+  const { template } = requirePrompt(name)
+  return interpolatePrompt(readOverride(name)?.template ?? template, values)
+}
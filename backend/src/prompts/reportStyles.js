@@ -0,0 +1,62 @@
+/**
+ * Report style presets
+ * Control tone, length, and structure of the final deep research report.
+ */
+
+export const DEFAULT_REPORT_STYLE = 'standard'
+
+export const REPORT_STYLE_PRESETS = {
+  standard: {
+    id: 'standard',
+    label: 'Standard',
+    description: 'Balanced report with headings and citations.',
+    instructions: '',
+  },
+  executive: {
+    id: 'executive',
+    label: 'Executive',
+    description: 'Decision-oriented summary for non-specialist leaders.',
+    instructions: `REPORT STYLE: EXECUTIVE
+- Audience: busy decision makers; assume no technical background.
+- Length: 400-700 words.
+- Structure: "Bottom line" (2-3 sentences) → "Key findings" (3-5 bullets) → "Implications" → "Recommended actions" (numbered) → "Risks and open questions".
+- Tone: direct and confident; lead every section with the conclusion, then the evidence.
+- Avoid jargon, formulas, and implementation detail; quantify impact where the sources allow.`,
+  },
+  technical: {
+    id: 'technical',
+    label: 'Technical',
+    description: 'In-depth write-up for practitioners and engineers.',
+    instructions: `REPORT STYLE: TECHNICAL
+- Audience: engineers and domain practitioners.
+- Length: thorough; prefer completeness over brevity.
+- Structure: "Overview" → "Background and definitions" → "Detailed analysis" (subsections per mechanism/component) → "Trade-offs and limitations" → "Implementation notes" → "Conclusion".
+- Tone: precise and neutral; use exact terminology, numbers, versions, and units.
+- Use tables for comparisons and code blocks for configuration or code when relevant.`,
+  },
+  eli5: {
+    id: 'eli5',
+    label: 'ELI5',
+    description: 'Plain-language explanation for a general audience.',
+    instructions: `REPORT STYLE: EXPLAIN LIKE I'M FIVE
+- Audience: curious readers with no background in the topic.
+- Length: 300-600 words.
+- Structure: "The short answer" → "How it works" (use one everyday analogy) → "Why it matters" → "Things people often get wrong".
+- Tone: friendly and simple; short sentences; explain every technical term the first time it appears.
+- Keep citations, but never let them interrupt the flow of a sentence.`,
+  },
+}
+
+export const resolveReportStyle = style => {
+  const key = String(style || '')
+    .trim()
+    .toLowerCase()
+  return REPORT_STYLE_PRESETS[key] || REPORT_STYLE_PRESETS[DEFAULT_REPORT_STYLE]
+}
+
+export const listReportStyles = () =>
+  Object.values(REPORT_STYLE_PRESETS).map(({ id, label, description }) => ({
+    id,
+    label,
+    description,
+  }))
@@ -0,0 +1,148 @@
+/**
+ * Research templates
+ * Domain-specific deep research modes selected by researchType. A template picks the dedicated
+ * search tool, adds extra tools, and layers instructions onto the general plan/step/report
+ * prompts. `requiredSection` is appended to the report when the model omits it; `extractMetrics`
+ * adds the standardized financial metrics table.
+ */
+
+const LEGAL_DISCLAIMER = `
+
+## Disclaimer
+This report is general legal information, not legal advice, and does not create a lawyer-client relationship. Laws differ by jurisdiction and change over time; verify the current law and consult a qualified lawyer licensed in the relevant jurisdiction before acting on it.
+`
+
+export const RESEARCH_TEMPLATES = {
+  regulatory: {
+    id: 'regulatory',
+    label: 'Standards & regulations',
+    description: 'ISO/IEC, IETF RFCs, W3C, EUR-Lex, and federal registers with clause-level citations.',
+    searchToolId: 'Tavily_standards_search',
+    toolIds: ['rfc_fetch'],
+    excludedToolIds: ['Tavily_web_search'],
+    stepInstructions: `STANDARDS & REGULATIONS REQUIREMENTS:
+- Prefer the authoritative text (the standard, RFC, regulation, or register entry) over commentary.
+- Fetch RFCs directly with rfc_fetch (use the section argument for a specific clause); use Tavily_standards_search for everything else.
+- Record the exact identifier, version/edition, and status of each document (e.g. "RFC 9110, Internet Standard", "Regulation (EU) 2016/679, consolidated 2016-05-04", "ISO/IEC 27001:2022").
+- Note when a document is obsoleted, superseded, amended, or not yet in force, and name the replacement.
+- Quote normative language (MUST/SHALL/SHOULD/MAY) verbatim when it matters, with its clause number.
+- Paywalled standards (ISO, IEC, IEEE): rely only on the publicly available abstract/preview and say so.`,
+    reportInstructions: `STANDARDS & REGULATIONS REPORT:
+- Cite with clause numbers preserved next to the source index: "[2] §4.2.1", "[1] Art. 6(1)(f)", "[3] 21 CFR 11.10(a)". Never round a clause reference up to the whole document.
+- Keep the document's own numbering and terminology; do not paraphrase defined terms.
+- Distinguish normative requirements from informative notes and from non-binding guidance.
+- Include a "Documents referenced" table: identifier, title, version/date, status (in force / obsoleted / draft), and source index.
+- Flag jurisdiction and effective dates; state explicitly when requirements differ between jurisdictions or versions.
+- End with a short note that the report is informational and not legal or compliance advice.`,
+  },
+  patent: {
+    id: 'patent',
+    label: 'Patents & prior art',
+    description: 'Prior-art style search over granted US patents plus non-patent literature.',
+    searchToolId: 'patent_search',
+    toolIds: ['Tavily_web_search'],
+    stepInstructions: `PATENT & PRIOR-ART REQUIREMENTS:
+- Use patent_search for patent literature; search several phrasings and synonyms of the key technical features, not just the user's wording.
+- Use Tavily_web_search only for non-patent literature (papers, product docs, standards) that may predate the patents.
+- For every patent, record its number (e.g. US10123456), assignee, filing date, and grant date; the filing date is what matters for priority.
+- Compare features against the independent claims, not just the abstract; quote claim language when a feature match depends on it.
+- Do not characterize infringement, validity, or freedom to operate.`,
+    reportInstructions: `PATENT & PRIOR-ART REPORT:
+- Every finding about a patent MUST cite its patent number alongside the source index, e.g. "US10123456 [3]". Never refer to a patent only by title or assignee.
+- Include a "Patents found" table: patent number, title, assignee, filing date, grant date, relevance to the question, and source index; order by filing date.
+- Map the question's key features to the closest claims in a feature-by-patent matrix where possible.
+- Separate patent literature from non-patent literature.
+- Note search limits: US grants only, keyword coverage, and no pending applications.
+- End with a short note that this is not a legal opinion on patentability, validity, or infringement.`,
+  },
+  clinical: {
+    id: 'clinical',
+    label: 'Medical & clinical evidence',
+    description: 'PubMed-first evidence review that grades findings by study design.',
+    searchToolId: 'pubmed_search',
+    toolIds: ['Tavily_academic_search'],
+    excludedToolIds: ['Tavily_web_search'],
+    stepInstructions: `CLINICAL EVIDENCE REQUIREMENTS:
+- Use pubmed_search first; start with study_type "systematic_review" or "meta_analysis", then "rct", then unrestricted searches. Use Tavily_academic_search only for guidelines or registries not indexed in PubMed.
+- For each study record: PMID, design, evidence level, year, sample size, population, intervention/comparator, and outcomes exactly as reported.
+- Rank evidence: systematic reviews/meta-analyses > RCTs > non-randomized trials > observational studies > case reports > narrative reviews/expert opinion.
+- Report effect sizes with confidence intervals when the abstract gives them; never convert relative to absolute risk unless the source does.
+- Flag small samples, surrogate outcomes, short follow-up, industry funding, and retractions when visible.`,
+    reportInstructions: `CLINICAL EVIDENCE REPORT:
+- Match the strength of language to the evidence level: "demonstrated" only for consistent meta-analytic or multiple-RCT evidence; "suggests" for single RCTs or observational data; "has been reported" for case reports or opinion.
+- State the study design next to every key claim, e.g. "In a 1,204-patient RCT [3], ...".
+- Include an "Evidence summary" table: study (PMID), design, evidence level, population, sample size, main outcome, and source index, ordered by evidence level.
+- Add a "Certainty of evidence" section that rates each main conclusion high / moderate / low / very low and explains why.
+- Call out conflicting results and gaps (populations not studied, missing long-term outcomes).
+- End with a "Medical disclaimer" section: the report is for information only and is not medical advice; decisions should be made with a qualified clinician.`,
+  },
+  legal: {
+    id: 'legal',
+    label: 'Legal research',
+    description: 'Jurisdiction-aware research over case law and legislation with a disclaimer.',
+    searchToolId: 'Tavily_legal_search',
+    toolIds: ['Tavily_web_search'],
+    planInstructions: `This is a LEGAL research question.
+- Step 1 MUST identify the jurisdiction(s) (country, state/province, court system) and the relevant date of law (today, or the date of the events in question). If the user did not specify them, record the assumption explicitly in "assumptions".
+- Plan separate steps for primary authority (statutes, regulations, binding case law in the jurisdiction) and secondary or persuasive authority (other jurisdictions, commentary).
+- Include a step that checks whether key statutes were amended or key cases overruled, distinguished, or superseded after the relevant date.
+- Use "requires_search": true for every step that relies on statutes or case law.`,
+    stepInstructions: `LEGAL RESEARCH REQUIREMENTS:
+- Stay within the jurisdiction(s) and date of law set in the plan assumptions; label any authority from elsewhere as persuasive only.
+- Prefer Tavily_legal_search (court and legislation sites); use Tavily_web_search only for commentary or recent news, and label it secondary.
+- Cite authorities precisely: case name, court, year, and reporter/neutral citation; statute name with section number. Keep pinpoint references (e.g. "s. 16600(b)", "at [42]").
+- Note whether each case is binding or persuasive in the jurisdiction, and whether it has been appealed, overruled, or distinguished when the sources say so.
+- Note the effective date of each statute version you rely on.`,
+    reportInstructions: `LEGAL RESEARCH REPORT:
+- Open with "Jurisdiction and date of law": the jurisdiction(s) covered and the date the analysis applies to, including any assumptions.
+- Structure the analysis as issue → rule (with authorities) → application → conclusion for each legal issue.
+- Cite every authority with its full citation next to the source index, e.g. "Edwards v. Arthur Andersen LLP, 44 Cal.4th 937 (2008) [2]"; keep section and pinpoint numbers.
+- Separate binding from persuasive authority, and flag open questions, circuit/court splits, and pending changes in the law.
+- Never state that an outcome is certain.
+- The report MUST end with a "Disclaimer" section stating it is general information, not legal advice.`,
+    requiredSection: {
+      pattern: /^#{1,6}\s*(?:\d+\.\s*)?disclaimer\b/im,
+      markdown: LEGAL_DISCLAIMER,
+    },
+  },
+  financial: {
+    id: 'financial',
+    label: 'Financial analysis',
+    description: 'Dated, sourced figures with a standardized revenue/growth/margin table.',
+    searchToolId: 'Tavily_web_search',
+    toolIds: [],
+    extractMetrics: true,
+    planInstructions: `This is a FINANCIAL analysis question.
+- Identify the companies/segments and the reporting periods to cover (latest fiscal year, latest quarter, and trailing comparisons) in "assumptions".
+- Plan steps that gather revenue, growth, and margins from primary filings (10-K/10-Q/annual reports, investor relations) before analysis steps.`,
+    stepInstructions: `FINANCIAL DATA REQUIREMENTS:
+- Every figure MUST carry its reporting period and date (e.g. "revenue of USD 4.2B in FY2024 (ended 2024-09-30)") and a citation.
+- Prefer primary sources: SEC/EDGAR or exchange filings, annual reports, investor-relations releases. Treat news and aggregator figures as secondary and label them.
+- Record currency and units exactly; state whether figures are GAAP or adjusted/non-GAAP.
+- Search for the most recent period available; if you only find older data, say so explicitly.
+- Never compute or estimate a figure the sources do not state; quote growth rates and margins as reported.`,
+    reportInstructions: `FINANCIAL ANALYSIS REPORT:
+- Include a "Key metrics" markdown table built from the extracted metrics: entity, metric, value, period, as-of date, and source index.
+- Mark stale figures (older than the staleness threshold) with "⚠ stale" in the table and mention in the text that more recent data may exist.
+- Give the reporting period and date next to every figure in the text; keep GAAP and non-GAAP figures apart.
+- Compare periods only when they are like-for-like (same fiscal calendar and basis).
+- End with a note that the report is informational and not investment advice.`,
+  },
+}
+
+/**
+ * Template for a researchType, or null for the built-in general/academic/comparative modes
+ */
+export const resolveResearchTemplate = researchType => {
+  const key = String(researchType || '')
+    .trim()
+    .toLowerCase()
+  return RESEARCH_TEMPLATES[key] || null
+}
+
+export const listResearchTemplates = () =>
+  Object.values(RESEARCH_TEMPLATES).map(({ id, label, description }) => ({
+    id,
+    label,
+    description,
+  }))
@@ -5,6 +5,7 @@
 
 import express from 'express'
 import { generateAgentForAuto } from '../services/agentForAutoService.js'
+import { sendError } from '../utils/errors.js'
 
 const router = express.Router()
 
@@ -46,10 +47,7 @@ router.post('/agent-for-auto', async (req, res) => {
     res.json({ agentName })
   } catch (error) {
     console.error('[API] generateAgentForAuto error:', error)
-    res.status(500).json({
-      error: 'Failed to generate agent for auto',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate agent for auto')
   }
 })
 
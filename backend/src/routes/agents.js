@@ -0,0 +1,108 @@
+/**
+ * Agents routes
+ * CRUD for agent presets that chat requests resolve server-side through "agent_id" (see
+ * /api/stream-chat). They are the same records as /api/local/agents.
+ */
+
+import express from 'express'
+import {
+  AgentPresetError,
+  buildAgentPreamble,
+  createAgentPreset,
+  deleteAgentPreset,
+  getAgentPreset,
+  listAgentPresets,
+  updateAgentPreset,
+} from '../services/agentPresetService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const notFound = (res, id) => res.status(404).json({ error: `Agent not found: ${id}` })
+
+const sendAgentError = (res, error, fallback) => {
+  if (error instanceof AgentPresetError) {
+    return res.status(400).json({ error: error.message, details: error.details })
+  }
+  console.error(`[API] ${fallback} error:`, error)
+  return sendError(res, error, `Failed to ${fallback}`)
+}
+
+/**
+ * GET /api/agents?space_id=...
+ * With space_id, only agents usable in that space (unbound agents and those bound to it)
+ */
+router.get('/agents', (req, res) => {
+  try {
+    res.json({ agents: listAgentPresets({ spaceId: req.query.space_id || req.query.spaceId }) })
+  } catch (error) {
+    sendAgentError(res, error, 'list agents')
+  }
+})
+
+/**
+ * GET /api/agents/:id
+ * The agent plus "preamble", the system prompt chat requests with its agent_id are sent
+ */
+router.get('/agents/:id', (req, res) => {
+  const agent = getAgentPreset(req.params.id)
+  if (!agent) return notFound(res, req.params.id)
+  res.json({ agent, preamble: buildAgentPreamble(agent) })
+})
+
+/**
+ * POST /api/agents
+ * Body:
+ * {
+ *   "name": "Code reviewer",
+ *   "description": "..." (optional),
+ *   "emoji": "🧐" (optional),
+ *   "prompt": "You review pull requests..." (optional, system prompt),
+ *   "provider" | "default_model_provider": "openai" (optional, default provider),
+ *   "default_model": "gpt-4o" (optional, used when requests run on the agent's provider),
+ *   "tool_ids": ["calculator", "web_search"] (optional, tools enabled by default),
+ *   "temperature": 0.2 (0-2), "top_p": 0.9 (0-1), "frequency_penalty": 0, "presence_penalty": 0
+ *     (-2-2) (optional),
+ *   "base_tone": "technical|friendly|professional|academic|creative|casual", "traits",
+ *     "warmth", "enthusiasm", "headings", "emojis", "custom_instruction",
+ *     "response_language": "English" (optional, response style as in the app's agent settings),
+ *   "space_ids": ["..."] (optional, spaces the agent is limited to; default all)
+ * }
+ * Other fields of the Supabase agents table are stored as given.
+ */
+router.post('/agents', (req, res) => {
+  try {
+    res.status(201).json({ agent: createAgentPreset(req.body) })
+  } catch (error) {
+    sendAgentError(res, error, 'create agent')
+  }
+})
+
+/**
+ * PUT /api/agents/:id
+ * Body: any fields of POST /api/agents; the others are kept
+ */
+router.put('/agents/:id', (req, res) => {
+  try {
+    const agent = updateAgentPreset(req.params.id, req.body)
+    if (!agent) return notFound(res, req.params.id)
+    res.json({ agent })
+  } catch (error) {
+    sendAgentError(res, error, 'update agent')
+  }
+})
+
+/**
+ * DELETE /api/agents/:id
+ * The default agent (is_default) cannot be deleted (409)
+ */
+router.delete('/agents/:id', (req, res) => {
+  const result = deleteAgentPreset(req.params.id)
+  if (result === 'not_found') return notFound(res, req.params.id)
+  if (result === 'default') {
+    return res.status(409).json({ error: `The default agent cannot be deleted: ${req.params.id}` })
+  }
+  res.json({ success: true })
+})
+
+export default router
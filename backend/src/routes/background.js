@@ -0,0 +1,61 @@
+/**
+ * Background mode routes
+ * Start/stop controls for the tray and OS service registration helpers
+ */
+
+import express from 'express'
+import { backgroundJobManager } from '../services/backgroundService.js'
+import { buildServiceDefinition } from '../services/serviceRegistration.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/background/status
+ * Return scheduler state and registered jobs
+ */
+router.get('/status', (req, res) => {
+  res.json(backgroundJobManager.getStatus())
+})
+
+/**
+ * POST /api/background/start
+ * Start running registered background jobs
+ */
+router.post('/start', (req, res) => {
+  res.json(backgroundJobManager.start())
+})
+
+/**
+ * POST /api/background/stop
+ * Stop all background jobs (the HTTP server keeps running)
+ */
+router.post('/stop', (req, res) => {
+  res.json(backgroundJobManager.stop())
+})
+
+/**
+ * POST /api/background/jobs/:name/run
+ * Trigger a registered job immediately
+ */
+router.post('/jobs/:name/run', async (req, res) => {
+  try {
+    const ran = await backgroundJobManager.runJob(req.params.name)
+    res.json({ success: true, ran, status: backgroundJobManager.getStatus() })
+  } catch (error) {
+    res.status(404).json({ success: false, error: error.message })
+  }
+})
+
+/**
+ * GET /api/background/service-definition?platform=launchd|windows|systemd
+ * Return the OS service definition and the commands needed to register it
+ */
+router.get('/service-definition', (req, res) => {
+  try {
+    res.json(buildServiceDefinition(req.query.platform))
+  } catch (error) {
+    res.status(400).json({ error: error.message })
+  }
+})
+
+export default router
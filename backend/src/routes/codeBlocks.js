@@ -0,0 +1,98 @@
+/**
+ * Code block routes
+ * POST /api/code-blocks/extract - list code blocks in an assistant message with inferred filenames
+ * POST /api/code-blocks/write   - write them into a sandboxed directory and return the manifest
+ */
+
+import express from 'express'
+import { extractCodeBlocks, writeCodeBlocks } from '../services/codeBlockService.js'
+import { sendError } from '../utils/errors.js'
+import { resolveSandboxedDir, SandboxError } from '../utils/pathSandbox.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/code-blocks/extract
+ *
+ * Request body: { "content": "assistant message markdown" }
+ *
+ * Response:
+ * {
+ *   "blocks": [{ "index": 0, "language": "js", "filename": "src/app.js", "inferred": false,
+ *                "code": "..." }]
+ * }
+ */
+router.post('/code-blocks/extract', (req, res) => {
+  try {
+    const { content } = req.body
+    if (typeof content !== 'string') {
+      return res.status(400).json({ error: 'Missing required field: content' })
+    }
+    res.json({ blocks: extractCodeBlocks(content) })
+  } catch (error) {
+    console.error('[API] code-blocks extract error:', error)
+    sendError(res, error, 'Failed to extract code blocks')
+  }
+})
+
+/**
+ * POST /api/code-blocks/write
+ * The desktop client shows its permission prompt for the chosen directory first and only then
+ * sends confirmed: true; the directory must also be inside QURIO_SANDBOX_DIRS.
+ *
+ * Request body:
+ * {
+ *   "content": "assistant message markdown",
+ *   "directory": "/abs/path/chosen/by/user",
+ *   "confirmed": true,
+ *   "include": [0, 2] (optional, block indexes to write; default all),
+ *   "filenames": { "3": "scripts/setup.sh" } (optional, overrides by block index),
+ *   "overwrite": false (optional)
+ * }
+ *
+ * Response:
+ * {
+ *   "directory": "/abs/path",
+ *   "files": [{ "filename": "src/app.js", "path": "/abs/path/src/app.js", "language": "js",
+ *               "bytes": 120, "status": "written|overwritten|skipped_exists|error",
+ *               "error": "..." (error only) }]
+ * }
+ */
+router.post('/code-blocks/write', (req, res) => {
+  try {
+    const { content, directory, confirmed, include, filenames = {}, overwrite = false } = req.body
+
+    if (typeof content !== 'string') {
+      return res.status(400).json({ error: 'Missing required field: content' })
+    }
+    if (!directory) {
+      return res.status(400).json({ error: 'Missing required field: directory' })
+    }
+    if (confirmed !== true) {
+      return res.status(403).json({ error: 'Writing files requires user confirmation' })
+    }
+
+    const resolvedDir = resolveSandboxedDir(directory)
+    const selected = Array.isArray(include) ? new Set(include.map(Number)) : null
+    const blocks = extractCodeBlocks(content)
+      .filter(block => !selected || selected.has(block.index))
+      .map(block => ({ ...block, filename: filenames[block.index] || block.filename }))
+
+    if (!blocks.length) {
+      return res.status(400).json({ error: 'No code blocks to write' })
+    }
+
+    res.json({
+      directory: resolvedDir,
+      files: writeCodeBlocks(blocks, resolvedDir, { overwrite: Boolean(overwrite) }),
+    })
+  } catch (error) {
+    if (error instanceof SandboxError) {
+      return res.status(403).json({ error: error.message })
+    }
+    console.error('[API] code-blocks write error:', error)
+    sendError(res, error, 'Failed to write code blocks')
+  }
+})
+
+export default router
@@ -0,0 +1,106 @@
+/**
+ * Code index routes
+ * Build and query symbol indexes over sandboxed local repositories. All routes answer 501 when git
+ * is not installed (see services/runtimeCapabilities.js).
+ */
+
+import express from 'express'
+import { getCodeIndex, indexRepository, searchCode } from '../services/codeIndexService.js'
+import { requireRuntime } from '../services/runtimeCapabilities.js'
+import { sendError } from '../utils/errors.js'
+import { SandboxError } from '../utils/pathSandbox.js'
+
+const router = express.Router()
+
+const sendIndexError = (res, error, fallback) => {
+  if (error instanceof SandboxError) {
+    return res.status(403).json({ error: error.message })
+  }
+  console.error(`[API] ${fallback} error:`, error)
+  return sendError(res, error, `Failed to ${fallback}`)
+}
+
+/**
+ * GET /api/code-index?repoPath=/abs/repo
+ * Index status (404 when the repository has not been indexed)
+ */
+router.get('/code-index', requireRuntime('git'), async (req, res) => {
+  try {
+    const { repoPath } = req.query
+    if (!repoPath) {
+      return res.status(400).json({ error: 'Missing required field: repoPath' })
+    }
+    const index = await getCodeIndex(repoPath)
+    if (!index) {
+      return res.status(404).json({ error: `Repository not indexed: ${repoPath}` })
+    }
+    res.json({
+      repo: index.repo,
+      files: Object.keys(index.files || {}).length,
+      chunks: index.chunks.length,
+      embeddingModel: index.embeddingModel,
+      indexedAt: index.indexedAt,
+    })
+  } catch (error) {
+    sendIndexError(res, error, 'load code index')
+  }
+})
+
+/**
+ * POST /api/code-index
+ * Build or incrementally refresh an index
+ *
+ * Request body:
+ * {
+ *   "repoPath": "/abs/repo" (must be inside QURIO_SANDBOX_DIRS),
+ *   "embedding": { "apiKey": "...", "baseUrl": "...", "model": "..." } (optional; defaults to
+ *     EMBEDDING_* env vars, lexical-only when unset)
+ * }
+ *
+ * Response: { "repo": "...", "files": 80, "chunks": 569, "reindexedFiles": 3,
+ *             "embedded": true, "indexedAt": "..." }
+ */
+router.post('/code-index', requireRuntime('git'), async (req, res) => {
+  try {
+    const { repoPath, embedding } = req.body
+    if (!repoPath) {
+      return res.status(400).json({ error: 'Missing required field: repoPath' })
+    }
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+    res.json(await indexRepository({ repoPath, embedding, signal: controller.signal }))
+  } catch (error) {
+    sendIndexError(res, error, 'index repository')
+  }
+})
+
+/**
+ * POST /api/code-search
+ *
+ * Request body: { "repoPath": "...", "query": "...", "kind": "function" (optional),
+ *                 "limit": 10 (optional), "embedding": {...} (optional) }
+ *
+ * Response:
+ * {
+ *   "results": [{ "file": "src/a.js", "line": 12, "end_line": 40, "kind": "function",
+ *                 "name": "...", "signature": "...", "snippet": "...", "score": 0.82 }]
+ * }
+ */
+router.post('/code-search', requireRuntime('git'), async (req, res) => {
+  try {
+    const { repoPath, query, kind, limit, embedding } = req.body
+    if (!repoPath) {
+      return res.status(400).json({ error: 'Missing required field: repoPath' })
+    }
+    if (!query) {
+      return res.status(400).json({ error: 'Missing required field: query' })
+    }
+    res.json({ results: await searchCode({ repoPath, query, kind, limit, embedding }) })
+  } catch (error) {
+    sendIndexError(res, error, 'search code')
+  }
+})
+
+export default router
@@ -0,0 +1,229 @@
+/**
+ * Conversations routes
+ * Local chat history (conversations and messages) plus spaces and agents, backed by the
+ * backend data store instead of Supabase
+ */
+
+import express from 'express'
+import {
+  AgentPresetError,
+  createAgentPreset,
+  deleteAgentPreset,
+  getAgentPreset,
+  updateAgentPreset,
+} from '../services/agentPresetService.js'
+import { forgetConversationMemory } from '../services/conversationMemoryService.js'
+import {
+  addMessage,
+  createConversation,
+  deleteConversation,
+  deleteEntity,
+  deleteMessage,
+  getConversation,
+  getEntity,
+  listConversations,
+  listEntities,
+  listMessages,
+  saveEntity,
+  updateConversation,
+  updateMessage,
+} from '../services/conversationStore.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const notFound = (res, label, id) => res.status(404).json({ error: `${label} not found: ${id}` })
+
+/**
+ * GET /api/conversations?limit=20&page=1&search=&spaceId=&favorites=true&sortBy=updated_at
+ * Optional: ascending=true; spaceId=null lists conversations without a space
+ */
+router.get('/conversations', (req, res) => {
+  try {
+    const { limit, page, search, spaceId, favorites, sortBy, ascending } = req.query
+    const result = listConversations({
+      limit,
+      page,
+      search,
+      spaceId: spaceId === 'null' ? '' : spaceId,
+      favoritesOnly: favorites === 'true',
+      sortBy,
+      ascending: ascending === 'true',
+    })
+    res.json(result)
+  } catch (error) {
+    console.error('[API] listConversations error:', error)
+    sendError(res, error, 'Failed to list conversations')
+  }
+})
+
+/**
+ * GET /api/conversations/:id
+ */
+router.get('/conversations/:id', (req, res) => {
+  const conversation = getConversation(req.params.id)
+  if (!conversation) return notFound(res, 'Conversation', req.params.id)
+  res.json({ conversation })
+})
+
+/**
+ * POST /api/conversations
+ * Body: { "title": "...", "space_id": "...", "api_provider": "...", "last_agent_id": "..." }
+ */
+router.post('/conversations', (req, res) => {
+  try {
+    res.status(201).json({ conversation: createConversation(req.body || {}) })
+  } catch (error) {
+    console.error('[API] createConversation error:', error)
+    sendError(res, error, 'Failed to create conversation')
+  }
+})
+
+/**
+ * PATCH /api/conversations/:id
+ * Body: any of title, title_emojis, space_id, api_provider, is_favorited, last_agent_id
+ */
+router.patch('/conversations/:id', (req, res) => {
+  try {
+    const conversation = updateConversation(req.params.id, req.body || {})
+    if (!conversation) return notFound(res, 'Conversation', req.params.id)
+    res.json({ conversation })
+  } catch (error) {
+    console.error('[API] updateConversation error:', error)
+    sendError(res, error, 'Failed to update conversation')
+  }
+})
+
+/**
+ * DELETE /api/conversations/:id
+ * Also removes the conversation's messages
+ */
+router.delete('/conversations/:id', (req, res) => {
+  const deleted = deleteConversation(req.params.id)
+  if (!deleted) return notFound(res, 'Conversation', req.params.id)
+  forgetConversationMemory(req.params.id)
+  res.json({ success: true })
+})
+
+/**
+ * GET /api/conversations/:id/messages
+ * Messages in creation order
+ */
+router.get('/conversations/:id/messages', (req, res) => {
+  if (!getConversation(req.params.id)) return notFound(res, 'Conversation', req.params.id)
+  res.json({ messages: listMessages(req.params.id) })
+})
+
+/**
+ * POST /api/conversations/:id/messages
+ * Body: { "role": "user|assistant|system|tool", "content": "...", ...extra fields }
+ */
+router.post('/conversations/:id/messages', (req, res) => {
+  try {
+    if (!req.body?.role) {
+      return res.status(400).json({ error: 'Missing required field: role' })
+    }
+    const message = addMessage(req.params.id, req.body)
+    if (!message) return notFound(res, 'Conversation', req.params.id)
+    res.status(201).json({ message })
+  } catch (error) {
+    console.error('[API] addMessage error:', error)
+    sendError(res, error, 'Failed to add message')
+  }
+})
+
+/**
+ * PATCH /api/conversations/:id/messages/:messageId
+ */
+router.patch('/conversations/:id/messages/:messageId', (req, res) => {
+  try {
+    const message = updateMessage(req.params.id, req.params.messageId, req.body || {})
+    if (!message) return notFound(res, 'Message', req.params.messageId)
+    res.json({ message })
+  } catch (error) {
+    console.error('[API] updateMessage error:', error)
+    sendError(res, error, 'Failed to update message')
+  }
+})
+
+/**
+ * DELETE /api/conversations/:id/messages/:messageId
+ */
+router.delete('/conversations/:id/messages/:messageId', (req, res) => {
+  const deleted = deleteMessage(req.params.id, req.params.messageId)
+  if (!deleted) return notFound(res, 'Message', req.params.messageId)
+  res.json({ success: true })
+})
+
+/**
+ * GET /api/local/spaces[/:id] and /api/local/agents[/:id]
+ */
+for (const kind of ['spaces', 'agents']) {
+  const label = kind === 'spaces' ? 'Space' : 'Agent'
+
+  router.get(`/local/${kind}`, (req, res) => {
+    try {
+      res.json({ [kind]: listEntities(kind) })
+    } catch (error) {
+      console.error(`[API] list ${kind} error:`, error)
+      sendError(res, error, `Failed to list ${kind}`)
+    }
+  })
+
+  router.get(`/local/${kind}/:id`, (req, res) => {
+    const record = getEntity(kind, req.params.id)
+    if (!record) return notFound(res, label, req.params.id)
+    res.json({ [label.toLowerCase()]: record })
+  })
+}
+
+/**
+ * PUT/DELETE /api/local/spaces/:id
+ * Free-form space records; PUT upserts by id
+ */
+router.put('/local/spaces/:id', (req, res) => {
+  try {
+    res.json({ space: saveEntity('spaces', { ...req.body, id: req.params.id }) })
+  } catch (error) {
+    console.error('[API] save spaces error:', error)
+    sendError(res, error, 'Failed to save space')
+  }
+})
+
+router.delete('/local/spaces/:id', (req, res) => {
+  const deleted = deleteEntity('spaces', req.params.id)
+  if (!deleted) return notFound(res, 'Space', req.params.id)
+  res.json({ success: true })
+})
+
+/**
+ * PUT/DELETE /api/local/agents/:id
+ * PUT upserts by id with the same rules as /api/agents: the result must be a valid agent (400
+ * with details otherwise), and the default agent cannot be deleted (409)
+ */
+router.put('/local/agents/:id', (req, res) => {
+  try {
+    const { id } = req.params
+    const agent = getAgentPreset(id)
+      ? updateAgentPreset(id, req.body)
+      : createAgentPreset(req.body || {}, { id })
+    res.json({ agent })
+  } catch (error) {
+    if (error instanceof AgentPresetError) {
+      return res.status(400).json({ error: error.message, details: error.details })
+    }
+    console.error('[API] save agents error:', error)
+    sendError(res, error, 'Failed to save agent')
+  }
+})
+
+router.delete('/local/agents/:id', (req, res) => {
+  const result = deleteAgentPreset(req.params.id)
+  if (result === 'not_found') return notFound(res, 'Agent', req.params.id)
+  if (result === 'default') {
+    return res.status(409).json({ error: `The default agent cannot be deleted: ${req.params.id}` })
+  }
+  res.json({ success: true })
+})
+
+export default router
@@ -5,6 +5,12 @@
 
 import express from 'express'
 import { generateDailyTip } from '../services/dailyTipService.js'
+import {
+  isCacheBypassed,
+  setCacheStatusHeader,
+  withResponseCache,
+} from '../services/responseCache.js'
+import { sendError } from '../utils/errors.js'
 
 const router = express.Router()
 
@@ -24,8 +30,11 @@ const router = express.Router()
  *
  * Response:
  * {
- *   "tip": "Generated tip text"
+ *   "tip": "Generated tip text",
+ *   "cache_status": "hit" | "miss" | "bypass" | "disabled"
  * }
+ *
+ * Cached like POST /api/title, per UTC day so a new day brings a new tip.
  */
 router.post('/daily-tip', async (req, res) => {
   try {
@@ -53,15 +62,26 @@ router.post('/daily-tip', async (req, res) => {
 
     console.log(`[API] generateDailyTip: provider=${provider}`)
 
-    const tip = await generateDailyTip(provider, language, category, apiKey, baseUrl, model)
+    const { value: tip, cacheStatus } = await withResponseCache(
+      'daily-tip',
+      {
+        provider,
+        baseUrl,
+        model,
+        apiKey,
+        language,
+        category,
+        day: new Date().toISOString().slice(0, 10),
+      },
+      () => generateDailyTip(provider, language, category, apiKey, baseUrl, model),
+      { bypass: isCacheBypassed(req) },
+    )
 
-    res.json({ tip })
+    setCacheStatusHeader(res, cacheStatus)
+    res.json({ tip, cache_status: cacheStatus })
   } catch (error) {
     console.error('[API] generateDailyTip error:', error)
-    res.status(500).json({
-      error: 'Failed to generate daily tip',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate daily tip')
   }
 })
 
@@ -5,11 +5,227 @@
  */
 
 import express from 'express'
-import { streamDeepResearch } from '../services/deepResearchAgentService.js'
+import { listReportStyles } from '../prompts/reportStyles.js'
+import { listResearchTemplates } from '../prompts/researchTemplates.js'
+import {
+  followupDeepResearch,
+  getFollowupRun,
+  getResumableRun,
+  resumeDeepResearch,
+  streamDeepResearch,
+} from '../services/deepResearchAgentService.js'
+import { resolveMessageAttachments } from '../services/attachmentService.js'
+import { CITATION_STYLES } from '../services/citationFormatService.js'
+import { normalizeLocale } from '../services/localeFormatService.js'
+import { plainTextStream } from '../services/plainTextStream.js'
+import { requiresApiKey } from '../services/providers/providerConfig.js'
+import { isSearchProviderSupported, SEARCH_PROVIDERS } from '../services/search/index.js'
+import { checkTerminologyInStream, getSpaceGlossary } from '../services/spaceGlossaryService.js'
+import { streamRegistry } from '../services/streamRegistry.js'
+import { getActiveTraceId } from '../services/tracing.js'
+import { validateMaxTurns } from '../services/turnLimits.js'
+import { recordUsage } from '../services/usageLedger.js'
+import { sendError, toErrorEvent } from '../utils/errors.js'
 import { createSseStream, getSseConfig } from '../utils/sse.js'
 
 const router = express.Router()
 
+/**
+ * Send research events over SSE; the stream can be cancelled via POST /api/streams/:id/cancel
+ * @param {Function} options.start - signal => async iterable of research events
+ */
+const streamResearchEvents = async (
+  req,
+  res,
+  { streamId, conversationId, provider, model, spaceGlossary, start },
+) => {
+  let activeStreamId
+  try {
+    const sse = createSseStream(res, { ...getSseConfig(), eventFilter: req.body.event_filter })
+    sse.writeComment('ok')
+
+    const controller = new AbortController()
+    req.on('aborted', () => {
+      controller.abort()
+    })
+    res.on('close', () => {
+      if (!res.writableEnded && !res.writableFinished) {
+        controller.abort()
+      }
+    })
+    activeStreamId = streamRegistry.register({
+      streamId,
+      controller,
+      kind: 'deep_research',
+      sessionId: req.body.sessionId ?? req.body.session_id,
+    })
+    sse.sendEvent({
+      type: 'stream_started',
+      stream_id: activeStreamId,
+      trace_id: getActiveTraceId(),
+    })
+
+    const checked = checkTerminologyInStream(start(controller.signal), spaceGlossary)
+    const plainText = req.body.plainText ?? req.body.plain_text
+    for await (const chunk of plainText ? plainTextStream(checked) : checked) {
+      if (chunk?.type === 'done') {
+        recordUsage({ conversationId, kind: 'deep_research', provider, model, usage: chunk.usage })
+      }
+      sse.sendEvent(chunk)
+    }
+
+    if (streamRegistry.isCancelled(activeStreamId)) {
+      sse.sendEvent({ type: 'cancelled', stream_id: activeStreamId })
+    }
+    sse.close()
+  } catch (error) {
+    if (activeStreamId && streamRegistry.isCancelled(activeStreamId)) {
+      res.write(`data: ${JSON.stringify({ type: 'cancelled', stream_id: activeStreamId })}\n\n`)
+      res.end()
+      return
+    }
+    console.error('[API] deepResearch error:', error)
+    if (!res.headersSent) {
+      sendError(res, error, 'Failed to stream deep research')
+    } else {
+      res.write(`data: ${JSON.stringify(toErrorEvent(error))}\n\n`)
+      res.end()
+    }
+  } finally {
+    if (activeStreamId) streamRegistry.unregister(activeStreamId)
+  }
+}
+
+/**
+ * POST /api/stream-deep-research
+ *
+ * Body (in addition to the chat fields):
+ * - toolIds: extra agent tools for the research steps (see GET /api/tools); "webpage_reader" lets
+ *   steps read whole pages (main content as Markdown, paged with "page") and adds them to sources
+ * - reportStyle | report_style: 'standard' | 'executive' | 'technical' | 'eli5' (see GET /api/report-styles)
+ * - glossary: true to append a "Glossary" section with short, source-cited definitions
+ * - researchType: 'academic' searches arXiv, Semantic Scholar, and PubMed directly (arxiv_search,
+ *   semantic_scholar_search, pubmed_search) next to Tavily; papers become sources with their DOI,
+ *   authors, year, and venue, and a paper found in several indexes is one source
+ * - researchType: 'comparative' with entities: ["A", "B", ...] (2-6) and optional criteria: ["..."]
+ *   researches each entity in parallel and emits an aligned comparison matrix
+ * - researchType | research_type: a research template id (e.g. 'regulatory', 'legal') for a
+ *   domain-specific search tool, extra tools, and citation rules (see GET /api/research-templates)
+ * - timeline: true/false to force timeline extraction (default: only for "history" questions)
+ * - numericCheck: true to extract numeric claims, normalize units, and add a "Data conflicts" section
+ * - stalenessDays: with researchType 'financial', flag figures older than this (default
+ *   FINANCE_STALENESS_DAYS or 365)
+ * - sourceBias | source_bias: true to label news sources with ownership/bias metadata from the
+ *   bundled dataset and add a "Source diversity" note to the report
+ * - sourceClusters | source_clusters: false to skip grouping sources by sub-topic (runs by default
+ *   when at least SOURCE_CLUSTER_MIN_SOURCES sources are collected and embeddings are configured)
+ * - noveltyThreshold | novelty_threshold: 0-1 share of new URLs a search must return; after two
+ *   successive searches below it, the step's remaining searches are skipped and its finding notes
+ *   "saturation reached" (default SEARCH_NOVELTY_THRESHOLD or 0.2; 0 disables). Repeated queries
+ *   are always skipped.
+ * - similarityCheck | similarity_check: true or { threshold: 0-1, rewrite: false } to compare the
+ *   report's sentences with the collected source texts; passages whose 5-word shingles match one
+ *   source above the threshold (default SIMILARITY_THRESHOLD or 0.5) are flagged and, unless
+ *   rewrite is false, paraphrased once by the model (citations kept)
+ * - citationCheck | citation_check: true or { threshold: 0-1, refetch: true } to verify every [n]
+ *   citation: the source must exist and contain enough of the citing sentence's keywords (default
+ *   CITATION_OVERLAP_THRESHOLD or 0.3); refetch reads the cited pages again instead of using the
+ *   text collected during research. On by default for researchType 'academic' (false turns it off)
+ * - proofread: true or style rules ({ passiveVoice, maxSentenceWords, bannedTerms, llm }; see
+ *   POST /api/proofread) to proofread the finished report with the report model and the rules;
+ *   issues are reported, the report is not changed
+ * - citationStyle | citation_style: 'apa' | 'ieee' | 'bibtex' to end the report with a References
+ *   section in that style, numbered like the [n] citations (a reference list the model wrote is
+ *   replaced). Sources with a DOI are completed from Crossref (authors, journal, volume, pages);
+ *   other sources are cited as web pages
+ * - reportLocale | report_locale: BCP 47 tag such as 'de-DE' to rewrite the report's numbers,
+ *   numeric dates and unit spacing in that locale's format (1.234,56; 05.03.2024; 5 %); false
+ *   or '' turns it off. Defaults to the reportLocale preference (see /api/preferences)
+ * - concurrentExecution | concurrent_execution: true to run independent plan steps in parallel.
+ *   A step depends on the steps listed in its "depends_on" (step numbers); without that, search
+ *   steps that are not comparisons/syntheses are independent and other steps wait for every
+ *   earlier step. Step events interleave and are tagged with their "step" number.
+ * - maxParallelSteps | max_parallel_steps: steps running at once in concurrent mode (default
+ *   DEEP_RESEARCH_MAX_PARALLEL or 3)
+ * - maxTurns | max_turns: model turns per research step, 1-20 (default MAX_RESEARCH_STEP_TURNS
+ *   or 4)
+ * - searchProvider | search_provider, searchApiKey | search_api_key, searchBaseUrl |
+ *   search_base_url: web search backend for the research steps (see POST /api/stream-chat)
+ * - space_id: applies the space's pinned credentials and terminology glossary to the report (see
+ *   /api/spaces/:spaceId/glossary)
+ * - stream_id: id for POST /api/streams/:id/cancel (generated when omitted)
+ * - session_id: the window's session (see POST /api/sessions); DELETE /api/sessions/:id/active
+ *   cancels the session's streams
+ * - conversation_id: books the run's token usage to this conversation (see GET /api/usage)
+ * - event_filter: event types to suppress, or { include: [...] } to send only those (done, error
+ *   and cancelled are always sent); see POST /api/stream-chat
+ * - plain_text: true for a screen-reader friendly report (no markdown, emoji or citation
+ *   brackets; the cited sources are listed in done.citations); see POST /api/stream-chat
+ *
+ * Response: Server-Sent Events stream
+ * - data: {"type":"stream_started","stream_id":"...","trace_id":"..."} (trace_id: see
+ *   GET /api/traces/:id; absent with TRACING=off)
+ * - data: {"type":"decomposition","sub_questions":["..."]} (only with "decompose": true)
+ * - data: {"type":"plan_invalid","errors":["plan[1].action: Required"],"attempts":2,
+ *   "fallback":false,"sub_question":0} (the plan still failed validation after
+ *   PLAN_REPAIR_ATTEMPTS fix-it prompts; research continues with it, or with a generic plan when
+ *   fallback is true; sub_question only with "decompose": true)
+ * - data: {"type":"comparison_plan","entities":[...],"criteria":[...]} (comparative mode)
+ * - data: {"type":"research_step","step":1,"total":4,"title":"...","status":"pending|running|done|error"}
+ * - data: {"type":"tool_call_delta","step":1,"total":4,"index":0,"id":"call_1",
+ *   "name":"Tavily_web_search","arguments_delta":"...","arguments":"...","partial_arguments":{...}}
+ *   (OpenAI-compatible providers, while a research step writes tool arguments; the consolidated
+ *   tool_call follows)
+ * - data: {"type":"tool_call",...} / {"type":"tool_result",...}
+ * - data: {"type":"turn_limit_reached","max_turns":4,"step":2,"total":4} (a step still wanted
+ *   tools after its last allowed turn and ended without a finding)
+ * - data: {"type":"comparison_matrix","matrix":{...}} (comparative mode)
+ *   matrix: { entities, criteria, rows: [{ criterion, cells: [{ entity, value, sources }] }], summary }
+ * - data: {"type":"timeline","events":[{"date":"1969-07-20","date_label":"...","title":"...","description":"...","sources":[1],"sort_key":19690720}]}
+ * - data: {"type":"data_conflicts","conflicts":[{"subject":"...","metric":"...","as_of":"2023","unit":"usd","min":0,"max":0,"spread":0.24,"claims":[...]}]}
+ * - data: {"type":"financial_metrics","metrics":{"entities":[{"entity":"...","metrics":[{"metric":"revenue","value":0,"unit":"USD billion","period":"FY2024","as_of":"2024-12-31","age_days":0,"stale":false,"sources":[1]}]}],"staleness_days":365,"stale_count":0}}
+ * - data: {"type":"source_diversity","sources":[{"url":"...","outlet":{"domain":"reuters.com","name":"Reuters","owner":"...","bias":"center","type":"wire"}}],"diversity":{"total":8,"labeled":5,"by_bias":{...},"by_type":{...},"by_owner":{...},"warnings":["..."]}}
+ * - data: {"type":"source_clusters","clusters":[{"id":1,"label":"...","summary":"...","sources":[1,4,7]}],"embedding_model":"..."}
+ * - data: {"type":"text","content":"..."}
+ * - data: {"type":"similarity_check","threshold":0.5,"passages":[{"text":"...","similarity":0.72,
+ *   "source":{"index":3,"url":"...","title":"..."},"rewritten":true}],"rewritten":1,"remaining":0}
+ *   (only with similarityCheck)
+ * - data: {"type":"report_revised","content":"...","reason":"similarity"} (replaces the streamed
+ *   report text when passages were rewritten)
+ * - data: {"type":"report_revised","content":"...","reason":"locale","locale":"de-DE","changes":3}
+ *   (replaces the streamed report text with reportLocale formatting applied)
+ * - data: {"type":"citation_check","threshold":0.3,"refetched":0,"passed":11,"failed":1,
+ *   "unverified":0,"citations":[{"index":4,"claim":"...","status":"fail","reason":"low_overlap",
+ *   "overlap":0.12,"source":{"url":"...","title":"..."},"evidence":"collected"}]} (reason:
+ *   missing_source | low_overlap fail, no_source_text | no_keywords unverified; the same list
+ *   follows as a "Citation check" text section)
+ * - data: {"type":"references","style":"apa","references":[{"index":1,"text":"...","csl":{...}}]}
+ *   (only with citationStyle; csl is the CSL-JSON item), followed by a "report_revised" event
+ *   with "reason":"references" when the model's own reference list was replaced, otherwise by
+ *   the References section as text
+ * - data: {"type":"proofread","issues":[{"rule":"grammar","severity":"error","message":"...",
+ *   "start":120,"end":131,"text":"...","suggestion":"..."}],"count":1,"llm":true} (only with
+ *   proofread; start/end index the final report)
+ * - data: {"type":"glossary","terms":[{"term":"...","definition":"...","sources":[1]}]} (only with "glossary": true)
+ * - data: {"type":"terminology_violations","violations":[...]} (space glossary only; see
+ *   POST /api/stream-chat)
+ * - data: {"type":"done","content":"...","sources":[...],"runId":"...","stats":{...}}
+ *   (plus "comparison", "timeline", "data_conflicts", "financial_metrics", "source_diversity",
+ *   "source_clusters", and "glossary" when enabled; with sourceBias, sources carry their "outlet" labels)
+ *   terminology_check: { violations } with a space glossary
+ *   similarity_check with similarityCheck, plus original_content when passages were rewritten
+ *   citation_check with citationCheck (academic research by default)
+ *   references with citationStyle
+ *   proofread with proofread
+ *   search_log: [{ tool, query, result_count, step, duration_ms, status, at }] for every search issued
+ *   stats: { total_duration_ms, plan_duration_ms, tokens, tool_calls, llm_calls, steps: [...], report }
+ *   runId: pass to POST /api/research/export to download the report as Markdown, PDF, or DOCX
+ *   (the run is persisted from the start, so an interrupted run can be resumed with
+ *   POST /api/research-runs/:id/resume)
+ *   usage: { prompt_tokens, completion_tokens, total_tokens } (steps and report combined)
+ * - data: {"type":"cancelled","stream_id":"..."} (final event after POST /api/streams/:id/cancel)
+ * - data: {"type":"error","error":"..."}
+ */
 router.post('/stream-deep-research', async (req, res) => {
   try {
     const {
@@ -29,10 +245,34 @@ router.post('/stream-deep-research', async (req, res) => {
       toolIds,
       plan,
       question,
-      researchType, // 'general' or 'academic'
-      concurrentExecution, // Enable concurrent step execution (experimental)
-      searchProvider,
+      researchType = req.body.research_type, // 'general' | 'academic' | 'comparative' | template
+      concurrentExecution = req.body.concurrent_execution, // Run independent steps in parallel
+      maxParallelSteps = req.body.max_parallel_steps, // Concurrent mode parallelism cap
+      decompose, // Split multi-part questions into sub-questions
+      reportStyle = req.body.report_style, // 'standard' | 'executive' | 'technical' | 'eli5'
+      glossary, // Append a generated glossary section to the report
+      entities, // Comparative mode: entities to compare
+      criteria, // Comparative mode: comparison criteria (generated when omitted)
+      timeline, // Force timeline extraction on/off (default: history questions only)
+      numericCheck, // Cross-check numeric claims across sources
+      stalenessDays = req.body.staleness_days, // Financial template staleness threshold
+      sourceBias = req.body.source_bias, // Annotate sources with ownership/bias metadata
+      sourceClusters = req.body.source_clusters, // false disables sub-topic source clustering
+      noveltyThreshold = req.body.novelty_threshold, // Search saturation stop rule
+      similarityCheck = req.body.similarity_check, // Paraphrase near-verbatim report passages
+      citationCheck = req.body.citation_check, // Verify [n] citations against their sources
+      proofread, // Proofread the finished report (true or style rules)
+      citationStyle = req.body.citation_style, // Format of the References section
+      reportLocale = req.body.report_locale, // Number/date formats of the report
+      searchProvider = req.body.search_provider, // Web search backend (default SEARCH_PROVIDER)
+      searchApiKey = req.body.search_api_key,
+      searchBaseUrl = req.body.search_base_url,
       tavilyApiKey,
+      userId, // Selects stored preferences (defaults to the shared profile)
+      spaceId = req.body.space_id, // Space whose terminology glossary applies
+      maxTurns = req.body.max_turns, // Tool-loop turn limit (see turnLimits)
+      stream_id: streamId, // Client-chosen id for POST /api/streams/:id/cancel
+      conversationId = req.body.conversation_id, // Books token usage (GET /api/usage)
     } = req.body
 
     // Debug: Log the received parameters
@@ -46,12 +286,35 @@ router.post('/stream-deep-research', async (req, res) => {
     if (!provider) {
       return res.status(400).json({ error: 'Missing required field: provider' })
     }
-    if (!apiKey) {
+    if (!apiKey && requiresApiKey(provider)) {
       return res.status(400).json({ error: 'Missing required field: apiKey' })
     }
     if (!messages || !Array.isArray(messages)) {
       return res.status(400).json({ error: 'Missing required field: messages' })
     }
+    const turnsError = validateMaxTurns(maxTurns, 'research_step')
+    if (turnsError) {
+      return res.status(400).json({ error: turnsError })
+    }
+    if (searchProvider && !isSearchProviderSupported(searchProvider)) {
+      return res.status(400).json({
+        error: `Unsupported search provider: ${searchProvider}. Supported: ${SEARCH_PROVIDERS.join(', ')}`,
+      })
+    }
+    if (reportLocale && !normalizeLocale(reportLocale)) {
+      return res.status(400).json({ error: `Unsupported report locale: ${reportLocale}` })
+    }
+    if (citationStyle && !CITATION_STYLES.includes(citationStyle)) {
+      return res.status(400).json({
+        error: `Unsupported citation style: ${citationStyle}. Supported: ${CITATION_STYLES.join(', ')}`,
+      })
+    }
+    const researchMessages = await resolveMessageAttachments(messages, { provider })
+    if (researchType === 'comparative' && (!Array.isArray(entities) || entities.length < 2)) {
+      return res
+        .status(400)
+        .json({ error: 'Comparative research requires at least 2 entities in "entities"' })
+    }
 
     const supportedProviders = [
       'gemini',
@@ -62,6 +325,9 @@ router.post('/stream-deep-research', async (req, res) => {
       'modelscope',
       'kimi',
       'nvidia',
+      'anthropic',
+      'ollama',
+      'local',
     ]
     if (!supportedProviders.includes(provider)) {
       return res.status(400).json({
@@ -69,58 +335,195 @@ router.post('/stream-deep-research', async (req, res) => {
       })
     }
 
-    const sse = createSseStream(res, getSseConfig())
-    sse.writeComment('ok')
+    if (streamId && streamRegistry.has(streamId)) {
+      return res.status(409).json({ error: `Stream already running: ${streamId}` })
+    }
 
-    const controller = new AbortController()
-    req.on('aborted', () => {
-      controller.abort()
-    })
-    res.on('close', () => {
-      if (!res.writableEnded && !res.writableFinished) {
-        controller.abort()
-      }
+    const spaceGlossary = getSpaceGlossary(spaceId)
+    await streamResearchEvents(req, res, {
+      streamId,
+      conversationId,
+      provider,
+      model,
+      spaceGlossary,
+      start: signal =>
+        streamDeepResearch({
+          provider,
+          apiKey,
+          baseUrl,
+          model,
+          messages: researchMessages,
+          tools,
+          toolChoice,
+          temperature,
+          top_k,
+          top_p,
+          frequency_penalty,
+          presence_penalty,
+          contextMessageLimit,
+          toolIds,
+          plan,
+          question,
+          researchType, // Pass researchType to service
+          concurrentExecution, // Pass concurrentExecution to service
+          maxParallelSteps,
+          decompose,
+          reportStyle,
+          glossary,
+          entities,
+          criteria,
+          timeline,
+          numericCheck,
+          stalenessDays,
+          sourceBias,
+          sourceClusters,
+          noveltyThreshold,
+          similarityCheck,
+          citationCheck,
+          proofread,
+          citationStyle,
+          reportLocale,
+          searchProvider,
+          searchApiKey,
+          searchBaseUrl,
+          tavilyApiKey,
+          maxTurns,
+          userId,
+          spaceGlossary,
+          signal,
+        }),
     })
+  } catch (error) {
+    console.error('[API] deepResearch error:', error)
+    sendError(res, error, 'Failed to stream deep research')
+  }
+})
 
-    for await (const chunk of streamDeepResearch({
-      provider,
+/**
+ * POST /api/research-runs/:id/resume
+ * Continue an interrupted, failed, or aborted deep research run from its last finished step.
+ * The stored plan is reused without a new planning phase; finished steps keep their findings and
+ * sources, and only the remaining steps and the report are run. Comparative runs cannot be resumed.
+ *
+ * Body (credentials are never stored with a run, so they are sent again):
+ * - apiKey: required unless the run's provider needs none
+ * - searchApiKey | search_api_key, tavilyApiKey: for the run's search provider
+ * - stream_id, session_id, conversation_id, event_filter: see POST /api/stream-deep-research
+ *
+ * Response: Server-Sent Events stream, as POST /api/stream-deep-research, starting with
+ * - data: {"type":"research_resumed","runId":"...","completed_steps":2,"total_steps":5}
+ * Finished steps are reported once as {"type":"research_step",...,"status":"done","resumed":true}.
+ * 404 when the run does not exist; 409 when it is finished, still running, or comparative.
+ */
+router.post('/research-runs/:id/resume', async (req, res) => {
+  try {
+    const {
       apiKey,
-      baseUrl,
+      searchApiKey = req.body.search_api_key,
+      tavilyApiKey,
+      stream_id: streamId,
+      conversation_id: conversationId,
+    } = req.body || {}
+    const run = getResumableRun(req.params.id)
+    const { provider, model, spaceGlossary } = run.request
+
+    if (!apiKey && requiresApiKey(provider)) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (streamId && streamRegistry.has(streamId)) {
+      return res.status(409).json({ error: `Stream already running: ${streamId}` })
+    }
+
+    await streamResearchEvents(req, res, {
+      streamId,
+      conversationId,
+      provider,
       model,
-      messages,
-      tools,
-      toolChoice,
-      temperature,
-      top_k,
-      top_p,
-      frequency_penalty,
-      presence_penalty,
-      contextMessageLimit,
-      toolIds,
-      plan,
+      spaceGlossary,
+      start: signal => resumeDeepResearch(run, { apiKey, searchApiKey, tavilyApiKey, signal }),
+    })
+  } catch (error) {
+    console.error('[API] resume deepResearch error:', error)
+    sendError(res, error, 'Failed to resume deep research')
+  }
+})
+
+/**
+ * POST /api/deep-research/:id/followup
+ * Research a refinement question ("go deeper on section 3") on top of a finished run. The plan
+ * covers only the new question; the earlier run's findings are context for its steps and report,
+ * and its sources keep their numbers, so [n] citations mean the same in both reports (new sources
+ * are numbered after them). The follow-up is a new run with the earlier run's settings; it can be
+ * resumed and followed up in turn.
+ *
+ * Body:
+ * - question: the follow-up question (required)
+ * - apiKey, searchApiKey | search_api_key, tavilyApiKey: credentials, as for a resume
+ * - stream_id, session_id, conversation_id, event_filter, plain_text: see
+ *   POST /api/stream-deep-research
+ *
+ * Response: Server-Sent Events stream, as POST /api/stream-deep-research, starting with
+ * - data: {"type":"research_followup","runId":"...","parent_run_id":"...","reused_sources":12}
+ * 404 when the run does not exist; 409 when it has not finished or is comparative.
+ */
+router.post('/deep-research/:id/followup', async (req, res) => {
+  try {
+    const {
       question,
-      researchType, // Pass researchType to service
-      concurrentExecution, // Pass concurrentExecution to service
-      searchProvider,
+      apiKey,
+      searchApiKey = req.body.search_api_key,
       tavilyApiKey,
-      signal: controller.signal,
-    })) {
-      sse.sendEvent(chunk)
+      stream_id: streamId,
+      conversation_id: conversationId,
+    } = req.body || {}
+    if (typeof question !== 'string' || !question.trim()) {
+      return res.status(400).json({ error: 'Missing required field: question' })
     }
+    const run = getFollowupRun(req.params.id)
+    const { provider, model, spaceGlossary } = run.request
 
-    sse.close()
-  } catch (error) {
-    console.error('[API] deepResearch error:', error)
-    if (!res.headersSent) {
-      res.status(500).json({
-        error: 'Failed to stream deep research',
-        message: error.message,
-      })
-    } else {
-      res.write(`data: ${JSON.stringify({ type: 'error', error: error.message })}\n\n`)
-      res.end()
+    if (!apiKey && requiresApiKey(provider)) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
     }
+    if (streamId && streamRegistry.has(streamId)) {
+      return res.status(409).json({ error: `Stream already running: ${streamId}` })
+    }
+
+    await streamResearchEvents(req, res, {
+      streamId,
+      conversationId,
+      provider,
+      model,
+      spaceGlossary,
+      start: signal =>
+        followupDeepResearch(run, {
+          question: question.trim(),
+          apiKey,
+          searchApiKey,
+          tavilyApiKey,
+          signal,
+        }),
+    })
+  } catch (error) {
+    console.error('[API] followup deepResearch error:', error)
+    sendError(res, error, 'Failed to follow up deep research')
   }
 })
 
+/**
+ * GET /api/report-styles
+ * List report style presets for deep research
+ */
+router.get('/report-styles', (req, res) => {
+  res.json({ styles: listReportStyles() })
+})
+
+/**
+ * GET /api/research-templates
+ * List domain-specific research templates usable as researchType
+ */
+router.get('/research-templates', (req, res) => {
+  res.json({ templates: listResearchTemplates() })
+})
+
 export default router
@@ -0,0 +1,79 @@
+/**
+ * Embeddings route
+ * POST /api/embeddings
+ */
+
+import express from 'express'
+import { createEmbeddings, EMBEDDING_PROVIDERS } from '../services/providers/embeddings/index.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const MAX_DIMENSIONS = 8192
+
+/**
+ * POST /api/embeddings
+ * Generate embeddings with a provider's embedding model
+ *
+ * Request body:
+ * {
+ *   "provider": "openai" | "openai_compatibility" | "gemini" | "siliconflow",
+ *   "apiKey": "API key for the provider",
+ *   "baseUrl": "Custom base URL (optional)",
+ *   "model": "text-embedding-3-small" (optional, provider default when omitted),
+ *   "input": "text" | ["text", ...] (up to 2048 texts; batched per provider limits),
+ *   "dimensions": 512 (optional, shortened output for models that support it)
+ * }
+ *
+ * Vectors are L2-normalized (unit length) float32 values; transient failures (429/5xx) are
+ * retried with backoff.
+ *
+ * Response:
+ * {
+ *   "provider": "openai", "model": "text-embedding-3-small", "dimensions": 1536,
+ *   "data": [{ "index": 0, "embedding": [0.0123, ...] }],
+ *   "usage": { "prompt_tokens": 8, "total_tokens": 8 }
+ * }
+ */
+router.post('/embeddings', async (req, res) => {
+  const { provider, apiKey, baseUrl, model, input, dimensions } = req.body || {}
+  if (!provider) {
+    return res.status(400).json({ error: 'Missing required field: provider' })
+  }
+  if (!EMBEDDING_PROVIDERS.includes(provider)) {
+    return res.status(400).json({
+      error: `Unsupported provider: ${provider}. Supported: ${EMBEDDING_PROVIDERS.join(', ')}`,
+    })
+  }
+  if (!apiKey) {
+    return res.status(400).json({ error: 'Missing required field: apiKey' })
+  }
+  const texts = Array.isArray(input) ? input : [input]
+  if (input === undefined || !texts.length || texts.some(text => typeof text !== 'string')) {
+    return res.status(400).json({ error: 'input must be a string or an array of strings' })
+  }
+  if (
+    dimensions !== undefined &&
+    (!Number.isInteger(dimensions) || dimensions < 1 || dimensions > MAX_DIMENSIONS)
+  ) {
+    return res
+      .status(400)
+      .json({ error: `dimensions must be an integer between 1 and ${MAX_DIMENSIONS}` })
+  }
+
+  try {
+    const result = await createEmbeddings({ provider, apiKey, baseUrl, model, input, dimensions })
+    res.json({
+      provider: result.provider,
+      model: result.model,
+      dimensions: result.dimensions,
+      data: result.embeddings.map((embedding, index) => ({ index, embedding })),
+      usage: result.usage,
+    })
+  } catch (error) {
+    console.error('[API] embeddings error:', error)
+    sendError(res, error, 'Failed to create embeddings')
+  }
+})
+
+export default router
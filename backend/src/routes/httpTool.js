@@ -0,0 +1,89 @@
+/**
+ * HTTP tool routes
+ * Manage the http_request domain allowlist and the named secrets it can inject.
+ * Secret values are stored encrypted and never returned in full.
+ */
+
+import express from 'express'
+import { getAllowedDomains, setAllowedDomains } from '../services/httpRequestService.js'
+import { deleteSecret, listSecrets, setSecret } from '../services/keyVault.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/http-tool/domains
+ * Response: { domains: ["api.example.com", "*.example.org"] }
+ */
+router.get('/http-tool/domains', (req, res) => {
+  try {
+    res.json({ domains: getAllowedDomains() })
+  } catch (error) {
+    console.error('[API] http-tool domains error:', error)
+    sendError(res, error, 'Failed to load allowlist')
+  }
+})
+
+/**
+ * PUT /api/http-tool/domains
+ * Body: { domains: ["api.example.com", "*.example.org"] } (replaces the stored list;
+ * HTTP_TOOL_ALLOWED_DOMAINS entries are always included)
+ */
+router.put('/http-tool/domains', (req, res) => {
+  try {
+    const { domains } = req.body || {}
+    if (!Array.isArray(domains)) {
+      return res.status(400).json({ error: 'Missing required field: domains' })
+    }
+    setAllowedDomains(domains)
+    res.json({ domains: getAllowedDomains() })
+  } catch (error) {
+    console.error('[API] http-tool domains error:', error)
+    sendError(res, error, 'Failed to save allowlist')
+  }
+})
+
+/**
+ * GET /api/secrets
+ * Response: { secrets: [{ name, domains, valueMasked, updatedAt }] }
+ */
+router.get('/secrets', (req, res) => {
+  try {
+    res.json({ secrets: listSecrets() })
+  } catch (error) {
+    console.error('[API] listSecrets error:', error)
+    sendError(res, error, 'Failed to list secrets')
+  }
+})
+
+/**
+ * PUT /api/secrets/:name
+ * Body: { value: "...", domains: ["api.example.com"] }
+ * Tools reference it as {{secret:NAME}}; it is only injected into requests to these domains.
+ */
+router.put('/secrets/:name', (req, res) => {
+  try {
+    const { value, domains } = req.body || {}
+    if (!value) {
+      return res.status(400).json({ error: 'Missing required field: value' })
+    }
+    if (!Array.isArray(domains) || domains.length === 0) {
+      return res.status(400).json({ error: 'Missing required field: domains' })
+    }
+    res.json({ secret: setSecret(req.params.name, { value, domains }) })
+  } catch (error) {
+    res.status(400).json({ error: error.message })
+  }
+})
+
+/**
+ * DELETE /api/secrets/:name
+ */
+router.delete('/secrets/:name', (req, res) => {
+  if (!deleteSecret(req.params.name)) {
+    return res.status(404).json({ error: `Secret not found: ${req.params.name}` })
+  }
+  res.json({ success: true })
+})
+
+export default router
@@ -0,0 +1,125 @@
+/**
+ * Image routes
+ * POST /api/images/generate - text-to-image; generated files are stored and served from here
+ */
+
+import express from 'express'
+import {
+  generateImages,
+  getGeneratedImage,
+  IMAGE_PROVIDERS,
+  readGeneratedImage,
+  toImageRef,
+} from '../services/images/index.js'
+import { resolveSpaceCredentials } from '../services/keyVault.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/images/generate
+ * Generate images from a prompt and store them
+ *
+ * Request body:
+ * {
+ *   "provider": "openai" | "openai_compatibility" | "gemini" | "siliconflow",
+ *   "apiKey": "..." (or the X-Api-Key header, or credentials pinned to "spaceId"),
+ *   "prompt": "A lighthouse at dusk, watercolor",
+ *   "baseUrl": "..." (optional),
+ *   "model": "..." (optional; gpt-image-1, imagen-3.0-generate-002, Kwai-Kolors/Kolors),
+ *   "n": 1 (optional, 1-4),
+ *   "size": "1024x1024" (optional; Imagen maps it to the closest aspect ratio),
+ *   "response_format": "url" | "b64_json" (optional, default "url")
+ * }
+ *
+ * Response:
+ * {
+ *   "provider": "openai", "model": "gpt-image-1",
+ *   "images": [{ "id": "...", "url": "/api/images/:id/file", "mime_type": "image/png",
+ *                "prompt": "...", "revised_prompt": null, "provider", "model", "created_at",
+ *                "source_url": "..." (when the provider answered a URL),
+ *                "b64_json": "..." (response_format b64_json only) }]
+ * }
+ */
+router.post('/images/generate', async (req, res) => {
+  try {
+    const {
+      prompt,
+      model,
+      n,
+      size,
+      spaceId = req.body.space_id,
+      responseFormat = req.body.response_format,
+    } = req.body || {}
+    const pinned = resolveSpaceCredentials(spaceId)
+    const provider = req.body?.provider || pinned?.provider
+    // Pinned credentials only apply to the provider they were pinned for
+    const spaceCredentials = pinned?.provider === provider ? pinned : null
+    const apiKey = req.body?.apiKey || req.get('x-api-key') || spaceCredentials?.apiKey
+    const baseUrl = req.body?.baseUrl || spaceCredentials?.baseUrl
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!IMAGE_PROVIDERS.includes(provider)) {
+      const supported = IMAGE_PROVIDERS.join(', ')
+      return res
+        .status(400)
+        .json({ error: `Unsupported provider: ${provider}. Supported: ${supported}` })
+    }
+    if (!apiKey) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (!prompt) {
+      return res.status(400).json({ error: 'Missing required field: prompt' })
+    }
+
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+    res.json(
+      await generateImages({
+        provider,
+        prompt,
+        apiKey,
+        baseUrl,
+        model,
+        n,
+        size,
+        responseFormat,
+        signal: controller.signal,
+      }),
+    )
+  } catch (error) {
+    console.error('[API] image generation error:', error)
+    sendError(res, error, 'Failed to generate image')
+  }
+})
+
+/**
+ * GET /api/images/:id
+ * Generated image metadata
+ */
+router.get('/images/:id', (req, res) => {
+  const record = getGeneratedImage(req.params.id)
+  if (!record) {
+    return res.status(404).json({ error: `Image not found: ${req.params.id}` })
+  }
+  res.json({ ...toImageRef(record), bytes: record.bytes })
+})
+
+/**
+ * GET /api/images/:id/file
+ * Generated image bytes
+ */
+router.get('/images/:id/file', (req, res) => {
+  const image = readGeneratedImage(req.params.id)
+  if (!image) {
+    return res.status(404).json({ error: `Image not found: ${req.params.id}` })
+  }
+  res.set('Cache-Control', 'private, max-age=31536000, immutable')
+  res.type(image.mimeType).send(image.buffer)
+})
+
+export default router
@@ -0,0 +1,194 @@
+/**
+ * Kimi files routes
+ * Upload documents to Moonshot for extraction and cache them, so chats with provider "kimi"
+ * reference the cache ("context_cache" on POST /api/stream-chat) instead of resending the text.
+ * Every route reads the Kimi API key from the X-Api-Key header (or "apiKey" in a JSON body), or
+ * from the credentials pinned to ?spaceId= when they are Kimi's.
+ */
+
+import express from 'express'
+import { resolveSpaceCredentials } from '../services/keyVault.js'
+import {
+  createKimiCache,
+  deleteKimiCache,
+  deleteKimiFile,
+  getKimiFileContent,
+  getMaxKimiFileBytes,
+  listKimiFiles,
+  uploadKimiFile,
+} from '../services/providers/kimiFiles.js'
+import { readUploadFile } from '../services/uploadService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+// Document bodies of any type except JSON, which the app-wide parser already handled
+const parseDocument = express.raw({
+  type: req => !req.is('application/json'),
+  limit: getMaxKimiFileBytes(),
+})
+
+const readCredentials = req => {
+  const pinned = resolveSpaceCredentials(req.query.spaceId)
+  // Pinned credentials only apply to the provider they were pinned for
+  const spaceCredentials = pinned?.provider === 'kimi' ? pinned : null
+  const body = Buffer.isBuffer(req.body) ? {} : req.body || {}
+  return {
+    apiKey: req.get('x-api-key') || body.apiKey || spaceCredentials?.apiKey,
+    baseUrl: req.query.baseUrl || body.baseUrl || spaceCredentials?.baseUrl,
+  }
+}
+
+// Cancel the Moonshot request when the client goes away
+const requestSignal = res => {
+  const controller = new AbortController()
+  res.on('close', () => {
+    if (!res.writableEnded) controller.abort()
+  })
+  return controller.signal
+}
+
+const missingKey = res => res.status(400).json({ error: 'Missing required field: apiKey' })
+
+/**
+ * GET /api/providers/kimi/files
+ * Response: { "files": [{ "id", "filename", "bytes", "purpose", "status", "created_at" }] }
+ */
+router.get('/providers/kimi/files', async (req, res) => {
+  try {
+    const credentials = readCredentials(req)
+    if (!credentials.apiKey) return missingKey(res)
+    res.json({ files: await listKimiFiles({ ...credentials, signal: requestSignal(res) }) })
+  } catch (error) {
+    console.error('[API] list Kimi files error:', error)
+    sendError(res, error, 'Failed to list Kimi files')
+  }
+})
+
+/**
+ * POST /api/providers/kimi/files?filename=report.pdf
+ * Upload a document for extraction (purpose "file-extract"). The raw file is the request body
+ * (up to KIMI_FILE_MAX_MB, default 100). A JSON body uploads a completed chunked upload instead
+ * (see POST /api/uploads): { "upload_id": "..." }
+ *
+ * Response: 201 { "file": { "id", "filename", "bytes", "purpose", "status", "created_at" } }
+ */
+router.post('/providers/kimi/files', parseDocument, async (req, res) => {
+  try {
+    const isFile = Buffer.isBuffer(req.body)
+    const credentials = readCredentials(req)
+    const uploadId = isFile ? null : req.body?.upload_id || req.body?.uploadId
+    if (!credentials.apiKey) return missingKey(res)
+    if (isFile ? !req.body.length : !uploadId) {
+      return res.status(400).json({ error: 'Missing required field: file body or upload_id' })
+    }
+
+    const file = isFile
+      ? { buffer: req.body, filename: req.query.filename, mimeType: req.get('content-type') }
+      : readUploadFile(uploadId)
+    if (file.buffer.length > getMaxKimiFileBytes()) {
+      return res.status(413).json({ error: `File is too large for Kimi: ${file.filename}` })
+    }
+    const uploaded = await uploadKimiFile({ ...credentials, file, signal: requestSignal(res) })
+    res.status(201).json({ file: uploaded })
+  } catch (error) {
+    console.error('[API] upload Kimi file error:', error)
+    sendError(res, error, 'Failed to upload Kimi file')
+  }
+})
+
+/**
+ * GET /api/providers/kimi/files/:id/content
+ * Response: { "id": "...", "content": "extracted text" }
+ */
+router.get('/providers/kimi/files/:id/content', async (req, res) => {
+  try {
+    const credentials = readCredentials(req)
+    if (!credentials.apiKey) return missingKey(res)
+    const content = await getKimiFileContent({
+      ...credentials,
+      id: req.params.id,
+      signal: requestSignal(res),
+    })
+    res.json({ id: req.params.id, content })
+  } catch (error) {
+    console.error('[API] Kimi file content error:', error)
+    sendError(res, error, 'Failed to read Kimi file content')
+  }
+})
+
+/**
+ * DELETE /api/providers/kimi/files/:id
+ */
+router.delete('/providers/kimi/files/:id', async (req, res) => {
+  try {
+    const credentials = readCredentials(req)
+    if (!credentials.apiKey) return missingKey(res)
+    await deleteKimiFile({ ...credentials, id: req.params.id, signal: requestSignal(res) })
+    res.json({ success: true })
+  } catch (error) {
+    console.error('[API] delete Kimi file error:', error)
+    sendError(res, error, 'Failed to delete Kimi file')
+  }
+})
+
+/**
+ * POST /api/providers/kimi/caches
+ * Cache the extracted text of uploaded files for chats ("context_cache" on POST /api/stream-chat)
+ *
+ * Body:
+ * {
+ *   "file_ids": ["..."] (required, in the order the model should read them),
+ *   "system": "Answer from these documents" (optional, cached ahead of them),
+ *   "model": "moonshot-v1" (optional, model family the cache is used with),
+ *   "ttl": 3600 (optional, seconds; default KIMI_CACHE_TTL_SECONDS or 3600),
+ *   "name": "handbook" (optional)
+ * }
+ *
+ * Response: 201 { "cache": { "id": "cache-...", "name", "model", "status", "tokens",
+ *   "expired_at" } }
+ */
+router.post('/providers/kimi/caches', async (req, res) => {
+  try {
+    const body = req.body || {}
+    const credentials = readCredentials(req)
+    const fileIds = body.file_ids ?? body.fileIds
+    if (!credentials.apiKey) return missingKey(res)
+    const validIds =
+      Array.isArray(fileIds) && fileIds.length && fileIds.every(id => typeof id === 'string')
+    if (!validIds) {
+      return res.status(400).json({ error: 'Missing required field: file_ids' })
+    }
+
+    const cache = await createKimiCache({
+      ...credentials,
+      fileIds,
+      system: typeof body.system === 'string' ? body.system : undefined,
+      model: body.model,
+      ttl: body.ttl,
+      name: body.name,
+      signal: requestSignal(res),
+    })
+    res.status(201).json({ cache })
+  } catch (error) {
+    console.error('[API] create Kimi cache error:', error)
+    sendError(res, error, 'Failed to create Kimi cache')
+  }
+})
+
+/**
+ * DELETE /api/providers/kimi/caches/:id
+ */
+router.delete('/providers/kimi/caches/:id', async (req, res) => {
+  try {
+    const credentials = readCredentials(req)
+    if (!credentials.apiKey) return missingKey(res)
+    await deleteKimiCache({ ...credentials, id: req.params.id, signal: requestSignal(res) })
+    res.json({ success: true })
+  } catch (error) {
+    console.error('[API] delete Kimi cache error:', error)
+    sendError(res, error, 'Failed to delete Kimi cache')
+  }
+})
+
+export default router
@@ -3,6 +3,7 @@
 
 import express from 'express'
 import { mcpToolManager } from '../services/mcpToolManager.js'
+import { toQurioError } from '../utils/errors.js'
 
 const router = express.Router()
 
@@ -24,6 +25,7 @@ router.get('/servers', (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
@@ -32,15 +34,17 @@ router.get('/servers', (req, res) => {
  * POST /api/mcp-tools/servers
  * Load a new MCP server
  * Body: { name: string, url: string, transport?: string, bearerToken?: string, headers?: object }
+ *   or, for a local stdio server (requires MCP_STDIO_ENABLED=1):
+ *   { name: string, command: string, args?: string[], env?: object, cwd?: string }
  */
 router.post('/servers', async (req, res) => {
   try {
-    const { name, url, transport, bearerToken, headers } = req.body
+    const { name, url, transport, bearerToken, headers, command, args, env, cwd } = req.body
 
-    if (!name || !url) {
+    if (!name || (!url && !command)) {
       return res.status(400).json({
         success: false,
-        error: 'Missing required fields: name and url',
+        error: 'Missing required fields: name and url (or command for stdio servers)',
       })
     }
 
@@ -49,6 +53,10 @@ router.post('/servers', async (req, res) => {
       transport,
       bearerToken,
       headers,
+      command,
+      args,
+      env,
+      cwd,
     })
 
     res.json({
@@ -68,6 +76,7 @@ router.post('/servers', async (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
@@ -97,6 +106,7 @@ router.get('/servers/:name/tools', (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
@@ -120,6 +130,7 @@ router.delete('/servers/:name', async (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
@@ -149,6 +160,7 @@ router.get('/tools', (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
@@ -186,6 +198,7 @@ router.get('/tool/:toolId', (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
@@ -193,16 +206,16 @@ router.get('/tool/:toolId', (req, res) => {
 /**
  * POST /api/mcp-tools/fetch
  * Fetch tools from an MCP server URL (temporary connection)
- * Body: { name: string, url: string, transport?: string, bearerToken?: string, headers?: object }
+ * Body: same as POST /api/mcp-tools/servers
  */
 router.post('/fetch', async (req, res) => {
   try {
-    const { name, url, transport, bearerToken, headers } = req.body
+    const { name, url, transport, bearerToken, headers, command, args, env, cwd } = req.body
 
-    if (!name || !url) {
+    if (!name || (!url && !command)) {
       return res.status(400).json({
         success: false,
-        error: 'Missing required fields: name and url',
+        error: 'Missing required fields: name and url (or command for stdio servers)',
       })
     }
 
@@ -211,6 +224,10 @@ router.post('/fetch', async (req, res) => {
       transport,
       bearerToken,
       headers,
+      command,
+      args,
+      env,
+      cwd,
     })
 
     res.json({
@@ -229,6 +246,7 @@ router.post('/fetch', async (req, res) => {
     res.status(500).json({
       success: false,
       error: error.message,
+      code: toQurioError(error).code,
     })
   }
 })
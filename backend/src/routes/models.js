@@ -0,0 +1,63 @@
+/**
+ * Models route
+ * GET /api/models - list available chat models for a provider
+ */
+
+import express from 'express'
+import { resolveSpaceCredentials } from '../services/keyVault.js'
+import { listProviderModels } from '../services/modelCatalogService.js'
+import { isProviderSupported } from '../services/providers/adapterFactory.js'
+import { getProviderConfig, requiresApiKey } from '../services/providers/providerConfig.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/models?provider=openai&baseUrl=...&refresh=1
+ * The API key is read from the X-Api-Key header, or from the credentials pinned to ?spaceId=
+ *
+ * Response:
+ * {
+ *   "provider": "openai",
+ *   "models": [{ "id": "gpt-4o", "name": "gpt-4o", "context_window": null,
+ *                "supports_tools": true, "supports_vision": true,
+ *                "probed_capabilities": { "json_mode": true, ... } (probed models only) }],
+ *   "capabilities": { "supportsThinking": false, "supportsAudioOutput": false, ... } (provider
+ *     feature flags for gating UI options),
+ *   "cached": false,
+ *   "fetchedAt": "2025-01-01T00:00:00.000Z"
+ * }
+ */
+router.get('/models', async (req, res) => {
+  try {
+    const pinned = resolveSpaceCredentials(req.query.spaceId)
+    const provider = req.query.provider || pinned?.provider
+    // Pinned credentials only apply to the provider they were pinned for
+    const spaceCredentials = pinned?.provider === provider ? pinned : null
+    const apiKey = req.get('x-api-key') || spaceCredentials?.apiKey
+    const baseUrl = req.query.baseUrl || spaceCredentials?.baseUrl
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!isProviderSupported(provider)) {
+      return res.status(400).json({ error: `Unsupported provider: ${provider}` })
+    }
+    if (!apiKey && requiresApiKey(provider)) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+
+    const catalog = await listProviderModels({
+      provider,
+      apiKey,
+      baseUrl,
+      refresh: req.query.refresh === '1' || req.query.refresh === 'true',
+    })
+    res.json({ ...catalog, capabilities: getProviderConfig(provider).capabilities })
+  } catch (error) {
+    console.error('[API] listModels error:', error)
+    sendError(res, error, 'Failed to list models')
+  }
+})
+
+export default router
@@ -0,0 +1,88 @@
+/**
+ * Preferences routes
+ * GET/PUT/DELETE /api/preferences
+ * Persistent answer preferences injected into chat and research system prompts
+ */
+
+import express from 'express'
+import {
+  PREFERENCE_OPTIONS,
+  getPreferences,
+  resetPreferences,
+  updatePreferences,
+  validatePreferences,
+} from '../services/preferencesService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/preferences?userId=...
+ *
+ * Response:
+ * {
+ *   "userId": "default",
+ *   "preferences": { "language": "", "units": "metric", "verbosity": "concise",
+ *                    "citationStyle": "numeric", "reportLocale": "de-DE",
+ *                    "titleEmojis": "", "customInstructions": "" },
+ *   "options": { "units": [...], "verbosity": [...], "citationStyle": [...],
+ *                "titleEmojis": [...] }
+ * }
+ */
+router.get('/preferences', (req, res) => {
+  try {
+    const userId = req.query.userId || 'default'
+    res.json({ userId, preferences: getPreferences(userId), options: PREFERENCE_OPTIONS })
+  } catch (error) {
+    console.error('[API] preferences error:', error)
+    sendError(res, error, 'Failed to load preferences')
+  }
+})
+
+/**
+ * PUT /api/preferences
+ *
+ * Request body (partial update; empty string clears a field):
+ * {
+ *   "userId": "..." (optional),
+ *   "language": "German",
+ *   "units": "metric" | "imperial" | "",
+ *   "verbosity": "concise" | "balanced" | "detailed" | "",
+ *   "citationStyle": "numeric" | "apa" | "mla" | "chicago" | "",
+ *   "reportLocale": "de-DE" (BCP 47; research reports get this locale's number and date formats,
+ *     e.g. 1.234,56 and 05.03.2024; empty leaves them as the model wrote them),
+ *   "titleEmojis": "on" | "off" | "" ("off": generated titles come without emoji; empty is on),
+ *   "customInstructions": "..." (max 2000 characters)
+ * }
+ *
+ * Response: { "userId": "...", "preferences": {...} }
+ */
+router.put('/preferences', (req, res) => {
+  try {
+    const { userId = 'default', ...patch } = req.body || {}
+    const errors = validatePreferences(patch)
+    if (errors.length) {
+      return res.status(400).json({ error: 'Invalid preferences', details: errors })
+    }
+    res.json({ userId, preferences: updatePreferences(userId, patch) })
+  } catch (error) {
+    console.error('[API] preferences error:', error)
+    sendError(res, error, 'Failed to save preferences')
+  }
+})
+
+/**
+ * DELETE /api/preferences?userId=...
+ * Reset to defaults
+ */
+router.delete('/preferences', (req, res) => {
+  try {
+    const userId = req.query.userId || 'default'
+    res.json({ userId, preferences: resetPreferences(userId) })
+  } catch (error) {
+    console.error('[API] preferences error:', error)
+    sendError(res, error, 'Failed to reset preferences')
+  }
+})
+
+export default router
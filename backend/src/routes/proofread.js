@@ -0,0 +1,74 @@
+/**
+ * Proofread route
+ * POST /api/proofread
+ * Grammar/style check with configurable rules; returns structured issues with spans
+ */
+
+import express from 'express'
+import { DEFAULT_STYLE_RULES, proofreadText } from '../services/proofreadService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/proofread
+ *
+ * Request body:
+ * {
+ *   "text": "Text to check",
+ *   "rules": {
+ *     "passiveVoice": true,
+ *     "maxSentenceWords": 35 (0 disables),
+ *     "bannedTerms": ["leverage", { "term": "utilize", "replacement": "use", "reason": "..." }],
+ *     "instructions": "House style notes for the model pass" (optional),
+ *     "llm": true (model pass for grammar/spelling; needs provider + apiKey)
+ *   },
+ *   "provider": "..." (optional), "apiKey": "..." (optional), "baseUrl": "...", "model": "..."
+ * }
+ *
+ * Response:
+ * {
+ *   "issues": [{ "rule": "passive_voice|sentence_length|banned_term|grammar|spelling|...",
+ *                "severity": "error|warning|suggestion", "message": "...",
+ *                "start": 0, "end": 5, "text": "...", "suggestion": "..." | null }],
+ *   "rules": {...}, "llm": true
+ * }
+ */
+router.post('/proofread', async (req, res) => {
+  try {
+    const { provider, apiKey, baseUrl, model, text, rules } = req.body
+
+    if (typeof text !== 'string' || !text) {
+      return res.status(400).json({ error: 'Missing required field: text' })
+    }
+
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+
+    const result = await proofreadText({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      text,
+      rules,
+      signal: controller.signal,
+    })
+    res.json(result)
+  } catch (error) {
+    console.error('[API] proofread error:', error)
+    sendError(res, error, 'Failed to proofread text')
+  }
+})
+
+/**
+ * GET /api/proofread/rules
+ * Default rule configuration
+ */
+router.get('/proofread/rules', (req, res) => {
+  res.json({ rules: DEFAULT_STYLE_RULES })
+})
+
+export default router
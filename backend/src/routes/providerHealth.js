@@ -0,0 +1,178 @@
+/**
+ * Provider health routes
+ * POST /api/providers/health - check a provider key and measure its latency
+ * POST /api/providers/:name/probe - detect what a model supports
+ */
+
+import express from 'express'
+import { isProviderSupported } from '../services/providers/adapterFactory.js'
+import {
+  CAPABILITY_CHECKS,
+  probeCapabilities,
+} from '../services/providers/capabilityProbe.js'
+import {
+  checkProviderHealth,
+  HEALTH_PROBES,
+  resolveProbeTimeout,
+} from '../services/providers/health.js'
+import { requiresApiKey } from '../services/providers/providerConfig.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/providers/health
+ * Send one minimal request to the provider and report its status for a settings badge
+ *
+ * Request body:
+ * {
+ *   "provider": "openai",
+ *   "apiKey" | "api_key": "...",
+ *   "baseUrl" | "base_url": "..." (optional),
+ *   "probe": "models" | "completion" (optional, default "models": lists models without spending
+ *     tokens; "completion" sends a 1-token completion to "model"),
+ *   "model": "..." (optional, completion probe; default the provider's default model),
+ *   "timeout_ms": 10000 (optional, at most 60000)
+ * }
+ *
+ * Response (200 whatever the provider answered):
+ * {
+ *   "provider": "openai",
+ *   "probe": "models",
+ *   "status": "ok" | "invalid_key" | "rate_limited" | "network_error" | "error",
+ *   "ok": true,
+ *   "latency_ms": 412,
+ *   "http_status": 200 (null when the provider was not reached),
+ *   "models_count": 57 (models probe),
+ *   "model": "gpt-4o-mini" (completion probe),
+ *   "error": "..." (when not ok),
+ *   "retry_after_ms": 20000 (rate_limited, when the provider says),
+ *   "checked_at": "2025-01-01T00:00:00.000Z"
+ * }
+ */
+router.post('/providers/health', async (req, res) => {
+  try {
+    const {
+      provider,
+      apiKey = req.body.api_key,
+      baseUrl = req.body.base_url,
+      model,
+      probe = 'models',
+      timeout_ms: timeoutMs,
+    } = req.body || {}
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!isProviderSupported(provider)) {
+      return res.status(400).json({ error: `Unsupported provider: ${provider}` })
+    }
+    if (!apiKey && requiresApiKey(provider)) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (!HEALTH_PROBES.includes(probe)) {
+      return res.status(400).json({ error: `probe must be one of: ${HEALTH_PROBES.join(', ')}` })
+    }
+
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableFinished) controller.abort()
+    })
+    res.json(
+      await checkProviderHealth({
+        provider,
+        apiKey,
+        baseUrl,
+        model,
+        probe,
+        timeoutMs: resolveProbeTimeout(timeoutMs),
+        signal: controller.signal,
+      }),
+    )
+  } catch (error) {
+    console.error('[API] providerHealth error:', error)
+    sendError(res, error, 'Failed to check provider health')
+  }
+})
+
+/**
+ * POST /api/providers/:name/probe
+ * Run a small capability detection suite against a model (one tiny request per check) and cache
+ * the results for the settings UI, GET /api/models and stream-chat request validation
+ *
+ * Request body:
+ * {
+ *   "apiKey" | "api_key": "...",
+ *   "baseUrl" | "base_url": "..." (optional),
+ *   "model": "..." (optional; default the provider's default model),
+ *   "checks": ["json_mode", "tool_calls", "vision", "reasoning_stream"] (optional; default all),
+ *   "refresh": true (optional; probe again instead of answering from the cache),
+ *   "timeout_ms": 30000 (optional, per check, at most 60000)
+ * }
+ *
+ * Response:
+ * {
+ *   "provider": "openai",
+ *   "model": "gpt-4o-mini",
+ *   "capabilities": {
+ *     "json_mode": { "supported": true, "latency_ms": 812 },
+ *     "vision": { "supported": null, "latency_ms": 95, "error": "Incorrect API key" },
+ *     ...
+ *   },
+ *   "cached": false,
+ *   "probed_at": "2025-01-01T00:00:00.000Z"
+ * }
+ * "supported" is null when the check could not reach a verdict (bad key, rate limit, network);
+ * such checks are not cached.
+ */
+router.post('/providers/:name/probe', async (req, res) => {
+  try {
+    const provider = req.params.name
+    const {
+      apiKey = req.body?.api_key,
+      baseUrl = req.body?.base_url,
+      model,
+      checks = CAPABILITY_CHECKS,
+      refresh = false,
+      timeout_ms: timeoutMs,
+    } = req.body || {}
+
+    if (!isProviderSupported(provider)) {
+      return res.status(400).json({ error: `Unsupported provider: ${provider}` })
+    }
+    if (!apiKey && requiresApiKey(provider)) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (
+      !Array.isArray(checks) ||
+      !checks.length ||
+      checks.some(check => !CAPABILITY_CHECKS.includes(check))
+    ) {
+      return res
+        .status(400)
+        .json({ error: `checks must be a list of: ${CAPABILITY_CHECKS.join(', ')}` })
+    }
+
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableFinished) controller.abort()
+    })
+    res.json(
+      await probeCapabilities({
+        provider,
+        apiKey,
+        baseUrl,
+        model,
+        checks: [...new Set(checks)],
+        refresh: refresh === true,
+        timeoutMs: timeoutMs === undefined ? undefined : resolveProbeTimeout(timeoutMs),
+        signal: controller.signal,
+      }),
+    )
+  } catch (error) {
+    console.error('[API] probeCapabilities error:', error)
+    sendError(res, error, 'Failed to probe provider capabilities')
+  }
+})
+
+export default router
@@ -0,0 +1,122 @@
+/**
+ * Quick Ask route
+ * POST /api/quick-ask
+ * Fast-path completion for the tray prompt window (short context, default model, no tools)
+ */
+
+import express from 'express'
+import { DEFAULT_MODELS } from '../services/providers/providerConfig.js'
+import { streamChat } from '../services/streamChatService.js'
+import { sendError, toErrorEvent } from '../utils/errors.js'
+import { createSseStream, getSseConfig } from '../utils/sse.js'
+
+const router = express.Router()
+
+const DEFAULT_CONTEXT_LIMIT = 4
+
+const QUICK_ASK_SYSTEM_PROMPT =
+  'You are a quick assistant answering from a small popup window. Answer directly and concisely. Prefer short paragraphs or a few bullets. Do not ask follow-up questions unless the request is ambiguous.'
+
+const getQuickAskContextLimit = () => {
+  const limit = Number.parseInt(process.env.QUICK_ASK_CONTEXT_LIMIT, 10)
+  return Number.isFinite(limit) && limit > 0 ? limit : DEFAULT_CONTEXT_LIMIT
+}
+
+/**
+ * POST /api/quick-ask
+ *
+ * Request body:
+ * {
+ *   "provider": "gemini" | "openai" | "openai_compatibility" | "siliconflow" | "glm" | "modelscope" | "kimi" | "nvidia" | "minimax",
+ *   "apiKey": "API key for the provider",
+ *   "baseUrl": "Custom base URL (optional)",
+ *   "model": "model-name" (optional, defaults to the provider default model),
+ *   "message": "Question text" (or "messages": [...] for a short follow-up thread),
+ *   "event_filter": { "include": ["text"] } (optional, see /api/stream-chat)
+ * }
+ *
+ * Response: Server-Sent Events stream (same event shapes as /api/stream-chat)
+ */
+router.post('/quick-ask', async (req, res) => {
+  try {
+    const { provider, apiKey, baseUrl, model, message, messages, temperature } = req.body
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!apiKey) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (!message && !Array.isArray(messages)) {
+      return res.status(400).json({ error: 'Missing required field: message' })
+    }
+
+    const supportedProviders = [
+      'gemini',
+      'openai',
+      'openai_compatibility',
+      'siliconflow',
+      'glm',
+      'modelscope',
+      'kimi',
+      'nvidia',
+      'minimax',
+    ]
+    if (!supportedProviders.includes(provider)) {
+      return res.status(400).json({
+        error: `Unsupported provider: ${provider}. Supported: ${supportedProviders.join(', ')}`,
+      })
+    }
+
+    const history = Array.isArray(messages)
+      ? messages.filter(m => m?.role !== 'system')
+      : [{ role: 'user', content: message }]
+    const quickMessages = [
+      { role: 'system', content: QUICK_ASK_SYSTEM_PROMPT },
+      ...history.slice(-getQuickAskContextLimit()),
+    ]
+
+    // Flush every chunk immediately: time-to-first-token matters more than packet count here
+    const sse = createSseStream(res, {
+      ...getSseConfig(),
+      flushMs: 0,
+      eventFilter: req.body.event_filter,
+    })
+    sse.writeComment('ok')
+
+    const controller = new AbortController()
+    req.on('aborted', () => controller.abort())
+    res.on('close', () => {
+      if (!res.writableEnded && !res.writableFinished) {
+        controller.abort()
+      }
+    })
+
+    for await (const chunk of streamChat({
+      provider,
+      apiKey,
+      baseUrl,
+      model: model || DEFAULT_MODELS[provider] || DEFAULT_MODELS.openai,
+      messages: quickMessages,
+      // Reasoning adds seconds before the first visible token
+      thinking: provider === 'glm' || provider === 'modelscope' ? { type: 'disabled' } : undefined,
+      temperature,
+      toolIds: [],
+      signal: controller.signal,
+    })) {
+      sse.sendEvent(chunk)
+    }
+
+    sse.close()
+  } catch (error) {
+    console.error('[API] quickAsk error:', error)
+    if (!res.headersSent) {
+      sendError(res, error, 'Failed to run quick ask')
+    } else {
+      res.write(`data: ${JSON.stringify(toErrorEvent(error))}\n\n`)
+      res.end()
+    }
+  }
+})
+
+export default router
@@ -0,0 +1,198 @@
+/**
+ * RAG routes
+ * Local knowledge base: ingest documentation sites, upload files, list/delete documents, and
+ * search chunks
+ */
+
+import express from 'express'
+import {
+  deleteDocument,
+  listDocuments,
+  saveUploadedDocument,
+  searchDocuments,
+} from '../services/ragStore.js'
+import { getSite, ingestSite, listSites } from '../services/siteIngestService.js'
+import { taskQueue } from '../services/taskQueue.js'
+import { readUploadFile } from '../services/uploadService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const DEFAULT_UPLOAD_LIMIT_MB = 20
+
+const resolveUploadLimit = () => {
+  const megabytes = Number.parseFloat(process.env.RAG_UPLOAD_MAX_MB)
+  return `${Number.isFinite(megabytes) && megabytes > 0 ? megabytes : DEFAULT_UPLOAD_LIMIT_MB}mb`
+}
+
+// File bodies of any type except JSON, which the app-wide parser already handled
+const parseUpload = express.raw({
+  type: req => !req.is('application/json'),
+  limit: resolveUploadLimit(),
+})
+
+/**
+ * POST /api/rag/ingest-site
+ * Pull a site into the knowledge base through its sitemap; re-posting the same site refreshes it
+ *
+ * Request body:
+ * {
+ *   "url": "https://docs.example.com",
+ *   "sitemapUrl": "https://docs.example.com/sitemap.xml" (optional; default: robots.txt
+ *     Sitemap lines or /sitemap.xml),
+ *   "pathPrefix": "/docs" (optional),
+ *   "maxPages": 200 (optional, max 2000),
+ *   "force": false (optional, refetch and re-embed unchanged pages),
+ *   "embedding": { "apiKey": "...", "baseUrl": "...", "model": "..." } (optional; defaults to
+ *     EMBEDDING_* env vars, lexical-only when unset),
+ *   "background": false (optional; true queues the ingestion and answers 202 { "task": {...} },
+ *     see GET /api/tasks/:id; the summary below becomes the task's "result")
+ * }
+ *
+ * Response: { "site_id": "...", "origin": "...", "pages": 120, "added": 3, "updated": 2,
+ *             "unchanged": 115, "removed": 1, "failed": [{ "url": "...", "error": "..." }],
+ *             "ingested_at": "..." }
+ */
+router.post('/rag/ingest-site', async (req, res) => {
+  try {
+    const { url, sitemapUrl, pathPrefix, maxPages, force, embedding, background } = req.body
+    if (!url) {
+      return res.status(400).json({ error: 'Missing required field: url' })
+    }
+    if (background) {
+      const task = taskQueue.enqueue(
+        'rag.ingest-site',
+        { url, sitemapUrl, pathPrefix, maxPages, force: Boolean(force), embedding },
+        { title: `Ingest ${url}`, dedupeKey: `rag.ingest-site:${url}:${pathPrefix || ''}` },
+      )
+      return res.status(202).json({ task })
+    }
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+    res.json(
+      await ingestSite({
+        url,
+        sitemapUrl,
+        pathPrefix,
+        maxPages,
+        force: Boolean(force),
+        embedding,
+        signal: controller.signal,
+      }),
+    )
+  } catch (error) {
+    console.error('[API] RAG ingest-site error:', error)
+    sendError(res, error, 'Failed to ingest site')
+  }
+})
+
+/**
+ * GET /api/rag/sites
+ * Ingested sites with page counts
+ */
+router.get('/rag/sites', (req, res) => {
+  res.json({ sites: listSites() })
+})
+
+/**
+ * GET /api/rag/sites/:id
+ * One ingested site with its page map (url -> { document_id, hash, lastmod })
+ */
+router.get('/rag/sites/:id', (req, res) => {
+  const site = getSite(req.params.id)
+  if (!site) {
+    return res.status(404).json({ error: `Site not found: ${req.params.id}` })
+  }
+  res.json(site)
+})
+
+/**
+ * GET /api/rag/documents?siteId=...
+ * Stored documents (without chunk bodies)
+ */
+router.get('/rag/documents', (req, res) => {
+  res.json({ documents: listDocuments({ siteId: req.query.siteId }) })
+})
+
+/**
+ * POST /api/rag/documents?filename=report.pdf&title=...&id=...
+ * Upload a file (.pdf, .docx, .md, .txt) into the knowledge base; the raw file is the request
+ * body (Content-Type of the file, up to RAG_UPLOAD_MAX_MB, default 20). Its text is extracted,
+ * chunked and embedded with EMBEDDING_* (lexical-only when unset). Re-uploading a file with the
+ * same name replaces the previous version unless another "id" is given.
+ * A JSON body { "title": "...", "text": "...", "id": "..." (optional) } stores plain text instead,
+ * and { "upload_id": "...", "title": "...", "id": "..." } a completed chunked upload (larger files;
+ * see POST /api/uploads).
+ * PDFs need a text layer; scanned PDFs are rejected with code "invalid_request".
+ *
+ * Response: { "document": { "id": "...", "title": "...", "source": { "type": "upload",
+ *             "filename": "report.pdf", "format": "pdf", "bytes": 48213 }, "chunk_count": 14,
+ *             "embedding_model": "...", "created_at": "...", "updated_at": "..." } }
+ */
+router.post('/rag/documents', parseUpload, async (req, res) => {
+  try {
+    const { filename, title, id } = req.query
+    const isFile = Buffer.isBuffer(req.body)
+    const uploadId = isFile ? null : req.body?.upload_id
+    if (isFile ? !req.body.length : !req.body?.text && !uploadId) {
+      return res.status(400).json({ error: 'Missing required field: file body, text or upload_id' })
+    }
+    const document = uploadId
+      ? await saveUploadedDocument({
+          ...readUploadFile(uploadId),
+          title: req.body.title,
+          id: req.body.id,
+        })
+      : await saveUploadedDocument({
+          buffer: isFile ? req.body : Buffer.from(String(req.body.text), 'utf8'),
+          filename: isFile ? filename : `${req.body.title || 'document'}.txt`,
+          mimeType: isFile ? req.get('content-type') : 'text/plain',
+          title: isFile ? title : req.body.title,
+          id: isFile ? id : req.body.id,
+        })
+    const { chunks, ...summary } = document
+    res.status(201).json({ document: { ...summary, chunk_count: chunks.length } })
+  } catch (error) {
+    console.error('[API] RAG upload error:', error)
+    sendError(res, error, 'Failed to store document')
+  }
+})
+
+/**
+ * DELETE /api/rag/documents/:id
+ */
+router.delete('/rag/documents/:id', (req, res) => {
+  if (!deleteDocument(req.params.id)) {
+    return res.status(404).json({ error: `Document not found: ${req.params.id}` })
+  }
+  res.json({ success: true })
+})
+
+/**
+ * POST /api/rag/search
+ *
+ * Request body: { "query": "...", "limit": 8 (optional), "siteId": "..." (optional),
+ *                 "documentIds": ["..."] (optional), "embedding": {...} (optional) }
+ *
+ * Response:
+ * {
+ *   "results": [{ "document_id": "...", "title": "...", "url": "...", "chunk": 0,
+ *                 "text": "...", "score": 0.71 }]
+ * }
+ */
+router.post('/rag/search', async (req, res) => {
+  try {
+    const { query, limit, siteId, documentIds, embedding } = req.body
+    if (!query) {
+      return res.status(400).json({ error: 'Missing required field: query' })
+    }
+    res.json({ results: await searchDocuments({ query, limit, siteId, documentIds, embedding }) })
+  } catch (error) {
+    console.error('[API] RAG search error:', error)
+    sendError(res, error, 'Failed to search documents')
+  }
+})
+
+export default router
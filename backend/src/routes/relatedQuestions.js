@@ -5,9 +5,20 @@
 
 import express from 'express'
 import { generateRelatedQuestions } from '../services/relatedQuestionsService.js'
+import {
+  isCacheBypassed,
+  setCacheStatusHeader,
+  withResponseCache,
+} from '../services/responseCache.js'
+import { sendError } from '../utils/errors.js'
 
 const router = express.Router()
 
+/**
+ * POST /api/related-questions
+ * Response: { "questions": ["..."], "cache_status": "hit" | "miss" | "bypass" | "disabled" }
+ * Identical requests are answered from the response cache (see POST /api/title).
+ */
 router.post('/related-questions', async (req, res) => {
   try {
     const { provider, messages, apiKey, baseUrl, model } = req.body
@@ -34,15 +45,18 @@ router.post('/related-questions', async (req, res) => {
 
     console.log(`[API] generateRelatedQuestions: provider=${provider}`)
 
-    const questions = await generateRelatedQuestions(provider, messages, apiKey, baseUrl, model)
+    const { value: questions, cacheStatus } = await withResponseCache(
+      'related-questions',
+      { provider, baseUrl, model, apiKey, messages },
+      () => generateRelatedQuestions(provider, messages, apiKey, baseUrl, model),
+      { bypass: isCacheBypassed(req), shouldCache: value => value?.length > 0 },
+    )
 
-    res.json({ questions })
+    setCacheStatusHeader(res, cacheStatus)
+    res.json({ questions, cache_status: cacheStatus })
   } catch (error) {
     console.error('[API] generateRelatedQuestions error:', error)
-    res.status(500).json({
-      error: 'Failed to generate related questions',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate related questions')
   }
 })
 
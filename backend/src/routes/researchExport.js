@@ -0,0 +1,54 @@
+/**
+ * Research export routes
+ * Download a deep research report as Markdown, PDF, or DOCX (see reportExport)
+ */
+
+import express from 'express'
+import { exportReport, exportResearchRun } from '../services/reportExport/index.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/research/export
+ * Render a finished report as a downloadable file
+ *
+ * Body (a persisted run, e.g. the "runId" of a stream-deep-research done event):
+ * { "run_id" | "runId": "...", "format": "markdown" | "md" | "pdf" | "docx" }
+ *
+ * Body (report content directly):
+ * {
+ *   "format": "pdf",
+ *   "content": "# Findings\n... [1] ...",
+ *   "sources": [{ "title": "...", "url": "https://..." }] (optional, in citation order),
+ *   "title": "..." (optional, document title and file name)
+ * }
+ *
+ * A "References" section listing the sources is appended unless the report already has one.
+ * PDFs use Helvetica, which only covers Latin scripts; set REPORT_PDF_FONT to the path of a
+ * TrueType font (e.g. NotoSansSC-Regular.ttf) to embed it for other scripts.
+ *
+ * Response: the file (Content-Disposition: attachment)
+ */
+router.post('/research/export', (req, res) => {
+  try {
+    const body = req.body || {}
+    const runId = body.runId ?? body.run_id
+    const file = runId
+      ? exportResearchRun(String(runId), body.format)
+      : exportReport({
+          format: body.format,
+          content: body.content,
+          sources: body.sources,
+          title: body.title,
+        })
+    res.attachment(file.filename)
+    res.type(file.mimeType)
+    res.send(file.buffer)
+  } catch (error) {
+    console.error('[API] research export error:', error)
+    sendError(res, error, 'Failed to export report')
+  }
+})
+
+export default router
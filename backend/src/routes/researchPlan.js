@@ -8,8 +8,12 @@ import {
   buildAcademicResearchPlanMessages,
   generateAcademicResearchPlan,
 } from '../services/academicResearchPlanService.js'
+import { resolveResearchTemplate } from '../prompts/researchTemplates.js'
+import { requiresApiKey } from '../services/providers/providerConfig.js'
 import { buildResearchPlanMessages, generateResearchPlan } from '../services/researchPlanService.js'
 import { streamChat } from '../services/streamChatService.js'
+import { recordUsage } from '../services/usageLedger.js'
+import { sendError, toErrorEvent } from '../utils/errors.js'
 import { createSseStream, getSseConfig } from '../utils/sse.js'
 
 const router = express.Router()
@@ -24,7 +28,8 @@ const router = express.Router()
  *   "message": "User message about research",
  *   "apiKey": "API key for the provider",
  *   "baseUrl": "Custom base URL (optional)",
- *   "model": "model-name" (optional)
+ *   "model": "model-name" (optional),
+ *   "researchType": "general" | "academic" | research template id (optional, alias research_type)
  * }
  *
  * Response:
@@ -34,7 +39,14 @@ const router = express.Router()
  */
 router.post('/research-plan', async (req, res) => {
   try {
-    const { provider, message, apiKey, baseUrl, model, researchType = 'general' } = req.body
+    const {
+      provider,
+      message,
+      apiKey,
+      baseUrl,
+      model,
+      researchType = req.body.research_type || 'general',
+    } = req.body
 
     if (!provider) {
       return res.status(400).json({ error: 'Missing required field: provider' })
@@ -42,7 +54,7 @@ router.post('/research-plan', async (req, res) => {
     if (!message) {
       return res.status(400).json({ error: 'Missing required field: message' })
     }
-    if (!apiKey) {
+    if (!apiKey && requiresApiKey(provider)) {
       return res.status(400).json({ error: 'Missing required field: apiKey' })
     }
 
@@ -55,6 +67,9 @@ router.post('/research-plan', async (req, res) => {
       'modelscope',
       'kimi',
       'nvidia',
+      'anthropic',
+      'ollama',
+      'local',
     ]
     if (!supportedProviders.includes(provider)) {
       return res.status(400).json({
@@ -72,21 +87,22 @@ router.post('/research-plan', async (req, res) => {
       `[API] Selected plan generator: ${researchType === 'academic' ? 'Academic' : 'General'}`,
     )
 
-    const plan = await planGenerator(provider, message, apiKey, baseUrl, model)
+    const plan = await planGenerator(provider, message, apiKey, baseUrl, model, {
+      planInstructions: resolveResearchTemplate(researchType)?.planInstructions,
+    })
 
     res.json({ plan })
   } catch (error) {
     console.error('[API] Research plan generation error:', error)
-    res.status(500).json({
-      error: 'Failed to generate research plan',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate research plan')
   }
 })
 
 /**
  * POST /api/research-plan-stream
  * Stream a structured deep research plan via SSE
+ * The done event carries "usage" token counts; pass "conversation_id" to book them in the
+ * usage ledger (GET /api/usage). "event_filter" suppresses event types (see /api/stream-chat).
  */
 router.post('/research-plan-stream', async (req, res) => {
   try {
@@ -104,13 +120,14 @@ router.post('/research-plan-stream', async (req, res) => {
       frequency_penalty,
       presence_penalty,
       contextMessageLimit,
-      researchType = 'general',
+      researchType = req.body.research_type || 'general',
+      conversationId = req.body.conversation_id, // Books token usage (GET /api/usage)
     } = req.body
 
     if (!provider || !message) {
       return res.status(400).json({ error: 'Missing required fields: provider, message' })
     }
-    if (!apiKey) {
+    if (!apiKey && requiresApiKey(provider)) {
       return res.status(400).json({ error: 'Missing required field: apiKey' })
     }
 
@@ -123,6 +140,9 @@ router.post('/research-plan-stream', async (req, res) => {
       'modelscope',
       'kimi',
       'nvidia',
+      'anthropic',
+      'ollama',
+      'local',
     ]
     if (!supportedProviders.includes(provider)) {
       return res.status(400).json({
@@ -132,7 +152,7 @@ router.post('/research-plan-stream', async (req, res) => {
 
     console.log(`[API] researchPlanStream: provider=${provider}, researchType=${researchType}`)
 
-    const sse = createSseStream(res, getSseConfig())
+    const sse = createSseStream(res, { ...getSseConfig(), eventFilter: req.body.event_filter })
     sse.writeComment('ok')
 
     const controller = new AbortController()
@@ -163,7 +183,9 @@ router.post('/research-plan-stream', async (req, res) => {
 
     const isAcademic = researchType === 'academic'
     const promptBuilder = isAcademic ? buildAcademicResearchPlanMessages : buildResearchPlanMessages
-    const promptMessages = promptBuilder(message)
+    const promptMessages = promptBuilder(message, {
+      planInstructions: resolveResearchTemplate(researchType)?.planInstructions,
+    })
 
     console.log(`[API] Streaming research plan with type: ${isAcademic ? 'Academic' : 'General'}`)
     for await (const chunk of streamChat({
@@ -180,8 +202,12 @@ router.post('/research-plan-stream', async (req, res) => {
       frequency_penalty,
       presence_penalty,
       contextMessageLimit,
+      applyPreferences: false,
       signal: controller.signal,
     })) {
+      if (chunk?.type === 'done') {
+        recordUsage({ conversationId, kind: 'research_plan', provider, model, usage: chunk.usage })
+      }
       sse.sendEvent(chunk)
     }
 
@@ -189,12 +215,9 @@ router.post('/research-plan-stream', async (req, res) => {
   } catch (error) {
     console.error('[API] researchPlanStream error:', error)
     if (!res.headersSent) {
-      res.status(500).json({
-        error: 'Failed to stream research plan',
-        message: error.message,
-      })
+      sendError(res, error, 'Failed to stream research plan')
     } else {
-      res.write(`data: ${JSON.stringify({ type: 'error', error: error.message })}\n\n`)
+      res.write(`data: ${JSON.stringify(toErrorEvent(error))}\n\n`)
       res.end()
     }
   }
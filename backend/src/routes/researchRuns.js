@@ -0,0 +1,61 @@
+/**
+ * Research runs routes
+ * Persisted deep research runs with their per-step stats
+ */
+
+import express from 'express'
+import { isResearchRunActive } from '../services/deepResearchAgentService.js'
+import {
+  deleteResearchRun,
+  getResearchRun,
+  listResearchRuns,
+} from '../services/researchRunStore.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+// A "running" record that no stream in this process is driving was cut off (crash or restart)
+const withLiveStatus = run =>
+  run.status === 'running' && !isResearchRunActive(run.id) ? { ...run, status: 'interrupted' } : run
+
+/**
+ * GET /api/research-runs?limit=50
+ * List persisted runs, newest first, with completedSteps/totalSteps progress. Status is
+ * running | done | error | aborted, or interrupted for a run the backend stopped in the middle
+ * of (resume it with POST /api/research-runs/:id/resume)
+ */
+router.get('/research-runs', (req, res) => {
+  try {
+    const limit = Number.parseInt(req.query.limit, 10)
+    const runs = listResearchRuns({ limit: Number.isFinite(limit) ? limit : undefined })
+    res.json({ runs: runs.map(withLiveStatus) })
+  } catch (error) {
+    console.error('[API] listResearchRuns error:', error)
+    sendError(res, error, 'Failed to list research runs')
+  }
+})
+
+/**
+ * GET /api/research-runs/:id
+ * Return a full run record (plan, completed_steps findings, report, sources, stats)
+ */
+router.get('/research-runs/:id', (req, res) => {
+  const run = getResearchRun(req.params.id)
+  if (!run) {
+    return res.status(404).json({ error: `Research run not found: ${req.params.id}` })
+  }
+  res.json({ run: withLiveStatus(run) })
+})
+
+/**
+ * DELETE /api/research-runs/:id
+ */
+router.delete('/research-runs/:id', (req, res) => {
+  const deleted = deleteResearchRun(req.params.id)
+  if (!deleted) {
+    return res.status(404).json({ error: `Research run not found: ${req.params.id}` })
+  }
+  res.json({ success: true })
+})
+
+export default router
@@ -0,0 +1,214 @@
+/**
+ * Saved prompts routes
+ * CRUD for request templates and POST /api/prompts/:id/run to expand and stream one
+ */
+
+import express from 'express'
+import {
+  createSavedPrompt,
+  deleteSavedPrompt,
+  expandSavedPrompt,
+  getSavedPrompt,
+  listSavedPrompts,
+  PromptTemplateError,
+  updateSavedPrompt,
+} from '../services/savedPromptService.js'
+import { streamChat } from '../services/streamChatService.js'
+import { sendError, toErrorEvent } from '../utils/errors.js'
+import { createSseStream, getSseConfig } from '../utils/sse.js'
+
+const router = express.Router()
+
+const sendTemplateError = (res, error, fallback) => {
+  if (error instanceof PromptTemplateError) {
+    return res.status(400).json({ error: error.message, details: error.details })
+  }
+  console.error(`[API] ${fallback} error:`, error)
+  return sendError(res, error, `Failed to ${fallback}`)
+}
+
+/**
+ * GET /api/prompts
+ */
+router.get('/prompts', (req, res) => {
+  try {
+    res.json({ prompts: listSavedPrompts() })
+  } catch (error) {
+    sendTemplateError(res, error, 'list prompts')
+  }
+})
+
+/**
+ * GET /api/prompts/:id
+ */
+router.get('/prompts/:id', (req, res) => {
+  const prompt = getSavedPrompt(req.params.id)
+  if (!prompt) {
+    return res.status(404).json({ error: `Prompt not found: ${req.params.id}` })
+  }
+  res.json({ prompt })
+})
+
+/**
+ * POST /api/prompts
+ * Body:
+ * {
+ *   "name": "Code review",
+ *   "description": "...",
+ *   "params": [{ "name": "language", "type": "string|text|number|boolean|enum", "required": true, "default": "...", "options": [...] }],
+ *   "messages": [{ "role": "system|user|assistant", "content": "Review this {{language}} code: {{code}}" }],
+ *   "toolIds": ["calculator"] (optional),
+ *   "settings": { "temperature": 0.2 } (optional)
+ * }
+ */
+router.post('/prompts', (req, res) => {
+  try {
+    res.status(201).json({ prompt: createSavedPrompt(req.body) })
+  } catch (error) {
+    sendTemplateError(res, error, 'create prompt')
+  }
+})
+
+/**
+ * PUT /api/prompts/:id
+ */
+router.put('/prompts/:id', (req, res) => {
+  try {
+    const prompt = updateSavedPrompt(req.params.id, req.body)
+    if (!prompt) {
+      return res.status(404).json({ error: `Prompt not found: ${req.params.id}` })
+    }
+    res.json({ prompt })
+  } catch (error) {
+    sendTemplateError(res, error, 'update prompt')
+  }
+})
+
+/**
+ * DELETE /api/prompts/:id
+ */
+router.delete('/prompts/:id', (req, res) => {
+  const deleted = deleteSavedPrompt(req.params.id)
+  if (!deleted) {
+    return res.status(404).json({ error: `Prompt not found: ${req.params.id}` })
+  }
+  res.json({ success: true })
+})
+
+/**
+ * POST /api/prompts/:id/run
+ * Expand a saved prompt with parameters and stream the answer
+ *
+ * Request body:
+ * {
+ *   "provider": "...", "apiKey": "...", "baseUrl": "..." (optional), "model": "..." (optional),
+ *   "params": { "language": "Rust", "code": "..." },
+ *   "messages": [...] (optional, prior conversation placed before the template messages),
+ *   "searchProvider", "searchApiKey", "searchBaseUrl", "tavilyApiKey" (optional, web search
+ *     backend and keys; see /api/stream-chat),
+ *   "event_filter": [...] (optional, see /api/stream-chat)
+ * }
+ *
+ * Response: Server-Sent Events stream (same events as /api/stream-chat)
+ */
+router.post('/prompts/:id/run', async (req, res) => {
+  try {
+    const {
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      params,
+      messages,
+      searchProvider,
+      searchApiKey,
+      searchBaseUrl,
+      tavilyApiKey,
+    } = req.body
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!apiKey) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+
+    const supportedProviders = [
+      'gemini',
+      'openai',
+      'openai_compatibility',
+      'siliconflow',
+      'glm',
+      'modelscope',
+      'kimi',
+      'nvidia',
+      'minimax',
+    ]
+    if (!supportedProviders.includes(provider)) {
+      return res.status(400).json({
+        error: `Unsupported provider: ${provider}. Supported: ${supportedProviders.join(', ')}`,
+      })
+    }
+
+    const template = getSavedPrompt(req.params.id)
+    if (!template) {
+      return res.status(404).json({ error: `Prompt not found: ${req.params.id}` })
+    }
+
+    let expanded
+    try {
+      expanded = expandSavedPrompt(template, params || {})
+    } catch (error) {
+      return sendTemplateError(res, error, 'expand prompt')
+    }
+
+    const sse = createSseStream(res, { ...getSseConfig(), eventFilter: req.body.event_filter })
+    sse.writeComment('ok')
+
+    const controller = new AbortController()
+    req.on('aborted', () => {
+      controller.abort()
+    })
+    res.on('close', () => {
+      if (!res.writableEnded && !res.writableFinished) {
+        controller.abort()
+      }
+    })
+
+    const { temperature, top_k, top_p, frequency_penalty, presence_penalty, responseFormat } =
+      expanded.settings
+    for await (const chunk of streamChat({
+      provider,
+      apiKey,
+      baseUrl,
+      model: model || expanded.settings.model,
+      messages: [...(Array.isArray(messages) ? messages : []), ...expanded.messages],
+      temperature,
+      top_k,
+      top_p,
+      frequency_penalty,
+      presence_penalty,
+      responseFormat,
+      toolIds: expanded.toolIds,
+      searchProvider,
+      searchApiKey,
+      searchBaseUrl,
+      tavilyApiKey,
+      signal: controller.signal,
+    })) {
+      sse.sendEvent(chunk)
+    }
+
+    sse.close()
+  } catch (error) {
+    console.error('[API] runSavedPrompt error:', error)
+    if (!res.headersSent) {
+      sendError(res, error, 'Failed to run prompt')
+    } else {
+      res.write(`data: ${JSON.stringify(toErrorEvent(error))}\n\n`)
+      res.end()
+    }
+  }
+})
+
+export default router
@@ -0,0 +1,77 @@
+/**
+ * Screenshot routes
+ * Rendered page screenshots attached to sources for visual citation checks
+ */
+
+import express from 'express'
+import { getResearchRun } from '../services/researchRunStore.js'
+import {
+  captureScreenshot,
+  getScreenshot,
+  readScreenshotImage,
+  toScreenshotRef,
+} from '../services/screenshotService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/screenshots
+ * Render a page and store its screenshot
+ *
+ * Request body:
+ * {
+ *   "url": "https://example.com/chart",
+ *   "runId": "..." (optional, attach to this research run's source with the same URL)
+ * }
+ *
+ * Response: { "id": "...", "url": "...", "captured_at": "...",
+ *             "image_url": "/api/screenshots/:id/image" }
+ */
+router.post('/screenshots', async (req, res) => {
+  const { url, runId = req.body.run_id } = req.body || {}
+  if (!url) {
+    return res.status(400).json({ error: 'Missing required field: url' })
+  }
+  if (runId && !getResearchRun(runId)) {
+    return res.status(404).json({ error: `Research run not found: ${runId}` })
+  }
+  try {
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+    const record = await captureScreenshot(url, { runId, signal: controller.signal })
+    res.json(toScreenshotRef(record))
+  } catch (error) {
+    console.error('[API] screenshot error:', error)
+    sendError(res, error, 'Failed to capture screenshot')
+  }
+})
+
+/**
+ * GET /api/screenshots/:id
+ * Screenshot metadata
+ */
+router.get('/screenshots/:id', (req, res) => {
+  const record = getScreenshot(req.params.id)
+  if (!record) {
+    return res.status(404).json({ error: `Screenshot not found: ${req.params.id}` })
+  }
+  res.json({ ...toScreenshotRef(record), mime_type: record.mime_type, bytes: record.bytes })
+})
+
+/**
+ * GET /api/screenshots/:id/image
+ * Screenshot image bytes
+ */
+router.get('/screenshots/:id/image', (req, res) => {
+  const image = readScreenshotImage(req.params.id)
+  if (!image) {
+    return res.status(404).json({ error: `Screenshot not found: ${req.params.id}` })
+  }
+  res.set('Cache-Control', 'private, max-age=31536000, immutable')
+  res.type(image.mimeType).send(image.buffer)
+})
+
+export default router
@@ -0,0 +1,70 @@
+/**
+ * Session routes
+ * Per-window sessions: each window asks for a session id, passes it as session_id when starting
+ * chat or deep research streams, and cancels just its own streams when it closes
+ */
+
+import express from 'express'
+import { sessionRegistry } from '../services/sessionRegistry.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/sessions
+ * Issue a session id for a window
+ *
+ * Request body (optional):
+ * {
+ *   "label": "main" (window label, shown in GET /api/sessions)
+ * }
+ *
+ * Response (201): { "session": { "session_id": "...", "label": "main", "created_at": "...",
+ *                                "active_streams": 0 } }
+ */
+router.post('/sessions', (req, res) => {
+  res.status(201).json({ session: sessionRegistry.create({ label: req.body?.label }) })
+})
+
+/**
+ * GET /api/sessions
+ * Issued sessions with their number of running streams
+ */
+router.get('/sessions', (req, res) => {
+  res.json({ sessions: sessionRegistry.list() })
+})
+
+/**
+ * GET /api/sessions/:id/active
+ * Running streams started with this session id (empty for unknown sessions)
+ * Response: { "session_id": "...", "streams": [{ "stream_id", "kind", "session_id",
+ *             "started_at", "cancelled" }] }
+ */
+router.get('/sessions/:id/active', (req, res) => {
+  res.json({ session_id: req.params.id, streams: sessionRegistry.active(req.params.id) })
+})
+
+/**
+ * DELETE /api/sessions/:id/active
+ * Cancel the session's running streams; each SSE response ends with a {"type":"cancelled"} event
+ * Response: { "success": true, "session_id": "...", "cancelled": ["<stream id>", ...] }
+ */
+router.delete('/sessions/:id/active', (req, res) => {
+  const { cancelled } = sessionRegistry.close(req.params.id)
+  res.json({ success: true, session_id: req.params.id, cancelled })
+})
+
+/**
+ * DELETE /api/sessions/:id
+ * End a session when its window closes: cancels its streams and forgets the id
+ * Response: { "success": true, "session_id": "...", "cancelled": [...] } (404 when the session
+ * was never issued and had no streams)
+ */
+router.delete('/sessions/:id', (req, res) => {
+  const { cancelled, existed } = sessionRegistry.close(req.params.id, { forget: true })
+  if (!existed && !cancelled.length) {
+    return res.status(404).json({ error: `Session not found: ${req.params.id}` })
+  }
+  res.json({ success: true, session_id: req.params.id, cancelled })
+})
+
+export default router
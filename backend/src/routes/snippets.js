@@ -0,0 +1,97 @@
+/**
+ * Snippets routes
+ * Save, search, and manage reusable answer snippets; pass snippetIds to /api/stream-chat to
+ * insert them as context blocks
+ */
+
+import express from 'express'
+import {
+  createSnippet,
+  deleteSnippet,
+  getSnippet,
+  searchSnippets,
+  SnippetError,
+  updateSnippet,
+} from '../services/snippetService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const sendSnippetError = (res, error, fallback) => {
+  if (error instanceof SnippetError) {
+    return res.status(400).json({ error: error.message, details: error.details })
+  }
+  console.error(`[API] ${fallback} error:`, error)
+  return sendError(res, error, `Failed to ${fallback}`)
+}
+
+/**
+ * GET /api/snippets?q=...&tags=a,b&limit=20
+ * Newest first without q, otherwise ranked by relevance
+ */
+router.get('/snippets', (req, res) => {
+  try {
+    const { q, tags, limit } = req.query
+    res.json({ snippets: searchSnippets({ query: q, tags, limit }) })
+  } catch (error) {
+    sendSnippetError(res, error, 'search snippets')
+  }
+})
+
+/**
+ * GET /api/snippets/:id
+ */
+router.get('/snippets/:id', (req, res) => {
+  const snippet = getSnippet(req.params.id)
+  if (!snippet) {
+    return res.status(404).json({ error: `Snippet not found: ${req.params.id}` })
+  }
+  res.json({ snippet })
+})
+
+/**
+ * POST /api/snippets
+ * Body:
+ * {
+ *   "content": "Selected answer text",
+ *   "title": "..." (optional, defaults to the first line),
+ *   "tags": ["sql", "postgres"] (optional),
+ *   "source": { "conversationId": "...", "messageId": "...", "url": "..." } (optional)
+ * }
+ * Response: { "snippet": {...} }
+ */
+router.post('/snippets', (req, res) => {
+  try {
+    res.status(201).json({ snippet: createSnippet(req.body) })
+  } catch (error) {
+    sendSnippetError(res, error, 'save snippet')
+  }
+})
+
+/**
+ * PATCH /api/snippets/:id
+ * Body: any of title, content, tags, source
+ */
+router.patch('/snippets/:id', (req, res) => {
+  try {
+    const snippet = updateSnippet(req.params.id, req.body)
+    if (!snippet) {
+      return res.status(404).json({ error: `Snippet not found: ${req.params.id}` })
+    }
+    res.json({ snippet })
+  } catch (error) {
+    sendSnippetError(res, error, 'update snippet')
+  }
+})
+
+/**
+ * DELETE /api/snippets/:id
+ */
+router.delete('/snippets/:id', (req, res) => {
+  if (!deleteSnippet(req.params.id)) {
+    return res.status(404).json({ error: `Snippet not found: ${req.params.id}` })
+  }
+  res.json({ success: true })
+})
+
+export default router
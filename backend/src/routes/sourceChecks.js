@@ -0,0 +1,60 @@
+/**
+ * Source check routes
+ * Link rot status of sources cited by saved research runs
+ */
+
+import express from 'express'
+import { LINK_STATUSES, listSourceChecks, runLinkCheck } from '../services/linkCheckService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/source-checks?status=broken
+ * Latest check per source URL, most recently changed first
+ *
+ * Response:
+ * {
+ *   "checks": [{ "url": "...", "status": "broken", "http_status": 404, "final_url": "...",
+ *                "previous_status": "ok", "checked_at": "...", "changed_at": "...",
+ *                "archived": { "url": "https://web.archive.org/web/...", "archived_at": "..." } }]
+ * }
+ */
+router.get('/source-checks', (req, res) => {
+  const { status } = req.query
+  if (status && !LINK_STATUSES.includes(status)) {
+    return res.status(400).json({ error: `Invalid status: ${status}` })
+  }
+  res.json({ checks: listSourceChecks({ status }) })
+})
+
+/**
+ * POST /api/source-checks/run
+ * Run a check pass now (the "link-check" background job runs it periodically)
+ *
+ * Request body: { "runId": "..." (optional, check every source of one run), "batchSize": 50 }
+ *
+ * Response: { "checked": 12, "changed": 2, "runs_marked": 3,
+ *             "by_status": { "ok": 9, "broken": 2, "redirected": 1, ... } }
+ */
+router.post('/source-checks/run', async (req, res) => {
+  try {
+    const { runId, batchSize } = req.body || {}
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+    res.json(
+      await runLinkCheck({
+        runId,
+        batchSize: Number.parseInt(batchSize, 10) || undefined,
+        signal: controller.signal,
+      }),
+    )
+  } catch (error) {
+    console.error('[API] source check error:', error)
+    sendError(res, error, 'Failed to check sources')
+  }
+})
+
+export default router
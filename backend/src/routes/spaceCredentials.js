@@ -0,0 +1,82 @@
+/**
+ * Space credentials routes
+ * Pin provider credentials to a space; keys are stored encrypted and never returned in full.
+ */
+
+import express from 'express'
+import {
+  deleteSpaceCredentials,
+  getSpaceCredentialsInfo,
+  listSpaceCredentials,
+  setSpaceCredentials,
+} from '../services/keyVault.js'
+import { isProviderSupported } from '../services/providers/adapterFactory.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/spaces/credentials
+ * List spaces with pinned credentials (masked)
+ */
+router.get('/spaces/credentials', (req, res) => {
+  try {
+    res.json({ credentials: listSpaceCredentials() })
+  } catch (error) {
+    console.error('[API] listSpaceCredentials error:', error)
+    sendError(res, error, 'Failed to list space credentials')
+  }
+})
+
+/**
+ * GET /api/spaces/:spaceId/credentials
+ * Response: { credentials: { spaceId, provider, baseUrl, model, apiKeyMasked, updatedAt } }
+ */
+router.get('/spaces/:spaceId/credentials', (req, res) => {
+  const credentials = getSpaceCredentialsInfo(req.params.spaceId)
+  if (!credentials) {
+    return res
+      .status(404)
+      .json({ error: `No credentials pinned for space: ${req.params.spaceId}` })
+  }
+  res.json({ credentials })
+})
+
+/**
+ * PUT /api/spaces/:spaceId/credentials
+ * Body: { provider, apiKey, baseUrl?, model? }
+ * Requests that send this spaceId (or space_id) use these credentials instead of their own.
+ */
+router.put('/spaces/:spaceId/credentials', (req, res) => {
+  try {
+    const { provider, apiKey, baseUrl, model } = req.body || {}
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!apiKey) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (!isProviderSupported(provider)) {
+      return res.status(400).json({ error: `Unsupported provider: ${provider}` })
+    }
+    res.json({ credentials: setSpaceCredentials(req.params.spaceId, req.body) })
+  } catch (error) {
+    console.error('[API] setSpaceCredentials error:', error)
+    sendError(res, error, 'Failed to save space credentials')
+  }
+})
+
+/**
+ * DELETE /api/spaces/:spaceId/credentials
+ */
+router.delete('/spaces/:spaceId/credentials', (req, res) => {
+  const deleted = deleteSpaceCredentials(req.params.spaceId)
+  if (!deleted) {
+    return res
+      .status(404)
+      .json({ error: `No credentials pinned for space: ${req.params.spaceId}` })
+  }
+  res.json({ success: true })
+})
+
+export default router
@@ -0,0 +1,80 @@
+/**
+ * Space glossary routes
+ * Per-space terminology injected into chat/research prompts and checked in answers
+ * (see spaceGlossaryService)
+ */
+
+import express from 'express'
+import {
+  checkTerminology,
+  deleteSpaceGlossary,
+  getSpaceGlossary,
+  setSpaceGlossary,
+} from '../services/spaceGlossaryService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const notFound = (res, spaceId) =>
+  res.status(404).json({ error: `No glossary defined for space: ${spaceId}` })
+
+/**
+ * GET /api/spaces/:spaceId/glossary
+ * Response: { glossary: { space_id, terms: [...], banned: [...], updated_at } }
+ */
+router.get('/spaces/:spaceId/glossary', (req, res) => {
+  const glossary = getSpaceGlossary(req.params.spaceId)
+  if (!glossary) return notFound(res, req.params.spaceId)
+  res.json({ glossary })
+})
+
+/**
+ * PUT /api/spaces/:spaceId/glossary
+ * Replace the glossary of a space (at most 500 entries)
+ *
+ * Body:
+ * {
+ *   "terms": [{ "term": "churn", "preferred": "Kundenabwanderung" (or "translation"),
+ *               "definition": "..." (optional), "avoid": ["Churn-Rate", "Abgang"] (optional) }],
+ *   "banned": ["leverage", { "term": "guarantee", "reason": "legal", "replacement": "aim" }]
+ * }
+ *
+ * Requests to /api/stream-chat and /api/stream-deep-research with this space_id get the rules in
+ * their system prompt, and final answers using "avoid" variants or banned terms are flagged in a
+ * "terminology_violations" event and the done event's "terminology_check".
+ */
+router.put('/spaces/:spaceId/glossary', (req, res) => {
+  try {
+    res.json({ glossary: setSpaceGlossary(req.params.spaceId, req.body || {}) })
+  } catch (error) {
+    console.error('[API] setSpaceGlossary error:', error)
+    sendError(res, error, 'Failed to save glossary')
+  }
+})
+
+/**
+ * DELETE /api/spaces/:spaceId/glossary
+ */
+router.delete('/spaces/:spaceId/glossary', (req, res) => {
+  if (!deleteSpaceGlossary(req.params.spaceId)) return notFound(res, req.params.spaceId)
+  res.json({ success: true })
+})
+
+/**
+ * POST /api/spaces/:spaceId/glossary/check
+ * Check any text (e.g. an edited report) against the space glossary
+ * Body: { "text": "..." }
+ * Response: { "violations": [{ "rule": "avoid" | "banned", "term", "expected", "reason", "count",
+ *                              "matches": [{ "index", "text" }] }] }
+ */
+router.post('/spaces/:spaceId/glossary/check', (req, res) => {
+  const { text } = req.body || {}
+  if (typeof text !== 'string') {
+    return res.status(400).json({ error: 'Missing required field: text' })
+  }
+  const glossary = getSpaceGlossary(req.params.spaceId)
+  if (!glossary) return notFound(res, req.params.spaceId)
+  res.json(checkTerminology(text, glossary))
+})
+
+export default router
@@ -0,0 +1,110 @@
+/**
+ * Storage routes
+ * Disk usage of the local data directory and pruning operations (see storageService)
+ */
+
+import express from 'express'
+import {
+  ATTACHMENT_KINDS,
+  deleteOldAttachments,
+  getStorageUsage,
+  pruneCaches,
+  vacuumDataStore,
+} from '../services/storageService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/storage/usage
+ * Response:
+ * {
+ *   "data_dir": "/home/me/.qurio/data", "total_bytes": 48213004, "total_files": 812,
+ *   "categories": [{ "category": "conversations" | "research" | "attachments" | "rag_indexes" |
+ *                    "caches" | "other", "bytes": 0, "files": 0,
+ *                    "collections": [{ "name": "uploads", "bytes": 0, "files": 0 }] }]
+ * }
+ */
+router.get('/storage/usage', (req, res) => {
+  try {
+    res.json(getStorageUsage())
+  } catch (error) {
+    console.error('[API] storage usage error:', error)
+    sendError(res, error, 'Failed to measure storage')
+  }
+})
+
+/**
+ * POST /api/storage/prune-caches
+ * Delete link check results and clear the model catalog and response caches; all are rebuilt on
+ * demand
+ * Response:
+ * { "cleared": ["source-checks", "model-catalog", "responses"], "removed_records": 0,
+ *   "freed_bytes": 0 }
+ */
+router.post('/storage/prune-caches', (req, res) => {
+  try {
+    res.json(pruneCaches())
+  } catch (error) {
+    console.error('[API] storage prune caches error:', error)
+    sendError(res, error, 'Failed to prune caches')
+  }
+})
+
+/**
+ * POST /api/storage/vacuum
+ * Compact the data store: temp files of interrupted writes, attachment files without a record,
+ * expired unfinished uploads, and empty collections. Unreadable records are listed, not deleted.
+ * Response: { "removed_files": 0, "freed_bytes": 0, "expired_uploads": 0, "corrupt_records": [] }
+ */
+router.post('/storage/vacuum', (req, res) => {
+  try {
+    res.json(vacuumDataStore())
+  } catch (error) {
+    console.error('[API] storage vacuum error:', error)
+    sendError(res, error, 'Failed to vacuum data store')
+  }
+})
+
+/**
+ * POST /api/storage/attachments/prune
+ * Bulk-delete attachments created before a cutoff
+ *
+ * Body:
+ * {
+ *   "older_than_days" | "olderThanDays": 90,
+ *   "kinds": ["uploads", "screenshots"] (optional, default both),
+ *   "dry_run" | "dryRun": true (optional, only list what would be deleted)
+ * }
+ *
+ * Response: { "cutoff": "...", "dry_run": false, "freed_bytes": 0,
+ *             "deleted": [{ "kind": "uploads", "id": "...", "name": "...", "bytes": 0,
+ *                           "created_at": "..." }] }
+ */
+router.post('/storage/attachments/prune', (req, res) => {
+  try {
+    const body = req.body || {}
+    const olderThanDays = Number(body.olderThanDays ?? body.older_than_days)
+    if (!Number.isFinite(olderThanDays) || olderThanDays < 0) {
+      return res.status(400).json({ error: 'Missing required field: older_than_days' })
+    }
+    const kinds = body.kinds ?? ATTACHMENT_KINDS
+    if (!Array.isArray(kinds) || kinds.some(kind => !ATTACHMENT_KINDS.includes(kind))) {
+      return res
+        .status(400)
+        .json({ error: `kinds must be a subset of: ${ATTACHMENT_KINDS.join(', ')}` })
+    }
+    res.json(
+      deleteOldAttachments({
+        olderThanDays,
+        kinds,
+        dryRun: Boolean(body.dryRun ?? body.dry_run),
+      }),
+    )
+  } catch (error) {
+    console.error('[API] storage attachments prune error:', error)
+    sendError(res, error, 'Failed to delete attachments')
+  }
+})
+
+export default router
@@ -1,11 +1,14 @@
 /**
  * Stream Chat route
  * POST /api/stream-chat
- * Uses Server-Sent Events (SSE) for streaming responses
+ * Uses Server-Sent Events (SSE) for streaming responses; GET /api/ws/chat (routes/wsChat.js)
+ * streams the same events over a WebSocket
  */
 
 import express from 'express'
-import { streamChat } from '../services/streamChatService.js'
+import { listSlashCommands } from '../services/slashCommandService.js'
+import { prepareStreamChat, runStreamChat } from '../services/streamChatRunner.js'
+import { sendError } from '../utils/errors.js'
 import { createSseStream, getSseConfig } from '../utils/sse.js'
 
 const router = express.Router()
@@ -20,7 +23,13 @@ const router = express.Router()
  *   "apiKey": "API key for the provider",
  *   "baseUrl": "Custom base URL (optional)",
  *   "model": "model-name" (optional),
- *   "messages": [...],
+ *   "messages": [...] (user content parts may attach files: { "type": "upload", "upload_id" }
+ *     for chunked uploads (POST /api/uploads), { "type": "image_base64", "data" }, file:// image
+ *     URLs, or { "type": "file" | "document", "path" | "data" }; images are sent as image parts,
+ *     PDFs natively where the provider supports documents, other files as extracted text;
+ *     see attachmentService),
+ *   (images, "tools" and a json_object "responseFormat" are refused with 400 when the model was
+ *   probed as lacking that capability; see POST /api/providers/:name/probe)
  *   "tools": [...] (optional),
  *   "toolChoice": ... (optional),
  *   "responseFormat": {...} (optional),
@@ -31,129 +40,159 @@ const router = express.Router()
  *   "frequency_penalty": 0 (optional),
  *   "presence_penalty": 0 (optional),
  *   "contextMessageLimit": 10 (optional),
- *   "toolIds": ["calculator", "local_time"] (optional),
- *   "searchProvider": "tavily" (optional),
- *   "tavilyApiKey": "Tavily API key" (optional)
+ *   "memory_token_budget" | "memoryTokenBudget": 8000 (optional, 500-1000000, 0 = off; default
+ *     CONVERSATION_MEMORY_TOKEN_BUDGET or off: once the history is over this many tokens, older
+ *     turns are summarized into a "conversation memory" system note that replaces them, cached
+ *     per conversation_id; with contextMessageLimit, messages past the limit are summarized
+ *     instead of dropped),
+ *   "memory_model" | "memoryModel": "..." (optional, writes the memory with the chat's provider;
+ *     default CONVERSATION_MEMORY_MODEL or the provider's default model),
+ *   "toolIds": ["calculator", "local_time"] (optional; with provider "gemini",
+ *     "google_search_grounding" and "gemini_code_execution" enable Gemini's built-in Google Search
+ *     and code execution: grounded pages are returned in the done event's "sources", and executed
+ *     code is reported as tool_call / tool_result events named "gemini_code_execution"; with
+ *     provider "glm", "glm_native_search" enables GLM's built-in web search in place of the
+ *     "web_search" tool, and the pages it used are returned in "sources"; "webpage_reader" reads
+ *     a page's main content as Markdown with its title, author, and date, one page of text per
+ *     call, and read pages are returned in "sources"),
+ *   "searchProvider" | "search_provider": "tavily" | "searxng" | "brave" | "serper" | "bing"
+ *     (optional, backend of the web/academic search tools; default SEARCH_PROVIDER or tavily),
+ *   "searchApiKey" | "search_api_key": key for that provider (optional, falls back to
+ *     TAVILY_API_KEY, BRAVE_SEARCH_API_KEY, SERPER_API_KEY, BING_SEARCH_API_KEY, SEARXNG_API_KEY),
+ *   "searchBaseUrl" | "search_base_url": instance URL (optional; SearXNG needs it or
+ *     SEARXNG_BASE_URL),
+ *   "tavilyApiKey": "Tavily API key" (optional),
+ *   "image_provider": "openai" | "openai_compatibility" | "gemini" | "siliconflow" (optional,
+ *     backend of the generate_image tool; defaults to the chat provider when it is one of these),
+ *   "image_api_key", "image_base_url", "image_model" (optional, for image_provider; without
+ *     image_provider the chat's apiKey and baseUrl are used),
+ *   "smartMode": false (optional, split multi-part questions and answer each part),
+ *   "userId": "..." (optional, selects stored preferences; see /api/preferences),
+ *   "maxTurns" | "max_turns": 10 (optional, model turns of the tool-calling loop, 1-50; default
+ *     MAX_STREAM_TURNS or 10),
+ *   "snippetIds": ["..."] (optional, saved snippets inserted as context; see /api/snippets),
+ *   "mcp_servers": ["name"] | true (optional, expose the tools of MCP servers loaded through
+ *     POST /api/mcp-tools/servers; each call is forwarded to its server and reported as
+ *     tool_call / tool_result events),
+ *   "stream_id": "..." (optional, id for POST /api/streams/:id/cancel; generated when omitted),
+ *   "session_id": "..." (optional, the window's session from POST /api/sessions; closing the
+ *     window cancels its streams via DELETE /api/sessions/:id/active),
+ *   "conversation_id": "..." (optional, books token usage to this conversation, see /api/usage,
+ *     and keys its conversation memory),
+ *   "persist_message": true (optional, with a conversation stored on the backend: the answer is
+ *     saved into it while it streams, crash-safe via a write-ahead journal; the message has status
+ *     "streaming", then "complete" | "cancelled" | "error", or "interrupted" after a crash),
+ *   "message_id": "..." (optional, id of the stored answer; generated when omitted),
+ *   "event_filter": ["thought", "tool_call", "tool_result"] | { "include": ["text"] } (optional,
+ *     suppress SSE event types server-side; done, error and cancelled are always sent),
+ *   "context_cache" | "contextCache": "cache-..." | { "id": "cache-...", "reset_ttl": 3600 }
+ *     (optional, provider "kimi" only: documents cached with POST /api/providers/kimi/caches are
+ *     put ahead of the conversation without resending them; reset_ttl extends the cache on use),
+ *   "audio": true | { "voice": "male-qn-qingse", "format": "mp3|wav|flac|pcm", "model":
+ *     "speech-02-turbo", "speed": 1 } (optional, providers with supportsAudioOutput (minimax):
+ *     the final answer is also spoken; voice/model default to MINIMAX_TTS_VOICE/MINIMAX_TTS_MODEL),
+ *   "plain_text": true (optional, screen-reader friendly output: markdown, emoji and citation
+ *     brackets are stripped from text events and done.content; done adds "citations":
+ *     [{ "index": 1, "title": "...", "url": "..." }] and the original "markdown_content"),
+ *   "max_words": 150 (optional, 10-5000, strict word budget for the answer),
+ *   "format": "bullets" | "table" | "short" | "long" | "bullets,short" (optional, one layout and
+ *     one length; "short" implies max_words 150 unless set, "long" asks for 400+ words),
+ *   "space_id": "..." (optional, applies the space's pinned credentials and terminology glossary;
+ *     see /api/spaces/:spaceId/glossary),
+ *   "agent_id" | "agentId": "..." (optional, an agent from /api/agents: its prompt, response style
+ *     and language go ahead of "messages" as a system message, and its provider, default_model,
+ *     tool_ids, temperature, top_p and penalties apply where the request leaves them unset;
+ *     404 for an unknown agent, 400 when the agent's space_ids do not include space_id)
  * }
  *
+ * max_words/format are injected as system-prompt constraints and checked on the final answer.
+ * When it misses grossly (over 1.5x the word budget, wrong layout, "long" under half length) it is
+ * truncated at a sentence or line boundary, or re-asked once as a rewrite for format problems.
+ *
+ * A last user message starting with a slash command (/research, /summarize, /translate to X)
+ * is routed to that subsystem; see GET /api/commands.
+ *
  * Response: Server-Sent Events stream
+ * - data: {"type":"stream_started","stream_id":"...","trace_id":"..."} (trace_id: see
+ *   GET /api/traces/:id; absent with TRACING=off)
+ * - data: {"type":"message_created","conversation_id":"...","message_id":"..."} (persist_message)
+ * - data: {"type":"conversation_memory","summarized_messages":12,"memory_tokens":310,
+ *   "cached":false} (older turns were replaced by the conversation memory; cached when no new
+ *   turns had to be summarized) | {"type":"conversation_memory","error":"..."} (summarizing
+ *   failed and the history was sent without a memory)
+ * - data: {"type":"command","command":"translate","args":{"language":"French"}} (slash commands only)
  * - data: {"type":"text","content":"..."}
  * - data: {"type":"thought","content":"..."}
- * - data: {"type":"done","content":"...","thought":"...","sources":[...],"toolCalls":[...]}
- * - data: {"type":"error","error":"..."}
+ * - data: {"type":"retrying","provider":"glm","attempt":1,"max_retries":4,"delay_ms":5000,
+ *   "status":429,"reason":"rate_limited|unavailable","error":"..."} (custom providers retry
+ *   transient 429/5xx failures with backoff, honouring Retry-After; PROVIDER_MAX_RETRIES)
+ * - data: {"type":"tool_call_delta","index":0,"id":"call_1","name":"web_search",
+ *   "arguments_delta":"rust","arguments":"{\"query\":\"rust","partial_arguments":{"query":"rust"},
+ *   "textIndex":0} (while the model writes tool arguments; partial_arguments is the best-effort
+ *   parse so far, and the consolidated tool_call follows before the tool runs)
+ * - data: {"type":"tool_call",...} / {"type":"tool_result",...}
+ * - data: {"type":"image","tool_call_id":"call_1","id":"...","url":"/api/images/:id/file",
+ *   "mime_type":"image/png","prompt":"...","revised_prompt":null} (one per image stored by a
+ *   generate_image call, right after its tool_result)
+ * - data: {"type":"turn_limit_reached","max_turns":10} (the model still wanted tools after the last
+ *   allowed turn; done follows with the text written so far)
+ * - data: {"type":"decomposition","sub_questions":[...]} (smart mode only)
+ * - data: {"type":"sub_question","index":0,"question":"...","status":"running|done|error"} (smart mode only)
+ * - data: {"type":"answer_revised","method":"truncate|rewrite","content":"...","violations":[...]}
+ *   (max_words/format only; replaces the streamed text)
+ * - data: {"type":"terminology_violations","violations":[{"rule":"avoid|banned","term":"...",
+ *   "expected":"...","reason":null,"count":1,"matches":[{"index":0,"text":"..."}]}]}
+ *   (spaces with a glossary, only when the final answer uses avoided or banned terms)
+ * - data: {"type":"audio","voice":"...","format":"mp3","data":"<base64>","duration_ms":0}
+ *   (with "audio", right before done; {"type":"audio","error":"..."} when synthesis failed)
+ * - data: {"type":"done","content":"...","thought":"...","sources":[...],"toolCalls":[...],
+ *   "usage":{"prompt_tokens":0,"completion_tokens":0,"total_tokens":0}}
+ *   with max_words/format also "answer_check":{"word_count":0,"violations":[{"rule":"max_words",
+ *   "detail":"...","gross":true}]}, and "original_content" when the answer was revised;
+ *   with a space glossary also "terminology_check":{"violations":[...]}
+ * - data: {"type":"cancelled","stream_id":"..."} (final event after POST /api/streams/:id/cancel)
+ * - data: {"type":"error","error":"...","code":"provider_auth|provider_rate_limit|upstream|..."}
+ *
+ * Failures carry a machine-readable "code" (see utils/errors.js): provider_auth (bad or missing
+ * key), provider_rate_limit (with retry_after_ms when known), invalid_request, upstream (provider
+ * or network failure), tool_failure (on tool_result events), internal.
  */
 router.post('/stream-chat', async (req, res) => {
+  let prepared
   try {
-    const {
-      provider,
-      apiKey,
-      baseUrl,
-      model,
-      messages,
-      tools,
-      toolChoice,
-      responseFormat,
-      thinking,
-      temperature,
-      top_k,
-      top_p,
-      frequency_penalty,
-      presence_penalty,
-      contextMessageLimit,
-      toolIds,
-      searchProvider,
-      tavilyApiKey,
-      userTools,
-    } = req.body
-
-    if (process.env.DEBUG_TOOLS === '1') {
-      console.log('[API] streamChat toolIds:', Array.isArray(toolIds) ? toolIds : [])
-    }
-
-    if (!provider) {
-      return res.status(400).json({ error: 'Missing required field: provider' })
-    }
-    if (!apiKey) {
-      return res.status(400).json({ error: 'Missing required field: apiKey' })
-    }
-    if (!messages || !Array.isArray(messages)) {
-      return res.status(400).json({ error: 'Missing required field: messages' })
-    }
-
-    const supportedProviders = [
-      'gemini',
-      'openai',
-      'openai_compatibility',
-      'siliconflow',
-      'glm',
-      'modelscope',
-      'kimi',
-      'nvidia',
-      'minimax',
-    ]
-    if (!supportedProviders.includes(provider)) {
-      return res.status(400).json({
-        error: `Unsupported provider: ${provider}. Supported: ${supportedProviders.join(', ')}`,
-      })
-    }
+    prepared = await prepareStreamChat(req.body)
+  } catch (error) {
+    console.error('[API] streamChat error:', error)
+    return sendError(res, error, 'Failed to stream chat')
+  }
+  if (prepared.rejection) {
+    const { status, ...body } = prepared.rejection
+    return res.status(status).json(body)
+  }
 
-    const sse = createSseStream(res, getSseConfig())
-    // Send an initial comment to ensure the connection is established
-    sse.writeComment('ok')
+  const sse = createSseStream(res, { ...getSseConfig(), eventFilter: req.body.event_filter })
+  // Send an initial comment to ensure the connection is established
+  sse.writeComment('ok')
 
-    // Create abort controller for client disconnect
-    const controller = new AbortController()
-    req.on('aborted', () => {
+  // Create abort controller for client disconnect
+  const controller = new AbortController()
+  req.on('aborted', () => {
+    controller.abort()
+  })
+  res.on('close', () => {
+    if (!res.writableEnded && !res.writableFinished) {
       controller.abort()
-    })
-    res.on('close', () => {
-      if (!res.writableEnded && !res.writableFinished) {
-        controller.abort()
-      }
-    })
-
-    // Stream response
-    let chunkCount = 0
-    for await (const chunk of streamChat({
-      provider,
-      apiKey,
-      baseUrl,
-      model,
-      messages,
-      tools,
-      toolChoice,
-      responseFormat,
-      thinking,
-      temperature,
-      top_k,
-      top_p,
-      frequency_penalty,
-      presence_penalty,
-      contextMessageLimit,
-      toolIds,
-      searchProvider,
-      tavilyApiKey,
-      userTools,
-      signal: controller.signal,
-    })) {
-      chunkCount++
-      // No per-chunk logging.
-      sse.sendEvent(chunk)
     }
+  })
+  await runStreamChat(prepared.request, sse, { controller })
+})
 
-    sse.close()
-  } catch (error) {
-    console.error('[API] streamChat error:', error)
-    if (!res.headersSent) {
-      res.status(500).json({
-        error: 'Failed to stream chat',
-        message: error.message,
-      })
-    } else {
-      res.write(`data: ${JSON.stringify({ type: 'error', error: error.message })}\n\n`)
-      res.end()
-    }
-  }
+/**
+ * GET /api/commands
+ * List server-side slash commands
+ */
+router.get('/commands', (req, res) => {
+  res.json({ commands: listSlashCommands() })
 })
 
 export default router
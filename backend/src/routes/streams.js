@@ -0,0 +1,30 @@
+/**
+ * Stream control routes
+ * List and cancel in-flight chat and deep research streams
+ */
+
+import express from 'express'
+import { streamRegistry } from '../services/streamRegistry.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/streams?session_id=...
+ * List running streams (only one session's with session_id)
+ */
+router.get('/streams', (req, res) => {
+  res.json({ streams: streamRegistry.list({ sessionId: req.query.session_id }) })
+})
+
+/**
+ * POST /api/streams/:id/cancel
+ * Abort a running stream; its SSE response ends with a {"type":"cancelled"} event
+ */
+router.post('/streams/:id/cancel', (req, res) => {
+  if (!streamRegistry.cancel(req.params.id)) {
+    return res.status(404).json({ error: `Stream not found: ${req.params.id}` })
+  }
+  res.json({ success: true, stream_id: req.params.id })
+})
+
+export default router
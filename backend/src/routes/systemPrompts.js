@@ -0,0 +1,90 @@
+/**
+ * System prompt routes
+ * View and override the built-in prompts (research plan, deep research steps and report, titles).
+ * These share the /api/prompts path with saved prompts: system prompts have fixed snake_case
+ * names, saved prompts have UUIDs, so any other :name falls through to the saved prompt routes.
+ */
+
+import express from 'express'
+import {
+  getSystemPrompt,
+  isSystemPromptName,
+  listSystemPrompts,
+  resetSystemPrompt,
+  saveSystemPrompt,
+} from '../prompts/promptStore.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/prompts/system
+ * List the overridable prompts with their variables and whether they are customized
+ */
+router.get('/prompts/system', (req, res) => {
+  try {
+    res.json({ prompts: listSystemPrompts() })
+  } catch (error) {
+    console.error('[API] listSystemPrompts error:', error)
+    sendError(res, error, 'Failed to list system prompts')
+  }
+})
+
+/**
+ * GET /api/prompts/:name
+ * Response: { prompt: { name, description, variables, customized, updated_at, template,
+ *   default_template } }
+ */
+router.get('/prompts/:name', (req, res, next) => {
+  if (!isSystemPromptName(req.params.name)) return next()
+  try {
+    res.json({ prompt: getSystemPrompt(req.params.name) })
+  } catch (error) {
+    console.error('[API] getSystemPrompt error:', error)
+    sendError(res, error, 'Failed to read system prompt')
+  }
+})
+
+/**
+ * PUT /api/prompts/:name
+ * Save an override; it is stored in the data dir as prompts/<name>.md
+ *
+ * Request body:
+ * {
+ *   "template": "... {{variable}} ..."
+ * }
+ *
+ * Placeholders must be variables of that prompt, and required variables must appear.
+ * Response: { prompt } (400 with details listing the problems when invalid)
+ */
+router.put('/prompts/:name', (req, res, next) => {
+  if (!isSystemPromptName(req.params.name)) return next()
+  const { template } = req.body || {}
+  if (template === undefined) {
+    return res.status(400).json({ error: 'Missing required field: template' })
+  }
+  try {
+    res.json({ prompt: saveSystemPrompt(req.params.name, template) })
+  } catch (error) {
+    console.error('[API] saveSystemPrompt error:', error)
+    sendError(res, error, 'Failed to save system prompt')
+  }
+})
+
+/**
+ * DELETE /api/prompts/:name
+ * Remove the override and go back to the built-in prompt
+ * Response: { success: true, reset: boolean (false when it was not customized), prompt }
+ */
+router.delete('/prompts/:name', (req, res, next) => {
+  if (!isSystemPromptName(req.params.name)) return next()
+  try {
+    const reset = resetSystemPrompt(req.params.name)
+    res.json({ success: true, reset, prompt: getSystemPrompt(req.params.name) })
+  } catch (error) {
+    console.error('[API] resetSystemPrompt error:', error)
+    sendError(res, error, 'Failed to reset system prompt')
+  }
+})
+
+export default router
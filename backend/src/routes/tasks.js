@@ -0,0 +1,54 @@
+/**
+ * Task routes
+ * Visibility into background tasks (site ingestion, watchlist runs, upload assembly) and their
+ * cancellation (see taskQueue)
+ */
+
+import express from 'express'
+import { taskQueue } from '../services/taskQueue.js'
+
+const router = express.Router()
+
+const notFound = (res, id) => res.status(404).json({ error: `Task not found: ${id}` })
+
+/**
+ * GET /api/tasks?status=running&kind=rag.ingest-site
+ * Tasks, newest first (the latest 200 finished tasks are kept)
+ *
+ * Response:
+ * {
+ *   "tasks": [{ "id": "...", "kind": "rag.ingest-site", "title": "...",
+ *               "status": "queued" | "running" | "done" | "error" | "cancelled" | "interrupted",
+ *               "progress": { "current": 40, "total": 120, "message": "...", "updated_at": "..." },
+ *               "result": {...} | null, "error": null, "created_at": "...", "started_at": "...",
+ *               "finished_at": null }]
+ * }
+ * "interrupted" marks tasks that were unfinished when the backend stopped.
+ */
+router.get('/tasks', (req, res) => {
+  res.json({ tasks: taskQueue.list({ status: req.query.status, kind: req.query.kind }) })
+})
+
+/**
+ * GET /api/tasks/:id
+ * Response: { "task": {...} }
+ */
+router.get('/tasks/:id', (req, res) => {
+  const task = taskQueue.get(req.params.id)
+  if (!task) return notFound(res, req.params.id)
+  res.json({ task })
+})
+
+/**
+ * POST /api/tasks/:id/cancel
+ * Cancel a queued task, or abort a running one (it reports "cancelled" once its work stopped;
+ * "cancel_requested" is set meanwhile). Finished tasks are returned unchanged.
+ * Response: { "task": {...} }
+ */
+router.post('/tasks/:id/cancel', (req, res) => {
+  const task = taskQueue.cancel(req.params.id)
+  if (!task) return notFound(res, req.params.id)
+  res.json({ task })
+})
+
+export default router
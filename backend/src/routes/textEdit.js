@@ -0,0 +1,102 @@
+/**
+ * Text edit route
+ * POST /api/edit-text
+ * Streams the model's revision of user text as tracked changes
+ */
+
+import express from 'express'
+import { streamTextEdit } from '../services/textEditService.js'
+import { sendError, toErrorEvent } from '../utils/errors.js'
+import { createSseStream, getSseConfig } from '../utils/sse.js'
+
+const router = express.Router()
+
+/**
+ * POST /api/edit-text
+ *
+ * Request body:
+ * {
+ *   "provider": "...", "apiKey": "...", "baseUrl": "..." (optional), "model": "..." (optional),
+ *   "text": "Original text",
+ *   "instruction": "Make it more formal" (optional),
+ *   "temperature": 0.2 (optional),
+ *   "event_filter": [...] (optional, see /api/stream-chat)
+ * }
+ *
+ * Response: Server-Sent Events stream
+ * - data: {"type":"diff","ops":[{"op":"equal|insert|delete","text":"...","position":12}]}
+ *   position is a character offset in the ORIGINAL text; concatenating equal+insert text
+ *   reproduces the revision, equal+delete reproduces the original
+ * - data: {"type":"done","content":"revised text","original":"...","changes":{"insert":2,"delete":1}}
+ * - data: {"type":"error","error":"..."}
+ */
+router.post('/edit-text', async (req, res) => {
+  try {
+    const { provider, apiKey, baseUrl, model, text, instruction, temperature } = req.body
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!apiKey) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (typeof text !== 'string' || !text) {
+      return res.status(400).json({ error: 'Missing required field: text' })
+    }
+
+    const supportedProviders = [
+      'gemini',
+      'openai',
+      'openai_compatibility',
+      'siliconflow',
+      'glm',
+      'modelscope',
+      'kimi',
+      'nvidia',
+      'minimax',
+    ]
+    if (!supportedProviders.includes(provider)) {
+      return res.status(400).json({
+        error: `Unsupported provider: ${provider}. Supported: ${supportedProviders.join(', ')}`,
+      })
+    }
+
+    const sse = createSseStream(res, { ...getSseConfig(), eventFilter: req.body.event_filter })
+    sse.writeComment('ok')
+
+    const controller = new AbortController()
+    req.on('aborted', () => {
+      controller.abort()
+    })
+    res.on('close', () => {
+      if (!res.writableEnded && !res.writableFinished) {
+        controller.abort()
+      }
+    })
+
+    for await (const chunk of streamTextEdit({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      text,
+      instruction,
+      temperature: temperature ?? 0.2,
+      signal: controller.signal,
+    })) {
+      sse.sendEvent(chunk)
+    }
+
+    sse.close()
+  } catch (error) {
+    console.error('[API] editText error:', error)
+    if (!res.headersSent) {
+      sendError(res, error, 'Failed to edit text')
+    } else {
+      res.write(`data: ${JSON.stringify(toErrorEvent(error))}\n\n`)
+      res.end()
+    }
+  }
+})
+
+export default router
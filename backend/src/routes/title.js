@@ -4,7 +4,14 @@
  */
 
 import express from 'express'
+import {
+  isCacheBypassed,
+  setCacheStatusHeader,
+  withResponseCache,
+} from '../services/responseCache.js'
+import { resolveTitleEmojis } from '../services/titleEmojiService.js'
 import { generateTitle } from '../services/titleService.js'
+import { sendError } from '../utils/errors.js'
 
 const router = express.Router()
 
@@ -18,23 +25,35 @@ const router = express.Router()
  *   "message": "User's first message",
  *   "apiKey": "API key for the provider",
  *   "baseUrl": "Custom base URL (optional)",
- *   "model": "model-name" (optional)
+ *   "model": "model-name" (optional),
+ *   "emojis": false (optional; overrides the titleEmojis preference of "userId")
  * }
  *
  * Response:
  * {
  *   "title": "Generated title",
- *   "emojis": ["🙂","✨"]
+ *   "emojis": ["🙂","✨"],
+ *   "cache_status": "hit" | "miss" | "bypass" | "disabled"
  * }
+ *
+ * With emoji off (per request or by preference) the prompt asks for none and "emojis" is always
+ * an empty array.
+ *
+ * Identical requests are answered from the response cache (see services/responseCache.js; also
+ * reported in the Cache-Status header). Send "Cache-Control: no-cache" to generate afresh.
  */
 router.post('/title', async (req, res) => {
   try {
-    const { provider, message, apiKey, baseUrl, model } = req.body
+    const { provider, message, apiKey, baseUrl, model, emojis, userId } = req.body
 
     if (!provider || !message) {
       return res.status(400).json({ error: 'Missing required fields: provider, message' })
     }
 
+    if (emojis !== undefined && typeof emojis !== 'boolean') {
+      return res.status(400).json({ error: 'emojis must be true or false' })
+    }
+
     const supportedProviders = [
       'gemini',
       'openai',
@@ -53,18 +72,23 @@ router.post('/title', async (req, res) => {
 
     console.log(`[API] generateTitle: provider=${provider}`)
 
-    const result = await generateTitle(provider, message, apiKey, baseUrl, model)
+    const withEmojis = resolveTitleEmojis(emojis, userId)
+    const { value: result, cacheStatus } = await withResponseCache(
+      'title',
+      { provider, baseUrl, model, apiKey, message, emojis: withEmojis },
+      () => generateTitle(provider, message, apiKey, baseUrl, model, { emojis: withEmojis }),
+      { bypass: isCacheBypassed(req), shouldCache: value => Boolean(value?.title) },
+    )
 
+    setCacheStatusHeader(res, cacheStatus)
     res.json({
       title: result?.title || 'New Conversation',
       emojis: Array.isArray(result?.emojis) ? result.emojis : [],
+      cache_status: cacheStatus,
     })
   } catch (error) {
     console.error('[API] generateTitle error:', error)
-    res.status(500).json({
-      error: 'Failed to generate title',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate title')
   }
 })
 
@@ -4,18 +4,38 @@
  */
 
 import express from 'express'
+import {
+  isCacheBypassed,
+  setCacheStatusHeader,
+  withResponseCache,
+} from '../services/responseCache.js'
 import { generateTitleAndSpace } from '../services/titleAndSpaceService.js'
+import { resolveTitleEmojis } from '../services/titleEmojiService.js'
+import { sendError } from '../utils/errors.js'
 
 const router = express.Router()
 
+/**
+ * POST /api/title-and-space
+ * Request body: as POST /api/title, plus "spaces": [{ label, ... }]
+ * Response: { "title": "...", "space": {...} | null, "emojis": [...],
+ *   "cache_status": "hit" | "miss" | "bypass" | "disabled" }
+ * "emojis": false (or the titleEmojis preference) asks for no emoji, as for POST /api/title.
+ * Identical requests (same message and spaces) are answered from the response cache (see
+ * POST /api/title).
+ */
 router.post('/title-and-space', async (req, res) => {
   try {
-    const { provider, message, spaces, apiKey, baseUrl, model } = req.body
+    const { provider, message, spaces, apiKey, baseUrl, model, emojis, userId } = req.body
 
     if (!provider || !message) {
       return res.status(400).json({ error: 'Missing required fields: provider, message' })
     }
 
+    if (emojis !== undefined && typeof emojis !== 'boolean') {
+      return res.status(400).json({ error: 'emojis must be true or false' })
+    }
+
     const supportedProviders = [
       'gemini',
       'openai',
@@ -34,22 +54,25 @@ router.post('/title-and-space', async (req, res) => {
 
     console.log(`[API] generateTitleAndSpace: provider=${provider}`)
 
-    const result = await generateTitleAndSpace(
-      provider,
-      message,
-      spaces || [],
-      apiKey,
-      baseUrl,
-      model,
+    const withEmojis = resolveTitleEmojis(emojis, userId)
+    const { value: result, cacheStatus } = await withResponseCache(
+      'title-and-space',
+      { provider, baseUrl, model, apiKey, message, spaces: spaces || [], emojis: withEmojis },
+      () =>
+        generateTitleAndSpace(provider, message, spaces || [], apiKey, baseUrl, model, {
+          emojis: withEmojis,
+        }),
+      {
+        bypass: isCacheBypassed(req),
+        shouldCache: value => Boolean(value?.title) && value.title !== 'New Conversation',
+      },
     )
 
-    res.json(result)
+    setCacheStatusHeader(res, cacheStatus)
+    res.json({ ...result, cache_status: cacheStatus })
   } catch (error) {
     console.error('[API] generateTitleAndSpace error:', error)
-    res.status(500).json({
-      error: 'Failed to generate title and space',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate title and space')
   }
 })
 
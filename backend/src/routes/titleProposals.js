@@ -0,0 +1,100 @@
+/**
+ * Title proposal routes
+ * Updated titles proposed for conversations whose topic drifted (see retitleService)
+ */
+
+import express from 'express'
+import { getConversation } from '../services/conversationStore.js'
+import {
+  acceptTitleProposal,
+  checkConversationTitle,
+  dismissTitleProposal,
+  listTitleProposals,
+  onTitleProposal,
+} from '../services/retitleService.js'
+import { sendError } from '../utils/errors.js'
+import { createSseStream, getSseConfig } from '../utils/sse.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/title-proposals
+ * Pending title proposals, newest first
+ *
+ * Response:
+ * {
+ *   "proposals": [{ "conversation_id": "...", "current_title": "...", "current_emojis": ["🐍"],
+ *                   "title": "...", "emojis": ["🚀"], "similarity": 0.31, "threshold": 0.5,
+ *                   "status": "pending", "proposed_at": "..." }]
+ * }
+ */
+router.get('/title-proposals', (req, res) => {
+  res.json({ proposals: listTitleProposals() })
+})
+
+/**
+ * GET /api/title-proposals/events
+ * Server-Sent Events stream of new proposals while the connection is open
+ * - data: {"type":"title_proposal","proposal":{...}}
+ */
+router.get('/title-proposals/events', (req, res) => {
+  const sse = createSseStream(res, getSseConfig())
+  sse.writeComment('ok')
+  const unsubscribe = onTitleProposal(proposal => {
+    sse.sendEvent({ type: 'title_proposal', proposal })
+  })
+  res.on('close', () => {
+    unsubscribe()
+    sse.close()
+  })
+})
+
+/**
+ * POST /api/conversations/:id/retitle-check
+ * Check one conversation for topic drift now (also when no message was added since the last check)
+ *
+ * Response: { "status": "proposed" | "on_topic" | "skipped", "reason": "...", "similarity": 0.31,
+ *             "proposal": {...} }
+ */
+router.post('/conversations/:id/retitle-check', async (req, res) => {
+  if (!getConversation(req.params.id)) {
+    return res.status(404).json({ error: `Conversation not found: ${req.params.id}` })
+  }
+  try {
+    res.json(await checkConversationTitle(req.params.id, { force: true }))
+  } catch (error) {
+    console.error('[API] retitle check error:', error)
+    sendError(res, error, 'Failed to check conversation title')
+  }
+})
+
+/**
+ * POST /api/title-proposals/:conversationId/accept
+ * Apply the pending proposal; body { "title", "emojis" } optionally overrides it
+ */
+router.post('/title-proposals/:conversationId/accept', (req, res) => {
+  const { title, emojis } = req.body || {}
+  const result = acceptTitleProposal(req.params.conversationId, { title, emojis })
+  if (!result) {
+    return res
+      .status(404)
+      .json({ error: `Title proposal not found: ${req.params.conversationId}` })
+  }
+  res.json(result)
+})
+
+/**
+ * POST /api/title-proposals/:conversationId/dismiss
+ * Keep the current title; the conversation is proposed again only after it drifts further
+ */
+router.post('/title-proposals/:conversationId/dismiss', (req, res) => {
+  const proposal = dismissTitleProposal(req.params.conversationId)
+  if (!proposal) {
+    return res
+      .status(404)
+      .json({ error: `Title proposal not found: ${req.params.conversationId}` })
+  }
+  res.json({ proposal })
+})
+
+export default router
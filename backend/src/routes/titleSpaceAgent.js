@@ -4,7 +4,9 @@
  */
 
 import express from 'express'
+import { resolveTitleEmojis } from '../services/titleEmojiService.js'
 import { generateTitleSpaceAndAgent } from '../services/titleSpaceAgentService.js'
+import { sendError } from '../utils/errors.js'
 
 const router = express.Router()
 
@@ -19,7 +21,8 @@ const router = express.Router()
  *   "spacesWithAgents": [{ label, description, agents: [{name, description?}] }],
  *   "apiKey": "API key for the provider",
  *   "baseUrl": "Custom base URL (optional)",
- *   "model": "model-name" (optional)
+ *   "model": "model-name" (optional),
+ *   "emojis": false (optional; overrides the titleEmojis preference of "userId")
  * }
  *
  * Response:
@@ -27,17 +30,22 @@ const router = express.Router()
  *   "title": "Generated title",
  *   "spaceLabel": "Selected space label" | null,
  *   "agentName": "Selected agent name" | null,
- *   "emojis": ["🙂","✨"]
+ *   "emojis": ["🙂","✨"] ([] when emoji are off)
  * }
  */
 router.post('/title-space-agent', async (req, res) => {
   try {
-    const { provider, message, spacesWithAgents, apiKey, baseUrl, model } = req.body
+    const { provider, message, spacesWithAgents, apiKey, baseUrl, model, emojis, userId } =
+      req.body
 
     if (!provider || !message) {
       return res.status(400).json({ error: 'Missing required fields: provider, message' })
     }
 
+    if (emojis !== undefined && typeof emojis !== 'boolean') {
+      return res.status(400).json({ error: 'emojis must be true or false' })
+    }
+
     const supportedProviders = [
       'gemini',
       'openai',
@@ -63,15 +71,13 @@ router.post('/title-space-agent', async (req, res) => {
       apiKey,
       baseUrl,
       model,
+      { emojis: resolveTitleEmojis(emojis, userId) },
     )
 
     res.json(result)
   } catch (error) {
     console.error('[API] generateTitleSpaceAndAgent error:', error)
-    res.status(500).json({
-      error: 'Failed to generate title, space, and agent',
-      message: error.message,
-    })
+    sendError(res, error, 'Failed to generate title, space, and agent')
   }
 })
 
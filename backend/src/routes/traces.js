@@ -0,0 +1,53 @@
+/**
+ * Trace routes
+ * Span trees of traced chat and research runs, for bug reports
+ */
+
+import express from 'express'
+import { getTrace } from '../services/tracing.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/traces/:id
+ * A request's trace; the id comes from the X-Trace-Id response header or the trace_id of a
+ * stream_started event. Traces of requests still running are returned with status "running".
+ *
+ * Response:
+ * {
+ *   "trace": {
+ *     "id": "...", "name": "POST /api/stream-chat", "kind": "chat" | "research" | "request",
+ *     "status": "running" | "ok" | "error", "started_at": "ISO", "duration_ms": 1234.5,
+ *     "root": {
+ *       "id": "...", "parent_id": null, "name": "POST /api/stream-chat", "started_at": "ISO",
+ *       "duration_ms": 1234.5, "status": "ok" | "error" | "running", "error": "..." (on errors),
+ *       "attributes": { "provider": "openai", "model": "...", "request": {...},
+ *         "status_code": 200 },
+ *       "events": [{ "name": "retrying", "time": "ISO", "data": {...} }],
+ *       "children": [
+ *         { "name": "llm", "attributes": { "provider", "model", "params", "messages": [...],
+ *           "output": "...", "tool_calls": [...], "usage": {...} }, "children": [] },
+ *         { "name": "tool", "attributes": { "tool": "calculator", "arguments": {...},
+ *           "result": {...} }, "children": [] },
+ *         { "name": "research_step", "attributes": { "step": 1, "total": 3 }, "children": [...] }
+ *       ]
+ *     }
+ *   }
+ * }
+ * API keys and other credentials are redacted; long prompts and outputs are truncated.
+ */
+router.get('/traces/:id', (req, res) => {
+  try {
+    const trace = getTrace(req.params.id)
+    if (!trace) {
+      return res.status(404).json({ error: `Trace not found: ${req.params.id}` })
+    }
+    res.json({ trace })
+  } catch (error) {
+    console.error('[API] traces error:', error)
+    sendError(res, error, 'Failed to load trace')
+  }
+})
+
+export default router
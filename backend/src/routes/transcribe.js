@@ -0,0 +1,103 @@
+/**
+ * Transcription route
+ * POST /api/transcribe - speech to text for dictated prompts
+ */
+
+import express from 'express'
+import {
+  getMaxAudioBytes,
+  transcribeAudio,
+  TRANSCRIPTION_PROVIDERS,
+} from '../services/audio/index.js'
+import { resolveSpaceCredentials } from '../services/keyVault.js'
+import { readUploadFile } from '../services/uploadService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+// Audio bodies of any type except JSON, which the app-wide parser already handled
+const parseAudio = express.raw({
+  type: req => !req.is('application/json'),
+  limit: getMaxAudioBytes(),
+})
+
+/**
+ * POST /api/transcribe?provider=openai&model=whisper-1&language=en&filename=note.m4a
+ * Transcribe a wav, mp3 or m4a recording. The raw audio is the request body (up to
+ * TRANSCRIBE_MAX_MB, default 25) and the API key is read from the X-Api-Key header, or from the
+ * credentials pinned to ?spaceId=. Query: provider, model (optional), language (optional
+ * ISO-639-1 hint), prompt (optional vocabulary hint), baseUrl (optional), filename (optional).
+ *
+ * A JSON body transcribes a completed chunked upload instead (see POST /api/uploads):
+ * {
+ *   "provider": "openai" | "openai_compatibility" | "siliconflow",
+ *   "apiKey": "...",
+ *   "upload_id": "...",
+ *   "baseUrl", "model", "language", "prompt" (optional)
+ * }
+ *
+ * Default models: whisper-1 (openai), FunAudioLLM/SenseVoiceSmall (siliconflow). Segments carry
+ * timestamps in seconds; they are empty for models that return none (gpt-4o-transcribe,
+ * SiliconFlow ASR).
+ *
+ * Response:
+ * {
+ *   "provider": "openai", "model": "whisper-1", "text": "Remind me to ...",
+ *   "language": "english", "duration": 4.2,
+ *   "segments": [{ "id": 0, "start": 0, "end": 4.2, "text": "Remind me to ..." }]
+ * }
+ */
+router.post('/transcribe', parseAudio, async (req, res) => {
+  try {
+    const isFile = Buffer.isBuffer(req.body)
+    const params = isFile ? req.query : req.body || {}
+    const pinned = isFile ? resolveSpaceCredentials(req.query.spaceId) : null
+    const provider = params.provider || pinned?.provider
+    // Pinned credentials only apply to the provider they were pinned for
+    const spaceCredentials = pinned?.provider === provider ? pinned : null
+    const apiKey = isFile ? req.get('x-api-key') || spaceCredentials?.apiKey : params.apiKey
+    const baseUrl = params.baseUrl || spaceCredentials?.baseUrl
+    const uploadId = isFile ? null : params.upload_id || params.uploadId
+
+    if (!provider) {
+      return res.status(400).json({ error: 'Missing required field: provider' })
+    }
+    if (!TRANSCRIPTION_PROVIDERS.includes(provider)) {
+      const supported = TRANSCRIPTION_PROVIDERS.join(', ')
+      return res
+        .status(400)
+        .json({ error: `Unsupported provider: ${provider}. Supported: ${supported}` })
+    }
+    if (!apiKey) {
+      return res.status(400).json({ error: 'Missing required field: apiKey' })
+    }
+    if (isFile ? !req.body.length : !uploadId) {
+      return res.status(400).json({ error: 'Missing required field: audio body or upload_id' })
+    }
+
+    const audio = isFile
+      ? { buffer: req.body, filename: req.query.filename, mimeType: req.get('content-type') }
+      : readUploadFile(uploadId)
+    const controller = new AbortController()
+    res.on('close', () => {
+      if (!res.writableEnded) controller.abort()
+    })
+    res.json(
+      await transcribeAudio({
+        provider,
+        audio,
+        apiKey,
+        baseUrl,
+        model: params.model,
+        language: params.language,
+        prompt: params.prompt,
+        signal: controller.signal,
+      }),
+    )
+  } catch (error) {
+    console.error('[API] transcribe error:', error)
+    sendError(res, error, 'Failed to transcribe audio')
+  }
+})
+
+export default router
@@ -0,0 +1,134 @@
+/**
+ * Upload routes
+ * Chunked, resumable uploads for large attachments and knowledge-base files (see uploadService)
+ */
+
+import express from 'express'
+import {
+  completeUpload,
+  deleteUpload,
+  getUpload,
+  getUploadConfig,
+  initUpload,
+  listUploads,
+  writeUploadPart,
+} from '../services/uploadService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+// Part bodies are raw bytes; JSON bodies were already consumed by the app-wide parser
+const parsePart = express.raw({
+  type: req => !req.is('application/json'),
+  limit: getUploadConfig().maxPartBytes,
+})
+
+const notFound = (res, id) => res.status(404).json({ error: `Upload not found: ${id}` })
+
+/**
+ * GET /api/uploads
+ * Response: { "uploads": [{ "id", "filename", "mime_type", "size", "status", ... }] }
+ */
+router.get('/uploads', (req, res) => {
+  res.json({ uploads: listUploads() })
+})
+
+/**
+ * POST /api/uploads
+ * Start a chunked upload
+ *
+ * Body:
+ * {
+ *   "filename": "handbook.pdf",
+ *   "size": 48213004,
+ *   "sha256": "hex SHA-256 of the whole file",
+ *   "mime_type" | "mimeType": "application/pdf" (optional),
+ *   "part_size" | "partSize": 5242880 (optional, 64 KB to 16 MB; default 5 MB)
+ * }
+ * The file may be at most UPLOAD_MAX_MB (default 200); unfinished uploads expire after
+ * UPLOAD_TTL_HOURS (default 24).
+ *
+ * Response: 201 { "upload": { "id": "...", "filename": "...", "mime_type": "...", "size": 0,
+ *   "sha256": "...", "part_size": 5242880, "total_parts": 10, "received_parts": [],
+ *   "missing_parts": [0, 1, ...], "status": "pending", "error": null, "created_at": "...",
+ *   "completed_at": null, "expires_at": "..." } }
+ */
+router.post('/uploads', (req, res) => {
+  try {
+    const body = req.body || {}
+    const upload = initUpload({
+      filename: body.filename,
+      size: body.size,
+      sha256: body.sha256,
+      mimeType: body.mimeType ?? body.mime_type,
+      partSize: body.partSize ?? body.part_size,
+    })
+    res.status(201).json({ upload })
+  } catch (error) {
+    console.error('[API] upload init error:', error)
+    sendError(res, error, 'Failed to start upload')
+  }
+})
+
+/**
+ * GET /api/uploads/:id
+ * Upload state; a client resuming an interrupted upload re-sends "missing_parts"
+ */
+router.get('/uploads/:id', (req, res) => {
+  const upload = getUpload(req.params.id)
+  if (!upload) return notFound(res, req.params.id)
+  res.json({ upload })
+})
+
+/**
+ * PUT /api/uploads/:id/parts/:index
+ * Send part <index> (zero-based) as the raw request body (Content-Type: application/octet-stream).
+ * Every part except the last must be exactly part_size bytes. An optional X-Part-SHA256 header is
+ * verified against the received bytes. Re-sending a part replaces it.
+ *
+ * Response: { "part": 3, "bytes": 5242880, "sha256": "...", "upload": {...} }
+ */
+router.put('/uploads/:id/parts/:index', parsePart, async (req, res) => {
+  try {
+    if (!Buffer.isBuffer(req.body)) {
+      return res.status(400).json({ error: 'Missing required field: part body' })
+    }
+    const result = await writeUploadPart(req.params.id, req.params.index, req.body, {
+      sha256: req.get('x-part-sha256'),
+    })
+    res.json(result)
+  } catch (error) {
+    console.error('[API] upload part error:', error)
+    sendError(res, error, 'Failed to store upload part')
+  }
+})
+
+/**
+ * POST /api/uploads/:id/complete
+ * Assemble the parts in a background task and verify the file checksum. Responds 202 with status
+ * "assembling" and the task (poll GET /api/uploads/:id or GET /api/tasks/:id); with
+ * { "wait": true } the response is sent once assembly finished. A checksum mismatch sets the
+ * upload back to "pending" with an "error".
+ *
+ * Response: 202 { "upload": {...}, "task": {...} } | 200 { "upload": {...} } (wait)
+ */
+router.post('/uploads/:id/complete', async (req, res) => {
+  try {
+    const { upload, task, done } = completeUpload(req.params.id)
+    if (!req.body?.wait) return res.status(202).json({ upload, task })
+    res.json({ upload: await done })
+  } catch (error) {
+    console.error('[API] upload complete error:', error)
+    sendError(res, error, 'Failed to complete upload')
+  }
+})
+
+/**
+ * DELETE /api/uploads/:id
+ */
+router.delete('/uploads/:id', (req, res) => {
+  if (!deleteUpload(req.params.id)) return notFound(res, req.params.id)
+  res.json({ success: true })
+})
+
+export default router
@@ -0,0 +1,35 @@
+/**
+ * Usage routes
+ * Token usage ledger aggregated per provider/model and conversation
+ */
+
+import express from 'express'
+import { summarizeUsage } from '../services/usageLedger.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/usage?conversationId=...&from=ISO&to=ISO
+ * Tokens and estimated cost (USD, from approximate list prices; see MODEL_PRICING) recorded from
+ * stream-chat, research-plan-stream, and stream-deep-research done events
+ *
+ * Response:
+ * {
+ *   "totals": { "requests": 12, "prompt_tokens": 0, "completion_tokens": 0, "total_tokens": 0,
+ *               "estimated_cost_usd": 0.0132, "unpriced_requests": 1 },
+ *   "by_model": [{ "provider": "openai", "model": "gpt-4o-mini", ...totals }],
+ *   "by_conversation": [{ "conversation_id": "..." | null, ...totals }]
+ * }
+ */
+router.get('/usage', (req, res) => {
+  try {
+    const { conversationId, from, to } = req.query
+    res.json(summarizeUsage({ conversationId, from, to }))
+  } catch (error) {
+    console.error('[API] usage error:', error)
+    sendError(res, error, 'Failed to load usage')
+  }
+})
+
+export default router
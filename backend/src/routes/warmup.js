@@ -0,0 +1,47 @@
+/**
+ * Warmup routes
+ * GET /api/warmup, POST /api/warmup
+ */
+
+import express from 'express'
+import { getLastWarmupReport, runWarmup } from '../services/warmupService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+/**
+ * GET /api/warmup
+ * Return the most recent warmup report (null if warmup has not run)
+ */
+router.get('/warmup', (req, res) => {
+  res.json({ report: getLastWarmupReport() })
+})
+
+/**
+ * POST /api/warmup
+ * Re-run warmup, e.g. when the app starts with credentials the backend does not hold
+ *
+ * Request body:
+ * {
+ *   "providers": ["openai", "glm"] (optional),
+ *   "provider": "default provider to ping" (optional),
+ *   "apiKey": "API key for the ping" (optional, ping is skipped without it),
+ *   "baseUrl": "Custom base URL (optional)",
+ *   "model": "model-name" (optional)
+ * }
+ */
+router.post('/warmup', async (req, res) => {
+  try {
+    const { providers, provider, apiKey, baseUrl, model } = req.body || {}
+    const report = await runWarmup({
+      providers: Array.isArray(providers) ? providers : provider ? [provider] : undefined,
+      ping: provider && apiKey ? { provider, apiKey, baseUrl, model } : undefined,
+    })
+    res.json({ report })
+  } catch (error) {
+    console.error('[API] warmup error:', error)
+    sendError(res, error, 'Failed to run warmup')
+  }
+})
+
+export default router
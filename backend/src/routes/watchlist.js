@@ -0,0 +1,193 @@
+/**
+ * Watchlist routes
+ * Saved research questions that are re-run on demand or on a schedule, with a report history and
+ * diffs between runs (see watchlistService)
+ */
+
+import express from 'express'
+import {
+  createWatchItem,
+  deleteWatchItem,
+  diffReports,
+  enqueueWatchItemRun,
+  getWatchItem,
+  getWatchReport,
+  listWatchItems,
+  listWatchReports,
+  runWatchItem,
+  updateWatchItem,
+} from '../services/watchlistService.js'
+import { sendError } from '../utils/errors.js'
+
+const router = express.Router()
+
+const notFound = (res, id) => res.status(404).json({ error: `Watch item not found: ${id}` })
+
+// Accept both camelCase and snake_case field names
+const readItemFields = body => ({
+  question: body.question,
+  provider: body.provider,
+  apiKey: body.apiKey ?? body.api_key,
+  baseUrl: body.baseUrl ?? body.base_url,
+  model: body.model,
+  spaceId: body.spaceId ?? body.space_id,
+  tavilyApiKey: body.tavilyApiKey ?? body.tavily_api_key,
+  intervalHours: body.intervalHours ?? body.interval_hours,
+  settings: body.settings,
+})
+
+/**
+ * GET /api/watchlist
+ * Saved watch items, most recently updated first
+ *
+ * Response:
+ * {
+ *   "items": [{ "id": "...", "question": "...", "provider": "gemini", "model": "...",
+ *               "space_id": null, "api_key_masked": "AIz****abcd", "has_tavily_key": false,
+ *               "settings": { "researchType": "general", "reportStyle": "executive" },
+ *               "interval_hours": 24, "next_run_at": "...", "last_run_at": "...",
+ *               "last_status": "done" | "error" | "aborted" | null, "last_error": null,
+ *               "report_count": 3, "running": false, "created_at": "...", "updated_at": "..." }]
+ * }
+ */
+router.get('/watchlist', (req, res) => {
+  res.json({ items: listWatchItems() })
+})
+
+/**
+ * POST /api/watchlist
+ * Save a research question
+ *
+ * Body:
+ * - question: the research question (required)
+ * - provider, apiKey, baseUrl, model: credentials used for each run (the key is stored encrypted)
+ * - spaceId | space_id: use the space's pinned credentials instead (see spaceCredentials routes)
+ * - tavilyApiKey | tavily_api_key: optional, stored encrypted
+ * - intervalHours | interval_hours: re-run every N hours (>= 1); omit or null for on demand only
+ * - settings: deep research options applied to every run (researchType, reportStyle, decompose,
+ *   glossary, timeline, numericCheck, sourceBias, noveltyThreshold, concurrentExecution,
+ *   maxParallelSteps, entities, criteria, toolIds, searchProvider, temperature, ...; see
+ *   POST /api/stream-deep-research)
+ *
+ * Response: 201 { "item": {...} }
+ */
+router.post('/watchlist', (req, res) => {
+  try {
+    res.status(201).json({ item: createWatchItem(readItemFields(req.body || {})) })
+  } catch (error) {
+    console.error('[API] watchlist create error:', error)
+    sendError(res, error, 'Failed to save watch item')
+  }
+})
+
+/**
+ * GET /api/watchlist/:id
+ * One watch item with its report history (newest first, without report bodies)
+ *
+ * Response:
+ * {
+ *   "item": {...},
+ *   "reports": [{ "id": "...", "run_id": "...", "created_at": "...", "source_count": 12,
+ *                 "diff": { "compared_to": "...", "paragraphs_added": 2, "paragraphs_removed": 1,
+ *                           "paragraphs_unchanged": 14, "sources_added": 3,
+ *                           "sources_removed": 0, "changed": true } | null }]
+ * }
+ */
+router.get('/watchlist/:id', (req, res) => {
+  const item = getWatchItem(req.params.id)
+  if (!item) return notFound(res, req.params.id)
+  res.json({ item, reports: listWatchReports(req.params.id) })
+})
+
+/**
+ * PATCH /api/watchlist/:id
+ * Update any of the POST fields; "apiKey": null clears the stored key, "interval_hours": null
+ * turns scheduling off, and "settings" replaces the stored settings
+ *
+ * Response: { "item": {...} }
+ */
+router.patch('/watchlist/:id', (req, res) => {
+  try {
+    const item = updateWatchItem(req.params.id, readItemFields(req.body || {}))
+    if (!item) return notFound(res, req.params.id)
+    res.json({ item })
+  } catch (error) {
+    console.error('[API] watchlist update error:', error)
+    sendError(res, error, 'Failed to update watch item')
+  }
+})
+
+/**
+ * DELETE /api/watchlist/:id
+ * Remove a watch item and its report history
+ */
+router.delete('/watchlist/:id', (req, res) => {
+  if (!deleteWatchItem(req.params.id)) return notFound(res, req.params.id)
+  res.json({ success: true })
+})
+
+/**
+ * POST /api/watchlist/:id/run
+ * Re-run the research now. By default the run is queued as a background task and the response is
+ * 202 { "status": "started", "task": {...} } (poll GET /api/tasks/:id or GET /api/watchlist/:id;
+ * cancel with POST /api/tasks/:id/cancel); with { "wait": true } the response is sent when the
+ * run finishes: { "report": { "id", "content", "sources", "diff", ... } }
+ */
+router.post('/watchlist/:id/run', async (req, res) => {
+  const item = getWatchItem(req.params.id)
+  if (!item) return notFound(res, req.params.id)
+  if (item.running) {
+    return res.status(409).json({ error: `Watch item is already running: ${req.params.id}` })
+  }
+
+  if (!req.body?.wait) {
+    return res.status(202).json({ status: 'started', task: enqueueWatchItemRun(req.params.id) })
+  }
+
+  const controller = new AbortController()
+  res.on('close', () => {
+    if (!res.writableEnded) controller.abort()
+  })
+  try {
+    res.json({ report: await runWatchItem(req.params.id, { signal: controller.signal }) })
+  } catch (error) {
+    console.error('[API] watchlist run error:', error)
+    sendError(res, error, 'Failed to run watch item')
+  }
+})
+
+/**
+ * GET /api/watchlist/:id/reports/:reportId
+ * Full report: { "report": { "id", "run_id", "created_at", "content", "sources": [{ title, url }],
+ *                            "diff": { "summary", "added", "removed", "sources_added",
+ *                                      "sources_removed" } | null } }
+ */
+router.get('/watchlist/:id/reports/:reportId', (req, res) => {
+  if (!getWatchItem(req.params.id)) return notFound(res, req.params.id)
+  const report = getWatchReport(req.params.id, req.params.reportId)
+  if (!report) return res.status(404).json({ error: `Report not found: ${req.params.reportId}` })
+  res.json({ report })
+})
+
+/**
+ * GET /api/watchlist/:id/diff?from=<reportId>&to=<reportId>
+ * Diff between any two reports; "to" defaults to the latest report and "from" to the one before it
+ *
+ * Response: { "from": "...", "to": "...", "summary": {...}, "added": ["paragraph", ...],
+ *             "removed": [...], "sources_added": ["url", ...], "sources_removed": [...] }
+ */
+router.get('/watchlist/:id/diff', (req, res) => {
+  if (!getWatchItem(req.params.id)) return notFound(res, req.params.id)
+  const history = listWatchReports(req.params.id)
+  const toId = req.query.to || history[0]?.id
+  const toIndex = history.findIndex(report => report.id === toId)
+  const fromId = req.query.from || history[toIndex + 1]?.id
+  const to = toId && getWatchReport(req.params.id, toId)
+  const from = fromId && getWatchReport(req.params.id, fromId)
+  if (!to || !from) {
+    return res.status(404).json({ error: 'Two reports are needed to compute a diff' })
+  }
+  res.json({ from: from.id, to: to.id, ...diffReports(from, to) })
+})
+
+export default router
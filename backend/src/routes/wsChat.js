@@ -0,0 +1,174 @@
+/**
+ * WebSocket chat route
+ * GET /api/ws/chat
+ * Same stream as POST /api/stream-chat for clients behind proxies or webviews that buffer SSE.
+ * Express does not route upgrade requests, so server.js (and the test app) attach this to the
+ * HTTP server with attachChatWebSocket().
+ */
+
+import { WebSocketServer } from 'ws'
+import { applySpaceCredentials } from '../middleware/spaceCredentials.js'
+import { estimateRequestTokens, runWithRateLimiter } from '../services/providers/rateLimiter.js'
+import { prepareStreamChat, runStreamChat } from '../services/streamChatRunner.js'
+import { finishTrace, runInTrace, startTrace } from '../services/tracing.js'
+import { ErrorCode, toErrorEvent } from '../utils/errors.js'
+import {
+  CLOSE_INVALID_PAYLOAD,
+  CLOSE_POLICY_VIOLATION,
+  createWebSocketSink,
+} from '../utils/webSocket.js'
+
+export const WS_CHAT_PATH = '/api/ws/chat'
+const FIRST_MESSAGE_TIMEOUT_MS = 30000
+// Large attachments belong in POST /api/uploads, as with the JSON routes
+const MAX_PAYLOAD_BYTES = 1024 * 1024
+
+// Answer an upgrade we will not accept with a plain HTTP response
+const refuseUpgrade = (socket, status, reason) => {
+  socket.end(`HTTP/1.1 ${status} ${reason}\r\nConnection: close\r\nContent-Length: 0\r\n\r\n`)
+}
+
+// One error frame, then close
+const failSocket = (socket, event, code) => {
+  socket.send(JSON.stringify(event))
+  socket.close(code)
+}
+
+const readRequestBody = data => {
+  try {
+    const body = JSON.parse(data.toString())
+    return body && typeof body === 'object' && !Array.isArray(body) ? body : null
+  } catch {
+    return null
+  }
+}
+
+const streamOverSocket = async (socket, req, body, { rateLimiter, controller }) => {
+  const apiKey = body.apiKey || req.headers['x-api-key']
+  try {
+    await rateLimiter.acquire({
+      provider: body.provider,
+      apiKey,
+      tokens: estimateRequestTokens(body),
+      signal: controller.signal,
+    })
+  } catch (error) {
+    if (controller.signal.aborted) return
+    return failSocket(socket, toErrorEvent(error), CLOSE_POLICY_VIOLATION)
+  }
+  // Further model calls of the stream take their own shares, as for POST /api/stream-chat
+  await runWithRateLimiter({ rateLimiter, provider: body.provider, apiKey }, () =>
+    streamPrepared(socket, body, { controller }),
+  )
+}
+
+const streamPrepared = async (socket, body, { controller }) => {
+  let prepared
+  try {
+    prepared = await prepareStreamChat(body)
+  } catch (error) {
+    if (controller.signal.aborted) return
+    console.error('[API] wsChat error:', error)
+    return failSocket(socket, toErrorEvent(error), CLOSE_POLICY_VIOLATION)
+  }
+  if (prepared.rejection) {
+    const { status, error, details } = prepared.rejection
+    return failSocket(
+      socket,
+      { type: 'error', error, code: ErrorCode.InvalidRequest, status, details },
+      CLOSE_POLICY_VIOLATION,
+    )
+  }
+
+  const sink = createWebSocketSink(socket, { eventFilter: body.event_filter })
+  await runStreamChat(prepared.request, sink, { controller })
+}
+
+const handleChatSocket = (socket, req, { rateLimiter }) => {
+  // Closing the socket cancels the stream, like a dropped SSE connection
+  const controller = new AbortController()
+  const timeout = setTimeout(() => {
+    failSocket(
+      socket,
+      { type: 'error', error: 'No StreamChatRequest received', code: ErrorCode.InvalidRequest },
+      CLOSE_POLICY_VIOLATION,
+    )
+  }, FIRST_MESSAGE_TIMEOUT_MS)
+  socket.once('close', () => {
+    clearTimeout(timeout)
+    controller.abort()
+  })
+
+  socket.once('message', async (data, isBinary) => {
+    clearTimeout(timeout)
+    const body = isBinary ? null : readRequestBody(data)
+    if (!body) {
+      return failSocket(
+        socket,
+        {
+          type: 'error',
+          error: 'The first message must be a StreamChatRequest JSON object',
+          code: ErrorCode.InvalidRequest,
+        },
+        CLOSE_INVALID_PAYLOAD,
+      )
+    }
+
+    // The middleware POST /api/stream-chat runs through, applied to the first message
+    applySpaceCredentials({ body }, null, () => {})
+    const started = startTrace({
+      id: req.headers['x-trace-id'],
+      name: `WS ${WS_CHAT_PATH}`,
+      kind: 'chat',
+      attributes: { provider: body.provider, model: body.model, request: body },
+    })
+    try {
+      await runInTrace(started?.context, () =>
+        streamOverSocket(socket, req, body, { rateLimiter, controller }),
+      )
+    } finally {
+      finishTrace(started?.trace)
+    }
+  })
+}
+
+/**
+ * GET /api/ws/chat (WebSocket)
+ * Stream a chat completion over a WebSocket
+ *
+ * The client sends one text frame, the same JSON body as POST /api/stream-chat (space
+ * credentials, rate limits, validation and event_filter all apply). The server answers with one
+ * JSON text frame per event, exactly the objects the SSE route sends as data lines
+ * (stream_started, text, thought, tool_call, ..., done | cancelled | error), then closes the
+ * socket with 1000. Closing the socket from the client cancels the stream; POST
+ * /api/streams/:id/cancel works too. The server pings every SSE_HEARTBEAT_MS. The stream is
+ * traced like the SSE route: an X-Trace-Id upgrade header sets the id, and stream_started
+ * carries it (see GET /api/traces/:id).
+ *
+ * Refusals are a single error frame followed by a close:
+ * - 1007: the first frame is not a JSON object
+ * - 1008: the request is invalid ({"type":"error","error":"...","code":"invalid_request",
+ *   "status":400|409,"details":[...]}), rate limited (code "provider_rate_limit" with
+ *   retry_after_ms), or no request arrived within 30s
+ * Upgrades from an origin outside FRONTEND_URLS get HTTP 403.
+ *
+ * @param {import('http').Server} server
+ * @param {import('express').Express} app - From createApp(); supplies allowed origins and the
+ *   rate limiter
+ * @returns {WebSocketServer}
+ */
+export const attachChatWebSocket = (server, app) => {
+  const { allowedOrigins, rateLimiter } = app.locals
+  const wss = new WebSocketServer({ noServer: true, maxPayload: MAX_PAYLOAD_BYTES })
+  wss.on('connection', (socket, req) => handleChatSocket(socket, req, { rateLimiter }))
+
+  server.on('upgrade', (req, socket, head) => {
+    const { pathname } = new URL(req.url, 'http://localhost')
+    if (pathname !== WS_CHAT_PATH) return refuseUpgrade(socket, 404, 'Not Found')
+    // Browsers do not apply CORS to WebSockets, so check the origin here
+    const origin = req.headers.origin
+    if (origin && !allowedOrigins.has(origin)) return refuseUpgrade(socket, 403, 'Forbidden')
+    wss.handleUpgrade(req, socket, head, ws => wss.emit('connection', ws, req))
+  })
+  return wss
+}
@@ -3,11 +3,15 @@
  * Express.js server for AI-powered backend API
  */
 
-import express from 'express'
-import cors from 'cors'
 import dotenv from 'dotenv'
 import fs from 'fs'
 import path from 'path'
+import { createApp } from './app.js'
+import { attachChatWebSocket } from './routes/wsChat.js'
+import { getWarmupConfig, runWarmup } from './services/warmupService.js'
+import { backgroundJobManager, getBackgroundConfig } from './services/backgroundService.js'
+import { recoverInterruptedMessages } from './services/messageJournal.js'
+import { detectRuntimes } from './services/runtimeCapabilities.js'
 
 // Load environment variables (.env then .env.local override if present)
 dotenv.config()
@@ -16,72 +20,41 @@ if (fs.existsSync(envLocalPath)) {
   dotenv.config({ path: envLocalPath, override: true })
 }
 
-const app = express()
 const PORT = process.env.PORT || 3001
 const HOST = process.env.HOST || '198.18.0.1'
-const FRONTEND_URLS = process.env.FRONTEND_URLS || 'http://localhost:3000'
-const ALLOWED_ORIGINS = new Set(
-  FRONTEND_URLS.split(',')
-    .map(origin => origin.trim())
-    .filter(Boolean),
-)
-
-// Middleware
-app.use(
-  cors({
-    origin(origin, callback) {
-      if (!origin || ALLOWED_ORIGINS.has(origin)) {
-        return callback(null, true)
-      }
-      return callback(new Error(`CORS blocked origin: ${origin}`))
-    },
-    credentials: true,
-  }),
-)
-app.use(express.json())
-
-// Health check endpoint
-app.get('/api/health', (req, res) => {
-  res.json({ status: 'ok', message: 'Qurio backend is running' })
-})
-
-// Import routes
-import titleSpaceAgentRoutes from './routes/titleSpaceAgent.js'
-import titleRoutes from './routes/title.js'
-import researchPlanRoutes from './routes/researchPlan.js'
-import dailyTipRoutes from './routes/dailyTip.js'
-import titleAndSpaceRoutes from './routes/titleAndSpace.js'
-import agentForAutoRoutes from './routes/agentForAuto.js'
-import relatedQuestionsRoutes from './routes/relatedQuestions.js'
-import streamChatRoutes from './routes/streamChat.js'
-import deepResearchChatRoutes from './routes/deepResearchChat.js'
-import toolsRoutes from './routes/tools.js'
-import mcpToolsRoutes from './routes/mcpTools.js'
-app.use('/api', titleSpaceAgentRoutes)
-app.use('/api', titleRoutes)
-app.use('/api', researchPlanRoutes)
-app.use('/api', dailyTipRoutes)
-app.use('/api', titleAndSpaceRoutes)
-app.use('/api', agentForAutoRoutes)
-app.use('/api', relatedQuestionsRoutes)
-app.use('/api', streamChatRoutes)
-app.use('/api', deepResearchChatRoutes)
-app.use('/api', toolsRoutes)
-app.use('/api/mcp-tools', mcpToolsRoutes)
-
-// 404 handler
-app.use((req, res) => {
-  res.status(404).json({ error: 'Not found' })
-})
-
-// Error handler
-app.use((err, req, res, next) => {
-  console.error(err.stack)
-  res.status(500).json({ error: 'Internal server error', message: err.message })
-})
+const app = createApp()
 
 // Start server
-app.listen(PORT, HOST, () => {
+const server = app.listen(PORT, HOST, () => {
   console.log(`🚀 Qurio backend running on http://${HOST}:${PORT}`)
   console.log(`📡 API endpoints available at http://${HOST}:${PORT}/api`)
+  try {
+    recoverInterruptedMessages()
+  } catch (error) {
+    console.warn('[MessageJournal] Recovery failed:', error.message)
+  }
+  detectRuntimes().then(({ mode, unavailable_features }) => {
+    if (mode === 'degraded') {
+      console.warn(`[Runtime] Degraded mode, unavailable: ${unavailable_features.join(', ')}`)
+    }
+  })
+  if (getBackgroundConfig().enabled) {
+    backgroundJobManager.start()
+  }
+  if (getWarmupConfig().enabled) {
+    runWarmup().catch(error => console.warn('[Warmup] Failed:', error.message))
+  }
 })
+
+// GET /api/ws/chat
+attachChatWebSocket(server, app)
+
+// Service managers (launchd, systemd) stop the process with SIGTERM/SIGINT
+const shutdown = signal => {
+  console.log(`[Server] ${signal} received, shutting down`)
+  backgroundJobManager.stop()
+  server.close(() => process.exit(0))
+  setTimeout(() => process.exit(0), 5000).unref()
+}
+process.on('SIGTERM', () => shutdown('SIGTERM'))
+process.on('SIGINT', () => shutdown('SIGINT'))
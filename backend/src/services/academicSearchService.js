@@ -0,0 +1,251 @@
+/**
+ * Academic search service
+ * Paper search through the free arXiv and Semantic Scholar APIs (PubMed lives in
+ * pubmedService.js). Results share one shape with PubMed's: title, authors, year, DOI, venue and
+ * abstract, plus url/content so they are collected as sources. SEMANTIC_SCHOLAR_API_KEY is
+ * optional and raises Semantic Scholar's shared rate limit.
+ */
+
+import { decodeEntities } from './htmlReadability.js'
+
+const ARXIV_API = 'https://export.arxiv.org/api/query'
+const SEMANTIC_SCHOLAR_API = 'https://api.semanticscholar.org/graph/v1/paper/search'
+const REQUEST_TIMEOUT_MS = 20000
+const MAX_ABSTRACT_CHARS = 1500
+const MAX_RESULTS = 30
+
+export const ARXIV_SORT_ORDERS = {
+  relevance: 'relevance',
+  submitted: 'submittedDate',
+  updated: 'lastUpdatedDate',
+}
+
+const SEMANTIC_SCHOLAR_FIELDS = [
+  'title',
+  'authors',
+  'year',
+  'publicationDate',
+  'venue',
+  'externalIds',
+  'abstract',
+  'url',
+  'citationCount',
+  'openAccessPdf',
+  'tldr',
+]
+
+const clampLimit = limit => Math.min(Math.max(Number(limit) || 10, 1), MAX_RESULTS)
+
+const truncate = text =>
+  text.length > MAX_ABSTRACT_CHARS ? `${text.slice(0, MAX_ABSTRACT_CHARS)}…` : text
+
+/**
+ * Bare, lower-case DOI ("10.1000/xyz") from a DOI, "doi:" string, or doi.org URL
+ * @returns {string|null}
+ */
+export const normalizeDoi = value => {
+  let text = String(value || '').trim()
+  try {
+    text = decodeURIComponent(text)
+  } catch {
+    // Not percent-encoded
+  }
+  const match = text.match(/(?:^|doi\.org\/|^doi:\s*)(10\.\d{4,9}\/\S+)$/i)
+  return match ? match[1].toLowerCase() : null
+}
+
+// DataCite DOI arXiv assigns every preprint
+export const arxivDoi = arxivId => `10.48550/arxiv.${arxivId.toLowerCase()}`
+
+const fetchWithTimeout = (url, { headers, signal } = {}) => {
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  return fetch(url, {
+    headers,
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+}
+
+const decodeXml = text =>
+  decodeEntities(
+    String(text || '')
+      .replace(/<!\[CDATA\[([\s\S]*?)\]\]>/g, '$1')
+      .replace(/<[^>]+>/g, '')
+  )
+    .replace(/\s+/g, ' ')
+    .trim()
+
+const tagPattern = (tag, flags) =>
+  new RegExp(`<${tag}(\\s[^>]*)?(?:/>|>([\\s\\S]*?)</${tag}>)`, flags)
+
+const firstTag = (xml, tag) => decodeXml(xml.match(tagPattern(tag))?.[2])
+
+const allTags = (xml, tag) =>
+  Array.from(xml.matchAll(tagPattern(tag, 'g'))).map(match => ({
+    attrs: match[1] || '',
+    raw: match[2] || '',
+    text: decodeXml(match[2]),
+  }))
+
+const readAttribute = (attrs, name) => attrs.match(new RegExp(`\\b${name}="([^"]*)"`))?.[1] || null
+
+// arXiv's query syntax: plain words are searched in all fields, all of them required
+const toArxivQuery = (query, category) => {
+  const text = String(query).trim()
+  const search = /\b(?:ti|au|abs|co|jr|cat|rn|id|all):/.test(text)
+    ? text
+    : text
+        .split(/\s+/)
+        .map(term => `all:${term.replace(/[()"]/g, '')}`)
+        .filter(term => term !== 'all:')
+        .join(' AND ')
+  return category ? `(${search}) AND cat:${category}` : search
+}
+
+const parseArxivEntry = xml => {
+  // <id> is the abstract page, e.g. http://arxiv.org/abs/2401.01234v2
+  const absUrl = firstTag(xml, 'id').replace(/^http:/, 'https:')
+  const versionedId = absUrl.replace(/^.*\/abs\//, '')
+  const arxivId = versionedId.replace(/v\d+$/, '')
+  const links = allTags(xml, 'link')
+  const pdfLink = links.find(link => readAttribute(link.attrs, 'title') === 'pdf')
+  const published = firstTag(xml, 'published')
+  const abstract = firstTag(xml, 'summary')
+  const journalDoi = normalizeDoi(firstTag(xml, 'arxiv:doi'))
+  return {
+    arxiv_id: arxivId,
+    title: firstTag(xml, 'title'),
+    authors: allTags(xml, 'author')
+      .map(author => firstTag(author.raw, 'name'))
+      .filter(Boolean),
+    year: published ? Number(published.slice(0, 4)) : null,
+    published_at: published || null,
+    updated_at: firstTag(xml, 'updated') || null,
+    // The published version's DOI when the authors linked one, else arXiv's own DataCite DOI
+    doi: journalDoi || (arxivId ? arxivDoi(arxivId) : null),
+    venue: firstTag(xml, 'arxiv:journal_ref') || 'arXiv',
+    categories: allTags(xml, 'category')
+      .map(category => readAttribute(category.attrs, 'term'))
+      .filter(Boolean),
+    pdf_url: pdfLink ? readAttribute(pdfLink.attrs, 'href')?.replace(/^http:/, 'https:') : null,
+    abstract: truncate(abstract),
+    url: `https://arxiv.org/abs/${arxivId}`,
+    content: truncate(abstract),
+  }
+}
+
+/**
+ * Search arXiv preprints
+ * @param {Object} args
+ * @param {string} args.query Plain words, or arXiv syntax (ti:, au:, abs:, cat:, AND/OR/ANDNOT)
+ * @param {string} [args.category] arXiv category, e.g. "cs.CL" or "q-bio.NC"
+ * @param {string} [args.sort] Key of ARXIV_SORT_ORDERS (default relevance)
+ * @param {number} [args.limit=10]
+ */
+export const searchArxiv = async ({ query, category, sort = 'relevance', limit = 10, signal }) => {
+  const search = toArxivQuery(query, category)
+  const params = new URLSearchParams({
+    search_query: search,
+    start: '0',
+    max_results: String(clampLimit(limit)),
+    sortBy: ARXIV_SORT_ORDERS[sort] || ARXIV_SORT_ORDERS.relevance,
+    sortOrder: 'descending',
+  })
+  const response = await fetchWithTimeout(`${ARXIV_API}?${params}`, {
+    headers: { Accept: 'application/atom+xml' },
+    signal,
+  })
+  if (!response.ok) throw new Error(`arXiv API error: HTTP ${response.status}`)
+  const xml = await response.text()
+  const entries = xml.match(/<entry>[\s\S]*?<\/entry>/g) || []
+  return {
+    query: search,
+    total_count: Number(firstTag(xml, 'opensearch:totalResults')) || entries.length,
+    results: entries.map(parseArxivEntry).filter(paper => paper.arxiv_id && paper.title),
+  }
+}
+
+const parseSemanticScholarPaper = paper => {
+  const ids = paper.externalIds || {}
+  const abstract = paper.abstract || paper.tldr?.text || ''
+  const doi = normalizeDoi(ids.DOI) || (ids.ArXiv ? arxivDoi(ids.ArXiv) : null)
+  return {
+    paper_id: paper.paperId,
+    title: paper.title || '',
+    authors: (paper.authors || []).map(author => author.name).filter(Boolean),
+    year: paper.year ?? null,
+    published_at: paper.publicationDate || null,
+    doi,
+    venue: paper.venue || null,
+    citation_count: paper.citationCount ?? null,
+    arxiv_id: ids.ArXiv || null,
+    pmid: ids.PubMed || null,
+    pdf_url: paper.openAccessPdf?.url || null,
+    abstract: truncate(abstract),
+    url: paper.url || `https://www.semanticscholar.org/paper/${paper.paperId}`,
+    content: truncate(abstract || paper.title || ''),
+  }
+}
+
+/**
+ * Search Semantic Scholar's paper index (all fields of study, with citation counts)
+ * @param {Object} args
+ * @param {string} args.query
+ * @param {string} [args.year] Year or range: "2020", "2018-2022", "2019-", "-2015"
+ * @param {string[]} [args.fieldsOfStudy] e.g. ["Computer Science", "Medicine"]
+ * @param {boolean} [args.openAccessOnly] Only papers with a free PDF
+ * @param {number} [args.minCitations]
+ * @param {number} [args.limit=10]
+ */
+export const searchSemanticScholar = async ({
+  query,
+  year,
+  fieldsOfStudy,
+  openAccessOnly,
+  minCitations,
+  limit = 10,
+  signal,
+}) => {
+  const params = new URLSearchParams({
+    query: String(query).trim(),
+    limit: String(clampLimit(limit)),
+    fields: SEMANTIC_SCHOLAR_FIELDS.join(','),
+  })
+  if (year) params.set('year', String(year))
+  if (fieldsOfStudy?.length) params.set('fieldsOfStudy', fieldsOfStudy.join(','))
+  if (openAccessOnly) params.set('openAccessPdf', '')
+  if (minCitations) params.set('minCitationCount', String(minCitations))
+
+  const apiKey = process.env.SEMANTIC_SCHOLAR_API_KEY
+  const response = await fetchWithTimeout(`${SEMANTIC_SCHOLAR_API}?${params}`, {
+    headers: { Accept: 'application/json', ...(apiKey ? { 'x-api-key': apiKey } : {}) },
+    signal,
+  })
+  if (response.status === 429) {
+    throw new Error(
+      'Semantic Scholar rate limit reached; retry later or set SEMANTIC_SCHOLAR_API_KEY',
+    )
+  }
+  if (!response.ok) throw new Error(`Semantic Scholar API error: HTTP ${response.status}`)
+  const data = await response.json()
+  return {
+    query: params.get('query'),
+    total_count: Number(data.total) || 0,
+    results: (data.data || []).filter(paper => paper?.title).map(parseSemanticScholarPaper),
+  }
+}
+
+/**
+ * Bibliographic fields of an academic search result, for the source it becomes
+ * @returns {{ doi?: string, authors?: string[], year?: number|string, venue?: string }}
+ */
+export const toCitationMetadata = item => {
+  const doi = normalizeDoi(item?.doi)
+  const authors = Array.isArray(item?.authors) ? item.authors.filter(Boolean) : []
+  const venue = item?.venue || item?.journal
+  return {
+    ...(doi ? { doi } : {}),
+    ...(authors.length ? { authors } : {}),
+    ...(item?.year ? { year: item.year } : {}),
+    ...(venue ? { venue } : {}),
+  }
+}
@@ -0,0 +1,242 @@
+/**
+ * Agent preset service
+ * Agent definitions (name, description, system prompt, default provider/model, enabled tools,
+ * sampling parameters, response style) on the local agents collection, with the Supabase
+ * "agents" column names (see conversationStore), and their resolution for chat requests: a
+ * request naming an agent_id gets the agent's preamble, tools, and parameters from the server,
+ * so the agent behaves the same whichever client sends it. An agent with space_ids is only
+ * offered in, and only usable from, those spaces.
+ */
+
+import { deleteEntity, getEntity, listEntities, saveEntity } from './conversationStore.js'
+
+const STRING_FIELDS = [
+  'name',
+  'description',
+  'prompt',
+  'emoji',
+  'provider',
+  'default_model_provider',
+  'default_model',
+  'lite_model_provider',
+  'lite_model',
+  'response_language',
+  'custom_instruction',
+]
+const NUMBER_RANGES = {
+  temperature: [0, 2],
+  top_p: [0, 1],
+  frequency_penalty: [-2, 2],
+  presence_penalty: [-2, 2],
+}
+const LIST_FIELDS = ['tool_ids', 'space_ids']
+
+// Same rules as the frontend's response style settings (src/lib/settings.js STYLE_PROMPTS)
+const STYLE_PROMPTS = {
+  base_tone: {
+    technical: 'Use a technical, precise tone suitable for developers.',
+    friendly: 'Use a friendly, approachable tone.',
+    professional: 'Use a professional, business-appropriate tone.',
+    academic: 'Use an academic, formal tone with clear reasoning.',
+    creative: 'Use a creative, vivid tone when appropriate.',
+    casual: 'Use a casual, conversational tone.',
+  },
+  traits: {
+    concise: 'Be concise and avoid filler.',
+    structured: 'Prefer structured answers with clear sections.',
+    detailed: 'Provide thorough explanations with necessary detail.',
+    actionable: 'Prioritize actionable steps and concrete recommendations.',
+    analytical: 'Use an analytical mindset and highlight trade-offs.',
+  },
+  warmth: {
+    gentle: 'Be gentle and considerate in phrasing.',
+    empathetic: 'Show empathy and acknowledge user intent or concerns.',
+    direct: 'Keep warmth minimal and focus on direct delivery.',
+    supportive: 'Be supportive and reassuring when appropriate.',
+  },
+  enthusiasm: {
+    low: 'Keep enthusiasm low and neutral.',
+    medium: 'Maintain a balanced, positive tone.',
+    high: 'Use an upbeat, energetic tone.',
+  },
+  headings: {
+    minimal: 'Use minimal formatting and avoid excessive headings.',
+    structured: 'Use headings and lists to improve scanability.',
+    detailed: 'Use clear headings, lists, and short summaries.',
+  },
+  emojis: {
+    none: 'Avoid using emojis.',
+    light: 'Use emojis sparingly.',
+    moderate: 'Use a moderate amount of emojis when fitting.',
+    expressive: 'Feel free to use emojis to add warmth and clarity.',
+  },
+}
+
+export class AgentPresetError extends Error {
+  constructor(message, details = []) {
+    super(message)
+    this.name = 'AgentPresetError'
+    this.details = details
+  }
+}
+
+const isBlank = value => value === undefined || value === null || value === ''
+
+/**
+ * Validate an agent definition
+ * @returns {string[]} Problems found (empty when valid)
+ */
+export const validateAgentPreset = agent => {
+  if (!agent || typeof agent !== 'object' || Array.isArray(agent)) {
+    return ['Agent must be an object']
+  }
+  const errors = []
+  if (!String(agent.name || '').trim()) errors.push('name is required')
+  for (const field of STRING_FIELDS) {
+    if (!isBlank(agent[field]) && typeof agent[field] !== 'string') {
+      errors.push(`${field} must be a string`)
+    }
+  }
+  for (const [field, [min, max]] of Object.entries(NUMBER_RANGES)) {
+    if (isBlank(agent[field])) continue
+    const value = agent[field]
+    if (typeof value !== 'number' || !Number.isFinite(value) || value < min || value > max) {
+      errors.push(`${field} must be a number between ${min} and ${max}`)
+    }
+  }
+  for (const field of LIST_FIELDS) {
+    if (isBlank(agent[field])) continue
+    const value = agent[field]
+    if (!Array.isArray(value) || value.some(item => typeof item !== 'string' || !item)) {
+      errors.push(`${field} must be an array of strings`)
+    }
+  }
+  for (const [field, options] of Object.entries(STYLE_PROMPTS)) {
+    const value = agent[field]
+    if (!isBlank(value) && value !== 'default' && !options[value]) {
+      errors.push(`${field} must be one of: default, ${Object.keys(options).join(', ')}`)
+    }
+  }
+  return errors
+}
+
+const isVisibleInSpace = (agent, spaceId) =>
+  !agent.space_ids?.length || agent.space_ids.includes(String(spaceId))
+
+/**
+ * Agents ordered by creation; with spaceId, only those usable in that space
+ */
+export const listAgentPresets = ({ spaceId } = {}) => {
+  const agents = listEntities('agents')
+  return spaceId ? agents.filter(agent => isVisibleInSpace(agent, spaceId)) : agents
+}
+
+export const getAgentPreset = id => getEntity('agents', String(id))
+
+/**
+ * @param {Object} payload
+ * @param {Object} [options]
+ * @param {string} [options.id] - Id of the new agent (default a random UUID)
+ */
+export const createAgentPreset = (payload, { id } = {}) => {
+  const errors = validateAgentPreset(payload)
+  if (errors.length) throw new AgentPresetError('Invalid agent', errors)
+  const { id: _id, created_at: _createdAt, ...fields } = payload
+  return saveEntity('agents', id ? { ...fields, id: String(id) } : fields)
+}
+
+/**
+ * Update the given fields of an agent (the result must still be valid)
+ * @returns {Object|null} null when the agent does not exist
+ */
+export const updateAgentPreset = (id, payload) => {
+  const existing = getAgentPreset(id)
+  if (!existing) return null
+  const { id: _id, created_at: _createdAt, ...fields } = payload || {}
+  const errors = validateAgentPreset({ ...existing, ...fields })
+  if (errors.length) throw new AgentPresetError('Invalid agent', errors)
+  return saveEntity('agents', { ...fields, id: existing.id })
+}
+
+/**
+ * @returns {'deleted'|'not_found'|'default'} The default agent cannot be deleted
+ */
+export const deleteAgentPreset = id => {
+  const existing = getAgentPreset(id)
+  if (!existing) return 'not_found'
+  if (existing.is_default) return 'default'
+  deleteEntity('agents', String(id))
+  return 'deleted'
+}
+
+/**
+ * System prompt of an agent, laid out as the frontend builds it: the agent prompt, its response
+ * style rules, and its answer language
+ * @returns {string} Empty when the agent adds nothing
+ */
+export const buildAgentPreamble = agent => {
+  const parts = []
+  const prompt = String(agent?.prompt || '').trim()
+  if (prompt) parts.push(`## Agent Prompt\n${prompt}`)
+
+  const rules = Object.entries(STYLE_PROMPTS)
+    .map(([field, options]) => options[agent?.[field]])
+    .filter(Boolean)
+  const customInstruction = String(agent?.custom_instruction || '').trim()
+  if (customInstruction) rules.push(customInstruction)
+  if (rules.length) parts.push(`## Response Style\n${rules.map(rule => `- ${rule}`).join('\n')}`)
+
+  const language = String(agent?.response_language || '').trim()
+  if (language) parts.push(`## Language\nReply in ${language}.`)
+  return parts.join('\n\n')
+}
+
+/**
+ * Fill a chat request from an agent: the preamble goes ahead of the messages, and the agent's
+ * provider, model, tools, and sampling parameters apply where the request leaves them unset.
+ * The agent's model is only used with the agent's provider.
+ */
+export const applyAgentPreset = (body, agent) => {
+  const agentProvider = agent.default_model_provider || agent.provider || undefined
+  const provider = body.provider || agentProvider
+  const preamble = buildAgentPreamble(agent)
+  const fallback = (value, field) =>
+    isBlank(value) && !isBlank(agent[field]) ? agent[field] : value
+  return {
+    ...body,
+    provider,
+    model: body.model || (provider === agentProvider && agent.default_model) || undefined,
+    temperature: fallback(body.temperature, 'temperature'),
+    top_p: fallback(body.top_p, 'top_p'),
+    frequency_penalty: fallback(body.frequency_penalty, 'frequency_penalty'),
+    presence_penalty: fallback(body.presence_penalty, 'presence_penalty'),
+    toolIds: body.toolIds ?? (Array.isArray(agent.tool_ids) ? agent.tool_ids : undefined),
+    messages:
+      preamble && Array.isArray(body.messages)
+        ? [{ role: 'system', content: preamble }, ...body.messages]
+        : body.messages,
+  }
+}
+
+/**
+ * Resolve a request's agent_id
+ * @param {Object} body - Chat request body (after space credentials are applied)
+ * @returns {{ body: Object, agent?: Object } | { rejection: { status: number, error: string } }}
+ */
+export const resolveRequestAgent = body => {
+  const agentId = body.agentId ?? body.agent_id
+  if (isBlank(agentId)) return { body }
+  const agent = getAgentPreset(agentId)
+  if (!agent) return { rejection: { status: 404, error: `Agent not found: ${agentId}` } }
+  const spaceId = body.spaceId ?? body.space_id
+  // A request outside any space can only use agents that are not bound to spaces
+  if (isBlank(spaceId) && agent.space_ids?.length) {
+    return { rejection: { status: 400, error: `Agent ${agentId} is only available in its spaces` } }
+  }
+  if (!isBlank(spaceId) && !isVisibleInSpace(agent, spaceId)) {
+    return {
+      rejection: { status: 400, error: `Agent ${agentId} is not available in space ${spaceId}` },
+    }
+  }
+  return { body: applyAgentPreset(body, agent), agent }
+}
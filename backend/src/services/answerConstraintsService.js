@@ -0,0 +1,304 @@
+/**
+ * Answer constraints service
+ * Server-side answer length and format controls (max_words, bullets, table, short/long).
+ * The constraints are injected into the system prompt, and because models often ignore them the
+ * finished answer is checked afterwards: a grossly over-long answer is truncated, a wrong format
+ * (or a "long" answer that came back far too short) is re-asked once as a rewrite.
+ */
+
+import { completeText } from './modelCompletion.js'
+
+export const ANSWER_FORMATS = ['bullets', 'table', 'short', 'long']
+const FORMAT_ALIASES = { bullet: 'bullets', list: 'bullets', bulleted: 'bullets', tabular: 'table' }
+const LAYOUTS = ['bullets', 'table']
+const LENGTHS = ['short', 'long']
+
+const MIN_WORDS = 10
+const MAX_WORDS = 5000
+const SHORT_MAX_WORDS = 150
+const LONG_MIN_WORDS = 400
+// How far an answer may miss before it is corrected rather than just reported
+const GROSS_OVER_FACTOR = 1.5
+const GROSS_UNDER_FACTOR = 0.5
+const MIN_BULLET_SHARE = 0.5
+
+// CJK characters count as one word each; otherwise words are whitespace-separated tokens with a
+// letter or digit, so markdown syntax (bullets, table pipes) is not counted
+const CJK = '\u3040-\u30ff\u3400-\u9fff\uac00-\ud7af'
+const WORD_PATTERN = new RegExp(`[${CJK}]|[^\\s${CJK}]*[\\p{L}\\p{N}][^\\s${CJK}]*`, 'gu')
+const BULLET_LINE_PATTERN = /^\s*(?:[-*+•]|\d+[.)])\s+\S/
+const HEADING_LINE_PATTERN = /^\s*#{1,6}\s/
+const TABLE_DIVIDER_PATTERN = /^\s*\|?\s*:?-{3,}:?\s*(?:\|\s*:?-{3,}:?\s*)+\|?\s*$/m
+const SENTENCE_END_PATTERN = /[.!?。！？](?=\s|$)/g
+
+export const countWords = text => (String(text || '').match(WORD_PATTERN) || []).length
+
+const parseFormats = format => {
+  if (format === undefined || format === null || format === '') return []
+  const values = Array.isArray(format) ? format : String(format).split(',')
+  return values.map(value => {
+    const key = String(value).trim().toLowerCase()
+    return FORMAT_ALIASES[key] || key
+  })
+}
+
+/**
+ * Validate request options
+ * @param {{ maxWords?: number, format?: string|string[] }} options
+ * @returns {string[]} Problems found (empty when valid)
+ */
+export const validateAnswerConstraints = ({ maxWords, format } = {}) => {
+  const errors = []
+  if (maxWords !== undefined && maxWords !== null) {
+    const value = Number(maxWords)
+    if (!Number.isInteger(value) || value < MIN_WORDS || value > MAX_WORDS) {
+      errors.push(`max_words must be an integer between ${MIN_WORDS} and ${MAX_WORDS}`)
+    }
+  }
+  const formats = parseFormats(format)
+  const unknown = formats.filter(value => !ANSWER_FORMATS.includes(value))
+  if (unknown.length) {
+    errors.push(`format must be one of: ${ANSWER_FORMATS.join(', ')} (got ${unknown.join(', ')})`)
+  }
+  if (formats.filter(value => LAYOUTS.includes(value)).length > 1) {
+    errors.push('format may include only one of: bullets, table')
+  }
+  if (formats.filter(value => LENGTHS.includes(value)).length > 1) {
+    errors.push('format may include only one of: short, long')
+  }
+  return errors
+}
+
+/**
+ * Normalized constraints, or null when the request sets none
+ * "short" implies a word budget; an explicit max_words always wins.
+ * @returns {{ maxWords, minWords, layout: string|null, length: string|null }|null}
+ */
+export const normalizeAnswerConstraints = ({ maxWords, format } = {}) => {
+  const formats = parseFormats(format)
+  const layout = formats.find(value => LAYOUTS.includes(value)) || null
+  const length = formats.find(value => LENGTHS.includes(value)) || null
+  const explicitMax = maxWords === undefined || maxWords === null ? null : Number(maxWords)
+  const resolvedMax = explicitMax ?? (length === 'short' ? SHORT_MAX_WORDS : null)
+  const minWords =
+    length === 'long' ? Math.min(LONG_MIN_WORDS, resolvedMax ?? LONG_MIN_WORDS) : null
+  if (!layout && !length && !resolvedMax) return null
+  return { maxWords: resolvedMax, minWords, layout, length }
+}
+
+const CONSTRAINTS_HEADER =
+  'Answer constraints (these override any other length or format guidance):'
+
+const constraintLines = constraints => {
+  const lines = []
+  if (constraints.layout === 'bullets') {
+    lines.push(
+      'Format the answer as a bulleted markdown list ("- " items). ' +
+        'At most a one-line lead-in before the list; no prose paragraphs.',
+    )
+  }
+  if (constraints.layout === 'table') {
+    lines.push(
+      'Present the answer as a markdown table (header row plus |---| divider). ' +
+        'Keep any text outside the table to one short line.',
+    )
+  }
+  if (constraints.length === 'short') lines.push('Be brief: give only the essential answer.')
+  if (constraints.length === 'long') {
+    lines.push(`Give a thorough, detailed answer of at least ${constraints.minWords} words.`)
+  }
+  if (constraints.maxWords) {
+    lines.push(`Use at most ${constraints.maxWords} words in total. This limit is strict.`)
+  }
+  return lines
+}
+
+/**
+ * Constraint instructions for a system prompt, or '' when there are none
+ */
+export const buildAnswerConstraintsPrompt = constraints => {
+  if (!constraints) return ''
+  const lines = constraintLines(constraints).map(line => `- ${line}`)
+  return [CONSTRAINTS_HEADER, ...lines].join('\n')
+}
+
+/**
+ * Add the constraints to the system prompt; merges into a leading system message if present
+ */
+export const applyAnswerConstraintsToMessages = (messages, constraints) => {
+  const prompt = buildAnswerConstraintsPrompt(constraints)
+  if (!prompt || !Array.isArray(messages)) return messages
+  const [first, ...rest] = messages
+  if (first?.role === 'system' && typeof first.content === 'string') {
+    return [{ ...first, content: `${first.content}\n\n${prompt}` }, ...rest]
+  }
+  return [{ role: 'system', content: prompt }, ...messages]
+}
+
+const nonEmptyLines = text =>
+  String(text || '')
+    .split('\n')
+    .filter(line => line.trim())
+
+/**
+ * Check a finished answer against its constraints
+ * @returns {{ word_count: number, violations: Array<{ rule, detail, gross: boolean }> }}
+ */
+export const checkAnswer = (content, constraints) => {
+  const wordCount = countWords(content)
+  const violations = []
+  if (constraints.maxWords && wordCount > constraints.maxWords) {
+    violations.push({
+      rule: 'max_words',
+      detail: `${wordCount} words (limit ${constraints.maxWords})`,
+      gross: wordCount > constraints.maxWords * GROSS_OVER_FACTOR,
+    })
+  }
+  if (constraints.minWords && wordCount < constraints.minWords) {
+    violations.push({
+      rule: 'long',
+      detail: `${wordCount} words (expected at least ${constraints.minWords})`,
+      gross: wordCount < constraints.minWords * GROSS_UNDER_FACTOR,
+    })
+  }
+  if (constraints.layout === 'bullets') {
+    const lines = nonEmptyLines(content).filter(line => !HEADING_LINE_PATTERN.test(line))
+    const bullets = lines.filter(line => BULLET_LINE_PATTERN.test(line)).length
+    if (bullets < 2 || bullets / Math.max(1, lines.length) < MIN_BULLET_SHARE) {
+      violations.push({
+        rule: 'bullets',
+        detail: `${bullets} of ${lines.length} lines are list items`,
+        gross: true,
+      })
+    }
+  }
+  if (constraints.layout === 'table' && !TABLE_DIVIDER_PATTERN.test(String(content || ''))) {
+    violations.push({ rule: 'table', detail: 'no markdown table found', gross: true })
+  }
+  return { word_count: wordCount, violations }
+}
+
+// Cut text to a word budget, preferring the last sentence end inside the budget
+const truncateText = (text, maxWords) => {
+  let count = 0
+  let cutAt = text.length
+  for (const match of text.matchAll(WORD_PATTERN)) {
+    count += 1
+    if (count > maxWords) {
+      cutAt = match.index
+      break
+    }
+  }
+  const head = text.slice(0, cutAt).trimEnd()
+  const sentenceEnds = Array.from(head.matchAll(SENTENCE_END_PATTERN))
+  const lastEnd = sentenceEnds.at(-1)
+  if (lastEnd && lastEnd.index + 1 >= head.length / 2) return head.slice(0, lastEnd.index + 1)
+  return `${head.replace(/[,;:\s]+$/, '')}…`
+}
+
+/**
+ * Shorten an answer to its word budget, keeping whole lines (list items, table rows) where possible
+ */
+export const truncateAnswer = (content, maxWords) => {
+  const kept = []
+  let used = 0
+  for (const line of String(content || '').split('\n')) {
+    const words = countWords(line)
+    if (used + words <= maxWords) {
+      kept.push(line)
+      used += words
+      continue
+    }
+    // Partial lines are only worth keeping for prose; a cut table row or list item just breaks
+    if (!kept.some(item => item.trim()) || !/^\s*(?:\||[-*+•]\s|\d+[.)]\s)/.test(line)) {
+      if (maxWords - used >= 5) kept.push(truncateText(line, maxWords - used))
+    }
+    break
+  }
+  return kept.join('\n').trimEnd()
+}
+
+const REWRITE_SYSTEM_PROMPT = `You rewrite answers so that they satisfy formatting constraints.
+Keep the facts, the language, and any citation markers such as [1] from the original answer.
+Do not add new information. Output only the rewritten answer.`
+
+const rewriteAnswer = async (content, constraints, completion) =>
+  completeText({
+    ...completion,
+    temperature: 0.2,
+    messages: [
+      { role: 'system', content: REWRITE_SYSTEM_PROMPT },
+      {
+        role: 'user',
+        content: `Constraints:\n${constraintLines(constraints)
+          .map(line => `- ${line}`)
+          .join('\n')}\n\nAnswer to rewrite:\n${content}`,
+      },
+    ],
+  })
+
+/**
+ * Bring an answer within its constraints
+ * @returns {Promise<{ content: string, method: 'truncate'|'rewrite', check: Object }|null>}
+ *   null when the answer is acceptable or could not be improved
+ */
+export const enforceAnswer = async (content, constraints, completion) => {
+  const check = checkAnswer(content, constraints)
+  const gross = check.violations.filter(violation => violation.gross)
+  if (!gross.length) return null
+
+  let revised = content
+  let method = 'truncate'
+  if (gross.some(violation => violation.rule !== 'max_words')) {
+    try {
+      const rewritten = await rewriteAnswer(content, constraints, completion)
+      if (rewritten) {
+        revised = rewritten
+        method = 'rewrite'
+      }
+    } catch (error) {
+      if (completion.signal?.aborted) throw error
+      console.warn('[AnswerConstraints] Rewrite failed:', error.message)
+    }
+  }
+  if (constraints.maxWords && countWords(revised) > constraints.maxWords * GROSS_OVER_FACTOR) {
+    revised = truncateAnswer(revised, constraints.maxWords)
+  }
+  if (revised === content) return null
+  return { content: revised, method, check: checkAnswer(revised, constraints) }
+}
+
+/**
+ * Pass a chat event stream through, post-checking the final answer
+ * A corrected answer is announced as an "answer_revised" event (clients replace the streamed
+ * text with it) and replaces the done event's content; done also carries the check result.
+ * @param {AsyncIterable} stream - Chat events
+ * @param {Object|null} constraints - From normalizeAnswerConstraints
+ * @param {Object} completion - { provider, apiKey, baseUrl, model, signal } for the rewrite pass
+ */
+export const enforceAnswerConstraints = async function* (stream, constraints, completion) {
+  for await (const event of stream) {
+    if (!constraints || event?.type !== 'done' || !event.content) {
+      yield event
+      continue
+    }
+    const original = checkAnswer(event.content, constraints)
+    const revision = await enforceAnswer(event.content, constraints, completion)
+    if (!revision) {
+      yield { ...event, answer_check: original }
+      continue
+    }
+    yield {
+      type: 'answer_revised',
+      method: revision.method,
+      content: revision.content,
+      violations: original.violations,
+    }
+    yield {
+      ...event,
+      content: revision.content,
+      original_content: event.content,
+      answer_check: { ...revision.check, revised: revision.method, original },
+    }
+  }
+}
@@ -0,0 +1,270 @@
+/**
+ * Attachment service
+ * Resolves the file parts of chat messages into content the provider can take. Accepted parts:
+ *   { "type": "upload", "upload_id": "..." } or an image_url of "upload:<id>" (chunked uploads)
+ *   { "type": "image_base64", "data": "<base64 or data URL>", "mime_type": "image/png" }
+ *   { "type": "image_url", "image_url": { "url": "file:///abs/photo.jpg" } }
+ *   { "type": "file", "path": "/abs/notes.pdf" } (or "url": "file:///...")
+ *   { "type": "document", "data": "<base64>" | "path": "/abs/paper.pdf", "filename": "..." }
+ * Local paths must lie inside QURIO_SANDBOX_DIRS. The type is sniffed from the bytes (falling back
+ * to the extension and declared MIME type). Images become image_url data parts, downscaled when
+ * larger than the image limits if the "sharp" package is installed (npm install sharp; it is not
+ * a dependency, as it ships native binaries per platform). PDFs are sent as
+ * native document parts to providers with supportsDocuments and as extracted text to the rest,
+ * like .docx, .md and .txt files.
+ *
+ * ATTACHMENT_MAX_MB caps any attachment (default 20), ATTACHMENT_MAX_IMAGE_MB (default 5) and
+ * ATTACHMENT_MAX_IMAGE_PX (longest side, default 2048) the images sent to the model.
+ */
+
+import fs from 'fs'
+import path from 'path'
+import { fileURLToPath } from 'url'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+import { resolveSandboxedDir, SandboxError } from '../utils/pathSandbox.js'
+import { extractDocumentText } from './documentTextExtractor.js'
+import { supportsCapability } from './providers/providerConfig.js'
+import { readUploadFile } from './uploadService.js'
+
+const DEFAULT_MAX_MB = 20
+const DEFAULT_MAX_IMAGE_MB = 5
+const DEFAULT_MAX_IMAGE_PX = 2048
+const MAX_ATTACHMENT_CHARS = 100000
+const MIN_IMAGE_PX = 256
+const IMAGE_MIME_PATTERN = /^image\/(png|jpeg|gif|webp)$/
+const DATA_URL_PATTERN = /^data:([^;,]+)?(?:;[^,]*)?;base64,(.*)$/s
+
+const MB = 1024 * 1024
+
+const MIME_BY_EXTENSION = {
+  '.png': 'image/png',
+  '.jpg': 'image/jpeg',
+  '.jpeg': 'image/jpeg',
+  '.gif': 'image/gif',
+  '.webp': 'image/webp',
+  '.pdf': 'application/pdf',
+}
+
+const readPositive = (value, fallback) => {
+  const number = Number.parseFloat(value)
+  return Number.isFinite(number) && number > 0 ? number : fallback
+}
+
+const getLimits = () => ({
+  maxBytes: readPositive(process.env.ATTACHMENT_MAX_MB, DEFAULT_MAX_MB) * MB,
+  maxImageBytes: readPositive(process.env.ATTACHMENT_MAX_IMAGE_MB, DEFAULT_MAX_IMAGE_MB) * MB,
+  maxImagePx: Math.round(readPositive(process.env.ATTACHMENT_MAX_IMAGE_PX, DEFAULT_MAX_IMAGE_PX)),
+})
+
+const invalid = (message, options) => new QurioError(ErrorCode.InvalidRequest, message, options)
+const tooLarge = message => invalid(message, { status: 413 })
+const formatMb = bytes => `${Math.round((bytes / MB) * 10) / 10} MB`
+
+/**
+ * MIME type from magic bytes (PNG, JPEG, GIF, WebP, PDF)
+ * @param {Buffer} buffer
+ * @returns {string|null}
+ */
+export const sniffMimeType = buffer => {
+  if (buffer.length >= 8 && buffer.readUInt32BE(0) === 0x89504e47) return 'image/png'
+  if (buffer.length >= 3 && buffer[0] === 0xff && buffer[1] === 0xd8 && buffer[2] === 0xff) {
+    return 'image/jpeg'
+  }
+  const head = buffer.subarray(0, 12).toString('latin1')
+  if (head.startsWith('GIF87a') || head.startsWith('GIF89a')) return 'image/gif'
+  if (head.startsWith('RIFF') && head.slice(8, 12) === 'WEBP') return 'image/webp'
+  if (head.startsWith('%PDF-')) return 'application/pdf'
+  return null
+}
+
+const detectMimeType = (buffer, { filename, mimeType } = {}) =>
+  sniffMimeType(buffer) ||
+  MIME_BY_EXTENSION[path.extname(String(filename || '')).toLowerCase()] ||
+  String(mimeType || '')
+    .split(';')[0]
+    .trim()
+    .toLowerCase()
+    .replace('image/jpg', 'image/jpeg') ||
+  null
+
+// --- Sources ----------------------------------------------------------------
+
+const readLocalFile = (filePath, { maxBytes }) => {
+  const target = filePath.startsWith('file:') ? fileURLToPath(filePath) : filePath
+  if (!path.isAbsolute(target)) throw invalid(`Attachment path must be absolute: ${filePath}`)
+  let resolved
+  try {
+    resolved = resolveSandboxedDir(target)
+  } catch (error) {
+    if (error instanceof SandboxError) throw invalid(error.message, { status: 403 })
+    throw error
+  }
+  const stat = fs.statSync(resolved, { throwIfNoEntry: false })
+  if (!stat?.isFile()) throw invalid(`Attachment not found: ${filePath}`, { status: 404 })
+  if (stat.size > maxBytes) {
+    throw tooLarge(`Attachment ${path.basename(resolved)} is over ${formatMb(maxBytes)}`)
+  }
+  return { buffer: fs.readFileSync(resolved), filename: path.basename(resolved) }
+}
+
+const decodeBase64 = (value, { maxBytes }) => {
+  const dataUrl = String(value || '').match(DATA_URL_PATTERN)
+  const base64 = (dataUrl ? dataUrl[2] : String(value || '')).replace(/\s+/g, '')
+  if (!base64) throw invalid('Attachment data is empty')
+  // Check before decoding so an oversized payload is not copied again
+  if (Math.floor((base64.length * 3) / 4) > maxBytes + 2) {
+    throw tooLarge(`Attachment is over ${formatMb(maxBytes)}`)
+  }
+  return { buffer: Buffer.from(base64, 'base64'), mimeType: dataUrl?.[1] }
+}
+
+const partUrl = part => {
+  const url = part?.type === 'image_url' ? part.image_url?.url || part.url : part?.url
+  return typeof url === 'string' ? url : null
+}
+
+/**
+ * Where the bytes of an attachment part come from (null for parts passed through unchanged)
+ */
+const sourceOf = part => {
+  if (!part || typeof part !== 'object') return null
+  const uploadId = part.upload_id || part.uploadId
+  if ((part.type === 'upload' || part.type === 'file') && uploadId) return { uploadId }
+  const url = partUrl(part)
+  if (url?.startsWith('upload:')) return { uploadId: url.slice('upload:'.length) }
+  if (part.type === 'image_url') return url?.startsWith('file:') ? { path: url } : null
+  if (part.type === 'image_base64') return { data: part.data ?? part.image_base64 }
+  if (part.type === 'file' || part.type === 'document') {
+    if (part.data) return { data: part.data }
+    if (part.path || url?.startsWith('file:')) return { path: part.path || url }
+    throw invalid(`A ${part.type} attachment needs upload_id, path or data`)
+  }
+  return null
+}
+
+const loadSource = (source, part, limits) => {
+  if (source.uploadId) {
+    const file = readUploadFile(source.uploadId)
+    if (file.buffer.length > limits.maxBytes) {
+      throw tooLarge(`Attachment ${file.filename} is over ${formatMb(limits.maxBytes)}`)
+    }
+    return file
+  }
+  const declaredMime = part.mime_type || part.mimeType
+  if (source.path) {
+    return { ...readLocalFile(String(source.path), limits), mimeType: declaredMime }
+  }
+  const { buffer, mimeType } = decodeBase64(source.data, limits)
+  return { buffer, filename: part.filename || 'attachment', mimeType: declaredMime || mimeType }
+}
+
+// --- Images -----------------------------------------------------------------
+
+let sharpLoader = null
+
+// sharp is installed separately: without it images are sent as they are, within the limits
+const loadSharp = () => {
+  sharpLoader ??= import('sharp').then(
+    module => module.default,
+    () => null,
+  )
+  return sharpLoader
+}
+
+const downscaleImage = async (sharp, buffer, mimeType, { maxImageBytes, maxImagePx }) => {
+  const { width = 0, height = 0, hasAlpha } = await sharp(buffer).metadata()
+  if (buffer.length <= maxImageBytes && Math.max(width, height) <= maxImagePx) {
+    return { buffer, mimeType }
+  }
+  // Keep transparency as PNG; everything else (including animated GIFs) becomes one JPEG frame
+  const outputMime = hasAlpha ? 'image/png' : 'image/jpeg'
+  let size = Math.min(maxImagePx, Math.max(width, height) || maxImagePx)
+  while (size >= MIN_IMAGE_PX) {
+    const pipeline = sharp(buffer)
+      .rotate()
+      .resize({ width: size, height: size, fit: 'inside', withoutEnlargement: true })
+    const output = await (hasAlpha
+      ? pipeline.png({ compressionLevel: 9 })
+      : pipeline.jpeg({ quality: 85 })
+    ).toBuffer()
+    if (output.length <= maxImageBytes) return { buffer: output, mimeType: outputMime }
+    size = Math.floor(size * 0.75)
+  }
+  throw tooLarge(`Image could not be reduced below ${formatMb(maxImageBytes)}`)
+}
+
+const toImagePart = async (buffer, mimeType, filename, limits) => {
+  const sharp = await loadSharp()
+  let image = { buffer, mimeType }
+  if (sharp) {
+    image = await downscaleImage(sharp, buffer, mimeType, limits)
+  } else if (buffer.length > limits.maxImageBytes) {
+    throw tooLarge(
+      `Image ${filename} is ${formatMb(buffer.length)}, over the ` +
+        `${formatMb(limits.maxImageBytes)} image limit; install the "sharp" package ` +
+        '(npm install sharp) to downscale large images',
+    )
+  }
+  return {
+    type: 'image_url',
+    image_url: { url: `data:${image.mimeType};base64,${image.buffer.toString('base64')}` },
+  }
+}
+
+// --- Documents --------------------------------------------------------------
+
+const toTextPart = (buffer, filename, mimeType) => {
+  const { text } = extractDocumentText(buffer, { filename, mimeType })
+  const clipped = text.length > MAX_ATTACHMENT_CHARS
+  return {
+    type: 'text',
+    text: `Attached file "${filename}":\n\n${text.slice(0, MAX_ATTACHMENT_CHARS)}${
+      clipped ? '\n\n[truncated]' : ''
+    }`,
+  }
+}
+
+const toAttachmentPart = async (part, source, { provider, limits }) => {
+  const { buffer, filename, mimeType: declaredMime } = loadSource(source, part, limits)
+  const mimeType = detectMimeType(buffer, { filename, mimeType: declaredMime })
+  if (IMAGE_MIME_PATTERN.test(mimeType)) {
+    return toImagePart(buffer, mimeType, filename, limits)
+  }
+  if (part.type === 'image_base64' || part.type === 'image_url') {
+    throw invalid(`Unsupported image type${mimeType ? `: ${mimeType}` : ''} (png, jpeg, gif, webp)`)
+  }
+  if (mimeType === 'application/pdf' && supportsCapability(provider, 'supportsDocuments')) {
+    // LangChain media part; the Gemini and Anthropic adapters send it as an inline document
+    return { type: 'media', mimeType, data: buffer.toString('base64'), filename }
+  }
+  return toTextPart(buffer, filename, mimeType)
+}
+
+/**
+ * Replace attachment parts in message content with image, document, or text parts
+ * @param {Array} messages - Chat messages; string contents and plain parts are left as they are
+ * @param {Object} [options]
+ * @param {string} [options.provider] - Decides whether PDFs go native or as extracted text
+ * @returns {Promise<Array>}
+ * @throws {QurioError} 400 for unreadable parts, 403 outside the sandbox, 404 for missing files,
+ *   413 over the size limits
+ */
+export const resolveMessageAttachments = async (messages, { provider } = {}) => {
+  if (!Array.isArray(messages)) return messages
+  const limits = getLimits()
+  return Promise.all(
+    messages.map(async message => {
+      if (!Array.isArray(message?.content)) return message
+      const sources = message.content.map(sourceOf)
+      if (!sources.some(Boolean)) return message
+      return {
+        ...message,
+        content: await Promise.all(
+          message.content.map((part, index) =>
+            sources[index] ? toAttachmentPart(part, sources[index], { provider, limits }) : part,
+          ),
+        ),
+      }
+    }),
+  )
+}
@@ -0,0 +1,57 @@
+/**
+ * OpenAI Transcription Provider
+ * POST {baseUrl}/audio/transcriptions (multipart); also used for OpenAI-compatible endpoints.
+ * whisper-1 answers verbose_json with segment timestamps; the gpt-4o-*-transcribe models only
+ * return json, so their segments are empty.
+ */
+
+import { PROVIDER_BASE_URLS } from '../providers/providerConfig.js'
+import { toHttpError, TranscriptionProvider } from './TranscriptionProvider.js'
+
+const JSON_ONLY_MODEL_PATTERN = /gpt-4o.*transcribe/i
+
+export class OpenAITranscriptionProvider extends TranscriptionProvider {
+  constructor(name = 'openai') {
+    super(name)
+  }
+
+  get defaultModel() {
+    return 'whisper-1'
+  }
+
+  get defaultBaseUrl() {
+    return PROVIDER_BASE_URLS.openai
+  }
+
+  buildForm(audio, { model, language, prompt }) {
+    const form = new FormData()
+    form.append('file', new Blob([audio.buffer], { type: audio.mimeType }), audio.filename)
+    form.append('model', model)
+    if (JSON_ONLY_MODEL_PATTERN.test(model)) {
+      form.append('response_format', 'json')
+    } else {
+      form.append('response_format', 'verbose_json')
+      form.append('timestamp_granularities[]', 'segment')
+    }
+    if (language) form.append('language', language)
+    if (prompt) form.append('prompt', prompt)
+    return form
+  }
+
+  async transcribeFile(audio, { apiKey, baseUrl, signal, ...options }) {
+    const response = await fetch(`${baseUrl}/audio/transcriptions`, {
+      method: 'POST',
+      headers: { Authorization: `Bearer ${apiKey}` },
+      body: this.buildForm(audio, options),
+      signal,
+    })
+    if (!response.ok) throw await toHttpError(response, this.name)
+    const data = await response.json()
+    return {
+      text: data?.text,
+      language: data?.language,
+      duration: data?.duration,
+      segments: data?.segments,
+    }
+  }
+}
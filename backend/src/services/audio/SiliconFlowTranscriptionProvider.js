@@ -0,0 +1,34 @@
+/**
+ * SiliconFlow Transcription Provider
+ * SiliconFlow's ASR endpoint (SenseVoice, TeleSpeech) shares the OpenAI multipart request but
+ * takes only file and model, and returns { text } without timestamps.
+ */
+
+import { PROVIDER_BASE_URLS } from '../providers/providerConfig.js'
+import { getRetryPolicy } from '../providers/retry.js'
+import { OpenAITranscriptionProvider } from './OpenAITranscriptionProvider.js'
+
+export class SiliconFlowTranscriptionProvider extends OpenAITranscriptionProvider {
+  constructor() {
+    super('siliconflow')
+  }
+
+  get defaultModel() {
+    return 'FunAudioLLM/SenseVoiceSmall'
+  }
+
+  get defaultBaseUrl() {
+    return PROVIDER_BASE_URLS.siliconflow
+  }
+
+  get retryPolicy() {
+    return getRetryPolicy('siliconflow')
+  }
+
+  buildForm(audio, { model }) {
+    const form = new FormData()
+    form.append('file', new Blob([audio.buffer], { type: audio.mimeType }), audio.filename)
+    form.append('model', model)
+    return form
+  }
+}
@@ -0,0 +1,107 @@
+/**
+ * Base Transcription Provider
+ * Sends one audio file to a speech-to-text endpoint, retries transient failures, and returns the
+ * text with timestamped segments in one shape for every provider.
+ */
+
+import { callWithRetry } from '../providers/retry.js'
+
+const DEFAULT_RETRY_POLICY = { maxRetries: 2, baseDelayMs: 1000 }
+// Long recordings take a while to transcribe
+const REQUEST_TIMEOUT_MS = 300000
+
+/**
+ * Error carrying the HTTP status and headers of a failed transcription request, so the retry
+ * layer and utils/errors can classify it
+ */
+export const toHttpError = async (response, provider) => {
+  const body = await response.text().catch(() => '')
+  let message = body
+  try {
+    const data = JSON.parse(body)
+    message = data?.error?.message || data?.message || body
+  } catch {
+    // Plain-text error body
+  }
+  return Object.assign(
+    new Error(`${provider} transcription failed (HTTP ${response.status}): ${message}`.trim()),
+    { status: response.status, headers: response.headers },
+  )
+}
+
+const roundSeconds = value =>
+  value != null && Number.isFinite(Number(value)) ? Math.round(value * 1000) / 1000 : null
+
+export const normalizeSegments = segments =>
+  (Array.isArray(segments) ? segments : [])
+    .filter(segment => segment && String(segment.text || '').trim())
+    .map((segment, index) => ({
+      id: index,
+      start: roundSeconds(segment.start),
+      end: roundSeconds(segment.end),
+      text: String(segment.text).trim(),
+    }))
+
+export class TranscriptionProvider {
+  constructor(name) {
+    this.name = name
+  }
+
+  get defaultModel() {
+    throw new Error(`${this.name}: defaultModel not implemented`)
+  }
+
+  get defaultBaseUrl() {
+    return ''
+  }
+
+  get retryPolicy() {
+    return DEFAULT_RETRY_POLICY
+  }
+
+  /**
+   * Transcribe one file
+   * @returns {Promise<{ text: string, language?: string, duration?: number, segments?: Array }>}
+   * @abstract
+   */
+  async transcribeFile(_audio, _options) {
+    throw new Error(`${this.name}: transcribeFile not implemented`)
+  }
+
+  /**
+   * Transcribe an audio file
+   * @param {{ buffer: Buffer, filename: string, mimeType: string }} audio
+   * @param {Object} options
+   * @param {string} options.apiKey
+   * @param {string} [options.baseUrl]
+   * @param {string} [options.model]
+   * @param {string} [options.language] - ISO-639-1 hint such as "en"
+   * @param {string} [options.prompt] - Vocabulary or style hint
+   * @param {AbortSignal} [options.signal]
+   * @returns {Promise<{ provider, model, text, language, duration, segments }>}
+   */
+  async transcribe(audio, options = {}) {
+    const model = options.model || this.defaultModel
+    const baseUrl = String(options.baseUrl || this.defaultBaseUrl).replace(/\/$/, '')
+    const result = await callWithRetry(
+      () =>
+        this.transcribeFile(audio, {
+          ...options,
+          model,
+          baseUrl,
+          signal: options.signal
+            ? AbortSignal.any([options.signal, AbortSignal.timeout(REQUEST_TIMEOUT_MS)])
+            : AbortSignal.timeout(REQUEST_TIMEOUT_MS),
+        }),
+      { provider: this.name, policy: this.retryPolicy, signal: options.signal },
+    )
+    return {
+      provider: this.name,
+      model,
+      text: String(result.text || '').trim(),
+      language: result.language || options.language || null,
+      duration: roundSeconds(result.duration),
+      segments: normalizeSegments(result.segments),
+    }
+  }
+}
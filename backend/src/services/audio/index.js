@@ -0,0 +1,118 @@
+/**
+ * Audio transcription
+ * Speech-to-text for dictated prompts (see POST /api/transcribe): OpenAI Whisper or
+ * gpt-4o-transcribe, OpenAI-compatible servers, and SiliconFlow's ASR models.
+ * TRANSCRIBE_MAX_MB caps the audio size (default 25, OpenAI's own limit).
+ */
+
+import path from 'path'
+import { ErrorCode, QurioError } from '../../utils/errors.js'
+import { OpenAITranscriptionProvider } from './OpenAITranscriptionProvider.js'
+import { SiliconFlowTranscriptionProvider } from './SiliconFlowTranscriptionProvider.js'
+
+export const TRANSCRIPTION_PROVIDERS = ['openai', 'openai_compatibility', 'siliconflow']
+export const AUDIO_FORMATS = ['wav', 'mp3', 'm4a']
+
+const DEFAULT_MAX_MB = 25
+const MB = 1024 * 1024
+
+const AUDIO_MIME_TYPES = { wav: 'audio/wav', mp3: 'audio/mpeg', m4a: 'audio/mp4' }
+const FORMATS_BY_EXTENSION = { '.wav': 'wav', '.mp3': 'mp3', '.m4a': 'm4a', '.mp4': 'm4a' }
+const FORMATS_BY_MIME = {
+  'audio/wav': 'wav',
+  'audio/x-wav': 'wav',
+  'audio/wave': 'wav',
+  'audio/mpeg': 'mp3',
+  'audio/mp3': 'mp3',
+  'audio/mp4': 'm4a',
+  'audio/m4a': 'm4a',
+  'audio/x-m4a': 'm4a',
+}
+
+const providerCache = new Map()
+
+/**
+ * Get transcription provider instance
+ * @param {string} provider - Provider name
+ * @returns {import('./TranscriptionProvider.js').TranscriptionProvider}
+ */
+export function getTranscriptionProvider(provider) {
+  if (providerCache.has(provider)) return providerCache.get(provider)
+
+  let instance
+  switch (provider) {
+    case 'openai':
+      instance = new OpenAITranscriptionProvider()
+      break
+    case 'openai_compatibility':
+      instance = new OpenAITranscriptionProvider('openai_compatibility')
+      break
+    case 'siliconflow':
+      instance = new SiliconFlowTranscriptionProvider()
+      break
+    default:
+      throw new QurioError(
+        ErrorCode.InvalidRequest,
+        `Unsupported transcription provider: ${provider}. ` +
+          `Supported: ${TRANSCRIPTION_PROVIDERS.join(', ')}`,
+      )
+  }
+
+  providerCache.set(provider, instance)
+  return instance
+}
+
+export const getMaxAudioBytes = () => {
+  const megabytes = Number.parseFloat(process.env.TRANSCRIBE_MAX_MB)
+  return (Number.isFinite(megabytes) && megabytes > 0 ? megabytes : DEFAULT_MAX_MB) * MB
+}
+
+/**
+ * Detect the audio format from magic bytes, then file name or MIME type
+ * @returns {'wav'|'mp3'|'m4a'|null}
+ */
+export const detectAudioFormat = (buffer, { filename, mimeType } = {}) => {
+  const head = buffer.subarray(0, 12).toString('latin1')
+  if (head.startsWith('RIFF') && head.slice(8, 12) === 'WAVE') return 'wav'
+  if (head.slice(4, 8) === 'ftyp') return 'm4a'
+  // ID3 tag or a bare MPEG audio frame header (11 sync bits)
+  if (head.startsWith('ID3') || (buffer[0] === 0xff && (buffer[1] & 0xe0) === 0xe0)) return 'mp3'
+  const byExtension = FORMATS_BY_EXTENSION[path.extname(String(filename || '')).toLowerCase()]
+  if (byExtension) return byExtension
+  return FORMATS_BY_MIME[String(mimeType || '').split(';')[0].trim().toLowerCase()] || null
+}
+
+/**
+ * Transcribe an audio file
+ * @param {Object} params
+ * @param {string} params.provider
+ * @param {{ buffer: Buffer, filename?: string, mimeType?: string }} params.audio
+ * @returns {Promise<{ provider, model, text, language, duration, segments }>}
+ * @throws {QurioError} invalid_request for empty, oversized, or unsupported audio
+ */
+export const transcribeAudio = async ({ provider, audio, ...options }) => {
+  const transcriber = getTranscriptionProvider(provider)
+  const { buffer } = audio
+  if (!buffer?.length) throw new QurioError(ErrorCode.InvalidRequest, 'Audio is empty')
+  const maxBytes = getMaxAudioBytes()
+  if (buffer.length > maxBytes) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Audio is over ${maxBytes / MB} MB`, {
+      status: 413,
+    })
+  }
+  const format = detectAudioFormat(buffer, audio)
+  if (!format) {
+    throw new QurioError(
+      ErrorCode.InvalidRequest,
+      `Unsupported audio type. Supported: ${AUDIO_FORMATS.join(', ')}`,
+      { status: 415 },
+    )
+  }
+  // Providers pick the decoder from the file name, so it must carry the right extension
+  const filename = String(audio.filename || 'audio')
+  const basename = path.basename(filename, path.extname(filename))
+  return transcriber.transcribe(
+    { buffer, filename: `${basename || 'audio'}.${format}`, mimeType: AUDIO_MIME_TYPES[format] },
+    options,
+  )
+}
@@ -0,0 +1,152 @@
+/**
+ * Background service mode
+ * Keeps periodic jobs (scheduled research, monitoring, sync) running while no window is attached.
+ */
+
+const DEFAULT_TICK_MS = 60000
+
+const parseBoolean = value => value === '1' || value === 'true'
+
+export const getBackgroundConfig = () => {
+  const tickMs = Number.parseInt(process.env.BACKGROUND_TICK_MS, 10)
+  return {
+    enabled: parseBoolean(process.env.BACKGROUND_MODE),
+    tickMs: Number.isFinite(tickMs) && tickMs > 0 ? tickMs : DEFAULT_TICK_MS,
+  }
+}
+
+/**
+ * Background Job Manager
+ * Jobs are registered once at import time by their owning services and only run while started.
+ */
+class BackgroundJobManager {
+  constructor() {
+    // Registered jobs by name: { name, description, intervalMs, handler }
+    this.jobs = new Map()
+
+    // Runtime state by job name: { timer, running, lastRunAt, lastDurationMs, lastError, runs }
+    this.state = new Map()
+
+    this.started = false
+    this.startedAt = null
+  }
+
+  /**
+   * @param {Object} job
+   * @param {number} [job.intervalMs] - Run interval (default BACKGROUND_TICK_MS, 60000)
+   */
+  registerJob({ name, description = '', intervalMs, handler }) {
+    if (!name || typeof handler !== 'function') {
+      throw new Error('Background job requires a name and a handler')
+    }
+    const interval =
+      Number.isFinite(intervalMs) && intervalMs > 0 ? intervalMs : getBackgroundConfig().tickMs
+    this.jobs.set(name, { name, description, intervalMs: interval, handler })
+    this.state.set(name, {
+      timer: null,
+      running: false,
+      lastRunAt: null,
+      lastDurationMs: null,
+      lastError: null,
+      runs: 0,
+    })
+
+    // Late registrations join an already running scheduler
+    if (this.started) this.scheduleJob(name)
+  }
+
+  unregisterJob(name) {
+    this.clearJobTimer(name)
+    this.jobs.delete(name)
+    this.state.delete(name)
+  }
+
+  async runJob(name) {
+    const job = this.jobs.get(name)
+    const state = this.state.get(name)
+    if (!job || !state) throw new Error(`Unknown background job: ${name}`)
+
+    // Skip overlapping runs of slow jobs
+    if (state.running) return false
+
+    state.running = true
+    const startedAt = Date.now()
+    try {
+      await job.handler()
+      state.lastError = null
+    } catch (error) {
+      console.error(`[Background] Job ${name} failed:`, error.message)
+      state.lastError = error.message
+    } finally {
+      state.running = false
+      state.runs += 1
+      state.lastRunAt = new Date(startedAt).toISOString()
+      state.lastDurationMs = Date.now() - startedAt
+    }
+    return true
+  }
+
+  scheduleJob(name) {
+    const job = this.jobs.get(name)
+    const state = this.state.get(name)
+    if (!job || !state || state.timer) return
+    state.timer = setInterval(() => {
+      this.runJob(name).catch(() => {})
+    }, job.intervalMs)
+    // Do not keep the process alive just for the timers; the HTTP server does that
+    state.timer.unref?.()
+  }
+
+  clearJobTimer(name) {
+    const state = this.state.get(name)
+    if (state?.timer) {
+      clearInterval(state.timer)
+      state.timer = null
+    }
+  }
+
+  start() {
+    if (this.started) return this.getStatus()
+    this.started = true
+    this.startedAt = new Date().toISOString()
+    for (const name of this.jobs.keys()) {
+      this.scheduleJob(name)
+    }
+    console.log(`[Background] Started with ${this.jobs.size} job(s)`)
+    return this.getStatus()
+  }
+
+  stop() {
+    if (!this.started) return this.getStatus()
+    for (const name of this.jobs.keys()) {
+      this.clearJobTimer(name)
+    }
+    this.started = false
+    this.startedAt = null
+    console.log('[Background] Stopped')
+    return this.getStatus()
+  }
+
+  getStatus() {
+    return {
+      running: this.started,
+      startedAt: this.startedAt,
+      pid: process.pid,
+      jobs: Array.from(this.jobs.values()).map(job => {
+        const state = this.state.get(job.name) || {}
+        return {
+          name: job.name,
+          description: job.description,
+          intervalMs: job.intervalMs,
+          running: Boolean(state.running),
+          runs: state.runs || 0,
+          lastRunAt: state.lastRunAt || null,
+          lastDurationMs: state.lastDurationMs ?? null,
+          lastError: state.lastError || null,
+        }
+      }),
+    }
+  }
+}
+
+export const backgroundJobManager = new BackgroundJobManager()
@@ -0,0 +1,232 @@
+/**
+ * Citation check service
+ * Verification pass for research reports (on by default for academic research): every [n]
+ * citation is resolved against the numbered source list, and the sentence it supports is compared
+ * with that source's text by keyword overlap. The text is what research collected for the URL,
+ * or, with refetch, the cited page read again. Citations of sources that do not exist fail
+ * outright; claims whose keywords mostly do not appear in the source fail on overlap.
+ */
+
+import { CITATION_PATTERN, splitPassages } from './reportSimilarityService.js'
+
+const DEFAULT_THRESHOLD = 0.3
+const MAX_CLAIM_CHARS = 300
+const MAX_RANGE = 20
+const MAX_FETCHES = 20
+const FETCH_CONCURRENCY = 4
+
+// Letter/digit runs without Han characters, or Han runs (split into character pairs below)
+const KEYWORD_PATTERN = /\p{Script=Han}+|(?:(?!\p{Script=Han})[\p{L}\p{N}])+/gu
+const STOPWORDS = new Set(
+  (
+    'the and for are but not you all any can had her was one our out has his how its may new ' +
+    'now old see two way who did get let put say she too use also been from have here into ' +
+    'just more most much must only over some such than that them then they this very were what ' +
+    'when which while will with would about after again being could does each other their ' +
+    'there these those through under where whose should because between during however within'
+  ).split(' '),
+)
+
+const REASON_LABELS = {
+  missing_source: 'no such source',
+  no_source_text: 'no source text to compare',
+  no_keywords: 'no keywords to compare',
+}
+
+export const resolveCitationThreshold = value => {
+  const raw = value ?? process.env.CITATION_OVERLAP_THRESHOLD
+  const threshold = Number(raw)
+  return raw !== undefined && raw !== '' && threshold > 0 && threshold <= 1
+    ? threshold
+    : DEFAULT_THRESHOLD
+}
+
+/**
+ * Keywords of a text: words of three or more letters that are not stopwords, numbers, and
+ * character pairs of Han text
+ * @returns {Set<string>}
+ */
+export const extractKeywords = text => {
+  const keywords = new Set()
+  const normalized = String(text || '')
+    .replace(CITATION_PATTERN, ' ')
+    .toLowerCase()
+  for (const [token] of normalized.matchAll(KEYWORD_PATTERN)) {
+    if (/\p{Script=Han}/u.test(token)) {
+      if (token.length === 1) keywords.add(token)
+      for (let index = 0; index + 2 <= token.length; index += 1) {
+        keywords.add(token.slice(index, index + 2))
+      }
+    } else if (/^\p{N}+$/u.test(token) || (token.length >= 3 && !STOPWORDS.has(token))) {
+      keywords.add(token)
+    }
+  }
+  return keywords
+}
+
+// "[2, 3]" -> [2, 3]; "[4-6]" -> [4, 5, 6] (ranges past MAX_RANGE keep their end points)
+const readCitationIndexes = marker => {
+  const indexes = []
+  for (const part of marker.slice(1, -1).split(',')) {
+    const [start, end = start] = part.split(/[-–]/).map(value => Number.parseInt(value, 10))
+    if (end < start || end - start > MAX_RANGE) {
+      indexes.push(start, end)
+      continue
+    }
+    for (let index = start; index <= end; index += 1) indexes.push(index)
+  }
+  return indexes
+}
+
+const toClaim = text => {
+  const claim = text
+    .replace(CITATION_PATTERN, '')
+    .replace(/^(?:[-*+]|\d+\.)\s+/, '')
+    .replace(/\s+/g, ' ')
+    .replace(/\s+([.,;:!?。，；：！？])/g, '$1')
+    .trim()
+  return claim.length > MAX_CLAIM_CHARS ? `${claim.slice(0, MAX_CLAIM_CHARS)}…` : claim
+}
+
+/**
+ * Every cited (sentence, source number) pair of a report, in report order
+ * Citations in headings, tables, code, quotes and reference lists are not claims and are skipped.
+ * @param {string} report
+ * @returns {Array<{ index: number, claim: string }>}
+ */
+export const extractCitations = report => {
+  const citations = []
+  for (const passage of splitPassages(String(report || ''))) {
+    const indexes = new Set()
+    for (const [marker] of passage.text.matchAll(CITATION_PATTERN)) {
+      readCitationIndexes(marker).forEach(index => indexes.add(index))
+    }
+    if (!indexes.size) continue
+    const claim = toClaim(passage.text)
+    for (const index of indexes) citations.push({ index, claim })
+  }
+  return citations
+}
+
+// Read pages a few at a time; a page that fails is left to the collected text
+const fetchPages = async (urls, fetchPage, signal) => {
+  const pages = new Map()
+  let next = 0
+  const worker = async () => {
+    while (next < urls.length && !signal?.aborted) {
+      const url = urls[next]
+      next += 1
+      try {
+        const text = await fetchPage(url)
+        if (text) pages.set(url, String(text))
+      } catch (error) {
+        if (signal?.aborted) throw error
+        console.warn('[CitationCheck] Re-fetch failed:', url, error.message)
+      }
+    }
+  }
+  await Promise.all(Array.from({ length: Math.min(FETCH_CONCURRENCY, urls.length) }, worker))
+  return pages
+}
+
+/**
+ * Verify a report's citations against its numbered sources
+ * @param {Object} params
+ * @param {string} params.report
+ * @param {Array<{ url?, uri?, title?, snippet? }>} params.sources - [1] is sources[0]
+ * @param {Map<string, string>} [params.sourceTexts] - url -> text collected during research
+ * @param {number} [params.threshold] - Share of a claim's keywords the source must contain
+ *   (default CITATION_OVERLAP_THRESHOLD or 0.3)
+ * @param {(url: string) => Promise<string>} [params.fetchPage] - Re-fetch cited pages (the first
+ *   MAX_FETCHES URLs) and compare against them instead of the collected text
+ * @param {AbortSignal} [params.signal]
+ * @returns {Promise<{ threshold, refetched, citations, passed, failed, unverified }>}
+ *   citations: [{ index, claim, status: 'pass'|'fail'|'unverified', reason, overlap,
+ *   source: { url, title }|null, evidence: 'page'|'collected'|null }]
+ */
+export const checkCitations = async ({
+  report,
+  sources = [],
+  sourceTexts,
+  threshold,
+  fetchPage,
+  signal,
+}) => {
+  const limit = resolveCitationThreshold(threshold)
+  const citations = extractCitations(report)
+  const urlOf = index => sources[index - 1]?.url || sources[index - 1]?.uri || ''
+
+  const citedUrls = [...new Set(citations.map(citation => urlOf(citation.index)).filter(Boolean))]
+  const pages = fetchPage
+    ? await fetchPages(citedUrls.slice(0, MAX_FETCHES), fetchPage, signal)
+    : new Map()
+
+  const keywordsByIndex = new Map()
+  const readSource = index => {
+    if (!keywordsByIndex.has(index)) {
+      const source = sources[index - 1]
+      const url = urlOf(index)
+      const page = pages.get(url)
+      const collected = sourceTexts?.get(url) || source?.snippet
+      const text = page || collected
+      keywordsByIndex.set(index, {
+        keywords: text ? extractKeywords(`${source?.title || ''}\n${text}`) : null,
+        evidence: page ? 'page' : collected ? 'collected' : null,
+      })
+    }
+    return keywordsByIndex.get(index)
+  }
+
+  const results = citations.map(({ index, claim }) => {
+    const source = sources[index - 1]
+    const result = (status, reason, overlap = null, evidence = null) => ({
+      index,
+      claim,
+      status,
+      reason,
+      overlap,
+      source: source ? { url: urlOf(index) || null, title: source.title || null } : null,
+      evidence,
+    })
+    if (!source) return result('fail', 'missing_source')
+    const { keywords, evidence } = readSource(index)
+    if (!keywords) return result('unverified', 'no_source_text')
+    const claimKeywords = extractKeywords(claim)
+    if (!claimKeywords.size) return result('unverified', 'no_keywords', null, evidence)
+    let found = 0
+    for (const keyword of claimKeywords) if (keywords.has(keyword)) found += 1
+    const overlap = Math.round((found / claimKeywords.size) * 100) / 100
+    return overlap >= limit
+      ? result('pass', null, overlap, evidence)
+      : result('fail', 'low_overlap', overlap, evidence)
+  })
+
+  const count = status => results.filter(citation => citation.status === status).length
+  return {
+    threshold: limit,
+    refetched: pages.size,
+    citations: results,
+    passed: count('pass'),
+    failed: count('fail'),
+    unverified: count('unverified'),
+  }
+}
+
+/**
+ * The "Citation check" section appended to the report ('' when it cites nothing)
+ */
+export const formatCitationCheckMarkdown = check => {
+  if (!check?.citations?.length) return ''
+  const lines = check.citations.map(citation => {
+    const detail =
+      citation.overlap === null
+        ? REASON_LABELS[citation.reason]
+        : `${Math.round(citation.overlap * 100)}% keyword overlap`
+    return `- **${citation.status}** [${citation.index}] (${detail}): ${citation.claim}`
+  })
+  const summary =
+    `${check.passed} of ${check.citations.length} citations passed; ` +
+    `a claim passes when at least ${Math.round(check.threshold * 100)}% of its keywords ` +
+    'appear in the cited source.'
+  return `\n\n## Citation check\n\n${summary}\n\n${lines.join('\n')}\n`
+}
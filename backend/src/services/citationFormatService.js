@@ -0,0 +1,475 @@
+/**
+ * Citation formatting
+ * Turns a report's collected sources into a formatted References section (citation_style on
+ * POST /api/stream-deep-research). Sources are first converted to CSL-JSON items; a source with a
+ * DOI (from academic search, a doi.org link, a publisher /doi/ URL, or an arXiv link) is completed
+ * from Crossref, and everything else is cited as a web page. Entries keep the report's numbering,
+ * so [n] in the text is the n-th reference in every style.
+ *
+ * CROSSREF_MAILTO (optional) identifies the lookups for Crossref's "polite" pool.
+ */
+
+import { arxivDoi, normalizeDoi } from './academicSearchService.js'
+
+export const CITATION_STYLES = ['apa', 'ieee', 'bibtex']
+
+const CROSSREF_API = 'https://api.crossref.org/works/'
+const REQUEST_TIMEOUT_MS = 10000
+const MAX_CROSSREF_LOOKUPS = 60
+const CROSSREF_CONCURRENCY = 4
+const APA_MAX_AUTHORS = 20
+const IEEE_MAX_AUTHORS = 6
+
+// Headings of a reference list a model wrote itself (replaced by the formatted one)
+export const REFERENCES_HEADING =
+  /^#{1,6}[ \t]*(?:\d+\.?[ \t]*)?(?:references|sources|bibliography|参考文献|参考资料|来源)[ \t]*$/im
+
+// Crossref work types that differ from their CSL name
+const CROSSREF_TYPES = {
+  'journal-article': 'article-journal',
+  'proceedings-article': 'paper-conference',
+  'book-chapter': 'chapter',
+  'posted-content': 'article',
+  dissertation: 'thesis',
+  'reference-entry': 'entry',
+}
+
+const BIBTEX_TYPES = {
+  'article-journal': 'article',
+  'paper-conference': 'inproceedings',
+  chapter: 'incollection',
+  book: 'book',
+  thesis: 'phdthesis',
+  report: 'techreport',
+}
+
+const IEEE_MONTHS = [
+  'Jan.',
+  'Feb.',
+  'Mar.',
+  'Apr.',
+  'May',
+  'Jun.',
+  'Jul.',
+  'Aug.',
+  'Sep.',
+  'Oct.',
+  'Nov.',
+  'Dec.',
+]
+
+// Publisher pages such as https://www.tandfonline.com/doi/full/10.1080/...
+const PUBLISHER_DOI_PATH = /\/doi\/(?:abs\/|full\/|pdf\/|epdf\/|epub\/)?(10\.\d{4,9}\/[^?#]+)/i
+const ARXIV_URL = /arxiv\.org\/(?:abs|pdf)\/([^?#]+?)(?:v\d+)?(?:\.pdf)?$/i
+
+const toDateParts = date => [[date.getUTCFullYear(), date.getUTCMonth() + 1, date.getUTCDate()]]
+
+const hostnameOf = url => {
+  try {
+    return new URL(url).hostname.replace(/^www\./, '')
+  } catch {
+    return ''
+  }
+}
+
+/**
+ * DOI of a source: its own doi field, else one found in its URL
+ * @returns {string|null}
+ */
+export const findSourceDoi = source => {
+  const url = String(source?.url || source?.uri || '')
+  const doi = normalizeDoi(source?.doi) || (/doi\.org\//i.test(url) ? normalizeDoi(url) : null)
+  if (doi) return doi
+  const publisherDoi = url.match(PUBLISHER_DOI_PATH)?.[1]
+  if (publisherDoi) return normalizeDoi(publisherDoi)
+  const arxivId = url.match(ARXIV_URL)?.[1]
+  return arxivId ? arxivDoi(arxivId) : null
+}
+
+const GROUP_AUTHOR = /\b(?:group|consortium|committee|collaboration|organi[sz]ation|association)\b/i
+
+/**
+ * CSL name of an author string: "Ada Lovelace", "Lovelace, Ada", PubMed's "Lovelace A", or a
+ * group author kept as a literal
+ */
+export const parseAuthorName = value => {
+  const name = String(value || '')
+    .replace(/\s+/g, ' ')
+    .trim()
+  if (!name) return null
+  if (GROUP_AUTHOR.test(name) || name.split(' ').length > 4) return { literal: name }
+  if (name.includes(',')) {
+    const [family, ...given] = name.split(',')
+    return { family: family.trim(), given: given.join(',').trim() }
+  }
+  const parts = name.split(' ')
+  if (parts.length === 1) return { literal: name }
+  const last = parts[parts.length - 1]
+  // PubMed writes initials after the family name, without dots
+  if (/^[A-Z]{1,3}$/.test(last)) {
+    return { family: parts.slice(0, -1).join(' '), given: last.split('').join(' ') }
+  }
+  return { family: last, given: parts.slice(0, -1).join(' ') }
+}
+
+/**
+ * CSL-JSON item of a collected source
+ * @param {Object} source - { title, url, doi?, authors?, year?, venue? }
+ * @param {number} index - 0-based position in the report's source list
+ * @param {Date} [accessed]
+ */
+export const toCslItem = (source, index, accessed = new Date()) => {
+  const url = source?.url || source?.uri || ''
+  const doi = findSourceDoi(source)
+  const venue = source?.venue || null
+  const isPreprint = Boolean(doi?.startsWith('10.48550/') || /^arxiv$/i.test(venue || ''))
+  const year = Number.parseInt(source?.year, 10)
+  return {
+    id: `ref${index + 1}`,
+    type: !doi ? 'webpage' : isPreprint ? 'article' : 'article-journal',
+    title: String(source?.title || url || 'Untitled source').trim(),
+    author: (Array.isArray(source?.authors) ? source.authors : [])
+      .map(parseAuthorName)
+      .filter(Boolean),
+    ...(year ? { issued: { 'date-parts': [[year]] } } : {}),
+    'container-title': venue || (doi ? null : hostnameOf(url) || null),
+    ...(doi ? { DOI: doi } : {}),
+    ...(url ? { URL: url } : {}),
+    ...(doi ? {} : { accessed: { 'date-parts': toDateParts(accessed) } }),
+  }
+}
+
+const fromCrossref = work => {
+  const firstOf = value => (Array.isArray(value) ? value[0] : value) || null
+  const authors = (work.author || [])
+    .map(author =>
+      author.family
+        ? { family: author.family, ...(author.given ? { given: author.given } : {}) }
+        : author.name
+          ? { literal: author.name }
+          : null,
+    )
+    .filter(Boolean)
+  const issued = work.issued?.['date-parts']?.[0]?.[0] ? work.issued : work.published
+  return {
+    type: CROSSREF_TYPES[work.type] || work.type,
+    title: firstOf(work.title),
+    ...(authors.length ? { author: authors } : {}),
+    ...(issued?.['date-parts']?.[0]?.[0] ? { issued: { 'date-parts': issued['date-parts'] } } : {}),
+    'container-title': firstOf(work['container-title']),
+    volume: work.volume,
+    issue: work.issue,
+    page: work.page,
+    publisher: work.publisher,
+  }
+}
+
+// Crossref metadata by DOI (null when Crossref does not know the DOI, e.g. arXiv's DataCite DOIs)
+const crossrefCache = new Map()
+const MAX_CACHED_WORKS = 500
+
+const cacheWork = (doi, work) => {
+  crossrefCache.set(doi, work)
+  if (crossrefCache.size > MAX_CACHED_WORKS) {
+    crossrefCache.delete(crossrefCache.keys().next().value)
+  }
+}
+
+const lookupCrossref = async (doi, signal) => {
+  if (crossrefCache.has(doi)) return crossrefCache.get(doi)
+  const mailto = process.env.CROSSREF_MAILTO
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(`${CROSSREF_API}${encodeURIComponent(doi)}`, {
+    headers: {
+      Accept: 'application/json',
+      'User-Agent': `QurioBot/1.0 (+https://github.com/havingautism/Qurio${
+        mailto ? `; mailto:${mailto}` : ''
+      })`,
+    },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (response.status === 404) {
+    cacheWork(doi, null)
+    return null
+  }
+  if (!response.ok) throw new Error(`Crossref error: HTTP ${response.status}`)
+  const work = fromCrossref((await response.json())?.message || {})
+  cacheWork(doi, work)
+  return work
+}
+
+const withoutEmpty = item =>
+  Object.fromEntries(
+    Object.entries(item).filter(
+      ([, value]) => value !== null && value !== undefined && value !== '',
+    ),
+  )
+
+/**
+ * CSL-JSON items of a report's sources, completed from Crossref where they have a DOI
+ * A failed lookup keeps the source's own metadata.
+ * @param {Array<Object>} sources - In citation order
+ * @returns {Promise<Array<Object>>}
+ */
+export const resolveCitationItems = async (sources, { signal, accessed = new Date() } = {}) => {
+  const items = (Array.isArray(sources) ? sources : []).map((source, index) =>
+    toCslItem(source, index, accessed),
+  )
+  const pending = items.filter(item => item.DOI).slice(0, MAX_CROSSREF_LOOKUPS)
+  const worker = async () => {
+    while (pending.length && !signal?.aborted) {
+      const item = pending.shift()
+      try {
+        const work = await lookupCrossref(item.DOI, signal)
+        if (!work) continue
+        Object.assign(item, withoutEmpty(work))
+      } catch (error) {
+        if (signal?.aborted) throw error
+        console.warn('[Citations] Crossref lookup failed:', item.DOI, error.message)
+      }
+    }
+  }
+  await Promise.all(Array.from({ length: CROSSREF_CONCURRENCY }, worker))
+  return items.map(withoutEmpty)
+}
+
+const yearOf = item => item.issued?.['date-parts']?.[0]?.[0] || null
+
+const escapeMarkdown = text => String(text).replace(/([\\`*_[\]<>])/g, '\\$1')
+
+// End a sentence with a period unless it already ends with punctuation
+const sentence = text => (/[.?!]$/.test(text) ? text : `${text}.`)
+
+const initials = given =>
+  String(given || '')
+    .split(/\s+/)
+    .filter(Boolean)
+    .map(part =>
+      part
+        .split('-')
+        .map(piece => `${piece.replace(/\.$/, '')[0]}.`)
+        .join('-'),
+    )
+    .join(' ')
+
+const pageRange = page => String(page || '').replace(/\s*[-‐–]+\s*/, '–')
+
+const apaName = author =>
+  author.literal || (author.given ? `${author.family}, ${initials(author.given)}` : author.family)
+
+const apaAuthors = authors => {
+  const names = authors.map(apaName)
+  if (names.length <= 1) return names[0] || ''
+  if (names.length > APA_MAX_AUTHORS) {
+    return `${names.slice(0, APA_MAX_AUTHORS - 1).join(', ')}, . . . ${names[names.length - 1]}`
+  }
+  return `${names.slice(0, -1).join(', ')}, & ${names[names.length - 1]}`
+}
+
+const doiUrl = item => (item.DOI ? `https://doi.org/${item.DOI}` : item.URL || '')
+
+/**
+ * APA 7th edition reference
+ */
+export const formatApa = item => {
+  const authors = item.author?.length ? escapeMarkdown(apaAuthors(item.author)) : ''
+  const date = `(${yearOf(item) || 'n.d.'}).`
+  const title = escapeMarkdown(item.title)
+  const container = item['container-title'] ? escapeMarkdown(item['container-title']) : ''
+  const link = doiUrl(item) ? ` <${doiUrl(item)}>` : ''
+
+  if (item.type === 'webpage') {
+    const head = authors ? `${sentence(authors)} ${date} *${sentence(title)}*` : ''
+    const body = head || `*${sentence(title)}* ${date}`
+    const site = container && container !== authors ? ` ${sentence(container)}` : ''
+    return `${body}${site}${link}`
+  }
+
+  let source = ''
+  if (container) {
+    const volume = item.volume ? `, *${escapeMarkdown(item.volume)}*` : ''
+    const issue = item.issue ? `(${escapeMarkdown(item.issue)})` : ''
+    const pages = item.page ? `, ${escapeMarkdown(pageRange(item.page))}` : ''
+    source = ` *${container}*${volume}${issue}${pages}.`
+  } else if (item.publisher) {
+    source = ` ${sentence(escapeMarkdown(item.publisher))}`
+  }
+  const head = authors ? `${sentence(authors)} ${date} ${sentence(title)}` : ''
+  return `${head || `${sentence(title)} ${date}`}${source}${link}`
+}
+
+const ieeeName = author =>
+  author.literal || [initials(author.given), author.family].filter(Boolean).join(' ')
+
+const ieeeAuthors = authors => {
+  const names = authors.map(ieeeName)
+  if (names.length > IEEE_MAX_AUTHORS) return `${names[0]} et al.`
+  if (names.length <= 2) return names.join(' and ')
+  return `${names.slice(0, -1).join(', ')}, and ${names[names.length - 1]}`
+}
+
+const ieeeDate = parts =>
+  parts ? `${IEEE_MONTHS[parts[1] - 1]} ${parts[2]}, ${parts[0]}` : ''
+
+/**
+ * IEEE reference (without its [n] label)
+ */
+export const formatIeee = item => {
+  const authors = item.author?.length ? escapeMarkdown(ieeeAuthors(item.author)) : ''
+  const title = `"${escapeMarkdown(item.title).replace(/[.,]$/, '')},"`
+  const container = item['container-title'] ? escapeMarkdown(item['container-title']) : ''
+  const lead = authors ? `${authors}, ${title}` : title
+
+  if (item.type === 'webpage') {
+    const accessed = ieeeDate(item.accessed?.['date-parts']?.[0])
+    const site = container ? ` ${container}.` : ''
+    const when = accessed ? ` Accessed: ${accessed}.` : ''
+    return `${lead}${site}${when} [Online]. Available: <${item.URL}>`
+  }
+
+  const details = [
+    container && `*${container}*`,
+    item.volume && `vol. ${escapeMarkdown(item.volume)}`,
+    item.issue && `no. ${escapeMarkdown(item.issue)}`,
+    item.page &&
+      `${/[-–]/.test(item.page) ? 'pp.' : 'p.'} ${escapeMarkdown(pageRange(item.page))}`,
+    yearOf(item),
+  ].filter(Boolean)
+  const doi = item.DOI ? `, doi: ${escapeMarkdown(item.DOI)}` : ''
+  const online = !item.DOI && item.URL ? ` [Online]. Available: <${item.URL}>` : ''
+  return `${lead} ${details.join(', ')}${doi}.${online}`.replace(/\s+\./g, '.')
+}
+
+const BIBTEX_SPECIAL = { '\\': '\\textbackslash{}', '~': '\\textasciitilde{}', '^': '\\^{}' }
+
+const escapeBibtex = text =>
+  String(text).replace(/[\\&%$#_{}~^]/g, char => BIBTEX_SPECIAL[char] || `\\${char}`)
+
+const bibtexName = author =>
+  author.literal
+    ? `{${escapeBibtex(author.literal)}}`
+    : escapeBibtex(author.given ? `${author.family}, ${author.given}` : author.family)
+
+const keyWord = text =>
+  String(text || '')
+    .normalize('NFKD')
+    .replace(/[^\w\s]/g, '')
+    .toLowerCase()
+    .split(/[\s_]+/)
+    .find(word => word.length > 3) || ''
+
+/**
+ * BibTeX entry
+ * @param {Object} item - CSL-JSON item
+ * @param {string} key - Citation key
+ */
+export const formatBibtex = (item, key) => {
+  const type = item.type === 'article' && item.DOI?.startsWith('10.48550/') ? 'misc' : null
+  const entryType = type || BIBTEX_TYPES[item.type] || 'misc'
+  const container = item['container-title']
+  const arxivId = item.DOI?.match(/^10\.48550\/arxiv\.(.+)$/)?.[1]
+  const fields = [
+    ['author', item.author?.length ? item.author.map(bibtexName).join(' and ') : null],
+    ['title', `{${escapeBibtex(item.title)}}`],
+    [
+      entryType === 'article'
+        ? 'journal'
+        : ['inproceedings', 'incollection'].includes(entryType)
+          ? 'booktitle'
+          : null,
+      container ? escapeBibtex(container) : null,
+    ],
+    ['year', yearOf(item)],
+    ['volume', item.volume ? escapeBibtex(item.volume) : null],
+    ['number', item.issue ? escapeBibtex(item.issue) : null],
+    ['pages', item.page ? escapeBibtex(String(item.page).replace(/\s*[-‐–]+\s*/, '--')) : null],
+    [
+      entryType === 'techreport' ? 'institution' : 'publisher',
+      ['book', 'techreport', 'incollection'].includes(entryType) && item.publisher
+        ? escapeBibtex(item.publisher)
+        : null,
+    ],
+    ['eprint', arxivId || null],
+    ['archiveprefix', arxivId ? 'arXiv' : null],
+    ['doi', item.DOI || null],
+    ['url', item.URL || null],
+    [
+      'howpublished',
+      entryType === 'misc' && !arxivId && container ? escapeBibtex(container) : null,
+    ],
+    [
+      'note',
+      item.accessed
+        ? `Accessed: ${item.accessed['date-parts'][0]
+            .map(part => String(part).padStart(2, '0'))
+            .join('-')}`
+        : null,
+    ],
+  ].filter(([name, value]) => name && value !== null && value !== undefined && value !== '')
+  const body = fields.map(([name, value]) => `  ${name} = {${value}},`).join('\n')
+  return `@${entryType}{${key},\n${body}\n}`
+}
+
+// Keys such as lovelace2024attention; repeats get a, b, c, ...
+const buildBibtexKeys = items => {
+  const used = new Map()
+  return items.map((item, index) => {
+    const first = item.author?.[0]
+    const name = keyWord(first?.family || first?.literal || item['container-title'])
+    const base = `${name}${yearOf(item) || ''}${keyWord(item.title)}` || `ref${index + 1}`
+    const count = used.get(base) || 0
+    used.set(base, count + 1)
+    return count ? `${base}${String.fromCharCode(96 + Math.min(count, 26))}` : base
+  })
+}
+
+/**
+ * Formatted reference entries in citation order
+ * @param {Array<Object>} items - CSL-JSON items from resolveCitationItems
+ * @param {string} style - One of CITATION_STYLES
+ * @returns {Array<{ index: number, text: string, csl: Object }>}
+ */
+export const formatReferences = (items, style) => {
+  const keys = style === 'bibtex' ? buildBibtexKeys(items) : []
+  return items.map((item, index) => ({
+    index: index + 1,
+    text:
+      style === 'bibtex'
+        ? formatBibtex(item, keys[index])
+        : style === 'ieee'
+          ? formatIeee(item)
+          : formatApa(item),
+    csl: item,
+  }))
+}
+
+/**
+ * Markdown body of the References section (without its heading)
+ */
+export const formatReferencesMarkdown = (references, style) => {
+  if (style === 'bibtex') {
+    const entries = references.map(({ index, text }) => `% [${index}]\n${text}`)
+    return `\`\`\`bibtex\n${entries.join('\n\n')}\n\`\`\``
+  }
+  if (style === 'ieee') {
+    return references.map(({ index, text }) => `[${index}] ${text}`).join('\n\n')
+  }
+  return references.map(({ index, text }) => `${index}. ${text}`).join('\n')
+}
+
+/**
+ * Replace the report's own reference list (or append one) with the formatted references
+ * The model's heading is kept, so a numbered "## 7. REFERENCES" stays in place.
+ * @returns {string} Report Markdown
+ */
+export const replaceReferencesSection = (report, referencesMarkdown) => {
+  const text = String(report || '')
+  const match = REFERENCES_HEADING.exec(text)
+  if (!match) return `${text.trimEnd()}\n\n## References\n\n${referencesMarkdown}\n`
+  const level = match[0].match(/^#+/)[0].length
+  const bodyStart = match.index + match[0].length
+  const next = text.slice(bodyStart).search(new RegExp(`^#{1,${level}}\\s`, 'm'))
+  const end = next === -1 ? text.length : bodyStart + next
+  const rest = text.slice(end)
+  return `${text.slice(0, bodyStart)}\n\n${referencesMarkdown}\n${rest ? `\n${rest}` : ''}`
+}
@@ -0,0 +1,173 @@
+/**
+ * Code block service
+ * Extracts fenced code blocks from assistant messages, infers filenames/languages, and writes
+ * them into a sandboxed directory.
+ */
+
+import fs from 'fs'
+import path from 'path'
+import { resolveSandboxedPath } from '../utils/pathSandbox.js'
+
+const FENCE_PATTERN = /^([ \t]*)(`{3,}|~{3,})([^\n`]*)\n([\s\S]*?)\n\1\2[ \t]*$/gm
+const FILE_PATH_PATTERN = /^[\w@.-]+(?:[/\\][\w@.-]+)*\.[\w-]+$|^(?:Dockerfile|Makefile|\.[\w.-]+)$/
+const MAX_FILE_BYTES = 1024 * 1024
+
+const LANGUAGE_EXTENSIONS = {
+  javascript: 'js',
+  js: 'js',
+  jsx: 'jsx',
+  typescript: 'ts',
+  ts: 'ts',
+  tsx: 'tsx',
+  python: 'py',
+  py: 'py',
+  rust: 'rs',
+  rs: 'rs',
+  go: 'go',
+  java: 'java',
+  kotlin: 'kt',
+  swift: 'swift',
+  c: 'c',
+  cpp: 'cpp',
+  'c++': 'cpp',
+  csharp: 'cs',
+  cs: 'cs',
+  ruby: 'rb',
+  php: 'php',
+  bash: 'sh',
+  sh: 'sh',
+  shell: 'sh',
+  zsh: 'sh',
+  powershell: 'ps1',
+  sql: 'sql',
+  html: 'html',
+  css: 'css',
+  scss: 'scss',
+  json: 'json',
+  yaml: 'yaml',
+  yml: 'yaml',
+  toml: 'toml',
+  xml: 'xml',
+  markdown: 'md',
+  md: 'md',
+  vue: 'vue',
+  svelte: 'svelte',
+  dockerfile: 'Dockerfile',
+  makefile: 'Makefile',
+}
+
+const EXTENSION_LANGUAGES = Object.fromEntries(
+  Object.entries(LANGUAGE_EXTENSIONS).map(([language, extension]) => [extension, language]),
+)
+
+const isFilePath = value => FILE_PATH_PATTERN.test(value) && !value.split(/[/\\]/).includes('..')
+
+// "// file: src/app.js", "# filename: main.py", "<!-- src/index.html -->"
+const FIRST_LINE_PATTERN =
+  /^\s*(?:\/\/|#|--|;|\/\*|<!--)\s*(?:file(?:name)?|path)?\s*:?\s*([^\s*>]+)\s*(?:\*\/|-->)?\s*$/i
+
+/**
+ * Filename from the fence info string: "ts title=src/a.ts", "python:app.py", "js src/a.js"
+ */
+const nameFromInfo = info => {
+  const title = info.match(/(?:title|file(?:name)?|path)=["']?([^"'\s]+)/i)
+  if (title) return title[1]
+  const colon = info.match(/^[\w+#-]+:(\S+)$/)
+  if (colon && isFilePath(colon[1])) return colon[1]
+  const words = info.split(/\s+/).slice(1)
+  return words.find(isFilePath) || null
+}
+
+// Markdown like "**src/app.js**", "`src/app.js`:", or "File: src/app.js" right above the fence
+const nameFromPrecedingLine = text => {
+  const line = text.trimEnd().split('\n').pop() || ''
+  const candidates = line.match(/[\w@./\\-]+\.[\w-]+|Dockerfile|Makefile/g) || []
+  const cleaned = line.replace(/[*_`:#>]/g, '').trim()
+  if (cleaned.length > 120) return null
+  return candidates.reverse().find(isFilePath) || null
+}
+
+const inferLanguage = (info, filename) => {
+  const language = info.split(/[\s:{]/)[0].toLowerCase()
+  if (language) return language
+  const extension = filename ? path.extname(filename).slice(1).toLowerCase() : ''
+  return EXTENSION_LANGUAGES[extension] || (filename ? path.basename(filename).toLowerCase() : '')
+}
+
+const defaultFilename = (language, index) => {
+  const extension = LANGUAGE_EXTENSIONS[language] || 'txt'
+  if (extension === 'Dockerfile' || extension === 'Makefile') return extension
+  return `snippet-${index + 1}.${extension}`
+}
+
+const dedupeFilename = (filename, used) => {
+  if (!used.has(filename)) return filename
+  const extension = path.extname(filename)
+  const stem = filename.slice(0, filename.length - extension.length)
+  let counter = 2
+  while (used.has(`${stem}-${counter}${extension}`)) counter += 1
+  return `${stem}-${counter}${extension}`
+}
+
+/**
+ * Extract fenced code blocks with inferred filenames and languages
+ * @returns {Array<{index: number, language: string, filename: string, inferred: boolean,
+ *   code: string}>} inferred is false when the filename came from the message itself
+ */
+export const extractCodeBlocks = content => {
+  const text = String(content || '')
+  const used = new Set()
+  const blocks = []
+  for (const match of text.matchAll(FENCE_PATTERN)) {
+    const info = match[3].trim()
+    let code = match[4].replace(new RegExp(`^${match[1]}`, 'gm'), '')
+    let filename = nameFromInfo(info) || nameFromPrecedingLine(text.slice(0, match.index))
+
+    if (!filename) {
+      const [firstLine, ...rest] = code.split('\n')
+      const fromComment = firstLine.match(FIRST_LINE_PATTERN)?.[1]
+      if (fromComment && isFilePath(fromComment)) {
+        filename = fromComment
+        code = rest.join('\n')
+      }
+    }
+
+    const explicit = Boolean(filename)
+    const language = inferLanguage(info, filename)
+    const candidate = filename || defaultFilename(language, blocks.length)
+    const resolvedName = dedupeFilename(candidate.replace(/\\/g, '/').replace(/^\.\//, ''), used)
+    used.add(resolvedName)
+    blocks.push({
+      index: blocks.length,
+      language,
+      filename: resolvedName,
+      inferred: !explicit,
+      code: code.endsWith('\n') ? code : `${code}\n`,
+    })
+  }
+  return blocks
+}
+
+/**
+ * Write blocks into a sandboxed directory
+ * @returns {Array<{filename: string, path: string, language: string, bytes: number,
+ *   status: 'written'|'overwritten'|'skipped_exists'|'error', error?: string}>}
+ */
+export const writeCodeBlocks = (blocks, directory, { overwrite = false } = {}) =>
+  blocks.map(block => {
+    const entry = { filename: block.filename, language: block.language, path: null, bytes: 0 }
+    try {
+      const target = resolveSandboxedPath(directory, block.filename)
+      const bytes = Buffer.byteLength(block.code)
+      if (bytes > MAX_FILE_BYTES) {
+        return { ...entry, path: target, status: 'error', error: 'File exceeds 1 MB limit' }
+      }
+      const exists = fs.existsSync(target)
+      if (exists && !overwrite) return { ...entry, path: target, status: 'skipped_exists' }
+      fs.mkdirSync(path.dirname(target), { recursive: true })
+      fs.writeFileSync(target, block.code)
+      return { ...entry, path: target, bytes, status: exists ? 'overwritten' : 'written' }
+    } catch (error) {
+      return { ...entry, status: 'error', error: error.message }
+    }
+  })
@@ -0,0 +1,226 @@
+/**
+ * Code index service
+ * Indexes sandboxed git repositories into symbol chunks (with optional embeddings) and serves
+ * ranked code search results with file/line references. Re-indexing only reprocesses files
+ * whose content changed.
+ */
+
+import crypto from 'crypto'
+import fs from 'fs'
+import path from 'path'
+import { readRecord, writeRecord } from '../utils/dataStore.js'
+import { resolveSandboxedPath } from '../utils/pathSandbox.js'
+import { detectLanguage, extractSymbols } from './codeSymbols.js'
+import { cosineSimilarity, embedTexts, resolveEmbeddingConfig } from './embeddingClient.js'
+import { listRepoFiles, resolveRepo } from './repoContextService.js'
+
+const COLLECTION = 'code-index'
+const MAX_FILE_BYTES = 256 * 1024
+const MAX_FILES = 5000
+const MAX_SNIPPET_CHARS = 1500
+const FILE_CHUNK_LINES = 60
+const DEFAULT_SEARCH_LIMIT = 10
+const SKIP_PATH_PATTERN =
+  /(?:^|\/)(?:node_modules|vendor|dist|build|target|\.git)\/|\.min\.js$|(?:^|\/)[^/]*lock[^/]*$/i
+
+const indexIdFor = repoDir => crypto.createHash('sha1').update(repoDir).digest('hex').slice(0, 16)
+
+const hashContent = content => crypto.createHash('sha1').update(content).digest('hex')
+
+const tokenize = text =>
+  String(text || '')
+    // split camelCase and snake_case so "streamChat" matches "stream chat"
+    .replace(/([a-z0-9])([A-Z])/g, '$1 $2')
+    .toLowerCase()
+    .match(/[a-z0-9]+/g) || []
+
+const chunkText = chunk => `${chunk.file}\n${chunk.signature || ''}\n${chunk.snippet}`
+
+/**
+ * Split one file into symbol chunks (or fixed-size chunks when no symbols are found)
+ */
+const chunkFile = (file, content, language) => {
+  const lines = content.split('\n')
+  const symbols = extractSymbols(content, language)
+  const toSnippet = (start, end) =>
+    lines
+      .slice(start - 1, end)
+      .join('\n')
+      .slice(0, MAX_SNIPPET_CHARS)
+
+  if (symbols.length) {
+    return symbols.map(symbol => ({
+      file,
+      language,
+      name: symbol.name,
+      kind: symbol.kind,
+      line: symbol.line,
+      endLine: symbol.endLine,
+      signature: symbol.signature,
+      snippet: toSnippet(symbol.line, symbol.endLine),
+    }))
+  }
+
+  const chunks = []
+  for (let start = 1; start <= lines.length; start += FILE_CHUNK_LINES) {
+    const end = Math.min(lines.length, start + FILE_CHUNK_LINES - 1)
+    const snippet = toSnippet(start, end)
+    if (!snippet.trim()) continue
+    chunks.push({
+      file,
+      language,
+      name: path.basename(file),
+      kind: 'file',
+      line: start,
+      endLine: end,
+      signature: '',
+      snippet,
+    })
+  }
+  return chunks
+}
+
+export const getCodeIndex = async repoPath => {
+  const repoDir = await resolveRepo(repoPath)
+  return readRecord(COLLECTION, indexIdFor(repoDir))
+}
+
+/**
+ * Build or refresh the index for a repository
+ * @returns {Promise<{repo: string, files: number, chunks: number, reindexedFiles: number,
+ *   embedded: boolean, indexedAt: string}>}
+ */
+export const indexRepository = async ({ repoPath, embedding, signal } = {}) => {
+  const repoDir = await resolveRepo(repoPath)
+  const id = indexIdFor(repoDir)
+  const previous = readRecord(COLLECTION, id)
+  const embeddingConfig = resolveEmbeddingConfig(embedding)
+  const embeddingModel = embeddingConfig?.model || null
+  const reuseVectors = previous?.embeddingModel === embeddingModel
+
+  const candidates = (await listRepoFiles(repoDir))
+    .filter(file => detectLanguage(file) && !SKIP_PATH_PATTERN.test(file))
+    .slice(0, MAX_FILES)
+
+  const files = {}
+  const chunks = []
+  const pending = []
+  let reindexedFiles = 0
+
+  for (const file of candidates) {
+    let content
+    try {
+      const target = resolveSandboxedPath(repoDir, file)
+      if (fs.statSync(target).size > MAX_FILE_BYTES) continue
+      content = fs.readFileSync(target, 'utf8')
+    } catch {
+      continue
+    }
+    const hash = hashContent(content)
+    files[file] = hash
+    const unchanged = previous?.files?.[file] === hash && reuseVectors
+    const fileChunks = unchanged
+      ? previous.chunks.filter(chunk => chunk.file === file)
+      : chunkFile(file, content, detectLanguage(file))
+    if (!unchanged) {
+      reindexedFiles += 1
+      if (embeddingConfig) pending.push(...fileChunks)
+    }
+    chunks.push(...fileChunks)
+  }
+
+  if (embeddingConfig && pending.length) {
+    const vectors = await embedTexts(pending.map(chunkText), embeddingConfig, { signal })
+    pending.forEach((chunk, index) => {
+      chunk.vector = vectors[index]
+    })
+  }
+
+  const record = {
+    id,
+    repo: repoDir,
+    embeddingModel,
+    indexedAt: new Date().toISOString(),
+    files,
+    chunks,
+  }
+  writeRecord(COLLECTION, id, record)
+  return {
+    repo: repoDir,
+    files: Object.keys(files).length,
+    chunks: chunks.length,
+    reindexedFiles,
+    embedded: Boolean(embeddingConfig),
+    indexedAt: record.indexedAt,
+  }
+}
+
+const lexicalScore = (chunk, terms) => {
+  if (!terms.length) return 0
+  const nameTokens = new Set(tokenize(chunk.name))
+  const pathTokens = new Set(tokenize(chunk.file))
+  const bodyTokens = tokenize(`${chunk.signature} ${chunk.snippet}`)
+  const bodyCounts = bodyTokens.reduce(
+    (map, token) => map.set(token, (map.get(token) || 0) + 1),
+    new Map(),
+  )
+  let score = 0
+  for (const term of terms) {
+    if (nameTokens.has(term)) score += 3
+    if (pathTokens.has(term)) score += 1
+    const count = bodyCounts.get(term) || 0
+    if (count) score += 1 + Math.log(count)
+  }
+  return score / (terms.length * 4)
+}
+
+/**
+ * Ranked code search over an indexed repository (indexes on first use)
+ * @returns {Promise<Array<{file, line, end_line, kind, name, signature, snippet, score}>>}
+ */
+export const searchCode = async ({
+  repoPath,
+  query,
+  limit = DEFAULT_SEARCH_LIMIT,
+  kind,
+  embedding,
+  signal,
+}) => {
+  let index = await getCodeIndex(repoPath)
+  if (!index) {
+    await indexRepository({ repoPath, embedding, signal })
+    index = await getCodeIndex(repoPath)
+  }
+
+  const terms = Array.from(new Set(tokenize(query)))
+  const embeddingConfig = resolveEmbeddingConfig(embedding)
+  let queryVector = null
+  if (embeddingConfig && index.embeddingModel === embeddingConfig.model) {
+    try {
+      ;[queryVector] = await embedTexts([query], embeddingConfig, { signal })
+    } catch (error) {
+      console.warn('[CodeIndex] Query embedding failed, using lexical ranking:', error.message)
+    }
+  }
+
+  return index.chunks
+    .filter(chunk => !kind || chunk.kind === kind)
+    .map(chunk => {
+      const lexical = lexicalScore(chunk, terms)
+      const semantic = queryVector && chunk.vector ? cosineSimilarity(queryVector, chunk.vector) : 0
+      return { chunk, score: queryVector ? 0.6 * semantic + 0.4 * lexical : lexical }
+    })
+    .filter(item => item.score > 0)
+    .sort((a, b) => b.score - a.score)
+    .slice(0, Math.max(1, Number(limit) || DEFAULT_SEARCH_LIMIT))
+    .map(({ chunk, score }) => ({
+      file: chunk.file,
+      line: chunk.line,
+      end_line: chunk.endLine,
+      kind: chunk.kind,
+      name: chunk.name,
+      signature: chunk.signature,
+      snippet: chunk.snippet,
+      score: Number(score.toFixed(4)),
+    }))
+}
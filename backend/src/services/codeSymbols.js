@@ -0,0 +1,174 @@
+/**
+ * Code symbol extraction
+ * Declaration patterns per language (functions, classes, types, methods) with line ranges.
+ * Pattern-based rather than a full parser so indexing needs no native grammars; each match
+ * yields { name, kind, line, endLine, signature }.
+ */
+
+import path from 'path'
+
+const MAX_SYMBOL_LINES = 80
+
+const CONTROL_WORDS = new Set([
+  'if',
+  'for',
+  'while',
+  'switch',
+  'catch',
+  'return',
+  'function',
+  'else',
+  'do',
+  'try',
+  'with',
+  'sizeof',
+])
+
+const JS_PATTERNS = [
+  [/^\s*(?:export\s+)?(?:default\s+)?(?:async\s+)?function\s*\*?\s*([A-Za-z_$][\w$]*)/, 'function'],
+  [/^\s*(?:export\s+)?(?:default\s+)?(?:abstract\s+)?class\s+([A-Za-z_$][\w$]*)/, 'class'],
+  [/^\s*(?:export\s+)?interface\s+([A-Za-z_$][\w$]*)/, 'interface'],
+  [/^\s*(?:export\s+)?type\s+([A-Za-z_$][\w$]*)\s*(?:<[^=]*>)?\s*=/, 'type'],
+  [/^\s*(?:export\s+)?(?:const\s+)?enum\s+([A-Za-z_$][\w$]*)/, 'enum'],
+  [
+    /^\s*(?:export\s+)?(?:const|let|var)\s+([A-Za-z_$][\w$]*)\s*=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*=>|\(\s*\{?\s*$|[A-Za-z_$][\w$]*\s*=>)/,
+    'function',
+  ],
+  [
+    /^\s+(?:(?:public|private|protected|static|async|readonly|get|set)\s+)*\*?([A-Za-z_$][\w$]*)\s*\([^)]*\)\s*(?::\s*[^{]+)?\{\s*$/,
+    'method',
+  ],
+]
+
+const LANGUAGE_PATTERNS = {
+  javascript: JS_PATTERNS,
+  typescript: JS_PATTERNS,
+  python: [
+    [/^\s*(?:async\s+)?def\s+([A-Za-z_]\w*)/, 'function'],
+    [/^\s*class\s+([A-Za-z_]\w*)/, 'class'],
+  ],
+  rust: [
+    [
+      /^\s*(?:pub(?:\([^)]*\))?\s+)?(?:const\s+)?(?:async\s+)?(?:unsafe\s+)?fn\s+([A-Za-z_]\w*)/,
+      'function',
+    ],
+    [/^\s*(?:pub(?:\([^)]*\))?\s+)?struct\s+([A-Za-z_]\w*)/, 'struct'],
+    [/^\s*(?:pub(?:\([^)]*\))?\s+)?enum\s+([A-Za-z_]\w*)/, 'enum'],
+    [/^\s*(?:pub(?:\([^)]*\))?\s+)?(?:unsafe\s+)?trait\s+([A-Za-z_]\w*)/, 'trait'],
+    [/^\s*(?:pub(?:\([^)]*\))?\s+)?type\s+([A-Za-z_]\w*)/, 'type'],
+    [/^\s*(?:pub(?:\([^)]*\))?\s+)?mod\s+([A-Za-z_]\w*)\s*\{/, 'module'],
+    [/^\s*impl(?:<[^>]*>)?\s+(?:[\w:<>, ]+\s+for\s+)?([A-Za-z_][\w:]*)/, 'impl'],
+    [/^\s*macro_rules!\s*([A-Za-z_]\w*)/, 'macro'],
+  ],
+  go: [
+    [/^func\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)/, 'function'],
+    [/^type\s+([A-Za-z_]\w*)\s+(?:struct|interface)/, 'type'],
+  ],
+  java: [
+    [/^\s*(?:[\w@]+\s+)*(?:class|interface|enum|record)\s+([A-Za-z_]\w*)/, 'class'],
+    [
+      /^\s+(?:(?:public|private|protected|static|final|abstract|synchronized|override|suspend|fun|async|virtual)\s+)*[\w<>[\],.? ]+\s+([A-Za-z_]\w*)\s*\([^;]*$/,
+      'method',
+    ],
+  ],
+  ruby: [
+    [/^\s*def\s+(?:self\.)?([A-Za-z_]\w*[?!]?)/, 'function'],
+    [/^\s*(?:class|module)\s+([A-Z]\w*)/, 'class'],
+  ],
+  php: [
+    [
+      /^\s*(?:(?:public|private|protected|static|abstract|final)\s+)*function\s+([A-Za-z_]\w*)/,
+      'function',
+    ],
+    [/^\s*(?:abstract\s+|final\s+)?(?:class|interface|trait)\s+([A-Za-z_]\w*)/, 'class'],
+  ],
+  c: [
+    [/^(?:struct|class|union|enum)\s+([A-Za-z_]\w*)\s*(?:[:{]|$)/, 'type'],
+    [/^[A-Za-z_][\w\s*&:<>,]*?[\s*&]([A-Za-z_][\w:~]*)\s*\([^;]*$/, 'function'],
+  ],
+}
+LANGUAGE_PATTERNS.kotlin = [
+  [/^\s*(?:[\w@]+\s+)*fun\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?([A-Za-z_]\w*)/, 'function'],
+  [/^\s*(?:[\w@]+\s+)*(?:class|interface|object)\s+([A-Za-z_]\w*)/, 'class'],
+]
+LANGUAGE_PATTERNS.csharp = LANGUAGE_PATTERNS.java
+LANGUAGE_PATTERNS.swift = [
+  [/^\s*(?:[\w@]+\s+)*func\s+([A-Za-z_]\w*)/, 'function'],
+  [/^\s*(?:[\w@]+\s+)*(?:class|struct|protocol|enum|extension)\s+([A-Za-z_]\w*)/, 'class'],
+]
+
+const EXTENSION_LANGUAGES = {
+  '.js': 'javascript',
+  '.mjs': 'javascript',
+  '.cjs': 'javascript',
+  '.jsx': 'javascript',
+  '.ts': 'typescript',
+  '.tsx': 'typescript',
+  '.mts': 'typescript',
+  '.vue': 'javascript',
+  '.svelte': 'javascript',
+  '.py': 'python',
+  '.rs': 'rust',
+  '.go': 'go',
+  '.java': 'java',
+  '.kt': 'kotlin',
+  '.kts': 'kotlin',
+  '.cs': 'csharp',
+  '.swift': 'swift',
+  '.rb': 'ruby',
+  '.php': 'php',
+  '.c': 'c',
+  '.h': 'c',
+  '.cc': 'c',
+  '.cpp': 'c',
+  '.hpp': 'c',
+}
+
+export const detectLanguage = filePath =>
+  EXTENSION_LANGUAGES[path.extname(filePath).toLowerCase()] || null
+
+const indentOf = line => line.match(/^\s*/)[0].length
+
+/**
+ * Extract symbols from source text
+ * @returns {Array<{name: string, kind: string, line: number, endLine: number,
+ *   signature: string}>} 1-based, inclusive line ranges
+ */
+export const extractSymbols = (source, language) => {
+  const patterns = LANGUAGE_PATTERNS[language]
+  if (!patterns) return []
+  const lines = String(source || '').split('\n')
+  const symbols = []
+  lines.forEach((line, index) => {
+    if (line.length > 400 || /^\s*(?:\/\/|#(?!\[)|\*|\/\*)/.test(line)) return
+    for (const [pattern, kind] of patterns) {
+      const name = line.match(pattern)?.[1]
+      if (!name || CONTROL_WORDS.has(name)) continue
+      symbols.push({
+        name,
+        kind,
+        line: index + 1,
+        indent: indentOf(line),
+        signature: line.trim().slice(0, 200),
+      })
+      break
+    }
+  })
+
+  // A symbol ends before the next symbol at the same or shallower indentation
+  return symbols.map((symbol, index) => {
+    const next = symbols
+      .slice(index + 1)
+      .find(candidate => candidate.indent <= symbol.indent)
+    const limit = next ? next.line - 1 : lines.length
+    let endLine = Math.min(limit, symbol.line + MAX_SYMBOL_LINES - 1)
+    while (endLine > symbol.line && !lines[endLine - 1].trim()) endLine -= 1
+    return {
+      name: symbol.name,
+      kind: symbol.kind,
+      line: symbol.line,
+      endLine,
+      signature: symbol.signature,
+    }
+  })
+}
@@ -0,0 +1,258 @@
+/**
+ * Comparative research helpers
+ * Prompts and matrix extraction for comparing N entities against a shared list of criteria.
+ */
+
+import { resolveReportStyle } from '../prompts/reportStyles.js'
+import { completeJson } from './modelCompletion.js'
+
+export const MAX_COMPARE_ENTITIES = 6
+const MAX_CRITERIA = 8
+const MAX_FINDINGS_CHARS_PER_ENTITY = 8000
+
+const DEFAULT_CRITERIA = [
+  'Key features',
+  'Strengths',
+  'Weaknesses',
+  'Cost',
+  'Maturity and adoption',
+]
+
+const normalizeNameList = (list, max) => {
+  const seen = new Set()
+  return (Array.isArray(list) ? list : [])
+    .map(item => (typeof item === 'string' ? item.trim() : ''))
+    .filter(item => {
+      const key = item.toLowerCase()
+      if (!item || seen.has(key)) return false
+      seen.add(key)
+      return true
+    })
+    .slice(0, max)
+}
+
+export const normalizeEntities = list => normalizeNameList(list, MAX_COMPARE_ENTITIES)
+
+export const normalizeCriteria = list => normalizeNameList(list, MAX_CRITERIA)
+
+/**
+ * Ask the model for comparison criteria when the caller did not provide any
+ */
+export const generateCriteria = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  question,
+  entities,
+  signal,
+}) => {
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: [
+        {
+          role: 'system',
+          content: `You choose criteria for comparing options side by side.
+Return 4-${MAX_CRITERIA} short, concrete, non-overlapping criteria that matter for the user's question, in the user's language.
+Return ONLY JSON: {"criteria": ["..."]}`,
+        },
+        {
+          role: 'user',
+          content: `Question: ${question || '(none)'}\nEntities: ${entities.join(', ')}`,
+        },
+      ],
+      temperature: 0,
+      signal,
+    })
+    const criteria = normalizeCriteria(Array.isArray(parsed) ? parsed : parsed?.criteria)
+    return criteria.length ? criteria : DEFAULT_CRITERIA
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[Comparative] Criteria generation failed, using defaults:', error.message)
+    return DEFAULT_CRITERIA
+  }
+}
+
+export const buildEntityResearchPrompt = ({ question, entity, entities, criteria }) => {
+  const others = entities.filter(item => item !== entity)
+  return `You are gathering evidence about ONE entity for a side-by-side comparison.
+Entity: ${entity}
+Compared against: ${others.join(', ') || '(none)'}
+User question: ${question || '(none)'}
+
+Criteria:
+${criteria.map((criterion, index) => `${index + 1}. ${criterion}`).join('\n')}
+
+Instructions:
+- Use the search tool to find current, specific evidence for EVERY criterion.
+- Report findings for this entity only; do not evaluate the other entities.
+- Write one "### <criterion>" section per criterion, in the order above.
+- Prefer concrete facts (numbers, versions, dates, prices) over general claims.
+- Mention source titles or URLs next to the facts they support.
+- If no evidence is found for a criterion, say "No evidence found" in that section.`
+}
+
+const truncateFindings = text => {
+  const value = String(text || '')
+  return value.length > MAX_FINDINGS_CHARS_PER_ENTITY
+    ? `${value.slice(0, MAX_FINDINGS_CHARS_PER_ENTITY)}\n...(truncated)`
+    : value
+}
+
+const formatEntityFindings = (entities, findingsByEntity) =>
+  entities
+    .map(
+      (entity, index) =>
+        `## ${entity}\n${truncateFindings(findingsByEntity[index]) || 'No findings.'}`,
+    )
+    .join('\n\n')
+
+/**
+ * Align model output to the requested entities x criteria grid so every cell exists
+ */
+export const alignComparisonMatrix = (parsed, entities, criteria, sourceCount) => {
+  const rows = Array.isArray(parsed?.rows) ? parsed.rows : []
+  const findRow = criterion =>
+    rows.find(row => String(row?.criterion || '').toLowerCase() === criterion.toLowerCase())
+  const findCell = (row, entity) =>
+    (Array.isArray(row?.cells) ? row.cells : []).find(
+      cell => String(cell?.entity || '').toLowerCase() === entity.toLowerCase(),
+    )
+  const toSources = value =>
+    (Array.isArray(value) ? value : [])
+      .map(item => Number.parseInt(item, 10))
+      .filter(item => Number.isFinite(item) && item >= 1 && item <= sourceCount)
+
+  return {
+    entities,
+    criteria,
+    rows: criteria.map((criterion, index) => {
+      const row = findRow(criterion) || rows[index]
+      return {
+        criterion,
+        cells: entities.map(entity => {
+          const cell = findCell(row, entity)
+          return {
+            entity,
+            value: typeof cell?.value === 'string' && cell.value.trim() ? cell.value.trim() : null,
+            sources: toSources(cell?.sources),
+          }
+        }),
+      }
+    }),
+    summary: typeof parsed?.summary === 'string' ? parsed.summary.trim() : '',
+  }
+}
+
+/**
+ * Build the structured comparison matrix from per-entity findings
+ * @returns {Promise<{entities: string[], criteria: string[], rows: Array, summary: string}>}
+ */
+export const extractComparisonMatrix = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  question,
+  entities,
+  criteria,
+  findingsByEntity,
+  sourcesList = [],
+  signal,
+}) => {
+  let parsed = null
+  try {
+    parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: [
+        {
+          role: 'system',
+          content: `You turn research notes into a comparison matrix.
+Rules:
+- One row per criterion, one cell per entity, using EXACTLY the given criterion and entity names.
+- Each cell "value" is a short phrase (max ~20 words) grounded in the notes; use null when the notes have no evidence.
+- "sources" lists numbers from the source list that support the cell. Never invent numbers.
+- "summary" is one or two sentences on the overall trade-off.
+
+Return ONLY JSON:
+{"rows": [{"criterion": "...", "cells": [{"entity": "...", "value": "...", "sources": [1]}]}], "summary": "..."}`,
+        },
+        {
+          role: 'user',
+          content: `Question: ${question || '(none)'}
+Entities: ${JSON.stringify(entities)}
+Criteria: ${JSON.stringify(criteria)}
+
+Source list:
+${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}
+
+Research notes:
+${formatEntityFindings(entities, findingsByEntity)}`,
+        },
+      ],
+      temperature: 0,
+      signal,
+    })
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[Comparative] Matrix extraction failed:', error.message)
+  }
+  return alignComparisonMatrix(parsed, entities, criteria, sourcesList.length)
+}
+
+/**
+ * Render the matrix as a markdown table
+ */
+export const formatMatrixMarkdown = matrix => {
+  const escapeCell = value => String(value ?? '—').replace(/\|/g, '\\|').replace(/\n+/g, ' ')
+  const header = `| Criterion | ${matrix.entities.map(escapeCell).join(' | ')} |`
+  const divider = `| --- | ${matrix.entities.map(() => '---').join(' | ')} |`
+  const body = matrix.rows.map(row => {
+    const cells = row.cells.map(cell => {
+      const citations = cell.sources.map(index => `[${index}]`).join('')
+      return escapeCell(cell.value ? `${cell.value}${citations ? ` ${citations}` : ''}` : null)
+    })
+    return `| ${escapeCell(row.criterion)} | ${cells.join(' | ')} |`
+  })
+  return [header, divider, ...body].join('\n')
+}
+
+export const buildComparativeReportPrompt = ({
+  question,
+  matrix,
+  findingsByEntity,
+  sourcesList,
+  reportStyle,
+}) => {
+  const styleInstructions = resolveReportStyle(reportStyle).instructions
+  return `You are writing a comparative research report.
+
+User question: ${question || '(none)'}
+Entities: ${matrix.entities.join(', ')}
+Criteria: ${matrix.criteria.join(', ')}
+
+Comparison matrix (already verified; reproduce it as-is):
+${formatMatrixMarkdown(matrix)}
+
+Research notes per entity:
+${formatEntityFindings(matrix.entities, findingsByEntity)}
+
+Report requirements:
+- Start with a short "Summary" that states the main trade-offs and which entity fits which situation.
+- Include the comparison matrix above as a markdown table right after the summary.
+- Then write one "## " section per criterion comparing ALL entities on that criterion.
+- End with "Recommendation" covering when to choose each entity.
+- Cite sources with [1], [2], ... using ONLY the numbers in the source list; do not invent facts.
+
+OFFICIAL SOURCE LIST (USE THESE AND ONLY THESE):
+${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}${
+    styleInstructions ? `\n\n${styleInstructions}` : ''
+  }`
+}
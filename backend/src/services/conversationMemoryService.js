@@ -0,0 +1,284 @@
+/**
+ * Conversation memory
+ * Keeps long chats coherent without sending the whole history: when a conversation's messages
+ * are over a token budget, the older turns are folded by a cheap model into a rolling
+ * "conversation memory" system note that takes their place, and only the recent turns are sent
+ * verbatim. Memories are cached by conversation id, so the next request only folds in the turns
+ * that have aged out since; an edited or deleted old message invalidates the cached memory.
+ *
+ * CONVERSATION_MEMORY_TOKEN_BUDGET sets the default budget (0 or unset keeps memory off; a
+ * request can set its own with memory_token_budget). CONVERSATION_MEMORY_MODEL picks the
+ * summarizing model (default: the provider's default model in providerConfig), and
+ * CONVERSATION_MEMORY_MAX_ENTRIES (default 200) bounds the cache.
+ */
+
+import { createHash } from 'crypto'
+import { completeText } from './modelCompletion.js'
+import { DEFAULT_MODELS, resolveProviderAlias } from './providers/providerConfig.js'
+
+// Rough size of a token, as in providers/rateLimiter
+const CHARS_PER_TOKEN = 4
+const CHARS_PER_WORD = 6
+// Shares of the budget for the verbatim recent turns and for the memory note
+const RECENT_SHARE = 0.6
+const MEMORY_SHARE = 0.25
+// Turns folded per summarizing call, so a first pass over a huge history fits the cheap model
+const FOLD_INPUT_TOKENS = 12000
+const MAX_TOOL_RESULT_CHARS = 600
+const MIN_MEMORY_WORDS = 80
+const DEFAULT_MAX_ENTRIES = 200
+export const MIN_MEMORY_TOKEN_BUDGET = 500
+export const MAX_MEMORY_TOKEN_BUDGET = 1000000
+
+// Map iteration order is insertion order: a read re-inserts, so the first key is least recent
+const memories = new Map()
+const folding = new Map()
+
+const readNonNegativeInt = (value, fallback) => {
+  const number = Number.parseInt(value, 10)
+  return Number.isFinite(number) && number >= 0 ? number : fallback
+}
+
+export const getConversationMemoryConfig = () => ({
+  tokenBudget: readNonNegativeInt(process.env.CONVERSATION_MEMORY_TOKEN_BUDGET, 0),
+  model: process.env.CONVERSATION_MEMORY_MODEL || null,
+  maxEntries:
+    readNonNegativeInt(process.env.CONVERSATION_MEMORY_MAX_ENTRIES, 0) || DEFAULT_MAX_ENTRIES,
+})
+
+/**
+ * @returns {string|null} Error message for an invalid per-request memory_token_budget
+ */
+export const validateMemoryTokenBudget = value => {
+  if (value === undefined || value === null) return null
+  const budget = Number(value)
+  if (budget === 0) return null
+  const range = `${MIN_MEMORY_TOKEN_BUDGET} and ${MAX_MEMORY_TOKEN_BUDGET}`
+  return Number.isInteger(budget) &&
+    budget >= MIN_MEMORY_TOKEN_BUDGET &&
+    budget <= MAX_MEMORY_TOKEN_BUDGET
+    ? null
+    : `memory_token_budget must be 0 (off) or an integer between ${range}`
+}
+
+const contentText = content => {
+  if (typeof content === 'string') return content
+  if (Array.isArray(content)) {
+    return content
+      .map(part => (typeof part === 'string' ? part : part?.text || ''))
+      .filter(Boolean)
+      .join('\n')
+  }
+  return content ? String(content) : ''
+}
+
+/**
+ * Estimated tokens of one chat message (text, tool calls, and a small per-message overhead;
+ * images are not counted)
+ */
+export const estimateMessageTokens = message => {
+  const toolCalls = message?.tool_calls ? JSON.stringify(message.tool_calls).length : 0
+  return Math.ceil((contentText(message?.content).length + toolCalls) / CHARS_PER_TOKEN) + 4
+}
+
+const sumTokens = messages =>
+  messages.reduce((sum, message) => sum + estimateMessageTokens(message), 0)
+
+/**
+ * Where the verbatim recent turns start: the newest user turns that fit in the recent share of
+ * the budget (and in keepMessages), but always the last user turn. A cut only falls on a user
+ * message, so tool calls stay with their results.
+ * @returns {number} Index into history; 0 when nothing needs to be folded
+ */
+export const findRecentStart = (history, { tokenBudget, keepMessages } = {}) => {
+  const overCount = count => keepMessages > 0 && count > keepMessages
+  if (sumTokens(history) <= tokenBudget && !overCount(history.length)) return 0
+  const recentBudget = Math.floor(tokenBudget * RECENT_SHARE)
+  let start = history.length
+  let tokens = 0
+  let cut = -1
+  for (let index = history.length - 1; index >= 0; index -= 1) {
+    tokens += estimateMessageTokens(history[index])
+    if (history[index]?.role !== 'user') continue
+    const fits = tokens <= recentBudget && !overCount(history.length - index)
+    if (cut !== -1 && !fits) break
+    cut = index
+    start = index
+  }
+  return cut === -1 ? 0 : start
+}
+
+const fingerprint = messages =>
+  createHash('sha256')
+    .update(JSON.stringify(messages.map(message => [message?.role, contentText(message.content)])))
+    .digest('hex')
+
+const readMemory = conversationId => {
+  const entry = memories.get(conversationId)
+  if (!entry) return null
+  memories.delete(conversationId)
+  memories.set(conversationId, entry)
+  return entry
+}
+
+const storeMemory = (conversationId, entry) => {
+  memories.delete(conversationId)
+  memories.set(conversationId, entry)
+  const { maxEntries } = getConversationMemoryConfig()
+  while (memories.size > maxEntries) memories.delete(memories.keys().next().value)
+}
+
+/**
+ * Drop the cached memory of a conversation (e.g. when it is deleted)
+ */
+export const forgetConversationMemory = conversationId => memories.delete(String(conversationId))
+
+export const clearConversationMemories = () => memories.clear()
+
+const describeTurn = message => {
+  const text = contentText(message?.content).trim()
+  if (message?.role === 'tool') {
+    const result =
+      text.length > MAX_TOOL_RESULT_CHARS ? `${text.slice(0, MAX_TOOL_RESULT_CHARS)}…` : text
+    return `Tool result${message.name ? ` (${message.name})` : ''}: ${result}`
+  }
+  if (message?.role === 'assistant') {
+    const calls = (message.tool_calls || []).map(call => call?.function?.name || call?.name)
+    const called = calls.length ? `[called tools: ${calls.filter(Boolean).join(', ')}] ` : ''
+    return `Assistant: ${called}${text}`.trim()
+  }
+  return `User: ${text}`
+}
+
+// Consecutive batches of turns of about FOLD_INPUT_TOKENS each
+const batchTurns = messages => {
+  const batches = []
+  let batch = []
+  let tokens = 0
+  for (const message of messages) {
+    const size = estimateMessageTokens(message)
+    if (batch.length && tokens + size > FOLD_INPUT_TOKENS) {
+      batches.push(batch)
+      batch = []
+      tokens = 0
+    }
+    batch.push(message)
+    tokens += size
+  }
+  if (batch.length) batches.push(batch)
+  return batches
+}
+
+const buildFoldMessages = (memory, turns, maxWords) => [
+  {
+    role: 'system',
+    content: [
+      'You maintain the running memory of a conversation between a user and an assistant.',
+      'Fold the new turns into the existing memory and return the updated memory only.',
+      "Keep what later turns may rely on: the user's goals, facts and preferences they stated,",
+      'decisions and conclusions, names, numbers, code identifiers, open questions, and what the',
+      'assistant promised or already answered. Drop greetings and repetition.',
+      `Write compact bullet points in the conversation's language, at most ${maxWords} words.`,
+    ].join(' '),
+  },
+  {
+    role: 'user',
+    content: `Existing memory:\n${memory || '(empty)'}\n\nNew turns:\n${turns
+      .map(describeTurn)
+      .join('\n\n')}`,
+  },
+]
+
+/**
+ * Summarize turns into a memory with the chat's provider and a cheap model
+ * @returns {(memory: string, turns: Object[], maxWords: number) => Promise<string>}
+ */
+export const createModelSummarizer =
+  ({ provider, apiKey, baseUrl, model, memoryModel, signal }) =>
+  async (memory, turns, maxWords) =>
+    completeText({
+      provider,
+      apiKey,
+      baseUrl,
+      model:
+        memoryModel ||
+        getConversationMemoryConfig().model ||
+        DEFAULT_MODELS[resolveProviderAlias(provider)] ||
+        model,
+      messages: buildFoldMessages(memory, turns, maxWords),
+      temperature: 0.2,
+      signal,
+    })
+
+// The memory of the older turns: the cached one with the turns that aged out since folded in
+const foldMemory = async (key, older, summarize, maxWords) => {
+  const cached = key ? readMemory(key) : null
+  const reusable =
+    cached &&
+    cached.count <= older.length &&
+    cached.hash === fingerprint(older.slice(0, cached.count))
+  let summary = reusable ? cached.summary : ''
+  const pending = older.slice(reusable ? cached.count : 0)
+  for (const batch of batchTurns(pending)) {
+    summary = String((await summarize(summary, batch, maxWords)) || '').trim() || summary
+  }
+  if (!summary) throw new Error('Conversation memory summary came back empty')
+  if (key && pending.length) {
+    storeMemory(key, { summary, count: older.length, hash: fingerprint(older) })
+  }
+  return { summary, cached: pending.length === 0 }
+}
+
+/**
+ * Fit a conversation into a token budget by replacing its older turns with a memory note
+ * @param {Object[]} messages - Chat messages, system messages included
+ * @param {Object} options
+ * @param {number} options.tokenBudget - Tokens of non-system history to stay near (0 = off)
+ * @param {number} [options.keepMessages] - Most verbatim messages (the request's
+ *   contextMessageLimit); older ones are folded into the memory instead of dropped
+ * @param {string} [options.conversationId] - Caches the memory; without one it is rebuilt
+ * @param {(memory: string, turns: Object[], maxWords: number) => Promise<string>} options.summarize
+ * @returns {Promise<{ messages: Object[], memory: null | { summarized_messages: number,
+ *   memory_tokens: number, cached: boolean } }>} memory is null when the history already fits
+ */
+export const compactConversation = async (
+  messages,
+  { tokenBudget, keepMessages, conversationId, summarize },
+) => {
+  if (!tokenBudget || !Array.isArray(messages)) return { messages, memory: null }
+  const system = messages.filter(message => message?.role === 'system')
+  const history = messages.filter(message => message?.role !== 'system')
+  const start = findRecentStart(history, { tokenBudget, keepMessages })
+  if (start === 0) return { messages, memory: null }
+
+  const older = history.slice(0, start)
+  const maxWords = Math.max(
+    MIN_MEMORY_WORDS,
+    Math.floor((tokenBudget * MEMORY_SHARE * CHARS_PER_TOKEN) / CHARS_PER_WORD),
+  )
+  const key = conversationId ? String(conversationId) : null
+  // Parallel requests over the same history (smart mode sub-questions) share one fold
+  const foldKey = key && `${key}:${fingerprint(older)}`
+  let fold = foldKey ? folding.get(foldKey) : null
+  if (!fold) {
+    fold = foldMemory(key, older, summarize, maxWords)
+    if (foldKey) {
+      folding.set(foldKey, fold)
+      fold.finally(() => folding.delete(foldKey)).catch(() => {})
+    }
+  }
+  const { summary, cached } = await fold
+
+  const note = {
+    role: 'system',
+    content: `Conversation memory (a summary of the ${older.length} earlier messages of this conversation, which are not repeated below):\n\n${summary}`,
+  }
+  return {
+    messages: [...system, note, ...history.slice(start)],
+    memory: {
+      summarized_messages: older.length,
+      memory_tokens: estimateMessageTokens(note),
+      cached,
+    },
+  }
+}
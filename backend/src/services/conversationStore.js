@@ -0,0 +1,182 @@
+/**
+ * Conversation store
+ * Local persistence for conversations, messages, spaces, and agents on the JSON data store, so
+ * chat history works without Supabase. Records keep the Supabase column names (snake_case) so
+ * rows can move between the two backends unchanged.
+ */
+
+import { randomUUID } from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+
+const CONVERSATIONS = 'conversations'
+const MESSAGES = 'conversation-messages'
+const ENTITY_COLLECTIONS = { spaces: 'spaces', agents: 'agents' }
+
+const MESSAGE_ROLES = ['system', 'user', 'assistant', 'tool']
+const CONVERSATION_FIELDS = [
+  'title',
+  'title_emojis',
+  'space_id',
+  'api_provider',
+  'is_favorited',
+  'last_agent_id',
+]
+const SORT_FIELDS = ['updated_at', 'created_at', 'title']
+
+const now = () => new Date().toISOString()
+
+const pick = (source, fields) =>
+  Object.fromEntries(fields.filter(field => source?.[field] !== undefined).map(f => [f, source[f]]))
+
+// Messages of one conversation live in a single record, ordered by created_at
+const readThread = conversationId =>
+  readRecord(MESSAGES, conversationId) || { conversation_id: conversationId, messages: [] }
+
+const touchConversation = id => {
+  const conversation = readRecord(CONVERSATIONS, id)
+  if (conversation) writeRecord(CONVERSATIONS, id, { ...conversation, updated_at: now() })
+}
+
+/**
+ * List conversations with optional title search, space filter, and page-based pagination
+ * @returns {{data: Array, count: number, hasMore: boolean}}
+ */
+export const listConversations = ({
+  limit = 20,
+  page = 1,
+  search,
+  spaceId,
+  favoritesOnly = false,
+  sortBy = 'updated_at',
+  ascending = false,
+} = {}) => {
+  const field = SORT_FIELDS.includes(sortBy) ? sortBy : 'updated_at'
+  const query = String(search || '')
+    .trim()
+    .toLowerCase()
+  const filtered = listRecords(CONVERSATIONS)
+    .filter(item => !query || String(item.title || '').toLowerCase().includes(query))
+    .filter(item => spaceId === undefined || String(item.space_id ?? '') === String(spaceId))
+    .filter(item => !favoritesOnly || item.is_favorited)
+    .sort((a, b) => {
+      const order = String(a[field] || '').localeCompare(String(b[field] || ''))
+      return ascending ? order : -order
+    })
+  const size = Math.min(Math.max(Number(limit) || 20, 1), 200)
+  const from = (Math.max(Number(page) || 1, 1) - 1) * size
+  return {
+    data: filtered.slice(from, from + size),
+    count: filtered.length,
+    hasMore: from + size < filtered.length,
+  }
+}
+
+export const getConversation = id => readRecord(CONVERSATIONS, id)
+
+export const createConversation = (payload = {}) => {
+  const timestamp = now()
+  const conversation = {
+    title: 'New Conversation',
+    is_favorited: false,
+    ...pick(payload, CONVERSATION_FIELDS),
+    id: payload.id ? String(payload.id) : randomUUID(),
+    created_at: payload.created_at || timestamp,
+    updated_at: timestamp,
+  }
+  return writeRecord(CONVERSATIONS, conversation.id, conversation)
+}
+
+export const updateConversation = (id, payload = {}) => {
+  const existing = readRecord(CONVERSATIONS, id)
+  if (!existing) return null
+  return writeRecord(CONVERSATIONS, id, {
+    ...existing,
+    ...pick(payload, CONVERSATION_FIELDS),
+    updated_at: now(),
+  })
+}
+
+/**
+ * Delete a conversation together with its messages
+ */
+export const deleteConversation = id => {
+  const deleted = deleteRecord(CONVERSATIONS, id)
+  deleteRecord(MESSAGES, id)
+  return deleted
+}
+
+export const listMessages = conversationId => readThread(conversationId).messages
+
+/**
+ * Append a message; extra fields (tool_calls, sources, thinking, ...) are stored as-is
+ * @returns {Object|null} The stored message, or null when the conversation does not exist
+ */
+export const addMessage = (conversationId, message = {}) => {
+  if (!readRecord(CONVERSATIONS, conversationId)) return null
+  if (!MESSAGE_ROLES.includes(message.role)) {
+    throw new Error(`role must be one of: ${MESSAGE_ROLES.join(', ')}`)
+  }
+  const thread = readThread(conversationId)
+  const stored = {
+    ...message,
+    id: message.id ? String(message.id) : randomUUID(),
+    conversation_id: conversationId,
+    created_at: message.created_at || now(),
+  }
+  thread.messages.push(stored)
+  writeRecord(MESSAGES, conversationId, thread)
+  touchConversation(conversationId)
+  return stored
+}
+
+export const updateMessage = (conversationId, messageId, payload = {}) => {
+  const thread = readThread(conversationId)
+  const index = thread.messages.findIndex(message => message.id === messageId)
+  if (index === -1) return null
+  const { id: _id, conversation_id: _conversationId, ...changes } = payload
+  thread.messages[index] = { ...thread.messages[index], ...changes }
+  writeRecord(MESSAGES, conversationId, thread)
+  touchConversation(conversationId)
+  return thread.messages[index]
+}
+
+export const deleteMessage = (conversationId, messageId) => {
+  const thread = readThread(conversationId)
+  const messages = thread.messages.filter(message => message.id !== messageId)
+  if (messages.length === thread.messages.length) return false
+  writeRecord(MESSAGES, conversationId, { ...thread, messages })
+  return true
+}
+
+/**
+ * Spaces and agents are stored as free-form records keyed by id
+ * @param {'spaces'|'agents'} kind
+ */
+const resolveEntityCollection = kind => {
+  const collection = ENTITY_COLLECTIONS[kind]
+  if (!collection) throw new Error(`Unknown entity kind: ${kind}`)
+  return collection
+}
+
+export const listEntities = kind =>
+  listRecords(resolveEntityCollection(kind)).sort((a, b) =>
+    String(a.created_at || '').localeCompare(String(b.created_at || '')),
+  )
+
+export const getEntity = (kind, id) => readRecord(resolveEntityCollection(kind), id)
+
+export const saveEntity = (kind, payload = {}) => {
+  const collection = resolveEntityCollection(kind)
+  const id = payload.id ? String(payload.id) : randomUUID()
+  const existing = readRecord(collection, id)
+  const timestamp = now()
+  return writeRecord(collection, id, {
+    ...existing,
+    ...payload,
+    id,
+    created_at: existing?.created_at || payload.created_at || timestamp,
+    updated_at: timestamp,
+  })
+}
+
+export const deleteEntity = (kind, id) => deleteRecord(resolveEntityCollection(kind), id)
@@ -4,10 +4,78 @@
  */
 
 import { ChatOpenAI } from '@langchain/openai'
+import { renderSystemPrompt } from '../prompts/promptStore.js'
+import { resolveReportStyle } from '../prompts/reportStyles.js'
+import { resolveResearchTemplate } from '../prompts/researchTemplates.js'
+import { yieldWhileRunning } from '../utils/eventQueue.js'
 import { generateAcademicResearchPlan } from './academicResearchPlanService.js'
+import { toCitationMetadata } from './academicSearchService.js'
+import { checkCitations, formatCitationCheckMarkdown } from './citationCheckService.js'
+import {
+  formatReferences,
+  formatReferencesMarkdown,
+  REFERENCES_HEADING,
+  replaceReferencesSection,
+  resolveCitationItems,
+} from './citationFormatService.js'
+import {
+  buildComparativeReportPrompt,
+  buildEntityResearchPrompt,
+  extractComparisonMatrix,
+  generateCriteria,
+  normalizeCriteria,
+  normalizeEntities,
+} from './comparativeResearchService.js'
+import { extractFinancialMetrics, formatMetricsForPrompt } from './financialMetricsService.js'
+import { formatGlossaryMarkdown, generateGlossary } from './glossaryService.js'
+import {
+  buildPlanRepairMessages,
+  parsePlanWithRecovery,
+  resolveStepDependencies,
+} from './planParser.js'
+import { buildPreferencesPrompt, getPreferences } from './preferencesService.js'
+import { proofreadText } from './proofreadService.js'
+import { limitModel } from './modelRateLimit.js'
+import { traceModel } from './modelTracing.js'
+import { getProviderAdapter } from './providers/adapterFactory.js'
+import { usesNativeApi } from './providers/providerConfig.js'
+import { analyzeNumericClaims, formatConflictsForPrompt } from './numericClaimsService.js'
+import { decomposeQuestion } from './questionDecompositionService.js'
+import { addSourceText, checkReportSimilarity } from './reportSimilarityService.js'
 import { generateResearchPlan } from './researchPlanService.js'
+import { formatForLocale, resolveReportLocale } from './localeFormatService.js'
+import { createResearchRun, getResearchRun, saveResearchRun } from './researchRunStore.js'
+import {
+  addUsage,
+  createResearchStats,
+  emptyUsage,
+  extractUsage,
+  toUsagePayload,
+} from './researchTelemetry.js'
+import {
+  createSaturationTracker,
+  resolveNoveltyThreshold,
+  SATURATION_NOTE,
+} from './searchSaturationService.js'
 import { normalizeTextContent, safeJsonParse, toLangChainMessages } from './serviceUtils.js'
-import { executeToolByName, getToolDefinitionsByIds, isLocalToolName } from './toolsService.js'
+import { clusterSources, formatClustersForPrompt } from './sourceClusteringService.js'
+import {
+  annotateSources,
+  formatDiversityForPrompt,
+  summarizeSourceDiversity,
+} from './sourceBiasService.js'
+import { buildGlossaryPrompt } from './spaceGlossaryService.js'
+import { extractTimeline, formatTimelineForPrompt, isTimelineQuestion } from './timelineService.js'
+import { withSpan } from './tracing.js'
+import { buildTurnLimitEvent, getDefaultMaxTurns, resolveMaxTurns } from './turnLimits.js'
+import {
+  executeToolByName,
+  getToolDefinitionsByIds,
+  isLocalToolName,
+  isSourceToolName,
+} from './toolsService.js'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+import { getToolCallFragments, ToolCallDeltaTracker } from '../utils/toolCallDeltas.js'
 
 const OPENAI_DEFAULT_BASE = 'https://api.openai.com/v1'
 const SILICONFLOW_BASE = 'https://api.siliconflow.cn/v1'
@@ -45,7 +113,23 @@ const buildModel = ({
   toolChoice,
   responseFormat,
   streaming,
+  includeUsage = false,
 }) => {
+  // Native (non OpenAI-compatible) APIs go through their provider adapter
+  if (usesNativeApi(provider)) {
+    return getProviderAdapter(provider).buildModel({
+      apiKey,
+      baseUrl,
+      model,
+      temperature,
+      top_k,
+      top_p,
+      tools,
+      toolChoice,
+      responseFormat,
+      streaming,
+    })
+  }
   if (!apiKey) throw new Error('Missing API key')
   const modelKwargs = {}
   if (responseFormat) modelKwargs.response_format = responseFormat
@@ -56,18 +140,25 @@ const buildModel = ({
   if (tools && tools.length > 0) modelKwargs.tools = tools
   if (toolChoice) modelKwargs.tool_choice = toolChoice
   if (streaming) {
-    modelKwargs.stream_options = { include_usage: false }
+    modelKwargs.stream_options = { include_usage: includeUsage }
   }
 
-  return new ChatOpenAI({
-    apiKey,
-    modelName: model || DEFAULT_MODELS[provider] || DEFAULT_MODELS.openai,
-    temperature,
-    streaming,
-    __includeRawResponse: true,
-    modelKwargs,
-    configuration: { baseURL: resolveBaseUrl(provider, baseUrl) },
-  })
+  const modelName = model || DEFAULT_MODELS[provider] || DEFAULT_MODELS.openai
+  return traceModel(
+    limitModel(
+      new ChatOpenAI({
+        apiKey,
+        modelName,
+        temperature,
+        streaming,
+        __includeRawResponse: true,
+        modelKwargs,
+        configuration: { baseURL: resolveBaseUrl(provider, baseUrl) },
+      }),
+      { provider, apiKey },
+    ),
+    { provider, model: modelName },
+  )
 }
 
 const getToolCallName = toolCall =>
@@ -150,28 +241,78 @@ const buildResearchStepEvent = ({ stepIndex, totalSteps, title, status, duration
   error: error ? String(error.message || error) : undefined,
 })
 
+// Internal event: trackRun persists it on the run record and does not forward it
+const buildCheckpointEvent = (stepIndex, content, sourcesMap) => ({
+  type: 'research_checkpoint',
+  step_index: stepIndex,
+  content,
+  sources: Array.from(sourcesMap.values()),
+})
+
+// Full result texts per sources map, for the report similarity check; kept out of the source
+// objects so they are not sent to clients or persisted with the run
+const sourceTextsByMap = new WeakMap()
+
+const getSourceTexts = sourcesMap => {
+  if (!sourceTextsByMap.has(sourcesMap)) sourceTextsByMap.set(sourcesMap, new Map())
+  return sourceTextsByMap.get(sourcesMap)
+}
+
 // collect web search sources
+const findSourceUrlByDoi = (sourcesMap, doi) => {
+  for (const [url, source] of sourcesMap) {
+    if (source.doi === doi) return url
+  }
+  return null
+}
+
+// Academic results (arxiv_search, semantic_scholar_search, pubmed_search) carry a DOI, authors,
+// and year; the same paper found through a second index stays one source
 const collectWebSearchSources = (result, sourcesMap) => {
   if (!result?.results || !Array.isArray(result.results)) return
   result.results.forEach(item => {
-    const url = item.url
+    const citation = toCitationMetadata(item)
+    const url = (citation.doi && findSourceUrlByDoi(sourcesMap, citation.doi)) || item.url
+    addSourceText(getSourceTexts(sourcesMap), url, item.content)
     if (url && !sourcesMap.has(url)) {
       sourcesMap.set(url, {
         title: item.title || 'Unknown Source',
         url,
         uri: url,
         snippet: item.content?.slice(0, 200) || '',
+        ...citation,
       })
     }
   })
 }
 
+// Paper indexes queried directly in academic research, next to Tavily_academic_search
+const ACADEMIC_SEARCH_TOOL_IDS = ['arxiv_search', 'semantic_scholar_search', 'pubmed_search']
+
 const isTavilySearchToolName = name =>
   name === 'Tavily_web_search' ||
   name === 'Tavily_academic_search' ||
+  name === 'Tavily_standards_search' ||
+  name === 'Tavily_legal_search' ||
   name === 'web_search' ||
   name === 'academic_search'
 
+// Planner input for a follow-up run: the new question, with what is already known
+const buildFollowupPlanRequest = (followup, question) => `Follow-up question: ${question || ''}
+
+This continues an earlier research run on "${followup.question}". Plan only the research the follow-up question still needs; do not plan steps for what the earlier findings already cover:
+${followup.findings.length ? followup.findings.map(item => `- ${item}`).join('\n') : '- None'}`
+
+// Earlier findings a follow-up run builds on (see followupDeepResearch)
+const buildFollowupInstructions = followup => {
+  if (!followup?.findings?.length) return ''
+  return `
+
+EARLIER RESEARCH:
+This run follows up an earlier research run on "${followup.question}". Its findings are below; they cite the same numbered sources. Build on them instead of repeating them, and concentrate on the follow-up question:
+${followup.findings.map(item => `- ${item}`).join('\n')}`
+}
+
 const buildStepPrompt = ({
   planMeta,
   step,
@@ -185,8 +326,13 @@ const buildStepPrompt = ({
   const isAcademic = researchType === 'academic'
 
   // Base information that appears in both prompts
+  const subQuestion =
+    typeof step.sub_question === 'number' && Array.isArray(planMeta.sub_questions)
+      ? planMeta.sub_questions[step.sub_question]
+      : null
+
   const baseInfo = `Goal: ${planMeta.goal || 'N/A'}
-Question type: ${planMeta.question_type || 'N/A'}
+Question type: ${planMeta.question_type || 'N/A'}${subQuestion ? `\nSub-question addressed by this step: ${subQuestion}` : ''}
 Step ${stepIndex + 1}: ${step.action || ''}
 Expected output: ${step.expected_output || 'N/A'}
 Deliverable format: ${step.deliverable_format || 'paragraph'}
@@ -203,67 +349,75 @@ Prior findings:
 ${priorFindings.length ? priorFindings.map(item => `- ${item}`).join('\n') : '- None'}
 
 Known sources (cite as [index]):
-${sourcesList.length ? sourcesList.join('\n') : '- None'}`
+${sourcesList.length ? sourcesList.join('\n') : '- None'}${buildFollowupInstructions(planMeta.followup)}`
 
   if (isAcademic) {
-    return `You are executing an academic research plan step.
-
-${baseInfo}
-
-CRITICAL ACADEMIC REQUIREMENTS:
-
-1. SOURCE QUALITY
-   - Prioritize peer-reviewed journal articles and conference proceedings
-   - For each source, note: publication venue, year, and whether it's peer-reviewed
-   - Distinguish between primary research, reviews, and meta-analyses
-   - Flag preprints or non-peer-reviewed sources explicitly
-
-2. EVIDENCE AND CITATION
-   - Cite ALL factual claims using [index] format
-   - Never make unsourced claims about research findings or statistics
-   - When multiple sources agree/disagree, cite all relevant ones
-   - Note the strength of evidence (e.g., "based on large-scale RCT" vs "preliminary findings")
-
-3. CRITICAL EVALUATION
-   - Assess methodological rigor of cited studies
-   - Note sample sizes, study designs, and potential limitations
-   - Identify potential biases or confounding factors
-   - Highlight any conflicting findings across studies
-
-4. SCHOLARLY LANGUAGE
-   - Use formal academic tone (third person, precise terminology)
-   - Employ appropriate hedging language ("suggests", "indicates", "implies")
-   - Avoid overgeneralizations or absolute claims
-   - Define technical terms when first introduced
-
-5. SYSTEMATIC APPROACH
-   - Follow the step's acceptance criteria rigorously
-   - If this is a search step, use broad, well-defined search terms
-   - If this is an analysis step, organize findings thematically
-   - Build logically on prior findings
-
-Instructions:
-- Use Tavily_academic_search or Tavily_web_search tools as needed to gather peer-reviewed evidence
-- When citing sources, use [1], [2], etc. based on the known sources list
-- Return a scholarly, well-structured output suitable for inclusion in an academic report
-- Maintain objectivity and acknowledge uncertainty where appropriate
-    
-    NEGATIVE CONSTRAINTS (CRITICAL):
-    - **NO OUTSIDE KNOWLEDGE**: You must ONLY use the information provided in "Prior findings" and "Known sources".
-    - **NO HALLUCINATION**: If the provided sources do not contain the answer, explicitly state it. DO NOT make up facts.
-    - **STRICT CITATION**: Every single factual claim must have a citation [x].
-    - **NO SYNTHETIC SOURCES**: Do not invent source titles or links. Use the [index] exactly as listed.`
-  }
-
-  // General research prompt (original)
-  return `You are executing a structured research plan step.
-
-${baseInfo}
-
-Instructions:
-- Use the available tools when needed to gather evidence.
-- When citing sources, use [1], [2], etc. based on the known sources list.
-- Return a concise step output that can be used by subsequent steps.`
+    return renderSystemPrompt('deep_research_academic_step', { step_context: baseInfo })
+  }
+
+  const template = resolveResearchTemplate(researchType)
+  const templateBlock = template ? `\n\n${template.stepInstructions}` : ''
+
+  // General research prompt (original), plus template-specific requirements
+  return renderSystemPrompt('deep_research_step', {
+    step_context: baseInfo,
+    template_instructions: templateBlock,
+  })
+}
+
+const buildSubQuestionInstructions = planMeta => {
+  const subQuestions = Array.isArray(planMeta.sub_questions) ? planMeta.sub_questions : []
+  if (subQuestions.length < 2) return ''
+  return `
+
+MULTI-PART QUESTION:
+The question has ${subQuestions.length} distinct parts. After a brief overall summary, include one dedicated section per sub-question, in this order:
+${subQuestions.map((item, index) => `${index + 1}. ${item}`).join('\n')}`
+}
+
+const buildTimelineInstructions = timeline => {
+  if (!Array.isArray(timeline) || timeline.length === 0) return ''
+  return `
+
+TIMELINE:
+A structured timeline has been extracted and is shown to the user separately. Keep dates in the report consistent with it, and organize the narrative chronologically:
+${formatTimelineForPrompt(timeline)}`
+}
+
+const buildDataConflictInstructions = conflicts => {
+  if (!Array.isArray(conflicts) || conflicts.length === 0) return ''
+  return `
+
+DATA CONFLICTS:
+Sources disagree on the figures below (units already normalized for comparison). Add a "Data conflicts" section near the end that lists each one with the competing figures and their citations, and explain likely causes (different dates, scopes, definitions, or methodologies). Do not silently pick one figure in the main text; name the range instead:
+${formatConflictsForPrompt(conflicts)}`
+}
+
+const buildFinancialMetricsInstructions = financialMetrics => {
+  if (!financialMetrics?.entities?.length) return ''
+  return `
+
+EXTRACTED FINANCIAL METRICS:
+Use these figures for the "Key metrics" table (entity | metric | value | period | as of | status | sources). Figures marked STALE are older than ${financialMetrics.staleness_days} days or undated; flag them as "⚠ stale":
+${formatMetricsForPrompt(financialMetrics)}`
+}
+
+const buildSourceDiversityInstructions = sourceDiversity => {
+  if (!sourceDiversity?.diversity?.labeled) return ''
+  return `
+
+SOURCE DIVERSITY:
+Some sources are news outlets with known ownership and editorial lean (from a bundled dataset, coarse and approximate). Add a short "Source diversity" note near the end that summarizes the mix, names any one-sided or concentrated coverage, and points out where a claim rests only on outlets of one lean or owner. Do not dismiss a source because of its label:
+${formatDiversityForPrompt(sourceDiversity.sources, sourceDiversity.diversity)}`
+}
+
+const buildSourceClusterInstructions = sourceClusters => {
+  if (!sourceClusters?.clusters?.length) return ''
+  return `
+
+SOURCE CLUSTERS:
+The sources fall into the sub-topics below. Organize the main findings section with one subsection per sub-topic (merge or skip a sub-topic only if it adds nothing), and cite each subsection's sources:
+${formatClustersForPrompt(sourceClusters)}`
 }
 
 const buildFinalReportPrompt = ({
@@ -272,8 +426,25 @@ const buildFinalReportPrompt = ({
   findings,
   sourcesList,
   researchType = 'general',
+  reportStyle,
+  timeline,
+  dataConflicts,
+  financialMetrics,
+  sourceDiversity,
+  sourceClusters,
 }) => {
   const isAcademic = researchType === 'academic'
+  const extraInstructions = [
+    buildSubQuestionInstructions(planMeta),
+    buildTimelineInstructions(timeline),
+    buildDataConflictInstructions(dataConflicts),
+    buildFinancialMetricsInstructions(financialMetrics),
+    buildSourceDiversityInstructions(sourceDiversity),
+    buildSourceClusterInstructions(sourceClusters),
+    buildFollowupInstructions(planMeta.followup),
+  ].join('')
+  const styleInstructions = resolveReportStyle(reportStyle).instructions
+  const styleBlock = styleInstructions ? `\n\n${styleInstructions}` : ''
 
   // Base information
   const baseInfo = `Question: ${question || planMeta.goal || 'N/A'}
@@ -284,123 +455,69 @@ Findings to synthesize:
 ${findings.length ? findings.map(item => `- ${item}`).join('\n') : '- None'}
 
 Sources (cite as [index]):
-${sourcesList.length ? sourcesList.join('\n') : '- None'}`
+${sourcesList.length ? sourcesList.join('\n') : '- None'}${extraInstructions}`
 
   if (isAcademic) {
-    return `You are writing an academic research report based on a systematic literature review.
-
-${baseInfo}
-
-REPORT STRUCTURE:
-
-Your report MUST follow this academic structure:
-
-## 1. ABSTRACT (150-250 words)
-   - Brief summary of research question, methods, key findings, and implications
-   - Written last, but appears first
-
-## 2. INTRODUCTION
-   - Background and context for the research question
-   - Significance and relevance of the topic
-   - Clear statement of research objectives/questions
-   - Scope and limitations of the review
-
-## 3. METHODOLOGY (if applicable)
-   - Search strategy (databases, keywords, timeframe)
-   - Inclusion/exclusion criteria
-   - Quality assessment approach
-   - Data extraction and synthesis methods
-
-## 4. LITERATURE REVIEW / FINDINGS
-   Organize thematically (NOT source-by-source):
-   - Group findings by major themes or subtopics
-   - For each theme:
-     * Synthesize what multiple sources say
-     * Cite all relevant sources [1][2][3]
-     * Note consensus and disagreements
-     * Assess quality of evidence
-   - Present conflicting findings objectively
-   - Distinguish between well-established and preliminary findings
-
-## 5. DISCUSSION
-   - Interpret the synthesized findings
-   - Compare with broader theoretical frameworks
-   - Address research questions posed in introduction
-   - Note implications for theory and practice
-   - Acknowledge limitations of the evidence base:
-     * Methodological limitations of cited studies
-     * Gaps in coverage (populations, contexts, outcomes)
-     * Potential publication bias
-   - Discuss areas of uncertainty or ongoing debate
-
-## 6. CONCLUSION
-   - Summarize key findings and their significance
-   - Highlight main contributions of this review
-   - Suggest directions for future research
-   - Provide actionable recommendations (if appropriate)
-
-## 7. REFERENCES
-   - **MANDATORY**: This section must ONLY contain sources listed in the "Sources" block provided above.
-   - **NO OMISSIONS**: Include every source you cited in the text.
-   - **NO ADDITIONS**: Do NOT add any external books, papers, or links that are not in the provided Source list.
-   - Format: "[index] Title. URL" (Copy exactly from the Source list).
-
-ACADEMIC WRITING STANDARDS:
-
-- **Tone**: Formal, objective, third-person
-- **Language**: Precise terminology, appropriate hedging
-- **Citations**: Every factual claim must have a citation
-- **Evidence hierarchy**: Note study designs and sample sizes
-- **Critical thinking**: Evaluate rather than just summarize
-- **Synthesis**: Integrate across sources, don't just list findings
-- **Limitations**: Always acknowledge what is NOT known
-
-QUALITY CHECKLIST:
-- [ ] Every factual claim is cited
-- [ ] Sources are critically evaluated, not just reported
-- [ ] Conflicting evidence is presented fairly
-- [ ] Limitations are explicitly discussed
-- [ ] Implications for future research are clear
-- [ ] Academic tone is maintained throughout
-
-NEGATIVE CONSTRAINTS (CRITICAL):
-- **NO EXTERNAL KNOWLEDGE**: You must ONLY use the information provided in the "Sources" section. Do not use outside knowledge to fill gaps.
-- **NO HALLUCINATION**: If the provided sources do not contain the answer, explicitly state "The provided sources do not contain information about X". DO NOT make up facts, authors, or years.
-- **STRICT CITATION**: Every single paragraph must contain at least one citation [x].
-- **NO SYNTHETIC SOURCES**: Do not invent source titles or links. Use the [index] exactly as listed in the "Sources" section.
-
-HALLUCINATION CHECK:
-Before writing each sentence, ask: "Is this fact present in source [x]?" If no, delete it.
-If you violate these constraints, the task is considered failed.
-
-Produce a comprehensive, publication-quality academic report.
-    
-    CRITICAL FINAL INSTRUCTION:
-    When writing the "7. REFERENCES" section, you MUST strictly copy the list below. Do NOT add anything else.
-    
-    OFFICIAL SOURCE LIST (USE THESE AND ONLY THESE):
-    ${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}`
-  }
-
-  // General research prompt (original)
-  return `You are a deep research writer producing a final report.
-
-${baseInfo}
-
-Requirements:
-- Evidence-driven and traceable: every factual claim must be backed by a citation.
-- Include a short "Self-check" section at the end with 3-5 bullets.
-- Use clear headings and complete the full report in one response.`
+    return renderSystemPrompt('deep_research_academic_report', {
+      report_context: baseInfo,
+      source_list: sourcesList.length ? sourcesList.join('\n') : 'No sources available.',
+      style_instructions: styleBlock,
+    })
+  }
+
+  const template = resolveResearchTemplate(researchType)
+  const templateBlock = template ? `\n\n${template.reportInstructions}` : ''
+
+  // General research prompt (original), plus template-specific requirements
+  return renderSystemPrompt('deep_research_report', {
+    report_context: baseInfo,
+    template_instructions: templateBlock,
+    style_instructions: styleBlock,
+  })
 }
 
 const buildSourcesList = sourcesMap =>
   Array.from(sourcesMap.values()).map((source, idx) => {
     const title = source.title || source.url || source.uri || `Source ${idx + 1}`
     const url = source.url || source.uri || ''
-    return `[${idx + 1}] ${title} ${url}`.trim()
+    const doi = source.doi ? ` (doi:${source.doi})` : ''
+    return `[${idx + 1}] ${title} ${url}${doi}`.trim()
+  })
+
+/**
+ * One tool-calling turn, streamed so argument fragments reach onToolCallDelta as they are
+ * generated. Only OpenAI-compatible models stream here; native adapters are invoked.
+ * @returns {Promise<{ content: string, finishReason, toolCalls, usage }>}
+ */
+const requestStepTurn = async (modelInstance, messages, { signal, onToolCallDelta, meta }) => {
+  if (!onToolCallDelta || !(modelInstance instanceof ChatOpenAI)) {
+    const response = await modelInstance.invoke(messages, { signal })
+    return {
+      content: normalizeTextContent(getResponseContent(response)),
+      finishReason: getFinishReasonFromResponse(response),
+      toolCalls: getToolCallsFromResponse(response),
+      usage: extractUsage(response),
+    }
+  }
+  const tracker = new ToolCallDeltaTracker()
+  let content = ''
+  let finishReason = null
+  let usage = null
+  const stream = await modelInstance.stream(messages, {
+    signal,
+    stream_options: { include_usage: true },
   })
+  for await (const chunk of stream) {
+    const raw = chunk?.additional_kwargs?.__raw_response?.choices?.[0]
+    content += normalizeTextContent(chunk?.content) || raw?.delta?.content || ''
+    finishReason = raw?.finish_reason || finishReason
+    usage = extractUsage(chunk) || usage
+    for (const event of tracker.push(getToolCallFragments(chunk), meta)) onToolCallDelta(event)
+  }
+  return { content, finishReason, toolCalls: tracker.toolCalls(), usage }
+}
 
-const runToolCallingStep = async ({
+const executeToolCallingStep = async ({
   modelInstance,
   baseMessages,
   sourcesMap,
@@ -408,18 +525,28 @@ const runToolCallingStep = async ({
   stepIndex,
   totalSteps,
   toolConfig,
-  maxLoops = 4,
+  stats,
+  saturation,
+  onToolCallDelta,
+  maxLoops = getDefaultMaxTurns('research_step'),
 }) => {
   let currentMessages = [...baseMessages]
   let loops = 0
   const toolEvents = []
+  const usage = emptyUsage()
+  const stepMeta = {
+    step: typeof stepIndex === 'number' ? stepIndex + 1 : undefined,
+    total: totalSteps,
+  }
   while (loops < maxLoops) {
     loops += 1
-    const response = await modelInstance.invoke(toLangChainMessages(currentMessages), {
+    const turn = await requestStepTurn(modelInstance, toLangChainMessages(currentMessages), {
       signal,
+      onToolCallDelta,
+      meta: stepMeta,
     })
-    const finishReason = getFinishReasonFromResponse(response)
-    const toolCalls = getToolCallsFromResponse(response)
+    addUsage(usage, turn.usage)
+    const { finishReason, toolCalls } = turn
     if (finishReason === 'tool_calls' && Array.isArray(toolCalls) && toolCalls.length > 0) {
       const assistantToolCalls = toolCalls
         .map(toolCall => {
@@ -476,9 +603,50 @@ const runToolCallingStep = async ({
           continue
         }
 
+        const skipReason = isTavilySearchToolName(toolName)
+          ? saturation?.checkQuery(parsedArgs?.query)
+          : null
+        if (skipReason) {
+          const output = { skipped: true, reason: skipReason }
+          currentMessages.push({
+            role: 'tool',
+            tool_call_id: toolCall.id,
+            name: toolName,
+            content: JSON.stringify(output),
+          })
+          stats?.recordSearch({
+            tool: toolName,
+            query: parsedArgs?.query,
+            stepIndex,
+            durationMs: 0,
+            skipped: true,
+          })
+          toolEvents.push(
+            buildToolResultEvent(toolCall, null, 0, output, {
+              step: typeof stepIndex === 'number' ? stepIndex + 1 : undefined,
+              total: totalSteps,
+            }),
+          )
+          continue
+        }
+
         try {
           const result = await executeToolByName(toolName, parsedArgs || {}, toolConfig)
           if (isTavilySearchToolName(toolName)) {
+            saturation?.recordResult(
+              parsedArgs?.query,
+              Array.isArray(result?.results) ? result.results.map(item => item?.url) : [],
+              sourcesMap,
+            )
+            collectWebSearchSources(result, sourcesMap)
+            stats?.recordSearch({
+              tool: toolName,
+              query: parsedArgs?.query,
+              resultCount: Array.isArray(result?.results) ? result.results.length : 0,
+              stepIndex,
+              durationMs: Date.now() - startedAt,
+            })
+          } else if (isSourceToolName(toolName)) {
             collectWebSearchSources(result, sourcesMap)
           }
           currentMessages.push({
@@ -494,6 +662,15 @@ const runToolCallingStep = async ({
             }),
           )
         } catch (error) {
+          if (isTavilySearchToolName(toolName)) {
+            stats?.recordSearch({
+              tool: toolName,
+              query: parsedArgs?.query,
+              stepIndex,
+              durationMs: Date.now() - startedAt,
+              error,
+            })
+          }
           currentMessages.push({
             role: 'tool',
             tool_call_id: toolCall.id,
@@ -510,36 +687,91 @@ const runToolCallingStep = async ({
       }
       continue
     }
-    const content = getResponseContent(response)
-    return { content: normalizeTextContent(content), toolEvents }
+    const content = turn.content
+    return {
+      content: saturation?.saturated && content ? `${content}\n\n${SATURATION_NOTE}` : content,
+      toolEvents,
+      usage,
+      llmCalls: loops,
+    }
   }
-  return { content: '', toolEvents }
+  // Still calling tools after the last allowed turn
+  toolEvents.push(buildTurnLimitEvent(maxLoops, stepMeta))
+  return { content: '', toolEvents, usage, llmCalls: loops }
 }
 
-const parsePlan = planText => {
-  const parsed = safeJsonParse(planText || '')
-  if (parsed && Array.isArray(parsed.plan)) return parsed
-  return {
-    goal: '',
-    assumptions: [],
-    question_type: 'analysis',
-    plan: [
-      {
-        step: 1,
-        action: 'Summarize the topic and gather key evidence.',
-        expected_output: 'A concise summary with evidence.',
-        deliverable_format: 'paragraph',
-        acceptance_criteria: [],
-        depth: 'medium',
-        requires_search: true,
-      },
-    ],
+/**
+ * Run a task that emits events through a callback and yield those events while it runs
+ * @returns the task's result
+ */
+// Each step is a span in the run's trace, with its model turns and tool calls inside
+const runToolCallingStep = params =>
+  withSpan(
+    'research_step',
+    {
+      step: typeof params.stepIndex === 'number' ? params.stepIndex + 1 : undefined,
+      total: params.totalSteps,
+    },
+    () => executeToolCallingStep(params),
+  )
+
+/**
+ * Parse and validate the plan, re-prompting the model with the errors before giving up
+ * @returns {Promise<Object>} parsePlanWithRecovery() result
+ */
+const parsePlan = async (planText, { provider, apiKey, baseUrl, model, signal }) => {
+  const repairWithModel = async (text, errors) => {
+    const repairModel = buildModel({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      temperature: 0,
+      responseFormat: provider !== 'gemini' ? { type: 'json_object' } : undefined,
+      streaming: false,
+    })
+    const response = await repairModel.invoke(
+      toLangChainMessages(buildPlanRepairMessages(text, errors)),
+      { signal },
+    )
+    return normalizeTextContent(getResponseContent(response))
   }
+
+  return withSpan('plan_parse', { plan_text: planText }, async span => {
+    const result = await parsePlanWithRecovery(planText, { repairWithModel })
+    if (span) {
+      Object.assign(span.attributes, {
+        stage: result.stage,
+        valid: result.valid,
+        attempts: result.attempts,
+        errors: result.errors,
+      })
+    }
+    return result
+  })
+}
+
+// SSE event for a plan that is still invalid after the repair attempts
+const toPlanInvalidEvent = (result, subQuestion) => ({
+  type: 'plan_invalid',
+  errors: result.validationErrors,
+  attempts: result.attempts,
+  fallback: result.stage === 'fallback',
+  ...(subQuestion !== undefined ? { sub_question: subQuestion } : {}),
+})
+
+const DEFAULT_MAX_PARALLEL_STEPS = 3
+
+const resolveMaxParallelSteps = value => {
+  const parsed = Number.parseInt(value ?? process.env.DEEP_RESEARCH_MAX_PARALLEL, 10)
+  return Number.isFinite(parsed) && parsed > 0 ? parsed : DEFAULT_MAX_PARALLEL_STEPS
 }
 
 /**
- * Execute all research steps concurrently using Promise.all
- * This is an experimental feature that runs all steps in parallel
+ * Execute research steps concurrently, respecting step dependencies
+ * Independent steps (see resolveStepDependencies) start as soon as a slot is free; a dependent
+ * step waits for the steps it needs and sees only their findings. At most maxParallel steps run
+ * at once. Events are pushed through yieldEvent as they happen and carry their step number.
  */
 const runStepsConcurrently = async ({
   steps,
@@ -548,32 +780,48 @@ const runStepsConcurrently = async ({
   question,
   toolModel,
   sourcesMap,
-  findings,
   signal,
   toolConfig,
   researchType,
+  stats,
+  createSaturation,
+  maxParallel,
+  maxTurns,
+  completedSteps = new Map(),
   yieldEvent,
 }) => {
-  console.log('[DeepResearch] Concurrent mode: emitting all step pending states')
+  const dependencies = resolveStepDependencies(steps)
+  const results = new Array(steps.length).fill('')
+  const finished = new Set()
+  const running = new Map()
+  let nextCandidate = 0
+
+  // Steps finished before a resume keep their findings
+  for (const [index, content] of completedSteps) {
+    results[index] = content
+    finished.add(index)
+  }
+
+  console.log(
+    `[DeepResearch] Concurrent mode: ${steps.length} steps, max ${maxParallel} in parallel`,
+  )
 
   // First, emit pending state for all steps so UI can display them all at once
   for (let i = 0; i < steps.length; i++) {
-    await yieldEvent(
-      buildResearchStepEvent({
-        stepIndex: i,
-        totalSteps: steps.length,
-        title: steps[i].action || 'Research',
-        status: 'pending',
-      }),
-    )
+    const event = buildResearchStepEvent({
+      stepIndex: i,
+      totalSteps: steps.length,
+      title: steps[i].action || 'Research',
+      status: finished.has(i) ? 'done' : 'pending',
+    })
+    await yieldEvent(finished.has(i) ? { ...event, resumed: true } : event)
   }
 
-  // Create all step promises
-  const stepPromises = steps.map(async (step, i) => {
+  const runStep = async i => {
+    const step = steps[i] || {}
     const stepTitle = step.action || 'Research'
     const stepStartedAt = Date.now()
 
-    // Yield running event
     await yieldEvent(
       buildResearchStepEvent({
         stepIndex: i,
@@ -583,14 +831,13 @@ const runStepsConcurrently = async ({
       }),
     )
 
-    // Build step prompt with current sources
-    // Note: In concurrent mode, priorFindings will be empty as steps run in parallel
+    // Build step prompt with the findings of the steps it depends on and the sources so far
     const sourcesList = buildSourcesList(sourcesMap)
     const stepPrompt = buildStepPrompt({
       planMeta,
       step,
       stepIndex: i,
-      priorFindings: findings, // Empty in concurrent mode
+      priorFindings: dependencies[i].map(dependency => results[dependency]).filter(Boolean),
       sourcesList,
       researchType,
     })
@@ -610,53 +857,432 @@ const runStepsConcurrently = async ({
         stepIndex: i,
         totalSteps: steps.length,
         toolConfig,
+        stats,
+        saturation: step.requires_search ? createSaturation() : undefined,
+        onToolCallDelta: yieldEvent,
+        maxLoops: maxTurns,
       })
 
-      // Yield tool events
       if (stepResult?.toolEvents?.length) {
         for (const event of stepResult.toolEvents) {
           await yieldEvent(event)
         }
       }
 
-      // Yield done event
+      const durationMs = Date.now() - stepStartedAt
+      stats.recordStep({
+        stepIndex: i,
+        title: stepTitle,
+        status: 'done',
+        durationMs,
+        usage: stepResult?.usage,
+        llmCalls: stepResult?.llmCalls,
+        toolEvents: stepResult?.toolEvents,
+      })
       await yieldEvent(
         buildResearchStepEvent({
           stepIndex: i,
           totalSteps: steps.length,
           title: stepTitle,
           status: 'done',
-          durationMs: Date.now() - stepStartedAt,
+          durationMs,
         }),
       )
-
-      return { content: stepResult?.content, index: i }
+      results[i] = stepResult?.content || ''
+      await yieldEvent(buildCheckpointEvent(i, results[i], sourcesMap))
     } catch (error) {
+      const durationMs = Date.now() - stepStartedAt
+      stats.recordStep({ stepIndex: i, title: stepTitle, status: 'error', durationMs })
       await yieldEvent(
         buildResearchStepEvent({
           stepIndex: i,
           totalSteps: steps.length,
           title: stepTitle,
           status: 'error',
-          durationMs: Date.now() - stepStartedAt,
+          durationMs,
           error,
         }),
       )
-      return { content: '', index: i }
     }
+  }
+
+  // Dependencies always point to earlier steps, so scanning in order never deadlocks
+  while (finished.size < steps.length) {
+    if (signal?.aborted) throw new Error('Request aborted')
+    for (let i = nextCandidate; i < steps.length && running.size < maxParallel; i += 1) {
+      if (finished.has(i) || running.has(i)) continue
+      if (!dependencies[i].every(dependency => finished.has(dependency))) continue
+      running.set(
+        i,
+        runStep(i).then(() => {
+          running.delete(i)
+          finished.add(i)
+        }),
+      )
+    }
+    while (finished.has(nextCandidate)) nextCandidate += 1
+    await Promise.race(running.values())
+  }
+
+  // Collect findings in plan order
+  return results.filter(Boolean)
+}
+
+// Cited pages read again for the citation check (webpage_reader: extracted main content, Jina
+// and Wayback fallbacks; the first page of text is enough to check a claim against)
+const fetchCitedPage = async url =>
+  (await executeToolByName('webpage_reader', { url }))?.results?.[0]?.content
+
+/**
+ * Stream the final report, then append optional post-processing sections (citation check,
+ * glossary, formatted references)
+ * @returns {Promise<{content: string, glossary: Array|undefined, similarity, citationCheck,
+ *   references}>}
+ *   via generator return value
+ */
+const streamFinalReport = async function* ({
+  params,
+  reportPrompt,
+  trimmedMessages,
+  sourcesList,
+  sourcesMap,
+  stats,
+}) {
+  const { provider, apiKey, baseUrl, model, question, glossary = false, signal } = params
+  const reportModel = buildModel({
+    provider,
+    apiKey,
+    baseUrl,
+    model,
+    temperature: params.temperature,
+    top_k: params.top_k,
+    top_p: params.top_p,
+    frequency_penalty: params.frequency_penalty,
+    presence_penalty: params.presence_penalty,
+    tools: [],
+    streaming: true,
+    includeUsage: true,
   })
 
-  // Execute all steps concurrently
-  const results = await Promise.all(stepPromises)
+  const preferencesPrompt = buildPreferencesPrompt(getPreferences(params.userId))
+  const glossaryPrompt = buildGlossaryPrompt(params.spaceGlossary)
+  const reportMessages = [
+    {
+      role: 'system',
+      content: [reportPrompt, preferencesPrompt, glossaryPrompt].filter(Boolean).join('\n\n'),
+    },
+    ...trimmedMessages,
+    { role: 'user', content: question || '' },
+  ]
 
-  // Collect findings in order
-  return results
-    .sort((a, b) => a.index - b.index)
-    .map(r => r.content)
-    .filter(Boolean)
+  const streamIterator = await reportModel.stream(toLangChainMessages(reportMessages), {
+    signal,
+  })
+
+  const reportStartedAt = Date.now()
+  const reportUsage = emptyUsage()
+  let fullContent = ''
+  for await (const chunk of streamIterator) {
+    const messageChunk = chunk?.message ?? chunk
+    addUsage(reportUsage, extractUsage(messageChunk))
+    const contentValue = messageChunk?.content ?? chunk?.content
+    const chunkText = normalizeTextContent(contentValue)
+    if (chunkText) {
+      fullContent += chunkText
+      yield { type: 'text', content: chunkText }
+    }
+  }
+  stats.recordReport({ durationMs: Date.now() - reportStartedAt, usage: reportUsage })
+
+  // Templates can require a section (e.g. a legal disclaimer); append it if the model left it out
+  const requiredSection = resolveResearchTemplate(params.researchType)?.requiredSection
+  if (requiredSection && !requiredSection.pattern.test(fullContent)) {
+    fullContent += requiredSection.markdown
+    yield { type: 'text', content: requiredSection.markdown }
+  }
+
+  // Optional self-check: paraphrase passages copied near-verbatim from a source
+  let similarity
+  if (params.similarityCheck && sourcesMap?.size) {
+    const options = typeof params.similarityCheck === 'object' ? params.similarityCheck : {}
+    const { content, check } = await checkReportSimilarity({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      report: fullContent,
+      sourceTexts: getSourceTexts(sourcesMap),
+      sources: Array.from(sourcesMap.values()),
+      threshold: options.threshold,
+      rewrite: options.rewrite !== false,
+      signal,
+    })
+    yield { type: 'similarity_check', ...check }
+    similarity = { check, originalContent: content !== fullContent ? fullContent : undefined }
+    if (content !== fullContent) {
+      fullContent = content
+      yield { type: 'report_revised', content, reason: 'similarity' }
+    }
+  }
+
+  // Academic reports verify their citations unless citationCheck is false
+  const citationOptions = params.citationCheck ?? params.researchType === 'academic'
+  let citationCheck
+  if (citationOptions) {
+    const options = typeof citationOptions === 'object' ? citationOptions : {}
+    citationCheck = await checkCitations({
+      report: fullContent,
+      sources: Array.from(sourcesMap?.values() || []),
+      sourceTexts: sourcesMap ? getSourceTexts(sourcesMap) : undefined,
+      threshold: options.threshold,
+      fetchPage: options.refetch ? fetchCitedPage : undefined,
+      signal,
+    })
+    yield { type: 'citation_check', ...citationCheck }
+    const citationMarkdown = formatCitationCheckMarkdown(citationCheck)
+    if (citationMarkdown) {
+      fullContent += citationMarkdown
+      yield { type: 'text', content: citationMarkdown }
+    }
+  }
+
+  let glossaryEntries
+  if (glossary) {
+    glossaryEntries = await generateGlossary({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      report: fullContent,
+      sourcesList,
+      signal,
+    })
+    const glossaryMarkdown = formatGlossaryMarkdown(glossaryEntries)
+    if (glossaryMarkdown) {
+      yield { type: 'glossary', terms: glossaryEntries }
+      fullContent += glossaryMarkdown
+      yield { type: 'text', content: glossaryMarkdown }
+    }
+  }
+
+  // Numbers and dates in the reader's locale, rewritten deterministically (reportLocale)
+  const reportLocale = resolveReportLocale(params.reportLocale, getPreferences(params.userId))
+  if (reportLocale) {
+    const { content, changes } = formatForLocale(fullContent, reportLocale)
+    if (changes) {
+      fullContent = content
+      yield { type: 'report_revised', content, reason: 'locale', locale: reportLocale, changes }
+    }
+  }
+
+  // Formatted References section (citationStyle): replaces a reference list the model wrote
+  let references
+  const citationStyle = params.citationStyle
+  if (citationStyle && sourcesMap?.size) {
+    const items = await resolveCitationItems(Array.from(sourcesMap.values()), { signal })
+    references = formatReferences(items, citationStyle)
+    yield { type: 'references', style: citationStyle, references }
+    const section = formatReferencesMarkdown(references, citationStyle)
+    if (REFERENCES_HEADING.test(fullContent)) {
+      fullContent = replaceReferencesSection(fullContent, section)
+      yield { type: 'report_revised', content: fullContent, reason: 'references' }
+    } else {
+      const appended = `\n\n## References\n\n${section}\n`
+      fullContent += appended
+      yield { type: 'text', content: appended }
+    }
+  }
+
+  // Optional proofreading of the finished report: grammar pass on the report model plus style
+  // rules; issues are reported with spans into the final content, the text is left as written
+  let proofread
+  if (params.proofread) {
+    const rules = typeof params.proofread === 'object' ? params.proofread : undefined
+    const { issues, llm } = await proofreadText({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      text: fullContent,
+      rules,
+      signal,
+    })
+    proofread = { issues, count: issues.length, llm }
+    yield { type: 'proofread', ...proofread }
+  }
+
+  return {
+    content: fullContent,
+    glossary: glossaryEntries?.length ? glossaryEntries : undefined,
+    similarity,
+    citationCheck,
+    references,
+    proofread,
+  }
 }
 
-export const streamDeepResearch = async function* (params) {
+/**
+ * Comparative mode: research each entity in parallel, build an aligned matrix, then write the report
+ */
+const runComparativeResearch = async function* ({
+  params,
+  trimmedMessages,
+  toolModel,
+  toolConfig,
+  stats,
+}) {
+  const { provider, apiKey, baseUrl, model, question, reportStyle, signal } = params
+  const entities = normalizeEntities(params.entities)
+  if (entities.length < 2) {
+    throw new Error('Comparative research requires at least 2 entities')
+  }
+
+  const planStartedAt = Date.now()
+  const requestedCriteria = normalizeCriteria(params.criteria)
+  const criteria = requestedCriteria.length
+    ? requestedCriteria
+    : await generateCriteria({ provider, apiKey, baseUrl, model, question, entities, signal })
+  const planMeta = {
+    goal: question || '',
+    question_type: 'comparison',
+    assumptions: [],
+    entities,
+    criteria,
+    plan: entities.map((entity, index) => ({
+      step: index + 1,
+      action: `Gather evidence on ${entity}`,
+      entity,
+      requires_search: true,
+    })),
+  }
+  stats.recordPlan(Date.now() - planStartedAt)
+  yield { type: 'comparison_plan', entities, criteria }
+
+  const sourcesMap = new Map()
+  const findingsByEntity = entities.map(() => '')
+  yield* yieldWhileRunning(push =>
+    Promise.all(
+      entities.map(async (entity, i) => {
+        const stepTitle = planMeta.plan[i].action
+        push(
+          buildResearchStepEvent({
+            stepIndex: i,
+            totalSteps: entities.length,
+            title: stepTitle,
+            status: 'running',
+          }),
+        )
+        const stepStartedAt = Date.now()
+        try {
+          const stepResult = await runToolCallingStep({
+            modelInstance: toolModel,
+            baseMessages: [
+              {
+                role: 'system',
+                content: buildEntityResearchPrompt({ question, entity, entities, criteria }),
+              },
+              ...trimmedMessages,
+              { role: 'user', content: question || `Research ${entity}` },
+            ],
+            sourcesMap,
+            signal,
+            stepIndex: i,
+            totalSteps: entities.length,
+            toolConfig,
+            stats,
+            onToolCallDelta: push,
+            maxLoops: resolveMaxTurns(params.maxTurns, 'research_step'),
+          })
+          for (const event of stepResult?.toolEvents || []) push(event)
+          findingsByEntity[i] = stepResult?.content || ''
+          const durationMs = Date.now() - stepStartedAt
+          stats.recordStep({
+            stepIndex: i,
+            title: stepTitle,
+            status: 'done',
+            durationMs,
+            usage: stepResult?.usage,
+            llmCalls: stepResult?.llmCalls,
+            toolEvents: stepResult?.toolEvents,
+          })
+          push(
+            buildResearchStepEvent({
+              stepIndex: i,
+              totalSteps: entities.length,
+              title: stepTitle,
+              status: 'done',
+              durationMs,
+            }),
+          )
+        } catch (error) {
+          const durationMs = Date.now() - stepStartedAt
+          stats.recordStep({ stepIndex: i, title: stepTitle, status: 'error', durationMs })
+          push(
+            buildResearchStepEvent({
+              stepIndex: i,
+              totalSteps: entities.length,
+              title: stepTitle,
+              status: 'error',
+              durationMs,
+              error,
+            }),
+          )
+        }
+      }),
+    ),
+  )
+
+  if (signal?.aborted) throw new Error('Request aborted')
+
+  const reportSourcesList = buildSourcesList(sourcesMap)
+  const matrix = await extractComparisonMatrix({
+    provider,
+    apiKey,
+    baseUrl,
+    model,
+    question,
+    entities,
+    criteria,
+    findingsByEntity,
+    sourcesList: reportSourcesList,
+    signal,
+  })
+  yield { type: 'comparison_matrix', matrix }
+
+  const reportPrompt = buildComparativeReportPrompt({
+    question,
+    matrix,
+    findingsByEntity,
+    sourcesList: reportSourcesList,
+    reportStyle,
+  })
+  const { content, glossary, similarity, citationCheck, references, proofread } =
+    yield* streamFinalReport({
+      params,
+      reportPrompt,
+      trimmedMessages,
+      sourcesList: reportSourcesList,
+      sourcesMap,
+      stats,
+    })
+
+  yield {
+    type: 'done',
+    content,
+    original_content: similarity?.originalContent,
+    sources: sourcesMap.size ? Array.from(sourcesMap.values()) : undefined,
+    comparison: matrix,
+    glossary,
+    similarity_check: similarity?.check,
+    citation_check: citationCheck,
+    references,
+    proofread,
+    plan: planMeta,
+    stats: stats.toJSON(),
+  }
+}
+
+const runDeepResearch = async function* (params, stats) {
   const {
     provider,
     apiKey,
@@ -674,14 +1300,40 @@ export const streamDeepResearch = async function* (params) {
     toolIds = [],
     plan,
     question,
-    researchType = 'general', // 'general' or 'academic'
-    concurrentExecution = false, // NEW: enable concurrent step execution (experimental)
-    searchProvider,
+    researchType = 'general', // 'general' | 'academic' | 'comparative' | research template id
+    concurrentExecution = false, // Run independent steps in parallel
+    maxParallelSteps, // Concurrent mode: steps running at once (default DEEP_RESEARCH_MAX_PARALLEL)
+    decompose = false, // Split multi-part questions and plan each part
+    reportStyle, // 'standard' | 'executive' | 'technical' | 'eli5'
+    timeline, // Force timeline extraction on/off (defaults to history questions only)
+    numericCheck = false, // Cross-check numeric claims and surface conflicting figures
+    stalenessDays, // Financial template: age in days after which figures are flagged stale
+    sourceBias = false, // Label news sources with ownership/bias and add a source diversity note
+    sourceClusters: clusterEnabled = true, // Group many sources into sub-topics (needs embeddings)
+    noveltyThreshold, // Share of new URLs below which successive searches end a step's searching
+    searchProvider, // 'tavily' | 'searxng' | 'brave' | 'serper' | 'bing' (see services/search)
+    searchApiKey,
+    searchBaseUrl,
     tavilyApiKey,
     signal,
   } = params
 
-  const toolConfig = { searchProvider, tavilyApiKey }
+  const toolConfig = {
+    searchProvider,
+    searchApiKey,
+    searchBaseUrl,
+    tavilyApiKey,
+    proofread: { provider, apiKey, baseUrl, model },
+  }
+  // Model turns per research step (default MAX_RESEARCH_STEP_TURNS)
+  const maxTurns = resolveMaxTurns(params.maxTurns, 'research_step')
+  // Queries are deduplicated across the whole run; saturation is tracked per step
+  const seenQueries = new Set()
+  const createSaturation = () =>
+    createSaturationTracker({
+      threshold: resolveNoveltyThreshold(noveltyThreshold),
+      seenQueries,
+    })
 
   const trimmedMessages =
     typeof contextMessageLimit === 'number' && contextMessageLimit > 0
@@ -690,9 +1342,16 @@ export const streamDeepResearch = async function* (params) {
 
   const agentToolDefinitions = getToolDefinitionsByIds(toolIds)
 
-  // Add search tool based on research type
-  const searchToolId = researchType === 'academic' ? 'Tavily_academic_search' : 'Tavily_web_search'
-  const searchToolDefinition = getToolDefinitionsByIds([searchToolId])
+  // Add search tool (and template tools) based on research type
+  const template = resolveResearchTemplate(researchType)
+  const searchToolId =
+    template?.searchToolId ||
+    (researchType === 'academic' ? 'Tavily_academic_search' : 'Tavily_web_search')
+  const searchToolDefinition = getToolDefinitionsByIds([
+    searchToolId,
+    ...(template?.toolIds || []),
+    ...(researchType === 'academic' ? ACADEMIC_SEARCH_TOOL_IDS : []),
+  ])
 
   const combinedTools = [
     ...(Array.isArray(tools) ? tools : []),
@@ -705,11 +1364,13 @@ export const streamDeepResearch = async function* (params) {
 
   const normalizedTools = []
   const toolNames = new Set()
-  const excludedSearchTool = researchType === 'academic' ? 'Tavily_web_search' : null
+  const excludedToolNames = new Set(
+    template?.excludedToolIds || (researchType === 'academic' ? ['Tavily_web_search'] : []),
+  )
   for (const tool of combinedTools) {
     const name = tool?.function?.name
-    // Skip Tavily_web_search in academic research (general research can use both search tools)
-    if (excludedSearchTool && name === excludedSearchTool) continue
+    // Skip Tavily_web_search in academic/template research (general research can use both)
+    if (excludedToolNames.has(name)) continue
     if (name && toolNames.has(name)) continue
     if (name) toolNames.add(name)
     normalizedTools.push(tool)
@@ -719,22 +1380,6 @@ export const streamDeepResearch = async function* (params) {
     `[DeepResearch] Normalized tools: ${normalizedTools.map(t => t?.function?.name).join(', ')}`,
   )
 
-  const planContent =
-    typeof plan === 'string' && plan.trim().length
-      ? plan
-      : await (researchType === 'academic' ? generateAcademicResearchPlan : generateResearchPlan)(
-          provider,
-          question || '',
-          apiKey,
-          baseUrl,
-          model,
-        )
-  const planMeta = parsePlan(planContent)
-  const steps = Array.isArray(planMeta.plan) ? planMeta.plan : []
-
-  const sourcesMap = new Map()
-  const findings = []
-
   const toolModel = buildModel({
     provider,
     apiKey,
@@ -750,100 +1395,145 @@ export const streamDeepResearch = async function* (params) {
     streaming: false,
   })
 
-  // Execute research steps (sequential or concurrent mode)
-  if (concurrentExecution) {
-    // CONCURRENT MODE: Execute all steps in parallel using Promise.all
-    console.log('[DeepResearch] Running steps concurrently (experimental)')
-
-    // First, immediately yield pending state for all steps
-    for (let i = 0; i < steps.length; i++) {
-      yield buildResearchStepEvent({
-        stepIndex: i,
-        totalSteps: steps.length,
-        title: steps[i].action || 'Research',
-        status: 'pending',
-      })
-    }
-
-    const eventQueue = []
-    let resolveNextEvent = null
+  if (researchType === 'comparative') {
+    yield* runComparativeResearch({ params, trimmedMessages, toolModel, toolConfig, stats })
+    return
+  }
 
-    // Callback that pushes to queue and wakes up the consumer
-    const yieldEvent = async event => {
-      eventQueue.push(event)
-      if (resolveNextEvent) {
-        resolveNextEvent()
-        resolveNextEvent = null
-      }
-    }
+  // Resuming a persisted run: keep its plan, sources, and finished steps (see resumeDeepResearch)
+  const resumed = params.resume
+  const completedSteps = new Map(
+    Object.entries(resumed?.completedSteps || {}).map(([index, content]) => [
+      Number(index),
+      content,
+    ]),
+  )
 
-    // Start execution WITHOUT awaiting it immediately
-    // We catch errors to avoid unhandled rejections, but we'll check the result in the loop
-    let isWorkDone = false
-    let workResult = []
-    let workError = null
+  // Following up a finished run: plan only the new question, on top of its findings and sources
+  const followup = params.followup
 
-    const workPromise = runStepsConcurrently({
-      steps,
-      planMeta,
-      trimmedMessages,
-      question,
-      toolModel,
-      sourcesMap,
-      findings,
-      signal,
-      toolConfig,
-      researchType,
-      yieldEvent,
+  const planStartedAt = Date.now()
+  const hasClientPlan = typeof plan === 'string' && plan.trim().length > 0
+  const planGenerator =
+    researchType === 'academic' ? generateAcademicResearchPlan : generateResearchPlan
+  const generatePlan = prompt =>
+    planGenerator(provider, prompt, apiKey, baseUrl, model, {
+      planInstructions: template?.planInstructions,
     })
-      .then(res => {
-        isWorkDone = true
-        workResult = res
-        // Wake up loop if it's waiting
-        if (resolveNextEvent) {
-          resolveNextEvent()
-          resolveNextEvent = null
-        }
-        return res
-      })
-      .catch(err => {
-        isWorkDone = true
-        workError = err
-        // Wake up loop if it's waiting
-        if (resolveNextEvent) {
-          resolveNextEvent()
-          resolveNextEvent = null
-        }
-      })
-
-    // Loop until work is done AND queue is empty
-    while (!isWorkDone || eventQueue.length > 0) {
-      // Flush currently available events
-      while (eventQueue.length > 0) {
-        yield eventQueue.shift()
-      }
-
-      // If work is not done and queue is empty, wait for next event or completion
-      if (!isWorkDone && eventQueue.length === 0) {
-        await new Promise(resolve => {
-          resolveNextEvent = resolve
-        })
+  const subQuestions =
+    decompose && !hasClientPlan && !resumed?.plan && !followup
+      ? await decomposeQuestion({ provider, apiKey, baseUrl, model, question, signal })
+      : null
+
+  let planMeta
+  if (resumed?.plan) {
+    planMeta = resumed.plan
+  } else if (subQuestions) {
+    yield { type: 'decomposition', sub_questions: subQuestions }
+    // Plan every sub-question in parallel, then merge steps in sub-question order
+    const subResults = await Promise.all(
+      subQuestions.map(async subQuestion =>
+        parsePlan(await generatePlan(subQuestion), {
+          provider,
+          apiKey,
+          baseUrl,
+          model,
+          signal,
+        }),
+      ),
+    )
+    for (const [subIndex, result] of subResults.entries()) {
+      if (!result.valid) yield toPlanInvalidEvent(result, subIndex)
+    }
+    const subPlans = subResults.map(result => result.plan)
+    planMeta = {
+      goal: question || '',
+      question_type: subPlans[0]?.question_type || 'analysis',
+      assumptions: Array.from(new Set(subPlans.flatMap(item => item.assumptions || []))),
+      sub_questions: subQuestions,
+      plan: subPlans.flatMap((subPlan, subIndex) =>
+        (subPlan.plan || []).map(step => ({ ...step, sub_question: subIndex })),
+      ),
+    }
+    planMeta.plan = planMeta.plan.map((step, index) => ({ ...step, step: index + 1 }))
+  } else {
+    const planContent = hasClientPlan
+      ? plan
+      : await generatePlan(
+          followup ? buildFollowupPlanRequest(followup, question) : question || '',
+        )
+    const result = await parsePlan(planContent, { provider, apiKey, baseUrl, model, signal })
+    if (!result.valid) yield toPlanInvalidEvent(result)
+    planMeta = result.plan
+    // Kept with the plan, so a resumed follow-up still sees the earlier findings
+    if (followup) {
+      planMeta = {
+        ...planMeta,
+        followup: {
+          run_id: followup.runId,
+          question: followup.question,
+          findings: followup.findings,
+        },
       }
     }
+  }
+  stats.recordPlan(Date.now() - planStartedAt)
+  const steps = Array.isArray(planMeta.plan) ? planMeta.plan : []
+  if (!resumed?.plan) yield { type: 'research_checkpoint', plan: planMeta }
 
-    // Check for errors after completion
-    if (workError) {
-      throw workError
-    }
+  // Seeded in their stored order, so [n] keeps pointing at the same source
+  const sourcesMap = new Map()
+  for (const source of resumed?.sources || followup?.sources || []) {
+    if (source?.url) sourcesMap.set(source.url, source)
+  }
+  const findings = []
 
+  // Execute research steps (sequential or concurrent mode)
+  if (concurrentExecution) {
+    // CONCURRENT MODE: independent steps run in parallel, dependent steps wait for their inputs
+    console.log('[DeepResearch] Running steps concurrently')
+
+    const workResult = yield* yieldWhileRunning(yieldEvent =>
+      runStepsConcurrently({
+        steps,
+        planMeta,
+        trimmedMessages,
+        question,
+        toolModel,
+        sourcesMap,
+        signal,
+        toolConfig,
+        researchType,
+        stats,
+        createSaturation,
+        maxParallel: resolveMaxParallelSteps(maxParallelSteps),
+        maxTurns,
+        completedSteps,
+        yieldEvent,
+      }),
+    )
     findings.push(...workResult)
   } else {
     // SEQUENTIAL MODE: Original implementation (default)
     console.log('[DeepResearch] Running steps sequentially')
 
     for (let i = 0; i < steps.length; i += 1) {
+      if (signal?.aborted) throw new Error('Request aborted')
       const step = steps[i] || {}
       const stepTitle = step.action || 'Research'
+      if (completedSteps.has(i)) {
+        if (completedSteps.get(i)) findings.push(completedSteps.get(i))
+        yield {
+          ...buildResearchStepEvent({
+            stepIndex: i,
+            totalSteps: steps.length,
+            title: stepTitle,
+            status: 'done',
+          }),
+          resumed: true,
+        }
+        continue
+      }
       const stepStartedAt = Date.now()
       yield buildResearchStepEvent({
         stepIndex: i,
@@ -869,15 +1559,21 @@ export const streamDeepResearch = async function* (params) {
       ]
 
       try {
-        const stepResult = await runToolCallingStep({
-          modelInstance: toolModel,
-          baseMessages: stepMessages,
-          sourcesMap,
-          signal,
-          stepIndex: i,
-          totalSteps: steps.length,
-          toolConfig,
-        })
+        const stepResult = yield* yieldWhileRunning(onToolCallDelta =>
+          runToolCallingStep({
+            modelInstance: toolModel,
+            baseMessages: stepMessages,
+            sourcesMap,
+            signal,
+            stepIndex: i,
+            totalSteps: steps.length,
+            toolConfig,
+            stats,
+            saturation: step.requires_search ? createSaturation() : undefined,
+            onToolCallDelta,
+            maxLoops: maxTurns,
+          }),
+        )
 
         if (stepResult?.toolEvents?.length) {
           for (const event of stepResult.toolEvents) {
@@ -885,20 +1581,33 @@ export const streamDeepResearch = async function* (params) {
           }
         }
         if (stepResult?.content) findings.push(stepResult.content)
+        yield buildCheckpointEvent(i, stepResult?.content || '', sourcesMap)
+        const durationMs = Date.now() - stepStartedAt
+        stats.recordStep({
+          stepIndex: i,
+          title: stepTitle,
+          status: 'done',
+          durationMs,
+          usage: stepResult?.usage,
+          llmCalls: stepResult?.llmCalls,
+          toolEvents: stepResult?.toolEvents,
+        })
         yield buildResearchStepEvent({
           stepIndex: i,
           totalSteps: steps.length,
           title: stepTitle,
           status: 'done',
-          durationMs: Date.now() - stepStartedAt,
+          durationMs,
         })
       } catch (error) {
+        const durationMs = Date.now() - stepStartedAt
+        stats.recordStep({ stepIndex: i, title: stepTitle, status: 'error', durationMs })
         yield buildResearchStepEvent({
           stepIndex: i,
           totalSteps: steps.length,
           title: stepTitle,
           status: 'error',
-          durationMs: Date.now() - stepStartedAt,
+          durationMs,
           error,
         })
       }
@@ -906,54 +1615,379 @@ export const streamDeepResearch = async function* (params) {
   }
 
   const reportSourcesList = buildSourcesList(sourcesMap)
+
+  // History questions get a structured timeline the frontend can render interactively
+  let timelineEvents
+  if (timeline ?? isTimelineQuestion(planMeta)) {
+    timelineEvents = await extractTimeline({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      question,
+      findings,
+      sourcesList: reportSourcesList,
+      signal,
+    })
+    if (timelineEvents.length) {
+      yield { type: 'timeline', events: timelineEvents }
+    }
+  }
+
+  let numericConflicts
+  if (numericCheck) {
+    const { conflicts } = await analyzeNumericClaims({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      question,
+      findings,
+      sourcesList: reportSourcesList,
+      signal,
+    })
+    numericConflicts = conflicts
+    yield { type: 'data_conflicts', conflicts }
+  }
+
+  let financialMetrics
+  if (template?.extractMetrics) {
+    financialMetrics = await extractFinancialMetrics({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      question,
+      findings,
+      sourcesList: reportSourcesList,
+      stalenessDays,
+      signal,
+    })
+    yield { type: 'financial_metrics', metrics: financialMetrics }
+  }
+
+  let sourceDiversity
+  if (sourceBias && sourcesMap.size) {
+    const annotated = annotateSources(Array.from(sourcesMap.values()))
+    sourceDiversity = { sources: annotated, diversity: summarizeSourceDiversity(annotated) }
+    yield { type: 'source_diversity', ...sourceDiversity }
+  }
+
+  // Large source sets are grouped by sub-topic to structure the findings and the UI source map
+  const sourceClusters = clusterEnabled
+    ? await clusterSources({
+        provider,
+        apiKey,
+        baseUrl,
+        model,
+        question,
+        sources: Array.from(sourcesMap.values()),
+        signal,
+      })
+    : null
+  if (sourceClusters) {
+    yield { type: 'source_clusters', ...sourceClusters }
+  }
+
   const reportPrompt = buildFinalReportPrompt({
     planMeta,
     question,
     findings,
     sourcesList: reportSourcesList,
     researchType, // Pass researchType to report prompt
+    reportStyle,
+    timeline: timelineEvents,
+    dataConflicts: numericConflicts,
+    financialMetrics,
+    sourceDiversity,
+    sourceClusters,
   })
 
   console.log(`[DeepResearch] Building final report prompt for type: ${researchType}`)
 
-  const reportModel = buildModel({
-    provider,
-    apiKey,
-    baseUrl,
-    model,
-    temperature,
-    top_k,
-    top_p,
-    frequency_penalty,
-    presence_penalty,
-    tools: [],
-    streaming: true,
+  const {
+    content: fullContent,
+    glossary: glossaryEntries,
+    similarity,
+    citationCheck,
+    references,
+    proofread,
+  } = yield* streamFinalReport({
+    params,
+    reportPrompt,
+    trimmedMessages,
+    sourcesList: reportSourcesList,
+    sourcesMap,
+    stats,
   })
 
-  const reportMessages = [
-    { role: 'system', content: reportPrompt },
-    ...trimmedMessages,
-    { role: 'user', content: question || '' },
-  ]
+  yield {
+    type: 'done',
+    content: fullContent,
+    original_content: similarity?.originalContent,
+    sources:
+      sourceDiversity?.sources ||
+      (sourcesMap.size ? Array.from(sourcesMap.values()) : undefined),
+    source_diversity: sourceDiversity?.diversity,
+    source_clusters: sourceClusters?.clusters,
+    glossary: glossaryEntries,
+    timeline: timelineEvents?.length ? timelineEvents : undefined,
+    data_conflicts: numericConflicts,
+    financial_metrics: financialMetrics,
+    similarity_check: similarity?.check,
+    citation_check: citationCheck,
+    references,
+    proofread,
+    plan: planMeta,
+    stats: stats.toJSON(),
+  }
+}
 
-  const streamIterator = await reportModel.stream(toLangChainMessages(reportMessages), {
-    signal,
-  })
+const persistRun = run => {
+  try {
+    saveResearchRun(run)
+  } catch (error) {
+    console.warn('[DeepResearch] Failed to persist run:', error.message)
+  }
+}
 
-  let fullContent = ''
-  for await (const chunk of streamIterator) {
-    const messageChunk = chunk?.message ?? chunk
-    const contentValue = messageChunk?.content ?? chunk?.content
-    const chunkText = normalizeTextContent(contentValue)
-    if (chunkText) {
-      fullContent += chunkText
-      yield { type: 'text', content: chunkText }
+// Runs executing in this process; a "running" record that is not listed here was interrupted
+const activeRunIds = new Set()
+
+export const isResearchRunActive = id => activeRunIds.has(id)
+
+// Credentials are never stored; a resume supplies them again. A follow-up's earlier findings
+// live in its plan and its sources in the run.
+const UNSTORED_PARAMS = new Set([
+  'apiKey',
+  'searchApiKey',
+  'tavilyApiKey',
+  'signal',
+  'resume',
+  'followup',
+])
+
+const toStoredRequest = params =>
+  Object.fromEntries(
+    Object.entries(params).filter(
+      ([key, value]) =>
+        !UNSTORED_PARAMS.has(key) && value !== undefined && typeof value !== 'function',
+    ),
+  )
+
+/**
+ * Drive a run and keep its record current: the plan and every finished step are checkpointed
+ * as they happen, the report when it is done
+ */
+const trackRun = async function* (params, run, stats) {
+  if (run) activeRunIds.add(run.id)
+  try {
+    for await (const event of runDeepResearch(params, stats)) {
+      if (event?.type === 'research_checkpoint') {
+        if (run) {
+          run = event.plan
+            ? { ...run, plan: event.plan }
+            : {
+                ...run,
+                completed_steps: { ...run.completed_steps, [event.step_index]: event.content },
+                sources: event.sources,
+              }
+          persistRun(run)
+        }
+        continue
+      }
+      if (event?.type === 'done') {
+        const { plan: planMeta, ...doneEvent } = event
+        const searchLog = stats.getSearchLog()
+        if (run) {
+          persistRun({
+            ...run,
+            status: 'done',
+            finishedAt: new Date().toISOString(),
+            error: undefined,
+            plan: planMeta,
+            content: doneEvent.content,
+            sources: doneEvent.sources || [],
+            glossary: doneEvent.glossary,
+            comparison: doneEvent.comparison,
+            timeline: doneEvent.timeline,
+            data_conflicts: doneEvent.data_conflicts,
+            citation_check: doneEvent.citation_check,
+            search_log: searchLog,
+            stats: doneEvent.stats,
+          })
+        }
+        yield {
+          ...doneEvent,
+          search_log: searchLog,
+          runId: run?.id,
+          usage: toUsagePayload(doneEvent.stats?.tokens),
+        }
+        continue
+      }
+      yield event
+    }
+  } catch (error) {
+    if (run) {
+      persistRun({
+        ...run,
+        status: params.signal?.aborted ? 'aborted' : 'error',
+        finishedAt: new Date().toISOString(),
+        error: error.message,
+        stats: stats.toJSON(),
+        search_log: stats.getSearchLog(),
+      })
     }
+    throw error
+  } finally {
+    if (run) activeRunIds.delete(run.id)
   }
+}
+
+/**
+ * Run deep research and persist the run (plan, per-step findings, sources, report, stats)
+ */
+const startRun = (params, fields) => {
+  try {
+    const run = createResearchRun({
+      question: params.question,
+      researchType: params.researchType,
+      provider: params.provider,
+      model: params.model,
+      request: toStoredRequest(params),
+    })
+    return fields ? saveResearchRun({ ...run, ...fields }) : run
+  } catch (error) {
+    console.warn('[DeepResearch] Failed to create run record:', error.message)
+    return null
+  }
+}
 
+export const streamDeepResearch = async function* (params) {
+  const stats = createResearchStats()
+  yield* trackRun(params, startRun(params), stats)
+}
+
+const MAX_FOLLOWUP_FINDINGS = 12
+const MAX_FOLLOWUP_FINDING_CHARS = 2000
+
+const notFound = id =>
+  new QurioError(ErrorCode.InvalidRequest, `Research run not found: ${id}`, { status: 404 })
+
+/**
+ * Check that a persisted run can be resumed
+ * @returns {Object} The run record
+ * @throws {QurioError} 404 when it does not exist, 409 when it cannot be resumed
+ */
+export const getResumableRun = id => {
+  const run = getResearchRun(id)
+  const conflict = message => new QurioError(ErrorCode.InvalidRequest, message, { status: 409 })
+  if (!run) throw notFound(id)
+  if (run.status === 'done') throw conflict(`Research run already finished: ${id}`)
+  if (isResearchRunActive(id)) throw conflict(`Research run is still running: ${id}`)
+  if (!run.request) throw conflict(`Research run was stored without its request: ${id}`)
+  if (run.researchType === 'comparative') {
+    throw conflict('Comparative research runs cannot be resumed')
+  }
+  return run
+}
+
+/**
+ * Continue an interrupted, failed, or aborted run: the stored plan is reused (no new planning
+ * phase), finished steps keep their findings and sources, and only the remaining steps and the
+ * report are run
+ * @param {Object} run - From getResumableRun
+ * @param {Object} overrides - Credentials (apiKey, searchApiKey, tavilyApiKey) and signal
+ */
+export const resumeDeepResearch = async function* (run, overrides = {}) {
+  const stats = createResearchStats()
+  const params = {
+    ...run.request,
+    ...overrides,
+    resume: {
+      plan: run.plan,
+      completedSteps: run.completed_steps,
+      sources: run.sources,
+    },
+  }
+  const resumedRun = {
+    ...run,
+    status: 'running',
+    finishedAt: null,
+    error: undefined,
+    resumedAt: new Date().toISOString(),
+  }
+  persistRun(resumedRun)
   yield {
-    type: 'done',
-    content: fullContent,
-    sources: sourcesMap.size ? Array.from(sourcesMap.values()) : undefined,
+    type: 'research_resumed',
+    runId: run.id,
+    completed_steps: Object.keys(run.completed_steps || {}).length,
+    total_steps: Array.isArray(run.plan?.plan) ? run.plan.plan.length : null,
+  }
+  yield* trackRun(params, resumedRun, stats)
+}
+
+/**
+ * Check that a persisted run can be followed up
+ * @returns {Object} The run record
+ * @throws {QurioError} 404 when it does not exist, 409 when it has not finished or is comparative
+ */
+export const getFollowupRun = id => {
+  const run = getResearchRun(id)
+  const conflict = message => new QurioError(ErrorCode.InvalidRequest, message, { status: 409 })
+  if (!run) throw notFound(id)
+  if (run.status !== 'done') throw conflict(`Research run has not finished: ${id}`)
+  if (!run.request) throw conflict(`Research run was stored without its request: ${id}`)
+  if (run.researchType === 'comparative') {
+    throw conflict('Comparative research runs cannot be followed up')
+  }
+  return run
+}
+
+// A run's step findings in plan order, after those of the run it followed up
+const collectFindings = run => {
+  const own = Object.entries(run.completed_steps || {})
+    .sort(([a], [b]) => Number(a) - Number(b))
+    .map(([, content]) => content)
+  return [...(run.plan?.followup?.findings || []), ...own]
+    .filter(Boolean)
+    .slice(-MAX_FOLLOWUP_FINDINGS)
+    .map(item =>
+      item.length > MAX_FOLLOWUP_FINDING_CHARS
+        ? `${item.slice(0, MAX_FOLLOWUP_FINDING_CHARS)}…`
+        : item,
+    )
+}
+
+/**
+ * Research a refinement question on top of a finished run, as a new run: the plan covers only
+ * the new question, the earlier findings are context for its steps and report, and the earlier
+ * sources keep their numbers (new sources are numbered after them)
+ * @param {Object} run - From getFollowupRun
+ * @param {Object} options - question (the follow-up question), credentials (apiKey,
+ *   searchApiKey, tavilyApiKey) and signal
+ */
+export const followupDeepResearch = async function* (run, { question, ...overrides }) {
+  const stats = createResearchStats()
+  const sources = Array.isArray(run.sources) ? run.sources : []
+  const params = {
+    ...run.request,
+    ...overrides,
+    question,
+    plan: undefined,
+    followup: {
+      runId: run.id,
+      question: run.question,
+      findings: collectFindings(run),
+      sources,
+    },
+  }
+  const followupRun = startRun(params, { parentRunId: run.id, sources })
+  yield {
+    type: 'research_followup',
+    runId: followupRun?.id,
+    parent_run_id: run.id,
+    reused_sources: sources.length,
   }
+  yield* trackRun(params, followupRun, stats)
 }
@@ -0,0 +1,272 @@
+/**
+ * Document text extraction
+ * Plain text from uploaded files for the knowledge base: .txt/.md as-is, .docx from its
+ * word/document.xml, and .pdf from its (Flate-compressed) content streams. Only built-in modules
+ * are used, so PDF support is best-effort: text-based PDFs work, scanned PDFs have no text layer
+ * and are rejected.
+ */
+
+import path from 'path'
+import zlib from 'zlib'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+
+const FORMATS_BY_EXTENSION = {
+  '.txt': 'text',
+  '.text': 'text',
+  '.md': 'markdown',
+  '.markdown': 'markdown',
+  '.pdf': 'pdf',
+  '.docx': 'docx',
+}
+const FORMATS_BY_MIME = {
+  'text/plain': 'text',
+  'text/markdown': 'markdown',
+  'application/pdf': 'pdf',
+  'application/vnd.openxmlformats-officedocument.wordprocessingml.document': 'docx',
+}
+export const SUPPORTED_DOCUMENT_FORMATS = ['text', 'markdown', 'pdf', 'docx']
+
+const unsupported = message => new QurioError(ErrorCode.InvalidRequest, message)
+
+/**
+ * Detect the format from file name, MIME type, or magic bytes
+ */
+export const detectDocumentFormat = (buffer, { filename, mimeType } = {}) => {
+  if (buffer.subarray(0, 5).toString('latin1') === '%PDF-') return 'pdf'
+  const byExtension = FORMATS_BY_EXTENSION[path.extname(String(filename || '')).toLowerCase()]
+  if (byExtension) return byExtension
+  const byMime = FORMATS_BY_MIME[String(mimeType || '').split(';')[0].trim().toLowerCase()]
+  if (byMime) return byMime
+  // ZIP container without a known name: only .docx is accepted
+  if (buffer.length >= 4 && buffer.readUInt32LE(0) === 0x04034b50) return 'docx'
+  return null
+}
+
+// --- DOCX -------------------------------------------------------------------
+
+// Read one entry of a ZIP archive via the central directory
+const readZipEntry = (buffer, entryName) => {
+  let eocd = -1
+  for (let offset = buffer.length - 22; offset >= Math.max(0, buffer.length - 65557); offset -= 1) {
+    if (buffer.readUInt32LE(offset) === 0x06054b50) {
+      eocd = offset
+      break
+    }
+  }
+  if (eocd === -1) throw unsupported('Invalid .docx file: ZIP directory not found')
+  const entries = buffer.readUInt16LE(eocd + 10)
+  let offset = buffer.readUInt32LE(eocd + 16)
+  for (let index = 0; index < entries; index += 1) {
+    if (buffer.readUInt32LE(offset) !== 0x02014b50) break
+    const method = buffer.readUInt16LE(offset + 10)
+    const compressedSize = buffer.readUInt32LE(offset + 20)
+    const nameLength = buffer.readUInt16LE(offset + 28)
+    const extraLength = buffer.readUInt16LE(offset + 30)
+    const commentLength = buffer.readUInt16LE(offset + 32)
+    const localOffset = buffer.readUInt32LE(offset + 42)
+    const name = buffer.toString('utf8', offset + 46, offset + 46 + nameLength)
+    if (name === entryName) {
+      const localHeaderLength =
+        30 + buffer.readUInt16LE(localOffset + 26) + buffer.readUInt16LE(localOffset + 28)
+      const dataStart = localOffset + localHeaderLength
+      const data = buffer.subarray(dataStart, dataStart + compressedSize)
+      if (method === 0) return data
+      if (method === 8) return zlib.inflateRawSync(data)
+      throw unsupported(`Unsupported ZIP compression method: ${method}`)
+    }
+    offset += 46 + nameLength + extraLength + commentLength
+  }
+  return null
+}
+
+const XML_ENTITIES = { amp: '&', lt: '<', gt: '>', quot: '"', apos: "'" }
+
+const decodeXmlEntities = text =>
+  text.replace(/&(#x[0-9a-f]+|#\d+|\w+);/gi, (match, entity) => {
+    if (entity[0] === '#') {
+      const code =
+        entity[1].toLowerCase() === 'x' ? parseInt(entity.slice(2), 16) : parseInt(entity.slice(1))
+      return Number.isFinite(code) ? String.fromCodePoint(code) : match
+    }
+    return XML_ENTITIES[entity] ?? match
+  })
+
+const extractDocx = buffer => {
+  const xml = readZipEntry(buffer, 'word/document.xml')
+  if (!xml) throw unsupported('Invalid .docx file: word/document.xml not found')
+  return decodeXmlEntities(
+    xml
+      .toString('utf8')
+      .replace(/<w:tab\/>/g, '\t')
+      .replace(/<w:(?:br|cr)\/>/g, '\n')
+      .replace(/<\/w:p>/g, '\n\n')
+      .replace(/<[^>]+>/g, ''),
+  )
+}
+
+// --- PDF --------------------------------------------------------------------
+
+const PDF_ESCAPES = { n: '\n', r: '\r', t: '\t', b: '\b', f: '\f' }
+
+// Literal string starting after "(" at `start`; returns [text, index after ")"]
+const readPdfString = (source, start) => {
+  let depth = 1
+  let text = ''
+  let index = start
+  while (index < source.length && depth > 0) {
+    const char = source[index]
+    if (char === '\\') {
+      const next = source[index + 1]
+      if (PDF_ESCAPES[next]) {
+        text += PDF_ESCAPES[next]
+        index += 2
+      } else if (/[0-7]/.test(next)) {
+        const octal = source.slice(index + 1, index + 4).match(/^[0-7]{1,3}/)[0]
+        text += String.fromCharCode(parseInt(octal, 8))
+        index += 1 + octal.length
+      } else if (next === '\r' || next === '\n') {
+        index += next === '\r' && source[index + 2] === '\n' ? 3 : 2
+      } else {
+        text += next ?? ''
+        index += 2
+      }
+      continue
+    }
+    if (char === '(') depth += 1
+    if (char === ')') depth -= 1
+    if (depth > 0) text += char
+    index += 1
+  }
+  return [text, index]
+}
+
+const decodeHexString = hex => {
+  const clean = hex.replace(/\s+/g, '')
+  const bytes = Buffer.from(clean.length % 2 ? `${clean}0` : clean, 'hex')
+  // Two-byte strings starting with a BOM are UTF-16BE; anything else is a single-byte encoding
+  if (bytes[0] === 0xfe && bytes[1] === 0xff) return bytes.subarray(2).swap16().toString('utf16le')
+  return bytes.toString('latin1')
+}
+
+const OPERATOR_PATTERN = /[A-Za-z'"*]+/y
+
+// Text shown by one content stream: Tj/TJ/'/" operands, with line breaks on moves and blocks
+const extractContentStreamText = source => {
+  let text = ''
+  let operands = []
+  let index = 0
+  while (index < source.length) {
+    const char = source[index]
+    if (char === '(') {
+      const [value, next] = readPdfString(source, index + 1)
+      operands.push(value)
+      index = next
+      continue
+    }
+    if (char === '<' && source[index + 1] !== '<') {
+      const end = source.indexOf('>', index)
+      if (end === -1) break
+      operands.push(decodeHexString(source.slice(index + 1, end)))
+      index = end + 1
+      continue
+    }
+    if (char === '[') {
+      operands.push('[')
+      index += 1
+      continue
+    }
+    if (char === ']') {
+      const start = operands.lastIndexOf('[')
+      const parts = start === -1 ? [] : operands.splice(start)
+      operands.push(parts.slice(1).join(''))
+      index += 1
+      continue
+    }
+    OPERATOR_PATTERN.lastIndex = index
+    const operator = OPERATOR_PATTERN.exec(source)
+    if (operator) {
+      const op = operator[0]
+      if (op === 'Tj' || op === 'TJ') text += operands.join('')
+      else if (op === "'" || op === '"') text += `\n${operands.join('')}`
+      else if (op === 'Td' || op === 'TD' || op === 'T*') text += '\n'
+      else if (op === 'ET') text += '\n\n'
+      operands = []
+      index += op.length
+      continue
+    }
+    index += 1
+  }
+  return text
+}
+
+// Images, embedded fonts, metadata, and cross-reference/object streams carry no page text
+const BINARY_STREAM_PATTERN =
+  /\/Subtype\s*\/Image|\/Length[123]\b|\/Type\s*\/(?:XRef|ObjStm|Metadata)/
+
+const extractPdf = buffer => {
+  const raw = buffer.toString('latin1')
+  const streamPattern = /<<((?:(?!>>)[\s\S])*?)>>\s*stream\r?\n/g
+  const parts = []
+  for (let match = streamPattern.exec(raw); match; match = streamPattern.exec(raw)) {
+    const dictionary = match[1]
+    const start = match.index + match[0].length
+    const end = raw.indexOf('endstream', start)
+    if (end === -1) break
+    streamPattern.lastIndex = end
+    if (BINARY_STREAM_PATTERN.test(dictionary)) continue
+    let data = buffer.subarray(start, end)
+    if (/\/FlateDecode/.test(dictionary)) {
+      try {
+        data = zlib.inflateSync(data)
+      } catch {
+        continue
+      }
+    } else if (/\/Filter/.test(dictionary)) {
+      continue
+    }
+    const source = data.toString('latin1')
+    if (/\bBT\b/.test(source)) parts.push(extractContentStreamText(source))
+  }
+  return parts.join('\n')
+}
+
+// --- Entry point --------------------------------------------------------------
+
+const cleanText = text =>
+  text
+    .replace(/\r\n?/g, '\n')
+    .replace(/[^\S\n]+/g, ' ')
+    .replace(/ *\n */g, '\n')
+    .replace(/\n{3,}/g, '\n\n')
+    .trim()
+
+/**
+ * Extract plain text from an uploaded document
+ * @param {Buffer} buffer
+ * @param {Object} [options]
+ * @param {string} [options.filename]
+ * @param {string} [options.mimeType]
+ * @returns {{ format: string, text: string }}
+ */
+export const extractDocumentText = (buffer, { filename, mimeType } = {}) => {
+  const format = detectDocumentFormat(buffer, { filename, mimeType })
+  if (!format) {
+    throw unsupported(
+      `Unsupported document type${filename ? `: ${filename}` : ''}. ` +
+        'Supported: .txt, .md, .pdf, .docx',
+    )
+  }
+  let text
+  if (format === 'pdf') text = extractPdf(buffer)
+  else if (format === 'docx') text = extractDocx(buffer)
+  else text = buffer.toString('utf8').replace(/^\uFEFF/, '')
+  text = cleanText(text)
+  if (!text) {
+    throw unsupported(
+      format === 'pdf'
+        ? 'No extractable text in PDF (scanned documents need OCR first)'
+        : 'Document contains no text',
+    )
+  }
+  return { format, text }
+}
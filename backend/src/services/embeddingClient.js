@@ -0,0 +1,95 @@
+/**
+ * Embedding client
+ * OpenAI-compatible /embeddings calls for backend features (code search, retrieval).
+ * Configured per call or via EMBEDDING_BASE_URL / EMBEDDING_API_KEY / EMBEDDING_MODEL.
+ * With a provider (EMBEDDING_PROVIDER: openai, gemini, siliconflow, ...) requests go through
+ * the embedding providers instead, and the model defaults to the provider's.
+ */
+
+import { getEmbeddingProvider } from './providers/embeddings/index.js'
+
+const DEFAULT_BATCH_SIZE = 64
+const DEFAULT_BASE_URL = 'https://api.openai.com/v1'
+
+/**
+ * Resolve embedding settings; returns null when embeddings are not configured
+ */
+export const resolveEmbeddingConfig = (overrides = {}) => {
+  const provider = overrides.provider || process.env.EMBEDDING_PROVIDER || ''
+  const apiKey = overrides.apiKey || process.env.EMBEDDING_API_KEY || ''
+  if (provider) {
+    if (!apiKey) return null
+    const embeddingProvider = getEmbeddingProvider(provider)
+    const baseUrl =
+      overrides.baseUrl || process.env.EMBEDDING_BASE_URL || embeddingProvider.defaultBaseUrl
+    return {
+      provider,
+      apiKey,
+      model: overrides.model || process.env.EMBEDDING_MODEL || embeddingProvider.defaultModel,
+      baseUrl: baseUrl.replace(/\/$/, ''),
+    }
+  }
+  const model = overrides.model || process.env.EMBEDDING_MODEL || ''
+  if (!apiKey || !model) return null
+  const baseUrl = overrides.baseUrl || process.env.EMBEDDING_BASE_URL || DEFAULT_BASE_URL
+  return { apiKey, model, baseUrl: baseUrl.replace(/\/$/, '') }
+}
+
+/**
+ * Embed texts in batches
+ * @returns {Promise<number[][]>} One vector per input, in order
+ */
+export const embedTexts = async (
+  texts,
+  config,
+  { batchSize = DEFAULT_BATCH_SIZE, signal } = {},
+) => {
+  if (config.provider) {
+    const { embeddings } = await getEmbeddingProvider(config.provider).embed(texts, {
+      ...config,
+      batchSize,
+      signal,
+    })
+    return embeddings
+  }
+  const vectors = []
+  for (let offset = 0; offset < texts.length; offset += batchSize) {
+    const batch = texts.slice(offset, offset + batchSize)
+    const response = await fetch(`${config.baseUrl}/embeddings`, {
+      method: 'POST',
+      headers: {
+        Authorization: `Bearer ${config.apiKey}`,
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify({ model: config.model, input: batch }),
+      signal,
+    })
+    if (!response.ok) {
+      const message = await response.text().catch(() => '')
+      throw new Error(message || `Embedding request failed: HTTP ${response.status}`)
+    }
+    const data = await response.json()
+    const items = Array.isArray(data?.data) ? data.data : []
+    if (items.length !== batch.length) {
+      throw new Error('Embedding response did not contain one vector per input')
+    }
+    items
+      .slice()
+      .sort((a, b) => (a.index ?? 0) - (b.index ?? 0))
+      .forEach(item => vectors.push(item.embedding))
+  }
+  return vectors
+}
+
+export const cosineSimilarity = (a, b) => {
+  if (!Array.isArray(a) || !Array.isArray(b) || a.length !== b.length) return 0
+  let dot = 0
+  let normA = 0
+  let normB = 0
+  for (let i = 0; i < a.length; i += 1) {
+    dot += a[i] * b[i]
+    normA += a[i] * a[i]
+    normB += b[i] * b[i]
+  }
+  return normA && normB ? dot / Math.sqrt(normA * normB) : 0
+}
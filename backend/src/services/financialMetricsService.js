@@ -0,0 +1,183 @@
+/**
+ * Financial metrics service
+ * Extracts a standardized metrics table (revenue, growth, margins, ...) from research findings
+ * and flags figures whose reporting period is older than a staleness threshold.
+ */
+
+import { completeJson } from './modelCompletion.js'
+
+const MAX_FINDINGS_CHARS = 24000
+const DAY_MS = 24 * 60 * 60 * 1000
+const DEFAULT_STALENESS_DAYS = 365
+
+export const FINANCIAL_METRICS = [
+  'revenue',
+  'revenue_growth',
+  'gross_margin',
+  'operating_margin',
+  'net_margin',
+  'ebitda',
+  'net_income',
+  'eps',
+  'free_cash_flow',
+]
+
+export const resolveStalenessDays = value => {
+  const days = Number.parseInt(value ?? process.env.FINANCE_STALENESS_DAYS, 10)
+  return Number.isFinite(days) && days > 0 ? days : DEFAULT_STALENESS_DAYS
+}
+
+/**
+ * End date of a reporting period: "2024-06-30", "2024-06", "Q3 2024", "2024 Q3", "FY2023", "2023"
+ * @returns {Date|null}
+ */
+export const parsePeriodEnd = value => {
+  const text = String(value || '').trim()
+  if (!text) return null
+  const exact = text.match(/^(\d{4})-(\d{2})(?:-(\d{2}))?$/)
+  if (exact) {
+    const [, year, month, day] = exact.map(Number)
+    return day ? new Date(Date.UTC(year, month - 1, day)) : new Date(Date.UTC(year, month, 0))
+  }
+  const quarter = text.match(/Q([1-4])\D*(\d{4})|(\d{4})\D*Q([1-4])/i)
+  if (quarter) {
+    const q = Number(quarter[1] || quarter[4])
+    const year = Number(quarter[2] || quarter[3])
+    return new Date(Date.UTC(year, q * 3, 0))
+  }
+  const half = text.match(/H([12])\D*(\d{4})|(\d{4})\D*H([12])/i)
+  if (half) {
+    const h = Number(half[1] || half[4])
+    const year = Number(half[2] || half[3])
+    return new Date(Date.UTC(year, h * 6, 0))
+  }
+  const year = text.match(/(?:FY\s*)?(\d{4})/i)
+  return year ? new Date(Date.UTC(Number(year[1]), 12, 0)) : null
+}
+
+const buildMetricsMessages = ({ question, findings, sourcesList }) => [
+  {
+    role: 'system',
+    content: `You extract financial figures from research notes into a standardized table.
+Rules:
+- One entry per company (or segment) and reporting period.
+- Use these metric keys only: ${FINANCIAL_METRICS.join(', ')}.
+- "value" is a plain number (no commas). Put the currency and scale in "unit" (e.g. "USD billion"); use "%" for growth and margins.
+- "period" is the reporting period exactly as stated (e.g. "FY2024", "Q2 2025", "TTM to 2025-03-31").
+- "as_of" is the period end date as YYYY-MM-DD, or YYYY when only the year is known.
+- "sources" lists numbers from the source list that report the figure. Skip figures with no source; never invent or compute numbers.
+
+Return ONLY JSON: {"entities": [{"entity": "...", "metrics": [{"metric": "revenue", "value": 0, "unit": "USD billion", "period": "FY2024", "as_of": "2024-12-31", "sources": [1]}]}]}`,
+  },
+  {
+    role: 'user',
+    content: `Question: ${question || '(none)'}
+
+Source list:
+${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}
+
+Research notes:
+${findings.join('\n\n').slice(0, MAX_FINDINGS_CHARS)}`,
+  },
+]
+
+/**
+ * Validate model output and annotate each figure with its age and staleness
+ */
+export const normalizeFinancialMetrics = (
+  parsed,
+  sourceCount,
+  { stalenessDays = DEFAULT_STALENESS_DAYS, now = Date.now() } = {},
+) => {
+  const list = Array.isArray(parsed) ? parsed : parsed?.entities
+  const entities = (Array.isArray(list) ? list : [])
+    .filter(item => item && typeof item.entity === 'string' && Array.isArray(item.metrics))
+    .map(item => ({
+      entity: item.entity.trim(),
+      metrics: item.metrics
+        .filter(metric => FINANCIAL_METRICS.includes(metric?.metric))
+        .map(metric => {
+          const value = Number.parseFloat(String(metric.value).replace(/,/g, ''))
+          const sources = (Array.isArray(metric.sources) ? metric.sources : [])
+            .map(source => Number.parseInt(source, 10))
+            .filter(source => Number.isFinite(source) && source >= 1 && source <= sourceCount)
+          if (!Number.isFinite(value) || !sources.length) return null
+          const periodEnd = parsePeriodEnd(metric.as_of) || parsePeriodEnd(metric.period)
+          const ageDays = periodEnd ? Math.floor((now - periodEnd.getTime()) / DAY_MS) : null
+          return {
+            metric: metric.metric,
+            value,
+            unit: String(metric.unit || '').trim(),
+            period: String(metric.period || metric.as_of || '').trim(),
+            as_of: periodEnd ? periodEnd.toISOString().slice(0, 10) : null,
+            age_days: ageDays,
+            // Undated figures cannot be shown to be current
+            stale: ageDays === null || ageDays > stalenessDays,
+            sources,
+          }
+        })
+        .filter(Boolean),
+    }))
+    .filter(item => item.metrics.length)
+
+  const staleCount = entities.reduce(
+    (count, item) => count + item.metrics.filter(metric => metric.stale).length,
+    0,
+  )
+  return { entities, staleness_days: stalenessDays, stale_count: staleCount }
+}
+
+/**
+ * Extract the metrics table from findings; failures degrade to an empty table
+ * @returns {Promise<{entities: Array, staleness_days: number, stale_count: number}>}
+ */
+export const extractFinancialMetrics = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  question,
+  findings = [],
+  sourcesList = [],
+  stalenessDays,
+  signal,
+}) => {
+  const days = resolveStalenessDays(stalenessDays)
+  if (!findings.length) return { entities: [], staleness_days: days, stale_count: 0 }
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: buildMetricsMessages({ question, findings, sourcesList }),
+      temperature: 0,
+      signal,
+    })
+    return normalizeFinancialMetrics(parsed, sourcesList.length, { stalenessDays: days })
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[FinancialMetrics] Metric extraction failed:', error.message)
+    return { entities: [], staleness_days: days, stale_count: 0 }
+  }
+}
+
+/**
+ * Report prompt section: reproduce the table and call out stale figures
+ */
+export const formatMetricsForPrompt = table => {
+  const rows = table.entities.flatMap(item =>
+    item.metrics.map(metric =>
+      [
+        item.entity,
+        metric.metric,
+        `${metric.value}${metric.unit === '%' ? '%' : ` ${metric.unit}`}`.trim(),
+        metric.period || 'n/a',
+        metric.as_of || 'undated',
+        metric.stale ? 'STALE' : 'current',
+        metric.sources.map(source => `[${source}]`).join(''),
+      ].join(' | '),
+    ),
+  )
+  return rows.join('\n')
+}
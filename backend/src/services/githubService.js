@@ -0,0 +1,160 @@
+/**
+ * GitHub service
+ * Repository search, issue/PR search, and issue thread retrieval via the GitHub REST API.
+ * Works unauthenticated for public data; a token (vault secret GITHUB_TOKEN or the GITHUB_TOKEN
+ * env var) raises rate limits and allows private repositories.
+ */
+
+import { resolveSecret } from './keyVault.js'
+
+const API_BASE = 'https://api.github.com'
+const REQUEST_TIMEOUT_MS = 15000
+const MAX_BODY_CHARS = 4000
+const MAX_COMMENTS = 30
+
+export const GITHUB_ACTIONS = ['search_repos', 'search_issues', 'get_issue']
+
+const resolveGithubToken = () =>
+  resolveSecret('GITHUB_TOKEN')?.value || process.env.GITHUB_TOKEN || ''
+
+const truncate = (text, max = MAX_BODY_CHARS) => {
+  const value = String(text || '')
+  return value.length > max ? `${value.slice(0, max)}…` : value
+}
+
+const githubFetch = async (path, { signal } = {}) => {
+  const token = resolveGithubToken()
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(`${API_BASE}${path}`, {
+    headers: {
+      Accept: 'application/vnd.github+json',
+      'X-GitHub-Api-Version': '2022-11-28',
+      'User-Agent': 'Qurio',
+      ...(token ? { Authorization: `Bearer ${token}` } : {}),
+    },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (!response.ok) {
+    const remaining = response.headers.get('x-ratelimit-remaining')
+    if (response.status === 403 && remaining === '0') {
+      throw new Error('GitHub rate limit exceeded. Add a GITHUB_TOKEN secret to raise the limit.')
+    }
+    if (response.status === 404) throw new Error(`GitHub resource not found: ${path}`)
+    throw new Error(`GitHub API error: HTTP ${response.status}`)
+  }
+  return response.json()
+}
+
+const parseRepo = repo => {
+  const match = String(repo || '')
+    .trim()
+    .replace(/^https?:\/\/github\.com\//i, '')
+    .match(/^([\w.-]+)\/([\w.-]+?)(?:\.git)?\/?$/)
+  if (!match) throw new Error('repo must be in "owner/name" form')
+  return `${match[1]}/${match[2]}`
+}
+
+const searchRepos = async ({ query, limit, signal }) => {
+  const params = new URLSearchParams({ q: query, per_page: String(limit) })
+  const data = await githubFetch(`/search/repositories?${params}`, { signal })
+  return {
+    total_count: data.total_count,
+    results: (data.items || []).map(repo => ({
+      title: repo.full_name,
+      url: repo.html_url,
+      content: repo.description || '',
+      stars: repo.stargazers_count,
+      language: repo.language,
+      archived: repo.archived,
+      updated_at: repo.pushed_at,
+    })),
+  }
+}
+
+const searchIssues = async ({ query, repo, type, state, limit, signal }) => {
+  const qualifiers = [query]
+  if (repo) qualifiers.push(`repo:${parseRepo(repo)}`)
+  if (type === 'issue' || type === 'pr') qualifiers.push(`is:${type}`)
+  if (state === 'open' || state === 'closed') qualifiers.push(`state:${state}`)
+  const params = new URLSearchParams({ q: qualifiers.join(' '), per_page: String(limit) })
+  const data = await githubFetch(`/search/issues?${params}`, { signal })
+  return {
+    total_count: data.total_count,
+    results: (data.items || []).map(item => ({
+      title: item.title,
+      url: item.html_url,
+      content: truncate(item.body, 500),
+      number: item.number,
+      type: item.pull_request ? 'pr' : 'issue',
+      state: item.state,
+      comments: item.comments,
+      created_at: item.created_at,
+      closed_at: item.closed_at,
+    })),
+  }
+}
+
+const getIssue = async ({ repo, number, signal }) => {
+  const fullName = parseRepo(repo)
+  const issue = await githubFetch(`/repos/${fullName}/issues/${number}`, { signal })
+  const comments =
+    issue.comments > 0
+      ? await githubFetch(
+          `/repos/${fullName}/issues/${number}/comments?per_page=${MAX_COMMENTS}`,
+          { signal },
+        )
+      : []
+  return {
+    number: issue.number,
+    type: issue.pull_request ? 'pr' : 'issue',
+    state: issue.state,
+    labels: (issue.labels || []).map(label => label.name),
+    author: issue.user?.login,
+    created_at: issue.created_at,
+    closed_at: issue.closed_at,
+    comment_count: issue.comments,
+    comments: comments.map(comment => ({
+      author: comment.user?.login,
+      created_at: comment.created_at,
+      body: truncate(comment.body),
+    })),
+    results: [
+      {
+        title: `${fullName}#${issue.number}: ${issue.title}`,
+        url: issue.html_url,
+        content: truncate(issue.body),
+      },
+    ],
+  }
+}
+
+/**
+ * Run a GitHub tool action
+ * @param {Object} args
+ * @param {'search_repos'|'search_issues'|'get_issue'} args.action
+ */
+export const queryGithub = async ({
+  action,
+  query,
+  repo,
+  number,
+  type,
+  state,
+  limit = 10,
+  signal,
+}) => {
+  const perPage = Math.min(Math.max(Number(limit) || 10, 1), 30)
+  switch (action) {
+    case 'search_repos':
+      if (!query) throw new Error('query is required for search_repos')
+      return searchRepos({ query, limit: perPage, signal })
+    case 'search_issues':
+      if (!query) throw new Error('query is required for search_issues')
+      return searchIssues({ query, repo, type, state, limit: perPage, signal })
+    case 'get_issue':
+      if (!repo || !number) throw new Error('repo and number are required for get_issue')
+      return getIssue({ repo, number, signal })
+    default:
+      throw new Error(`action must be one of: ${GITHUB_ACTIONS.join(', ')}`)
+  }
+}
@@ -0,0 +1,99 @@
+/**
+ * Glossary service
+ * Extracts technical terms from a finished report and generates short, source-cited definitions.
+ */
+
+import { completeJson } from './modelCompletion.js'
+
+const MAX_GLOSSARY_TERMS = 15
+const MAX_REPORT_CHARS = 24000
+
+const buildGlossaryMessages = ({ report, sourcesList }) => [
+  {
+    role: 'system',
+    content: `You build glossaries for research reports.
+Rules:
+- Pick up to ${MAX_GLOSSARY_TERMS} technical terms, acronyms, or jargon that a non-expert reader of the report would need explained.
+- Skip common words and terms the report already defines inline.
+- Each definition is one or two plain sentences, in the report's language.
+- Ground definitions in the report and the source list. When a definition relies on a source, list its number in "sources".
+- Never invent source numbers.
+
+Return ONLY JSON: {"terms": [{"term": "...", "definition": "...", "sources": [1]}]}`,
+  },
+  {
+    role: 'user',
+    content: `Source list:
+${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}
+
+Report:
+${String(report || '').slice(0, MAX_REPORT_CHARS)}`,
+  },
+]
+
+const normalizeEntries = (parsed, sourceCount) => {
+  const list = Array.isArray(parsed) ? parsed : parsed?.terms || parsed?.glossary
+  if (!Array.isArray(list)) return []
+  const seen = new Set()
+  return list
+    .filter(item => item && typeof item.term === 'string' && typeof item.definition === 'string')
+    .map(item => ({
+      term: item.term.trim(),
+      definition: item.definition.trim(),
+      sources: (Array.isArray(item.sources) ? item.sources : [])
+        .map(value => Number.parseInt(value, 10))
+        .filter(value => Number.isFinite(value) && value >= 1 && value <= sourceCount),
+    }))
+    .filter(item => {
+      const key = item.term.toLowerCase()
+      if (!item.term || !item.definition || seen.has(key)) return false
+      seen.add(key)
+      return true
+    })
+    .slice(0, MAX_GLOSSARY_TERMS)
+    .sort((a, b) => a.term.localeCompare(b.term))
+}
+
+/**
+ * Generate glossary entries for a report
+ * @returns {Promise<Array<{term: string, definition: string, sources: number[]}>>}
+ */
+export const generateGlossary = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  report,
+  sourcesList = [],
+  signal,
+}) => {
+  if (!String(report || '').trim()) return []
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: buildGlossaryMessages({ report, sourcesList }),
+      temperature: 0.2,
+      signal,
+    })
+    return normalizeEntries(parsed, sourcesList.length)
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[Glossary] Glossary generation failed:', error.message)
+    return []
+  }
+}
+
+/**
+ * Render glossary entries as a markdown section appended to the report
+ */
+export const formatGlossaryMarkdown = entries => {
+  if (!entries?.length) return ''
+  const lines = entries.map(entry => {
+    const citations = entry.sources.map(index => `[${index}]`).join('')
+    return `- **${entry.term}**: ${entry.definition}${citations ? ` ${citations}` : ''}`
+  })
+  return `\n\n## Glossary\n\n${lines.join('\n')}\n`
+}
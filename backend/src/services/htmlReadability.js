@@ -0,0 +1,698 @@
+/**
+ * HTML readability
+ * Main-content extraction for the webpage reader: the page is parsed into a small DOM tree,
+ * navigation chrome and unlikely blocks are dropped, text blocks are scored Readability-style
+ * (length, commas, class names, link density) to find the article container, and that container
+ * is converted to Markdown. Metadata comes from <meta> tags, JSON-LD and the document itself.
+ */
+
+const VOID_TAGS = new Set([
+  'area',
+  'base',
+  'br',
+  'col',
+  'embed',
+  'hr',
+  'img',
+  'input',
+  'link',
+  'meta',
+  'param',
+  'source',
+  'track',
+  'wbr',
+])
+// Content is raw text up to the closing tag
+const RAW_TEXT_TAGS = new Set(['script', 'style', 'textarea', 'title', 'noscript', 'template'])
+const BLOCK_TAGS = new Set([
+  'address',
+  'article',
+  'aside',
+  'blockquote',
+  'body',
+  'dd',
+  'details',
+  'dialog',
+  'div',
+  'dl',
+  'dt',
+  'fieldset',
+  'figcaption',
+  'figure',
+  'footer',
+  'form',
+  'h1',
+  'h2',
+  'h3',
+  'h4',
+  'h5',
+  'h6',
+  'header',
+  'hgroup',
+  'hr',
+  'html',
+  'li',
+  'main',
+  'nav',
+  'ol',
+  'p',
+  'pre',
+  'section',
+  'summary',
+  'table',
+  'tbody',
+  'td',
+  'tfoot',
+  'th',
+  'thead',
+  'tr',
+  'ul',
+])
+// Opening one of these closes an open element of the listed kinds
+const IMPLIED_CLOSES = {
+  li: ['li'],
+  dt: ['dt', 'dd'],
+  dd: ['dt', 'dd'],
+  tr: ['tr', 'td', 'th'],
+  td: ['td', 'th'],
+  th: ['td', 'th'],
+  option: ['option'],
+}
+// Never part of the readable content
+const DROPPED_TAGS = new Set([
+  'script',
+  'style',
+  'noscript',
+  'template',
+  'svg',
+  'canvas',
+  'iframe',
+  'object',
+  'embed',
+  'form',
+  'button',
+  'input',
+  'select',
+  'textarea',
+  'nav',
+  'aside',
+  'footer',
+  'dialog',
+  'menu',
+  'head',
+  'title',
+])
+const PARAGRAPH_TAGS = new Set(['p', 'pre', 'td', 'blockquote'])
+
+const UNLIKELY_PATTERN =
+  /-ad-|ad-break|agegate|banner|breadcrumb|combx|comment|community|cookie|cover-wrap|disqus|extra|footer|gdpr|header|legends|menu|modal|newsletter|pager|pagination|popup|promo|related|remark|replies|rss|share|shoutbox|sidebar|skyscraper|social|sponsor|subscribe|supplemental|yom-remote/i
+const MAYBE_CANDIDATE_PATTERN = /and|article|body|column|content|main|shadow/i
+const POSITIVE_PATTERN =
+  /article|body|content|entry|hentry|h-entry|main|page|post|text|blog|story/i
+const NEGATIVE_PATTERN =
+  /-ad-|hidden|^hid$| hid$| hid |^hid |banner|combx|comment|com-|contact|foot|footer|footnote|gdpr|masthead|media|meta|outbrain|promo|related|scroll|share|shoutbox|sidebar|skyscraper|sponsor|shopping|tags|tool|widget/i
+
+const NAMED_ENTITIES = {
+  amp: '&',
+  lt: '<',
+  gt: '>',
+  quot: '"',
+  apos: "'",
+  nbsp: ' ',
+  copy: '©',
+  reg: '®',
+  trade: '™',
+  hellip: '…',
+  mdash: '—',
+  ndash: '–',
+  lsquo: '‘',
+  rsquo: '’',
+  ldquo: '“',
+  rdquo: '”',
+  laquo: '«',
+  raquo: '»',
+  bull: '•',
+  middot: '·',
+  times: '×',
+  deg: '°',
+  euro: '€',
+  pound: '£',
+  yen: '¥',
+  cent: '¢',
+  sect: '§',
+  para: '¶',
+  shy: '',
+  zwj: '',
+  zwnj: '',
+}
+
+export const decodeEntities = text =>
+  String(text || '').replace(/&(#x[\da-f]+|#\d+|[a-z][a-z\d]*);/gi, (match, entity) => {
+    if (entity[0] === '#') {
+      const code =
+        entity[1] === 'x' || entity[1] === 'X'
+          ? Number.parseInt(entity.slice(2), 16)
+          : Number.parseInt(entity.slice(1), 10)
+      return code > 0 && code <= 0x10ffff ? String.fromCodePoint(code) : match
+    }
+    return NAMED_ENTITIES[entity.toLowerCase()] ?? match
+  })
+
+const TOKEN_PATTERN =
+  /<!--[\s\S]*?(?:-->|$)|<![^>]*>|<\?[^>]*>|<\/([a-zA-Z][\w:-]*)\s*>|<([a-zA-Z][\w:-]*)((?:[^>"']|"[^"]*"|'[^']*')*)>|[^<]+|</g
+const ATTRIBUTE_PATTERN = /([^\s"'>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+)))?/g
+
+const readAttributes = source => {
+  const attributes = {}
+  for (const [, name, double, single, bare] of source.matchAll(ATTRIBUTE_PATTERN)) {
+    const key = name.toLowerCase()
+    if (!(key in attributes)) attributes[key] = decodeEntities(double ?? single ?? bare ?? '')
+  }
+  return attributes
+}
+
+const createElement = (tag, attributes, parent) => ({ tag, attributes, children: [], parent })
+
+/**
+ * Parse HTML into a tree of { tag, attributes, children, parent } elements and { text } nodes
+ * Lenient like a browser: unknown closing tags are ignored, unclosed elements end with their
+ * parent, and the usual implied closes (li, td, p before a block) apply.
+ */
+export const parseHtml = html => {
+  const source = String(html || '')
+  const lowerSource = source.toLowerCase()
+  const root = createElement('#root', {}, null)
+  const stack = [root]
+  const current = () => stack[stack.length - 1]
+  const closeTag = tag => {
+    const index = stack.findLastIndex(element => element.tag === tag)
+    if (index > 0) stack.length = index
+  }
+
+  TOKEN_PATTERN.lastIndex = 0
+  let match
+  while ((match = TOKEN_PATTERN.exec(source))) {
+    const [token, closing, opening, attributeSource] = match
+    if (closing) {
+      closeTag(closing.toLowerCase())
+      continue
+    }
+    if (!opening) {
+      // Text (a lone "<" included); comments and doctypes are skipped
+      if (!token.startsWith('<!') && !token.startsWith('<?')) {
+        current().children.push({ text: decodeEntities(token), parent: current() })
+      }
+      continue
+    }
+
+    const tag = opening.toLowerCase()
+    const implied = IMPLIED_CLOSES[tag]
+    if (implied && implied.includes(current().tag)) stack.pop()
+    if (BLOCK_TAGS.has(tag) && current().tag === 'p') stack.pop()
+
+    const element = createElement(tag, readAttributes(attributeSource), current())
+    current().children.push(element)
+    if (RAW_TEXT_TAGS.has(tag)) {
+      const end = lowerSource.indexOf(`</${tag}`, TOKEN_PATTERN.lastIndex)
+      const text = source.slice(TOKEN_PATTERN.lastIndex, end === -1 ? source.length : end)
+      const decoded = tag === 'title' || tag === 'textarea' ? decodeEntities(text) : text
+      element.children.push({ text: decoded, parent: element })
+      TOKEN_PATTERN.lastIndex = end === -1 ? source.length : source.indexOf('>', end) + 1 || end
+      continue
+    }
+    if (!VOID_TAGS.has(tag) && !attributeSource.trim().endsWith('/')) stack.push(element)
+  }
+  return root
+}
+
+const isElement = node => Boolean(node?.tag)
+
+const walk = (node, visit) => {
+  for (const child of node.children || []) {
+    if (visit(child) !== false && isElement(child)) walk(child, visit)
+  }
+}
+
+const findAll = (node, predicate) => {
+  const found = []
+  walk(node, child => {
+    if (isElement(child) && predicate(child)) found.push(child)
+  })
+  return found
+}
+
+const find = (node, predicate) => findAll(node, predicate)[0] || null
+
+export const textOf = node => {
+  if (!isElement(node)) return node?.text || ''
+  if (DROPPED_TAGS.has(node.tag) && node.tag !== 'title') return ''
+  return node.children.map(textOf).join('')
+}
+
+const normalizeSpace = text => text.replace(/\s+/g, ' ').trim()
+
+const classAndId = element =>
+  `${element.attributes.class || ''} ${element.attributes.id || ''}`.trim()
+
+const isHidden = element =>
+  'hidden' in element.attributes ||
+  element.attributes['aria-hidden'] === 'true' ||
+  /display\s*:\s*none|visibility\s*:\s*hidden/i.test(element.attributes.style || '')
+
+const linkDensity = element => {
+  const length = normalizeSpace(textOf(element)).length
+  if (!length) return 0
+  const linkLength = findAll(element, child => child.tag === 'a').reduce(
+    (total, link) => total + normalizeSpace(textOf(link)).length,
+    0,
+  )
+  return linkLength / length
+}
+
+const classWeight = element => {
+  const names = classAndId(element)
+  if (!names) return 0
+  return (NEGATIVE_PATTERN.test(names) ? -25 : 0) + (POSITIVE_PATTERN.test(names) ? 25 : 0)
+}
+
+const TAG_WEIGHTS = {
+  div: 5,
+  article: 10,
+  main: 10,
+  section: 3,
+  pre: 3,
+  td: 3,
+  blockquote: 3,
+  ol: -3,
+  ul: -3,
+  dl: -3,
+  form: -3,
+  th: -5,
+  h1: -5,
+  h2: -5,
+  h3: -5,
+  h4: -5,
+  h5: -5,
+  h6: -5,
+}
+
+// Remove chrome, hidden blocks, and blocks whose class/id mark them as not content
+const pruneUnlikely = node => {
+  node.children = node.children.filter(child => {
+    if (!isElement(child)) return true
+    if (DROPPED_TAGS.has(child.tag) || isHidden(child)) return false
+    if (/navigation|complementary|dialog|menu/i.test(child.attributes.role || '')) return false
+    const names = classAndId(child)
+    if (
+      names &&
+      child.tag !== 'body' &&
+      child.tag !== 'article' &&
+      child.tag !== 'main' &&
+      UNLIKELY_PATTERN.test(names) &&
+      !MAYBE_CANDIDATE_PATTERN.test(names)
+    ) {
+      return false
+    }
+    pruneUnlikely(child)
+    return true
+  })
+}
+
+const hasBlockChildren = element =>
+  element.children.some(child => isElement(child) && BLOCK_TAGS.has(child.tag))
+
+/**
+ * The element holding the main content, and its siblings that belong to it
+ * @returns {Array<Object>} Elements in document order
+ */
+const selectContent = body => {
+  const scores = new Map()
+  const scoreOf = element => {
+    if (!scores.has(element)) {
+      scores.set(element, (TAG_WEIGHTS[element.tag] || 0) + classWeight(element))
+    }
+    return scores.get(element)
+  }
+
+  const paragraphs = findAll(
+    body,
+    element =>
+      PARAGRAPH_TAGS.has(element.tag) ||
+      ((element.tag === 'div' || element.tag === 'section') && !hasBlockChildren(element)),
+  )
+  for (const paragraph of paragraphs) {
+    const text = normalizeSpace(textOf(paragraph))
+    if (text.length < 25) continue
+    const score = 1 + text.split(/[,，、]/).length + Math.min(Math.floor(text.length / 100), 3)
+    let ancestor = paragraph.parent
+    for (let level = 0; ancestor && ancestor.tag !== '#root' && level < 3; level += 1) {
+      scores.set(ancestor, scoreOf(ancestor) + score / (level === 0 ? 1 : level * 2))
+      ancestor = ancestor.parent
+    }
+  }
+
+  let top = null
+  let topScore = 0
+  for (const [element, score] of scores) {
+    const adjusted = score * (1 - linkDensity(element))
+    scores.set(element, adjusted)
+    if (adjusted > topScore) {
+      top = element
+      topScore = adjusted
+    }
+  }
+  if (!top) {
+    return [find(body, element => element.tag === 'article' || element.tag === 'main') || body]
+  }
+
+  // A candidate that is most of its parent's text stands for the parent (article split in parts)
+  while (top.parent && top.parent.tag !== '#root' && top.parent.tag !== 'html') {
+    const parentLength = normalizeSpace(textOf(top.parent)).length
+    if (!parentLength || normalizeSpace(textOf(top)).length / parentLength < 0.8) break
+    top = top.parent
+  }
+  if (!top.parent || top.tag === 'body') return [top]
+
+  const threshold = Math.max(10, topScore * 0.2)
+  return top.parent.children.filter(sibling => {
+    if (sibling === top) return true
+    if (!isElement(sibling)) return false
+    if ((scores.get(sibling) || 0) >= threshold) return true
+    if (sibling.tag !== 'p') return false
+    const text = normalizeSpace(textOf(sibling))
+    const density = linkDensity(sibling)
+    return (
+      (text.length > 80 && density < 0.25) ||
+      (text.length > 0 && density === 0 && /\.( |$)/.test(text))
+    )
+  })
+}
+
+// Inside the content: drop link lists and other blocks that are mostly links
+const pruneLinkClusters = element => {
+  element.children = element.children.filter(child => {
+    if (!isElement(child)) return true
+    if (['div', 'section', 'ul', 'ol', 'table'].includes(child.tag)) {
+      const text = normalizeSpace(textOf(child))
+      if (!text) return findAll(child, node => node.tag === 'img').length > 0
+      if (classWeight(child) < 0 && text.length < 200) return false
+      if (linkDensity(child) > 0.5 && text.length < 300) return false
+    }
+    pruneLinkClusters(child)
+    return true
+  })
+}
+
+const resolveUrl = (value, baseUrl) => {
+  if (!value) return ''
+  try {
+    const url = new URL(value.trim(), baseUrl)
+    return ['http:', 'https:', 'mailto:'].includes(url.protocol) ? url.href : ''
+  } catch {
+    return ''
+  }
+}
+
+const wrapInline = (marker, text) => {
+  const match = text.match(/^(\s*)([\s\S]*?)(\s*)$/)
+  return match[2] ? `${match[1]}${marker}${match[2]}${marker}${match[3]}` : text
+}
+
+const codeLanguage = element => {
+  const names = `${element.attributes.class || ''} ${
+    find(element, child => child.tag === 'code')?.attributes.class || ''
+  }`
+  return names.match(/(?:language|lang)-([\w+#-]+)/)?.[1] || ''
+}
+
+const fence = text => {
+  const longest = Math.max(2, ...(text.match(/`+/g) || []).map(run => run.length))
+  return '`'.repeat(longest + 1)
+}
+
+// Inline Markdown of an element's content (blocks inside inline elements are flattened)
+const renderInline = (node, context) => {
+  if (!isElement(node)) return node.text
+  const content = () => node.children.map(child => renderInline(child, context)).join('')
+  switch (node.tag) {
+    case 'br':
+      return '\n'
+    case 'strong':
+    case 'b':
+      return wrapInline('**', content())
+    case 'em':
+    case 'i':
+      return wrapInline('_', content())
+    case 'del':
+    case 's':
+    case 'strike':
+      return wrapInline('~~', content())
+    case 'code':
+    case 'kbd':
+    case 'samp': {
+      const text = textOf(node).replace(/\s+/g, ' ')
+      if (!text.trim()) return text
+      const ticks = text.includes('`') ? '``' : '`'
+      return `${ticks}${ticks.length > 1 ? ' ' : ''}${text}${ticks.length > 1 ? ' ' : ''}${ticks}`
+    }
+    case 'a': {
+      const text = normalizeSpace(content())
+      const href = resolveUrl(node.attributes.href, context.baseUrl)
+      if (!text) return ''
+      return href && !node.attributes.href.startsWith('#') ? `[${text}](${href})` : text
+    }
+    case 'img': {
+      const src = resolveUrl(node.attributes.src || node.attributes['data-src'], context.baseUrl)
+      const alt = normalizeSpace(node.attributes.alt || '')
+      return src ? `![${alt}](${src})` : alt
+    }
+    case 'sup':
+    case 'sub':
+    default:
+      return DROPPED_TAGS.has(node.tag) ? '' : content()
+  }
+}
+
+const toParagraph = text =>
+  text
+    .replace(/[ \t\r\f\v]+/g, ' ')
+    .replace(/ *\n */g, '\n')
+    .trim()
+
+const renderTable = (table, context) => {
+  if (find(table, element => element.tag === 'table')) return null
+  const rows = findAll(table, element => element.tag === 'tr').map(row =>
+    row.children
+      .filter(cell => cell.tag === 'td' || cell.tag === 'th')
+      .map(cell =>
+        normalizeSpace(cell.children.map(child => renderInline(child, context)).join(''))
+          .replace(/\|/g, '\\|'),
+      ),
+  )
+  const width = Math.max(0, ...rows.map(row => row.length))
+  if (rows.length < 1 || width < 2) return null
+  const pad = row => [...row, ...Array(width - row.length).fill('')]
+  const line = row => `| ${pad(row).join(' | ')} |`
+  return [line(rows[0]), line(Array(width).fill('---')), ...rows.slice(1).map(line)].join('\n')
+}
+
+const indentLines = (text, firstPrefix, prefix) =>
+  text
+    .split('\n')
+    .map((line, index) => (index === 0 ? firstPrefix : line ? prefix : '') + line)
+    .join('\n')
+
+// Markdown blocks of an element's children; runs of inline content become paragraphs
+const renderChildBlocks = (element, context) => {
+  const blocks = []
+  let inline = ''
+  const flush = () => {
+    const paragraph = toParagraph(inline)
+    if (paragraph) blocks.push(paragraph)
+    inline = ''
+  }
+  for (const child of element.children) {
+    if (isElement(child) && BLOCK_TAGS.has(child.tag)) {
+      flush()
+      blocks.push(...renderBlocks(child, context))
+    } else {
+      inline += renderInline(child, context)
+    }
+  }
+  flush()
+  return blocks
+}
+
+const renderBlocks = (element, context) => {
+  switch (element.tag) {
+    case 'h1':
+    case 'h2':
+    case 'h3':
+    case 'h4':
+    case 'h5':
+    case 'h6': {
+      const text = normalizeSpace(
+        element.children.map(child => renderInline(child, context)).join(''),
+      )
+      return text ? [`${'#'.repeat(Number(element.tag[1]))} ${text}`] : []
+    }
+    case 'hr':
+      return ['---']
+    case 'pre': {
+      const code = textOf(element).replace(/^\n/, '').replace(/\s+$/, '')
+      if (!code) return []
+      const marker = fence(code)
+      return [`${marker}${codeLanguage(element)}\n${code}\n${marker}`]
+    }
+    case 'ul':
+    case 'ol': {
+      let number = Number.parseInt(element.attributes.start, 10) || 1
+      const items = element.children
+        .filter(child => child.tag === 'li')
+        .map(item => {
+          const text = renderChildBlocks(item, context).join('\n\n')
+          if (!text) return ''
+          const marker = element.tag === 'ol' ? `${number++}. ` : '- '
+          return indentLines(text, marker, ' '.repeat(marker.length))
+        })
+        .filter(Boolean)
+      return items.length ? [items.join('\n')] : []
+    }
+    case 'blockquote': {
+      const text = renderChildBlocks(element, context).join('\n\n')
+      return text ? [text.split('\n').map(line => (line ? `> ${line}` : '>')).join('\n')] : []
+    }
+    case 'table': {
+      const table = renderTable(element, context)
+      return table ? [table] : renderChildBlocks(element, context)
+    }
+    default:
+      return renderChildBlocks(element, context)
+  }
+}
+
+const toMarkdown = (elements, baseUrl) =>
+  elements
+    .flatMap(element =>
+      isElement(element) ? renderBlocks(element, { baseUrl }) : [toParagraph(element.text)],
+    )
+    .filter(Boolean)
+    .join('\n\n')
+
+const readMeta = root => {
+  const meta = {}
+  for (const element of findAll(root, node => node.tag === 'meta')) {
+    const key = (
+      element.attributes.property ||
+      element.attributes.name ||
+      element.attributes.itemprop ||
+      ''
+    ).toLowerCase()
+    if (key && element.attributes.content && !(key in meta)) meta[key] = element.attributes.content
+  }
+  return meta
+}
+
+// Article objects of the page's JSON-LD (arrays and @graph flattened)
+const readJsonLd = root =>
+  findAll(
+    root,
+    node => node.tag === 'script' && /ld\+json/i.test(node.attributes.type || ''),
+  ).flatMap(script => {
+    try {
+      const data = JSON.parse(textOf({ ...script, tag: 'div' }))
+      const items = Array.isArray(data) ? data : data?.['@graph'] || [data]
+      return items.filter(item => item && typeof item === 'object')
+    } catch {
+      return []
+    }
+  })
+
+const namesOf = value =>
+  (Array.isArray(value) ? value : [value])
+    .map(item => (typeof item === 'string' ? item : item?.name))
+    .filter(name => typeof name === 'string' && name.trim())
+    .map(name => name.trim())
+
+const toIsoDate = value => {
+  if (!value) return null
+  const date = new Date(value)
+  return Number.isNaN(date.getTime()) ? String(value) : date.toISOString()
+}
+
+/**
+ * Title, author, published date and other metadata of a parsed page
+ */
+const extractMetadata = (root, baseUrl) => {
+  const meta = readMeta(root)
+  const jsonLd = readJsonLd(root)
+  const article =
+    jsonLd.find(item => /article|posting|report|blog/i.test(String(item['@type'] || ''))) ||
+    jsonLd[0] ||
+    {}
+  const titleElement = find(root, node => node.tag === 'title')
+  const heading = find(root, node => node.tag === 'h1')
+  const metaAuthor = meta.author || meta['article:author'] || meta['dc.creator'] || ''
+  const relAuthor = find(root, node => /\bauthor\b/i.test(node.attributes.rel || ''))
+  const authors = [
+    ...namesOf(article.author),
+    ...(metaAuthor && !/^https?:/i.test(metaAuthor) ? [metaAuthor] : []),
+    ...(relAuthor ? [normalizeSpace(textOf(relAuthor))] : []),
+  ].filter(Boolean)
+  const time = find(root, node => node.tag === 'time' && node.attributes.datetime)
+  const link = rel =>
+    find(
+      root,
+      node =>
+        (node.tag === 'link' || node.tag === 'a') &&
+        (node.attributes.rel || '').toLowerCase().split(/\s+/).includes(rel),
+    )?.attributes.href
+
+  return {
+    title: normalizeSpace(
+      meta['og:title'] ||
+        meta['twitter:title'] ||
+        article.headline ||
+        textOf(titleElement) ||
+        textOf(heading || {}),
+    ),
+    author: [...new Set(authors)].join(', ') || null,
+    published_at: toIsoDate(
+      meta['article:published_time'] ||
+        article.datePublished ||
+        meta.date ||
+        meta.pubdate ||
+        meta['publish-date'] ||
+        meta['dc.date'] ||
+        time?.attributes.datetime,
+    ),
+    modified_at: toIsoDate(meta['article:modified_time'] || article.dateModified),
+    site_name: meta['og:site_name'] || article.publisher?.name || null,
+    description: normalizeSpace(meta.description || meta['og:description'] || '') || null,
+    lang: find(root, node => node.tag === 'html')?.attributes.lang || null,
+    canonical_url: resolveUrl(link('canonical'), baseUrl) || null,
+    next_page_url: resolveUrl(link('next'), baseUrl) || null,
+  }
+}
+
+/**
+ * Metadata and main content (as Markdown) of an HTML page
+ * @param {string} html
+ * @param {string} pageUrl - Base for relative links and images
+ * @returns {{ metadata: Object, markdown: string }}
+ */
+export const extractReadableContent = (html, pageUrl) => {
+  const root = parseHtml(html)
+  const metadata = extractMetadata(root, pageUrl)
+  const body = find(root, node => node.tag === 'body') || root
+  pruneUnlikely(body)
+  const content = selectContent(body)
+  content.forEach(element => isElement(element) && pruneLinkClusters(element))
+  let markdown = toMarkdown(content, pageUrl)
+  // The title usually repeats as the first heading; keep a single copy
+  const firstLine = markdown.split('\n', 1)[0]
+  if (metadata.title && /^# /.test(firstLine) && firstLine.slice(2) === metadata.title) {
+    markdown = markdown.slice(firstLine.length).trimStart()
+  }
+  return { metadata, markdown }
+}
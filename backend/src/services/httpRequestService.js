@@ -0,0 +1,197 @@
+/**
+ * HTTP request service
+ * Generic GET/POST JSON caller for the http_request tool, restricted to a user-managed domain
+ * allowlist. Secrets are referenced as {{secret:NAME}} and injected server-side, so the model
+ * never sees key values.
+ */
+
+import { readRecord, writeRecord } from '../utils/dataStore.js'
+import { resolveSecret } from './keyVault.js'
+
+const COLLECTION = 'http-tool'
+const ALLOWLIST_ID = 'allowlist'
+const METHODS = ['GET', 'POST']
+const REQUEST_TIMEOUT_MS = 15000
+const MAX_RESPONSE_CHARS = 100000
+const MAX_REDIRECTS = 3
+const SECRET_PATTERN = /\{\{\s*secret:([A-Za-z_][A-Za-z0-9_]*)\s*\}\}/g
+
+export class HttpToolError extends Error {
+  constructor(message) {
+    super(message)
+    this.name = 'HttpToolError'
+  }
+}
+
+const normalizeDomain = domain =>
+  String(domain || '')
+    .trim()
+    .toLowerCase()
+    .replace(/^https?:\/\//, '')
+    .replace(/\/.*$/, '')
+
+/**
+ * Allowed domains: stored list plus HTTP_TOOL_ALLOWED_DOMAINS (comma-separated).
+ * "*.example.com" also allows subdomains.
+ */
+export const getAllowedDomains = () => {
+  const stored = readRecord(COLLECTION, ALLOWLIST_ID)?.domains || []
+  const fromEnv = String(process.env.HTTP_TOOL_ALLOWED_DOMAINS || '')
+    .split(',')
+    .map(normalizeDomain)
+    .filter(Boolean)
+  return Array.from(new Set([...stored, ...fromEnv]))
+}
+
+export const setAllowedDomains = domains => {
+  const normalized = Array.from(
+    new Set((Array.isArray(domains) ? domains : []).map(normalizeDomain).filter(Boolean)),
+  )
+  writeRecord(COLLECTION, ALLOWLIST_ID, {
+    domains: normalized,
+    updatedAt: new Date().toISOString(),
+  })
+  return normalized
+}
+
+export const matchesDomain = (hostname, pattern) => {
+  const host = hostname.toLowerCase()
+  if (pattern.startsWith('*.')) {
+    const base = pattern.slice(2)
+    return host === base || host.endsWith(`.${base}`)
+  }
+  return host === pattern
+}
+
+const assertAllowedUrl = (rawUrl, allowed) => {
+  let url
+  try {
+    url = new URL(rawUrl)
+  } catch {
+    throw new HttpToolError(`Invalid URL: ${rawUrl}`)
+  }
+  if (!['http:', 'https:'].includes(url.protocol)) {
+    throw new HttpToolError('Only http and https URLs are allowed')
+  }
+  if (url.username || url.password) throw new HttpToolError('Credentials in URLs are not allowed')
+  if (!allowed.some(pattern => matchesDomain(url.hostname, pattern))) {
+    throw new HttpToolError(`Domain is not on the allowlist: ${url.hostname}`)
+  }
+  return url
+}
+
+/**
+ * Replace {{secret:NAME}} placeholders; each secret must be bound to the request's host
+ */
+const injectSecrets = (value, hostname, used) =>
+  value.replace(SECRET_PATTERN, (_, name) => {
+    const secret = resolveSecret(name)
+    if (!secret) throw new HttpToolError(`Unknown secret: ${name}`)
+    if (!secret.domains.some(pattern => matchesDomain(hostname, pattern))) {
+      throw new HttpToolError(`Secret ${name} is not allowed for ${hostname}`)
+    }
+    used.add(secret.value)
+    return secret.value
+  })
+
+const injectSecretsDeep = (value, hostname, used) => {
+  if (typeof value === 'string') return injectSecrets(value, hostname, used)
+  if (Array.isArray(value)) return value.map(item => injectSecretsDeep(item, hostname, used))
+  if (value && typeof value === 'object') {
+    return Object.fromEntries(
+      Object.entries(value).map(([key, item]) => [key, injectSecretsDeep(item, hostname, used)]),
+    )
+  }
+  return value
+}
+
+const redact = (text, secrets) =>
+  Array.from(secrets).reduce(
+    (output, secret) => (secret ? output.split(secret).join('[REDACTED]') : output),
+    text,
+  )
+
+/**
+ * Perform an allowlisted request
+ * @returns {Promise<{status: number, ok: boolean, url: string, content_type: string,
+ *   data: any, truncated: boolean}>}
+ */
+export const performHttpRequest = async ({
+  method = 'GET',
+  url,
+  headers = {},
+  query = {},
+  body,
+  signal,
+}) => {
+  const verb = String(method).toUpperCase()
+  if (!METHODS.includes(verb)) {
+    throw new HttpToolError(`method must be one of: ${METHODS.join(', ')}`)
+  }
+  const allowed = getAllowedDomains()
+  if (!allowed.length) {
+    throw new HttpToolError('No domains are allowlisted for http_request')
+  }
+
+  const used = new Set()
+  const { hostname } = assertAllowedUrl(String(url || ''), allowed)
+  let target = new URL(injectSecrets(String(url), hostname, used))
+  if (target.hostname !== hostname) throw new HttpToolError('Secrets may not change the URL host')
+  for (const [key, value] of Object.entries(query || {})) {
+    target.searchParams.set(key, injectSecrets(String(value), target.hostname, used))
+  }
+
+  const requestHeaders = { Accept: 'application/json, text/plain;q=0.9, */*;q=0.5' }
+  for (const [key, value] of Object.entries(headers || {})) {
+    requestHeaders[key] = injectSecrets(String(value), target.hostname, used)
+  }
+  let payload
+  if (verb === 'POST' && body !== undefined) {
+    payload = JSON.stringify(injectSecretsDeep(body, target.hostname, used))
+    requestHeaders['Content-Type'] = requestHeaders['Content-Type'] || 'application/json'
+  }
+
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const combinedSignal = signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal
+
+  let response
+  for (let redirects = 0; ; redirects += 1) {
+    response = await fetch(target, {
+      method: verb,
+      headers: requestHeaders,
+      body: payload,
+      redirect: 'manual',
+      signal: combinedSignal,
+    })
+    const location = response.headers.get('location')
+    if (response.status < 300 || response.status >= 400 || !location) break
+    if (redirects >= MAX_REDIRECTS) throw new HttpToolError('Too many redirects')
+    const next = assertAllowedUrl(new URL(location, target).toString(), allowed)
+    // Never forward injected secrets to a different host
+    if (next.hostname !== target.hostname && used.size) {
+      throw new HttpToolError(`Redirect to ${next.hostname} would leak secrets`)
+    }
+    target = next
+  }
+
+  const contentType = response.headers.get('content-type') || ''
+  let text = redact(await response.text(), used)
+  const truncated = text.length > MAX_RESPONSE_CHARS
+  if (truncated) text = text.slice(0, MAX_RESPONSE_CHARS)
+  let data = text
+  if (contentType.includes('json') && !truncated) {
+    try {
+      data = JSON.parse(text)
+    } catch {
+      data = text
+    }
+  }
+  return {
+    status: response.status,
+    ok: response.ok,
+    url: redact(target.toString(), used),
+    content_type: contentType,
+    data,
+    truncated,
+  }
+}
@@ -0,0 +1,56 @@
+/**
+ * Gemini Image Provider
+ * Imagen through the Gemini API: POST {baseUrl}/models/{model}:predict. Imagen takes an aspect
+ * ratio instead of a size, so the requested size maps to the closest supported ratio.
+ */
+
+import { ImageProvider, toHttpError } from './ImageProvider.js'
+
+const ASPECT_RATIOS = ['1:1', '3:4', '4:3', '9:16', '16:9']
+
+const toAspectRatio = size => {
+  const [width, height] = String(size || '')
+    .split('x')
+    .map(Number)
+  if (!width || !height) return undefined
+  const ratio = width / height
+  return ASPECT_RATIOS.reduce((best, candidate) => {
+    const [w, h] = candidate.split(':').map(Number)
+    const [bw, bh] = best.split(':').map(Number)
+    const distance = Math.abs(Math.log(w / h / ratio))
+    return distance < Math.abs(Math.log(bw / bh / ratio)) ? candidate : best
+  })
+}
+
+export class GeminiImageProvider extends ImageProvider {
+  constructor() {
+    super('gemini')
+  }
+
+  get defaultModel() {
+    return 'imagen-3.0-generate-002'
+  }
+
+  get defaultBaseUrl() {
+    return 'https://generativelanguage.googleapis.com/v1beta'
+  }
+
+  async generateImages(prompt, { apiKey, baseUrl, model, n, size, signal }) {
+    const aspectRatio = toAspectRatio(size)
+    const response = await fetch(`${baseUrl}/models/${encodeURIComponent(model)}:predict`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', 'x-goog-api-key': apiKey },
+      body: JSON.stringify({
+        instances: [{ prompt }],
+        parameters: { sampleCount: n || 1, ...(aspectRatio ? { aspectRatio } : {}) },
+      }),
+      signal,
+    })
+    if (!response.ok) throw await toHttpError(response, this.name)
+    const data = await response.json()
+    return (data?.predictions || []).map(prediction => ({
+      b64_json: prediction?.bytesBase64Encoded,
+      mime_type: prediction?.mimeType,
+    }))
+  }
+}
@@ -0,0 +1,92 @@
+/**
+ * Base Image Provider
+ * Sends one text-to-image request, retries transient failures, and returns the generated images
+ * in one shape for every provider: each image carries either base64 data or a URL to fetch.
+ */
+
+import { callWithRetry } from '../providers/retry.js'
+
+const DEFAULT_RETRY_POLICY = { maxRetries: 2, baseDelayMs: 1000 }
+// Large images can take a minute or more to render
+const REQUEST_TIMEOUT_MS = 180000
+
+/**
+ * Error carrying the HTTP status and headers of a failed image request, so the retry layer and
+ * utils/errors can classify it
+ */
+export const toHttpError = async (response, provider) => {
+  const body = await response.text().catch(() => '')
+  let message = body
+  try {
+    const data = JSON.parse(body)
+    message = data?.error?.message || data?.message || body
+  } catch {
+    // Plain-text error body
+  }
+  return Object.assign(
+    new Error(`${provider} image generation failed (HTTP ${response.status}): ${message}`.trim()),
+    { status: response.status, headers: response.headers },
+  )
+}
+
+export class ImageProvider {
+  constructor(name) {
+    this.name = name
+  }
+
+  get defaultModel() {
+    throw new Error(`${this.name}: defaultModel not implemented`)
+  }
+
+  get defaultBaseUrl() {
+    return ''
+  }
+
+  get retryPolicy() {
+    return DEFAULT_RETRY_POLICY
+  }
+
+  /**
+   * Run one generation request
+   * @returns {Promise<Array<{ b64_json?: string, url?: string, mime_type?: string,
+   *   revised_prompt?: string }>>}
+   * @abstract
+   */
+  async generateImages(_prompt, _options) {
+    throw new Error(`${this.name}: generateImages not implemented`)
+  }
+
+  /**
+   * Generate images from a prompt
+   * @param {string} prompt
+   * @param {Object} options
+   * @param {string} options.apiKey
+   * @param {string} [options.baseUrl]
+   * @param {string} [options.model]
+   * @param {number} [options.n] - Number of images (1-4)
+   * @param {string} [options.size] - "1024x1024" style; providers map it to what they support
+   * @param {AbortSignal} [options.signal]
+   * @returns {Promise<{ provider, model, images }>}
+   */
+  async generate(prompt, options = {}) {
+    const model = options.model || this.defaultModel
+    const baseUrl = String(options.baseUrl || this.defaultBaseUrl).replace(/\/$/, '')
+    const images = await callWithRetry(
+      () =>
+        this.generateImages(prompt, {
+          ...options,
+          model,
+          baseUrl,
+          signal: options.signal
+            ? AbortSignal.any([options.signal, AbortSignal.timeout(REQUEST_TIMEOUT_MS)])
+            : AbortSignal.timeout(REQUEST_TIMEOUT_MS),
+        }),
+      { provider: this.name, policy: this.retryPolicy, signal: options.signal },
+    )
+    return {
+      provider: this.name,
+      model,
+      images: (Array.isArray(images) ? images : []).filter(image => image?.b64_json || image?.url),
+    }
+  }
+}
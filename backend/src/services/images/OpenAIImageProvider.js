@@ -0,0 +1,47 @@
+/**
+ * OpenAI Image Provider
+ * POST {baseUrl}/images/generations; also used for OpenAI-compatible endpoints.
+ * gpt-image-1 always answers base64 and rejects response_format; DALL·E is asked for base64 too,
+ * since its URLs expire after an hour. Other servers answer in their default format.
+ */
+
+import { PROVIDER_BASE_URLS } from '../providers/providerConfig.js'
+import { ImageProvider, toHttpError } from './ImageProvider.js'
+
+const DALLE_MODEL_PATTERN = /^dall-e/i
+
+export class OpenAIImageProvider extends ImageProvider {
+  constructor(name = 'openai') {
+    super(name)
+  }
+
+  get defaultModel() {
+    return 'gpt-image-1'
+  }
+
+  get defaultBaseUrl() {
+    return PROVIDER_BASE_URLS.openai
+  }
+
+  async generateImages(prompt, { apiKey, baseUrl, model, n, size, signal }) {
+    const response = await fetch(`${baseUrl}/images/generations`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${apiKey}` },
+      body: JSON.stringify({
+        model,
+        prompt,
+        ...(n ? { n } : {}),
+        ...(size ? { size } : {}),
+        ...(DALLE_MODEL_PATTERN.test(model) ? { response_format: 'b64_json' } : {}),
+      }),
+      signal,
+    })
+    if (!response.ok) throw await toHttpError(response, this.name)
+    const data = await response.json()
+    return (data?.data || []).map(image => ({
+      b64_json: image?.b64_json,
+      url: image?.url,
+      revised_prompt: image?.revised_prompt,
+    }))
+  }
+}
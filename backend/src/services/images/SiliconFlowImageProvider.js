@@ -0,0 +1,39 @@
+/**
+ * SiliconFlow Image Provider
+ * POST {baseUrl}/images/generations with image_size and batch_size; answers with image URLs that
+ * stay valid for about an hour, so they are downloaded right away.
+ */
+
+import { PROVIDER_BASE_URLS } from '../providers/providerConfig.js'
+import { ImageProvider, toHttpError } from './ImageProvider.js'
+
+export class SiliconFlowImageProvider extends ImageProvider {
+  constructor() {
+    super('siliconflow')
+  }
+
+  get defaultModel() {
+    return 'Kwai-Kolors/Kolors'
+  }
+
+  get defaultBaseUrl() {
+    return PROVIDER_BASE_URLS.siliconflow
+  }
+
+  async generateImages(prompt, { apiKey, baseUrl, model, n, size, signal }) {
+    const response = await fetch(`${baseUrl}/images/generations`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${apiKey}` },
+      body: JSON.stringify({
+        model,
+        prompt,
+        ...(n ? { batch_size: n } : {}),
+        ...(size ? { image_size: size } : {}),
+      }),
+      signal,
+    })
+    if (!response.ok) throw await toHttpError(response, this.name)
+    const data = await response.json()
+    return (data?.images || data?.data || []).map(image => ({ url: image?.url }))
+  }
+}
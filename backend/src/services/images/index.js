@@ -0,0 +1,216 @@
+/**
+ * Image generation
+ * Text-to-image for POST /api/images/generate and the generate_image agent tool: OpenAI
+ * (gpt-image-1, DALL·E), OpenAI-compatible servers, Gemini Imagen, and SiliconFlow. Every image is
+ * stored in the "generated-images" data collection, so it outlives the provider's expiring URLs
+ * and is served from GET /api/images/:id/file. IMAGE_MAX_MB caps one image (default 20).
+ */
+
+import crypto from 'crypto'
+import fs from 'fs'
+import path from 'path'
+import { ErrorCode, QurioError } from '../../utils/errors.js'
+import { readRecord, resolveCollectionDir, writeRecord } from '../../utils/dataStore.js'
+import { sniffMimeType } from '../attachmentService.js'
+import { GeminiImageProvider } from './GeminiImageProvider.js'
+import { OpenAIImageProvider } from './OpenAIImageProvider.js'
+import { SiliconFlowImageProvider } from './SiliconFlowImageProvider.js'
+
+export const IMAGE_PROVIDERS = ['openai', 'openai_compatibility', 'gemini', 'siliconflow']
+export const IMAGE_RESPONSE_FORMATS = ['url', 'b64_json']
+export const MAX_IMAGES_PER_REQUEST = 4
+
+const COLLECTION = 'generated-images'
+const DEFAULT_MAX_MB = 20
+const MB = 1024 * 1024
+const MAX_PROMPT_CHARS = 4000
+const DOWNLOAD_TIMEOUT_MS = 60000
+const IMAGE_EXTENSIONS = {
+  'image/png': 'png',
+  'image/jpeg': 'jpg',
+  'image/webp': 'webp',
+  'image/gif': 'gif',
+}
+
+const providerCache = new Map()
+
+/**
+ * Get image provider instance
+ * @param {string} provider - Provider name
+ * @returns {import('./ImageProvider.js').ImageProvider}
+ */
+export function getImageProvider(provider) {
+  if (providerCache.has(provider)) return providerCache.get(provider)
+
+  let instance
+  switch (provider) {
+    case 'openai':
+      instance = new OpenAIImageProvider()
+      break
+    case 'openai_compatibility':
+      instance = new OpenAIImageProvider('openai_compatibility')
+      break
+    case 'gemini':
+      instance = new GeminiImageProvider()
+      break
+    case 'siliconflow':
+      instance = new SiliconFlowImageProvider()
+      break
+    default:
+      throw new QurioError(
+        ErrorCode.InvalidRequest,
+        `Unsupported image provider: ${provider}. Supported: ${IMAGE_PROVIDERS.join(', ')}`,
+      )
+  }
+
+  providerCache.set(provider, instance)
+  return instance
+}
+
+const getMaxImageBytes = () => {
+  const megabytes = Number.parseFloat(process.env.IMAGE_MAX_MB)
+  return (Number.isFinite(megabytes) && megabytes > 0 ? megabytes : DEFAULT_MAX_MB) * MB
+}
+
+const imagePath = record => path.join(resolveCollectionDir(COLLECTION), record.file)
+
+// Provider URLs expire, so the bytes are fetched while the link is fresh
+const downloadImage = async (url, signal) => {
+  const target = new URL(url)
+  if (!/^https?:$/.test(target.protocol)) throw new Error(`Unsupported image URL: ${url}`)
+  const timeoutSignal = AbortSignal.timeout(DOWNLOAD_TIMEOUT_MS)
+  const response = await fetch(target, {
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (!response.ok) throw new Error(`Image download failed: HTTP ${response.status}`)
+  return {
+    buffer: Buffer.from(await response.arrayBuffer()),
+    mimeType: (response.headers.get('content-type') || '').split(';')[0].trim(),
+  }
+}
+
+const storeImage = async (image, { prompt, provider, model, signal }) => {
+  const downloaded = image.b64_json
+    ? { buffer: Buffer.from(image.b64_json, 'base64'), mimeType: image.mime_type }
+    : await downloadImage(image.url, signal)
+  const { buffer } = downloaded
+  const mimeType = sniffMimeType(buffer) || downloaded.mimeType
+  if (!IMAGE_EXTENSIONS[mimeType]) {
+    throw new QurioError(ErrorCode.Upstream, 'Provider returned an unsupported image type', {
+      details: { mime_type: mimeType || null },
+    })
+  }
+  const maxBytes = getMaxImageBytes()
+  if (buffer.length > maxBytes) {
+    throw new QurioError(ErrorCode.Upstream, `Generated image is over ${maxBytes / MB} MB`)
+  }
+  const id = crypto.randomUUID()
+  const record = {
+    id,
+    file: `${id}.${IMAGE_EXTENSIONS[mimeType]}`,
+    mime_type: mimeType,
+    bytes: buffer.length,
+    prompt,
+    revised_prompt: image.revised_prompt || null,
+    provider,
+    model,
+    source_url: image.url || null,
+    created_at: new Date().toISOString(),
+  }
+  fs.writeFileSync(imagePath(record), buffer)
+  writeRecord(COLLECTION, id, record)
+  return { record, buffer }
+}
+
+export const getGeneratedImage = id => readRecord(COLLECTION, id)
+
+/**
+ * Stored bytes of a generated image
+ * @returns {{ buffer: Buffer, mimeType: string }|null}
+ */
+export const readGeneratedImage = id => {
+  const record = getGeneratedImage(id)
+  if (!record?.file || !fs.existsSync(imagePath(record))) return null
+  return { buffer: fs.readFileSync(imagePath(record)), mimeType: record.mime_type }
+}
+
+/**
+ * Public shape of a generated image for API responses, tool results and "image" SSE events
+ */
+export const toImageRef = record => ({
+  id: record.id,
+  url: `/api/images/${record.id}/file`,
+  mime_type: record.mime_type,
+  prompt: record.prompt,
+  revised_prompt: record.revised_prompt,
+  provider: record.provider,
+  model: record.model,
+  created_at: record.created_at,
+})
+
+/**
+ * Generate images and store them
+ * @param {Object} params
+ * @param {string} params.provider
+ * @param {string} params.prompt
+ * @param {string} params.apiKey
+ * @param {string} [params.baseUrl]
+ * @param {string} [params.model]
+ * @param {number} [params.n] - 1 to MAX_IMAGES_PER_REQUEST (default 1)
+ * @param {string} [params.size] - "1024x1024" style
+ * @param {'url'|'b64_json'} [params.responseFormat] - b64_json also returns the bytes inline
+ * @param {AbortSignal} [params.signal]
+ * @returns {Promise<{ provider, model, images: Array<Object> }>} images: toImageRef shapes, plus
+ *   "b64_json" with responseFormat b64_json and "source_url" when the provider answered a URL
+ * @throws {QurioError} invalid_request for a missing prompt, bad n/size or unknown provider
+ */
+export const generateImages = async ({
+  provider,
+  prompt,
+  n = 1,
+  size,
+  responseFormat = 'url',
+  signal,
+  ...options
+}) => {
+  const generator = getImageProvider(provider)
+  const text = String(prompt || '').trim()
+  if (!text) throw new QurioError(ErrorCode.InvalidRequest, 'Prompt is empty')
+  if (text.length > MAX_PROMPT_CHARS) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Prompt is over ${MAX_PROMPT_CHARS} characters`)
+  }
+  const count = Number(n)
+  if (!Number.isInteger(count) || count < 1 || count > MAX_IMAGES_PER_REQUEST) {
+    throw new QurioError(ErrorCode.InvalidRequest, `n must be 1-${MAX_IMAGES_PER_REQUEST}`)
+  }
+  if (size && !/^\d{2,5}x\d{2,5}$/.test(String(size))) {
+    throw new QurioError(ErrorCode.InvalidRequest, 'size must look like "1024x1024"')
+  }
+  if (!IMAGE_RESPONSE_FORMATS.includes(responseFormat)) {
+    throw new QurioError(
+      ErrorCode.InvalidRequest,
+      `Unsupported response_format: ${responseFormat}. ` +
+        `Supported: ${IMAGE_RESPONSE_FORMATS.join(', ')}`,
+    )
+  }
+
+  const result = await generator.generate(text, { ...options, n: count, size, signal })
+  if (!result.images.length) {
+    throw new QurioError(ErrorCode.Upstream, `${provider} returned no images`)
+  }
+  const images = []
+  for (const image of result.images.slice(0, count)) {
+    const { record, buffer } = await storeImage(image, {
+      prompt: text,
+      provider: result.provider,
+      model: result.model,
+      signal,
+    })
+    images.push({
+      ...toImageRef(record),
+      ...(record.source_url ? { source_url: record.source_url } : {}),
+      ...(responseFormat === 'b64_json' ? { b64_json: buffer.toString('base64') } : {}),
+    })
+  }
+  return { provider: result.provider, model: result.model, images }
+}
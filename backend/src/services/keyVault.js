@@ -0,0 +1,161 @@
+/**
+ * Key vault
+ * Encrypted-at-rest provider credentials pinned per space, resolved server-side by space_id,
+ * plus named domain-bound secrets for tool requests. The encrypt/decrypt helpers are shared by
+ * other stores that keep secrets on disk.
+ */
+
+import crypto from 'crypto'
+import fs from 'fs'
+import path from 'path'
+import { deleteRecord, getDataDir, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+
+const COLLECTION = 'space-credentials'
+const KEY_FILE = 'vault.key'
+
+let cachedKey = null
+
+/**
+ * 32-byte key from QURIO_VAULT_KEY, or a random key kept next to the data store
+ */
+const getVaultKey = () => {
+  if (cachedKey) return cachedKey
+  const secret = process.env.QURIO_VAULT_KEY
+  if (secret) {
+    cachedKey = crypto.createHash('sha256').update(secret).digest()
+    return cachedKey
+  }
+  const keyPath = path.join(getDataDir(), KEY_FILE)
+  if (fs.existsSync(keyPath)) {
+    cachedKey = Buffer.from(fs.readFileSync(keyPath, 'utf8').trim(), 'base64')
+    return cachedKey
+  }
+  fs.mkdirSync(getDataDir(), { recursive: true })
+  cachedKey = crypto.randomBytes(32)
+  fs.writeFileSync(keyPath, cachedKey.toString('base64'), { mode: 0o600 })
+  return cachedKey
+}
+
+export const encryptSecret = plaintext => {
+  const iv = crypto.randomBytes(12)
+  const cipher = crypto.createCipheriv('aes-256-gcm', getVaultKey(), iv)
+  const data = Buffer.concat([cipher.update(String(plaintext), 'utf8'), cipher.final()])
+  return [iv, cipher.getAuthTag(), data].map(part => part.toString('base64')).join('.')
+}
+
+export const decryptSecret = payload => {
+  const [iv, tag, data] = String(payload)
+    .split('.')
+    .map(part => Buffer.from(part, 'base64'))
+  const decipher = crypto.createDecipheriv('aes-256-gcm', getVaultKey(), iv)
+  decipher.setAuthTag(tag)
+  return Buffer.concat([decipher.update(data), decipher.final()]).toString('utf8')
+}
+
+export const maskSecret = secret => {
+  const value = String(secret || '')
+  if (value.length <= 8) return '****'
+  return `${value.slice(0, 3)}****${value.slice(-4)}`
+}
+
+const toPublicEntry = record => ({
+  spaceId: record.spaceId,
+  provider: record.provider,
+  baseUrl: record.baseUrl || null,
+  model: record.model || null,
+  apiKeyMasked: record.apiKeyMasked,
+  updatedAt: record.updatedAt,
+})
+
+/**
+ * Pin provider credentials to a space (replaces any existing entry)
+ */
+export const setSpaceCredentials = (spaceId, { provider, apiKey, baseUrl, model }) => {
+  const record = {
+    spaceId: String(spaceId),
+    provider,
+    apiKeyEncrypted: encryptSecret(apiKey),
+    apiKeyMasked: maskSecret(apiKey),
+    baseUrl: baseUrl || null,
+    model: model || null,
+    updatedAt: new Date().toISOString(),
+  }
+  writeRecord(COLLECTION, record.spaceId, record)
+  return toPublicEntry(record)
+}
+
+export const deleteSpaceCredentials = spaceId => deleteRecord(COLLECTION, String(spaceId))
+
+/**
+ * Masked view of a space's pinned credentials, or null
+ */
+export const getSpaceCredentialsInfo = spaceId => {
+  const record = readRecord(COLLECTION, String(spaceId))
+  return record ? toPublicEntry(record) : null
+}
+
+export const listSpaceCredentials = () => listRecords(COLLECTION).map(toPublicEntry)
+
+/**
+ * Decrypted credentials for a space, or null when none are pinned
+ */
+export const resolveSpaceCredentials = spaceId => {
+  if (!spaceId) return null
+  const record = readRecord(COLLECTION, String(spaceId))
+  if (!record?.apiKeyEncrypted) return null
+  try {
+    return {
+      provider: record.provider,
+      apiKey: decryptSecret(record.apiKeyEncrypted),
+      baseUrl: record.baseUrl || undefined,
+      model: record.model || undefined,
+    }
+  } catch (error) {
+    console.warn(`[KeyVault] Failed to decrypt credentials for space ${spaceId}:`, error.message)
+    return null
+  }
+}
+
+const SECRETS_COLLECTION = 'secrets'
+const SECRET_NAME_PATTERN = /^[A-Za-z_][A-Za-z0-9_]{0,63}$/
+
+const toPublicSecret = record => ({
+  name: record.name,
+  domains: record.domains,
+  valueMasked: record.valueMasked,
+  updatedAt: record.updatedAt,
+})
+
+/**
+ * Store a named secret usable only for requests to the given domains
+ */
+export const setSecret = (name, { value, domains = [] }) => {
+  if (!SECRET_NAME_PATTERN.test(String(name || ''))) {
+    throw new Error('Secret name must be an identifier (letters, digits, underscore)')
+  }
+  if (!value) throw new Error('Secret value is required')
+  const record = {
+    name,
+    domains: (Array.isArray(domains) ? domains : [domains])
+      .map(domain => String(domain).trim().toLowerCase())
+      .filter(Boolean),
+    valueEncrypted: encryptSecret(value),
+    valueMasked: maskSecret(value),
+    updatedAt: new Date().toISOString(),
+  }
+  writeRecord(SECRETS_COLLECTION, name, record)
+  return toPublicSecret(record)
+}
+
+export const deleteSecret = name => deleteRecord(SECRETS_COLLECTION, String(name))
+
+export const listSecrets = () => listRecords(SECRETS_COLLECTION).map(toPublicSecret)
+
+/**
+ * Decrypted secret with its domain binding, or null
+ */
+export const resolveSecret = name => {
+  const record = readRecord(SECRETS_COLLECTION, String(name))
+  if (!record?.valueEncrypted) return null
+  return { value: decryptSecret(record.valueEncrypted), domains: record.domains || [] }
+}
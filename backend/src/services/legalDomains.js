@@ -0,0 +1,53 @@
+/**
+ * Legal domains configuration
+ * Case law, legislation, and court sources preferred by the Tavily_legal_search tool
+ */
+
+export const LEGAL_DOMAINS = [
+  // Case law (US)
+  'courtlistener.com',
+  'law.justia.com',
+  'supreme.justia.com',
+  'casetext.com',
+  'supremecourt.gov',
+  'uscourts.gov',
+  'law.cornell.edu',
+  'caselaw.findlaw.com',
+  'scholar.google.com',
+
+  // Legislation (US)
+  'congress.gov',
+  'uscode.house.gov',
+  'ecfr.gov',
+  'federalregister.gov',
+  'govinfo.gov',
+
+  // UK and Commonwealth
+  'bailii.org',
+  'legislation.gov.uk',
+  'supremecourt.uk',
+  'judiciary.uk',
+  'canlii.org',
+  'laws-lois.justice.gc.ca',
+  'austlii.edu.au',
+  'legislation.gov.au',
+  'nzlii.org',
+  'legislation.govt.nz',
+
+  // EU and international
+  'eur-lex.europa.eu',
+  'curia.europa.eu',
+  'hudoc.echr.coe.int',
+  'icj-cij.org',
+  'worldlii.org',
+  'commonlii.org',
+
+  // Other national portals
+  'gesetze-im-internet.de',
+  'legifrance.gouv.fr',
+  'boe.es',
+  'normattiva.it',
+  'indiankanoon.org',
+  'elitigation.sg',
+  'hklii.hk',
+]
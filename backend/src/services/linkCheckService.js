@@ -0,0 +1,247 @@
+/**
+ * Link check service
+ * Periodically re-validates source URLs cited by saved research runs (link rot detection):
+ * each URL is classified as ok / redirected / paywalled / restricted / broken / unreachable,
+ * affected citations in the stored runs are marked, and dead or changed links get an archived
+ * Wayback Machine snapshot suggested as a replacement.
+ * Runs as the "link-check" background job (see backgroundService).
+ */
+
+import crypto from 'crypto'
+import { listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import { fetchPublicUrl } from '../utils/publicFetch.js'
+import { backgroundJobManager } from './backgroundService.js'
+import { getResearchRun, saveResearchRun } from './researchRunStore.js'
+import { findSnapshot } from './waybackService.js'
+
+const COLLECTION = 'source-checks'
+const RUNS_COLLECTION = 'research-runs'
+const USER_AGENT = 'Mozilla/5.0 (compatible; QurioLinkCheck/1.0)'
+const REQUEST_TIMEOUT_MS = 15000
+const CONCURRENCY = 4
+const PAYWALL_SNIFF_CHARS = 200000
+const DEFAULT_INTERVAL_MS = 6 * 60 * 60 * 1000
+const DEFAULT_RECHECK_HOURS = 7 * 24
+const DEFAULT_BATCH_SIZE = 50
+
+export const LINK_STATUSES = [
+  'ok',
+  'redirected',
+  'paywalled',
+  'restricted',
+  'broken',
+  'unreachable',
+]
+// Statuses worth an archived replacement
+const NEEDS_ARCHIVE = new Set(['redirected', 'paywalled', 'broken', 'unreachable'])
+
+const PAYWALL_PATTERNS = [
+  /"isAccessibleForFree"\s*:\s*"?false/i,
+  /class=["'][^"']*\b(?:paywall|piano-offer|meteredContent|subscriber-only)\b/i,
+]
+const LOGIN_PATH_PATTERN = /\/(?:login|signin|sign-in|subscribe|subscription|account\/access)\b/i
+
+const parsePositiveInt = (value, fallback) => {
+  const number = Number.parseInt(value, 10)
+  return Number.isFinite(number) && number > 0 ? number : fallback
+}
+
+export const getLinkCheckConfig = () => ({
+  intervalMs: parsePositiveInt(process.env.LINK_CHECK_INTERVAL_MS, DEFAULT_INTERVAL_MS),
+  recheckHours: parsePositiveInt(process.env.LINK_CHECK_RECHECK_HOURS, DEFAULT_RECHECK_HOURS),
+  batchSize: parsePositiveInt(process.env.LINK_CHECK_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+})
+
+const checkIdFor = url => crypto.createHash('sha1').update(url).digest('hex').slice(0, 16)
+
+// Same resource despite cosmetic differences (scheme upgrade, trailing slash, www)
+const canonicalUrl = value => {
+  try {
+    const url = new URL(value)
+    return `${url.host.replace(/^www\./, '')}${url.pathname.replace(/\/+$/, '')}${url.search}`
+  } catch {
+    return String(value)
+  }
+}
+
+/**
+ * Fetch a URL and classify its current state
+ * @returns {Promise<{ status, http_status, final_url, error }>}
+ */
+export const checkLink = async (url, { signal } = {}) => {
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  let response
+  try {
+    // A URL on a private or local address is reported unreachable without being requested
+    response = await fetchPublicUrl(url, {
+      headers: { 'User-Agent': USER_AGENT, Accept: 'text/html,application/xhtml+xml,*/*' },
+      signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+    })
+  } catch (error) {
+    if (signal?.aborted) throw error
+    return { status: 'unreachable', http_status: null, final_url: null, error: error.message }
+  }
+
+  const finalUrl = response.url || url
+  const result = { http_status: response.status, final_url: finalUrl, error: null }
+  if (response.status === 404 || response.status === 410) return { ...result, status: 'broken' }
+  if (response.status === 402) return { ...result, status: 'paywalled' }
+  if (response.status === 401 || response.status === 403 || response.status === 451) {
+    return { ...result, status: 'restricted' }
+  }
+  if (!response.ok) return { ...result, status: 'unreachable' }
+
+  const moved = canonicalUrl(finalUrl) !== canonicalUrl(url)
+  if (moved && LOGIN_PATH_PATTERN.test(new URL(finalUrl).pathname)) {
+    return { ...result, status: 'paywalled' }
+  }
+  if (/html/i.test(response.headers.get('content-type') || '')) {
+    const html = (await response.text().catch(() => '')).slice(0, PAYWALL_SNIFF_CHARS)
+    if (PAYWALL_PATTERNS.some(pattern => pattern.test(html))) {
+      return { ...result, status: 'paywalled' }
+    }
+  }
+  return { ...result, status: moved ? 'redirected' : 'ok' }
+}
+
+export const getSourceCheck = url => readRecord(COLLECTION, checkIdFor(url))
+
+/**
+ * Stored check results, optionally filtered by status
+ */
+export const listSourceChecks = ({ status } = {}) =>
+  listRecords(COLLECTION)
+    .filter(check => !status || check.status === status)
+    .sort((a, b) => String(b.changed_at).localeCompare(String(a.changed_at)))
+
+/**
+ * Re-check one URL, keep its history, and look up an archived copy when it went bad
+ */
+const refreshSourceCheck = async (url, { near, signal }) => {
+  const previous = getSourceCheck(url)
+  const result = await checkLink(url, { signal })
+  const checkedAt = new Date().toISOString()
+  const changed = !previous || previous.status !== result.status
+
+  let archived = previous?.archived || null
+  if (NEEDS_ARCHIVE.has(result.status) && (changed || !archived)) {
+    try {
+      archived = await findSnapshot(url, { near, signal })
+    } catch (error) {
+      if (signal?.aborted) throw error
+      console.warn('[LinkCheck] Wayback lookup failed:', error.message)
+    }
+  }
+
+  return writeRecord(COLLECTION, checkIdFor(url), {
+    url,
+    ...result,
+    previous_status: changed ? previous?.status || null : previous.previous_status || null,
+    first_checked_at: previous?.first_checked_at || checkedAt,
+    checked_at: checkedAt,
+    changed_at: changed ? checkedAt : previous.changed_at,
+    archived: NEEDS_ARCHIVE.has(result.status) ? archived : null,
+  })
+}
+
+const sourceUrl = source => source?.url || source?.uri || null
+
+const toLinkStatus = check => ({
+  status: check.status,
+  http_status: check.http_status,
+  final_url: check.final_url !== check.url ? check.final_url : undefined,
+  checked_at: check.checked_at,
+  archived_url: check.archived?.url,
+  archived_at: check.archived?.archived_at,
+})
+
+/**
+ * Write the latest check results onto a run's sources and its link_check summary
+ */
+const markRun = ({ id }) => {
+  // Re-read so a run saved while URLs were being checked is not overwritten
+  const run = getResearchRun(id)
+  if (!run) return null
+  const sources = (run.sources || []).map(source => {
+    const url = sourceUrl(source)
+    const check = url ? getSourceCheck(url) : null
+    return check ? { ...source, link_status: toLinkStatus(check) } : source
+  })
+  const counts = Object.fromEntries(LINK_STATUSES.map(status => [status, 0]))
+  sources.forEach(source => {
+    if (source.link_status) counts[source.link_status.status] += 1
+  })
+  saveResearchRun({
+    ...run,
+    sources,
+    link_check: { checked_at: new Date().toISOString(), counts },
+  })
+  return counts
+}
+
+/**
+ * One pass: re-check the least recently checked source URLs of finished runs, then mark runs
+ * @param {Object} [options]
+ * @param {string} [options.runId] Check only this run's sources (ignores the recheck age)
+ * @param {number} [options.batchSize] Max URLs to fetch in this pass
+ * @returns {Promise<{ checked: number, changed: number, runs_marked: number, by_status }>}
+ */
+export const runLinkCheck = async ({ runId, batchSize, signal } = {}) => {
+  const config = getLinkCheckConfig()
+  const runs = runId
+    ? [getResearchRun(runId)].filter(Boolean)
+    : listRecords(RUNS_COLLECTION).filter(run => run.status === 'done')
+
+  // URL -> finish date of the earliest run citing it (the snapshot we want is from then)
+  const citedAt = new Map()
+  for (const run of runs) {
+    for (const source of run.sources || []) {
+      const url = sourceUrl(source)
+      if (!url || !/^https?:/i.test(url)) continue
+      const when = run.finishedAt || run.startedAt
+      if (!citedAt.has(url) || String(when) < String(citedAt.get(url))) citedAt.set(url, when)
+    }
+  }
+
+  const staleBefore = Date.now() - config.recheckHours * 60 * 60 * 1000
+  const due = Array.from(citedAt.keys())
+    .map(url => ({ url, checkedAt: Date.parse(getSourceCheck(url)?.checked_at || '') || 0 }))
+    .filter(item => runId || item.checkedAt < staleBefore)
+    .sort((a, b) => a.checkedAt - b.checkedAt)
+    .slice(0, batchSize || config.batchSize)
+
+  let changed = 0
+  const byStatus = Object.fromEntries(LINK_STATUSES.map(status => [status, 0]))
+  for (let offset = 0; offset < due.length; offset += CONCURRENCY) {
+    const batch = due.slice(offset, offset + CONCURRENCY)
+    const results = await Promise.all(
+      batch.map(async ({ url }) => {
+        const before = getSourceCheck(url)?.status
+        const check = await refreshSourceCheck(url, { near: citedAt.get(url), signal })
+        return { check, changed: before !== undefined && before !== check.status }
+      }),
+    )
+    results.forEach(item => {
+      byStatus[item.check.status] += 1
+      if (item.changed) changed += 1
+    })
+  }
+
+  const checkedUrls = new Set(due.map(item => item.url))
+  const affected = runs.filter(run =>
+    (run.sources || []).some(source => checkedUrls.has(sourceUrl(source))),
+  )
+  affected.forEach(markRun)
+
+  return { checked: due.length, changed, runs_marked: affected.length, by_status: byStatus }
+}
+
+backgroundJobManager.registerJob({
+  name: 'link-check',
+  description: 'Re-validate sources cited by saved research runs and suggest archived copies',
+  intervalMs: getLinkCheckConfig().intervalMs,
+  handler: async () => {
+    const summary = await runLinkCheck()
+    if (summary.checked) console.log('[LinkCheck] Pass finished:', JSON.stringify(summary))
+  },
+})
@@ -0,0 +1,232 @@
+/**
+ * Locale format service
+ * Rewrites numbers, numeric dates and unit spacing in generated reports to a locale's conventions
+ * (1,234.56 vs 1.234,56; 2024-03-05 vs 05.03.2024; 5% vs 5 %). The rewrite is deterministic and
+ * uses Intl, so it does not depend on the model getting separators right.
+ *
+ * Left as written: code, links, citation brackets, outline numbers and numbers after "Section",
+ * "Table", "version" and the like, dates with month names (unambiguous, and rewriting them would
+ * mean translating the month), slash dates that read both ways (03/05/2024), and 1,234 / 1.234
+ * when nothing else in the report tells which separator is the decimal one.
+ */
+
+// Unit symbols whose spacing follows the locale, mapped to Intl units
+const UNITS = {
+  'km/h': 'kilometer-per-hour',
+  mph: 'mile-per-hour',
+  '%': 'percent',
+  '°C': 'celsius',
+  '°F': 'fahrenheit',
+  km: 'kilometer',
+  cm: 'centimeter',
+  mm: 'millimeter',
+  kg: 'kilogram',
+  mL: 'milliliter',
+  ml: 'milliliter',
+  mi: 'mile',
+  ft: 'foot',
+  lb: 'pound',
+  lbs: 'pound',
+  oz: 'ounce',
+  TB: 'terabyte',
+  GB: 'gigabyte',
+  MB: 'megabyte',
+  KB: 'kilobyte',
+  kB: 'kilobyte',
+  m: 'meter',
+  g: 'gram',
+  L: 'liter',
+}
+// Only units after a space: "5m" is as likely to mean five million
+const SPACED_UNITS = new Set(['m', 'g', 'L'])
+
+const escapeRegex = value => value.replace(/[.*+?^${}()|[\]\\/]/g, '\\$&')
+const UNIT_ALTERNATION = Object.keys(UNITS)
+  .sort((a, b) => b.length - a.length)
+  .map(escapeRegex)
+  .join('|')
+
+const BEFORE = String.raw`(?<![\p{L}\p{N}_.,/#:]|\p{L}-)`
+const AFTER = String.raw`(?![\p{L}\p{N}_/:-]|[.,]\p{N})`
+const TOKEN_PATTERN = new RegExp(
+  [
+    String.raw`${BEFORE}(\d{4})-(\d{2})-(\d{2})${AFTER}`,
+    String.raw`${BEFORE}(\d{1,2})([./])(\d{1,2})\5(\d{4})${AFTER}`,
+    String.raw`${BEFORE}(\d+(?:[.,]\d+)*)(?:([ \u00a0\u202f]?)(${UNIT_ALTERNATION})(?![\p{L}\p{N}_/]))?` +
+      String.raw`(?![\p{L}\p{N}_]|[.,]\p{N})`,
+  ].join('|'),
+  'gu',
+)
+
+// Code, link targets, bare URLs, citations, and outline/version numbers
+const PROTECTED_PATTERN = new RegExp(
+  [
+    '`+[^`]*`+',
+    String.raw`\]\([^)\s]*\)`,
+    String.raw`<https?:[^>\s]+>`,
+    String.raw`https?:\/\/[^\s)>\]]+`,
+    String.raw`\[\^?\d[\d\s,–-]*\]`,
+    '【[\\d\\s,–-]*】',
+    String.raw`^\s*(?:#{1,6}\s+)?\d+(?:\.\d+)+\.?(?=\s)`,
+    String.raw`\b(?:Section|Chapter|Figure|Fig\.|Table|Version|version|v)\s*\d+(?:\.\d+)+`,
+    String.raw`§\s*\d+(?:\.\d+)*`,
+  ].join('|'),
+)
+const SPLIT_PATTERN = new RegExp(`(${PROTECTED_PATTERN.source})`)
+const FENCE_PATTERN = /^\s*(```|~~~)/
+
+const POINT_STYLE = /^(?:\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+\.\d+)$/
+const COMMA_STYLE = /^(?:\d{1,3}(?:\.\d{3})+(?:,\d+)?|\d+,\d+)$/
+
+/**
+ * Canonical BCP 47 tag Intl can format for, or null
+ */
+export const normalizeLocale = value => {
+  if (typeof value !== 'string' || !value.trim()) return null
+  try {
+    const [locale] = Intl.getCanonicalLocales(value.trim())
+    return Intl.NumberFormat.supportedLocalesOf(locale).length ? locale : null
+  } catch {
+    return null
+  }
+}
+
+/**
+ * Locale to format a report for: the request's override (a tag, or false / '' to turn formatting
+ * off) or else the reportLocale preference
+ */
+export const resolveReportLocale = (override, preferences) =>
+  normalizeLocale(override ?? preferences?.reportLocale)
+
+/**
+ * Read a number token as { integer, fraction, grouped } under the report's decimal convention
+ * ('point' for 1,234.56, 'comma' for 1.234,56; null when unknown); null when it is not a number
+ * or could be read both ways
+ */
+const parseNumber = (token, convention) => {
+  if (/^\d+$/.test(token)) return { integer: token, fraction: '', grouped: false }
+  const point = POINT_STYLE.test(token)
+  const comma = COMMA_STYLE.test(token)
+  const style = point && comma ? convention : point ? 'point' : comma ? 'comma' : null
+  if (!style) return null
+  const [group, decimal] = style === 'point' ? [',', '.'] : ['.', ',']
+  const [integer, fraction = ''] = token.split(decimal)
+  return { integer: integer.split(group).join(''), fraction, grouped: integer.includes(group) }
+}
+
+// The convention most unambiguous numbers use; a tie or no evidence leaves it unknown
+const detectConvention = segments => {
+  let point = 0
+  let comma = 0
+  for (const segment of segments) {
+    for (const match of segment.matchAll(TOKEN_PATTERN)) {
+      const token = match[8]
+      if (!token || /^\d+$/.test(token)) continue
+      const isPoint = POINT_STYLE.test(token)
+      const isComma = COMMA_STYLE.test(token)
+      if (isPoint && !isComma) point += 1
+      if (isComma && !isPoint) comma += 1
+    }
+  }
+  if (point === comma) return null
+  return point > comma ? 'point' : 'comma'
+}
+
+const createFormatter = locale => {
+  const numberFormats = new Map()
+  const numberFormat = options => {
+    const key = JSON.stringify(options)
+    if (!numberFormats.has(key)) numberFormats.set(key, new Intl.NumberFormat(locale, options))
+    return numberFormats.get(key)
+  }
+  const dateFormat = new Intl.DateTimeFormat(locale, {
+    year: 'numeric',
+    month: '2-digit',
+    day: '2-digit',
+    timeZone: 'UTC',
+  })
+
+  const formatDate = (year, month, day) => {
+    const date = new Date(Date.UTC(year, month - 1, day))
+    if (date.getUTCMonth() !== month - 1 || date.getUTCDate() !== day) return null
+    return dateFormat.format(date)
+  }
+
+  // Numbers keep their precision; grouping is only added where the source grouped
+  const formatNumber = ({ integer, fraction, grouped }, unit, symbol) => {
+    const digits = fraction.length
+    const options = {
+      minimumFractionDigits: digits,
+      maximumFractionDigits: digits,
+      useGrouping: grouped,
+      ...(unit ? { style: 'unit', unit, unitDisplay: 'short' } : {}),
+    }
+    // A decimal string is formatted exactly, without a round trip through floating point
+    const parts = numberFormat(options).formatToParts(digits ? `${integer}.${fraction}` : integer)
+    // The unit keeps the symbol as written; only spacing and placement follow the locale
+    return parts.map(part => (part.type === 'unit' ? symbol : part.value)).join('')
+  }
+
+  return { formatDate, formatNumber }
+}
+
+const rewriteToken = ({ formatDate, formatNumber }, convention, groups) => {
+  const [isoYear, isoMonth, isoDay, first, separator, second, year, token, space, symbol] = groups
+  if (isoYear) return formatDate(+isoYear, +isoMonth, +isoDay)
+  if (year) {
+    if (separator === '.') return formatDate(+year, +second, +first)
+    // Slash dates: only when one part is over 12 can day and month be told apart
+    if (+first > 12) return formatDate(+year, +second, +first)
+    if (+second > 12) return formatDate(+year, +first, +second)
+    return null
+  }
+  const number = parseNumber(token, convention)
+  if (!number) return null
+  const unit = symbol && (space || !SPACED_UNITS.has(symbol)) ? UNITS[symbol] : null
+  if (!unit && !number.fraction && !number.grouped) return null
+  const formatted = formatNumber(number, unit, symbol)
+  if (!formatted || unit) return formatted
+  return `${formatted}${space || ''}${symbol || ''}`
+}
+
+/**
+ * Rewrite a markdown report for a locale
+ * @param {string} markdown
+ * @param {string} locale - BCP 47 tag, e.g. "de-DE"
+ * @returns {{ content: string, changes: number }} changes: numbers and dates rewritten
+ */
+export const formatForLocale = (markdown, locale) => {
+  const content = String(markdown || '')
+  const tag = normalizeLocale(locale)
+  if (!tag || !content) return { content, changes: 0 }
+
+  // Lines outside code fences, split into [text, protected, text, ...]
+  let inFence = false
+  const lines = content.split('\n').map(line => {
+    if (FENCE_PATTERN.test(line)) {
+      inFence = !inFence
+      return line
+    }
+    return inFence ? line : line.split(SPLIT_PATTERN)
+  })
+  const textSegments = lines
+    .filter(Array.isArray)
+    .flatMap(parts => parts.filter((_, index) => index % 2 === 0))
+  const convention = detectConvention(textSegments)
+  const formatter = createFormatter(tag)
+
+  let changes = 0
+  const rewriteSegment = segment =>
+    segment.replace(TOKEN_PATTERN, (match, ...groups) => {
+      const formatted = rewriteToken(formatter, convention, groups)
+      if (!formatted || formatted === match) return match
+      changes += 1
+      return formatted
+    })
+  const rewritten = lines.map(parts =>
+    Array.isArray(parts)
+      ? parts.map((part, index) => (index % 2 === 1 ? part : rewriteSegment(part))).join('')
+      : parts,
+  )
+  return { content: rewritten.join('\n'), changes }
+}
@@ -5,7 +5,8 @@ import { MultiServerMCPClient } from '@langchain/mcp-adapters'
 
 /**
  * MCP Tool Manager
- * Handles loading, caching, and managing MCP tools from ModelScope servers
+ * Handles loading, caching, and managing MCP tools from remote (SSE / streamable HTTP) and
+ * local stdio servers
  */
 class MCPToolManager {
   constructor() {
@@ -30,6 +31,21 @@ class MCPToolManager {
       }
     }
 
+    // Local servers launched as a child process and spoken to over stdin/stdout
+    if (serverConfig?.command) {
+      if (process.env.MCP_STDIO_ENABLED !== '1') {
+        throw new Error('stdio MCP servers are disabled (set MCP_STDIO_ENABLED=1 to allow them)')
+      }
+      return {
+        name,
+        transport: 'stdio',
+        command: serverConfig.command,
+        args: Array.isArray(serverConfig.args) ? serverConfig.args.map(String) : [],
+        env: serverConfig.env || undefined,
+        cwd: serverConfig.cwd || undefined,
+      }
+    }
+
     const url = serverConfig?.url || serverConfig?.serverUrl || serverConfig?.sseUrl
     if (!url) {
       throw new Error(`Missing server URL for ${name}`)
@@ -54,7 +70,11 @@ class MCPToolManager {
     return 'sse'
   }
 
-  buildServerConfig({ url, transport, headers, bearerToken }) {
+  buildServerConfig({ url, transport, headers, bearerToken, command, args, env, cwd }) {
+    if (transport === 'stdio') {
+      return { transport, command, args, env, cwd }
+    }
+
     const authHeaders = bearerToken ? { Authorization: `Bearer ${bearerToken}` } : {}
     const mergedHeaders = { ...headers, ...authHeaders }
 
@@ -118,7 +138,10 @@ class MCPToolManager {
       })
 
       // Store the connection
-      this.connections.set(name, { client, url: normalizedConfig.url })
+      this.connections.set(name, {
+        client,
+        url: normalizedConfig.url || normalizedConfig.command,
+      })
 
       console.log(`[MCP Manager] ✅ Connected to ${name}`)
 
@@ -355,6 +378,20 @@ class MCPToolManager {
     return this.listMcpTools().filter(tool => tool.config.mcpServer === serverName)
   }
 
+  /**
+   * Tools of loaded servers, in the user-tool shape stream_chat executes via executeCustomTool
+   * @param {string[]|null} serverNames - Servers to include (null: every loaded server)
+   * @returns {Array} Array of tools
+   */
+  listToolsForServers(serverNames = null) {
+    const wanted = serverNames ? new Set(serverNames) : null
+    return this.listMcpTools().filter(
+      tool =>
+        this.loadedServers.has(tool.config?.mcpServer) &&
+        (!wanted || wanted.has(tool.config.mcpServer)),
+    )
+  }
+
   /**
    * Execute an MCP tool
    * @param {string} toolId - Tool ID
@@ -409,7 +446,8 @@ class MCPToolManager {
 
     try {
       const normalizedConfig = this.normalizeServerConfig(serverName, serverConfig)
-      console.log(`[MCP Manager] Fetching tools from ${serverName} at ${normalizedConfig.url}`)
+      const target = normalizedConfig.url || normalizedConfig.command
+      console.log(`[MCP Manager] Fetching tools from ${serverName} at ${target}`)
 
       const serverConfigPayload = this.buildServerConfig(normalizedConfig)
       tempClient = new MultiServerMCPClient({
@@ -0,0 +1,175 @@
+/**
+ * Message journal
+ * Crash-safe persistence of an assistant answer while it streams into a stored conversation.
+ * The message is created up front with status "streaming"; every text/thought delta is appended
+ * to a write-ahead journal (<data>/message-journal/<message id>.jsonl) and the accumulated text is
+ * checkpointed into the message record every CHECKPOINT_MS. The done event finalizes the message
+ * in one atomic record write (content, thinking, sources, usage, status "complete") before the
+ * journal is dropped. On startup, journals left behind by a crash are replayed into their
+ * messages, which are marked "interrupted" with everything that was received.
+ */
+
+import fs from 'fs'
+import path from 'path'
+import { randomUUID } from 'crypto'
+import { resolveCollectionDir } from '../utils/dataStore.js'
+import { addMessage, getConversation, listMessages, updateMessage } from './conversationStore.js'
+
+const JOURNAL_COLLECTION = 'message-journal'
+const CHECKPOINT_MS = 2000
+const INTERRUPTED_ERROR = 'The backend stopped before this answer finished'
+
+const journalPath = messageId =>
+  path.join(
+    resolveCollectionDir(JOURNAL_COLLECTION),
+    `${String(messageId).replace(/[^\w.-]/g, '_')}.jsonl`,
+  )
+
+const now = () => new Date().toISOString()
+
+const appendEntry = (filePath, entry) => fs.appendFileSync(filePath, `${JSON.stringify(entry)}\n`)
+
+/**
+ * Replay a journal; a torn last line (crash mid-append) is skipped
+ * @returns {{ header: Object|null, content: string, thought: string }}
+ */
+const readJournal = filePath => {
+  let header = null
+  let content = ''
+  let thought = ''
+  for (const line of fs.readFileSync(filePath, 'utf8').split('\n')) {
+    if (!line) continue
+    let entry
+    try {
+      entry = JSON.parse(line)
+    } catch {
+      continue
+    }
+    if (entry.t === 'header') header = entry
+    else if (entry.t === 'text') content += entry.c
+    else if (entry.t === 'thought') thought += entry.c
+  }
+  return { header, content, thought }
+}
+
+/**
+ * Pass stream events through while persisting the answer into a conversation. Streams without a
+ * conversationId, or whose conversation is not stored on the backend, pass through unchanged.
+ * @param {AsyncIterable<Object>} events
+ * @param {Object} options
+ * @param {string} [options.conversationId]
+ * @param {string} [options.messageId] - Id for the assistant message (default random)
+ * @param {string} [options.streamId]
+ * @param {string} [options.provider]
+ * @param {string} [options.model]
+ * @param {AbortSignal} [options.signal] - An aborted stream is stored as "cancelled"
+ */
+export async function* journalStreamedMessage(
+  events,
+  { conversationId, messageId, streamId, provider, model, signal } = {},
+) {
+  if (!conversationId || !getConversation(conversationId)) {
+    yield* events
+    return
+  }
+
+  const message = addMessage(conversationId, {
+    id: messageId || randomUUID(),
+    role: 'assistant',
+    content: '',
+    status: 'streaming',
+    stream_id: streamId || null,
+    provider: provider || null,
+    model: model || null,
+  })
+  const filePath = journalPath(message.id)
+  appendEntry(filePath, {
+    t: 'header',
+    conversation_id: conversationId,
+    message_id: message.id,
+    started_at: now(),
+  })
+  yield { type: 'message_created', conversation_id: conversationId, message_id: message.id }
+
+  let content = ''
+  let thought = ''
+  let checkpointAt = Date.now()
+  let final = null
+  let failure = null
+  const persist = changes => updateMessage(conversationId, message.id, changes)
+
+  try {
+    for await (const event of events) {
+      if ((event?.type === 'text' || event?.type === 'thought') && event.content) {
+        appendEntry(filePath, { t: event.type, c: event.content })
+        if (event.type === 'text') content += event.content
+        else thought += event.content
+        if (Date.now() - checkpointAt >= CHECKPOINT_MS) {
+          persist({ content, thinking: thought || undefined })
+          checkpointAt = Date.now()
+        }
+      } else if (event?.type === 'done') {
+        final = event
+      }
+      yield event
+    }
+  } catch (error) {
+    failure = error
+    throw error
+  } finally {
+    // One record write commits the final state; the journal only goes once it is durable
+    persist(
+      final
+        ? {
+            content: final.content ?? content,
+            thinking: final.thought || thought || undefined,
+            sources: final.sources,
+            usage: final.usage,
+            status: 'complete',
+            completed_at: now(),
+          }
+        : {
+            content,
+            thinking: thought || undefined,
+            status: failure && !signal?.aborted ? 'error' : 'cancelled',
+            error: signal?.aborted ? undefined : failure?.message,
+            completed_at: now(),
+          },
+    )
+    fs.rmSync(filePath, { force: true })
+  }
+}
+
+/**
+ * Replay journals left by a crash into their messages and mark them "interrupted"
+ * @returns {number} Messages recovered
+ */
+export const recoverInterruptedMessages = () => {
+  const dir = resolveCollectionDir(JOURNAL_COLLECTION)
+  let recovered = 0
+  for (const name of fs.readdirSync(dir).filter(file => file.endsWith('.jsonl'))) {
+    const filePath = path.join(dir, name)
+    try {
+      const { header, content, thought } = readJournal(filePath)
+      const stored = header
+        ? listMessages(header.conversation_id).find(item => item.id === header.message_id)
+        : null
+      // A crash right after finalizing leaves a journal for a message that is already complete
+      if (stored?.status === 'streaming') {
+        updateMessage(header.conversation_id, header.message_id, {
+          content: content.length >= String(stored.content || '').length ? content : stored.content,
+          thinking: thought || stored.thinking,
+          status: 'interrupted',
+          error: INTERRUPTED_ERROR,
+          completed_at: now(),
+        })
+        recovered += 1
+      }
+      fs.rmSync(filePath, { force: true })
+    } catch (error) {
+      console.warn(`[MessageJournal] Failed to recover ${name}:`, error.message)
+    }
+  }
+  if (recovered) console.log(`[MessageJournal] Recovered ${recovered} interrupted message(s)`)
+  return recovered
+}
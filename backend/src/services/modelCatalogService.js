@@ -0,0 +1,72 @@
+/**
+ * Model catalog service
+ * Lists chat models per provider through the provider adapters and caches the result
+ * (MODELS_CACHE_TTL_MS, default 10 minutes) per provider, base URL, and API key. Models that
+ * were probed (POST /api/providers/:name/probe) report the probed capabilities instead of the
+ * guesses from the provider table and model name.
+ */
+
+import { createHash } from 'crypto'
+import { getProviderAdapter } from './providers/adapterFactory.js'
+import { getProbedCapabilities } from './providers/capabilityProbe.js'
+
+const DEFAULT_TTL_MS = 10 * 60 * 1000
+
+const cache = new Map()
+
+const getTtlMs = () => {
+  const ttl = Number.parseInt(process.env.MODELS_CACHE_TTL_MS, 10)
+  return Number.isFinite(ttl) && ttl >= 0 ? ttl : DEFAULT_TTL_MS
+}
+
+// Keys are hashed so the cache never holds them in plain text
+const buildCacheKey = (provider, apiKey, baseUrl) =>
+  [provider, baseUrl || '', createHash('sha256').update(String(apiKey)).digest('hex')].join('|')
+
+const applyProbedCapabilities = (provider, baseUrl, models) =>
+  models.map(descriptor => {
+    const probed = getProbedCapabilities({ provider, baseUrl, model: descriptor.id })
+    if (!probed) return descriptor
+    return {
+      ...descriptor,
+      supports_tools: probed.tool_calls ?? descriptor.supports_tools,
+      supports_vision: probed.vision ?? descriptor.supports_vision,
+      probed_capabilities: probed,
+    }
+  })
+
+/**
+ * List models for a provider
+ * @returns {Promise<{provider: string, models: Array, cached: boolean, fetchedAt: string}>}
+ */
+export const listProviderModels = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  refresh = false,
+  signal,
+}) => {
+  const key = buildCacheKey(provider, apiKey, baseUrl)
+  const entry = cache.get(key)
+  if (!refresh && entry && Date.now() - entry.fetchedAt < getTtlMs()) {
+    return {
+      provider,
+      models: applyProbedCapabilities(provider, baseUrl, entry.models),
+      cached: true,
+      fetchedAt: entry.at,
+    }
+  }
+
+  const models = await getProviderAdapter(provider).listModels({ apiKey, baseUrl, signal })
+  models.sort((a, b) => a.id.localeCompare(b.id))
+  const at = new Date().toISOString()
+  cache.set(key, { models, fetchedAt: Date.now(), at })
+  return {
+    provider,
+    models: applyProbedCapabilities(provider, baseUrl, models),
+    cached: false,
+    fetchedAt: at,
+  }
+}
+
+export const clearModelCache = () => cache.clear()
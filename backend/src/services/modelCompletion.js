@@ -0,0 +1,79 @@
+/**
+ * Model completion helpers
+ * One-shot (non-streaming) completions through the provider adapters, for auxiliary passes
+ * such as decomposition, extraction, and post-processing.
+ */
+
+import { jsonrepair } from 'jsonrepair'
+import { getProviderAdapter } from './providers/adapterFactory.js'
+import { normalizeGeminiMessages, normalizeTextContent, toLangChainMessages } from './serviceUtils.js'
+
+// Reasoning output only slows down auxiliary passes and can leak into JSON
+const resolveAuxThinking = provider =>
+  provider === 'glm' || provider === 'modelscope' ? { type: 'disabled' } : undefined
+
+const stripThinking = text => String(text || '').replace(/<think>[\s\S]*?<\/think>/gi, '')
+
+/**
+ * Run a single non-streaming completion and return the text content
+ */
+export const completeText = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  messages,
+  temperature,
+  responseFormat,
+  signal,
+}) => {
+  const adapter = getProviderAdapter(provider)
+  const modelInstance = adapter.buildModel({
+    apiKey,
+    baseUrl,
+    model,
+    temperature,
+    responseFormat,
+    thinking: resolveAuxThinking(provider),
+    streaming: false,
+  })
+  const ordered = provider === 'gemini' ? normalizeGeminiMessages(messages) : messages
+  const response = await modelInstance.invoke(
+    toLangChainMessages(ordered),
+    signal ? { signal } : undefined,
+  )
+  return stripThinking(normalizeTextContent(adapter.getResponseContent(response))).trim()
+}
+
+/**
+ * Parse model JSON output, tolerating markdown fences and minor syntax errors
+ */
+export const parseModelJson = text => {
+  const cleaned = stripThinking(text)
+    .replace(/```(?:json)?\s*([\s\S]*?)```/g, '$1')
+    .trim()
+  const match = cleaned.match(/\{[\s\S]*\}|\[[\s\S]*\]/)
+  const candidate = match ? match[0] : cleaned
+  try {
+    return JSON.parse(candidate)
+  } catch {
+    try {
+      return JSON.parse(jsonrepair(candidate))
+    } catch {
+      return null
+    }
+  }
+}
+
+/**
+ * Run a completion that is expected to return JSON
+ * @returns {Promise<Object|Array|null>} Parsed JSON or null when the output is unusable
+ */
+export const completeJson = async params => {
+  const text = await completeText({
+    ...params,
+    responseFormat:
+      params.responseFormat ?? (params.provider !== 'gemini' ? { type: 'json_object' } : undefined),
+  })
+  return parseModelJson(text)
+}
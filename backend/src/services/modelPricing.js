@@ -0,0 +1,77 @@
+/**
+ * Model pricing
+ * Approximate list prices (USD per million input/output tokens) for cost estimates in the usage
+ * ledger. Models match by longest id prefix; MODEL_PRICING (JSON, same shape) adds or overrides
+ * entries. Local providers are free; unknown models have no estimate.
+ */
+
+const MODEL_PRICES = {
+  'gpt-5-nano': [0.05, 0.4],
+  'gpt-5-mini': [0.25, 2],
+  'gpt-5': [1.25, 10],
+  'gpt-4.1-nano': [0.1, 0.4],
+  'gpt-4.1-mini': [0.4, 1.6],
+  'gpt-4.1': [2, 8],
+  'gpt-4o-mini': [0.15, 0.6],
+  'gpt-4o': [2.5, 10],
+  'o4-mini': [1.1, 4.4],
+  'o3-mini': [1.1, 4.4],
+  o3: [2, 8],
+  'claude-opus-4': [15, 75],
+  'claude-sonnet-4': [3, 15],
+  'claude-haiku-4': [1, 5],
+  'claude-3-7-sonnet': [3, 15],
+  'claude-3-5-haiku': [0.8, 4],
+  'gemini-2.5-pro': [1.25, 10],
+  'gemini-2.5-flash-lite': [0.1, 0.4],
+  'gemini-2.5-flash': [0.3, 2.5],
+  'gemini-2.0-flash': [0.1, 0.4],
+  'deepseek-chat': [0.27, 1.1],
+  'deepseek-v3': [0.27, 1.1],
+  'deepseek-reasoner': [0.55, 2.19],
+  'deepseek-r1': [0.55, 2.19],
+  'glm-4.5': [0.6, 2.2],
+  'glm-4.6': [0.6, 2.2],
+  'kimi-k2': [0.6, 2.5],
+}
+
+const FREE_PROVIDERS = new Set(['ollama', 'local'])
+
+const loadPriceTable = () => {
+  if (!process.env.MODEL_PRICING) return MODEL_PRICES
+  try {
+    return { ...MODEL_PRICES, ...JSON.parse(process.env.MODEL_PRICING) }
+  } catch (error) {
+    console.warn('[ModelPricing] Ignoring invalid MODEL_PRICING:', error.message)
+    return MODEL_PRICES
+  }
+}
+
+/**
+ * [input, output] USD per million tokens, or null when the model is unknown
+ */
+export const resolveModelPrice = (provider, model) => {
+  if (FREE_PROVIDERS.has(provider)) return [0, 0]
+  // Drop org prefixes such as "deepseek-ai/" used by aggregator providers
+  const id = String(model || '')
+    .toLowerCase()
+    .split('/')
+    .pop()
+  if (!id) return null
+  const table = loadPriceTable()
+  const prefix = Object.keys(table)
+    .filter(key => id.startsWith(key.toLowerCase()))
+    .sort((a, b) => b.length - a.length)[0]
+  return prefix ? table[prefix] : null
+}
+
+/**
+ * Estimated USD cost of a usage block ({ prompt_tokens, completion_tokens }), or null
+ */
+export const estimateCost = ({ provider, model, usage }) => {
+  const price = resolveModelPrice(provider, model)
+  if (!price) return null
+  const cost =
+    ((usage?.prompt_tokens || 0) * price[0] + (usage?.completion_tokens || 0) * price[1]) / 1e6
+  return Number(cost.toFixed(6))
+}
@@ -0,0 +1,52 @@
+/**
+ * Model call rate limiting
+ * A LangChain callback handler that takes a rate limiter share (services/providers/
+ * rateLimiter.js) before each chat model call, so a request making many calls (tool loops,
+ * research steps, report checks) is metered per call rather than once. Models built outside a
+ * rate limit context are returned untouched.
+ */
+
+import { BaseCallbackHandler } from '@langchain/core/callbacks/base'
+import {
+  estimateRequestTokens,
+  getRateLimitContext,
+  rateLimitKey,
+} from './providers/rateLimiter.js'
+
+const createRateLimitHandler = (context, { provider, apiKey }) => {
+  const key = rateLimitKey(provider, apiKey)
+  return BaseCallbackHandler.fromMethods({
+    // Awaited, and a rejection fails the call instead of being logged
+    awaitHandlers: true,
+    raiseError: true,
+    async handleChatModelStart(llm, messages) {
+      // The share the request took on arrival pays for its first call on the same key
+      if (context.prepaid === key) {
+        context.prepaid = null
+        return
+      }
+      await context.rateLimiter.acquire({
+        provider,
+        apiKey,
+        tokens: estimateRequestTokens((messages?.[0] || []).map(message => message?.content)),
+      })
+    },
+  })
+}
+
+/**
+ * Meter a model's calls against the current request's rate limiter
+ * @param {Object} model - LangChain chat model
+ * @param {{ provider: string, apiKey?: string }} credentials - The key the model calls with
+ * @returns {Object} The same model
+ */
+export const limitModel = (model, { provider, apiKey } = {}) => {
+  const context = getRateLimitContext()
+  if (!context || !model || !provider) return model
+  const handler = createRateLimitHandler(context, { provider, apiKey })
+  const existing = model.callbacks
+  if (Array.isArray(existing)) model.callbacks = [...existing, handler]
+  else if (typeof existing?.addHandler === 'function') existing.addHandler(handler)
+  else model.callbacks = [handler]
+  return model
+}
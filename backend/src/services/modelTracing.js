@@ -0,0 +1,76 @@
+/**
+ * Model call tracing
+ * A LangChain callback handler that records each chat model call as an "llm" span (prompt
+ * messages, output, tool calls, token usage) in the trace that was active when the model was
+ * built. Models built outside a trace are returned untouched.
+ */
+
+import { BaseCallbackHandler } from '@langchain/core/callbacks/base'
+import { getTraceContext, startSpan } from './tracing.js'
+
+const toTraceMessage = message => ({
+  role: message?.type ?? message?._getType?.() ?? message?.role,
+  content: message?.content,
+  tool_calls: message?.tool_calls?.length ? message.tool_calls : undefined,
+  tool_call_id: message?.tool_call_id,
+})
+
+const readOutput = output => {
+  const generation = output?.generations?.[0]?.[0]
+  const message = generation?.message
+  return {
+    output: message ? message.content : generation?.text,
+    tool_calls: message?.tool_calls?.length ? message.tool_calls : undefined,
+    usage: output?.llmOutput?.tokenUsage || message?.usage_metadata,
+  }
+}
+
+const createTraceHandler = (buildContext, attributes) => {
+  // LangChain run id -> open span
+  const runs = new Map()
+  // Within the same trace, nest under whatever span is current at call time (a research step);
+  // callbacks are awaited, so the caller's async context is still active here
+  const resolveContext = () => {
+    const current = getTraceContext()
+    return current?.trace === buildContext.trace ? current : buildContext
+  }
+
+  return BaseCallbackHandler.fromMethods({
+    awaitHandlers: true,
+    handleChatModelStart(llm, messages, runId, parentRunId, extraParams) {
+      const { model, ...params } = extraParams?.invocation_params || {}
+      const started = startSpan(resolveContext(), 'llm', {
+        ...attributes,
+        model: model || attributes.model,
+        params,
+        messages: (messages?.[0] || []).map(toTraceMessage),
+      })
+      if (started) runs.set(runId, started)
+    },
+    handleLLMEnd(output, runId) {
+      runs.get(runId)?.end(null, readOutput(output))
+      runs.delete(runId)
+    },
+    handleLLMError(error, runId) {
+      runs.get(runId)?.end(error)
+      runs.delete(runId)
+    },
+  })
+}
+
+/**
+ * Record a model's calls in the current trace
+ * @param {Object} model - LangChain chat model
+ * @param {Object} [attributes] - e.g. { provider, model }
+ * @returns {Object} The same model
+ */
+export const traceModel = (model, attributes = {}) => {
+  const context = getTraceContext()
+  if (!context || !model) return model
+  const handler = createTraceHandler(context, attributes)
+  const existing = model.callbacks
+  if (Array.isArray(existing)) model.callbacks = [...existing, handler]
+  else if (typeof existing?.addHandler === 'function') existing.addHandler(handler)
+  else model.callbacks = [handler]
+  return model
+}
@@ -0,0 +1,273 @@
+/**
+ * Numeric claims service
+ * Extracts numeric claims from findings, normalizes units, and flags figures that disagree
+ * across sources.
+ */
+
+import { completeJson } from './modelCompletion.js'
+
+const MAX_CLAIMS = 60
+const MAX_FINDINGS_CHARS = 24000
+const DEFAULT_CONFLICT_TOLERANCE = 0.1
+
+const SCALE_WORDS = {
+  thousand: 1e3,
+  k: 1e3,
+  million: 1e6,
+  mn: 1e6,
+  m: 1e6,
+  billion: 1e9,
+  bn: 1e9,
+  b: 1e9,
+  trillion: 1e12,
+  tn: 1e12,
+  t: 1e12,
+  万: 1e4,
+  亿: 1e8,
+}
+
+// unit alias -> [base unit, factor]
+const UNIT_TABLE = {
+  '%': ['%', 1],
+  percent: ['%', 1],
+  pct: ['%', 1],
+  bp: ['%', 0.01],
+  bps: ['%', 0.01],
+  mm: ['m', 0.001],
+  cm: ['m', 0.01],
+  m: ['m', 1],
+  km: ['m', 1000],
+  mi: ['m', 1609.344],
+  mile: ['m', 1609.344],
+  miles: ['m', 1609.344],
+  mg: ['kg', 1e-6],
+  g: ['kg', 0.001],
+  kg: ['kg', 1],
+  t: ['kg', 1000],
+  tonne: ['kg', 1000],
+  tonnes: ['kg', 1000],
+  lb: ['kg', 0.45359237],
+  lbs: ['kg', 0.45359237],
+  wh: ['wh', 1],
+  kwh: ['wh', 1e3],
+  mwh: ['wh', 1e6],
+  gwh: ['wh', 1e9],
+  twh: ['wh', 1e12],
+  w: ['w', 1],
+  kw: ['w', 1e3],
+  mw: ['w', 1e6],
+  gw: ['w', 1e9],
+  ms: ['s', 0.001],
+  s: ['s', 1],
+  sec: ['s', 1],
+  min: ['s', 60],
+  h: ['s', 3600],
+  hr: ['s', 3600],
+  hours: ['s', 3600],
+  day: ['s', 86400],
+  days: ['s', 86400],
+  元: ['cny', 1],
+  rmb: ['cny', 1],
+  yuan: ['cny', 1],
+  dollars: ['usd', 1],
+  kb: ['byte', 1e3],
+  mb: ['byte', 1e6],
+  gb: ['byte', 1e9],
+  tb: ['byte', 1e12],
+}
+
+const CURRENCY_SYMBOLS = { $: 'usd', '€': 'eur', '£': 'gbp', '¥': 'cny', '￥': 'cny' }
+
+/**
+ * Normalize a value/unit pair to a base unit
+ * e.g. (2.5, "billion USD") -> { value: 2500000000, unit: "usd" }
+ *      (500, "km") -> { value: 500000, unit: "m" }
+ */
+export const normalizeQuantity = (rawValue, rawUnit = '') => {
+  let value =
+    typeof rawValue === 'number' ? rawValue : Number.parseFloat(String(rawValue).replace(/,/g, ''))
+  if (!Number.isFinite(value)) return null
+
+  let unitText = String(rawUnit || '').trim()
+  for (const [symbol, code] of Object.entries(CURRENCY_SYMBOLS)) {
+    if (unitText.includes(symbol)) unitText = `${unitText.replace(symbol, '')} ${code}`
+  }
+  const tokens = unitText
+    .toLowerCase()
+    .split(/[\s/]+|(?<=[万亿])/)
+    .filter(Boolean)
+
+  const rest = []
+  let scaled = false
+  for (const token of tokens) {
+    // Scale words only count before the unit itself ("billion usd", not "usd m" ambiguity)
+    if (!scaled && rest.length === 0 && SCALE_WORDS[token] && tokens.length > 1) {
+      value *= SCALE_WORDS[token]
+      scaled = true
+      continue
+    }
+    rest.push(token)
+  }
+  if (!scaled && rest.length === 1 && SCALE_WORDS[rest[0]] && !UNIT_TABLE[rest[0]]) {
+    value *= SCALE_WORDS[rest[0]]
+    return { value, unit: '' }
+  }
+
+  const unit = rest.join(' ')
+  const mapped = UNIT_TABLE[unit]
+  if (mapped) return { value: value * mapped[1], unit: mapped[0] }
+  return { value, unit }
+}
+
+const buildClaimMessages = ({ question, findings, sourcesList }) => [
+  {
+    role: 'system',
+    content: `You extract numeric claims from research notes.
+Rules:
+- Extract up to ${MAX_CLAIMS} claims that state a quantity (size, price, count, share, rate, duration, date-bound figure).
+- "subject" is what is measured (e.g. "global EV market"), "metric" the quantity (e.g. "market size").
+- "value" is a plain number (no commas); put scale and unit words in "unit" (e.g. "billion USD", "%", "km").
+- "as_of" is the year or date the figure refers to, or null.
+- "sources" lists numbers from the source list the notes attribute the figure to. Never invent numbers.
+- Use the same subject/metric wording when different notes report the same quantity.
+
+Return ONLY JSON: {"claims": [{"subject": "...", "metric": "...", "value": 0, "unit": "...", "as_of": "2023", "quote": "...", "sources": [1]}]}`,
+  },
+  {
+    role: 'user',
+    content: `Question: ${question || '(none)'}
+
+Source list:
+${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}
+
+Research notes:
+${findings.join('\n\n').slice(0, MAX_FINDINGS_CHARS)}`,
+  },
+]
+
+export const normalizeClaims = (parsed, sourceCount) => {
+  const list = Array.isArray(parsed) ? parsed : parsed?.claims
+  if (!Array.isArray(list)) return []
+  return list
+    .filter(item => item && typeof item.subject === 'string' && typeof item.metric === 'string')
+    .map(item => {
+      const normalized = normalizeQuantity(item.value, item.unit)
+      if (!normalized) return null
+      return {
+        subject: item.subject.trim(),
+        metric: item.metric.trim(),
+        value: Number.parseFloat(String(item.value).replace(/,/g, '')),
+        unit: String(item.unit || '').trim(),
+        as_of: item.as_of ? String(item.as_of).trim() : null,
+        quote: typeof item.quote === 'string' ? item.quote.trim() : '',
+        sources: (Array.isArray(item.sources) ? item.sources : [])
+          .map(value => Number.parseInt(value, 10))
+          .filter(value => Number.isFinite(value) && value >= 1 && value <= sourceCount),
+        normalized_value: normalized.value,
+        normalized_unit: normalized.unit,
+      }
+    })
+    .filter(Boolean)
+    .slice(0, MAX_CLAIMS)
+}
+
+const claimKey = claim =>
+  [claim.subject, claim.metric, claim.as_of || '', claim.normalized_unit]
+    .map(part => String(part).toLowerCase().replace(/\s+/g, ' ').trim())
+    .join('|')
+
+/**
+ * Group claims about the same quantity and flag groups whose values disagree beyond the tolerance
+ */
+export const detectDataConflicts = (claims, { tolerance = DEFAULT_CONFLICT_TOLERANCE } = {}) => {
+  const groups = new Map()
+  for (const claim of claims) {
+    const key = claimKey(claim)
+    if (!groups.has(key)) groups.set(key, [])
+    groups.get(key).push(claim)
+  }
+
+  const conflicts = []
+  for (const group of groups.values()) {
+    if (group.length < 2) continue
+    // Figures from the same single source are not a cross-source conflict
+    const sourceSets = new Set(group.map(claim => claim.sources.join(',')))
+    if (sourceSets.size < 2) continue
+    const values = group.map(claim => claim.normalized_value)
+    const min = Math.min(...values)
+    const max = Math.max(...values)
+    const scale = Math.max(Math.abs(min), Math.abs(max))
+    const spread = scale === 0 ? 0 : (max - min) / scale
+    if (spread <= tolerance) continue
+    const [first] = group
+    conflicts.push({
+      subject: first.subject,
+      metric: first.metric,
+      as_of: first.as_of,
+      unit: first.normalized_unit,
+      min,
+      max,
+      spread: Number(spread.toFixed(4)),
+      claims: group.map(({ value, unit, quote, sources, normalized_value }) => ({
+        value,
+        unit,
+        quote,
+        sources,
+        normalized_value,
+      })),
+    })
+  }
+  return conflicts.sort((a, b) => b.spread - a.spread)
+}
+
+/**
+ * Extract claims from findings and return both the claims and the detected conflicts
+ * @returns {Promise<{claims: Array, conflicts: Array}>}
+ */
+export const analyzeNumericClaims = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  question,
+  findings = [],
+  sourcesList = [],
+  tolerance,
+  signal,
+}) => {
+  if (!findings.length) return { claims: [], conflicts: [] }
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: buildClaimMessages({ question, findings, sourcesList }),
+      temperature: 0,
+      signal,
+    })
+    const claims = normalizeClaims(parsed, sourcesList.length)
+    return { claims, conflicts: detectDataConflicts(claims, { tolerance }) }
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[NumericClaims] Claim extraction failed:', error.message)
+    return { claims: [], conflicts: [] }
+  }
+}
+
+/**
+ * Report prompt section asking the writer to surface the conflicts explicitly
+ */
+export const formatConflictsForPrompt = conflicts =>
+  conflicts
+    .map(conflict => {
+      const figures = conflict.claims
+        .map(claim => {
+          const citations = claim.sources.map(index => `[${index}]`).join('')
+          return `${claim.value} ${claim.unit}`.trim() + (citations ? ` ${citations}` : '')
+        })
+        .join(' vs ')
+      const asOf = conflict.as_of ? ` (${conflict.as_of})` : ''
+      return `- ${conflict.subject} — ${conflict.metric}${asOf}: ${figures}`
+    })
+    .join('\n')
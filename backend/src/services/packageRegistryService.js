@@ -0,0 +1,163 @@
+/**
+ * Package registry service
+ * Live package metadata from npm, crates.io, and PyPI: latest version, deprecation/yank status,
+ * minimum toolchain (engines.node / rust_version / requires_python), and dependencies.
+ */
+
+const REQUEST_TIMEOUT_MS = 10000
+const USER_AGENT = 'Qurio (https://github.com/havingautism/Qurio)'
+const MAX_DEPENDENCIES = 100
+
+export const PACKAGE_ECOSYSTEMS = ['npm', 'crates', 'pypi']
+
+const fetchJson = async (url, { signal } = {}) => {
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(url, {
+    headers: { Accept: 'application/json', 'User-Agent': USER_AGENT },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (response.status === 404) return null
+  if (!response.ok) throw new Error(`Registry request failed: HTTP ${response.status}`)
+  return response.json()
+}
+
+const toDependencyList = (record, kind) =>
+  Object.entries(record || {}).map(([name, requirement]) => ({ name, requirement, kind }))
+
+const lookupNpm = async (name, version, options) => {
+  const data = await fetchJson(
+    `https://registry.npmjs.org/${encodeURIComponent(name).replace(/^%40/, '@')}`,
+    options,
+  )
+  if (!data) return null
+  const latest = data['dist-tags']?.latest
+  const resolved = version || latest
+  const manifest = data.versions?.[resolved]
+  if (!manifest) throw new Error(`Version not found: ${name}@${resolved}`)
+  return {
+    ecosystem: 'npm',
+    name: data.name,
+    version: resolved,
+    latest,
+    published_at: data.time?.[resolved] || null,
+    deprecated: manifest.deprecated || null,
+    description: manifest.description || data.description || '',
+    license: manifest.license || data.license || null,
+    min_toolchain: manifest.engines?.node ? `node ${manifest.engines.node}` : null,
+    homepage: manifest.homepage || null,
+    repository: manifest.repository?.url || manifest.repository || null,
+    url: `https://www.npmjs.com/package/${data.name}`,
+    dependencies: [
+      ...toDependencyList(manifest.dependencies, 'normal'),
+      ...toDependencyList(manifest.peerDependencies, 'peer'),
+      ...toDependencyList(manifest.optionalDependencies, 'optional'),
+    ],
+  }
+}
+
+const lookupCrate = async (name, version, options) => {
+  const base = `https://crates.io/api/v1/crates/${encodeURIComponent(name)}`
+  const data = await fetchJson(base, options)
+  if (!data?.crate) return null
+  const latest = data.crate.max_stable_version || data.crate.newest_version
+  const resolved = version || latest
+  const release = (data.versions || []).find(item => item.num === resolved)
+  if (!release) throw new Error(`Version not found: ${name}@${resolved}`)
+  const deps = await fetchJson(`${base}/${encodeURIComponent(resolved)}/dependencies`, options)
+  return {
+    ecosystem: 'crates',
+    name: data.crate.name,
+    version: resolved,
+    latest,
+    published_at: release.created_at || null,
+    deprecated: release.yanked ? 'This version has been yanked' : null,
+    description: data.crate.description || '',
+    license: release.license || null,
+    min_toolchain: release.rust_version ? `rust ${release.rust_version}` : null,
+    homepage: data.crate.homepage || null,
+    repository: data.crate.repository || null,
+    url: `https://crates.io/crates/${data.crate.name}`,
+    dependencies: (deps?.dependencies || []).map(dep => ({
+      name: dep.crate_id,
+      requirement: dep.req,
+      kind: dep.optional ? 'optional' : dep.kind,
+    })),
+  }
+}
+
+const lookupPypi = async (name, version, options) => {
+  const path = version
+    ? `${encodeURIComponent(name)}/${encodeURIComponent(version)}`
+    : encodeURIComponent(name)
+  const data = await fetchJson(`https://pypi.org/pypi/${path}/json`, options)
+  if (!data?.info) return null
+  const info = data.info
+  const latest = version
+    ? (await fetchJson(`https://pypi.org/pypi/${encodeURIComponent(name)}/json`, options))?.info
+        ?.version
+    : info.version
+  const inactive = (info.classifiers || []).includes('Development Status :: 7 - Inactive')
+  return {
+    ecosystem: 'pypi',
+    name: info.name,
+    version: info.version,
+    latest,
+    published_at: data.urls?.[0]?.upload_time_iso_8601 || null,
+    deprecated: info.yanked
+      ? `Yanked${info.yanked_reason ? `: ${info.yanked_reason}` : ''}`
+      : inactive
+        ? 'Marked inactive (Development Status :: 7 - Inactive)'
+        : null,
+    description: info.summary || '',
+    license: info.license_expression || info.license || null,
+    min_toolchain: info.requires_python ? `python ${info.requires_python}` : null,
+    homepage: info.home_page || info.project_urls?.Homepage || null,
+    repository: info.project_urls?.Source || info.project_urls?.Repository || null,
+    url: `https://pypi.org/project/${info.name}/${info.version}/`,
+    dependencies: (info.requires_dist || []).map(requirement => ({
+      name: requirement.split(/[\s;<>=!~[(]/)[0],
+      requirement,
+      kind: requirement.includes('extra ==') ? 'optional' : 'normal',
+    })),
+  }
+}
+
+const LOOKUPS = { npm: lookupNpm, crates: lookupCrate, pypi: lookupPypi }
+
+/**
+ * Look up a package; the `results` entry makes the page citable as a source
+ */
+export const lookupPackage = async ({
+  ecosystem,
+  name,
+  version,
+  includeDependencies = true,
+  signal,
+}) => {
+  const lookup = LOOKUPS[ecosystem]
+  if (!lookup) throw new Error(`ecosystem must be one of: ${PACKAGE_ECOSYSTEMS.join(', ')}`)
+  const info = await lookup(String(name).trim(), version, { signal })
+  if (!info) return { found: false, ecosystem, name, results: [] }
+
+  const dependencies = includeDependencies
+    ? info.dependencies.slice(0, MAX_DEPENDENCIES)
+    : undefined
+  const summary = [
+    `${info.name} ${info.version} (latest: ${info.latest})`,
+    info.deprecated ? `Deprecated: ${info.deprecated}` : null,
+    info.min_toolchain ? `Requires ${info.min_toolchain}` : null,
+    info.published_at ? `Published ${info.published_at}` : null,
+    info.description,
+  ]
+    .filter(Boolean)
+    .join('. ')
+  return {
+    found: true,
+    ...info,
+    dependencies,
+    dependency_count: info.dependencies.length,
+    results: [
+      { title: `${ecosystem}: ${info.name} ${info.version}`, url: info.url, content: summary },
+    ],
+  }
+}
@@ -0,0 +1,146 @@
+/**
+ * Patent service
+ * US patent search via the PatentsView PatentSearch API: titles, assignees, filing/grant dates,
+ * abstracts, and (optionally) the independent claims.
+ * Requires a free API key: vault secret PATENTSVIEW_API_KEY or the PATENTSVIEW_API_KEY env var.
+ */
+
+import { resolveSecret } from './keyVault.js'
+
+const API_BASE = 'https://search.patentsview.org/api/v1'
+const REQUEST_TIMEOUT_MS = 20000
+const MAX_CLAIM_CHARS = 1500
+const MAX_ABSTRACT_CHARS = 1200
+
+const PATENT_FIELDS = [
+  'patent_id',
+  'patent_title',
+  'patent_date',
+  'patent_abstract',
+  'patent_type',
+  'assignees.assignee_organization',
+  'inventors.inventor_name_first',
+  'inventors.inventor_name_last',
+  'application.filing_date',
+]
+
+const resolvePatentsViewKey = () =>
+  resolveSecret('PATENTSVIEW_API_KEY')?.value || process.env.PATENTSVIEW_API_KEY || ''
+
+const truncate = (text, max) => {
+  const value = String(text || '').trim()
+  return value.length > max ? `${value.slice(0, max)}…` : value
+}
+
+const formatInventor = inventor =>
+  [inventor.inventor_name_first, inventor.inventor_name_last].filter(Boolean).join(' ')
+
+const patentsViewFetch = async (endpoint, body, { signal } = {}) => {
+  const apiKey = resolvePatentsViewKey()
+  if (!apiKey) {
+    throw new Error('PatentsView API key not configured. Set PATENTSVIEW_API_KEY.')
+  }
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(`${API_BASE}/${endpoint}/`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', 'X-Api-Key': apiKey },
+    body: JSON.stringify(body),
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (!response.ok) throw new Error(`PatentsView API error: HTTP ${response.status}`)
+  return response.json()
+}
+
+const buildQuery = ({ query, assignee, dateFrom, dateTo }) => {
+  const clauses = []
+  if (query) {
+    clauses.push({
+      _or: [
+        { _text_all: { patent_title: query } },
+        { _text_all: { patent_abstract: query } },
+      ],
+    })
+  }
+  if (assignee) clauses.push({ _contains: { 'assignees.assignee_organization': assignee } })
+  if (dateFrom) clauses.push({ _gte: { patent_date: dateFrom } })
+  if (dateTo) clauses.push({ _lte: { patent_date: dateTo } })
+  return clauses.length === 1 ? clauses[0] : { _and: clauses }
+}
+
+// Independent claims define the scope; dependent ones are omitted to keep results compact
+const fetchIndependentClaims = async (patentIds, { signal }) => {
+  const data = await patentsViewFetch(
+    'g_claim',
+    {
+      q: { _and: [{ patent_id: patentIds }, { claim_dependent: null }] },
+      f: ['patent_id', 'claim_sequence', 'claim_text'],
+      o: { size: 1000 },
+      s: [{ patent_id: 'asc' }, { claim_sequence: 'asc' }],
+    },
+    { signal },
+  )
+  const claims = new Map()
+  for (const claim of data.g_claims || []) {
+    if (!claims.has(claim.patent_id)) claims.set(claim.patent_id, [])
+    claims.get(claim.patent_id).push(truncate(claim.claim_text, MAX_CLAIM_CHARS))
+  }
+  return claims
+}
+
+/**
+ * Search granted US patents
+ * @param {Object} args
+ * @param {string} [args.query] Words that must all appear in the title or abstract
+ * @param {string} [args.assignee] Assignee organization substring
+ * @param {string} [args.dateFrom] Grant date lower bound (YYYY-MM-DD)
+ * @param {string} [args.dateTo] Grant date upper bound (YYYY-MM-DD)
+ * @param {boolean} [args.includeClaims=true]
+ * @param {number} [args.limit=10]
+ */
+export const searchPatents = async ({
+  query,
+  assignee,
+  dateFrom,
+  dateTo,
+  includeClaims = true,
+  limit = 10,
+  signal,
+}) => {
+  if (!query && !assignee) throw new Error('query or assignee is required')
+  const size = Math.min(Math.max(Number(limit) || 10, 1), 25)
+  const data = await patentsViewFetch(
+    'patent',
+    {
+      q: buildQuery({ query, assignee, dateFrom, dateTo }),
+      f: PATENT_FIELDS,
+      o: { size },
+      s: [{ patent_date: 'desc' }],
+    },
+    { signal },
+  )
+  const patents = data.patents || []
+  const claims =
+    includeClaims && patents.length
+      ? await fetchIndependentClaims(patents.map(patent => patent.patent_id), { signal })
+      : new Map()
+
+  return {
+    total_hits: data.total_hits ?? patents.length,
+    results: patents.map(patent => {
+      const number = `US${patent.patent_id}`
+      const independentClaims = claims.get(patent.patent_id) || []
+      return {
+        title: `${number}: ${patent.patent_title}`,
+        url: `https://patents.google.com/patent/${number}`,
+        content: truncate(patent.patent_abstract, MAX_ABSTRACT_CHARS),
+        patent_number: number,
+        type: patent.patent_type,
+        granted: patent.patent_date,
+        filed: patent.application?.[0]?.filing_date || null,
+        assignees: (patent.assignees || []).map(item => item.assignee_organization).filter(Boolean),
+        inventors: (patent.inventors || []).map(formatInventor).filter(Boolean),
+        independent_claims: includeClaims ? independentClaims : undefined,
+      }
+    }),
+  }
+}
@@ -0,0 +1,209 @@
+/**
+ * Plain text stream
+ * Screen-reader friendly answers (plain_text: true on the streaming routes): markdown syntax,
+ * emoji and citation brackets are removed from the streamed text, and the cited sources move to a
+ * structured "citations" list on the done event. Text is converted line by line, so markers split
+ * across chunks are never half-stripped; an overlong line is flushed early at a point where no
+ * inline marker is open.
+ */
+
+import { EMOJI_PATTERN } from './regexConstants.js'
+
+const MAX_PENDING_CHARS = 400
+
+// [1], [1, 2], [1-3], [^1] and the full-width 【1】
+const CITATION_PATTERN = /\s?(?:\[\^?(\d[\d\s,–^-]*)\]|【(\d[\d\s,–-]*)】)/g
+const FENCE_PATTERN = /^\s*(```|~~~)/
+const TABLE_SEPARATOR_PATTERN = /^\s*\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)*\|?\s*$/
+const RULE_PATTERN = /^\s*([-*_])(\s*\1){2,}\s*$/
+
+const expandCitation = value =>
+  value
+    .replace(/\^/g, '')
+    .split(/\s*,\s*/)
+    .flatMap(part => {
+      const [start, end] = part.split(/\s*[–-]\s*/).map(Number)
+      if (!end || end < start || end - start > 50) return [start]
+      return Array.from({ length: end - start + 1 }, (_, offset) => start + offset)
+    })
+    .filter(Number.isInteger)
+
+// Inline code keeps its content verbatim (brackets in code are not citations)
+const stripInline = (text, citations) =>
+  text
+    .split(/(`+[^`]*`+)/)
+    .map((segment, index) => {
+      if (index % 2 === 1) return segment.replace(/^`+\s?|\s?`+$/g, '')
+      return segment
+        .replace(/!\[([^\]]*)\]\([^)]*\)/g, '$1')
+        .replace(CITATION_PATTERN, (match, bracketed, fullWidth) => {
+          citations.push(...expandCitation(bracketed || fullWidth))
+          return ''
+        })
+        .replace(/\[([^\]]+)\]\([^)]*\)/g, '$1')
+        .replace(/<\/?[a-z][^>]*>/gi, '')
+        .replace(/(\*\*|__)(?=\S)(.+?)(?<=\S)\1/g, '$2')
+        .replace(/~~(?=\S)(.+?)(?<=\S)~~/g, '$1')
+        .replace(/(^|[^\w*])\*(?=\S)(.+?)(?<=\S)\*(?!\w)/g, '$1$2')
+        .replace(/(^|\W)_(?=\S)(.+?)(?<=\S)_(?!\w)/g, '$1$2')
+        .replace(EMOJI_PATTERN, '')
+    })
+    .join('')
+    .replace(/[ \t]{2,}/g, ' ')
+    .replace(/ +([.,;:!?)])/g, '$1')
+
+/**
+ * Incremental markdown-to-plain-text converter
+ * @returns {{ push(text: string): string, flush(): string, citations: number[] }}
+ */
+export const createPlainTextConverter = () => {
+  const citations = []
+  let pending = ''
+  let inFence = false
+  // The pending text continues a line flushed early, so it has no line-level markers
+  let continuation = false
+
+  const convertLine = line => {
+    if (continuation) {
+      continuation = false
+      return stripInline(line, citations).trimEnd()
+    }
+    if (FENCE_PATTERN.test(line)) {
+      inFence = !inFence
+      return null
+    }
+    if (inFence) return line
+    if (TABLE_SEPARATOR_PATTERN.test(line) && line.includes('-') && line.includes('|')) return null
+    if (RULE_PATTERN.test(line)) return null
+    let text = line
+      .replace(/^\s{0,3}#{1,6}\s+/, '')
+      .replace(/\s+#+\s*$/, '')
+      .replace(/^\s*(>\s?)+/, '')
+      .replace(/^(\s*)[-*+]\s+(\[[ xX]\]\s+)?/, '$1')
+    if (/^\s*\|.*\|\s*$/.test(text)) {
+      text = text
+        .trim()
+        .replace(/^\||\|$/g, '')
+        .split('|')
+        .map(cell => cell.trim())
+        .filter(Boolean)
+        .join(', ')
+    }
+    return stripInline(text, citations).trimEnd()
+  }
+
+  const convertLines = lines =>
+    lines
+      .map(convertLine)
+      .filter(line => line !== null)
+      .map(line => `${line}\n`)
+      .join('')
+
+  // Last whitespace before which every inline marker is closed
+  const safeSplitIndex = text => {
+    let depth = 0
+    let backticks = 0
+    let safe = -1
+    for (let index = 0; index < text.length; index += 1) {
+      const char = text[index]
+      if (char === '`') backticks += 1
+      else if (backticks % 2 === 0 && (char === '[' || char === '(' || char === '【')) depth += 1
+      else if (backticks % 2 === 0 && (char === ']' || char === ')' || char === '】')) {
+        depth = Math.max(0, depth - 1)
+      } else if (/\s/.test(char) && depth === 0 && backticks % 2 === 0) {
+        const prefix = text.slice(0, index)
+        const balanced = ['**', '__', '~~'].every(marker => prefix.split(marker).length % 2 === 1)
+        if (balanced && (prefix.match(/(?<![*\w])\*(?!\*)/g) || []).length % 2 === 0) safe = index
+      }
+    }
+    return safe
+  }
+
+  return {
+    citations,
+    push(text) {
+      pending += text
+      const lastBreak = pending.lastIndexOf('\n')
+      let output = ''
+      if (lastBreak !== -1) {
+        output = convertLines(pending.slice(0, lastBreak).split('\n'))
+        pending = pending.slice(lastBreak + 1)
+      }
+      if (pending.length > MAX_PENDING_CHARS && !inFence) {
+        const split = safeSplitIndex(pending)
+        if (split > 0) {
+          output += `${convertLine(pending.slice(0, split)) ?? ''} `
+          pending = pending.slice(split + 1)
+          continuation = true
+        }
+      }
+      return output
+    },
+    flush() {
+      if (!pending) return ''
+      const line = convertLine(pending)
+      pending = ''
+      return line ?? ''
+    },
+  }
+}
+
+/**
+ * Convert a whole markdown text
+ * @returns {{ text: string, citations: number[] }} citations: cited source numbers, in order of
+ *   first use
+ */
+export const toPlainText = markdown => {
+  const converter = createPlainTextConverter()
+  const text = (converter.push(String(markdown || '')) + converter.flush())
+    .replace(/\n{3,}/g, '\n\n')
+    .trim()
+  return { text, citations: [...new Set(converter.citations)] }
+}
+
+// Cited numbers refer to the sources list, 1-based
+const toCitations = (numbers, sources) =>
+  numbers.map(number => {
+    const source = Array.isArray(sources) ? sources[number - 1] : null
+    return {
+      index: number,
+      title: source?.title ?? null,
+      url: source?.uri ?? source?.url ?? null,
+    }
+  })
+
+/**
+ * Rewrite a stream's text, answer_revised and done events as plain text
+ * done gets "citations": [{ index, title, url }] and keeps the original in "markdown_content"
+ */
+export const plainTextStream = async function* (stream) {
+  const converter = createPlainTextConverter()
+  for await (const event of stream) {
+    if (event?.type === 'text' && typeof event.content === 'string') {
+      const content = converter.push(event.content)
+      if (content) yield { ...event, content }
+      continue
+    }
+    if (event?.type === 'answer_revised' && typeof event.content === 'string') {
+      yield { ...event, content: toPlainText(event.content).text }
+      continue
+    }
+    if (event?.type === 'done') {
+      const rest = converter.flush()
+      if (rest) yield { type: 'text', content: rest }
+      if (typeof event.content !== 'string') {
+        yield event
+        continue
+      }
+      const { text, citations } = toPlainText(event.content)
+      yield {
+        ...event,
+        content: text,
+        markdown_content: event.content,
+        citations: toCitations(citations, event.sources),
+      }
+      continue
+    }
+    yield event
+  }
+}
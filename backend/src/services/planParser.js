@@ -0,0 +1,262 @@
+/**
+ * Research plan parser
+ * Tolerant parsing of model-generated plans: strip fences, repair JSON, then ask the model to fix it.
+ * Parsed plans are checked against PLAN_SCHEMA; a plan that parses but misses required fields goes
+ * back to the model with the validation errors (PLAN_REPAIR_ATTEMPTS times, default 2).
+ */
+
+import { jsonrepair } from 'jsonrepair'
+import { z } from 'zod'
+
+const DEFAULT_REPAIR_ATTEMPTS = 2
+
+export const FALLBACK_PLAN = {
+  goal: '',
+  assumptions: [],
+  question_type: 'analysis',
+  plan: [
+    {
+      step: 1,
+      action: 'Summarize the topic and gather key evidence.',
+      expected_output: 'A concise summary with evidence.',
+      deliverable_format: 'paragraph',
+      acceptance_criteria: [],
+      depth: 'medium',
+      requires_search: true,
+    },
+  ],
+}
+
+export const stripCodeFences = text =>
+  String(text || '')
+    .replace(/^\uFEFF/, '')
+    .replace(/<think>[\s\S]*?<\/think>/gi, '')
+    .replace(/```(?:json|JSON|json5)?\s*([\s\S]*?)```/g, '$1')
+    .trim()
+
+/**
+ * Slice from the first "{" to the last "}" so leading/trailing prose is ignored
+ */
+const extractObjectText = text => {
+  const start = text.indexOf('{')
+  const end = text.lastIndexOf('}')
+  if (start === -1) return text
+  return end > start ? text.slice(start, end + 1) : text.slice(start)
+}
+
+const isPlanShape = value => value && typeof value === 'object' && Array.isArray(value.plan)
+
+// Fields the step runner and report writer rely on; anything else the model adds is kept
+const PLAN_STEP_SCHEMA = z
+  .object({
+    step: z.number().int().positive().optional(),
+    action: z.string().trim().min(1),
+    expected_output: z.string().trim().min(1),
+    deliverable_format: z.string().optional(),
+    acceptance_criteria: z.array(z.string()).optional(),
+    depth: z.enum(['low', 'medium', 'high']).optional(),
+    requires_search: z.boolean(),
+    depends_on: z.array(z.number().int()).optional(),
+  })
+  .passthrough()
+
+export const PLAN_SCHEMA = z
+  .object({
+    goal: z.string().trim().min(1),
+    question_type: z.string().optional(),
+    assumptions: z.array(z.string()).optional(),
+    plan: z.array(PLAN_STEP_SCHEMA).min(1),
+  })
+  .passthrough()
+
+/**
+ * Check a parsed plan against PLAN_SCHEMA
+ * @returns {string[]} Problems as "path: message" (empty when valid)
+ */
+export const validatePlan = value => {
+  const result = PLAN_SCHEMA.safeParse(value)
+  if (result.success) return []
+  return result.error.issues.map(issue => {
+    const path = issue.path
+      .map((key, index) => (typeof key === 'number' ? `[${key}]` : index ? `.${key}` : key))
+      .join('')
+    return `${path || '(root)'}: ${issue.message}`
+  })
+}
+
+export const resolveRepairAttempts = value => {
+  const parsed = Number.parseInt(value ?? process.env.PLAN_REPAIR_ATTEMPTS, 10)
+  return Number.isFinite(parsed) && parsed >= 0 ? parsed : DEFAULT_REPAIR_ATTEMPTS
+}
+
+/**
+ * Fill defaults so downstream prompt builders never see missing fields
+ */
+export const normalizePlan = parsed => ({
+  ...parsed,
+  goal: typeof parsed.goal === 'string' ? parsed.goal : '',
+  assumptions: Array.isArray(parsed.assumptions) ? parsed.assumptions : [],
+  question_type: parsed.question_type || 'analysis',
+  plan: parsed.plan
+    .filter(step => step && typeof step === 'object')
+    .map((step, index) => ({
+      ...step,
+      step: Number.isFinite(step.step) ? step.step : index + 1,
+      action: step.action || step.title || step.description || `Step ${index + 1}`,
+      acceptance_criteria: Array.isArray(step.acceptance_criteria) ? step.acceptance_criteria : [],
+    })),
+})
+
+// Steps that build on earlier results (comparisons, syntheses) rather than gather new evidence
+const SYNTHESIS_ACTION_PATTERN =
+  /\b(?:compar|contrast|synthes|summari[sz]|conclu|recommend|combin|integrat|reconcil|based on)/i
+
+/**
+ * Indices of the earlier steps whose findings each step needs
+ * Explicit `depends_on` step numbers win; otherwise a search step that is not a synthesis is
+ * independent, and every other step depends on all steps before it.
+ * @param {Array} steps - Normalized plan steps
+ * @returns {number[][]}
+ */
+export const resolveStepDependencies = steps =>
+  steps.map((step, index) => {
+    if (Array.isArray(step.depends_on)) {
+      return step.depends_on
+        .map(number => steps.findIndex(candidate => candidate.step === Number(number)))
+        .filter(dependency => dependency >= 0 && dependency < index)
+    }
+    const independent = step.requires_search && !SYNTHESIS_ACTION_PATTERN.test(step.action || '')
+    return independent ? [] : Array.from({ length: index }, (_, dependency) => dependency)
+  })
+
+/**
+ * Try each local repair stage in order
+ * @returns {{ plan: Object|null, stage: string|null, errors: string[], schemaErrors: string[] }}
+ *   plan is normalized; schemaErrors are the raw plan's validatePlan() problems
+ */
+export const tryParsePlan = text => {
+  const errors = []
+  const raw = String(text || '')
+  const stripped = stripCodeFences(raw)
+  const candidate = extractObjectText(stripped)
+
+  const stages = [
+    ['direct', () => JSON.parse(raw)],
+    ['strip_fences', () => JSON.parse(candidate)],
+    ['json_repair', () => JSON.parse(jsonrepair(candidate))],
+  ]
+
+  for (const [stage, parse] of stages) {
+    try {
+      const parsed = parse()
+      // Some models wrap the plan: { "research_plan": { ... } } or return the steps array directly
+      const unwrapped = Array.isArray(parsed)
+        ? { plan: parsed }
+        : isPlanShape(parsed)
+          ? parsed
+          : Object.values(parsed || {}).find(isPlanShape)
+      if (isPlanShape(unwrapped) && unwrapped.plan.length > 0) {
+        return {
+          plan: normalizePlan(unwrapped),
+          stage,
+          errors,
+          schemaErrors: validatePlan(unwrapped),
+        }
+      }
+      errors.push(`${stage}: parsed JSON has no non-empty "plan" array`)
+    } catch (error) {
+      errors.push(`${stage}: ${error.message}`)
+    }
+  }
+  return { plan: null, stage: null, errors, schemaErrors: [] }
+}
+
+/**
+ * Parse a plan, asking the model to fix it before giving up
+ * Each repair attempt sends the latest text with the errors found in it. When no attempt yields a
+ * valid plan, the last plan that parsed is used as it is (missing fields filled with defaults), or
+ * a generic single-step plan when none did; valid is then false and validationErrors says why.
+ * @param {string} planText - Raw plan text from the model
+ * @param {Object} options
+ * @param {Function} [options.repairWithModel] - async (text, errors) => fixed text
+ * @param {number} [options.maxRepairAttempts] - Default PLAN_REPAIR_ATTEMPTS or 2
+ * @returns {Promise<{ plan: Object, stage: string, valid: boolean, attempts: number,
+ *   errors: string[], validationErrors: string[] }>} stage: "direct" | "strip_fences" |
+ *   "json_repair" | "model_repair" | "invalid" | "fallback"; errors: every failure on the way
+ */
+export const parsePlanWithRecovery = async (
+  planText,
+  { repairWithModel, maxRepairAttempts = resolveRepairAttempts() } = {},
+) => {
+  const errors = []
+  let text = planText
+  let lastParsed = null
+  let validationErrors = []
+  let attempts = 0
+
+  for (;;) {
+    const result = tryParsePlan(text)
+    const label = attempts ? `model_repair ${attempts}` : null
+    if (result.plan && !result.schemaErrors.length) {
+      if (attempts) {
+        console.warn(`[PlanParser] Plan recovered via model fix-it prompt (attempt ${attempts})`)
+      } else if (result.stage !== 'direct') {
+        console.warn(`[PlanParser] Plan recovered via ${result.stage}`)
+      }
+      return {
+        plan: result.plan,
+        stage: attempts ? 'model_repair' : result.stage,
+        valid: true,
+        attempts,
+        errors,
+        validationErrors: [],
+      }
+    }
+
+    if (result.plan) lastParsed = result.plan
+    validationErrors = result.plan ? result.schemaErrors : result.errors
+    errors.push(...validationErrors.map(error => (label ? `${label}/${error}` : error)))
+
+    if (
+      attempts >= maxRepairAttempts ||
+      typeof repairWithModel !== 'function' ||
+      !String(text || '').trim()
+    ) {
+      break
+    }
+    attempts += 1
+    try {
+      text = await repairWithModel(text, validationErrors)
+    } catch (error) {
+      errors.push(`model_repair ${attempts}: ${error.message}`)
+      break
+    }
+  }
+
+  console.warn(
+    `[PlanParser] ${lastParsed ? 'Using invalid plan' : 'Falling back to generic plan'}. ` +
+      `Failures: ${errors.join(' | ')}`,
+  )
+  return {
+    plan: lastParsed || FALLBACK_PLAN,
+    stage: lastParsed ? 'invalid' : 'fallback',
+    valid: false,
+    attempts,
+    errors,
+    validationErrors,
+  }
+}
+
+export const buildPlanRepairMessages = (planText, errors) => [
+  {
+    role: 'system',
+    content: `You repair malformed research plans. Return ONLY valid JSON (no markdown, no commentary) with this shape:
+{"goal": "string", "question_type": "string", "assumptions": ["string"], "plan": [{"step": 1, "action": "string", "expected_output": "string", "deliverable_format": "string", "acceptance_criteria": ["string"], "depth": "low|medium|high", "requires_search": true}]}
+Every step needs a non-empty "action" and "expected_output" and a boolean "requires_search"; "goal" must not be empty.
+Keep the original content and step order; only fix the structure.`,
+  },
+  {
+    role: 'user',
+    content: `Errors:\n${errors.map(error => `- ${error}`).join('\n')}\n\nMalformed plan:\n${planText}`,
+  },
+]
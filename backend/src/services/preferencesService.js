@@ -0,0 +1,141 @@
+/**
+ * Preferences service
+ * Persistent per-user answer preferences (language, units, verbosity, citation style) that are
+ * injected into system prompts for chat and research. reportLocale is applied after generation
+ * instead: research reports get its number and date formats (see localeFormatService), and
+ * titleEmojis: "off" stops the title endpoints from picking emoji (see titleEmojiService).
+ */
+
+import { readRecord, writeRecord, deleteRecord } from '../utils/dataStore.js'
+import { normalizeLocale } from './localeFormatService.js'
+
+const COLLECTION = 'preferences'
+const DEFAULT_USER_ID = 'default'
+const MAX_CUSTOM_INSTRUCTIONS = 2000
+
+export const PREFERENCE_OPTIONS = {
+  units: ['', 'metric', 'imperial'],
+  verbosity: ['', 'concise', 'balanced', 'detailed'],
+  citationStyle: ['', 'numeric', 'apa', 'mla', 'chicago'],
+  titleEmojis: ['', 'on', 'off'],
+}
+
+export const DEFAULT_PREFERENCES = {
+  language: '',
+  units: '',
+  verbosity: '',
+  citationStyle: '',
+  reportLocale: '',
+  titleEmojis: '',
+  customInstructions: '',
+}
+
+const resolveUserId = userId => String(userId || DEFAULT_USER_ID)
+
+/**
+ * Validate a partial update
+ * @returns {string[]} Problems found (empty when valid)
+ */
+export const validatePreferences = patch => {
+  const errors = []
+  if (!patch || typeof patch !== 'object') return ['Preferences must be an object']
+  for (const [key, options] of Object.entries(PREFERENCE_OPTIONS)) {
+    if (patch[key] !== undefined && !options.includes(patch[key])) {
+      errors.push(`${key} must be one of: ${options.filter(Boolean).join(', ')} (or empty)`)
+    }
+  }
+  if (patch.language !== undefined && typeof patch.language !== 'string') {
+    errors.push('language must be a string')
+  }
+  if (patch.reportLocale !== undefined && patch.reportLocale !== '') {
+    if (!normalizeLocale(patch.reportLocale)) {
+      errors.push('reportLocale must be a BCP 47 locale such as "de-DE" (or empty)')
+    }
+  }
+  if (patch.customInstructions !== undefined) {
+    if (typeof patch.customInstructions !== 'string') {
+      errors.push('customInstructions must be a string')
+    } else if (patch.customInstructions.length > MAX_CUSTOM_INSTRUCTIONS) {
+      errors.push(`customInstructions must be at most ${MAX_CUSTOM_INSTRUCTIONS} characters`)
+    }
+  }
+  return errors
+}
+
+export const getPreferences = userId => {
+  try {
+    const record = readRecord(COLLECTION, resolveUserId(userId))
+    return { ...DEFAULT_PREFERENCES, ...(record?.preferences || {}) }
+  } catch (error) {
+    console.warn('[Preferences] Failed to load preferences:', error.message)
+    return { ...DEFAULT_PREFERENCES }
+  }
+}
+
+export const updatePreferences = (userId, patch) => {
+  const current = getPreferences(userId)
+  const next = { ...current }
+  for (const key of Object.keys(DEFAULT_PREFERENCES)) {
+    if (patch[key] !== undefined) next[key] = String(patch[key]).trim()
+  }
+  if (next.reportLocale) next.reportLocale = normalizeLocale(next.reportLocale)
+  writeRecord(COLLECTION, resolveUserId(userId), {
+    userId: resolveUserId(userId),
+    preferences: next,
+    updatedAt: new Date().toISOString(),
+  })
+  return next
+}
+
+export const resetPreferences = userId => {
+  deleteRecord(COLLECTION, resolveUserId(userId))
+  return { ...DEFAULT_PREFERENCES }
+}
+
+const VERBOSITY_TEXT = {
+  concise: 'Keep answers short and to the point; skip background the user did not ask for.',
+  balanced: 'Give complete but focused answers.',
+  detailed: 'Give thorough, detailed answers with context and examples.',
+}
+
+const CITATION_TEXT = {
+  numeric: 'Cite sources with bracketed numbers like [1].',
+  apa: 'When citing sources, format references in APA style.',
+  mla: 'When citing sources, format references in MLA style.',
+  chicago: 'When citing sources, format references in Chicago style.',
+}
+
+/**
+ * Preference instructions for a system prompt, or '' when nothing is set
+ */
+export const buildPreferencesPrompt = preferences => {
+  const prefs = { ...DEFAULT_PREFERENCES, ...(preferences || {}) }
+  const lines = []
+  if (prefs.language) {
+    lines.push(`Answer in ${prefs.language} unless the user explicitly asks for another language.`)
+  }
+  if (prefs.units === 'metric') lines.push('Use metric (SI) units; convert imperial figures.')
+  if (prefs.units === 'imperial') {
+    lines.push('Use imperial/US customary units; convert metric figures.')
+  }
+  if (VERBOSITY_TEXT[prefs.verbosity]) lines.push(VERBOSITY_TEXT[prefs.verbosity])
+  if (CITATION_TEXT[prefs.citationStyle]) lines.push(CITATION_TEXT[prefs.citationStyle])
+  if (prefs.customInstructions) lines.push(prefs.customInstructions)
+  if (!lines.length) return ''
+  return `User preferences (apply unless the current request says otherwise):\n${lines
+    .map(line => `- ${line}`)
+    .join('\n')}`
+}
+
+/**
+ * Add the user's preferences as a system message; merges into a leading system message if present
+ */
+export const applyPreferencesToMessages = (messages, userId) => {
+  const prompt = buildPreferencesPrompt(getPreferences(userId))
+  if (!prompt || !Array.isArray(messages)) return messages
+  const [first, ...rest] = messages
+  if (first?.role === 'system' && typeof first.content === 'string') {
+    return [{ ...first, content: `${first.content}\n\n${prompt}` }, ...rest]
+  }
+  return [{ role: 'system', content: prompt }, ...messages]
+}
@@ -0,0 +1,235 @@
+/**
+ * Proofread service
+ * Grammar and style checks with configurable rules (passive voice, sentence length, banned terms)
+ * that return structured issues with character spans and suggested fixes.
+ */
+
+import { completeJson } from './modelCompletion.js'
+
+export const DEFAULT_STYLE_RULES = {
+  passiveVoice: true,
+  maxSentenceWords: 35,
+  bannedTerms: [],
+  instructions: '',
+  llm: true,
+}
+
+const MAX_LLM_CHARS = 16000
+const SEVERITIES = ['error', 'warning', 'suggestion']
+
+const IRREGULAR_PARTICIPLES = `begun bought brought built caught chosen done drawn driven eaten
+  fallen felt found forgotten given gone grown held hidden kept known laid led left lost made meant
+  met paid put read run said seen sent set shown sold spent spoken stolen taken taught thought
+  thrown told understood won worn written`.split(/\s+/)
+
+const PARTICIPLE = `(?:\\w+ed|${IRREGULAR_PARTICIPLES.join('|')})`
+const PASSIVE_PATTERN = new RegExp(
+  `\\b(?:am|is|are|was|were|be|been|being)\\s+(?:\\w+ly\\s+)?${PARTICIPLE}\\b`,
+  'gi',
+)
+
+const SENTENCE_PATTERN = /[^.!?。！？\n]+[.!?。！？]*/g
+const CJK_PATTERN = /[\p{Script=Han}\p{Script=Hiragana}\p{Script=Katakana}\p{Script=Hangul}]/gu
+
+const escapeRegExp = value => value.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')
+
+export const normalizeStyleRules = (rules = {}) => ({
+  passiveVoice: rules.passiveVoice ?? DEFAULT_STYLE_RULES.passiveVoice,
+  maxSentenceWords:
+    Number.isFinite(Number(rules.maxSentenceWords)) && Number(rules.maxSentenceWords) > 0
+      ? Number(rules.maxSentenceWords)
+      : rules.maxSentenceWords === 0 || rules.maxSentenceWords === false
+        ? null
+        : DEFAULT_STYLE_RULES.maxSentenceWords,
+  bannedTerms: (Array.isArray(rules.bannedTerms) ? rules.bannedTerms : [])
+    .map(item => (typeof item === 'string' ? { term: item } : item))
+    .filter(item => typeof item?.term === 'string' && item.term.trim())
+    .map(item => ({
+      term: item.term.trim(),
+      replacement: item.replacement || null,
+      reason: item.reason || null,
+    })),
+  instructions: typeof rules.instructions === 'string' ? rules.instructions : '',
+  llm: rules.llm ?? DEFAULT_STYLE_RULES.llm,
+})
+
+// CJK text has no spaces; two characters count roughly as one word
+const countWords = sentence => {
+  const cjk = (sentence.match(CJK_PATTERN) || []).length
+  const words = (sentence.replace(CJK_PATTERN, ' ').match(/[\p{L}\p{N}]+/gu) || []).length
+  return words + Math.ceil(cjk / 2)
+}
+
+const buildIssue = ({ rule, severity = 'warning', message, start, text, suggestion = null }) => ({
+  rule,
+  severity,
+  message,
+  start,
+  end: start + text.length,
+  text,
+  suggestion,
+})
+
+/**
+ * Deterministic rule checks (no model call)
+ */
+export const checkStyleRules = (text, rules = {}) => {
+  const value = String(text || '')
+  const config = normalizeStyleRules(rules)
+  const issues = []
+
+  if (config.passiveVoice) {
+    for (const match of value.matchAll(PASSIVE_PATTERN)) {
+      issues.push(
+        buildIssue({
+          rule: 'passive_voice',
+          severity: 'suggestion',
+          message: 'Passive voice; consider naming who performs the action.',
+          start: match.index,
+          text: match[0],
+        }),
+      )
+    }
+  }
+
+  if (config.maxSentenceWords) {
+    for (const match of value.matchAll(SENTENCE_PATTERN)) {
+      const sentence = match[0].trim()
+      if (!sentence) continue
+      const words = countWords(sentence)
+      if (words > config.maxSentenceWords) {
+        issues.push(
+          buildIssue({
+            rule: 'sentence_length',
+            message: `Sentence has about ${words} words (limit ${config.maxSentenceWords}); consider splitting it.`,
+            start: match.index + match[0].indexOf(sentence),
+            text: sentence,
+          }),
+        )
+      }
+    }
+  }
+
+  for (const banned of config.bannedTerms) {
+    const escaped = escapeRegExp(banned.term)
+    const prefix = /^\w/.test(banned.term) ? '\\b' : ''
+    const suffix = /\w$/.test(banned.term) ? '\\b' : ''
+    const pattern = new RegExp(`${prefix}${escaped}${suffix}`, 'gi')
+    for (const match of value.matchAll(pattern)) {
+      issues.push(
+        buildIssue({
+          rule: 'banned_term',
+          severity: 'error',
+          message: banned.reason
+            ? `"${banned.term}" is not allowed: ${banned.reason}`
+            : `"${banned.term}" is not allowed.`,
+          start: match.index,
+          text: match[0],
+          suggestion: banned.replacement,
+        }),
+      )
+    }
+  }
+
+  return issues
+}
+
+const buildProofreadMessages = (text, config) => {
+  const houseRules = config.instructions
+    ? `\n\nHouse style rules to enforce:\n${config.instructions}`
+    : ''
+  return [
+    {
+      role: 'system',
+      content: `You are a meticulous proofreader. Find grammar, spelling, punctuation, word-choice, and clarity problems.
+Rules:
+- "quote" must be copied EXACTLY from the text (shortest span that contains the problem).
+- "suggestion" is the replacement for the quoted span, or null when the fix is not a simple replacement.
+- "rule" is one of: grammar, spelling, punctuation, word_choice, clarity, style.
+- "severity" is one of: ${SEVERITIES.join(', ')}.
+- Do not report passive voice or sentence length; those are checked separately.
+- Write messages in the text's language.${houseRules}
+
+Return ONLY JSON: {"issues": [{"quote": "...", "message": "...", "suggestion": "...", "rule": "grammar", "severity": "error"}]}`,
+    },
+    { role: 'user', content: text.slice(0, MAX_LLM_CHARS) },
+  ]
+}
+
+/**
+ * Map model-quoted spans back to character offsets, walking forward so repeats resolve in order
+ */
+const locateModelIssues = (text, parsed) => {
+  const list = Array.isArray(parsed) ? parsed : parsed?.issues
+  if (!Array.isArray(list)) return []
+  const cursors = new Map()
+  const issues = []
+  for (const item of list) {
+    const quote = typeof item?.quote === 'string' ? item.quote : ''
+    if (!quote) continue
+    const from = cursors.get(quote) || 0
+    let start = text.indexOf(quote, from)
+    if (start === -1) start = text.indexOf(quote)
+    if (start === -1) continue
+    cursors.set(quote, start + quote.length)
+    issues.push(
+      buildIssue({
+        rule: typeof item.rule === 'string' ? item.rule : 'grammar',
+        severity: SEVERITIES.includes(item.severity) ? item.severity : 'warning',
+        message: String(item.message || 'Possible issue.'),
+        start,
+        text: quote,
+        suggestion: typeof item.suggestion === 'string' ? item.suggestion : null,
+      }),
+    )
+  }
+  return issues
+}
+
+/**
+ * Proofread text with rule checks plus (when credentials are given) a model pass
+ * @returns {Promise<{ issues: Array, rules: Object, llm: boolean }>}
+ */
+export const proofreadText = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  text,
+  rules,
+  signal,
+}) => {
+  const value = String(text || '')
+  const config = normalizeStyleRules(rules)
+  const issues = checkStyleRules(value, config)
+
+  const useModel = Boolean(config.llm && provider && apiKey && value.trim())
+  if (useModel) {
+    try {
+      const parsed = await completeJson({
+        provider,
+        apiKey,
+        baseUrl,
+        model,
+        messages: buildProofreadMessages(value, config),
+        temperature: 0,
+        signal,
+      })
+      issues.push(...locateModelIssues(value, parsed))
+    } catch (error) {
+      if (signal?.aborted) throw error
+      console.warn('[Proofread] Model pass failed, returning rule checks only:', error.message)
+    }
+  }
+
+  const seen = new Set()
+  const unique = issues
+    .sort((a, b) => a.start - b.start || a.end - b.end)
+    .filter(issue => {
+      const key = `${issue.rule}:${issue.start}:${issue.end}`
+      if (seen.has(key)) return false
+      seen.add(key)
+      return true
+    })
+  return { issues: unique, rules: config, llm: useModel }
+}
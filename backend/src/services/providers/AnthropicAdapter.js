@@ -0,0 +1,118 @@
+/**
+ * Anthropic Provider Adapter
+ * Handles Claude models through the native Messages API (streaming, thinking, tool use)
+ */
+
+import { AnthropicChatModel, resolveAnthropicThinking } from './AnthropicChatModel.js'
+import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { getProviderConfig } from './providerConfig.js'
+
+const CLAUDE_CONTEXT_WINDOW = 200000
+
+export class AnthropicAdapter extends BaseProviderAdapter {
+  constructor() {
+    super('anthropic')
+  }
+
+  get capabilities() {
+    return getProviderConfig('anthropic').capabilities
+  }
+
+  get config() {
+    return getProviderConfig('anthropic')
+  }
+
+  /**
+   * List Claude models (GET /v1/models with x-api-key auth)
+   * @override
+   */
+  async listModels({ apiKey, baseUrl, signal } = {}) {
+    if (!apiKey) throw new Error('Missing API key for Anthropic')
+    const base = String(baseUrl || this.config.baseURL).replace(/\/+$/, '')
+    const timeoutSignal = AbortSignal.timeout(10000)
+    const response = await fetch(`${base}/models?limit=1000`, {
+      headers: { 'x-api-key': apiKey, 'anthropic-version': '2023-06-01' },
+      signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+    })
+    if (!response.ok) {
+      throw Object.assign(new Error(`Failed to list anthropic models: HTTP ${response.status}`), {
+        status: response.status,
+        headers: response.headers,
+      })
+    }
+    const data = await response.json()
+    return (data.data || []).map(item => ({
+      ...this.toModelDescriptor(item),
+      context_window: CLAUDE_CONTEXT_WINDOW,
+      supports_vision: true,
+    }))
+  }
+
+  /**
+   * Build Anthropic model instance
+   * Note: frequency/presence penalties have no Messages API equivalent and are dropped
+   */
+  buildModel(params) {
+    const {
+      apiKey,
+      baseUrl,
+      model,
+      temperature,
+      top_k,
+      top_p,
+      tools,
+      toolChoice,
+      responseFormat,
+      thinking,
+      streaming,
+    } = params
+
+    if (!apiKey) throw new Error('Missing API key for Anthropic')
+
+    return new AnthropicChatModel({
+      apiKey,
+      baseUrl: baseUrl || this.config.baseURL,
+      model: model || this.config.defaultModel,
+      temperature,
+      topK: top_k,
+      topP: top_p,
+      tools,
+      toolChoice,
+      responseFormat,
+      thinking: resolveAnthropicThinking(thinking),
+      streaming,
+    })
+  }
+
+  /**
+   * Execute request with streaming support
+   * Tool use blocks stream as OpenAI-style tool_call deltas
+   */
+  async execute(messages, params) {
+    const { tools, stream } = params
+
+    const modelInstance = this.buildModel({
+      ...params,
+      tools,
+      streaming: stream,
+    })
+
+    if (stream) {
+      return {
+        type: 'stream',
+        modelInstance,
+        messages,
+      }
+    }
+
+    return this.executeNonStreamingForToolCalls(messages, params)
+  }
+
+  /**
+   * Pass plain messages straight through; AnthropicChatModel converts them itself
+   * @override
+   */
+  async createStreamIterator(modelInstance, messages, signal) {
+    return modelInstance.stream(messages, signal ? { signal } : undefined)
+  }
+}
@@ -0,0 +1,396 @@
+/**
+ * Anthropic Messages API model
+ * Minimal chat model with the invoke()/stream() surface the services use on LangChain models.
+ * Responses and stream chunks mirror the OpenAI shape under additional_kwargs.__raw_response
+ * (delta.content, delta.tool_calls, finish_reason) so the existing tool loops work unchanged;
+ * thinking is exposed as additional_kwargs.reasoning_content.
+ */
+
+import { readServerSentEvents } from '../../utils/sse.js'
+
+const ANTHROPIC_VERSION = '2023-06-01'
+const DEFAULT_MAX_TOKENS = 8192
+const DEFAULT_THINKING_BUDGET = 4096
+const MAX_CACHED_THINKING = 200
+
+const FINISH_REASONS = {
+  end_turn: 'stop',
+  stop_sequence: 'stop',
+  tool_use: 'tool_calls',
+  max_tokens: 'length',
+  refusal: 'content_filter',
+}
+
+// Thinking blocks must be sent back ahead of the tool_use blocks they produced, but callers only
+// keep OpenAI-style tool_calls, so the blocks are remembered by tool_use id
+const thinkingByToolUseId = new Map()
+
+const rememberThinking = (toolUseIds, blocks) => {
+  if (!blocks.length || !toolUseIds.length) return
+  toolUseIds.forEach(id => thinkingByToolUseId.set(id, blocks))
+  while (thinkingByToolUseId.size > MAX_CACHED_THINKING) {
+    thinkingByToolUseId.delete(thinkingByToolUseId.keys().next().value)
+  }
+}
+
+const getMessageType = message => {
+  const type = message?.role || message?.type || message?._getType?.()
+  if (type === 'human') return 'user'
+  if (type === 'ai') return 'assistant'
+  return type
+}
+
+const toContentBlocks = content => {
+  if (typeof content === 'string') return content ? [{ type: 'text', text: content }] : []
+  if (!Array.isArray(content)) return []
+  return content
+    .map(part => {
+      if (typeof part === 'string') return { type: 'text', text: part }
+      if (part?.type === 'text') return { type: 'text', text: part.text || '' }
+      if (part?.type === 'image_url') {
+        const url = part.image_url?.url || part.image_url
+        const dataUrl = String(url || '').match(/^data:([^;]+);base64,(.+)$/)
+        return dataUrl
+          ? { type: 'image', source: { type: 'base64', media_type: dataUrl[1], data: dataUrl[2] } }
+          : { type: 'image', source: { type: 'url', url } }
+      }
+      if (part?.type === 'media' && part.data) {
+        const source = { type: 'base64', media_type: part.mimeType, data: part.data }
+        return { type: part.mimeType === 'application/pdf' ? 'document' : 'image', source }
+      }
+      return null
+    })
+    .filter(block => block && (block.type !== 'text' || block.text))
+}
+
+const contentToText = content =>
+  toContentBlocks(content)
+    .filter(block => block.type === 'text')
+    .map(block => block.text)
+    .join('\n')
+
+const parseArguments = value => {
+  if (value && typeof value === 'object') return value
+  try {
+    return value ? JSON.parse(value) : {}
+  } catch {
+    return {}
+  }
+}
+
+// Accept OpenAI-style ({ function: { name, arguments } }) and LangChain ({ name, args }) calls
+const toToolUseBlock = toolCall => ({
+  type: 'tool_use',
+  id: toolCall.id,
+  name: toolCall.function?.name || toolCall.name,
+  input: parseArguments(toolCall.function?.arguments ?? toolCall.args),
+})
+
+/**
+ * Convert chat messages (plain or LangChain) into { system, messages } for the Messages API
+ * Tool results become user tool_result blocks; consecutive same-role turns are merged.
+ */
+export const toAnthropicMessages = messages => {
+  const system = []
+  const turns = []
+  const pushTurn = (role, blocks) => {
+    if (!blocks.length) return
+    const last = turns[turns.length - 1]
+    if (last?.role === role) last.content.push(...blocks)
+    else turns.push({ role, content: [...blocks] })
+  }
+
+  for (const message of messages || []) {
+    const type = getMessageType(message)
+    if (type === 'system') {
+      const text = contentToText(message.content)
+      if (text) system.push(text)
+    } else if (type === 'assistant') {
+      const toolCalls = message.tool_calls?.length
+        ? message.tool_calls
+        : message.additional_kwargs?.tool_calls || []
+      const toolUses = toolCalls.map(toToolUseBlock).filter(block => block.id && block.name)
+      const thinking = toolUses.length ? thinkingByToolUseId.get(toolUses[0].id) || [] : []
+      pushTurn('assistant', [...thinking, ...toContentBlocks(message.content), ...toolUses])
+    } else if (type === 'tool') {
+      pushTurn('user', [
+        {
+          type: 'tool_result',
+          tool_use_id: message.tool_call_id,
+          content: contentToText(message.content) || '(empty)',
+        },
+      ])
+    } else {
+      pushTurn('user', toContentBlocks(message.content))
+    }
+  }
+  return { system: system.join('\n\n'), messages: turns }
+}
+
+const toAnthropicTools = tools =>
+  (tools || [])
+    .map(tool => tool?.function || tool)
+    .filter(fn => fn?.name)
+    .map(fn => ({
+      name: fn.name,
+      description: fn.description || '',
+      input_schema: fn.parameters || fn.input_schema || { type: 'object', properties: {} },
+    }))
+
+const toAnthropicToolChoice = toolChoice => {
+  if (!toolChoice) return undefined
+  if (toolChoice === 'auto') return { type: 'auto' }
+  if (toolChoice === 'none') return { type: 'none' }
+  if (toolChoice === 'required' || toolChoice === 'any') return { type: 'any' }
+  const name = toolChoice.function?.name || toolChoice.name
+  return name ? { type: 'tool', name } : undefined
+}
+
+const toUsageMetadata = usage => {
+  const input =
+    (usage?.input_tokens || 0) +
+    (usage?.cache_creation_input_tokens || 0) +
+    (usage?.cache_read_input_tokens || 0)
+  const output = usage?.output_tokens || 0
+  return { input_tokens: input, output_tokens: output, total_tokens: input + output }
+}
+
+const toOpenAIToolCall = (block, index) => ({
+  index,
+  id: block.id,
+  type: 'function',
+  function: { name: block.name, arguments: JSON.stringify(block.input || {}) },
+})
+
+const buildChunk = ({ text = '', thinking = '', toolCalls, finishReason = null, usage }) => ({
+  content: text,
+  additional_kwargs: {
+    ...(thinking ? { reasoning_content: thinking } : {}),
+    __raw_response: {
+      choices: [
+        {
+          delta: { content: text || null, ...(toolCalls ? { tool_calls: toolCalls } : {}) },
+          finish_reason: finishReason,
+        },
+      ],
+    },
+  },
+  ...(usage ? { usage_metadata: usage } : {}),
+})
+
+export class AnthropicChatModel {
+  constructor({
+    apiKey,
+    baseUrl,
+    model,
+    temperature,
+    topK,
+    topP,
+    maxTokens,
+    tools,
+    toolChoice,
+    responseFormat,
+    thinking,
+    streaming = false,
+  }) {
+    this.apiKey = apiKey
+    this.baseUrl = String(baseUrl).replace(/\/+$/, '')
+    this.model = model
+    this.temperature = temperature
+    this.topK = topK
+    this.topP = topP
+    this.maxTokens = maxTokens || DEFAULT_MAX_TOKENS
+    this.tools = toAnthropicTools(tools)
+    this.toolChoice = toAnthropicToolChoice(toolChoice)
+    this.jsonMode = responseFormat?.type === 'json_object' || responseFormat?.type === 'json_schema'
+    this.thinking = thinking
+    this.streaming = streaming
+  }
+
+  buildRequestBody(messages, stream) {
+    const { system, messages: turns } = toAnthropicMessages(messages)
+    const jsonHint = this.jsonMode ? 'Respond with a single valid JSON object and nothing else.' : ''
+    const body = {
+      model: this.model,
+      max_tokens: this.maxTokens,
+      messages: turns,
+      stream,
+    }
+    const systemText = [system, jsonHint].filter(Boolean).join('\n\n')
+    if (systemText) body.system = systemText
+
+    if (this.thinking) {
+      const budget = Math.min(this.thinking.budget_tokens, this.maxTokens - 1024)
+      body.thinking = { type: 'enabled', budget_tokens: Math.max(budget, 1024) }
+      body.max_tokens = Math.max(this.maxTokens, body.thinking.budget_tokens + 1024)
+    } else {
+      // Sampling parameters are not accepted together with extended thinking
+      if (this.temperature !== undefined) body.temperature = Math.min(this.temperature, 1)
+      if (this.topK !== undefined) body.top_k = this.topK
+      if (this.topP !== undefined) body.top_p = this.topP
+    }
+    if (this.tools.length) {
+      body.tools = this.tools
+      // Extended thinking only supports auto/none tool choice
+      if (this.toolChoice && (!this.thinking || ['auto', 'none'].includes(this.toolChoice.type))) {
+        body.tool_choice = this.toolChoice
+      }
+    }
+    return body
+  }
+
+  async request(messages, { stream, signal }) {
+    const response = await fetch(`${this.baseUrl}/messages`, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        'x-api-key': this.apiKey,
+        'anthropic-version': ANTHROPIC_VERSION,
+      },
+      body: JSON.stringify(this.buildRequestBody(messages, stream)),
+      signal,
+    })
+    if (!response.ok) {
+      const detail = await response.text().catch(() => '')
+      let message = detail
+      try {
+        message = JSON.parse(detail)?.error?.message || detail
+      } catch {
+        // Non-JSON error body
+      }
+      throw new Error(
+        `Anthropic API error: HTTP ${response.status}${message ? ` ${message}` : ''}`,
+      )
+    }
+    return response
+  }
+
+  /**
+   * Non-streaming completion
+   */
+  async invoke(messages, options = {}) {
+    const response = await this.request(messages, { stream: false, signal: options.signal })
+    const data = await response.json()
+    const blocks = data.content || []
+    const text = blocks
+      .filter(block => block.type === 'text')
+      .map(block => block.text)
+      .join('')
+    const thinking = blocks
+      .filter(block => block.type === 'thinking')
+      .map(block => block.thinking)
+      .join('')
+    const toolUses = blocks.filter(block => block.type === 'tool_use')
+    const toolCalls = toolUses.map(toOpenAIToolCall)
+    rememberThinking(
+      toolUses.map(block => block.id),
+      blocks.filter(block => block.type === 'thinking' || block.type === 'redacted_thinking'),
+    )
+
+    return {
+      content: text,
+      tool_calls: toolUses.map(block => ({ id: block.id, name: block.name, args: block.input })),
+      additional_kwargs: {
+        ...(thinking ? { reasoning_content: thinking } : {}),
+        ...(toolCalls.length ? { tool_calls: toolCalls } : {}),
+        __raw_response: {
+          id: data.id,
+          model: data.model,
+          choices: [
+            {
+              message: { role: 'assistant', content: text, tool_calls: toolCalls },
+              finish_reason: FINISH_REASONS[data.stop_reason] || data.stop_reason || null,
+            },
+          ],
+        },
+      },
+      usage_metadata: toUsageMetadata(data.usage),
+      response_metadata: { stop_reason: data.stop_reason, model: data.model },
+    }
+  }
+
+  /**
+   * Streaming completion: message_start -> content_block_* -> message_delta -> message_stop
+   */
+  async stream(messages, options = {}) {
+    const response = await this.request(messages, { stream: true, signal: options.signal })
+    return this.parseStream(response.body)
+  }
+
+  async *parseStream(body) {
+    const blocks = new Map()
+    let toolIndex = 0
+    let inputUsage = null
+
+    for await (const message of readServerSentEvents(body)) {
+      const { event } = message
+      const data = JSON.parse(message.data)
+      const type = data?.type || event
+      if (type === 'message_start') {
+        inputUsage = data.message?.usage || null
+      } else if (type === 'content_block_start') {
+        const block = { ...data.content_block, partialJson: '' }
+        if (block.type === 'tool_use') {
+          block.toolIndex = toolIndex++
+          yield buildChunk({
+            toolCalls: [
+              {
+                index: block.toolIndex,
+                id: block.id,
+                type: 'function',
+                function: { name: block.name, arguments: '' },
+              },
+            ],
+          })
+        }
+        blocks.set(data.index, block)
+      } else if (type === 'content_block_delta') {
+        const block = blocks.get(data.index)
+        const delta = data.delta || {}
+        if (delta.type === 'text_delta') {
+          yield buildChunk({ text: delta.text })
+        } else if (delta.type === 'thinking_delta') {
+          if (block) block.thinking = `${block.thinking || ''}${delta.thinking}`
+          yield buildChunk({ thinking: delta.thinking })
+        } else if (delta.type === 'signature_delta') {
+          if (block) block.signature = `${block.signature || ''}${delta.signature}`
+        } else if (delta.type === 'input_json_delta' && block) {
+          block.partialJson += delta.partial_json
+          yield buildChunk({
+            toolCalls: [{ index: block.toolIndex, function: { arguments: delta.partial_json } }],
+          })
+        }
+      } else if (type === 'message_delta') {
+        const stopReason = data.delta?.stop_reason
+        if (stopReason === 'tool_use') {
+          const ordered = Array.from(blocks.values())
+          rememberThinking(
+            ordered.filter(block => block.type === 'tool_use').map(block => block.id),
+            ordered
+              .filter(block => block.type === 'thinking' || block.type === 'redacted_thinking')
+              .map(({ partialJson: _partialJson, ...block }) => block),
+          )
+        }
+        yield buildChunk({
+          finishReason: FINISH_REASONS[stopReason] || stopReason || null,
+          usage: toUsageMetadata({ ...inputUsage, output_tokens: data.usage?.output_tokens }),
+        })
+      } else if (type === 'error') {
+        throw new Error(`Anthropic stream error: ${data.error?.message || 'unknown error'}`)
+      }
+    }
+  }
+}
+
+export const resolveAnthropicThinking = thinking => {
+  if (!thinking || thinking === false) return null
+  if (thinking === true) return { budget_tokens: DEFAULT_THINKING_BUDGET }
+  if (thinking.type === 'disabled') return null
+  if (thinking.type === 'enabled' || thinking.budget_tokens || thinking.budgetTokens) {
+    return {
+      budget_tokens:
+        Number(thinking.budget_tokens || thinking.budgetTokens) || DEFAULT_THINKING_BUDGET,
+    }
+  }
+  return null
+}
@@ -3,8 +3,25 @@
  * Abstract base class defining the interface for all provider adapters
  */
 
+import { extractUsage } from '../researchTelemetry.js'
 import { safeJsonParse, toLangChainMessages } from '../serviceUtils.js'
 
+const MODEL_LIST_TIMEOUT_MS = 10000
+
+// Embedding, audio, image, and moderation models are not usable for chat
+const NON_CHAT_MODEL_PATTERN =
+  /embed|whisper|tts|dall-e|moderation|rerank|davinci|babbage|transcribe|speech|bge-|stable-diffusion|flux|image/i
+const VISION_MODEL_PATTERN =
+  /vision|vl\b|-vl|vl-|gpt-4o|gpt-4\.1|gpt-5|\bo[134]\b|\bo[134]-|gemini|llava|pixtral|glm-4v|internvl/i
+
+export const isVisionModel = id => VISION_MODEL_PATTERN.test(String(id || ''))
+
+// "moonshot-v1-8k" -> 8192, "qwen-128k" -> 131072
+const inferContextWindow = id => {
+  const match = String(id).match(/(\d+)k\b/i)
+  return match ? Number(match[1]) * 1024 : null
+}
+
 export class BaseProviderAdapter {
   constructor(providerName) {
     this.providerName = providerName
@@ -44,6 +61,86 @@ export class BaseProviderAdapter {
     throw new Error('Must implement execute()')
   }
 
+  /**
+   * Path of the model-listing endpoint relative to the base URL
+   */
+  get modelListPath() {
+    return '/models'
+  }
+
+  /**
+   * Model ids to report when the provider has no listing endpoint
+   */
+  get knownModels() {
+    return []
+  }
+
+  /**
+   * List chat models available to this key
+   * Default: OpenAI-compatible GET {baseURL}/models
+   * @param {Object} params - { apiKey, baseUrl, signal }
+   * @returns {Promise<Array<{id, name, context_window, supports_tools, supports_vision}>>}
+   */
+  async listModels({ apiKey, baseUrl, signal } = {}) {
+    if (!apiKey) throw new Error(`Missing API key for ${this.providerName}`)
+    const base = String(baseUrl || this.config.baseURL || '').replace(/\/+$/, '')
+    const timeoutSignal = AbortSignal.timeout(MODEL_LIST_TIMEOUT_MS)
+    const response = await fetch(`${base}${this.modelListPath}`, {
+      headers: { Authorization: `Bearer ${apiKey}` },
+      signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+    })
+    if ((response.status === 404 || response.status === 405) && this.knownModels.length) {
+      return this.knownModels.map(id => this.toModelDescriptor({ id }))
+    }
+    if (!response.ok) {
+      throw Object.assign(
+        new Error(`Failed to list ${this.providerName} models: HTTP ${response.status}`),
+        { status: response.status, headers: response.headers },
+      )
+    }
+    const data = await response.json()
+    return (data.data || data.models || [])
+      .filter(item => item?.id && !NON_CHAT_MODEL_PATTERN.test(item.id))
+      .map(item => this.toModelDescriptor(item))
+  }
+
+  /**
+   * Normalize a provider model entry into a ModelDescriptor
+   */
+  toModelDescriptor(raw) {
+    const id = raw.id
+    return {
+      id,
+      name: raw.name || raw.display_name || id,
+      context_window:
+        raw.context_length ?? raw.context_window ?? raw.max_context_length ?? inferContextWindow(id),
+      supports_tools: Boolean(this.capabilities.supportsToolCalls),
+      supports_vision: isVisionModel(id),
+    }
+  }
+
+  /**
+   * Adjust plain chat messages before they are converted to LangChain messages
+   * Default: pass through unchanged; strict OpenAI-compatible providers override this
+   * @param {Array} messages - Message history
+   * @returns {Array} Message history to send
+   */
+  prepareMessages(messages) {
+    return messages
+  }
+
+  /**
+   * LangChain messages for one model call
+   * Default: prepareMessages() and the standard conversion; providers whose requests carry
+   * messages LangChain has no type for (Kimi's cache references) override this
+   * @param {Array} messages - Message history
+   * @param {Object} modelInstance - The model the messages are sent to
+   * @returns {Array} LangChain messages
+   */
+  toModelMessages(messages, modelInstance) {
+    return toLangChainMessages(this.prepareMessages(messages))
+  }
+
   /**
    * Handle streaming response
    * @param {Object} modelInstance - Model instance
@@ -52,7 +149,7 @@ export class BaseProviderAdapter {
    * @returns {AsyncGenerator} Stream iterator
    */
   async createStreamIterator(modelInstance, messages, signal) {
-    const langchainMessages = toLangChainMessages(messages)
+    const langchainMessages = this.toModelMessages(messages, modelInstance)
     return await modelInstance.stream(langchainMessages, signal ? { signal } : undefined)
   }
 
@@ -108,13 +205,31 @@ export class BaseProviderAdapter {
     )
   }
 
+  /**
+   * Sources the provider found with its own built-in search (e.g. Gemini grounding)
+   * @param {Object} messageChunk - Streaming message chunk or response
+   * @returns {Array<{ title: string, url: string }>}
+   */
+  extractSources(messageChunk) {
+    return []
+  }
+
+  /**
+   * Calls of provider-executed tools (e.g. Gemini code execution) found in a chunk
+   * @param {Object} messageChunk - Streaming message chunk or response
+   * @returns {Array<{ kind: 'call'|'result', name: string, arguments?: Object, output?: Object }>}
+   */
+  extractNativeToolEvents(messageChunk) {
+    return []
+  }
+
   /**
    * Execute non-streaming request for tool calls
    * Used when provider doesn't support streaming tool calls
    */
   async executeNonStreamingForToolCalls(messages, params) {
     const nonStreamingModel = this.buildModel({ ...params, streaming: false })
-    const langchainMessages = toLangChainMessages(messages)
+    const langchainMessages = this.toModelMessages(messages, nonStreamingModel)
     const response = await nonStreamingModel.invoke(
       langchainMessages,
       params.signal ? { signal: params.signal } : undefined,
@@ -149,6 +264,7 @@ export class BaseProviderAdapter {
           type: 'tool_calls',
           toolCalls: this.normalizeToolCalls(toolCalls),
           thought: match[1],
+          usage: extractUsage(response),
         }
       }
     }
@@ -158,6 +274,7 @@ export class BaseProviderAdapter {
         type: 'tool_calls',
         toolCalls: this.normalizeToolCalls(toolCalls),
         thought, // Return extracted thought
+        usage: extractUsage(response),
       }
     }
 
@@ -165,6 +282,7 @@ export class BaseProviderAdapter {
       type: 'response', // Standardize on 'response' for non-streaming final answer
       response,
       thought, // Return extracted thought
+      usage: extractUsage(response),
     }
   }
 
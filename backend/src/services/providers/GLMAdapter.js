@@ -5,8 +5,18 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { convertOpenAICompatMessages } from './openaiCompatMessages.js'
 import { getProviderConfig } from './providerConfig.js'
 
+// Tool id (see toolsService) that enables GLM's server-side web search instead of Tavily
+export const GLM_NATIVE_SEARCH_TOOL_ID = 'glm_native_search'
+
+// "search_std" is Zhipu's basic (cheapest) engine; search_result returns the pages it used
+const GLM_WEB_SEARCH_TOOL = {
+  type: 'web_search',
+  web_search: { enable: true, search_engine: 'search_std', search_result: true },
+}
+
 /**
  * Check if model supports GLM tool streaming (glm-4.6+)
  * @param {string} model - Model name
@@ -31,6 +41,28 @@ export class GLMAdapter extends BaseProviderAdapter {
     return getProviderConfig('glm')
   }
 
+  /**
+   * Strict OpenAI-compatible message shape (see openaiCompatMessages)
+   * @override
+   */
+  prepareMessages(messages) {
+    return convertOpenAICompatMessages(messages)
+  }
+
+  // Zhipu has no public model-listing endpoint
+  get knownModels() {
+    return [
+      'glm-4.7',
+      'glm-4.6',
+      'glm-4.5',
+      'glm-4.5-air',
+      'glm-4-plus',
+      'glm-4-flash',
+      'glm-4v-plus',
+      'glm-4v-flash',
+    ]
+  }
+
   /**
    * Build GLM model instance
    */
@@ -48,6 +80,7 @@ export class GLMAdapter extends BaseProviderAdapter {
       responseFormat,
       thinking,
       streaming,
+      toolIds,
     } = params
 
     if (!apiKey) throw new Error('Missing API key for GLM')
@@ -69,7 +102,10 @@ export class GLMAdapter extends BaseProviderAdapter {
     if (top_p !== undefined) modelKwargs.top_p = top_p
     if (frequency_penalty !== undefined) modelKwargs.frequency_penalty = frequency_penalty
     if (presence_penalty !== undefined) modelKwargs.presence_penalty = presence_penalty
-    if (tools && tools.length > 0) modelKwargs.tools = tools
+    // Web search runs on Zhipu's side, next to any function tools
+    const nativeSearch = Array.isArray(toolIds) && toolIds.includes(GLM_NATIVE_SEARCH_TOOL_ID)
+    const requestTools = [...(tools || []), ...(nativeSearch ? [GLM_WEB_SEARCH_TOOL] : [])]
+    if (requestTools.length > 0) modelKwargs.tools = requestTools
     if (toolChoice) modelKwargs.tool_choice = toolChoice
     if (streaming) {
       modelKwargs.stream_options = { include_usage: false }
@@ -88,11 +124,28 @@ export class GLMAdapter extends BaseProviderAdapter {
       temperature,
       streaming,
       __includeRawResponse: true,
+      // Transient failures are retried (with retrying events) by providers/retry.js
+      maxRetries: 0,
       modelKwargs,
       configuration: { baseURL: this.config.baseURL },
     })
   }
 
+  /**
+   * Pages GLM's built-in web search used (the top-level "web_search" list of the response,
+   * sent with the first streamed chunk)
+   * @override
+   */
+  extractSources(messageChunk) {
+    const results =
+      messageChunk?.additional_kwargs?.__raw_response?.web_search ||
+      messageChunk?.additional_kwargs?.web_search
+    if (!Array.isArray(results)) return []
+    return results
+      .filter(item => item?.link)
+      .map(item => ({ title: item.title || item.link, url: item.link }))
+  }
+
   /**
    * Execute request with streaming support
    * Note: GLM tool calls are unreliable in streaming for some models.
@@ -7,6 +7,19 @@ import { ChatGoogleGenerativeAI } from '@langchain/google-genai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
 import { getProviderConfig } from './providerConfig.js'
 
+const GEMINI_API_BASE = 'https://generativelanguage.googleapis.com/v1beta'
+
+// Tool ids (see toolsService) that enable Gemini's built-in tools instead of local functions
+export const GEMINI_NATIVE_TOOLS = {
+  google_search_grounding: { googleSearch: {} },
+  gemini_code_execution: { codeExecution: {} },
+}
+
+const getContentParts = messageChunk => {
+  const content = messageChunk?.content ?? messageChunk?.message?.content
+  return Array.isArray(content) ? content : []
+}
+
 export class GeminiAdapter extends BaseProviderAdapter {
   constructor() {
     super('gemini')
@@ -20,12 +33,39 @@ export class GeminiAdapter extends BaseProviderAdapter {
     return getProviderConfig('gemini')
   }
 
+  /**
+   * List Gemini models that support generateContent
+   * @override
+   */
+  async listModels({ apiKey, signal } = {}) {
+    if (!apiKey) throw new Error('Missing API key for Gemini')
+    const timeoutSignal = AbortSignal.timeout(10000)
+    const response = await fetch(`${GEMINI_API_BASE}/models?pageSize=1000`, {
+      headers: { 'x-goog-api-key': apiKey },
+      signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+    })
+    if (!response.ok) {
+      throw Object.assign(new Error(`Failed to list gemini models: HTTP ${response.status}`), {
+        status: response.status,
+        headers: response.headers,
+      })
+    }
+    const data = await response.json()
+    return (data.models || [])
+      .filter(item => item.supportedGenerationMethods?.includes('generateContent'))
+      .map(item => ({
+        ...this.toModelDescriptor({ id: item.name.replace(/^models\//, '') }),
+        name: item.displayName || item.name,
+        context_window: item.inputTokenLimit ?? null,
+      }))
+  }
+
   /**
    * Build Gemini model instance
    * Note: Gemini uses ChatGoogleGenerativeAI, not ChatOpenAI
    */
   buildModel(params) {
-    const { apiKey, model, temperature, top_k, top_p, tools, streaming } = params
+    const { apiKey, model, temperature, top_k, top_p, streaming, toolIds } = params
 
     if (!apiKey) throw new Error('Missing API key for Gemini')
 
@@ -34,7 +74,7 @@ export class GeminiAdapter extends BaseProviderAdapter {
     // but ChatGoogleGenerativeAI doesn't accept these params directly or via modelKwargs
     // Need to research LangChain's ChatGoogleGenerativeAI API documentation
 
-    return new ChatGoogleGenerativeAI({
+    const modelInstance = new ChatGoogleGenerativeAI({
       apiKey,
       model: model || this.config.defaultModel,
       temperature,
@@ -42,6 +82,12 @@ export class GeminiAdapter extends BaseProviderAdapter {
       ...(top_p !== undefined ? { topP: top_p } : {}),
       streaming,
     })
+
+    // Built-in tools run on Google's side, so their results arrive in the response itself
+    const nativeTools = (Array.isArray(toolIds) ? toolIds : [])
+      .map(id => GEMINI_NATIVE_TOOLS[id])
+      .filter(Boolean)
+    return nativeTools.length ? modelInstance.bindTools(nativeTools) : modelInstance
   }
 
   /**
@@ -94,6 +140,52 @@ export class GeminiAdapter extends BaseProviderAdapter {
     return super.extractThinkingContent(messageChunk)
   }
 
+  /**
+   * Web sources from Google Search grounding metadata
+   * @override
+   */
+  extractSources(messageChunk) {
+    const metadata =
+      messageChunk?.response_metadata?.groundingMetadata ||
+      messageChunk?.additional_kwargs?.groundingMetadata
+    const chunks = metadata?.groundingChunks
+    if (!Array.isArray(chunks)) return []
+    return chunks
+      .map(chunk => chunk?.web)
+      .filter(web => web?.uri)
+      .map(web => ({ title: web.title || web.uri, url: web.uri }))
+  }
+
+  /**
+   * Code the model ran with the code execution tool, and its output
+   * @override
+   */
+  extractNativeToolEvents(messageChunk) {
+    const events = []
+    for (const part of getContentParts(messageChunk)) {
+      if (part?.type === 'executableCode' && part.executableCode) {
+        events.push({
+          kind: 'call',
+          name: 'gemini_code_execution',
+          arguments: {
+            language: String(part.executableCode.language || 'PYTHON').toLowerCase(),
+            code: part.executableCode.code || '',
+          },
+        })
+      } else if (part?.type === 'codeExecutionResult' && part.codeExecutionResult) {
+        events.push({
+          kind: 'result',
+          name: 'gemini_code_execution',
+          output: {
+            outcome: part.codeExecutionResult.outcome || null,
+            output: part.codeExecutionResult.output || '',
+          },
+        })
+      }
+    }
+    return events
+  }
+
   /**
    * Override parseToolCalls for Gemini-specific format
    * Gemini may have different tool_calls structure
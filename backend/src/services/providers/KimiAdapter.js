@@ -1,12 +1,18 @@
 /**
  * Kimi Provider Adapter
- * Handles Moonshot AI's Kimi models
+ * Handles Moonshot AI's Kimi models, including context cache references (see kimiFiles)
  */
 
+import { ChatMessage } from '@langchain/core/messages'
 import { ChatOpenAI } from '@langchain/openai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { normalizeContextCache, toCacheReference } from './kimiFiles.js'
+import { convertOpenAICompatMessages } from './openaiCompatMessages.js'
 import { getProviderConfig } from './providerConfig.js'
 
+// The context cache a model instance was built for
+const CONTEXT_CACHE = Symbol('kimiContextCache')
+
 export class KimiAdapter extends BaseProviderAdapter {
   constructor() {
     super('kimi')
@@ -20,8 +26,28 @@ export class KimiAdapter extends BaseProviderAdapter {
     return getProviderConfig('kimi')
   }
 
+  /**
+   * Strict OpenAI-compatible message shape (see openaiCompatMessages)
+   * @override
+   */
+  prepareMessages(messages) {
+    return convertOpenAICompatMessages(messages)
+  }
+
+  /**
+   * A model built with a contextCache sends { role: "cache" } ahead of the conversation, so the
+   * cached documents are read from Moonshot's cache instead of the request
+   * @override
+   */
+  toModelMessages(messages, modelInstance) {
+    const converted = super.toModelMessages(messages, modelInstance)
+    const cache = modelInstance?.[CONTEXT_CACHE]
+    return cache ? [new ChatMessage(toCacheReference(cache), 'cache'), ...converted] : converted
+  }
+
   /**
    * Build Kimi model instance
+   * @param {Object} params - Also contextCache: "cache-id" or { id, reset_ttl }
    */
   buildModel(params) {
     const {
@@ -52,15 +78,20 @@ export class KimiAdapter extends BaseProviderAdapter {
       modelKwargs.stream_options = { include_usage: false }
     }
 
-    return new ChatOpenAI({
+    const modelInstance = new ChatOpenAI({
       apiKey,
       modelName: model || this.config.defaultModel,
       temperature,
       streaming,
       __includeRawResponse: true,
+      // Transient failures are retried (with retrying events) by providers/retry.js
+      maxRetries: 0,
       modelKwargs,
       configuration: { baseURL: this.config.baseURL },
     })
+    const cache = normalizeContextCache(params.contextCache)
+    if (cache) modelInstance[CONTEXT_CACHE] = cache
+    return modelInstance
   }
 
   /**
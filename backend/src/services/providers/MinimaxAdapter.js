@@ -1,12 +1,160 @@
 /**
  * MiniMax Provider Adapter
- * Handles MiniMax AI models (OpenAI-compatible)
+ * Handles MiniMax AI models (OpenAI-compatible), with their quirks:
+ * - with reasoning_split, thinking arrives in reasoning_details, and streamed chunks repeat the
+ *   whole reasoning so far instead of sending the new part
+ * - interleaved thinking: M2 models expect a tool-calling turn's thinking back in the history,
+ *   which is sent as <think> text ahead of the turn's content
+ * - tool calls left in the text in M2's native XML format (<minimax:tool_call>) are parsed into
+ *   regular tool calls
+ * Spoken answers (audio output) are synthesized separately, see minimaxSpeech.js.
  */
 
+import { randomUUID } from 'node:crypto'
 import { ChatOpenAI } from '@langchain/openai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { convertOpenAICompatMessages } from './openaiCompatMessages.js'
 import { getProviderConfig } from './providerConfig.js'
 
+const TOOL_CALL_OPEN = '<minimax:tool_call>'
+const TOOL_CALL_CLOSE = '</minimax:tool_call>'
+const TOOL_CALL_BLOCK_PATTERN = /<minimax:tool_call>([\s\S]*?)<\/minimax:tool_call>/g
+const INVOKE_PATTERN = /<invoke name="([^"]+)">([\s\S]*?)<\/invoke>/g
+const PARAMETER_PATTERN = /<parameter name="([^"]+)">([\s\S]*?)<\/parameter>/g
+
+const getRawChoice = messageChunk => messageChunk?.additional_kwargs?.__raw_response?.choices?.[0]
+
+const readReasoningDetails = details =>
+  Array.isArray(details) ? details.map(detail => detail?.text || '').join('') : ''
+
+// Parameter values are text; JSON values (numbers, booleans, arrays, objects) are decoded
+const readParameter = value => {
+  const text = value.trim()
+  try {
+    return JSON.parse(text)
+  } catch {
+    return text
+  }
+}
+
+const readParameters = body =>
+  Object.fromEntries(
+    [...body.matchAll(PARAMETER_PATTERN)].map(([, key, value]) => [key, readParameter(value)]),
+  )
+
+/**
+ * Tool calls of the <invoke> elements inside a <minimax:tool_call> block
+ * @param {string} markup - Block content
+ * @param {number} [offset] - Index of the first call
+ * @returns {Array<{ index, id, type, function: { name, arguments } }>}
+ */
+export const parseMinimaxToolCalls = (markup, offset = 0) =>
+  [...markup.matchAll(INVOKE_PATTERN)].map(([, name, body], position) => ({
+    index: offset + position,
+    id: `call_${randomUUID()}`,
+    type: 'function',
+    function: { name, arguments: JSON.stringify(readParameters(body)) },
+  }))
+
+/**
+ * Text with its <minimax:tool_call> blocks removed, and the tool calls they held
+ */
+const splitToolCallMarkup = text => {
+  const toolCalls = []
+  const content = text.replace(TOOL_CALL_BLOCK_PATTERN, (_, markup) => {
+    toolCalls.push(...parseMinimaxToolCalls(markup, toolCalls.length))
+    return ''
+  })
+  return { content, toolCalls }
+}
+
+// Length of the longest end of `text` that could be the start of `tag`
+const partialTagLength = (text, tag) => {
+  for (let length = Math.min(tag.length - 1, text.length); length > 0; length -= 1) {
+    if (text.endsWith(tag.slice(0, length))) return length
+  }
+  return 0
+}
+
+/**
+ * Streamed text with tool-call blocks held back until they close
+ * push() returns the text that can be shown; parsed calls collect in toolCalls
+ */
+class ToolCallMarkupFilter {
+  constructor() {
+    this.pending = ''
+    this.inBlock = false
+    this.toolCalls = []
+  }
+
+  push(text) {
+    this.pending += text
+    let visible = ''
+    while (this.pending) {
+      if (this.inBlock) {
+        const end = this.pending.indexOf(TOOL_CALL_CLOSE)
+        if (end === -1) break
+        this.toolCalls.push(
+          ...parseMinimaxToolCalls(this.pending.slice(0, end), this.toolCalls.length),
+        )
+        this.pending = this.pending.slice(end + TOOL_CALL_CLOSE.length)
+        this.inBlock = false
+        continue
+      }
+      const start = this.pending.indexOf(TOOL_CALL_OPEN)
+      if (start === -1) {
+        const keep = partialTagLength(this.pending, TOOL_CALL_OPEN)
+        visible += this.pending.slice(0, this.pending.length - keep)
+        this.pending = this.pending.slice(this.pending.length - keep)
+        break
+      }
+      visible += this.pending.slice(0, start)
+      this.pending = this.pending.slice(start + TOOL_CALL_OPEN.length)
+      this.inBlock = true
+    }
+    return visible
+  }
+
+  // Text still held back at the end of the stream (an unfinished block is shown as it is)
+  flush() {
+    const rest = this.inBlock ? `${TOOL_CALL_OPEN}${this.pending}` : this.pending
+    this.pending = ''
+    this.inBlock = false
+    return rest
+  }
+}
+
+/**
+ * Copy of a streamed chunk with new text and reasoning, kept consistent in the raw delta that
+ * streamChat falls back to
+ */
+const rewriteChunk = (messageChunk, { content, reasoning }) => {
+  const { __raw_response: raw, ...kwargs } = messageChunk.additional_kwargs || {}
+  const choice = raw?.choices?.[0]
+  const delta = { ...choice?.delta, content, reasoning_details: undefined }
+  return {
+    ...messageChunk,
+    content,
+    additional_kwargs: {
+      ...kwargs,
+      ...(reasoning ? { reasoning_content: reasoning } : {}),
+      ...(raw
+        ? { __raw_response: { ...raw, choices: [{ ...choice, delta }, ...raw.choices.slice(1)] } }
+        : {}),
+    },
+  }
+}
+
+// Chunk announcing tool calls parsed from the text, in the shape of a streamed tool_calls delta
+const toolCallsChunk = toolCalls => ({
+  content: '',
+  additional_kwargs: {
+    __raw_response: {
+      choices: [{ index: 0, delta: { tool_calls: toolCalls }, finish_reason: 'tool_calls' }],
+    },
+  },
+})
+
 export class MinimaxAdapter extends BaseProviderAdapter {
   constructor() {
     super('minimax')
@@ -20,6 +168,25 @@ export class MinimaxAdapter extends BaseProviderAdapter {
     return getProviderConfig('minimax')
   }
 
+  /**
+   * Strict OpenAI-compatible message shape (see openaiCompatMessages)
+   * @override
+   */
+  prepareMessages(messages) {
+    return convertOpenAICompatMessages(
+      messages.map(message => {
+        if (message?.role !== 'assistant' || !message.reasoning_content) return message
+        const { reasoning_content: reasoning, ...rest } = message
+        const content = typeof rest.content === 'string' ? rest.content : ''
+        return { ...rest, content: `<think>\n${reasoning}\n</think>\n\n${content}`.trim() }
+      }),
+    )
+  }
+
+  get knownModels() {
+    return ['MiniMax-M2.1', 'MiniMax-M2', 'MiniMax-M1', 'MiniMax-Text-01']
+  }
+
   /**
    * Build MiniMax model instance
    */
@@ -65,11 +232,93 @@ export class MinimaxAdapter extends BaseProviderAdapter {
       temperature,
       streaming,
       __includeRawResponse: true,
+      // Transient failures are retried (with retrying events) by providers/retry.js
+      maxRetries: 0,
       modelKwargs,
       configuration: { baseURL: this.config.baseURL },
     })
   }
 
+  /**
+   * Thinking of a streamed chunk (reasoning_content, set from reasoning_details by
+   * createStreamIterator) or of a whole response (reasoning_details of the message)
+   * @override
+   */
+  extractThinkingContent(messageChunk) {
+    return (
+      super.extractThinkingContent(messageChunk) ||
+      readReasoningDetails(getRawChoice(messageChunk)?.message?.reasoning_details) ||
+      null
+    )
+  }
+
+  /**
+   * Stream with reasoning turned into increments and XML tool calls parsed out of the text
+   * @override
+   */
+  async createStreamIterator(modelInstance, messages, signal) {
+    const stream = await super.createStreamIterator(modelInstance, messages, signal)
+    return (async function* () {
+      let reasoningSoFar = ''
+      const filter = new ToolCallMarkupFilter()
+      for await (const chunk of stream) {
+        const messageChunk = chunk?.message ?? chunk
+        const delta = getRawChoice(messageChunk)?.delta
+        // Each chunk carries the reasoning so far; chunks that do not extend it are new text
+        const details = readReasoningDetails(delta?.reasoning_details)
+        const reasoning = details.startsWith(reasoningSoFar)
+          ? details.slice(reasoningSoFar.length)
+          : details
+        reasoningSoFar = details.startsWith(reasoningSoFar) ? details : reasoningSoFar + details
+
+        const text =
+          typeof messageChunk?.content === 'string' && messageChunk.content
+            ? messageChunk.content
+            : typeof delta?.content === 'string'
+              ? delta.content
+              : ''
+        const content = filter.push(text)
+        yield content === text && !details
+          ? chunk
+          : rewriteChunk(messageChunk, { content, reasoning })
+      }
+      const rest = filter.flush()
+      if (rest) yield { content: rest, additional_kwargs: {} }
+      if (filter.toolCalls.length) yield toolCallsChunk(filter.toolCalls)
+    })()
+  }
+
+  /**
+   * Answer text without tool-call markup
+   * @override
+   */
+  getResponseContent(response) {
+    const content = super.getResponseContent(response)
+    return typeof content === 'string' ? splitToolCallMarkup(content).content : content
+  }
+
+  /**
+   * Tool calls, or the ones written into the text as XML
+   * @override
+   */
+  parseToolCalls(response) {
+    const toolCalls = super.parseToolCalls(response)
+    if (toolCalls?.length) return toolCalls
+    const content = super.getResponseContent(response)
+    const parsed = typeof content === 'string' ? splitToolCallMarkup(content).toolCalls : []
+    return parsed.length ? parsed : toolCalls
+  }
+
+  /**
+   * XML tool calls end the turn like regular ones
+   * @override
+   */
+  getFinishReason(response) {
+    const finishReason = super.getFinishReason(response)
+    if (finishReason === 'tool_calls') return finishReason
+    return this.parseToolCalls(response)?.length ? 'tool_calls' : finishReason
+  }
+
   /**
    * Execute request with streaming support
    * MiniMax supports streaming tool calls (OpenAI-compatible)
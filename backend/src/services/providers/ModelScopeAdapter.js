@@ -5,6 +5,7 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { convertOpenAICompatMessages } from './openaiCompatMessages.js'
 import { getProviderConfig } from './providerConfig.js'
 
 export class ModelScopeAdapter extends BaseProviderAdapter {
@@ -20,6 +21,14 @@ export class ModelScopeAdapter extends BaseProviderAdapter {
     return getProviderConfig('modelscope')
   }
 
+  /**
+   * Strict OpenAI-compatible message shape (see openaiCompatMessages)
+   * @override
+   */
+  prepareMessages(messages) {
+    return convertOpenAICompatMessages(messages)
+  }
+
   /**
    * Build ModelScope model instance
    */
@@ -77,6 +86,8 @@ export class ModelScopeAdapter extends BaseProviderAdapter {
       temperature,
       streaming,
       __includeRawResponse: true,
+      // Transient failures are retried (with retrying events) by providers/retry.js
+      maxRetries: 0,
       modelKwargs,
       configuration: { baseURL: this.config.baseURL },
     })
@@ -5,6 +5,7 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { convertOpenAICompatMessages } from './openaiCompatMessages.js'
 import { getProviderConfig } from './providerConfig.js'
 
 export class NvidiaNimAdapter extends BaseProviderAdapter {
@@ -20,6 +21,14 @@ export class NvidiaNimAdapter extends BaseProviderAdapter {
     return getProviderConfig('nvidia')
   }
 
+  /**
+   * Strict OpenAI-compatible message shape (see openaiCompatMessages)
+   * @override
+   */
+  prepareMessages(messages) {
+    return convertOpenAICompatMessages(messages)
+  }
+
   /**
    * Build NVIDIA NIM model instance
    */
@@ -68,6 +77,8 @@ export class NvidiaNimAdapter extends BaseProviderAdapter {
       temperature,
       streaming,
       __includeRawResponse: true,
+      // Transient failures are retried (with retrying events) by providers/retry.js
+      maxRetries: 0,
       modelKwargs,
       // chat_template_kwargs,
       configuration: { baseURL: resolvedBase },
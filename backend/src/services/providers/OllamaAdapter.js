@@ -0,0 +1,163 @@
+/**
+ * Ollama Provider Adapter
+ * Handles local models served by Ollama; no API key required.
+ * Uses the native /api/chat endpoint by default, or the OpenAI-compatible API when the base URL
+ * ends in /v1 (e.g. http://localhost:11434/v1).
+ */
+
+import { ChatOpenAI } from '@langchain/openai'
+import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { OllamaChatModel } from './OllamaChatModel.js'
+import { getProviderConfig } from './providerConfig.js'
+
+const VISION_MODEL_PATTERN = /llava|vision|-vl|bakllava|moondream|gemma3|minicpm-v|llama4/i
+
+const resolveBaseUrl = baseUrl =>
+  String(baseUrl || process.env.OLLAMA_BASE_URL || getProviderConfig('ollama').baseURL).replace(
+    /\/+$/,
+    '',
+  )
+
+const isOpenAICompatible = baseUrl => /\/v1$/.test(baseUrl)
+
+// Ollama's think flag is a plain boolean
+const resolveThink = thinking => {
+  if (thinking === undefined || thinking === null) return undefined
+  if (typeof thinking === 'boolean') return thinking
+  return thinking.type !== 'disabled'
+}
+
+export class OllamaAdapter extends BaseProviderAdapter {
+  constructor() {
+    super('ollama')
+  }
+
+  get capabilities() {
+    return getProviderConfig('ollama').capabilities
+  }
+
+  get config() {
+    return getProviderConfig('ollama')
+  }
+
+  /**
+   * List locally pulled models (GET /api/tags)
+   * @override
+   */
+  async listModels({ baseUrl, signal } = {}) {
+    const host = resolveBaseUrl(baseUrl).replace(/\/v1$/, '')
+    const timeoutSignal = AbortSignal.timeout(10000)
+    const response = await fetch(`${host}/api/tags`, {
+      signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+    })
+    if (!response.ok) {
+      throw Object.assign(new Error(`Failed to list ollama models: HTTP ${response.status}`), {
+        status: response.status,
+        headers: response.headers,
+      })
+    }
+    const data = await response.json()
+    return (data.models || [])
+      .filter(item => !/embed/i.test(item.name))
+      .map(item => ({
+        ...this.toModelDescriptor({ id: item.name }),
+        name: item.details?.parameter_size
+          ? `${item.name} (${item.details.parameter_size})`
+          : item.name,
+        supports_vision:
+          VISION_MODEL_PATTERN.test(item.name) || Boolean(item.details?.families?.includes('clip')),
+      }))
+  }
+
+  /**
+   * Build Ollama model instance (native or OpenAI-compatible by base URL)
+   */
+  buildModel(params) {
+    const {
+      apiKey,
+      baseUrl,
+      model,
+      temperature,
+      top_k,
+      top_p,
+      frequency_penalty,
+      presence_penalty,
+      tools,
+      toolChoice,
+      responseFormat,
+      thinking,
+      streaming,
+    } = params
+
+    const resolvedBase = resolveBaseUrl(baseUrl)
+    const modelName = model || this.config.defaultModel
+
+    if (isOpenAICompatible(resolvedBase)) {
+      const modelKwargs = {}
+      if (tools && tools.length > 0) modelKwargs.tools = tools
+      if (toolChoice) modelKwargs.tool_choice = toolChoice
+      if (responseFormat) modelKwargs.response_format = responseFormat
+      if (top_p !== undefined) modelKwargs.top_p = top_p
+      if (frequency_penalty !== undefined) modelKwargs.frequency_penalty = frequency_penalty
+      if (presence_penalty !== undefined) modelKwargs.presence_penalty = presence_penalty
+      return new ChatOpenAI({
+        // Ollama ignores the key, but the OpenAI client requires one
+        apiKey: apiKey || 'ollama',
+        modelName,
+        temperature,
+        streaming,
+        __includeRawResponse: true,
+        modelKwargs,
+        configuration: { baseURL: resolvedBase },
+      })
+    }
+
+    return new OllamaChatModel({
+      baseUrl: resolvedBase,
+      model: modelName,
+      temperature,
+      topK: top_k,
+      topP: top_p,
+      frequencyPenalty: frequency_penalty,
+      presencePenalty: presence_penalty,
+      tools,
+      responseFormat,
+      thinking: resolveThink(thinking),
+      streaming,
+    })
+  }
+
+  /**
+   * Execute request with streaming support
+   */
+  async execute(messages, params) {
+    const { tools, stream } = params
+
+    const modelInstance = this.buildModel({
+      ...params,
+      tools,
+      streaming: stream,
+    })
+
+    if (stream) {
+      return {
+        type: 'stream',
+        modelInstance,
+        messages,
+      }
+    }
+
+    return this.executeNonStreamingForToolCalls(messages, params)
+  }
+
+  /**
+   * Native mode converts plain messages itself; OpenAI-compatible mode uses LangChain messages
+   * @override
+   */
+  async createStreamIterator(modelInstance, messages, signal) {
+    if (modelInstance instanceof OllamaChatModel) {
+      return modelInstance.stream(messages, signal ? { signal } : undefined)
+    }
+    return super.createStreamIterator(modelInstance, messages, signal)
+  }
+}
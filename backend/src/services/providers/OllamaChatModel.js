@@ -0,0 +1,221 @@
+/**
+ * Ollama native chat model
+ * invoke()/stream() against Ollama's /api/chat (NDJSON streaming, no API key). Like
+ * AnthropicChatModel, responses mirror the OpenAI shape under additional_kwargs.__raw_response
+ * so the shared tool loops can consume them; thinking is exposed as reasoning_content.
+ */
+
+import { readLineBatches } from '../../utils/lineReader.js'
+
+const getMessageType = message => {
+  const type = message?.role || message?.type || message?._getType?.()
+  if (type === 'human') return 'user'
+  if (type === 'ai') return 'assistant'
+  return type
+}
+
+// Ollama takes plain text content plus a separate base64 images array
+const splitContent = content => {
+  if (typeof content === 'string') return { content, images: [] }
+  const parts = Array.isArray(content) ? content : []
+  const text = []
+  const images = []
+  for (const part of parts) {
+    if (typeof part === 'string') text.push(part)
+    else if (part?.type === 'text') text.push(part.text || '')
+    else if (part?.type === 'image_url') {
+      const url = String(part.image_url?.url || part.image_url || '')
+      const base64 = url.match(/^data:[^;]+;base64,(.+)$/)
+      if (base64) images.push(base64[1])
+    }
+  }
+  return { content: text.join('\n'), images }
+}
+
+const parseArguments = value => {
+  if (value && typeof value === 'object') return value
+  try {
+    return value ? JSON.parse(value) : {}
+  } catch {
+    return {}
+  }
+}
+
+/**
+ * Convert chat messages (plain or LangChain) into /api/chat messages
+ */
+export const toOllamaMessages = messages => {
+  const toolNames = new Map()
+  return (messages || []).map(message => {
+    const role = getMessageType(message)
+    const { content, images } = splitContent(message.content)
+    if (role === 'assistant') {
+      const toolCalls = message.tool_calls?.length
+        ? message.tool_calls
+        : message.additional_kwargs?.tool_calls || []
+      const converted = toolCalls.map(toolCall => {
+        const name = toolCall.function?.name || toolCall.name
+        toolNames.set(toolCall.id, name)
+        const args = parseArguments(toolCall.function?.arguments ?? toolCall.args)
+        return { function: { name, arguments: args } }
+      })
+      return { role, content, ...(converted.length ? { tool_calls: converted } : {}) }
+    }
+    if (role === 'tool') {
+      const toolName = message.name || toolNames.get(message.tool_call_id)
+      return { role, content, ...(toolName ? { tool_name: toolName } : {}) }
+    }
+    return {
+      role: role === 'system' ? 'system' : 'user',
+      content,
+      ...(images.length ? { images } : {}),
+    }
+  })
+}
+
+const toOpenAIToolCalls = (toolCalls, offset) =>
+  (toolCalls || []).map((toolCall, index) => ({
+    index: offset + index,
+    id: `call_${Date.now().toString(36)}_${offset + index}`,
+    type: 'function',
+    function: {
+      name: toolCall.function?.name,
+      arguments: JSON.stringify(toolCall.function?.arguments || {}),
+    },
+  }))
+
+const toUsageMetadata = data => {
+  const input = data?.prompt_eval_count || 0
+  const output = data?.eval_count || 0
+  return { input_tokens: input, output_tokens: output, total_tokens: input + output }
+}
+
+const buildChunk = ({ text = '', thinking = '', toolCalls, finishReason = null, usage }) => ({
+  content: text,
+  additional_kwargs: {
+    ...(thinking ? { reasoning_content: thinking } : {}),
+    __raw_response: {
+      choices: [
+        {
+          delta: { content: text || null, ...(toolCalls ? { tool_calls: toolCalls } : {}) },
+          finish_reason: finishReason,
+        },
+      ],
+    },
+  },
+  ...(usage ? { usage_metadata: usage } : {}),
+})
+
+export async function* readJsonLines(body, options) {
+  for await (const lines of readLineBatches(body, options)) {
+    for (const line of lines) {
+      if (line.trim()) yield JSON.parse(line)
+    }
+  }
+}
+
+export class OllamaChatModel {
+  constructor({
+    baseUrl,
+    model,
+    temperature,
+    topK,
+    topP,
+    frequencyPenalty,
+    presencePenalty,
+    tools,
+    responseFormat,
+    thinking,
+    streaming = false,
+  }) {
+    this.baseUrl = String(baseUrl).replace(/\/+$/, '')
+    this.model = model
+    this.options = Object.fromEntries(
+      Object.entries({
+        temperature,
+        top_k: topK,
+        top_p: topP,
+        frequency_penalty: frequencyPenalty,
+        presence_penalty: presencePenalty,
+      }).filter(([, value]) => value !== undefined),
+    )
+    this.tools = (tools || []).filter(tool => tool?.function?.name)
+    this.format = responseFormat?.type === 'json_object' ? 'json' : undefined
+    this.thinking = thinking
+    this.streaming = streaming
+  }
+
+  async request(messages, { stream, signal }) {
+    const body = {
+      model: this.model,
+      messages: toOllamaMessages(messages),
+      stream,
+      ...(Object.keys(this.options).length ? { options: this.options } : {}),
+      ...(this.tools.length ? { tools: this.tools } : {}),
+      ...(this.format ? { format: this.format } : {}),
+      ...(this.thinking !== undefined ? { think: this.thinking } : {}),
+    }
+    const response = await fetch(`${this.baseUrl}/api/chat`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(body),
+      signal,
+    })
+    if (!response.ok) {
+      const detail = await response.text().catch(() => '')
+      throw new Error(`Ollama API error: HTTP ${response.status}${detail ? ` ${detail}` : ''}`)
+    }
+    return response
+  }
+
+  async invoke(messages, options = {}) {
+    const response = await this.request(messages, { stream: false, signal: options.signal })
+    const data = await response.json()
+    const message = data.message || {}
+    const toolCalls = toOpenAIToolCalls(message.tool_calls, 0)
+    return {
+      content: message.content || '',
+      additional_kwargs: {
+        ...(message.thinking ? { reasoning_content: message.thinking } : {}),
+        ...(toolCalls.length ? { tool_calls: toolCalls } : {}),
+        __raw_response: {
+          model: data.model,
+          choices: [
+            {
+              message: { role: 'assistant', content: message.content || '', tool_calls: toolCalls },
+              finish_reason: toolCalls.length ? 'tool_calls' : data.done_reason || 'stop',
+            },
+          ],
+        },
+      },
+      usage_metadata: toUsageMetadata(data),
+    }
+  }
+
+  async stream(messages, options = {}) {
+    const response = await this.request(messages, { stream: true, signal: options.signal })
+    return this.parseStream(response.body)
+  }
+
+  async *parseStream(body) {
+    let toolCount = 0
+    for await (const data of readJsonLines(body)) {
+      if (data.error) throw new Error(`Ollama stream error: ${data.error}`)
+      const message = data.message || {}
+      // Ollama emits each tool call whole, so one chunk carries the complete arguments
+      const toolCalls = message.tool_calls?.length
+        ? toOpenAIToolCalls(message.tool_calls, toolCount)
+        : undefined
+      toolCount += toolCalls?.length || 0
+      if (message.content || message.thinking || toolCalls) {
+        yield buildChunk({ text: message.content, thinking: message.thinking, toolCalls })
+      }
+      if (data.done) {
+        yield buildChunk({
+          finishReason: toolCount ? 'tool_calls' : data.done_reason || 'stop',
+          usage: toUsageMetadata(data),
+        })
+      }
+    }
+  }
+}
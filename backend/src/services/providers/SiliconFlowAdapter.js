@@ -5,6 +5,7 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { BaseProviderAdapter } from './BaseProviderAdapter.js'
+import { convertOpenAICompatMessages } from './openaiCompatMessages.js'
 import { getProviderConfig } from './providerConfig.js'
 
 export class SiliconFlowAdapter extends BaseProviderAdapter {
@@ -20,6 +21,18 @@ export class SiliconFlowAdapter extends BaseProviderAdapter {
     return getProviderConfig('siliconflow')
   }
 
+  /**
+   * Strict OpenAI-compatible message shape (see openaiCompatMessages)
+   * @override
+   */
+  prepareMessages(messages) {
+    return convertOpenAICompatMessages(messages)
+  }
+
+  get modelListPath() {
+    return '/models?type=text&sub_type=chat'
+  }
+
   /**
    * Build SiliconFlow model instance
    */
@@ -68,6 +81,8 @@ export class SiliconFlowAdapter extends BaseProviderAdapter {
       temperature,
       streaming,
       __includeRawResponse: true,
+      // Transient failures are retried (with retrying events) by providers/retry.js
+      maxRetries: 0,
       modelKwargs,
       configuration: { baseURL: this.config.baseURL },
     })
@@ -3,6 +3,7 @@
  * Creates the appropriate adapter based on provider name
  */
 
+import { AnthropicAdapter } from './AnthropicAdapter.js'
 import { GeminiAdapter } from './GeminiAdapter.js'
 import { GLMAdapter } from './GLMAdapter.js'
 import { KimiAdapter } from './KimiAdapter.js'
@@ -10,7 +11,10 @@ import { ModelScopeAdapter } from './ModelScopeAdapter.js'
 import { OpenAIAdapter } from './OpenAIAdapter.js'
 import { SiliconFlowAdapter } from './SiliconFlowAdapter.js'
 import { NvidiaNimAdapter } from './NvidiaNimAdapter.js'
+import { OllamaAdapter } from './OllamaAdapter.js'
 import { MinimaxAdapter } from './MinimaxAdapter.js'
+import { limitModel } from '../modelRateLimit.js'
+import { traceModel } from '../modelTracing.js'
 
 // Cache adapter instances for reuse
 const adapterCache = new Map()
@@ -56,6 +60,14 @@ export function getProviderAdapter(provider) {
       // MiniMax has its own dedicated adapter
       adapter = new MinimaxAdapter()
       break
+    case 'anthropic':
+      adapter = new AnthropicAdapter()
+      break
+    case 'ollama':
+    case 'local':
+      // Local models, no API key
+      adapter = new OllamaAdapter()
+      break
     default:
       // Fallback to OpenAI adapter for unknown providers
       // (assumes OpenAI-compatible API)
@@ -63,6 +75,15 @@ export function getProviderAdapter(provider) {
       adapter = new OpenAIAdapter()
   }
 
+  // Models built during a traced request report their calls to it, and each call takes a share
+  // of the request's rate limiter
+  const buildModel = adapter.buildModel.bind(adapter)
+  adapter.buildModel = params =>
+    traceModel(limitModel(buildModel(params), { provider, apiKey: params?.apiKey }), {
+      provider,
+      model: params?.model,
+    })
+
   // Cache for future use
   adapterCache.set(provider, adapter)
   return adapter
@@ -84,5 +105,8 @@ export function isProviderSupported(provider) {
     'gemini',
     'nvidia',
     'minimax',
+    'anthropic',
+    'ollama',
+    'local',
   ].includes(provider)
 }
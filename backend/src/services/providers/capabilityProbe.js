@@ -0,0 +1,283 @@
+/**
+ * Provider capability probe
+ * Detects what a model actually supports with a few tiny requests through its provider adapter,
+ * instead of trusting the per-provider PROVIDER_CAPABILITIES table (which cannot know about
+ * OpenAI-compatible endpoints or individual models):
+ * - json_mode: accepts response_format json_object and answers with JSON
+ * - tool_calls: calls a trivial tool when asked to
+ * - vision: reads the colour of a small solid red image
+ * - reasoning_stream: streams reasoning content when thinking is enabled
+ *
+ * Results are cached per provider, base URL and model (CAPABILITY_PROBE_TTL_HOURS, default a
+ * week) in the data dir. Only conclusive answers are cached: a check that failed on the key,
+ * a rate limit or the network reports supported: null and is retried next time. Cached results
+ * annotate GET /api/models and let stream-chat reject requests the model cannot serve.
+ */
+
+import { createHash } from 'crypto'
+import { readRecord, writeRecord } from '../../utils/dataStore.js'
+import { ErrorCode, toQurioError } from '../../utils/errors.js'
+import { normalizeTextContent, toLangChainMessages } from '../serviceUtils.js'
+import { getProviderAdapter } from './adapterFactory.js'
+import { DEFAULT_MODELS, resolveProviderAlias } from './providerConfig.js'
+
+const COLLECTION = 'capability-probes'
+const DEFAULT_TTL_HOURS = 24 * 7
+const DEFAULT_TIMEOUT_MS = 30000
+
+export const CAPABILITY_CHECKS = ['json_mode', 'tool_calls', 'vision', 'reasoning_stream']
+
+// 8x8 solid red PNG
+const RED_PNG_BASE64 =
+  'iVBORw0KGgoAAAANSUhEUgAAAAgAAAAICAIAAABLbSncAAAAEUlEQVR42mP4z8CAFTEMLQkAKP8/wc53yE8AAAAASUVORK5CYII='
+const PROBE_TOOL = {
+  type: 'function',
+  function: {
+    name: 'get_probe_value',
+    description: 'Returns the probe value. Always call this when asked for the probe value.',
+    parameters: { type: 'object', properties: {}, required: [] },
+  },
+}
+// Accepted by every adapter that supports thinking (type for GLM/Ollama, budget for the rest)
+const PROBE_THINKING = { type: 'enabled', budget_tokens: 1024 }
+
+const getTtlMs = () => {
+  const hours = Number.parseFloat(process.env.CAPABILITY_PROBE_TTL_HOURS)
+  return (Number.isFinite(hours) && hours >= 0 ? hours : DEFAULT_TTL_HOURS) * 60 * 60 * 1000
+}
+
+const resolveModel = (provider, model) => model || DEFAULT_MODELS[resolveProviderAlias(provider)]
+
+// Capabilities belong to the model behind an endpoint, not to the key
+const buildRecordId = (provider, baseUrl, model) =>
+  createHash('sha256')
+    .update([resolveProviderAlias(provider), baseUrl || '', model || ''].join('|'))
+    .digest('hex')
+    .slice(0, 32)
+
+const readProbeRecord = (provider, baseUrl, model) => {
+  try {
+    const record = readRecord(COLLECTION, buildRecordId(provider, baseUrl, model))
+    if (!record || Date.now() - Date.parse(record.probed_at) > getTtlMs()) return null
+    return record
+  } catch (error) {
+    console.warn('[CapabilityProbe] Failed to read cached probe:', error.message)
+    return null
+  }
+}
+
+const invoke = (adapter, params, messages, signal) =>
+  adapter
+    .buildModel({ ...params, streaming: false })
+    .invoke(toLangChainMessages(adapter.prepareMessages(messages)), { signal })
+
+const responseText = (adapter, response) =>
+  normalizeTextContent(adapter.getResponseContent(response)).trim()
+
+const CHECKS = {
+  json_mode: async ({ adapter, params, signal }) => {
+    const response = await invoke(
+      adapter,
+      { ...params, responseFormat: { type: 'json_object' } },
+      [{ role: 'user', content: 'Reply with the JSON object {"ok": true} and nothing else.' }],
+      signal,
+    )
+    const text = responseText(adapter, response).replace(/^```(?:json)?\s*|\s*```$/g, '')
+    try {
+      return JSON.parse(text)?.ok === true
+    } catch {
+      return false
+    }
+  },
+
+  tool_calls: async ({ adapter, params, signal }) => {
+    const response = await invoke(
+      adapter,
+      { ...params, tools: [PROBE_TOOL] },
+      [{ role: 'user', content: 'What is the probe value? Use the tool.' }],
+      signal,
+    )
+    const toolCalls = adapter.parseToolCalls(response)
+    return Array.isArray(toolCalls) && toolCalls.length > 0
+  },
+
+  vision: async ({ adapter, params, signal }) => {
+    const response = await invoke(
+      adapter,
+      params,
+      [
+        {
+          role: 'user',
+          content: [
+            { type: 'text', text: 'What colour is this image? Answer with one word.' },
+            { type: 'image_url', image_url: { url: `data:image/png;base64,${RED_PNG_BASE64}` } },
+          ],
+        },
+      ],
+      signal,
+    )
+    return /red/i.test(responseText(adapter, response))
+  },
+
+  reasoning_stream: async ({ adapter, params, signal }) => {
+    const modelInstance = adapter.buildModel({
+      ...params,
+      thinking: PROBE_THINKING,
+      streaming: true,
+    })
+    const stream = await adapter.createStreamIterator(
+      modelInstance,
+      [{ role: 'user', content: 'What is 17 * 23? Answer with the number only.' }],
+      signal,
+    )
+    for await (const chunk of stream) {
+      const messageChunk = chunk?.message ?? chunk
+      if (adapter.extractThinkingContent(messageChunk)) return true
+      if (/<think>/i.test(normalizeTextContent(messageChunk?.content))) return true
+    }
+    return false
+  },
+}
+
+/**
+ * A failure that says nothing about the model (bad key, rate limit, network, timeout)
+ */
+const isInconclusive = error => {
+  if (error?.name === 'TimeoutError' || error?.name === 'AbortError') return true
+  const { code } = toQurioError(error)
+  return code === ErrorCode.ProviderAuth || code === ErrorCode.ProviderRateLimit
+}
+
+const runCheck = async (name, context) => {
+  const startedAt = Date.now()
+  try {
+    const supported = await CHECKS[name](context)
+    return { supported, latency_ms: Date.now() - startedAt }
+  } catch (error) {
+    // Anything else (HTTP 400 for an unknown parameter, an image the model refuses) means no
+    return {
+      supported: isInconclusive(error) ? null : false,
+      latency_ms: Date.now() - startedAt,
+      error: toQurioError(error).message,
+    }
+  }
+}
+
+/**
+ * Probe a model's capabilities, answering from the cache when every check is known
+ * @param {Object} args
+ * @param {string} args.provider
+ * @param {string} [args.apiKey]
+ * @param {string} [args.baseUrl]
+ * @param {string} [args.model] - Default: the provider's default model
+ * @param {string[]} [args.checks] - Subset of CAPABILITY_CHECKS (default all)
+ * @param {boolean} [args.refresh] - Ignore the cache
+ * @param {number} [args.timeoutMs] - Per check (default 30s)
+ * @param {AbortSignal} [args.signal]
+ * @returns {Promise<Object>} { provider, model, capabilities: { [check]: { supported, latency_ms,
+ *   error } }, cached, probed_at }
+ */
+export const probeCapabilities = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  checks = CAPABILITY_CHECKS,
+  refresh = false,
+  timeoutMs = DEFAULT_TIMEOUT_MS,
+  signal,
+}) => {
+  const resolvedModel = resolveModel(provider, model)
+  const cachedRecord = refresh ? null : readProbeRecord(provider, baseUrl, resolvedModel)
+  const cachedCapabilities = cachedRecord?.capabilities || {}
+  const pending = checks.filter(name => typeof cachedCapabilities[name]?.supported !== 'boolean')
+  if (cachedRecord && !pending.length) {
+    return {
+      provider,
+      model: resolvedModel,
+      capabilities: Object.fromEntries(checks.map(name => [name, cachedCapabilities[name]])),
+      cached: true,
+      probed_at: cachedRecord.probed_at,
+    }
+  }
+
+  const adapter = getProviderAdapter(provider)
+  const params = { apiKey, baseUrl, model: resolvedModel, temperature: 0 }
+  const results = await Promise.all(
+    pending.map(async name => {
+      const timeoutSignal = AbortSignal.timeout(timeoutMs)
+      const checkSignal = signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal
+      return [name, await runCheck(name, { adapter, params, signal: checkSignal })]
+    }),
+  )
+
+  const capabilities = { ...cachedCapabilities, ...Object.fromEntries(results) }
+  const probedAt = new Date().toISOString()
+  const conclusive = Object.fromEntries(
+    Object.entries(capabilities).filter(([, result]) => typeof result.supported === 'boolean'),
+  )
+  if (Object.keys(conclusive).length) {
+    writeRecord(COLLECTION, buildRecordId(provider, baseUrl, resolvedModel), {
+      provider: resolveProviderAlias(provider),
+      base_url: baseUrl || null,
+      model: resolvedModel,
+      capabilities: conclusive,
+      probed_at: probedAt,
+    })
+  }
+  return {
+    provider,
+    model: resolvedModel,
+    capabilities: Object.fromEntries(checks.map(name => [name, capabilities[name]])),
+    cached: false,
+    probed_at: probedAt,
+  }
+}
+
+/**
+ * Cached probe results for a model: { [check]: boolean } for the checks that were conclusive, or
+ * null when the model was never probed (callers then fall back to PROVIDER_CAPABILITIES)
+ */
+export const getProbedCapabilities = ({ provider, baseUrl, model }) => {
+  const record = readProbeRecord(provider, baseUrl, resolveModel(provider, model))
+  if (!record) return null
+  return Object.fromEntries(
+    Object.entries(record.capabilities || {}).map(([name, result]) => [name, result.supported]),
+  )
+}
+
+const hasImageParts = messages =>
+  (Array.isArray(messages) ? messages : []).some(
+    message =>
+      Array.isArray(message?.content) &&
+      message.content.some(part => part?.type === 'image_url' || part?.type === 'image'),
+  )
+
+/**
+ * Problems with a chat request given what the model was probed to support; empty when fine or
+ * when the model was never probed
+ * @returns {string[]}
+ */
+export const validateProbedCapabilities = ({
+  provider,
+  baseUrl,
+  model,
+  messages,
+  tools,
+  responseFormat,
+}) => {
+  const probed = getProbedCapabilities({ provider, baseUrl, model })
+  if (!probed) return []
+  const name = resolveModel(provider, model)
+  const errors = []
+  if (probed.vision === false && hasImageParts(messages)) {
+    errors.push(`${name} does not accept images`)
+  }
+  if (probed.tool_calls === false && Array.isArray(tools) && tools.length) {
+    errors.push(`${name} does not support tool calls`)
+  }
+  if (probed.json_mode === false && responseFormat?.type === 'json_object') {
+    errors.push(`${name} does not support JSON mode`)
+  }
+  return errors
+}
@@ -0,0 +1,120 @@
+/**
+ * Base Embedding Provider
+ * Splits input into provider-sized batches, retries transient failures, and returns unit-length
+ * float32 vectors so similarity scores are comparable across providers.
+ */
+
+import { callWithRetry } from '../retry.js'
+
+const DEFAULT_RETRY_POLICY = { maxRetries: 3, baseDelayMs: 1000 }
+const REQUEST_TIMEOUT_MS = 60000
+
+/**
+ * Scale a vector to unit length, rounded to float32 precision
+ */
+export const normalizeVector = vector => {
+  const values = Array.from(vector, Number)
+  const norm = Math.sqrt(values.reduce((sum, value) => sum + value * value, 0))
+  return values.map(value => Math.fround(norm ? value / norm : 0))
+}
+
+/**
+ * Error carrying the HTTP status and headers of a failed embedding request, so the retry layer
+ * and utils/errors can classify it
+ */
+export const toHttpError = async (response, provider) => {
+  const body = await response.text().catch(() => '')
+  let message = body
+  try {
+    const data = JSON.parse(body)
+    message = data?.error?.message || data?.message || body
+  } catch {
+    // Plain-text error body
+  }
+  return Object.assign(
+    new Error(`${provider} embeddings failed (HTTP ${response.status}): ${message}`.trim()),
+    { status: response.status, headers: response.headers },
+  )
+}
+
+export class EmbeddingProvider {
+  constructor(name) {
+    this.name = name
+  }
+
+  get defaultModel() {
+    throw new Error(`${this.name}: defaultModel not implemented`)
+  }
+
+  get defaultBaseUrl() {
+    return ''
+  }
+
+  // Largest number of inputs accepted by one request
+  get maxBatchSize() {
+    return 64
+  }
+
+  get retryPolicy() {
+    return DEFAULT_RETRY_POLICY
+  }
+
+  /**
+   * Embed one batch
+   * @returns {Promise<{ vectors: number[][], usage?: { prompt_tokens, total_tokens } }>}
+   * @abstract
+   */
+  async embedBatch(_texts, _options) {
+    throw new Error(`${this.name}: embedBatch not implemented`)
+  }
+
+  /**
+   * Embed texts in batches
+   * @param {string[]} texts
+   * @param {Object} options
+   * @param {string} options.apiKey
+   * @param {string} [options.baseUrl]
+   * @param {string} [options.model]
+   * @param {number} [options.dimensions] Output size, for models that support shortening
+   * @param {number} [options.batchSize]
+   * @param {AbortSignal} [options.signal]
+   * @returns {Promise<{ provider, model, dimensions, embeddings: number[][], usage }>}
+   */
+  async embed(texts, options = {}) {
+    const model = options.model || this.defaultModel
+    const baseUrl = String(options.baseUrl || this.defaultBaseUrl).replace(/\/$/, '')
+    const batchSize = Math.min(options.batchSize || this.maxBatchSize, this.maxBatchSize)
+    const embeddings = []
+    const usage = { prompt_tokens: 0, total_tokens: 0 }
+
+    for (let offset = 0; offset < texts.length; offset += batchSize) {
+      const batch = texts.slice(offset, offset + batchSize)
+      const result = await callWithRetry(
+        () =>
+          this.embedBatch(batch, {
+            ...options,
+            model,
+            baseUrl,
+            signal: options.signal
+              ? AbortSignal.any([options.signal, AbortSignal.timeout(REQUEST_TIMEOUT_MS)])
+              : AbortSignal.timeout(REQUEST_TIMEOUT_MS),
+          }),
+        { provider: this.name, policy: this.retryPolicy, signal: options.signal },
+      )
+      if (result.vectors.length !== batch.length) {
+        throw new Error(`${this.name} embeddings did not return one vector per input`)
+      }
+      result.vectors.forEach(vector => embeddings.push(normalizeVector(vector)))
+      usage.prompt_tokens += result.usage?.prompt_tokens || 0
+      usage.total_tokens += result.usage?.total_tokens || 0
+    }
+
+    return {
+      provider: this.name,
+      model,
+      dimensions: embeddings[0]?.length || 0,
+      embeddings,
+      usage,
+    }
+  }
+}
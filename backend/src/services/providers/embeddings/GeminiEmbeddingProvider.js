@@ -0,0 +1,47 @@
+/**
+ * Gemini Embedding Provider
+ * POST models/{model}:batchEmbedContents on the Generative Language API
+ */
+
+import { EmbeddingProvider, toHttpError } from './EmbeddingProvider.js'
+
+const GEMINI_BASE_URL = 'https://generativelanguage.googleapis.com/v1beta'
+
+export class GeminiEmbeddingProvider extends EmbeddingProvider {
+  constructor() {
+    super('gemini')
+  }
+
+  get defaultModel() {
+    return 'text-embedding-004'
+  }
+
+  get defaultBaseUrl() {
+    return GEMINI_BASE_URL
+  }
+
+  get maxBatchSize() {
+    return 100
+  }
+
+  async embedBatch(texts, { apiKey, baseUrl, model, dimensions, signal }) {
+    const modelName = model.startsWith('models/') ? model : `models/${model}`
+    const response = await fetch(`${baseUrl}/${modelName}:batchEmbedContents`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', 'x-goog-api-key': apiKey },
+      body: JSON.stringify({
+        requests: texts.map(text => ({
+          model: modelName,
+          content: { parts: [{ text }] },
+          ...(dimensions ? { outputDimensionality: dimensions } : {}),
+        })),
+      }),
+      signal,
+    })
+    if (!response.ok) throw await toHttpError(response, this.name)
+    const data = await response.json()
+    return {
+      vectors: (data?.embeddings || []).map(item => item.values),
+    }
+  }
+}
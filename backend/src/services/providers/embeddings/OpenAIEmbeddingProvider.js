@@ -0,0 +1,51 @@
+/**
+ * OpenAI Embedding Provider
+ * POST {baseUrl}/embeddings; also used for OpenAI-compatible endpoints
+ */
+
+import { PROVIDER_BASE_URLS } from '../providerConfig.js'
+import { EmbeddingProvider, toHttpError } from './EmbeddingProvider.js'
+
+export class OpenAIEmbeddingProvider extends EmbeddingProvider {
+  constructor(name = 'openai') {
+    super(name)
+  }
+
+  get defaultModel() {
+    return 'text-embedding-3-small'
+  }
+
+  get defaultBaseUrl() {
+    return PROVIDER_BASE_URLS.openai
+  }
+
+  get maxBatchSize() {
+    return 256
+  }
+
+  async embedBatch(texts, { apiKey, baseUrl, model, dimensions, signal }) {
+    const response = await fetch(`${baseUrl}/embeddings`, {
+      method: 'POST',
+      headers: {
+        Authorization: `Bearer ${apiKey}`,
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify({
+        model,
+        input: texts,
+        encoding_format: 'float',
+        ...(dimensions ? { dimensions } : {}),
+      }),
+      signal,
+    })
+    if (!response.ok) throw await toHttpError(response, this.name)
+    const data = await response.json()
+    const items = (Array.isArray(data?.data) ? data.data : [])
+      .slice()
+      .sort((a, b) => (a.index ?? 0) - (b.index ?? 0))
+    return {
+      vectors: items.map(item => item.embedding),
+      usage: data?.usage,
+    }
+  }
+}
@@ -0,0 +1,30 @@
+/**
+ * SiliconFlow Embedding Provider
+ * OpenAI-compatible /embeddings with smaller batches and the shared SiliconFlow retry policy
+ */
+
+import { PROVIDER_BASE_URLS } from '../providerConfig.js'
+import { getRetryPolicy } from '../retry.js'
+import { OpenAIEmbeddingProvider } from './OpenAIEmbeddingProvider.js'
+
+export class SiliconFlowEmbeddingProvider extends OpenAIEmbeddingProvider {
+  constructor() {
+    super('siliconflow')
+  }
+
+  get defaultModel() {
+    return 'BAAI/bge-m3'
+  }
+
+  get defaultBaseUrl() {
+    return PROVIDER_BASE_URLS.siliconflow
+  }
+
+  get maxBatchSize() {
+    return 32
+  }
+
+  get retryPolicy() {
+    return getRetryPolicy('siliconflow')
+  }
+}
@@ -0,0 +1,59 @@
+/**
+ * Embedding providers
+ * Provider-backed vector generation for retrieval features (see POST /api/embeddings)
+ */
+
+import { GeminiEmbeddingProvider } from './GeminiEmbeddingProvider.js'
+import { OpenAIEmbeddingProvider } from './OpenAIEmbeddingProvider.js'
+import { SiliconFlowEmbeddingProvider } from './SiliconFlowEmbeddingProvider.js'
+
+export const EMBEDDING_PROVIDERS = ['openai', 'openai_compatibility', 'gemini', 'siliconflow']
+const MAX_INPUTS = 2048
+
+const providerCache = new Map()
+
+/**
+ * Get embedding provider instance
+ * @param {string} provider - Provider name
+ * @returns {EmbeddingProvider}
+ */
+export function getEmbeddingProvider(provider) {
+  if (providerCache.has(provider)) return providerCache.get(provider)
+
+  let instance
+  switch (provider) {
+    case 'openai':
+      instance = new OpenAIEmbeddingProvider()
+      break
+    case 'openai_compatibility':
+      instance = new OpenAIEmbeddingProvider('openai_compatibility')
+      break
+    case 'gemini':
+      instance = new GeminiEmbeddingProvider()
+      break
+    case 'siliconflow':
+      instance = new SiliconFlowEmbeddingProvider()
+      break
+    default:
+      throw new Error(
+        `Unsupported embedding provider: ${provider}. Supported: ${EMBEDDING_PROVIDERS.join(', ')}`,
+      )
+  }
+
+  providerCache.set(provider, instance)
+  return instance
+}
+
+/**
+ * Embed one or more texts
+ * @param {Object} params
+ * @param {string} params.provider
+ * @param {string|string[]} params.input
+ * @returns {Promise<{ provider, model, dimensions, embeddings: number[][], usage }>}
+ */
+export const createEmbeddings = async ({ provider, input, ...options }) => {
+  const texts = (Array.isArray(input) ? input : [input]).map(text => String(text ?? ''))
+  if (!texts.length) throw new Error('input must contain at least one text')
+  if (texts.length > MAX_INPUTS) throw new Error(`input is limited to ${MAX_INPUTS} texts`)
+  return getEmbeddingProvider(provider).embed(texts, options)
+}
@@ -0,0 +1,141 @@
+/**
+ * Provider health probe
+ * Checks a provider key with one cheap request and reports a status the settings UI can show as
+ * a badge: ok, invalid_key, rate_limited, network_error, or error (any other upstream failure,
+ * e.g. an unknown model or a 5xx).
+ *
+ * The default "models" probe lists models (no tokens spent); the "completion" probe sends a
+ * 1-token completion, which also proves the model answers.
+ */
+
+import { ErrorCode, toQurioError } from '../../utils/errors.js'
+import { completeText } from '../modelCompletion.js'
+import { pingModel } from '../warmupService.js'
+import { getProviderAdapter } from './adapterFactory.js'
+import { DEFAULT_MODELS, resolveProviderAlias, usesNativeApi } from './providerConfig.js'
+
+export const HEALTH_PROBES = ['models', 'completion']
+
+const DEFAULT_TIMEOUT_MS = 10000
+const MAX_TIMEOUT_MS = 60000
+
+export const resolveProbeTimeout = value => {
+  const timeoutMs = Number.parseInt(value, 10)
+  return Number.isFinite(timeoutMs) && timeoutMs > 0
+    ? Math.min(timeoutMs, MAX_TIMEOUT_MS)
+    : DEFAULT_TIMEOUT_MS
+}
+
+const statusFromHttp = httpStatus => {
+  if (httpStatus >= 200 && httpStatus < 300) return 'ok'
+  if (httpStatus === 401 || httpStatus === 403) return 'invalid_key'
+  if (httpStatus === 429) return 'rate_limited'
+  return 'error'
+}
+
+const isTimeout = error => error?.name === 'TimeoutError' || error?.name === 'AbortError'
+
+/**
+ * Map a thrown error onto a health status; failures without an HTTP status never reached the API
+ */
+const classifyError = error => {
+  if (isTimeout(error)) return { status: 'network_error', httpStatus: null }
+  const mapped = toQurioError(error)
+  const httpStatus = Number(error?.status ?? error?.response?.status) || null
+  if (httpStatus) return { status: statusFromHttp(httpStatus), httpStatus, mapped }
+  if (mapped.code === ErrorCode.ProviderAuth) return { status: 'invalid_key', httpStatus, mapped }
+  if (mapped.code === ErrorCode.ProviderRateLimit) {
+    return { status: 'rate_limited', httpStatus, mapped }
+  }
+  if (mapped.code === ErrorCode.Upstream) return { status: 'network_error', httpStatus, mapped }
+  return { status: 'error', httpStatus, mapped }
+}
+
+const probeModels = async ({ provider, apiKey, baseUrl, signal }) => {
+  const models = await getProviderAdapter(provider).listModels({ apiKey, baseUrl, signal })
+  return { httpStatus: 200, modelsCount: models.length }
+}
+
+const probeCompletion = async ({ provider, apiKey, baseUrl, model, timeoutMs, signal }) => {
+  // Anthropic and Ollama have their own APIs; go through their adapters
+  if (usesNativeApi(provider)) {
+    await completeText({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: [{ role: 'user', content: 'ping' }],
+      signal,
+    })
+    return { httpStatus: 200 }
+  }
+  // A raw request: no client-side retries, so a 429 is reported instead of waited out
+  const result = await pingModel({ provider, apiKey, baseUrl, model, timeoutMs, signal })
+  if (result.error) {
+    const error = new Error(result.error)
+    error.name = result.error === 'Ping timed out' ? 'TimeoutError' : error.name
+    throw error
+  }
+  return { httpStatus: result.status, failed: !result.ok }
+}
+
+/**
+ * Probe one provider
+ * @param {Object} args
+ * @param {string} args.provider
+ * @param {string} [args.apiKey]
+ * @param {string} [args.baseUrl]
+ * @param {string} [args.model] - Completion probe model (default: the provider's default model)
+ * @param {'models'|'completion'} [args.probe] - Default "models"
+ * @param {number} [args.timeoutMs] - Default 10s, at most 60s
+ * @returns {Promise<Object>} { provider, probe, status, ok, latency_ms, http_status, model,
+ *   models_count, error, checked_at }
+ */
+export const checkProviderHealth = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  probe = 'models',
+  timeoutMs = DEFAULT_TIMEOUT_MS,
+  signal,
+}) => {
+  const resolvedModel =
+    probe === 'completion' ? model || DEFAULT_MODELS[resolveProviderAlias(provider)] : undefined
+  const timeoutSignal = AbortSignal.timeout(timeoutMs)
+  const probeSignal = signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal
+  const args = { provider, apiKey, baseUrl, model: resolvedModel, timeoutMs, signal: probeSignal }
+  const startedAt = Date.now()
+  const report = {
+    provider,
+    probe,
+    model: resolvedModel,
+    checked_at: new Date().toISOString(),
+  }
+
+  try {
+    const result = probe === 'completion' ? await probeCompletion(args) : await probeModels(args)
+    const status = statusFromHttp(result.httpStatus)
+    return {
+      ...report,
+      status,
+      ok: status === 'ok',
+      latency_ms: Date.now() - startedAt,
+      http_status: result.httpStatus,
+      models_count: result.modelsCount,
+      error: result.failed ? `HTTP ${result.httpStatus}` : undefined,
+    }
+  } catch (error) {
+    const latencyMs = Date.now() - startedAt
+    const { status, httpStatus, mapped } = classifyError(error)
+    return {
+      ...report,
+      status,
+      ok: false,
+      latency_ms: latencyMs,
+      http_status: httpStatus,
+      error: isTimeout(error) ? `Timed out after ${timeoutMs}ms` : mapped?.message || error.message,
+      retry_after_ms: mapped?.retryAfterMs,
+    }
+  }
+}
@@ -0,0 +1,222 @@
+/**
+ * Kimi (Moonshot) files and context caching
+ * Documents uploaded with purpose "file-extract" (PDF, Word, slides, spreadsheets, ...) are parsed
+ * by Moonshot, and their extracted text can be stored in a context cache. A chat request then
+ * references the cache with a leading { role: "cache" } message instead of resending the
+ * document; cached tokens are billed at a fraction of the input price.
+ */
+
+import { PROVIDER_BASE_URLS } from './providerConfig.js'
+import { callWithRetry } from './retry.js'
+
+const REQUEST_TIMEOUT_MS = 120000
+const DEFAULT_MAX_FILE_MB = 100
+// Caches are created for the model family, not one context size
+const DEFAULT_CACHE_MODEL = 'moonshot-v1'
+const DEFAULT_CACHE_TTL_SECONDS = 3600
+const CACHE_ID_PATTERN = /^[A-Za-z0-9_-]{1,128}$/
+
+export const getMaxKimiFileBytes = () => {
+  const value = Number.parseFloat(process.env.KIMI_FILE_MAX_MB)
+  return (Number.isFinite(value) && value > 0 ? value : DEFAULT_MAX_FILE_MB) * 1024 * 1024
+}
+
+const resolveCacheTtl = value => {
+  const ttl = Number.parseInt(value ?? process.env.KIMI_CACHE_TTL_SECONDS, 10)
+  return Number.isFinite(ttl) && ttl > 0 ? ttl : DEFAULT_CACHE_TTL_SECONDS
+}
+
+/**
+ * Error carrying the HTTP status and headers of a failed Moonshot request, so the retry layer
+ * and utils/errors can classify it
+ */
+const toHttpError = async (response, action) => {
+  const body = await response.text().catch(() => '')
+  let message = body
+  try {
+    const data = JSON.parse(body)
+    message = data?.error?.message || data?.message || body
+  } catch {
+    // Plain-text error body
+  }
+  return Object.assign(
+    new Error(`Kimi ${action} failed (HTTP ${response.status}): ${message}`.trim()),
+    { status: response.status, headers: response.headers },
+  )
+}
+
+const request = async (
+  path,
+  { apiKey, baseUrl, method = 'GET', body, action, signal, read = 'json' },
+) => {
+  if (!apiKey) throw new Error('Missing API key for Kimi')
+  const base = String(baseUrl || PROVIDER_BASE_URLS.kimi).replace(/\/+$/, '')
+  const isJson = body && !(body instanceof FormData)
+  return callWithRetry(
+    async () => {
+      const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+      const response = await fetch(`${base}${path}`, {
+        method,
+        headers: {
+          Authorization: `Bearer ${apiKey}`,
+          ...(isJson ? { 'Content-Type': 'application/json' } : {}),
+        },
+        body: isJson ? JSON.stringify(body) : body,
+        signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+      })
+      if (!response.ok) throw await toHttpError(response, action)
+      return read === 'text' ? response.text() : response.json()
+    },
+    { provider: 'kimi', signal },
+  )
+}
+
+const toFile = raw => ({
+  id: raw.id,
+  filename: raw.filename,
+  bytes: raw.bytes ?? null,
+  purpose: raw.purpose,
+  status: raw.status ?? null,
+  created_at: raw.created_at ? new Date(raw.created_at * 1000).toISOString() : null,
+})
+
+/**
+ * Upload a document for extraction
+ * @param {Object} params
+ * @param {{ buffer: Buffer, filename: string, mimeType?: string }} params.file
+ * @returns {Promise<Object>} { id, filename, bytes, purpose, status, created_at }
+ */
+export const uploadKimiFile = async ({ apiKey, baseUrl, file, signal }) => {
+  const form = new FormData()
+  form.append(
+    'file',
+    new Blob([file.buffer], { type: file.mimeType || 'application/octet-stream' }),
+    file.filename || 'document',
+  )
+  form.append('purpose', 'file-extract')
+  const uploaded = await request('/files', {
+    apiKey,
+    baseUrl,
+    method: 'POST',
+    body: form,
+    action: 'file upload',
+    signal,
+  })
+  return toFile(uploaded)
+}
+
+export const listKimiFiles = async ({ apiKey, baseUrl, signal }) => {
+  const data = await request('/files', { apiKey, baseUrl, action: 'file list', signal })
+  return (data?.data || []).map(toFile)
+}
+
+/**
+ * Text Moonshot extracted from an uploaded file
+ * @returns {Promise<string>}
+ */
+export const getKimiFileContent = async ({ apiKey, baseUrl, id, signal }) => {
+  const body = await request(`/files/${encodeURIComponent(id)}/content`, {
+    apiKey,
+    baseUrl,
+    action: 'file content',
+    read: 'text',
+    signal,
+  })
+  // { content, file_type, filename, title, type } for extracted documents
+  try {
+    const data = JSON.parse(body)
+    return typeof data?.content === 'string' ? data.content : body
+  } catch {
+    return body
+  }
+}
+
+export const deleteKimiFile = ({ apiKey, baseUrl, id, signal }) =>
+  request(`/files/${encodeURIComponent(id)}`, {
+    apiKey,
+    baseUrl,
+    method: 'DELETE',
+    action: 'file delete',
+    signal,
+  })
+
+const toCache = raw => ({
+  id: raw.id,
+  name: raw.name ?? null,
+  model: raw.model ?? null,
+  status: raw.status ?? null,
+  tokens: raw.tokens ?? null,
+  expired_at: raw.expired_at ? new Date(raw.expired_at * 1000).toISOString() : null,
+})
+
+/**
+ * Cache the extracted text of uploaded files (and an optional system prompt) for reuse
+ * @param {Object} params
+ * @param {Array<string>} params.fileIds - Uploaded files, in the order the model should see them
+ * @param {string} [params.system] - Instructions cached ahead of the documents
+ * @param {string} [params.model] - Model family (default "moonshot-v1")
+ * @param {number} [params.ttl] - Seconds (default KIMI_CACHE_TTL_SECONDS or 3600)
+ * @param {string} [params.name]
+ * @returns {Promise<Object>} { id, name, model, status, tokens, expired_at }
+ */
+export const createKimiCache = async ({
+  apiKey,
+  baseUrl,
+  fileIds,
+  system,
+  model,
+  ttl,
+  name,
+  signal,
+}) => {
+  const documents = await Promise.all(
+    fileIds.map(id => getKimiFileContent({ apiKey, baseUrl, id, signal })),
+  )
+  const messages = [
+    ...(system ? [{ role: 'system', content: system }] : []),
+    ...documents.map(content => ({ role: 'system', content })),
+  ]
+  const cache = await request('/caching', {
+    apiKey,
+    baseUrl,
+    method: 'POST',
+    body: {
+      model: model || DEFAULT_CACHE_MODEL,
+      messages,
+      ttl: resolveCacheTtl(ttl),
+      ...(name ? { name } : {}),
+    },
+    action: 'cache create',
+    signal,
+  })
+  return toCache(cache)
+}
+
+export const deleteKimiCache = ({ apiKey, baseUrl, id, signal }) =>
+  request(`/caching/${encodeURIComponent(id)}`, {
+    apiKey,
+    baseUrl,
+    method: 'DELETE',
+    action: 'cache delete',
+    signal,
+  })
+
+/**
+ * Read a chat request's contextCache ("cache-id" or { id, reset_ttl })
+ * @returns {{ id: string, resetTtl: number|null }|null}
+ */
+export const normalizeContextCache = value => {
+  const id = typeof value === 'string' ? value : value?.id
+  if (!id) return null
+  const resetTtl = Number.parseInt(value?.reset_ttl ?? value?.resetTtl, 10)
+  return { id: String(id), resetTtl: Number.isFinite(resetTtl) && resetTtl > 0 ? resetTtl : null }
+}
+
+export const isValidContextCacheId = id => CACHE_ID_PATTERN.test(String(id || ''))
+
+/**
+ * Content of the { role: "cache" } message that puts a cache in front of a conversation;
+ * reset_ttl extends the cache's lifetime with every use
+ */
+export const toCacheReference = ({ id, resetTtl }) =>
+  resetTtl ? `cache_id=${id};reset_ttl=${resetTtl}` : `cache_id=${id}`
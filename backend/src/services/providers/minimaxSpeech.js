@@ -0,0 +1,109 @@
+/**
+ * MiniMax speech output
+ * Chats with provider "minimax" can ask for a spoken answer ("audio" on POST /api/stream-chat):
+ * when the answer is complete its plain text is synthesized with MiniMax's text-to-speech API
+ * (t2a_v2) and sent as an "audio" event ahead of the done event.
+ */
+
+import { toPlainText } from '../plainTextStream.js'
+import { PROVIDER_BASE_URLS } from './providerConfig.js'
+import { callWithRetry } from './retry.js'
+
+const REQUEST_TIMEOUT_MS = 60000
+// t2a_v2 accepts up to 10,000 characters per request
+const MAX_SPEECH_CHARS = 10000
+const DEFAULT_SPEECH_MODEL = 'speech-02-turbo'
+const DEFAULT_VOICE = 'male-qn-qingse'
+export const AUDIO_FORMATS = ['mp3', 'wav', 'flac', 'pcm']
+
+// base_resp status codes with an HTTP meaning, so the retry layer and utils/errors classify them
+const STATUS_BY_CODE = { 1002: 429, 1004: 401, 1008: 402, 1039: 429, 2013: 400 }
+
+/**
+ * Read a chat request's audio option (true, or { voice, format, model, speed })
+ * @returns {{ voice: string, format: string, model: string, speed: number }|null}
+ */
+export const normalizeAudioOutput = value => {
+  if (!value) return null
+  const options = typeof value === 'object' ? value : {}
+  const speed = Number(options.speed)
+  const voice = options.voice || options.voice_id || process.env.MINIMAX_TTS_VOICE
+  return {
+    voice: String(voice || DEFAULT_VOICE),
+    format: String(options.format || 'mp3').toLowerCase(),
+    model: String(options.model || process.env.MINIMAX_TTS_MODEL || DEFAULT_SPEECH_MODEL),
+    speed: speed >= 0.5 && speed <= 2 ? speed : 1,
+  }
+}
+
+/**
+ * Synthesize speech
+ * @param {Object} params
+ * @param {string} params.text
+ * @param {{ voice, format, model, speed }} params.audio - From normalizeAudioOutput
+ * @returns {Promise<{ format: string, data: string, duration_ms: number|null }>} data is base64
+ */
+export const synthesizeMinimaxSpeech = async ({ apiKey, baseUrl, text, audio, signal }) => {
+  if (!apiKey) throw new Error('Missing API key for MiniMax')
+  const base = String(baseUrl || PROVIDER_BASE_URLS.minimax).replace(/\/+$/, '')
+  const body = {
+    model: audio.model,
+    text: text.slice(0, MAX_SPEECH_CHARS),
+    stream: false,
+    voice_setting: { voice_id: audio.voice, speed: audio.speed, vol: 1, pitch: 0 },
+    audio_setting: { format: audio.format, sample_rate: 32000, bitrate: 128000, channel: 1 },
+    output_format: 'hex',
+  }
+
+  const data = await callWithRetry(
+    async () => {
+      const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+      const response = await fetch(`${base}/t2a_v2`, {
+        method: 'POST',
+        headers: { Authorization: `Bearer ${apiKey}`, 'Content-Type': 'application/json' },
+        body: JSON.stringify(body),
+        signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+      })
+      const result = await response.json().catch(() => null)
+      // Failures arrive as HTTP 200 with a non-zero base_resp.status_code
+      const code = result?.base_resp?.status_code
+      if (!response.ok || code) {
+        const message = result?.base_resp?.status_msg || `HTTP ${response.status}`
+        throw Object.assign(new Error(`MiniMax speech synthesis failed: ${message}`), {
+          status: response.ok ? STATUS_BY_CODE[code] || 502 : response.status,
+          headers: response.headers,
+        })
+      }
+      return result
+    },
+    { provider: 'minimax', signal },
+  )
+
+  if (!data?.data?.audio) throw new Error('MiniMax speech synthesis returned no audio')
+  return {
+    format: data.extra_info?.audio_format || audio.format,
+    data: Buffer.from(data.data.audio, 'hex').toString('base64'),
+    duration_ms: data.extra_info?.audio_length ?? null,
+  }
+}
+
+/**
+ * Pass a chat event stream through, speaking the final answer before its done event
+ * A failed synthesis is reported as { type: 'audio', error } and does not fail the chat.
+ */
+export const speakAnswerInStream = async function* (events, { apiKey, baseUrl, audio, signal }) {
+  for await (const event of events) {
+    const text = event?.type === 'done' ? toPlainText(event.content).text : ''
+    if (text && !signal?.aborted) {
+      try {
+        const speech = await synthesizeMinimaxSpeech({ apiKey, baseUrl, text, audio, signal })
+        yield { type: 'audio', voice: audio.voice, ...speech }
+      } catch (error) {
+        if (signal?.aborted) throw error
+        console.warn('[MiniMax] Speech synthesis failed:', error.message)
+        yield { type: 'audio', error: error.message }
+      }
+    }
+    yield event
+  }
+}
@@ -0,0 +1,142 @@
+/**
+ * OpenAI-compatible message conversion
+ * Shared by the custom providers (SiliconFlow, GLM, ModelScope, Kimi, NVIDIA, MiniMax), whose
+ * APIs are stricter than OpenAI's: assistant/tool/system content must be a plain string, every
+ * tool result must answer a tool call of the preceding assistant turn, and every assistant tool
+ * call must be answered. Chat history from the client often breaks those rules (tool calls saved
+ * without their results, results without ids), which these providers reject or misread.
+ */
+
+import { normalizeTextContent } from '../serviceUtils.js'
+
+const ROLE_ALIASES = {
+  ai: 'assistant',
+  model: 'assistant',
+  human: 'user',
+  developer: 'system',
+  function: 'tool',
+}
+
+const normalizeRole = role => {
+  const value = ROLE_ALIASES[role] || role
+  return ['system', 'user', 'assistant', 'tool'].includes(value) ? value : 'user'
+}
+
+const toImagePart = part => {
+  const source = part.image_url ?? part.url ?? part.image
+  const url = typeof source === 'string' ? source : source?.url
+  return url ? { type: 'image_url', image_url: { url } } : null
+}
+
+/**
+ * User content: text and image parts, collapsed to a string when there is only text
+ */
+const toUserContent = content => {
+  if (!Array.isArray(content)) return normalizeTextContent(content)
+  const parts = content
+    .map(part => {
+      if (typeof part === 'string') return part ? { type: 'text', text: part } : null
+      if (part?.type === 'image_url' || part?.type === 'image') return toImagePart(part)
+      if (part?.type === 'input_audio' && part.input_audio?.data) return part
+      return part?.text ? { type: 'text', text: part.text } : null
+    })
+    .filter(Boolean)
+  if (parts.every(part => part.type === 'text')) {
+    return parts.map(part => part.text).join('\n')
+  }
+  return parts
+}
+
+// Tool output may arrive as a string, text parts, or a raw result object
+const toToolContent = content => {
+  if (typeof content === 'string') return content
+  if (Array.isArray(content) && content.every(part => typeof part === 'string' || part?.text)) {
+    return normalizeTextContent(content)
+  }
+  if (content === undefined || content === null) return ''
+  return JSON.stringify(content)
+}
+
+const toArguments = value => {
+  if (value === undefined || value === null || value === '') return '{}'
+  return typeof value === 'string' ? value : JSON.stringify(value)
+}
+
+const normalizeToolCall = toolCall => {
+  const name = toolCall?.function?.name || toolCall?.name
+  if (!toolCall?.id || !name) return null
+  return {
+    id: toolCall.id,
+    type: 'function',
+    function: {
+      name,
+      arguments: toArguments(toolCall.function?.arguments ?? toolCall.arguments ?? toolCall.args),
+    },
+  }
+}
+
+/**
+ * Convert chat history into messages every OpenAI-compatible provider accepts
+ * - roles are mapped onto system/user/assistant/tool
+ * - user turns keep text and image parts; other roles get plain string content
+ * - assistant tool calls are kept only when one of the tool messages right after answers them
+ * - tool results with no matching call are folded into a user message so their content is kept
+ * @param {Array} messages - Plain { role, content, tool_calls?, tool_call_id?, name? } messages
+ * @returns {Array} OpenAI chat-completions messages
+ */
+export const convertOpenAICompatMessages = messages => {
+  const list = (messages || []).filter(Boolean)
+  // Ids of the tool results directly following the assistant turn at `index`
+  const answeredAfter = index => {
+    const ids = new Set()
+    for (let next = index + 1; next < list.length; next += 1) {
+      if (normalizeRole(list[next].role) !== 'tool') break
+      if (list[next].tool_call_id) ids.add(list[next].tool_call_id)
+    }
+    return ids
+  }
+
+  const pendingCalls = new Set()
+  const converted = []
+  list.forEach((message, index) => {
+    const role = normalizeRole(message.role)
+
+    if (role === 'system') {
+      const content = normalizeTextContent(message.content)
+      if (content) converted.push({ role, content })
+      return
+    }
+
+    if (role === 'assistant') {
+      const answered = message.tool_calls?.length ? answeredAfter(index) : new Set()
+      const toolCalls = (message.tool_calls || [])
+        .map(normalizeToolCall)
+        .filter(toolCall => toolCall && answered.has(toolCall.id))
+      const content = normalizeTextContent(message.content)
+      if (!content && !toolCalls.length) return
+      pendingCalls.clear()
+      toolCalls.forEach(toolCall => pendingCalls.add(toolCall.id))
+      converted.push(
+        toolCalls.length ? { role, content, tool_calls: toolCalls } : { role, content },
+      )
+      return
+    }
+
+    if (role === 'tool') {
+      const content = toToolContent(message.content)
+      if (pendingCalls.has(message.tool_call_id)) {
+        pendingCalls.delete(message.tool_call_id)
+        converted.push({ role, tool_call_id: message.tool_call_id, content })
+        return
+      }
+      const label = message.name ? `Tool result (${message.name})` : 'Tool result'
+      converted.push({ role: 'user', content: `${label}:\n${content}` })
+      return
+    }
+
+    pendingCalls.clear()
+    const content = toUserContent(message.content)
+    if (content.length) converted.push({ role: 'user', content })
+  })
+  return converted
+}
@@ -12,6 +12,8 @@ export const PROVIDER_BASE_URLS = {
   kimi: 'https://api.moonshot.cn/v1',
   nvidia: 'https://integrate.api.nvidia.com/v1',
   minimax: 'https://api.minimax.io/v1',
+  anthropic: 'https://api.anthropic.com/v1',
+  ollama: 'http://localhost:11434',
 }
 
 // Default models
@@ -24,6 +26,8 @@ export const DEFAULT_MODELS = {
   kimi: 'moonshot-v1-8k',
   nvidia: 'deepseek-ai/deepseek-r1',
   minimax: 'MiniMax-M2.1',
+  anthropic: 'claude-sonnet-4-5',
+  ollama: 'llama3.1',
 }
 
 // Provider capabilities matrix
@@ -35,6 +39,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: false,
     supportsVision: true,
+    supportsDocuments: false,
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   siliconflow: {
     supportsStreaming: true,
@@ -43,6 +50,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: true, // DeepSeek models
     supportsVision: false,
+    supportsDocuments: false,
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   glm: {
     supportsStreaming: true,
@@ -52,6 +62,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: true,
     supportsVision: false,
+    supportsDocuments: false,
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   modelscope: {
     supportsStreaming: true,
@@ -60,6 +73,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: true,
     supportsVision: false,
+    supportsDocuments: false,
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   kimi: {
     supportsStreaming: true,
@@ -70,6 +86,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: false,
     supportsVision: false,
+    supportsDocuments: false, // PDFs go through the file-extract files API, not inline parts
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   gemini: {
     supportsStreaming: true,
@@ -78,6 +97,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: false, // Uses different format
     supportsThinking: true,
     supportsVision: true,
+    supportsDocuments: true, // Inline PDF parts
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   nvidia: {
     supportsStreaming: true,
@@ -86,6 +108,9 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: true,
     supportsVision: true,
+    supportsDocuments: false,
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
   },
   minimax: {
     supportsStreaming: true,
@@ -94,7 +119,74 @@ export const PROVIDER_CAPABILITIES = {
     supportsJsonSchema: true,
     supportsThinking: true, // Interleaved Thinking via reasoning_split
     supportsVision: false,
+    supportsDocuments: false,
+    supportsInterleavedThinking: true, // Earlier reasoning is sent back with tool calls
+    supportsAudioOutput: true, // Answers spoken with MiniMax speech synthesis (t2a_v2)
   },
+  anthropic: {
+    supportsStreaming: true,
+    supportsToolCalls: true,
+    supportsStreamingToolCalls: true,
+    supportsJsonSchema: false, // No response_format; JSON mode is a system prompt hint
+    supportsThinking: true, // Extended thinking blocks
+    supportsVision: true,
+    supportsDocuments: true, // PDF document blocks
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
+  },
+  ollama: {
+    supportsStreaming: true,
+    supportsToolCalls: true, // Depends on the model (llama3.1, qwen3, mistral, ...)
+    supportsStreamingToolCalls: true,
+    supportsJsonSchema: false, // format: "json" only
+    supportsThinking: true, // think flag on reasoning models
+    supportsVision: true,
+    supportsDocuments: false,
+    supportsInterleavedThinking: false,
+    supportsAudioOutput: false,
+  },
+}
+
+// Requests and tokens per minute per API key, enforced before LLM routes run (see
+// services/providers/rateLimiter.js). Entry-tier values of the providers' published limits;
+// override with RATE_LIMIT_<PROVIDER>_RPM / _TPM (0 = unlimited). Unlisted providers are unlimited.
+export const PROVIDER_RATE_LIMITS = {
+  openai: { rpm: 500, tpm: 200000 },
+  anthropic: { rpm: 50, tpm: 30000 },
+  siliconflow: { rpm: 1000, tpm: 50000 },
+  kimi: { rpm: 200, tpm: 2000000 },
+  nvidia: { rpm: 40, tpm: 0 },
+}
+
+// Aliases for providers registered under more than one name
+const PROVIDER_ALIASES = {
+  local: 'ollama',
+}
+
+// Local providers that run without an API key
+const KEYLESS_PROVIDERS = ['ollama']
+
+// Providers whose API is not OpenAI-compatible and must be called through their adapter
+const NATIVE_API_PROVIDERS = ['anthropic', 'ollama']
+
+export const resolveProviderAlias = provider => PROVIDER_ALIASES[provider] || provider
+
+/**
+ * Check if provider needs an API key
+ * @param {string} provider - Provider name
+ * @returns {boolean} Whether an API key is required
+ */
+export function requiresApiKey(provider) {
+  return !KEYLESS_PROVIDERS.includes(resolveProviderAlias(provider))
+}
+
+/**
+ * Check if provider must be called through its adapter rather than a ChatOpenAI client
+ * @param {string} provider - Provider name
+ * @returns {boolean} Whether the provider has a native (non OpenAI-compatible) API
+ */
+export function usesNativeApi(provider) {
+  return NATIVE_API_PROVIDERS.includes(resolveProviderAlias(provider))
 }
 
 /**
@@ -103,10 +195,11 @@ export const PROVIDER_CAPABILITIES = {
  * @returns {Object} Provider configuration
  */
 export function getProviderConfig(provider) {
+  const name = resolveProviderAlias(provider)
   return {
-    baseURL: PROVIDER_BASE_URLS[provider],
-    defaultModel: DEFAULT_MODELS[provider],
-    capabilities: PROVIDER_CAPABILITIES[provider] || {},
+    baseURL: PROVIDER_BASE_URLS[name],
+    defaultModel: DEFAULT_MODELS[name],
+    capabilities: PROVIDER_CAPABILITIES[name] || {},
   }
 }
 
@@ -117,5 +210,5 @@ export function getProviderConfig(provider) {
  * @returns {boolean} Whether capability is supported
  */
 export function supportsCapability(provider, capability) {
-  return PROVIDER_CAPABILITIES[provider]?.[capability] ?? false
+  return PROVIDER_CAPABILITIES[resolveProviderAlias(provider)]?.[capability] ?? false
 }
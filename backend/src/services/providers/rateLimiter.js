@@ -0,0 +1,189 @@
+/**
+ * Provider rate limiter
+ * Token buckets per (provider, API key hash) for requests and estimated tokens per minute, sized
+ * by PROVIDER_RATE_LIMITS. A request takes its share up front, so buckets can run negative and
+ * later requests wait behind earlier ones (first come, first served). In "queue" mode a request
+ * over the limit is delayed until its share has refilled; in "reject" mode, or when the delay
+ * would exceed maxWaitMs, it fails with a provider_rate_limit error carrying retryAfterMs.
+ *
+ * A request takes one share when it arrives (middleware/rateLimit.js); its work then runs in a
+ * rate limit context, so each further model call it makes takes its own share (see
+ * services/modelRateLimit.js).
+ */
+
+import { AsyncLocalStorage } from 'async_hooks'
+import crypto from 'crypto'
+import { ErrorCode, QurioError } from '../../utils/errors.js'
+import { PROVIDER_RATE_LIMITS, resolveProviderAlias } from './providerConfig.js'
+import { sleep } from './retry.js'
+
+const MINUTE_MS = 60000
+const DEFAULT_MAX_WAIT_MS = 60000
+// Rough size of a token, for estimating a request's input
+const CHARS_PER_TOKEN = 4
+
+export const RATE_LIMIT_MODES = ['queue', 'reject', 'off']
+
+// The limiter serving the current request, and the share it already took on arrival
+const storage = new AsyncLocalStorage()
+
+class TokenBucket {
+  constructor(perMinute, now) {
+    this.capacity = perMinute
+    this.tokens = perMinute
+    this.refillPerMs = perMinute / MINUTE_MS
+    this.updatedAt = now
+  }
+
+  refill(now) {
+    this.tokens = Math.min(this.capacity, this.tokens + (now - this.updatedAt) * this.refillPerMs)
+    this.updatedAt = now
+  }
+
+  // A request larger than the whole bucket is charged the whole bucket, so it can still run
+  cost(amount) {
+    return Math.min(amount, this.capacity)
+  }
+
+  /** Milliseconds until `amount` is available after everything already taken */
+  waitFor(amount, now) {
+    this.refill(now)
+    const deficit = this.cost(amount) - this.tokens
+    return deficit > 0 ? Math.ceil(deficit / this.refillPerMs) : 0
+  }
+
+  take(amount) {
+    this.tokens -= this.cost(amount)
+  }
+
+  refund(amount, now) {
+    this.refill(now)
+    this.tokens = Math.min(this.capacity, this.tokens + this.cost(amount))
+  }
+}
+
+const readLimit = (env, name, fallback) => {
+  const value = Number.parseInt(env[name], 10)
+  return Number.isFinite(value) && value >= 0 ? value : fallback
+}
+
+/**
+ * Per-minute limits for a provider: PROVIDER_RATE_LIMITS with RATE_LIMIT_<PROVIDER>_RPM / _TPM
+ * overrides (0 = unlimited)
+ * @returns {{ rpm: number, tpm: number }}
+ */
+export const resolveRateLimits = (provider, env = process.env) => {
+  const name = resolveProviderAlias(provider)
+  const defaults = PROVIDER_RATE_LIMITS[name] || {}
+  const prefix = `RATE_LIMIT_${String(name).toUpperCase()}`
+  return {
+    rpm: readLimit(env, `${prefix}_RPM`, defaults.rpm || 0),
+    tpm: readLimit(env, `${prefix}_TPM`, defaults.tpm || 0),
+  }
+}
+
+/**
+ * Estimated input tokens of a request body
+ */
+export const estimateRequestTokens = body =>
+  Math.ceil(JSON.stringify(body ?? '').length / CHARS_PER_TOKEN)
+
+// Keys are only kept as a hash
+const hashApiKey = apiKey =>
+  crypto
+    .createHash('sha256')
+    .update(String(apiKey || ''))
+    .digest('hex')
+    .slice(0, 16)
+
+/**
+ * Bucket key of a provider and API key
+ */
+export const rateLimitKey = (provider, apiKey) =>
+  `${resolveProviderAlias(provider)}:${hashApiKey(apiKey)}`
+
+/**
+ * Run a request's work in a rate limit context. The share the request took on arrival covers its
+ * first model call on that key; every other model call takes its own share.
+ * @param {Object} context
+ * @param {Object} context.rateLimiter - From createRateLimiter()
+ * @param {string} [context.provider] - Provider of the share already taken
+ * @param {string} [context.apiKey]
+ * @param {Function} fn
+ */
+export const runWithRateLimiter = ({ rateLimiter, provider, apiKey }, fn) =>
+  storage.run({ rateLimiter, prepaid: provider ? rateLimitKey(provider, apiKey) : null }, fn)
+
+/** @returns {{ rateLimiter: Object, prepaid: string | null } | null} */
+export const getRateLimitContext = () => storage.getStore() || null
+
+/**
+ * Create a rate limiter; the app holds one for its lifetime
+ * @param {Object} [options]
+ * @param {string} [options.mode] - "queue" | "reject" | "off" (default RATE_LIMIT_MODE or "queue")
+ * @param {number} [options.maxWaitMs] - Longest queue delay before rejecting
+ *   (default RATE_LIMIT_MAX_WAIT_MS or 60s)
+ * @param {Object} [options.env] - Source of the per-provider limit overrides
+ */
+export const createRateLimiter = ({
+  mode = process.env.RATE_LIMIT_MODE || 'queue',
+  maxWaitMs = readLimit(process.env, 'RATE_LIMIT_MAX_WAIT_MS', DEFAULT_MAX_WAIT_MS),
+  env = process.env,
+} = {}) => {
+  if (!RATE_LIMIT_MODES.includes(mode)) {
+    throw new Error(`Invalid RATE_LIMIT_MODE: ${mode}. Supported: ${RATE_LIMIT_MODES.join(', ')}`)
+  }
+  // "provider:keyHash" -> { requests: TokenBucket | null, tokens: TokenBucket | null }
+  const buckets = new Map()
+
+  const getBuckets = (provider, apiKey, now) => {
+    const key = rateLimitKey(provider, apiKey)
+    if (!buckets.has(key)) {
+      const { rpm, tpm } = resolveRateLimits(provider, env)
+      buckets.set(key, {
+        requests: rpm ? new TokenBucket(rpm, now) : null,
+        tokens: tpm ? new TokenBucket(tpm, now) : null,
+      })
+    }
+    return buckets.get(key)
+  }
+
+  /**
+   * Take a request's share of its buckets, waiting in queue mode until it is available
+   * @param {Object} request
+   * @param {string} request.provider
+   * @param {string} [request.apiKey]
+   * @param {number} [request.tokens] - Estimated tokens (see estimateRequestTokens)
+   * @param {AbortSignal} [request.signal] - Aborting while queued gives the share back
+   * @returns {Promise<{ waitedMs: number }>}
+   * @throws {QurioError} provider_rate_limit when the request may not wait
+   */
+  const acquire = async ({ provider, apiKey, tokens = 0, signal }) => {
+    if (mode === 'off' || !provider) return { waitedMs: 0 }
+    const now = Date.now()
+    const { requests, tokens: tokenBucket } = getBuckets(provider, apiKey, now)
+    const shares = [
+      [requests, 1],
+      [tokenBucket, tokens],
+    ].filter(([bucket]) => bucket)
+    const waitMs = Math.max(0, ...shares.map(([bucket, amount]) => bucket.waitFor(amount, now)))
+    if (waitMs > 0 && (mode === 'reject' || waitMs > maxWaitMs)) {
+      throw new QurioError(
+        ErrorCode.ProviderRateLimit,
+        `Rate limit for ${provider} reached; retry in ${Math.ceil(waitMs / 1000)}s`,
+        { retryAfterMs: waitMs },
+      )
+    }
+    for (const [bucket, amount] of shares) bucket.take(amount)
+    if (!waitMs) return { waitedMs: 0 }
+    try {
+      await sleep(waitMs, signal)
+    } catch (error) {
+      for (const [bucket, amount] of shares) bucket.refund(amount, Date.now())
+      throw error
+    }
+    return { waitedMs: waitMs }
+  }
+
+  return { mode, acquire }
+}
@@ -0,0 +1,161 @@
+/**
+ * Provider retry layer
+ * Retries transient failures (429 rate limits, 502/503/504, dropped connections) of the custom
+ * OpenAI-compatible providers with exponential backoff, honouring Retry-After. Only request setup
+ * is retried: a stream that already produced a chunk is never replayed. Each wait is announced as
+ * a `retrying` event so the UI can show "rate limited, retrying in 5s".
+ */
+
+import { addSpanEvent } from '../tracing.js'
+
+const RETRYABLE_STATUSES = new Set([408, 429, 500, 502, 503, 504])
+const RETRYABLE_CODES = new Set([
+  'ECONNRESET',
+  'ECONNREFUSED',
+  'ETIMEDOUT',
+  'EAI_AGAIN',
+  'UND_ERR_SOCKET',
+  'UND_ERR_CONNECT_TIMEOUT',
+])
+const RETRYABLE_MESSAGE_PATTERN =
+  /\b429\b|rate.?limit|too many requests|\b50[234]\b|overloaded|temporarily unavailable/i
+
+// LangChain's own retries are disabled for these providers (maxRetries: 0) so this layer owns them
+const RETRY_POLICIES = {
+  siliconflow: { maxRetries: 4, baseDelayMs: 2000 },
+  glm: { maxRetries: 4, baseDelayMs: 2000 },
+  nvidia: { maxRetries: 4, baseDelayMs: 3000 },
+  modelscope: { maxRetries: 3, baseDelayMs: 2000 },
+  kimi: { maxRetries: 3, baseDelayMs: 1500 },
+  minimax: { maxRetries: 3, baseDelayMs: 1500 },
+}
+const MAX_DELAY_MS = 30000
+
+export const getRetryPolicy = provider => {
+  const policy = RETRY_POLICIES[provider]
+  if (!policy) return null
+  const override = Number.parseInt(process.env.PROVIDER_MAX_RETRIES, 10)
+  return Number.isFinite(override) && override >= 0 ? { ...policy, maxRetries: override } : policy
+}
+
+const getStatus = error =>
+  error?.status ?? error?.response?.status ?? error?.statusCode ?? error?.cause?.status ?? null
+
+const readHeader = (headers, name) => {
+  if (!headers) return null
+  if (typeof headers.get === 'function') return headers.get(name)
+  return headers[name] ?? headers[name.toLowerCase()] ?? null
+}
+
+/**
+ * Delay requested by the provider via Retry-After (seconds or HTTP date)
+ */
+export const getRetryAfterMs = error => {
+  const value = readHeader(error?.headers || error?.response?.headers, 'retry-after')
+  if (!value) return null
+  const seconds = Number(value)
+  if (Number.isFinite(seconds)) return Math.max(0, seconds * 1000)
+  const date = Date.parse(value)
+  return Number.isNaN(date) ? null : Math.max(0, date - Date.now())
+}
+
+export const isRetryableError = error => {
+  if (!error || error.name === 'AbortError') return false
+  const status = getStatus(error)
+  if (status) return RETRYABLE_STATUSES.has(Number(status))
+  const code = error.code || error.cause?.code
+  if (code && RETRYABLE_CODES.has(code)) return true
+  return RETRYABLE_MESSAGE_PATTERN.test(String(error.message || ''))
+}
+
+export const sleep = (ms, signal) =>
+  new Promise((resolve, reject) => {
+    if (signal?.aborted) return reject(new Error('Request aborted'))
+    const timer = setTimeout(() => {
+      signal?.removeEventListener('abort', onAbort)
+      resolve()
+    }, ms)
+    const onAbort = () => {
+      clearTimeout(timer)
+      reject(new Error('Request aborted'))
+    }
+    signal?.addEventListener('abort', onAbort, { once: true })
+  })
+
+/**
+ * Run a provider call, retrying transient failures
+ * Generator: yields `retrying` events before each wait and returns the call's result, so callers
+ * use `const result = yield* withProviderRetry(...)`.
+ * @param {() => Promise<any>} call
+ * @param {Object} options
+ * @param {string} options.provider
+ * @param {{ maxRetries: number, baseDelayMs: number }} [options.policy] Overrides the provider's
+ * @param {AbortSignal} [options.signal]
+ */
+export const withProviderRetry = async function* (call, options = {}) {
+  const { provider, signal } = options
+  const policy = options.policy || getRetryPolicy(provider)
+  for (let attempt = 0; ; attempt += 1) {
+    try {
+      return await call()
+    } catch (error) {
+      if (!policy || attempt >= policy.maxRetries || signal?.aborted || !isRetryableError(error)) {
+        throw error
+      }
+      const backoff = Math.min(MAX_DELAY_MS, policy.baseDelayMs * 2 ** attempt)
+      const delayMs = Math.round(
+        Math.min(MAX_DELAY_MS, getRetryAfterMs(error) ?? backoff * (0.75 + Math.random() * 0.5)),
+      )
+      const status = getStatus(error) ? Number(getStatus(error)) : undefined
+      const rateLimited = status === 429 || /\b429\b|rate.?limit/i.test(error.message || '')
+      console.warn(
+        `[Retry] ${provider} attempt ${attempt + 1} failed (${status || error.message}); ` +
+          `retrying in ${delayMs}ms`,
+      )
+      const event = {
+        type: 'retrying',
+        provider,
+        attempt: attempt + 1,
+        max_retries: policy.maxRetries,
+        delay_ms: delayMs,
+        status,
+        reason: rateLimited ? 'rate_limited' : 'unavailable',
+        error: String(error.message || error),
+      }
+      addSpanEvent('retrying', event)
+      yield event
+      await sleep(delayMs, signal)
+    }
+  }
+}
+
+/**
+ * Promise form of withProviderRetry for non-streaming calls; waits are only logged
+ */
+export const callWithRetry = async (call, options) => {
+  const attempts = withProviderRetry(call, options)
+  for (let step = await attempts.next(); ; step = await attempts.next()) {
+    if (step.done) return step.value
+  }
+}
+
+/**
+ * Pull the first chunk of a stream so request failures surface while they are still retryable
+ * @returns {Promise<AsyncIterable>} Stream yielding the first chunk followed by the rest
+ */
+export const primeStream = async streamPromise => {
+  const iterator = (await streamPromise)[Symbol.asyncIterator]()
+  const first = await iterator.next()
+  return (async function* () {
+    try {
+      if (first.done) return
+      yield first.value
+      for (let next = await iterator.next(); !next.done; next = await iterator.next()) {
+        yield next.value
+      }
+    } finally {
+      // Release the underlying request when the consumer stops early
+      await iterator.return?.()
+    }
+  })()
+}
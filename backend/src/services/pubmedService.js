@@ -0,0 +1,174 @@
+/**
+ * PubMed service
+ * Literature search through NCBI E-utilities (ESearch + EFetch) with structured study metadata:
+ * design and evidence level from publication types, population/outcomes from structured abstracts.
+ * Results share the title/authors/year/DOI shape of academicSearchService.js.
+ * NCBI_API_KEY is optional and raises the rate limit from 3 to 10 requests per second.
+ */
+
+import { decodeEntities } from './htmlReadability.js'
+
+const EUTILS_BASE = 'https://eutils.ncbi.nlm.nih.gov/entrez/eutils'
+const REQUEST_TIMEOUT_MS = 20000
+const MAX_SECTION_CHARS = 1200
+
+// Highest level first; the first matching publication type decides the design
+const STUDY_DESIGNS = [
+  { type: 'Meta-Analysis', design: 'meta-analysis', level: 1 },
+  { type: 'Systematic Review', design: 'systematic review', level: 1 },
+  { type: 'Practice Guideline', design: 'guideline', level: 1 },
+  { type: 'Guideline', design: 'guideline', level: 1 },
+  { type: 'Randomized Controlled Trial', design: 'randomized controlled trial', level: 2 },
+  { type: 'Controlled Clinical Trial', design: 'controlled clinical trial', level: 3 },
+  { type: 'Clinical Trial', design: 'clinical trial', level: 3 },
+  { type: 'Observational Study', design: 'observational study', level: 4 },
+  { type: 'Comparative Study', design: 'comparative study', level: 4 },
+  { type: 'Case Reports', design: 'case report', level: 5 },
+  { type: 'Review', design: 'narrative review', level: 5 },
+  { type: 'Editorial', design: 'editorial', level: 6 },
+  { type: 'Comment', design: 'comment', level: 6 },
+]
+
+export const PUBMED_STUDY_FILTERS = {
+  meta_analysis: 'Meta-Analysis[pt]',
+  systematic_review: 'Systematic Review[pt]',
+  rct: 'Randomized Controlled Trial[pt]',
+  clinical_trial: 'Clinical Trial[pt]',
+  guideline: '(Guideline[pt] OR Practice Guideline[pt])',
+}
+
+const POPULATION_LABELS = /^(?:patients?|participants?|population|subjects?|setting|methods?)/i
+const OUTCOME_LABELS = /^(?:results?|outcomes?|main outcome|findings|conclusions?)/i
+
+const decodeXml = text =>
+  decodeEntities(String(text || '').replace(/<[^>]+>/g, ''))
+    .replace(/\s+/g, ' ')
+    .trim()
+
+const firstTag = (xml, tag) => {
+  const match = xml.match(new RegExp(`<${tag}(?:\\s[^>]*)?>([\\s\\S]*?)</${tag}>`))
+  return match ? decodeXml(match[1]) : ''
+}
+
+const allTags = (xml, tag) =>
+  Array.from(xml.matchAll(new RegExp(`<${tag}(\\s[^>]*)?>([\\s\\S]*?)</${tag}>`, 'g'))).map(
+    match => ({ attrs: match[1] || '', text: decodeXml(match[2]) }),
+  )
+
+const truncate = text =>
+  text.length > MAX_SECTION_CHARS ? `${text.slice(0, MAX_SECTION_CHARS)}…` : text
+
+const eutilsFetch = async (endpoint, params, { signal } = {}) => {
+  const query = new URLSearchParams({ db: 'pubmed', tool: 'qurio', ...params })
+  if (process.env.NCBI_API_KEY) query.set('api_key', process.env.NCBI_API_KEY)
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(`${EUTILS_BASE}/${endpoint}?${query}`, {
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (!response.ok) throw new Error(`PubMed E-utilities error: HTTP ${response.status}`)
+  return response
+}
+
+const classifyDesign = publicationTypes => {
+  const match = STUDY_DESIGNS.find(item => publicationTypes.includes(item.type))
+  return match ? { design: match.design, evidence_level: match.level } : { design: 'other', evidence_level: null }
+}
+
+// "n = 1,234", "1234 patients", "1,234 participants were enrolled"
+const extractSampleSize = text => {
+  const match =
+    text.match(/\bn\s*=\s*(\d[\d,]*)/i) ||
+    text.match(/\b(\d[\d,]*)\s+(?:patients|participants|subjects|adults|children|women|men)\b/i)
+  return match ? Number(match[1].replace(/,/g, '')) : null
+}
+
+// "Lovelace A", or a group author's CollectiveName
+const parseAuthors = xml =>
+  (xml.match(/<Author\b[^>]*>[\s\S]*?<\/Author>/g) || [])
+    .map(author => {
+      const lastName = firstTag(author, 'LastName')
+      const initials = firstTag(author, 'Initials')
+      return lastName ? `${lastName} ${initials}`.trim() : firstTag(author, 'CollectiveName')
+    })
+    .filter(Boolean)
+
+const parseArticle = xml => {
+  const pmid = firstTag(xml, 'PMID')
+  const sections = allTags(xml, 'AbstractText').map(({ attrs, text }) => ({
+    label: (attrs.match(/Label="([^"]*)"/) || [])[1] || '',
+    text,
+  }))
+  const abstract = sections
+    .map(section => (section.label ? `${section.label}: ${section.text}` : section.text))
+    .join(' ')
+  const population = sections.filter(section => POPULATION_LABELS.test(section.label))
+  const outcomes = sections.filter(section => OUTCOME_LABELS.test(section.label))
+  const publicationTypes = allTags(xml, 'PublicationType').map(item => item.text)
+  const doi = (xml.match(/<ArticleId IdType="doi">([^<]+)<\/ArticleId>/) || [])[1] || null
+  // PubDate holds either <Year> or a free-form <MedlineDate> such as "2019 Nov-Dec"
+  const pubDate = (xml.match(/<PubDate>([\s\S]*?)<\/PubDate>/) || [])[1] || ''
+  const year = firstTag(pubDate, 'Year') || (pubDate.match(/\d{4}/) || [])[0] || null
+
+  return {
+    pmid,
+    title: firstTag(xml, 'ArticleTitle'),
+    authors: parseAuthors(xml),
+    journal: firstTag(xml, 'Title'),
+    year,
+    doi,
+    publication_types: publicationTypes,
+    ...classifyDesign(publicationTypes),
+    sample_size: extractSampleSize(abstract),
+    population: population.length ? truncate(population.map(item => item.text).join(' ')) : null,
+    outcomes: outcomes.length ? truncate(outcomes.map(item => item.text).join(' ')) : null,
+    mesh_terms: allTags(xml, 'DescriptorName').map(item => item.text),
+    abstract: truncate(abstract),
+  }
+}
+
+/**
+ * Search PubMed and return structured study metadata
+ * @param {Object} args
+ * @param {string} args.query PubMed query syntax is supported (MeSH, [tiab], boolean operators)
+ * @param {string} [args.studyType] Key of PUBMED_STUDY_FILTERS
+ * @param {string} [args.dateFrom] Publication date lower bound (YYYY or YYYY/MM/DD)
+ * @param {string} [args.dateTo] Publication date upper bound
+ * @param {number} [args.limit=10]
+ */
+export const searchPubmed = async ({ query, studyType, dateFrom, dateTo, limit = 10, signal }) => {
+  const filter = PUBMED_STUDY_FILTERS[studyType]
+  const term = filter ? `(${query}) AND ${filter}` : query
+  const retmax = Math.min(Math.max(Number(limit) || 10, 1), 30)
+  const dateParams =
+    dateFrom || dateTo
+      ? { datetype: 'pdat', mindate: dateFrom || '1800', maxdate: dateTo || '3000' }
+      : {}
+  const search = await (
+    await eutilsFetch(
+      'esearch.fcgi',
+      { term, retmode: 'json', retmax: String(retmax), sort: 'relevance', ...dateParams },
+      { signal },
+    )
+  ).json()
+  const ids = search.esearchresult?.idlist || []
+  if (!ids.length) return { query: term, total_count: 0, results: [] }
+
+  const xml = await (
+    await eutilsFetch('efetch.fcgi', { id: ids.join(','), retmode: 'xml' }, { signal })
+  ).text()
+  const articles = (xml.match(/<PubmedArticle>[\s\S]*?<\/PubmedArticle>/g) || []).map(parseArticle)
+  const byId = new Map(articles.map(article => [article.pmid, article]))
+
+  return {
+    query: term,
+    total_count: Number(search.esearchresult?.count) || ids.length,
+    results: ids
+      .map(id => byId.get(id))
+      .filter(Boolean)
+      .map(article => ({
+        ...article,
+        url: `https://pubmed.ncbi.nlm.nih.gov/${article.pmid}/`,
+        content: article.abstract,
+      })),
+  }
+}
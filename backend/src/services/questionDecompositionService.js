@@ -0,0 +1,228 @@
+/**
+ * Question decomposition service
+ * Splits multi-part user questions into independent sub-questions, answers each, and
+ * synthesizes a combined answer with one section per sub-question.
+ */
+
+import { yieldWhileRunning } from '../utils/eventQueue.js'
+import { completeJson } from './modelCompletion.js'
+import { getLastUserText } from './serviceUtils.js'
+import { streamChat } from './streamChatService.js'
+
+const MAX_SUB_QUESTIONS = 5
+
+/**
+ * Cheap pre-check so single questions never pay for a decomposition call
+ */
+export const looksMultiPart = text => {
+  const value = String(text || '')
+  if (value.length < 20) return false
+  const questionMarks = (value.match(/[?？]/g) || []).length
+  const listItems = (value.match(/^\s*(?:\d+[.)、]|[-*•])\s+/gm) || []).length
+  const connectors = /\b(also|additionally|as well as|and then|secondly|finally)\b|另外|还有|以及|其次|同时/i
+  return questionMarks >= 2 || listItems >= 2 || (questionMarks >= 1 && connectors.test(value))
+}
+
+const buildDecompositionMessages = question => [
+  {
+    role: 'system',
+    content: `You split user requests into independent sub-questions.
+Rules:
+- Only split when the request contains multiple DISTINCT questions that can be answered separately.
+- Each sub-question must be self-contained (repeat the needed context from the original request).
+- Keep the user's language.
+- Return at most ${MAX_SUB_QUESTIONS} sub-questions, in the order they were asked.
+- If the request is a single question, return exactly one item.
+
+Return ONLY JSON: {"sub_questions": ["..."]}`,
+  },
+  { role: 'user', content: question },
+]
+
+/**
+ * Decompose a question
+ * @returns {Promise<string[]|null>} Sub-questions, or null when the question is not multi-part
+ */
+export const decomposeQuestion = async ({ provider, apiKey, baseUrl, model, question, signal }) => {
+  if (!looksMultiPart(question)) return null
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: buildDecompositionMessages(question),
+      temperature: 0,
+      signal,
+    })
+    const list = Array.isArray(parsed) ? parsed : parsed?.sub_questions
+    const subQuestions = Array.isArray(list)
+      ? list
+          .map(item => (typeof item === 'string' ? item : item?.question))
+          .filter(item => typeof item === 'string' && item.trim())
+          .map(item => item.trim())
+          .slice(0, MAX_SUB_QUESTIONS)
+      : []
+    return subQuestions.length > 1 ? subQuestions : null
+  } catch (error) {
+    console.warn('[Decompose] Decomposition failed, answering as a single question:', error.message)
+    return null
+  }
+}
+
+const sourceKey = source => source?.uri || source?.url || source?.title
+
+// [1], [2, 3], ... but not the text of a Markdown link ([1](https://...))
+const CITATION_PATTERN = /\[(\d+(?:\s*,\s*\d+)*)\](?!\()/g
+
+/**
+ * Rewrite an answer's citation numbers; numbers missing from the map are kept
+ * @param {string} answer
+ * @param {Map<number, number>} numbers - Old citation number to new
+ */
+export const renumberCitations = (answer, numbers) =>
+  String(answer || '').replace(
+    CITATION_PATTERN,
+    (_, list) =>
+      `[${list
+        .split(',')
+        .map(item => numbers.get(Number(item)) ?? Number(item))
+        .join(', ')}]`,
+  )
+
+/**
+ * Merge the sources of the sub-answers, in sub-question order, into one list and renumber each
+ * answer's citations against it (every sub-answer numbers its own sources from [1])
+ * @param {{ question: string, answer: string, sources?: Object[] }[]} subAnswers
+ * @returns {{ sources: Object[], subAnswers: { question: string, answer: string }[] }}
+ */
+export const mergeSubAnswerSources = subAnswers => {
+  const positions = new Map()
+  const sources = []
+  const renumbered = subAnswers.map(({ question, answer, sources: own = [] }) => {
+    const numbers = new Map()
+    own.forEach((source, index) => {
+      const key = sourceKey(source)
+      if (!key) return
+      if (!positions.has(key)) {
+        sources.push(source)
+        positions.set(key, sources.length)
+      }
+      numbers.set(index + 1, positions.get(key))
+    })
+    return { question, answer: renumberCitations(answer, numbers) }
+  })
+  return { sources, subAnswers: renumbered }
+}
+
+export const buildSynthesisPrompt = (question, subAnswers) => `The user asked a multi-part question. Each part has already been answered separately below.
+Write ONE combined answer:
+- Start with a one or two sentence overview.
+- Then write one "## " section per sub-question, in the same order, titled with a short form of the sub-question.
+- Keep the facts and citations ([1], [2], ...) from the sub-answers; they already share one numbering. Do not invent new facts or citation numbers.
+- Remove repetition across sections.
+
+Original question:
+${question}
+
+${subAnswers
+  .map(
+    (item, index) => `### Sub-question ${index + 1}: ${item.question}
+${item.answer || '(no answer was produced)'}`,
+  )
+  .join('\n\n')}`
+
+/**
+ * Stream chat with decomposition ("smart mode")
+ * Falls through to plain streamChat when the last user message is not multi-part.
+ */
+export const streamDecomposedChat = async function* (params) {
+  const { messages, signal } = params
+  const question = getLastUserText(messages)
+  const subQuestions = await decomposeQuestion({ ...params, question })
+
+  if (!subQuestions) {
+    yield* streamChat(params)
+    return
+  }
+
+  yield { type: 'decomposition', sub_questions: subQuestions }
+
+  const history = (messages || []).slice(0, -1)
+  const answered = subQuestions.map(subQuestion => ({ question: subQuestion, answer: '' }))
+
+  // Answer every sub-question in parallel; only tool events and status are forwarded live
+  yield* yieldWhileRunning(push =>
+    Promise.all(
+      subQuestions.map(async (subQuestion, index) => {
+        push({ type: 'sub_question', index, question: subQuestion, status: 'running' })
+        const startedAt = Date.now()
+        try {
+          for await (const event of streamChat({
+            ...params,
+            messages: [...history, { role: 'user', content: subQuestion }],
+            signal,
+          })) {
+            if (event.type === 'tool_call' || event.type === 'tool_result') {
+              push({ ...event, subQuestion: index })
+            } else if (event.type === 'done') {
+              answered[index] = {
+                question: subQuestion,
+                answer: event.content || '',
+                sources: event.sources || [],
+              }
+            }
+          }
+          push({
+            type: 'sub_question',
+            index,
+            question: subQuestion,
+            status: 'done',
+            duration_ms: Date.now() - startedAt,
+          })
+        } catch (error) {
+          push({
+            type: 'sub_question',
+            index,
+            question: subQuestion,
+            status: 'error',
+            duration_ms: Date.now() - startedAt,
+            error: error.message,
+          })
+        }
+      }),
+    ),
+  )
+
+  // Sub-answers finish in any order; sources are numbered in sub-question order
+  const { sources, subAnswers } = mergeSubAnswerSources(answered)
+
+  // Synthesis streams like a normal answer; tools are not needed at this point
+  const synthesisMessages = [
+    ...history.filter(m => m?.role === 'system'),
+    { role: 'user', content: buildSynthesisPrompt(question, subAnswers) },
+  ]
+  for await (const event of streamChat({
+    ...params,
+    messages: synthesisMessages,
+    contextMessageLimit: undefined,
+    toolIds: [],
+    tools: [],
+    userTools: [],
+  })) {
+    if (event.type === 'done') {
+      const merged = [...sources]
+      const keys = new Set(merged.map(sourceKey))
+      for (const source of event.sources || []) {
+        if (sourceKey(source) && !keys.has(sourceKey(source))) merged.push(source)
+      }
+      yield {
+        ...event,
+        sources: merged.length ? merged : undefined,
+        subQuestions: subAnswers,
+      }
+      continue
+    }
+    yield event
+  }
+}
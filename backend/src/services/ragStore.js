@@ -0,0 +1,207 @@
+/**
+ * RAG store
+ * Local knowledge base on the JSON data store: documents (ingested sites and uploaded files) are
+ * split into overlapping text chunks (embedded when EMBEDDING_* is configured) and served by
+ * hybrid lexical/semantic search, over the API and the knowledge_search tool.
+ */
+
+import crypto from 'crypto'
+import path from 'path'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import { extractDocumentText } from './documentTextExtractor.js'
+import { cosineSimilarity, embedTexts, resolveEmbeddingConfig } from './embeddingClient.js'
+
+const COLLECTION = 'rag-documents'
+const CHUNK_CHARS = 1200
+const CHUNK_OVERLAP_CHARS = 200
+const DEFAULT_SEARCH_LIMIT = 8
+
+export const hashContent = content => crypto.createHash('sha1').update(content).digest('hex')
+
+export const documentIdFor = key => hashContent(String(key)).slice(0, 16)
+
+const tokenize = text =>
+  String(text || '')
+    .toLowerCase()
+    .match(/[\p{L}\p{N}]+/gu) || []
+
+/**
+ * Split text into chunks of about CHUNK_CHARS, breaking on paragraph then sentence boundaries,
+ * with CHUNK_OVERLAP_CHARS carried over so facts spanning a boundary stay retrievable
+ */
+export const chunkText = text => {
+  const paragraphs = String(text || '')
+    .split(/\n\s*\n/)
+    .map(item => item.replace(/\s+/g, ' ').trim())
+    .filter(Boolean)
+  const pieces = paragraphs.flatMap(paragraph =>
+    paragraph.length <= CHUNK_CHARS
+      ? [paragraph]
+      : paragraph.match(/[^.!?]+[.!?]*\s*/g) || [paragraph],
+  )
+  const chunks = []
+  let current = ''
+  for (const piece of pieces) {
+    if (current && current.length + piece.length + 1 > CHUNK_CHARS) {
+      chunks.push(current.trim())
+      current = current.slice(-CHUNK_OVERLAP_CHARS)
+    }
+    current = current ? `${current} ${piece}` : piece
+    while (current.length > CHUNK_CHARS * 1.5) {
+      chunks.push(current.slice(0, CHUNK_CHARS).trim())
+      current = current.slice(CHUNK_CHARS - CHUNK_OVERLAP_CHARS)
+    }
+  }
+  if (current.trim()) chunks.push(current.trim())
+  return chunks
+}
+
+export const getDocument = id => readRecord(COLLECTION, id)
+
+/**
+ * Documents without their chunk bodies
+ * @param {Object} [filter]
+ * @param {string} [filter.siteId] Only documents ingested from this site
+ */
+export const listDocuments = ({ siteId } = {}) =>
+  listRecords(COLLECTION)
+    .filter(doc => !siteId || doc.source?.siteId === siteId)
+    .map(({ chunks, ...doc }) => ({ ...doc, chunk_count: chunks?.length || 0 }))
+    .sort((a, b) => String(b.updated_at).localeCompare(String(a.updated_at)))
+
+export const deleteDocument = id => deleteRecord(COLLECTION, id)
+
+/**
+ * Chunk, embed and store a document (replacing any previous version with the same id)
+ * @param {Object} args
+ * @param {string} args.id
+ * @param {string} args.title
+ * @param {string} [args.url]
+ * @param {string} args.text
+ * @param {Object} [args.source] Origin metadata, e.g. { type: 'site', siteId }
+ * @param {Object} [args.embedding] { apiKey, baseUrl, model } overrides for EMBEDDING_* env vars
+ */
+export const saveDocument = async ({ id, title, url, text, source, embedding, signal }) => {
+  const previous = getDocument(id)
+  const chunks = chunkText(text).map((chunk, index) => ({ index, text: chunk }))
+  const embeddingConfig = resolveEmbeddingConfig(embedding)
+  if (embeddingConfig && chunks.length) {
+    const vectors = await embedTexts(
+      chunks.map(chunk => `${title || ''}\n${chunk.text}`),
+      embeddingConfig,
+      { signal },
+    )
+    chunks.forEach((chunk, index) => {
+      chunk.vector = vectors[index]
+    })
+  }
+  const timestamp = new Date().toISOString()
+  return writeRecord(COLLECTION, id, {
+    id,
+    title: title || url || id,
+    url: url || null,
+    source: source || null,
+    hash: hashContent(text),
+    embedding_model: embeddingConfig?.model || null,
+    created_at: previous?.created_at || timestamp,
+    updated_at: timestamp,
+    chunks,
+  })
+}
+
+/**
+ * Extract, chunk and store an uploaded file; re-uploading a file with the same name replaces it
+ * @param {Object} args
+ * @param {Buffer} args.buffer File contents
+ * @param {string} [args.filename]
+ * @param {string} [args.mimeType]
+ * @param {string} [args.title] Defaults to the file name
+ * @param {string} [args.id] Document id (default: derived from the file name)
+ * @param {Object} [args.embedding] { provider, apiKey, baseUrl, model } overrides
+ */
+export const saveUploadedDocument = async ({
+  buffer,
+  filename,
+  mimeType,
+  title,
+  id,
+  embedding,
+  signal,
+}) => {
+  const { format, text } = extractDocumentText(buffer, { filename, mimeType })
+  const name = filename ? path.basename(filename) : null
+  return saveDocument({
+    id: id || documentIdFor(`upload:${name || hashContent(text)}`),
+    title: title || name || text.slice(0, 80),
+    text,
+    source: { type: 'upload', filename: name, format, bytes: buffer.length },
+    embedding,
+    signal,
+  })
+}
+
+const lexicalScore = (chunk, title, terms) => {
+  if (!terms.length) return 0
+  const titleTokens = new Set(tokenize(title))
+  const counts = tokenize(chunk.text).reduce(
+    (map, token) => map.set(token, (map.get(token) || 0) + 1),
+    new Map(),
+  )
+  let score = 0
+  for (const term of terms) {
+    if (titleTokens.has(term)) score += 1
+    const count = counts.get(term) || 0
+    if (count) score += 1 + Math.log(count)
+  }
+  return score / (terms.length * 3)
+}
+
+/**
+ * Ranked chunk search across stored documents
+ * @returns {Promise<Array<{document_id, title, url, chunk, text, score}>>}
+ */
+export const searchDocuments = async ({
+  query,
+  limit = DEFAULT_SEARCH_LIMIT,
+  siteId,
+  documentIds,
+  embedding,
+  signal,
+}) => {
+  const documents = listRecords(COLLECTION)
+    .filter(doc => !siteId || doc.source?.siteId === siteId)
+    .filter(doc => !documentIds?.length || documentIds.includes(doc.id))
+  const terms = Array.from(new Set(tokenize(query)))
+  const embeddingConfig = resolveEmbeddingConfig(embedding)
+  let queryVector = null
+  if (embeddingConfig && documents.some(doc => doc.embedding_model === embeddingConfig.model)) {
+    try {
+      ;[queryVector] = await embedTexts([query], embeddingConfig, { signal })
+    } catch (error) {
+      console.warn('[RAG] Query embedding failed, using lexical ranking:', error.message)
+    }
+  }
+
+  return documents
+    .flatMap(doc =>
+      (doc.chunks || []).map(chunk => {
+        const lexical = lexicalScore(chunk, doc.title, terms)
+        const semantic =
+          queryVector && chunk.vector && doc.embedding_model === embeddingConfig.model
+            ? cosineSimilarity(queryVector, chunk.vector)
+            : 0
+        return { doc, chunk, score: queryVector ? 0.7 * semantic + 0.3 * lexical : lexical }
+      }),
+    )
+    .filter(item => item.score > 0)
+    .sort((a, b) => b.score - a.score)
+    .slice(0, Math.max(1, Number(limit) || DEFAULT_SEARCH_LIMIT))
+    .map(({ doc, chunk, score }) => ({
+      document_id: doc.id,
+      title: doc.title,
+      url: doc.url,
+      chunk: chunk.index,
+      text: chunk.text,
+      score: Number(score.toFixed(4)),
+    }))
+}
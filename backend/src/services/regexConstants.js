@@ -1,2 +1,10 @@
 export const TIME_KEYWORDS_REGEX =
   /今天|今年|现在|本周|本月|最近|刚刚|明天|昨天|上周|上个月|去年|today|current|now|this week|this month|recently|tomorrow|yesterday|last week|last month|last year/i
+
+// Emoji, including skin tones, keycaps, flags, and the joiners/selectors that combine them
+// (©, ® and ™ are pictographic too, but read fine as text)
+const EMOJI_BASE = String.raw`(?:(?![©®™])\p{Extended_Pictographic}|\p{Regional_Indicator})`
+export const EMOJI_PATTERN = new RegExp(
+  String.raw`${EMOJI_BASE}(?:[\u{1F3FB}-\u{1F3FF}\u{FE0F}\u{20E3}]|\u{200D}${EMOJI_BASE})*`,
+  'gu',
+)
@@ -0,0 +1,147 @@
+/**
+ * Repository context service
+ * Read-only access to a local git repository inside the sandbox allowlist: list files, read
+ * files, grep, and recent commits, for "ask about my codebase" chats without indexing.
+ */
+
+import { execFile } from 'child_process'
+import fs from 'fs'
+import { promisify } from 'util'
+import { resolveSandboxedDir, resolveSandboxedPath } from '../utils/pathSandbox.js'
+import { assertRuntime } from './runtimeCapabilities.js'
+
+const execFileAsync = promisify(execFile)
+
+const GIT_TIMEOUT_MS = 10000
+const GIT_MAX_BUFFER = 4 * 1024 * 1024
+const MAX_LIST_FILES = 500
+const MAX_READ_CHARS = 40000
+const MAX_GREP_MATCHES = 100
+const MAX_COMMITS = 50
+
+export const REPO_ACTIONS = ['list_files', 'read_file', 'grep', 'recent_commits']
+
+const runGit = async (repoDir, args) => {
+  await assertRuntime('git')
+  try {
+    const { stdout } = await execFileAsync(
+      process.env.GIT_PATH || 'git',
+      ['-C', repoDir, ...args],
+      { timeout: GIT_TIMEOUT_MS, maxBuffer: GIT_MAX_BUFFER },
+    )
+    return stdout
+  } catch (error) {
+    // git grep exits with 1 when nothing matches
+    if (error.code === 1 && args[0] === 'grep') return ''
+    const message = String(error.stderr || error.message).trim().split('\n')[0]
+    throw new Error(`git ${args[0]} failed: ${message}`)
+  }
+}
+
+export const resolveRepo = async repoPath => {
+  const repoDir = resolveSandboxedDir(repoPath)
+  const inside = await runGit(repoDir, ['rev-parse', '--is-inside-work-tree']).catch(() => '')
+  if (inside.trim() !== 'true') throw new Error(`Not a git repository: ${repoPath}`)
+  return repoDir
+}
+
+/**
+ * Tracked plus untracked (non-ignored) files, relative to the repository root
+ */
+export const listRepoFiles = async (repoDir, subPath) => {
+  const args = ['ls-files', '--cached', '--others', '--exclude-standard']
+  if (subPath) args.push('--', subPath)
+  return (await runGit(repoDir, args)).split('\n').filter(Boolean)
+}
+
+const listFiles = async (repoDir, { path: subPath, limit = MAX_LIST_FILES }) => {
+  const files = await listRepoFiles(repoDir, subPath)
+  const max = Math.min(Number(limit) || MAX_LIST_FILES, MAX_LIST_FILES)
+  return { total: files.length, files: files.slice(0, max), truncated: files.length > max }
+}
+
+const readFile = (repoDir, { path: filePath, start_line: startLine, end_line: endLine }) => {
+  if (!filePath) throw new Error('path is required for read_file')
+  const target = resolveSandboxedPath(repoDir, filePath)
+  if (!fs.existsSync(target) || !fs.statSync(target).isFile()) {
+    throw new Error(`File not found: ${filePath}`)
+  }
+  const buffer = fs.readFileSync(target)
+  if (buffer.subarray(0, 8000).includes(0)) {
+    return { path: filePath, binary: true, bytes: buffer.length }
+  }
+  const lines = buffer.toString('utf8').split('\n')
+  const from = Math.max(1, Number(startLine) || 1)
+  const to = Math.min(lines.length, Number(endLine) || lines.length)
+  let content = lines.slice(from - 1, to).join('\n')
+  const truncated = content.length > MAX_READ_CHARS
+  if (truncated) content = content.slice(0, MAX_READ_CHARS)
+  return {
+    path: filePath,
+    start_line: from,
+    end_line: to,
+    total_lines: lines.length,
+    content,
+    truncated,
+  }
+}
+
+const grep = async (repoDir, { pattern, path: subPath, ignore_case: ignoreCase, limit }) => {
+  if (!pattern) throw new Error('pattern is required for grep')
+  const args = ['grep', '-n', '-I', '--untracked', '-E']
+  if (ignoreCase) args.push('-i')
+  args.push('-e', pattern)
+  if (subPath) args.push('--', subPath)
+  const lines = (await runGit(repoDir, args)).split('\n').filter(Boolean)
+  const max = Math.min(Number(limit) || MAX_GREP_MATCHES, MAX_GREP_MATCHES)
+  const matches = lines.slice(0, max).map(line => {
+    const [, file, lineNumber, text] = line.match(/^(.*?):(\d+):(.*)$/) || [null, '', '0', line]
+    return { file, line: Number(lineNumber), text: text.slice(0, 300) }
+  })
+  return { total: lines.length, matches, truncated: lines.length > max }
+}
+
+const recentCommits = async (repoDir, { path: subPath, limit = 10 }) => {
+  const count = Math.min(Number(limit) || 10, MAX_COMMITS)
+  const args = [
+    'log',
+    `-n${count}`,
+    '--date=iso-strict',
+    '--pretty=format:%H%x1f%an%x1f%ad%x1f%s',
+    '--shortstat',
+  ]
+  if (subPath) args.push('--', subPath)
+  const commits = []
+  for (const line of (await runGit(repoDir, args)).split('\n')) {
+    if (line.includes('\x1f')) {
+      const [hash, author, date, subject] = line.split('\x1f')
+      commits.push({ hash: hash.slice(0, 12), author, date, subject, stat: null })
+    } else if (line.trim() && commits.length) {
+      commits[commits.length - 1].stat = line.trim()
+    }
+  }
+  return { commits }
+}
+
+/**
+ * Run a read-only repository action
+ * @param {Object} args - { repo_path, action, path?, pattern?, start_line?, end_line?,
+ *   ignore_case?, limit? }
+ */
+export const queryRepository = async args => {
+  const { repo_path: repoPath, action } = args
+  if (!REPO_ACTIONS.includes(action)) {
+    throw new Error(`action must be one of: ${REPO_ACTIONS.join(', ')}`)
+  }
+  const repoDir = await resolveRepo(repoPath)
+  switch (action) {
+    case 'list_files':
+      return { repo: repoDir, ...(await listFiles(repoDir, args)) }
+    case 'read_file':
+      return { repo: repoDir, ...readFile(repoDir, args) }
+    case 'grep':
+      return { repo: repoDir, ...(await grep(repoDir, args)) }
+    default:
+      return { repo: repoDir, ...(await recentCommits(repoDir, args)) }
+  }
+}
@@ -0,0 +1,253 @@
+/**
+ * DOCX renderer
+ * Writes report Markdown blocks as a WordprocessingML document with real heading styles (so
+ * Word's navigation pane and table of contents pick them up), lists, quotes, code, tables, and
+ * hyperlinks.
+ */
+
+import { createZip } from '../../utils/zipWriter.js'
+import { parseMarkdownBlocks } from './markdownBlocks.js'
+
+const FONT = 'Calibri'
+const CODE_FONT = 'Consolas'
+const HEADING_COLOR = '1F3D73'
+const LINK_COLOR = '1A5CBF'
+
+// Characters XML 1.0 does not allow
+const INVALID_XML = /[^\x09\x0A\x0D\x20-\uD7FF\uE000-\uFFFD\u{10000}-\u{10FFFF}]/gu
+
+const escapeXml = value =>
+  String(value ?? '')
+    .replace(INVALID_XML, '')
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;')
+
+const NAMESPACES =
+  'xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" ' +
+  'xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"'
+
+const runXml = (text, { bold, italic, code, link } = {}) => {
+  const props = [
+    link ? '<w:rStyle w:val="Hyperlink"/>' : '',
+    code ? `<w:rFonts w:ascii="${CODE_FONT}" w:hAnsi="${CODE_FONT}"/>` : '',
+    bold ? '<w:b/>' : '',
+    italic ? '<w:i/>' : '',
+    code ? '<w:shd w:val="clear" w:color="auto" w:fill="F2F2F4"/>' : '',
+  ].join('')
+  return (
+    `<w:r>${props ? `<w:rPr>${props}</w:rPr>` : ''}` +
+    `<w:t xml:space="preserve">${escapeXml(text)}</w:t></w:r>`
+  )
+}
+
+class DocxBuilder {
+  constructor() {
+    this.body = []
+    this.links = new Map()
+  }
+
+  linkId(url) {
+    if (!this.links.has(url)) this.links.set(url, `rIdLink${this.links.size + 1}`)
+    return this.links.get(url)
+  }
+
+  runs(runs) {
+    return runs
+      .map(run =>
+        run.link
+          ? `<w:hyperlink r:id="${this.linkId(run.link)}">${runXml(run.text, run)}</w:hyperlink>`
+          : runXml(run.text, run),
+      )
+      .join('')
+  }
+
+  paragraph(runs, props = '') {
+    this.body.push(`<w:p>${props ? `<w:pPr>${props}</w:pPr>` : ''}${this.runs(runs)}</w:p>`)
+  }
+
+  block(block) {
+    switch (block.type) {
+      case 'heading':
+        return this.paragraph(block.runs, `<w:pStyle w:val="Heading${block.level}"/>`)
+      case 'list_item': {
+        const left = 360 * (block.depth + 1)
+        return this.paragraph(
+          [{ text: `${block.marker}\t` }, ...block.runs],
+          '<w:pStyle w:val="ListParagraph"/>' +
+            `<w:tabs><w:tab w:val="left" w:pos="${left}"/></w:tabs>` +
+            `<w:ind w:left="${left}" w:hanging="360"/>`,
+        )
+      }
+      case 'quote':
+        return this.paragraph(block.runs, '<w:pStyle w:val="Quote"/>')
+      case 'code': {
+        const lines = block.text.split('\n')
+        const runs = lines
+          .map((line, index) => `${index ? '<w:r><w:br/></w:r>' : ''}${runXml(line)}`)
+          .join('')
+        this.body.push(`<w:p><w:pPr><w:pStyle w:val="Code"/></w:pPr>${runs}</w:p>`)
+        return
+      }
+      case 'table':
+        return this.table(block)
+      case 'rule':
+        return this.body.push(
+          '<w:p><w:pPr><w:pBdr><w:bottom w:val="single" w:sz="6" w:space="1" ' +
+            'w:color="CCD1DB"/></w:pBdr></w:pPr></w:p>',
+        )
+      default:
+        return this.paragraph(block.runs)
+    }
+  }
+
+  table({ rows }) {
+    const columns = Math.max(...rows.map(row => row.length))
+    const border = side => `<w:${side} w:val="single" w:sz="4" w:space="0" w:color="CCD1DB"/>`
+    const rowsXml = rows.map((row, rowIndex) => {
+      const cells = Array.from({ length: columns }, (_, index) => {
+        const runs = (row[index] || []).map(run => (rowIndex ? run : { ...run, bold: true }))
+        const shading = rowIndex ? '' : '<w:shd w:val="clear" w:color="auto" w:fill="EBF0F7"/>'
+        return (
+          `<w:tc><w:tcPr><w:tcW w:w="0" w:type="auto"/>${shading}</w:tcPr>` +
+          `<w:p>${this.runs(runs)}</w:p></w:tc>`
+        )
+      })
+      const header = rowIndex ? '' : '<w:trPr><w:tblHeader/></w:trPr>'
+      return `<w:tr>${header}${cells.join('')}</w:tr>`
+    })
+    this.body.push(
+      '<w:tbl><w:tblPr><w:tblW w:w="5000" w:type="pct"/><w:tblBorders>' +
+        ['top', 'left', 'bottom', 'right', 'insideH', 'insideV'].map(border).join('') +
+        '</w:tblBorders><w:tblCellMar><w:left w:w="80" w:type="dxa"/>' +
+        '<w:right w:w="80" w:type="dxa"/></w:tblCellMar></w:tblPr>' +
+        `<w:tblGrid>${'<w:gridCol/>'.repeat(columns)}</w:tblGrid>${rowsXml.join('')}</w:tbl>`,
+    )
+    // Word merges adjacent tables; keep an empty paragraph between them
+    this.body.push('<w:p/>')
+  }
+
+  document() {
+    return (
+      `<?xml version="1.0" encoding="UTF-8" standalone="yes"?><w:document ${NAMESPACES}><w:body>` +
+      this.body.join('') +
+      '<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1134" w:right="1134" ' +
+      'w:bottom="1134" w:left="1134" w:header="567" w:footer="567" w:gutter="0"/></w:sectPr>' +
+      '</w:body></w:document>'
+    )
+  }
+
+  relationships() {
+    const links = Array.from(this.links.entries()).map(
+      ([url, id]) =>
+        `<Relationship Id="${id}" Type="http://schemas.openxmlformats.org/officeDocument/2006/` +
+        `relationships/hyperlink" Target="${escapeXml(url)}" TargetMode="External"/>`,
+    )
+    return (
+      '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>' +
+      '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">' +
+      '<Relationship Id="rIdStyles" Type="http://schemas.openxmlformats.org/officeDocument/2006/' +
+      'relationships/styles" Target="styles.xml"/>' +
+      `${links.join('')}</Relationships>`
+    )
+  }
+}
+
+const paragraphStyle = (id, name, { run = '', paragraph = '', basedOn = 'Normal' } = {}) =>
+  `<w:style w:type="paragraph" w:styleId="${id}"><w:name w:val="${name}"/>` +
+  `<w:basedOn w:val="${basedOn}"/><w:next w:val="Normal"/><w:qFormat/>` +
+  `<w:pPr>${paragraph}</w:pPr><w:rPr>${run}</w:rPr></w:style>`
+
+const HEADING_SIZES = [36, 30, 26, 24, 22, 22]
+
+const STYLES_XML =
+  `<?xml version="1.0" encoding="UTF-8" standalone="yes"?><w:styles ${NAMESPACES}>` +
+  '<w:docDefaults><w:rPrDefault><w:rPr>' +
+  `<w:rFonts w:ascii="${FONT}" w:hAnsi="${FONT}" w:eastAsia="${FONT}" w:cs="${FONT}"/>` +
+  '<w:sz w:val="22"/><w:szCs w:val="22"/></w:rPr></w:rPrDefault>' +
+  '<w:pPrDefault><w:pPr><w:spacing w:after="120" w:line="288" w:lineRule="auto"/></w:pPr>' +
+  '</w:pPrDefault></w:docDefaults>' +
+  '<w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/>' +
+  '<w:qFormat/></w:style>' +
+  HEADING_SIZES.map((size, index) =>
+    paragraphStyle(`Heading${index + 1}`, `heading ${index + 1}`, {
+      paragraph:
+        '<w:keepNext/>' +
+        (index < 2
+          ? '<w:pBdr><w:bottom w:val="single" w:sz="4" w:space="2" w:color="CCD1DB"/></w:pBdr>'
+          : '') +
+        `<w:spacing w:before="${index < 2 ? 360 : 240}" w:after="120"/>` +
+        `<w:outlineLvl w:val="${index}"/>`,
+      run:
+        `<w:b/><w:color w:val="${HEADING_COLOR}"/>` +
+        `<w:sz w:val="${size}"/><w:szCs w:val="${size}"/>`,
+    }),
+  ).join('') +
+  paragraphStyle('ListParagraph', 'List Paragraph', { paragraph: '<w:spacing w:after="60"/>' }) +
+  paragraphStyle('Quote', 'Quote', {
+    paragraph:
+      '<w:pBdr><w:left w:val="single" w:sz="18" w:space="8" w:color="CCD1DB"/></w:pBdr>' +
+      '<w:ind w:left="284"/>',
+    run: '<w:i/><w:color w:val="6B7079"/>',
+  }) +
+  paragraphStyle('Code', 'Code', {
+    paragraph:
+      '<w:shd w:val="clear" w:color="auto" w:fill="F2F2F4"/><w:spacing w:line="240" ' +
+      'w:lineRule="auto"/>',
+    run: `<w:rFonts w:ascii="${CODE_FONT}" w:hAnsi="${CODE_FONT}"/><w:sz w:val="18"/>`,
+  }) +
+  '<w:style w:type="character" w:styleId="Hyperlink"><w:name w:val="Hyperlink"/>' +
+  `<w:rPr><w:color w:val="${LINK_COLOR}"/><w:u w:val="single"/></w:rPr></w:style>` +
+  '</w:styles>'
+
+const CONTENT_TYPES_XML =
+  '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>' +
+  '<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">' +
+  '<Default Extension="rels" ContentType="application/' +
+  'vnd.openxmlformats-package.relationships+xml"/>' +
+  '<Default Extension="xml" ContentType="application/xml"/>' +
+  '<Override PartName="/word/document.xml" ContentType="application/' +
+  'vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>' +
+  '<Override PartName="/word/styles.xml" ContentType="application/' +
+  'vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>' +
+  '<Override PartName="/docProps/core.xml" ContentType="application/' +
+  'vnd.openxmlformats-package.core-properties+xml"/></Types>'
+
+const ROOT_RELS_XML =
+  '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>' +
+  '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">' +
+  '<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/' +
+  'relationships/officeDocument" Target="word/document.xml"/>' +
+  '<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/' +
+  'metadata/core-properties" Target="docProps/core.xml"/></Relationships>'
+
+const coreXml = title =>
+  '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>' +
+  '<cp:coreProperties ' +
+  'xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" ' +
+  'xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:dcterms="http://purl.org/dc/terms/" ' +
+  'xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">' +
+  `<dc:title>${escapeXml(title || 'Research report')}</dc:title><dc:creator>Qurio</dc:creator>` +
+  `<dcterms:created xsi:type="dcterms:W3CDTF">${new Date().toISOString()}</dcterms:created>` +
+  '</cp:coreProperties>'
+
+/**
+ * Render report Markdown as a .docx file
+ * @param {string} markdown
+ * @param {Object} [options]
+ * @param {string} [options.title] - Document title property
+ * @returns {Buffer}
+ */
+export const renderDocx = (markdown, { title } = {}) => {
+  const builder = new DocxBuilder()
+  for (const block of parseMarkdownBlocks(markdown)) builder.block(block)
+  return createZip([
+    { name: '[Content_Types].xml', data: CONTENT_TYPES_XML },
+    { name: '_rels/.rels', data: ROOT_RELS_XML },
+    { name: 'docProps/core.xml', data: coreXml(title) },
+    { name: 'word/document.xml', data: builder.document() },
+    { name: 'word/styles.xml', data: STYLES_XML },
+    { name: 'word/_rels/document.xml.rels', data: builder.relationships() },
+  ])
+}
@@ -0,0 +1,110 @@
+/**
+ * Research report export
+ * Turns a finished report (Markdown plus its sources) into a downloadable file: Markdown with a
+ * references section, a styled PDF, or a DOCX document. The PDF and DOCX are rendered from the
+ * same Markdown, so all three formats carry the same content.
+ */
+
+import { ErrorCode, QurioError } from '../../utils/errors.js'
+import { REFERENCES_HEADING } from '../citationFormatService.js'
+import { getResearchRun } from '../researchRunStore.js'
+import { renderDocx } from './docxRenderer.js'
+import { renderPdf } from './pdfRenderer.js'
+
+export const EXPORT_FORMATS = {
+  markdown: { extension: 'md', mimeType: 'text/markdown; charset=utf-8' },
+  pdf: { extension: 'pdf', mimeType: 'application/pdf' },
+  docx: {
+    extension: 'docx',
+    mimeType: 'application/vnd.openxmlformats-officedocument.wordprocessingml.document',
+  },
+}
+
+const FORMAT_ALIASES = { md: 'markdown' }
+
+export const resolveExportFormat = format => {
+  const key = String(format || 'markdown').toLowerCase()
+  return EXPORT_FORMATS[key] ? key : FORMAT_ALIASES[key] || null
+}
+
+const formatReference = (source, index) => {
+  const url = source?.url || source?.uri || ''
+  const title = String(source?.title || url || 'Untitled source').replace(/[[\]]/g, '')
+  return `${index + 1}. ${url ? `[${title}](${url})` : title}`
+}
+
+/**
+ * Report Markdown with a title heading and a numbered references section matching the [n]
+ * citations (source n is the n-th entry of sources)
+ */
+export const buildReportMarkdown = ({ content, sources = [], title }) => {
+  let markdown = String(content || '').trim()
+  if (title && !/^#\s/.test(markdown)) markdown = `# ${title}\n\n${markdown}`
+  const references = (Array.isArray(sources) ? sources : []).filter(
+    source => source?.url || source?.uri || source?.title,
+  )
+  // Reports that already have their own reference list (or a citation_style one) keep it
+  if (references.length && !REFERENCES_HEADING.test(markdown)) {
+    markdown += `\n\n## References\n\n${references.map(formatReference).join('\n')}`
+  }
+  return `${markdown}\n`
+}
+
+const toFilename = (title, extension) => {
+  const base =
+    String(title || 'research-report')
+      .replace(/[\\/:*?"<>|\x00-\x1f]+/g, ' ')
+      .replace(/\s+/g, ' ')
+      .trim()
+      .slice(0, 80) || 'research-report'
+  return `${base}.${extension}`
+}
+
+/**
+ * Render a report in an export format
+ * @param {Object} args
+ * @param {string} args.format - markdown (md) | pdf | docx
+ * @param {string} args.content - Final report Markdown
+ * @param {Array<{title, url}>} [args.sources] - Sources in citation order
+ * @param {string} [args.title] - Document title (also names the file)
+ * @returns {{ buffer: Buffer, filename: string, mimeType: string }}
+ */
+export const exportReport = ({ format, content, sources, title }) => {
+  const key = resolveExportFormat(format)
+  if (!key) {
+    throw new QurioError(
+      ErrorCode.InvalidRequest,
+      `format must be one of: ${Object.keys(EXPORT_FORMATS).join(', ')}`,
+    )
+  }
+  if (!String(content || '').trim()) {
+    throw new QurioError(ErrorCode.InvalidRequest, 'Missing required field: content')
+  }
+  const markdown = buildReportMarkdown({ content, sources, title })
+  const { extension, mimeType } = EXPORT_FORMATS[key]
+  const buffer =
+    key === 'pdf'
+      ? renderPdf(markdown, { title })
+      : key === 'docx'
+        ? renderDocx(markdown, { title })
+        : Buffer.from(markdown, 'utf8')
+  return { buffer, filename: toFilename(title, extension), mimeType }
+}
+
+/**
+ * Export a persisted deep research run (its final report, sources, and question as the title)
+ */
+export const exportResearchRun = (runId, format) => {
+  const run = getResearchRun(runId)
+  if (!run) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Research run not found: ${runId}`, {
+      status: 404,
+    })
+  }
+  if (run.status !== 'done' || !run.content) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Research run has no final report: ${runId}`, {
+      status: 409,
+    })
+  }
+  return exportReport({ format, content: run.content, sources: run.sources, title: run.question })
+}
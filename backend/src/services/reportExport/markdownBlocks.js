@@ -0,0 +1,146 @@
+/**
+ * Markdown blocks
+ * Parses report Markdown into the blocks and inline runs the PDF and DOCX renderers lay out.
+ * Covers what research reports use: headings, paragraphs, lists, block quotes, code blocks,
+ * tables, and rules; emphasis, inline code, and links inside text.
+ */
+
+const INLINE_PATTERN =
+  /(`+)([\s\S]+?)\1|\*\*([\s\S]+?)\*\*|__([\s\S]+?)__|\*([^*\s][^*]*?)\*|(?<![\w])_([^_\s][^_]*?)_(?![\w])|!?\[([^\]]*)\]\(([^)\s]+)(?:\s+"[^"]*")?\)|<(https?:\/\/[^>\s]+)>/g
+
+/**
+ * Inline Markdown to styled runs
+ * @returns {Array<{ text: string, bold?: boolean, italic?: boolean, code?: boolean,
+ *   link?: string }>}
+ */
+export const parseInlineRuns = (text, style = {}) => {
+  const runs = []
+  const push = (value, extra = {}) => {
+    if (value) runs.push({ ...style, ...extra, text: value })
+  }
+  let lastIndex = 0
+  for (const match of String(text || '').matchAll(INLINE_PATTERN)) {
+    push(match.input.slice(lastIndex, match.index))
+    lastIndex = match.index + match[0].length
+    const [, , code, bold, boldAlt, italic, italicAlt, label, href, autolink] = match
+    if (code !== undefined) push(code.trim(), { code: true })
+    else if (bold !== undefined || boldAlt !== undefined) {
+      runs.push(...parseInlineRuns(bold ?? boldAlt, { ...style, bold: true }))
+    } else if (italic !== undefined || italicAlt !== undefined) {
+      runs.push(...parseInlineRuns(italic ?? italicAlt, { ...style, italic: true }))
+    } else if (autolink !== undefined) push(autolink, { link: autolink })
+    else if (match[0].startsWith('!')) push(label || href, { link: href })
+    else runs.push(...parseInlineRuns(label || href, { ...style, link: href }))
+  }
+  push(String(text || '').slice(lastIndex))
+  return runs
+}
+
+const HEADING = /^(#{1,6})\s+(.*?)\s*#*\s*$/
+const FENCE = /^\s*(```|~~~)/
+const RULE = /^\s*([-*_])(\s*\1){2,}\s*$/
+const LIST_ITEM = /^(\s*)([-*+]|\d+[.)])\s+(.*)$/
+const QUOTE = /^\s*>\s?(.*)$/
+const TABLE_ROW = /^\s*\|.*\|\s*$/
+const TABLE_DIVIDER = /^\s*\|?\s*:?-{2,}:?\s*(\|\s*:?-{2,}:?\s*)*\|?\s*$/
+
+const splitTableRow = line =>
+  line
+    .trim()
+    .replace(/^\||\|$/g, '')
+    .split(/(?<!\\)\|/)
+    .map(cell => cell.trim().replace(/\\\|/g, '|'))
+
+/**
+ * @returns {Array<Object>} Blocks: heading { level, runs }, paragraph { runs },
+ *   list_item { ordered, marker, depth, runs }, quote { runs }, code { text }, table { rows },
+ *   rule
+ */
+export const parseMarkdownBlocks = markdown => {
+  const lines = String(markdown || '')
+    .replace(/\r\n?/g, '\n')
+    .split('\n')
+  const blocks = []
+  let paragraph = []
+
+  const flushParagraph = () => {
+    if (paragraph.length) {
+      blocks.push({ type: 'paragraph', runs: parseInlineRuns(paragraph.join(' ')) })
+    }
+    paragraph = []
+  }
+
+  for (let index = 0; index < lines.length; index += 1) {
+    const line = lines[index]
+    if (!line.trim()) {
+      flushParagraph()
+      continue
+    }
+    if (FENCE.test(line)) {
+      flushParagraph()
+      const fence = line.trim().slice(0, 3)
+      const code = []
+      for (index += 1; index < lines.length && !lines[index].trim().startsWith(fence); index += 1) {
+        code.push(lines[index])
+      }
+      blocks.push({ type: 'code', text: code.join('\n') })
+      continue
+    }
+    const heading = line.match(HEADING)
+    if (heading) {
+      flushParagraph()
+      blocks.push({ type: 'heading', level: heading[1].length, runs: parseInlineRuns(heading[2]) })
+      continue
+    }
+    if (RULE.test(line)) {
+      flushParagraph()
+      blocks.push({ type: 'rule' })
+      continue
+    }
+    if (TABLE_ROW.test(line) && TABLE_DIVIDER.test(lines[index + 1] || '')) {
+      flushParagraph()
+      const rows = [splitTableRow(line)]
+      for (index += 2; index < lines.length && TABLE_ROW.test(lines[index]); index += 1) {
+        rows.push(splitTableRow(lines[index]))
+      }
+      index -= 1
+      blocks.push({ type: 'table', rows: rows.map(row => row.map(cell => parseInlineRuns(cell))) })
+      continue
+    }
+    const item = line.match(LIST_ITEM)
+    if (item) {
+      flushParagraph()
+      const ordered = /\d/.test(item[2])
+      blocks.push({
+        type: 'list_item',
+        ordered,
+        marker: ordered ? `${Number.parseInt(item[2], 10)}.` : '•',
+        depth: Math.floor(item[1].replace(/\t/g, '    ').length / 2),
+        runs: parseInlineRuns(item[3]),
+      })
+      continue
+    }
+    const quote = line.match(QUOTE)
+    if (quote) {
+      flushParagraph()
+      const text = [quote[1]]
+      while (index + 1 < lines.length && QUOTE.test(lines[index + 1])) {
+        index += 1
+        text.push(lines[index].match(QUOTE)[1])
+      }
+      blocks.push({ type: 'quote', runs: parseInlineRuns(text.join(' ')) })
+      continue
+    }
+    // Continuation lines of a list item belong to it
+    const previous = blocks[blocks.length - 1]
+    if (!paragraph.length && previous?.type === 'list_item' && /^\s+\S/.test(line)) {
+      previous.runs.push(...parseInlineRuns(` ${line.trim()}`))
+      continue
+    }
+    paragraph.push(line.trim())
+  }
+  flushParagraph()
+  return blocks
+}
+
+export const runsToText = runs => runs.map(run => run.text).join('')
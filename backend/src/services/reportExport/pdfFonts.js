@@ -0,0 +1,207 @@
+/**
+ * PDF fonts
+ * Metrics and text encoding for the report PDF: the standard Helvetica/Courier faces
+ * (WinAnsiEncoding, Latin scripts only) or, with REPORT_PDF_FONT, an embedded TrueType font that
+ * covers any script it has glyphs for (e.g. a CJK font such as Noto Sans SC).
+ */
+
+import fs from 'fs'
+import path from 'path'
+
+// Helvetica advance widths (1/1000 em) of ASCII 32-126
+const HELVETICA_WIDTHS = [
+  278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+  556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+  611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+  667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+  222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+]
+const BOLD_WIDTH_FACTOR = 1.06
+const COURIER_WIDTH = 600
+
+// WinAnsiEncoding bytes 0x80-0x9F
+const WIN_ANSI_EXTRAS = {
+  '€': 0x80,
+  '‚': 0x82,
+  '„': 0x84,
+  '…': 0x85,
+  '†': 0x86,
+  '‡': 0x87,
+  '‰': 0x89,
+  '‹': 0x8b,
+  '‘': 0x91,
+  '’': 0x92,
+  '“': 0x93,
+  '”': 0x94,
+  '•': 0x95,
+  '–': 0x96,
+  '—': 0x97,
+  '™': 0x99,
+  '›': 0x9b,
+}
+
+const toWinAnsi = char => {
+  const code = char.codePointAt(0)
+  if ((code >= 0x20 && code < 0x7f) || (code >= 0xa0 && code <= 0xff)) return code
+  return WIN_ANSI_EXTRAS[char] ?? 0x3f
+}
+
+const hex = (value, digits) => value.toString(16).padStart(digits, '0')
+
+const createStandardFont = (name, baseFont, { bold = false, mono = false } = {}) => ({
+  name,
+  baseFont,
+  charWidth(char) {
+    if (mono) return COURIER_WIDTH
+    const code = toWinAnsi(char)
+    const width = code >= 32 && code < 127 ? HELVETICA_WIDTHS[code - 32] : 556
+    return bold ? width * BOLD_WIDTH_FACTOR : width
+  },
+  measure(text, size) {
+    let width = 0
+    for (const char of text) width += this.charWidth(char)
+    return (width * size) / 1000
+  },
+  encode: text => `<${Array.from(text, char => hex(toWinAnsi(char), 2)).join('')}>`,
+})
+
+/**
+ * TrueType tables needed to embed a font as a CIDFontType2 with Identity-H encoding
+ */
+const parseTrueType = buffer => {
+  const signature = buffer.toString('latin1', 0, 4)
+  if (signature === 'ttcf' || signature === 'OTTO') {
+    throw new Error('REPORT_PDF_FONT must be a TrueType (.ttf) font, not a collection or CFF font')
+  }
+  const tables = {}
+  for (let index = 0; index < buffer.readUInt16BE(4); index += 1) {
+    const offset = 12 + index * 16
+    tables[buffer.toString('latin1', offset, offset + 4)] = buffer.readUInt32BE(offset + 8)
+  }
+  for (const tag of ['head', 'hhea', 'hmtx', 'cmap']) {
+    if (tables[tag] === undefined) throw new Error(`REPORT_PDF_FONT has no ${tag} table`)
+  }
+  const { head, hhea, hmtx, cmap } = tables
+  const unitsPerEm = buffer.readUInt16BE(head + 18)
+  const scale = value => Math.round((value * 1000) / unitsPerEm)
+  const metricsCount = buffer.readUInt16BE(hhea + 34)
+
+  // Prefer the full Unicode subtable (format 12), then the BMP one (format 4)
+  const subtables = Array.from({ length: buffer.readUInt16BE(cmap + 2) }, (_, index) => {
+    const offset = cmap + 4 + index * 8
+    const start = cmap + buffer.readUInt32BE(offset + 4)
+    return { format: buffer.readUInt16BE(start), start }
+  })
+  const subtable =
+    subtables.find(table => table.format === 12) || subtables.find(table => table.format === 4)
+  if (!subtable) throw new Error('REPORT_PDF_FONT has no Unicode cmap')
+
+  const lookupFormat12 = codePoint => {
+    const groups = buffer.readUInt32BE(subtable.start + 12)
+    for (let index = 0; index < groups; index += 1) {
+      const offset = subtable.start + 16 + index * 12
+      const start = buffer.readUInt32BE(offset)
+      if (codePoint < start) return 0
+      if (codePoint <= buffer.readUInt32BE(offset + 4)) {
+        return buffer.readUInt32BE(offset + 8) + codePoint - start
+      }
+    }
+    return 0
+  }
+
+  const lookupFormat4 = codePoint => {
+    if (codePoint > 0xffff) return 0
+    const segments = buffer.readUInt16BE(subtable.start + 6) / 2
+    const endCodes = subtable.start + 14
+    const startCodes = endCodes + segments * 2 + 2
+    const deltas = startCodes + segments * 2
+    const rangeOffsets = deltas + segments * 2
+    for (let index = 0; index < segments; index += 1) {
+      if (codePoint > buffer.readUInt16BE(endCodes + index * 2)) continue
+      const start = buffer.readUInt16BE(startCodes + index * 2)
+      if (codePoint < start) return 0
+      const delta = buffer.readInt16BE(deltas + index * 2)
+      const rangeOffset = buffer.readUInt16BE(rangeOffsets + index * 2)
+      if (!rangeOffset) return (codePoint + delta) & 0xffff
+      const glyph = buffer.readUInt16BE(
+        rangeOffsets + index * 2 + rangeOffset + (codePoint - start) * 2,
+      )
+      return glyph ? (glyph + delta) & 0xffff : 0
+    }
+    return 0
+  }
+
+  return {
+    glyphFor: subtable.format === 12 ? lookupFormat12 : lookupFormat4,
+    advance: glyph => scale(buffer.readUInt16BE(hmtx + Math.min(glyph, metricsCount - 1) * 4)),
+    ascent: scale(buffer.readInt16BE(hhea + 4)),
+    descent: scale(buffer.readInt16BE(hhea + 6)),
+    bbox: [36, 38, 40, 42].map(offset => scale(buffer.readInt16BE(head + offset))),
+  }
+}
+
+const createTrueTypeFont = (name, fontPath) => {
+  const data = fs.readFileSync(fontPath)
+  const metrics = parseTrueType(data)
+  const glyphs = new Map()
+  // Glyphs drawn so far, for the width array and the ToUnicode map
+  const used = new Map()
+
+  const glyphOf = char => {
+    if (!glyphs.has(char)) glyphs.set(char, metrics.glyphFor(char.codePointAt(0)))
+    return glyphs.get(char)
+  }
+
+  return {
+    name,
+    embedded: true,
+    data,
+    metrics,
+    used,
+    baseFont: path
+      .basename(fontPath)
+      .replace(/\.[^.]+$/, '')
+      .replace(/[^A-Za-z0-9-]/g, ''),
+    measure(text, size) {
+      let width = 0
+      for (const char of text) width += metrics.advance(glyphOf(char))
+      return (width * size) / 1000
+    },
+    encode(text) {
+      const codes = Array.from(text, char => {
+        const glyph = glyphOf(char)
+        if (glyph && !used.has(glyph)) used.set(glyph, char)
+        return hex(glyph, 4)
+      })
+      return `<${codes.join('')}>`
+    },
+  }
+}
+
+/**
+ * Fonts of one PDF: fontFor(run) picks the face of a styled run. With an embedded font every run
+ * uses it and bold/italic are synthesized by the renderer.
+ */
+export const createPdfFonts = () => {
+  const fontPath = process.env.REPORT_PDF_FONT
+  if (fontPath) {
+    const font = createTrueTypeFont('F1', fontPath)
+    return { fonts: [font], synthetic: true, fontFor: () => font }
+  }
+  const faces = {
+    regular: createStandardFont('F1', 'Helvetica'),
+    bold: createStandardFont('F2', 'Helvetica-Bold', { bold: true }),
+    italic: createStandardFont('F3', 'Helvetica-Oblique'),
+    boldItalic: createStandardFont('F4', 'Helvetica-BoldOblique', { bold: true }),
+    code: createStandardFont('F5', 'Courier', { mono: true }),
+  }
+  return {
+    fonts: Object.values(faces),
+    synthetic: false,
+    fontFor: ({ bold, italic, code } = {}) => {
+      if (code) return faces.code
+      if (bold) return italic ? faces.boldItalic : faces.bold
+      return italic ? faces.italic : faces.regular
+    },
+  }
+}
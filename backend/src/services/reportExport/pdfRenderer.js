@@ -0,0 +1,469 @@
+/**
+ * PDF renderer
+ * Lays out report Markdown blocks on A4 pages (wrapped text, styled headings, lists, quotes,
+ * code and tables, clickable links, page numbers) and serializes a PDF 1.7 file.
+ */
+
+import zlib from 'zlib'
+import { parseMarkdownBlocks } from './markdownBlocks.js'
+import { createPdfFonts } from './pdfFonts.js'
+
+const PAGE_WIDTH = 595.28
+const PAGE_HEIGHT = 841.89
+const MARGIN = 56
+const CONTENT_WIDTH = PAGE_WIDTH - MARGIN * 2
+const FOOTER_Y = 32
+
+const BODY_SIZE = 10.5
+const CODE_SIZE = 9
+const TABLE_SIZE = 9.5
+const LINE_FACTOR = 1.45
+const HEADING_SIZES = [20, 16, 13.5, 12, 11, 11]
+const LIST_INDENT = 16
+
+const COLORS = {
+  text: [0.13, 0.13, 0.15],
+  heading: [0.1, 0.24, 0.45],
+  muted: [0.42, 0.44, 0.48],
+  link: [0.1, 0.36, 0.75],
+  rule: [0.8, 0.82, 0.86],
+  codeBackground: [0.95, 0.95, 0.96],
+  tableHeader: [0.92, 0.94, 0.97],
+}
+
+// CJK characters may break anywhere; other text breaks at whitespace
+const CJK = '\u2E80-\u9FFF\uAC00-\uD7AF\uF900-\uFAFF\uFF00-\uFFEF'
+const BREAK_TOKENS = new RegExp(`[${CJK}]|[^\\s${CJK}]+|\\s+`, 'g')
+
+const num = value => Number(value.toFixed(2))
+const color = ([r, g, b], op) => `${r} ${g} ${b} ${op}`
+
+// Hard-wrap a line (code) at the last character that fits
+const fitToWidth = (text, font, size, width) => {
+  const lines = ['']
+  for (const char of text) {
+    const current = lines[lines.length - 1]
+    if (current && font.measure(current + char, size) > width) lines.push(char)
+    else lines[lines.length - 1] = current + char
+  }
+  return lines
+}
+
+class PdfLayout {
+  constructor(fonts) {
+    this.fonts = fonts
+    this.pages = []
+    this.newPage()
+  }
+
+  newPage() {
+    this.page = { ops: [], links: [] }
+    this.pages.push(this.page)
+    this.y = PAGE_HEIGHT - MARGIN
+  }
+
+  ensure(height) {
+    if (this.y - height < MARGIN && this.y < PAGE_HEIGHT - MARGIN) this.newPage()
+  }
+
+  gap(height) {
+    if (this.y < PAGE_HEIGHT - MARGIN) this.y -= height
+  }
+
+  fillRect(x, y, width, height, fill) {
+    this.page.ops.push(`${color(fill, 'rg')} ${num(x)} ${num(y)} ${num(width)} ${num(height)} re f`)
+  }
+
+  line(x1, y1, x2, y2, stroke, width = 0.6) {
+    this.page.ops.push(
+      `${color(stroke, 'RG')} ${width} w ${num(x1)} ${num(y1)} m ${num(x2)} ${num(y2)} l S`,
+    )
+  }
+
+  text(value, x, y, { font, size, fill = COLORS.text, bold = false, italic = false }) {
+    const synthetic = this.fonts.synthetic
+    const skew = synthetic && italic ? 0.2 : 0
+    // Synthetic bold strokes the glyph outlines; the render mode outlives the text object
+    let mode = ''
+    if (synthetic) mode = bold ? `2 Tr ${num(size * 0.03)} w ${color(fill, 'RG')} ` : '0 Tr '
+    this.page.ops.push(
+      `BT /${font.name} ${size} Tf ${color(fill, 'rg')} ${mode}` +
+        `1 0 ${skew} 1 ${num(x)} ${num(y)} Tm ${font.encode(value)} Tj ET`,
+    )
+  }
+
+  /**
+   * Break styled runs into lines no wider than width
+   * @returns {Array<Array<{ text, run, font, x, width }>>}
+   */
+  wrap(runs, size, width) {
+    const lines = []
+    let line = []
+    let lineWidth = 0
+    const breakLine = () => {
+      lines.push(line)
+      line = []
+      lineWidth = 0
+    }
+    const place = (text, run, font) => {
+      const pieceWidth = font.measure(text, size)
+      const last = line[line.length - 1]
+      if (last?.run === run) {
+        last.text += text
+        last.width += pieceWidth
+      } else {
+        line.push({ text, run, font, x: lineWidth, width: pieceWidth })
+      }
+      lineWidth += pieceWidth
+    }
+
+    for (const run of runs) {
+      const font = this.fonts.fontFor(run)
+      for (const token of run.text.match(BREAK_TOKENS) || []) {
+        if (token.includes('\n')) {
+          breakLine()
+          continue
+        }
+        const blank = !token.trim()
+        if (blank && !line.length) continue
+        const tokenWidth = font.measure(token, size)
+        if (!blank && lineWidth + tokenWidth > width && line.length) breakLine()
+        if (tokenWidth <= width) {
+          place(token, run, font)
+          continue
+        }
+        // Longer than a whole line (URLs): split anywhere
+        for (const char of token) {
+          if (lineWidth + font.measure(char, size) > width && line.length) breakLine()
+          place(char, run, font)
+        }
+      }
+    }
+    if (line.length) lines.push(line)
+    return lines
+  }
+
+  /**
+   * Draw wrapped runs, starting new pages as needed
+   */
+  paragraph(runs, { size = BODY_SIZE, x = MARGIN, width = CONTENT_WIDTH, fill, style } = {}) {
+    const leading = size * LINE_FACTOR
+    const lines = this.wrap(style ? runs.map(run => ({ ...run, ...style })) : runs, size, width)
+    for (const line of lines) {
+      this.ensure(leading)
+      this.y -= leading
+      const baseline = this.y + (leading - size) / 2
+      for (const piece of line) {
+        const { run } = piece
+        const pieceFill = run.link ? COLORS.link : fill || COLORS.text
+        this.text(piece.text, x + piece.x, baseline, {
+          font: piece.font,
+          size,
+          fill: pieceFill,
+          bold: run.bold,
+          italic: run.italic,
+        })
+        if (run.link) {
+          this.page.links.push({
+            rect: [x + piece.x, baseline - 2, x + piece.x + piece.width, baseline + size],
+            url: run.link,
+          })
+        }
+      }
+    }
+    return lines.length * leading
+  }
+
+  heading({ level, runs }) {
+    const size = HEADING_SIZES[level - 1]
+    this.gap(level <= 2 ? 14 : 9)
+    this.ensure(size * LINE_FACTOR * 2)
+    this.paragraph(runs, { size, fill: COLORS.heading, style: { bold: true } })
+    if (level <= 2) {
+      this.y -= 3
+      this.line(MARGIN, this.y, MARGIN + CONTENT_WIDTH, this.y, COLORS.rule, level === 1 ? 1 : 0.6)
+    }
+    this.y -= 6
+  }
+
+  listItem({ marker, depth, runs }) {
+    const indent = MARGIN + LIST_INDENT * (depth + 1)
+    const markerWidth = marker === '•' ? 10 : 18
+    const leading = BODY_SIZE * LINE_FACTOR
+    this.ensure(leading)
+    const font = this.fonts.fontFor({})
+    this.text(marker, indent - markerWidth, this.y - leading + (leading - BODY_SIZE) / 2, {
+      font,
+      size: BODY_SIZE,
+    })
+    this.paragraph(runs, { x: indent, width: MARGIN + CONTENT_WIDTH - indent })
+    this.y -= 3
+  }
+
+  quote({ runs }) {
+    const top = this.y
+    const page = this.page
+    this.paragraph(runs, {
+      x: MARGIN + 14,
+      width: CONTENT_WIDTH - 14,
+      fill: COLORS.muted,
+      style: { italic: true },
+    })
+    // The bar only spans the part on the current page
+    const barTop = page === this.page ? top : PAGE_HEIGHT - MARGIN
+    this.fillRect(MARGIN + 2, this.y, 3, barTop - this.y, COLORS.rule)
+    this.y -= 7
+  }
+
+  code({ text }) {
+    const leading = CODE_SIZE * LINE_FACTOR
+    const font = this.fonts.fontFor({ code: true })
+    const lines = text
+      .replace(/\t/g, '  ')
+      .split('\n')
+      .flatMap(line => fitToWidth(line, font, CODE_SIZE, CONTENT_WIDTH - 16))
+    this.gap(2)
+    for (const line of lines) {
+      this.ensure(leading)
+      this.y -= leading
+      this.fillRect(MARGIN, this.y, CONTENT_WIDTH, leading, COLORS.codeBackground)
+      const baseline = this.y + (leading - CODE_SIZE) / 2 + 1
+      this.text(line, MARGIN + 8, baseline, { font, size: CODE_SIZE })
+    }
+    this.y -= 8
+  }
+
+  table({ rows }) {
+    const columns = Math.max(...rows.map(row => row.length))
+    const cellWidth = CONTENT_WIDTH / columns
+    const padding = 4
+    const leading = TABLE_SIZE * LINE_FACTOR
+    this.gap(2)
+    rows.forEach((row, rowIndex) => {
+      const style = rowIndex === 0 ? { bold: true } : undefined
+      const cells = Array.from({ length: columns }, (_, index) =>
+        this.wrap(
+          (row[index] || []).map(run => ({ ...run, ...style })),
+          TABLE_SIZE,
+          cellWidth - padding * 2,
+        ),
+      )
+      const height = Math.max(1, ...cells.map(lines => lines.length)) * leading + padding * 2
+      this.ensure(height)
+      const top = this.y
+      if (rowIndex === 0) {
+        this.fillRect(MARGIN, top - height, CONTENT_WIDTH, height, COLORS.tableHeader)
+      }
+      cells.forEach((lines, index) => {
+        const x = MARGIN + index * cellWidth + padding
+        lines.forEach((line, lineIndex) => {
+          const baseline = top - padding - (lineIndex + 1) * leading + (leading - TABLE_SIZE) / 2
+          for (const piece of line) {
+            this.text(piece.text, x + piece.x, baseline, {
+              font: piece.font,
+              size: TABLE_SIZE,
+              fill: piece.run.link ? COLORS.link : COLORS.text,
+              bold: piece.run.bold,
+              italic: piece.run.italic,
+            })
+          }
+        })
+      })
+      this.line(MARGIN, top - height, MARGIN + CONTENT_WIDTH, top - height, COLORS.rule)
+      if (rowIndex === 0) this.line(MARGIN, top, MARGIN + CONTENT_WIDTH, top, COLORS.rule)
+      this.y = top - height
+    })
+    this.y -= 8
+  }
+
+  block(block) {
+    switch (block.type) {
+      case 'heading':
+        return this.heading(block)
+      case 'list_item':
+        return this.listItem(block)
+      case 'quote':
+        return this.quote(block)
+      case 'code':
+        return this.code(block)
+      case 'table':
+        return this.table(block)
+      case 'rule':
+        this.gap(6)
+        this.line(MARGIN, this.y, MARGIN + CONTENT_WIDTH, this.y, COLORS.rule)
+        this.y -= 10
+        return
+      default:
+        this.paragraph(block.runs)
+        this.y -= 7
+    }
+  }
+
+  pageNumbers() {
+    const font = this.fonts.fontFor({})
+    this.pages.forEach((page, index) => {
+      this.page = page
+      const label = `${index + 1} / ${this.pages.length}`
+      const x = (PAGE_WIDTH - font.measure(label, 8.5)) / 2
+      this.text(label, x, FOOTER_Y, { font, size: 8.5, fill: COLORS.muted })
+    })
+  }
+}
+
+const escapeLiteral = value => String(value).replace(/[\\()]/g, match => `\\${match}`)
+
+// Text strings outside ASCII are written as UTF-16BE with a byte order mark
+const textString = value =>
+  /^[\x20-\x7e]*$/.test(value)
+    ? `(${escapeLiteral(value)})`
+    : `<feff${Buffer.from(value, 'utf16le').swap16().toString('hex')}>`
+
+const stream = (data, extra = '') => {
+  const compressed = zlib.deflateSync(data)
+  return Buffer.concat([
+    Buffer.from(`<< /Length ${compressed.length} /Filter /FlateDecode${extra} >>\nstream\n`),
+    compressed,
+    Buffer.from('\nendstream'),
+  ])
+}
+
+const toUnicodeCMap = used => {
+  const entries = Array.from(used.entries())
+  const chunks = []
+  for (let index = 0; index < entries.length; index += 100) {
+    const chunk = entries.slice(index, index + 100)
+    chunks.push(
+      `${chunk.length} beginbfchar\n` +
+        chunk
+          .map(
+            ([glyph, char]) =>
+              `<${glyph.toString(16).padStart(4, '0')}> <${Buffer.from(char, 'utf16le')
+                .swap16()
+                .toString('hex')}>`,
+          )
+          .join('\n') +
+        '\nendbfchar',
+    )
+  }
+  return [
+    '/CIDInit /ProcSet findresource begin 12 dict begin begincmap',
+    '/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def',
+    '/CMapName /Adobe-Identity-UCS def /CMapType 2 def',
+    '1 begincodespacerange <0000> <ffff> endcodespacerange',
+    ...chunks,
+    'endcmap CMapName currentdict /CMap defineresource pop end end',
+  ].join('\n')
+}
+
+const serialize = (layout, { title }) => {
+  const objects = []
+  const add = body => {
+    objects.push(body)
+    return objects.length
+  }
+  const reserve = () => add(null)
+
+  const catalogId = reserve()
+  const pagesId = reserve()
+
+  const fontRefs = layout.fonts.fonts.map(font => {
+    if (!font.embedded) {
+      const id = add(
+        `<< /Type /Font /Subtype /Type1 /BaseFont /${font.baseFont} /Encoding /WinAnsiEncoding >>`,
+      )
+      return `/${font.name} ${id} 0 R`
+    }
+    const { metrics, used } = font
+    const fileId = add(stream(font.data, ` /Length1 ${font.data.length}`))
+    const descriptorId = add(
+      `<< /Type /FontDescriptor /FontName /${font.baseFont} /Flags 32 ` +
+        `/FontBBox [${metrics.bbox.join(' ')}] /ItalicAngle 0 /Ascent ${metrics.ascent} ` +
+        `/Descent ${metrics.descent} /CapHeight ${metrics.ascent} /StemV 80 ` +
+        `/FontFile2 ${fileId} 0 R >>`,
+    )
+    const widths = Array.from(used.keys())
+      .sort((a, b) => a - b)
+      .map(glyph => `${glyph} [${metrics.advance(glyph)}]`)
+      .join(' ')
+    const cidFontId = add(
+      `<< /Type /Font /Subtype /CIDFontType2 /BaseFont /${font.baseFont} ` +
+        '/CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> ' +
+        `/FontDescriptor ${descriptorId} 0 R /CIDToGIDMap /Identity /DW 1000 /W [${widths}] >>`,
+    )
+    const toUnicodeId = add(stream(toUnicodeCMap(used)))
+    const id = add(
+      `<< /Type /Font /Subtype /Type0 /BaseFont /${font.baseFont} /Encoding /Identity-H ` +
+        `/DescendantFonts [${cidFontId} 0 R] /ToUnicode ${toUnicodeId} 0 R >>`,
+    )
+    return `/${font.name} ${id} 0 R`
+  })
+
+  const pageIds = layout.pages.map(page => {
+    const contentId = add(stream(Buffer.from(page.ops.join('\n'), 'latin1')))
+    const annotations = page.links.map(({ rect, url }) =>
+      add(
+        `<< /Type /Annot /Subtype /Link /Rect [${rect.map(num).join(' ')}] /Border [0 0 0] ` +
+          `/A << /S /URI /URI (${escapeLiteral(encodeURI(decodeSafe(url)))}) >> >>`,
+      ),
+    )
+    return add(
+      `<< /Type /Page /Parent ${pagesId} 0 R /MediaBox [0 0 ${PAGE_WIDTH} ${PAGE_HEIGHT}] ` +
+        `/Resources << /Font << ${fontRefs.join(' ')} >> >> /Contents ${contentId} 0 R` +
+        (annotations.length ? ` /Annots [${annotations.map(id => `${id} 0 R`).join(' ')}]` : '') +
+        ' >>',
+    )
+  })
+
+  objects[catalogId - 1] = `<< /Type /Catalog /Pages ${pagesId} 0 R >>`
+  const kids = pageIds.map(id => `${id} 0 R`).join(' ')
+  objects[pagesId - 1] = `<< /Type /Pages /Kids [${kids}] /Count ${pageIds.length} >>`
+  const infoId = add(
+    `<< /Title ${textString(title || 'Research report')} /Producer (Qurio) ` +
+      `/CreationDate (D:${new Date().toISOString().replace(/[-:T]/g, '').slice(0, 14)}Z) >>`,
+  )
+
+  const parts = [Buffer.from('%PDF-1.7\n%\xe2\xe3\xcf\xd3\n', 'latin1')]
+  const offsets = []
+  let length = parts[0].length
+  objects.forEach((body, index) => {
+    const chunk = Buffer.concat([
+      Buffer.from(`${index + 1} 0 obj\n`),
+      Buffer.isBuffer(body) ? body : Buffer.from(body, 'latin1'),
+      Buffer.from('\nendobj\n'),
+    ])
+    offsets.push(length)
+    parts.push(chunk)
+    length += chunk.length
+  })
+  parts.push(
+    Buffer.from(
+      `xref\n0 ${objects.length + 1}\n0000000000 65535 f \n` +
+        offsets.map(offset => `${String(offset).padStart(10, '0')} 00000 n \n`).join('') +
+        `trailer\n<< /Size ${objects.length + 1} /Root ${catalogId} 0 R /Info ${infoId} 0 R >>\n` +
+        `startxref\n${length}\n%%EOF\n`,
+    ),
+  )
+  return Buffer.concat(parts)
+}
+
+const decodeSafe = url => {
+  try {
+    return decodeURI(url)
+  } catch {
+    return url
+  }
+}
+
+/**
+ * Render report Markdown as a PDF
+ * @param {string} markdown
+ * @param {Object} [options]
+ * @param {string} [options.title] - Document title metadata
+ * @returns {Buffer}
+ */
+export const renderPdf = (markdown, { title } = {}) => {
+  const layout = new PdfLayout(createPdfFonts())
+  for (const block of parseMarkdownBlocks(markdown)) layout.block(block)
+  layout.pageNumbers()
+  return serialize(layout, { title })
+}
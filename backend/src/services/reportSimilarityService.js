@@ -0,0 +1,233 @@
+/**
+ * Report similarity service
+ * Self-check for generated reports: every prose sentence is compared with the source texts
+ * collected during research (5-word shingle containment), near-verbatim passages above the
+ * threshold are flagged, and the model is asked once to paraphrase just those spans while keeping
+ * their citations.
+ */
+
+import { completeJson } from './modelCompletion.js'
+
+const SHINGLE_SIZE = 5
+const MIN_PASSAGE_TOKENS = 12
+const DEFAULT_THRESHOLD = 0.5
+const MAX_REWRITES = 20
+const MAX_SOURCE_CHARS = 20000
+
+// Han characters are one token each; everything else is split into letter/digit runs
+const TOKEN_PATTERN = /\p{Script=Han}|[\p{L}\p{N}]+/gu
+export const CITATION_PATTERN = /\[\d+(?:\s*[,\-–]\s*\d+)*\]/g
+// A sentence ends before whitespace and a non-lowercase character, so decimals and "e.g." stay
+// inside their sentence
+const SENTENCE_PATTERN = /.+?(?:[.!?]+["')\]]*(?=\s+[^\p{Ll}]|\s*$)|[。！？]+|$)/gu
+// Reference lists repeat source titles verbatim by design
+const REFERENCES_HEADING_PATTERN = /^#{1,6}\s*(sources|references|bibliography|参考|来源)/i
+
+const REWRITE_SYSTEM_PROMPT = `You paraphrase passages of a research report that copy their
+sources too closely. For each passage, write a version with the same meaning and facts in clearly
+different wording and sentence structure. Keep every citation marker such as [3] attached to the
+claim it supports, keep numbers, names and technical terms exact, and keep the passage's language.
+Return JSON only: {"rewrites":[{"id":1,"text":"..."}]}`
+
+export const resolveSimilarityThreshold = value => {
+  const raw = value ?? process.env.SIMILARITY_THRESHOLD
+  const threshold = Number(raw)
+  return raw !== undefined && raw !== '' && threshold > 0 && threshold <= 1
+    ? threshold
+    : DEFAULT_THRESHOLD
+}
+
+const tokenize = text =>
+  String(text || '')
+    .replace(CITATION_PATTERN, ' ')
+    .toLowerCase()
+    .match(TOKEN_PATTERN) || []
+
+const shingles = tokens => {
+  const set = new Set()
+  for (let index = 0; index + SHINGLE_SIZE <= tokens.length; index += 1) {
+    set.add(tokens.slice(index, index + SHINGLE_SIZE).join(' '))
+  }
+  return set
+}
+
+/**
+ * Accumulate source text per URL (a page can be returned by several searches with different
+ * excerpts)
+ */
+export const addSourceText = (sourceTexts, url, text) => {
+  if (!url || !text) return
+  const current = sourceTexts.get(url) || ''
+  if (current.length >= MAX_SOURCE_CHARS || current.includes(text)) return
+  sourceTexts.set(url, `${current}\n${text}`.slice(0, MAX_SOURCE_CHARS))
+}
+
+// Prose sentences with their offsets; headings, tables, code, quotes and reference lists are
+// skipped (also used by the citation check)
+export const splitPassages = report => {
+  const passages = []
+  let offset = 0
+  let inCode = false
+  let inReferences = false
+  for (const line of report.split('\n')) {
+    const lineStart = offset
+    offset += line.length + 1
+    const trimmed = line.trim()
+    if (trimmed.startsWith('```')) {
+      inCode = !inCode
+      continue
+    }
+    if (/^#{1,6}\s/.test(trimmed)) {
+      inReferences = REFERENCES_HEADING_PATTERN.test(trimmed)
+      continue
+    }
+    if (inCode || inReferences || !trimmed || /^[|>]/.test(trimmed)) continue
+    for (const match of line.matchAll(SENTENCE_PATTERN)) {
+      const text = match[0].trim()
+      if (!text) continue
+      const start = lineStart + match.index + match[0].indexOf(text)
+      passages.push({ start, end: start + text.length, text })
+    }
+  }
+  return passages
+}
+
+/**
+ * Flag report passages that are near-verbatim copies of a source
+ * @param {string} report
+ * @param {Map<string, string>} sourceTexts - url -> collected text
+ * @param {Object} [options]
+ * @param {number} [options.threshold] - Share of a passage's 5-word shingles found in one source
+ * @param {Array<{ url?, uri?, title? }>} [options.sources] - Numbered sources, for labels
+ * @returns {Array<{ start, end, text, similarity, source: { index, url, title } }>}
+ */
+export const findSimilarPassages = (report, sourceTexts, { threshold, sources = [] } = {}) => {
+  const limit = resolveSimilarityThreshold(threshold)
+  if (!report || !sourceTexts?.size) return []
+  const shingleIndex = new Map()
+  const urls = Array.from(sourceTexts.keys())
+  urls.forEach((url, sourceIndex) => {
+    for (const shingle of shingles(tokenize(sourceTexts.get(url)))) {
+      if (!shingleIndex.has(shingle)) shingleIndex.set(shingle, [])
+      shingleIndex.get(shingle).push(sourceIndex)
+    }
+  })
+
+  const flagged = []
+  for (const passage of splitPassages(report)) {
+    const tokens = tokenize(passage.text)
+    if (tokens.length < MIN_PASSAGE_TOKENS) continue
+    const passageShingles = shingles(tokens)
+    const hits = new Map()
+    for (const shingle of passageShingles) {
+      for (const sourceIndex of shingleIndex.get(shingle) || []) {
+        hits.set(sourceIndex, (hits.get(sourceIndex) || 0) + 1)
+      }
+    }
+    let best = null
+    for (const [sourceIndex, count] of hits) {
+      if (!best || count > best.count) best = { sourceIndex, count }
+    }
+    const similarity = best ? best.count / passageShingles.size : 0
+    if (similarity < limit) continue
+    const url = urls[best.sourceIndex]
+    const listIndex = sources.findIndex(source => (source?.url || source?.uri) === url)
+    flagged.push({
+      ...passage,
+      similarity: Math.round(similarity * 100) / 100,
+      source: {
+        index: listIndex === -1 ? null : listIndex + 1,
+        url,
+        title: sources[listIndex]?.title || null,
+      },
+    })
+  }
+  return flagged
+}
+
+/**
+ * Check a report and paraphrase its near-verbatim passages
+ * @param {Object} params
+ * @param {string} params.report
+ * @param {Map<string, string>} params.sourceTexts
+ * @param {Array} [params.sources]
+ * @param {number} [params.threshold]
+ * @param {boolean} [params.rewrite=true] - false only flags passages
+ * @returns {Promise<{ content: string, check: { threshold, passages, rewritten, remaining } }>}
+ *   content is the report with rewritten spans replaced (unchanged when nothing was rewritten)
+ */
+export const checkReportSimilarity = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  report,
+  sourceTexts,
+  sources,
+  threshold,
+  rewrite = true,
+  signal,
+}) => {
+  const limit = resolveSimilarityThreshold(threshold)
+  const flagged = findSimilarPassages(report, sourceTexts, { threshold: limit, sources })
+  const summarize = (content, rewritten) => ({
+    content,
+    check: {
+      threshold: limit,
+      passages: flagged.map(({ start, end, ...passage }) => passage),
+      rewritten,
+      remaining: flagged.length - rewritten,
+    },
+  })
+  if (!flagged.length || !rewrite) return summarize(report, 0)
+
+  const targets = flagged.slice(0, MAX_REWRITES)
+  let parsed = null
+  try {
+    parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      temperature: 0.4,
+      messages: [
+        { role: 'system', content: REWRITE_SYSTEM_PROMPT },
+        {
+          role: 'user',
+          content: JSON.stringify({
+            passages: targets.map((passage, index) => ({ id: index + 1, text: passage.text })),
+          }),
+        },
+      ],
+      signal,
+    })
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[Similarity] Rewrite failed:', error.message)
+    return summarize(report, 0)
+  }
+
+  const rewrites = new Map(
+    (Array.isArray(parsed?.rewrites) ? parsed.rewrites : [])
+      .filter(item => typeof item?.text === 'string' && item.text.trim())
+      .map(item => [Number(item.id), item.text.trim()]),
+  )
+  let content = report
+  let rewritten = 0
+  // Replace from the end so earlier offsets stay valid
+  for (let index = targets.length - 1; index >= 0; index -= 1) {
+    const text = rewrites.get(index + 1)
+    const passage = targets[index]
+    if (!text || text === passage.text) continue
+    // Keep a rewrite only when it is actually further from the source
+    const recheck = findSimilarPassages(text, sourceTexts, { threshold: limit })
+    if (recheck.length) continue
+    content = content.slice(0, passage.start) + text + content.slice(passage.end)
+    passage.rewritten = true
+    rewritten += 1
+  }
+  flagged.forEach(passage => {
+    passage.rewritten = Boolean(passage.rewritten)
+  })
+  return summarize(content, rewritten)
+}
@@ -0,0 +1,70 @@
+/**
+ * Research run store
+ * Persists deep research runs (plan, report, sources, stats) to the local data store.
+ */
+
+import { randomUUID } from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+
+const COLLECTION = 'research-runs'
+
+/**
+ * @param {Object} args
+ * @param {Object} [args.request] - Request parameters without credentials, kept for resuming
+ */
+export const createResearchRun = ({ question, researchType, provider, model, request }) => {
+  const run = {
+    id: randomUUID(),
+    question: question || '',
+    researchType: researchType || 'general',
+    provider,
+    model: model || null,
+    status: 'running',
+    startedAt: new Date().toISOString(),
+    finishedAt: null,
+    request: request || null,
+  }
+  return writeRecord(COLLECTION, run.id, run)
+}
+
+export const saveResearchRun = run => writeRecord(COLLECTION, run.id, run)
+
+export const getResearchRun = id => readRecord(COLLECTION, id)
+
+export const deleteResearchRun = id => deleteRecord(COLLECTION, id)
+
+/**
+ * List runs newest first, without the heavy report/plan payloads
+ * completedSteps/totalSteps show how far an unfinished run got (see resumeDeepResearch);
+ * parentRunId links a follow-up to the run it continues (see followupDeepResearch)
+ */
+export const listResearchRuns = ({ limit = 50 } = {}) =>
+  listRecords(COLLECTION)
+    .sort((a, b) => String(b.startedAt || '').localeCompare(String(a.startedAt || '')))
+    .slice(0, limit)
+    .map(
+      ({
+        id,
+        question,
+        researchType,
+        status,
+        startedAt,
+        finishedAt,
+        stats,
+        plan,
+        completed_steps: completedSteps,
+        parentRunId,
+      }) => ({
+        id,
+        question,
+        researchType,
+        status,
+        startedAt,
+        finishedAt,
+        totalDurationMs: stats?.total_duration_ms ?? null,
+        totalTokens: stats?.tokens?.total ?? null,
+        completedSteps: Object.keys(completedSteps || {}).length,
+        totalSteps: Array.isArray(plan?.plan) ? plan.plan.length : null,
+        parentRunId: parentRunId || null,
+      }),
+    )
@@ -0,0 +1,111 @@
+/**
+ * Deep research telemetry
+ * Per-step token usage, tool call counts, and durations for the research done event.
+ */
+
+export const emptyUsage = () => ({ input: 0, output: 0, total: 0 })
+
+/**
+ * Extract token usage from a LangChain message/chunk (usage_metadata) or the raw OpenAI payload
+ */
+export const extractUsage = response => {
+  const metadata = response?.usage_metadata
+  if (metadata && (metadata.input_tokens || metadata.output_tokens)) {
+    const input = metadata.input_tokens || 0
+    const output = metadata.output_tokens || 0
+    return { input, output, total: metadata.total_tokens || input + output }
+  }
+  const raw =
+    response?.additional_kwargs?.__raw_response?.usage || response?.response_metadata?.tokenUsage
+  if (raw) {
+    const input = raw.prompt_tokens ?? raw.promptTokens ?? 0
+    const output = raw.completion_tokens ?? raw.completionTokens ?? 0
+    return { input, output, total: raw.total_tokens ?? raw.totalTokens ?? input + output }
+  }
+  return null
+}
+
+export const addUsage = (target, usage) => {
+  if (!usage) return target
+  target.input += usage.input || 0
+  target.output += usage.output || 0
+  target.total += usage.total || 0
+  return target
+}
+
+/**
+ * OpenAI-style usage block for SSE done events
+ */
+export const toUsagePayload = usage => ({
+  prompt_tokens: usage?.input || 0,
+  completion_tokens: usage?.output || 0,
+  total_tokens: usage?.total || 0,
+})
+
+/**
+ * Collects stats over a run; steps may finish out of order in concurrent mode
+ */
+export const createResearchStats = () => {
+  const startedAt = Date.now()
+  const steps = new Map()
+  const searchLog = []
+  const stats = {
+    planDurationMs: null,
+    report: null,
+  }
+
+  return {
+    recordPlan(durationMs) {
+      stats.planDurationMs = durationMs
+    },
+    recordStep({ stepIndex, title, status, durationMs, usage, llmCalls, toolEvents }) {
+      const results = (toolEvents || []).filter(event => event.type === 'tool_result')
+      steps.set(stepIndex, {
+        step: stepIndex + 1,
+        title,
+        status,
+        duration_ms: durationMs,
+        llm_calls: llmCalls || 0,
+        tool_calls: results.length,
+        tool_errors: results.filter(event => event.status === 'error').length,
+        tokens: usage || emptyUsage(),
+      })
+    },
+    recordSearch({ tool, query, resultCount, stepIndex, durationMs, error, skipped }) {
+      searchLog.push({
+        tool,
+        query: query || '',
+        result_count: resultCount ?? 0,
+        step: typeof stepIndex === 'number' ? stepIndex + 1 : null,
+        duration_ms: durationMs,
+        status: error ? 'error' : skipped ? 'skipped' : 'done',
+        error: error ? error.message || String(error) : undefined,
+        at: new Date().toISOString(),
+      })
+    },
+    /**
+     * Every search query issued during the run, in the order issued
+     */
+    getSearchLog() {
+      return searchLog.slice()
+    },
+    recordReport({ durationMs, usage }) {
+      stats.report = { duration_ms: durationMs, tokens: usage || emptyUsage() }
+    },
+    toJSON() {
+      const stepList = Array.from(steps.values()).sort((a, b) => a.step - b.step)
+      const tokens = emptyUsage()
+      stepList.forEach(step => addUsage(tokens, step.tokens))
+      if (stats.report) addUsage(tokens, stats.report.tokens)
+      return {
+        total_duration_ms: Date.now() - startedAt,
+        plan_duration_ms: stats.planDurationMs,
+        tokens,
+        tool_calls: stepList.reduce((sum, step) => sum + step.tool_calls, 0),
+        llm_calls: stepList.reduce((sum, step) => sum + step.llm_calls, 0) + (stats.report ? 1 : 0),
+        steps: stepList,
+        report: stats.report,
+      }
+    },
+  }
+}
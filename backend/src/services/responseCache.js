@@ -0,0 +1,97 @@
+/**
+ * Response cache
+ * In-process LRU + TTL cache for idempotent generation endpoints (title, title-and-space, daily
+ * tip, related questions), so identical requests do not hit the model again. Entries are keyed by
+ * a hash of the endpoint, provider, base URL, model, API key, and prompt inputs.
+ *
+ * RESPONSE_CACHE_TTL_MS (default 10 minutes; 0 disables caching) and RESPONSE_CACHE_MAX_ENTRIES
+ * (default 500) configure it. A request with "Cache-Control: no-cache" skips the lookup and
+ * stores its fresh result.
+ */
+
+import { createHash } from 'crypto'
+
+const DEFAULT_TTL_MS = 10 * 60 * 1000
+const DEFAULT_MAX_ENTRIES = 500
+
+// Map iteration order is insertion order: a read re-inserts, so the first key is least recent
+const entries = new Map()
+
+export const getResponseCacheConfig = () => {
+  const ttlMs = Number.parseInt(process.env.RESPONSE_CACHE_TTL_MS, 10)
+  const maxEntries = Number.parseInt(process.env.RESPONSE_CACHE_MAX_ENTRIES, 10)
+  return {
+    ttlMs: Number.isFinite(ttlMs) && ttlMs >= 0 ? ttlMs : DEFAULT_TTL_MS,
+    maxEntries: Number.isFinite(maxEntries) && maxEntries > 0 ? maxEntries : DEFAULT_MAX_ENTRIES,
+  }
+}
+
+const buildCacheKey = (namespace, input) =>
+  createHash('sha256')
+    .update(JSON.stringify([namespace, input]))
+    .digest('hex')
+
+/**
+ * Whether the request asked to skip cached responses
+ * @param {import('express').Request} req
+ */
+export const isCacheBypassed = req => /no-cache|no-store/i.test(req.get('cache-control') || '')
+
+/**
+ * Return a cached value or compute and store it
+ * @param {string} namespace - Endpoint name
+ * @param {Object} input - Everything the result depends on (provider, baseUrl, model, apiKey,
+ *   prompt inputs); the API key only ever exists hashed in the key
+ * @param {() => Promise<*>} compute
+ * @param {Object} [options]
+ * @param {boolean} [options.bypass] - Skip the lookup (the fresh result is still stored)
+ * @param {(value: *) => boolean} [options.shouldCache] - Keep fallback/empty results out
+ * @returns {Promise<{ value: *, cacheStatus: 'hit'|'miss'|'bypass'|'disabled' }>}
+ */
+export const withResponseCache = async (
+  namespace,
+  input,
+  compute,
+  { bypass = false, shouldCache = Boolean } = {},
+) => {
+  const { ttlMs, maxEntries } = getResponseCacheConfig()
+  if (!ttlMs) return { value: await compute(), cacheStatus: 'disabled' }
+
+  const key = buildCacheKey(namespace, input)
+  const entry = entries.get(key)
+  if (entry && Date.now() - entry.storedAt >= ttlMs) entries.delete(key)
+  else if (entry && !bypass) {
+    entries.delete(key)
+    entries.set(key, entry)
+    return { value: structuredClone(entry.value), cacheStatus: 'hit' }
+  }
+
+  const value = await compute()
+  if (shouldCache(value)) {
+    entries.delete(key)
+    entries.set(key, { value: structuredClone(value), storedAt: Date.now() })
+    while (entries.size > maxEntries) entries.delete(entries.keys().next().value)
+  }
+  return { value, cacheStatus: bypass ? 'bypass' : 'miss' }
+}
+
+// Cache-Status header values (RFC 9211)
+const CACHE_STATUS_HEADERS = {
+  hit: 'qurio; hit',
+  miss: 'qurio; fwd=miss',
+  bypass: 'qurio; fwd=request',
+  disabled: 'qurio; fwd=bypass',
+}
+
+/**
+ * Set the Cache-Status header for a withResponseCache result
+ * @param {import('express').Response} res
+ */
+export const setCacheStatusHeader = (res, cacheStatus) =>
+  res.set('Cache-Status', CACHE_STATUS_HEADERS[cacheStatus] || CACHE_STATUS_HEADERS.miss)
+
+export const clearResponseCache = () => {
+  const cleared = entries.size
+  entries.clear()
+  return cleared
+}
@@ -0,0 +1,253 @@
+/**
+ * Conversation re-title service
+ * Titles are generated from the first message and then frozen, so long conversations that move
+ * to another topic keep a misleading title. This service compares the embedding of the current
+ * title with the embedding of the recent messages; when the similarity drops below
+ * RETITLE_DRIFT_THRESHOLD it generates a new title/emoji and stores it as a proposal (the title
+ * itself only changes once the proposal is accepted). New proposals are pushed to listeners,
+ * which GET /api/title-proposals/events forwards as "title_proposal" SSE events.
+ * Runs as the "conversation-retitle" background job (see backgroundService).
+ */
+
+import { listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import { backgroundJobManager } from './backgroundService.js'
+import { getConversation, listMessages, updateConversation } from './conversationStore.js'
+import { cosineSimilarity, embedTexts, resolveEmbeddingConfig } from './embeddingClient.js'
+import { resolveSpaceCredentials } from './keyVault.js'
+import { normalizeTextContent } from './serviceUtils.js'
+import { resolveTitleEmojis } from './titleEmojiService.js'
+import { generateTitle } from './titleService.js'
+
+const COLLECTION = 'title-proposals'
+const CONVERSATIONS_COLLECTION = 'conversations'
+const DEFAULT_INTERVAL_MS = 30 * 60 * 1000
+const DEFAULT_DRIFT_THRESHOLD = 0.5
+const DEFAULT_BATCH_SIZE = 20
+const MIN_MESSAGES = 4
+const RECENT_MESSAGES = 6
+const MAX_CONTEXT_CHARS = 4000
+// A dismissed proposal is only repeated once the title fits this much worse than back then
+const DISMISS_MARGIN = 0.1
+
+const listeners = new Set()
+
+const parsePositiveNumber = (value, fallback) => {
+  const number = Number.parseFloat(value)
+  return Number.isFinite(number) && number > 0 ? number : fallback
+}
+
+export const getRetitleConfig = () => ({
+  intervalMs: parsePositiveNumber(process.env.RETITLE_INTERVAL_MS, DEFAULT_INTERVAL_MS),
+  driftThreshold: parsePositiveNumber(
+    process.env.RETITLE_DRIFT_THRESHOLD,
+    DEFAULT_DRIFT_THRESHOLD,
+  ),
+  batchSize: parsePositiveNumber(process.env.RETITLE_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+})
+
+/**
+ * Subscribe to new title proposals
+ * @returns {() => void} Unsubscribe
+ */
+export const onTitleProposal = listener => {
+  listeners.add(listener)
+  return () => listeners.delete(listener)
+}
+
+const emitProposal = proposal => {
+  listeners.forEach(listener => {
+    try {
+      listener(proposal)
+    } catch (error) {
+      console.warn('[Retitle] Listener failed:', error.message)
+    }
+  })
+}
+
+// Recent user/assistant turns as plain text, newest kept when over the size limit
+const buildRecentContext = messages =>
+  messages
+    .filter(message => message.role === 'user' || message.role === 'assistant')
+    .slice(-RECENT_MESSAGES)
+    .map(message => `${message.role}: ${normalizeTextContent(message.content).trim()}`)
+    .filter(line => !line.endsWith(':'))
+    .join('\n\n')
+    .slice(-MAX_CONTEXT_CHARS)
+
+// Credentials pinned to the conversation's space, else the RETITLE_* settings
+const resolveTitleModel = conversation => {
+  const pinned = resolveSpaceCredentials(conversation.space_id)
+  if (pinned?.provider && pinned.apiKey) return pinned
+  const provider = process.env.RETITLE_PROVIDER
+  const apiKey = process.env.RETITLE_API_KEY
+  if (!provider || !apiKey) return null
+  return {
+    provider,
+    apiKey,
+    baseUrl: process.env.RETITLE_BASE_URL || undefined,
+    model: process.env.RETITLE_MODEL || undefined,
+  }
+}
+
+const sameTitle = (a, b) =>
+  String(a || '')
+    .trim()
+    .toLowerCase() ===
+  String(b || '')
+    .trim()
+    .toLowerCase()
+
+export const getTitleProposal = conversationId => readRecord(COLLECTION, conversationId)
+
+/**
+ * Pending proposals, newest first
+ */
+export const listTitleProposals = () =>
+  listRecords(COLLECTION)
+    .map(record => record.proposal)
+    .filter(proposal => proposal?.status === 'pending')
+    .sort((a, b) => String(b.proposed_at).localeCompare(String(a.proposed_at)))
+
+/**
+ * Check one conversation for topic drift and propose a new title when it drifted
+ * @param {string} conversationId
+ * @param {Object} [options]
+ * @param {boolean} [options.force] Re-check even when no message was added since the last check
+ * @returns {Promise<{ status: 'proposed'|'on_topic'|'skipped', reason?, similarity?, proposal? }>}
+ */
+export const checkConversationTitle = async (conversationId, { force = false, signal } = {}) => {
+  const conversation = getConversation(conversationId)
+  if (!conversation) throw new Error(`Conversation not found: ${conversationId}`)
+  const messages = listMessages(conversationId)
+  const previous = getTitleProposal(conversationId)
+  if (messages.length < MIN_MESSAGES) return { status: 'skipped', reason: 'too_short' }
+  if (!force && previous?.checked_message_count === messages.length) {
+    return { status: 'skipped', reason: 'unchanged' }
+  }
+  const embeddingConfig = resolveEmbeddingConfig()
+  if (!embeddingConfig) return { status: 'skipped', reason: 'embeddings_not_configured' }
+  const context = buildRecentContext(messages)
+  if (!context || !conversation.title) return { status: 'skipped', reason: 'no_content' }
+
+  const config = getRetitleConfig()
+  const [titleVector, contextVector] = await embedTexts(
+    [conversation.title, context],
+    embeddingConfig,
+    { signal },
+  )
+  const similarity = Number(cosineSimilarity(titleVector, contextVector).toFixed(4))
+  const record = {
+    conversation_id: conversationId,
+    checked_message_count: messages.length,
+    checked_at: new Date().toISOString(),
+    similarity,
+    proposal: previous?.proposal || null,
+  }
+  if (similarity >= config.driftThreshold) {
+    writeRecord(COLLECTION, conversationId, record)
+    return { status: 'on_topic', similarity }
+  }
+  const dismissed = previous?.proposal?.status === 'dismissed' ? previous.proposal : null
+  if (dismissed && similarity > dismissed.similarity - DISMISS_MARGIN) {
+    writeRecord(COLLECTION, conversationId, record)
+    return { status: 'skipped', reason: 'dismissed', similarity }
+  }
+
+  const titleModel = resolveTitleModel(conversation)
+  if (!titleModel) {
+    writeRecord(COLLECTION, conversationId, record)
+    return { status: 'skipped', reason: 'no_title_model', similarity }
+  }
+  const { title, emojis } = await generateTitle(
+    titleModel.provider,
+    context,
+    titleModel.apiKey,
+    titleModel.baseUrl,
+    titleModel.model,
+    { emojis: resolveTitleEmojis() },
+  )
+  if (!title || sameTitle(title, conversation.title)) {
+    writeRecord(COLLECTION, conversationId, record)
+    return { status: 'on_topic', similarity }
+  }
+
+  const proposal = {
+    conversation_id: conversationId,
+    current_title: conversation.title,
+    current_emojis: conversation.title_emojis || [],
+    title,
+    emojis,
+    similarity,
+    threshold: config.driftThreshold,
+    status: 'pending',
+    proposed_at: record.checked_at,
+  }
+  writeRecord(COLLECTION, conversationId, { ...record, proposal })
+  emitProposal(proposal)
+  return { status: 'proposed', similarity, proposal }
+}
+
+const resolveProposal = (conversationId, status) => {
+  const record = getTitleProposal(conversationId)
+  if (record?.proposal?.status !== 'pending') return null
+  const proposal = { ...record.proposal, status, resolved_at: new Date().toISOString() }
+  writeRecord(COLLECTION, conversationId, { ...record, proposal })
+  return proposal
+}
+
+/**
+ * Apply a pending proposal to the conversation
+ * @param {Object} [overrides] Edited { title, emojis } to apply instead of the proposed ones
+ * @returns {{ proposal: Object, conversation: Object }|null} null when nothing is pending
+ */
+export const acceptTitleProposal = (conversationId, overrides = {}) => {
+  const pending = getTitleProposal(conversationId)?.proposal
+  if (pending?.status !== 'pending') return null
+  const conversation = updateConversation(conversationId, {
+    title: overrides.title || pending.title,
+    title_emojis: overrides.emojis || pending.emojis,
+  })
+  return { proposal: resolveProposal(conversationId, 'accepted'), conversation }
+}
+
+export const dismissTitleProposal = conversationId => resolveProposal(conversationId, 'dismissed')
+
+/**
+ * One pass over conversations that received messages since their last check
+ * @returns {Promise<{ checked: number, proposed: number, failed: number }>}
+ */
+export const runRetitlePass = async ({ batchSize, signal } = {}) => {
+  const summary = { checked: 0, proposed: 0, failed: 0 }
+  if (!resolveEmbeddingConfig()) return summary
+  const due = listRecords(CONVERSATIONS_COLLECTION)
+    .filter(conversation => {
+      const checkedAt = getTitleProposal(conversation.id)?.checked_at
+      return !checkedAt || String(conversation.updated_at) > checkedAt
+    })
+    .sort((a, b) => String(b.updated_at).localeCompare(String(a.updated_at)))
+    .slice(0, batchSize || getRetitleConfig().batchSize)
+
+  for (const conversation of due) {
+    if (signal?.aborted) break
+    try {
+      const result = await checkConversationTitle(conversation.id, { signal })
+      if (result.status === 'skipped') continue
+      summary.checked += 1
+      if (result.status === 'proposed') summary.proposed += 1
+    } catch (error) {
+      summary.failed += 1
+      console.warn(`[Retitle] Check failed for ${conversation.id}:`, error.message)
+    }
+  }
+  return summary
+}
+
+backgroundJobManager.registerJob({
+  name: 'conversation-retitle',
+  description: 'Propose new titles for conversations whose topic drifted from their title',
+  intervalMs: getRetitleConfig().intervalMs,
+  handler: async () => {
+    const summary = await runRetitlePass()
+    if (summary.checked) console.log('[Retitle] Pass finished:', JSON.stringify(summary))
+  },
+})
@@ -0,0 +1,109 @@
+/**
+ * RFC service
+ * Fetch IETF RFCs by number from the RFC Editor, with status metadata (obsoleted/updated by)
+ * and optional extraction of a single section so clause numbers can be cited precisely.
+ */
+
+const RFC_BASE = 'https://www.rfc-editor.org/rfc'
+const REQUEST_TIMEOUT_MS = 15000
+const MAX_TEXT_CHARS = 20000
+
+const fetchWithTimeout = async (url, { signal } = {}) => {
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(url, {
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (response.status === 404) return null
+  if (!response.ok) throw new Error(`RFC Editor request failed: HTTP ${response.status}`)
+  return response
+}
+
+const normalizeDocList = value =>
+  (Array.isArray(value) ? value : [])
+    .map(item => String(item).trim())
+    .filter(Boolean)
+    .map(item => item.replace(/^RFC0*/i, 'RFC '))
+
+// Drop page-break furniture (form feeds, running headers/footers) from the plain-text format
+const stripPagination = text =>
+  text
+    .replace(/\n[^\n]*\[Page \d+\]\n?\f?\n?[^\n]*\n/g, '\n')
+    .replace(/\f/g, '')
+    .replace(/\n{3,}/g, '\n\n')
+
+/**
+ * Extract one numbered section (e.g. "4.2" or "Appendix A") including its subsections
+ */
+export const extractRfcSection = (text, section) => {
+  const id = String(section).trim().replace(/\.$/, '')
+  const escaped = id.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')
+  const start = text.match(new RegExp(`^${escaped}\\.?\\s{1,4}\\S.*$`, 'm'))
+  if (!start) return null
+  const rest = text.slice(start.index + start[0].length)
+  // Next heading at the same or higher level ends the section
+  const depth = id.split('.').length
+  const headingPattern = /^((?:\d+|Appendix [A-Z]|[A-Z])(?:\.\d+)*)\.?\s{1,4}\S.*$/gm
+  let end = rest.length
+  for (const match of rest.matchAll(headingPattern)) {
+    const candidate = match[1]
+    if (candidate.startsWith(`${id}.`)) continue
+    if (candidate.split('.').length <= depth) {
+      end = match.index
+      break
+    }
+  }
+  return `${start[0]}${rest.slice(0, end)}`.trim()
+}
+
+/**
+ * Fetch an RFC by number
+ * @param {Object} args
+ * @param {number} args.number RFC number, e.g. 9110
+ * @param {string} [args.section] Section to extract, e.g. "15.5.4"
+ */
+export const fetchRfc = async ({ number, section, signal }) => {
+  const rfcNumber = Number(String(number).replace(/^rfc\s*/i, ''))
+  if (!Number.isInteger(rfcNumber) || rfcNumber <= 0) throw new Error('Invalid RFC number')
+
+  const [metaResponse, textResponse] = await Promise.all([
+    fetchWithTimeout(`${RFC_BASE}/rfc${rfcNumber}.json`, { signal }),
+    fetchWithTimeout(`${RFC_BASE}/rfc${rfcNumber}.txt`, { signal }),
+  ])
+  if (!textResponse) throw new Error(`RFC ${rfcNumber} not found`)
+  const meta = metaResponse ? await metaResponse.json().catch(() => ({})) : {}
+  const fullText = stripPagination(await textResponse.text())
+
+  let text = fullText
+  let sectionFound = null
+  if (section) {
+    const extracted = extractRfcSection(fullText, section)
+    sectionFound = Boolean(extracted)
+    if (extracted) text = extracted
+  }
+  const truncated = text.length > MAX_TEXT_CHARS
+  if (truncated) text = text.slice(0, MAX_TEXT_CHARS)
+
+  const title = String(meta.title || '').trim() || `RFC ${rfcNumber}`
+  const anchor = section && sectionFound ? `#section-${String(section).replace(/\.$/, '')}` : ''
+  const url = `${RFC_BASE}/rfc${rfcNumber}${anchor}`
+  return {
+    rfc: rfcNumber,
+    title,
+    status: meta.status || null,
+    published: meta.pub_date || null,
+    obsoleted_by: normalizeDocList(meta.obsoleted_by),
+    updated_by: normalizeDocList(meta.updated_by),
+    obsoletes: normalizeDocList(meta.obsoletes),
+    section: section || null,
+    section_found: sectionFound,
+    text,
+    truncated,
+    results: [
+      {
+        title: `RFC ${rfcNumber}${anchor ? ` §${section}` : ''}: ${title}`,
+        url,
+        content: String(meta.abstract || text.slice(0, 500)).trim(),
+      },
+    ],
+  }
+}
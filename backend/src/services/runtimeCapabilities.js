@@ -0,0 +1,109 @@
+/**
+ * Runtime capabilities
+ * Detects the external programs some features shell out to (currently git, for the repository
+ * tools and the code index) once per process. When one is missing the backend runs in
+ * "degraded" mode: the features that need it answer 501 with what is unavailable, and
+ * /api/health reports the mode, instead of each request failing with a spawn error.
+ *
+ * GIT_PATH overrides the git executable (default "git" on PATH).
+ */
+
+import { execFile } from 'child_process'
+import { promisify } from 'util'
+import { ErrorCode, QurioError, sendError } from '../utils/errors.js'
+
+const execFileAsync = promisify(execFile)
+
+const PROBE_TIMEOUT_MS = 5000
+
+const RUNTIMES = {
+  git: {
+    command: () => process.env.GIT_PATH || 'git',
+    args: ['--version'],
+    toolIds: ['repo_context', 'code_search'],
+    features: ['repo_context tool', 'code_search tool', '/api/code-index', '/api/code-search'],
+  },
+}
+
+let detection = null
+let detected = null
+
+const probeRuntime = async name => {
+  const { command, args } = RUNTIMES[name]
+  try {
+    const { stdout } = await execFileAsync(command(), args, { timeout: PROBE_TIMEOUT_MS })
+    return { available: true, version: stdout.trim().split('\n')[0] }
+  } catch (error) {
+    const reason = error.code === 'ENOENT' ? `${command()} not found` : error.message
+    return { available: false, error: reason }
+  }
+}
+
+const toStatus = runtimes => {
+  const missing = Object.keys(runtimes).filter(name => !runtimes[name].available)
+  return {
+    mode: missing.length ? 'degraded' : 'full',
+    runtimes,
+    unavailable_features: missing.flatMap(name => RUNTIMES[name].features),
+  }
+}
+
+/**
+ * Probe every runtime (once; later calls share the first result)
+ * @returns {Promise<{ mode: 'full'|'degraded', runtimes: Object, unavailable_features: string[] }>}
+ */
+export const detectRuntimes = () => {
+  detection ??= Promise.all(
+    Object.keys(RUNTIMES).map(async name => [name, await probeRuntime(name)]),
+  ).then(entries => {
+    detected = Object.fromEntries(entries)
+    return toStatus(detected)
+  })
+  return detection
+}
+
+/**
+ * Probe again, e.g. after the user installed a missing program
+ */
+export const redetectRuntimes = () => {
+  detection = null
+  return detectRuntimes()
+}
+
+/**
+ * Tool ids whose runtime is known to be missing (empty until detection has finished)
+ */
+export const getUnavailableToolIds = () =>
+  new Set(
+    Object.keys(RUNTIMES)
+      .filter(name => detected?.[name]?.available === false)
+      .flatMap(name => RUNTIMES[name].toolIds),
+  )
+
+export const isRuntimeAvailable = async name => {
+  await detectRuntimes()
+  return detected[name]?.available !== false
+}
+
+export const runtimeUnavailableError = name =>
+  new QurioError(
+    ErrorCode.RuntimeUnavailable,
+    `${name} is not available on this machine (${detected?.[name]?.error || 'not found'}); ` +
+      `unavailable: ${RUNTIMES[name].features.join(', ')}`,
+    { details: { runtime: name, unavailable_features: RUNTIMES[name].features } },
+  )
+
+/**
+ * Throw a 501 error when the runtime is missing
+ */
+export const assertRuntime = async name => {
+  if (!(await isRuntimeAvailable(name))) throw runtimeUnavailableError(name)
+}
+
+/**
+ * Express middleware answering 501 when the runtime is missing
+ */
+export const requireRuntime = name => async (req, res, next) => {
+  if (await isRuntimeAvailable(name)) return next()
+  sendError(res, runtimeUnavailableError(name))
+}
@@ -0,0 +1,174 @@
+/**
+ * Saved prompt service
+ * Named request templates with typed parameter slots that expand into chat messages.
+ */
+
+import { randomUUID } from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+
+const COLLECTION = 'saved-prompts'
+const PARAM_TYPES = ['string', 'text', 'number', 'boolean', 'enum']
+const MESSAGE_ROLES = ['system', 'user', 'assistant']
+const SLOT_PATTERN = /\{\{\s*([a-zA-Z_][\w]*)\s*\}\}/g
+
+export class PromptTemplateError extends Error {
+  constructor(message, details = []) {
+    super(message)
+    this.name = 'PromptTemplateError'
+    this.details = details
+  }
+}
+
+/**
+ * Validate a template definition
+ * @returns {string[]} Problems found (empty when valid)
+ */
+export const validatePromptTemplate = template => {
+  const errors = []
+  if (!template || typeof template !== 'object') return ['Template must be an object']
+  if (!String(template.name || '').trim()) errors.push('name is required')
+
+  const params = Array.isArray(template.params) ? template.params : []
+  const names = new Set()
+  params.forEach((param, index) => {
+    if (!/^[a-zA-Z_]\w*$/.test(param?.name || '')) {
+      errors.push(`params[${index}].name must be an identifier`)
+    } else if (names.has(param.name)) {
+      errors.push(`params[${index}].name "${param.name}" is duplicated`)
+    } else {
+      names.add(param.name)
+    }
+    if (!PARAM_TYPES.includes(param?.type || 'string')) {
+      errors.push(`params[${index}].type must be one of: ${PARAM_TYPES.join(', ')}`)
+    }
+    if (param?.type === 'enum' && (!Array.isArray(param.options) || !param.options.length)) {
+      errors.push(`params[${index}].options is required for enum params`)
+    }
+  })
+
+  if (!Array.isArray(template.messages) || template.messages.length === 0) {
+    errors.push('messages must be a non-empty array')
+  } else {
+    template.messages.forEach((message, index) => {
+      if (!MESSAGE_ROLES.includes(message?.role)) {
+        errors.push(`messages[${index}].role must be one of: ${MESSAGE_ROLES.join(', ')}`)
+      }
+      if (typeof message?.content !== 'string') {
+        errors.push(`messages[${index}].content must be a string`)
+        return
+      }
+      for (const [, slot] of message.content.matchAll(SLOT_PATTERN)) {
+        if (!names.has(slot)) {
+          errors.push(`messages[${index}] uses undeclared slot "{{${slot}}}"`)
+        }
+      }
+    })
+  }
+
+  if (template.toolIds !== undefined && !Array.isArray(template.toolIds)) {
+    errors.push('toolIds must be an array')
+  }
+  return errors
+}
+
+const toStoredTemplate = (id, template, existing) => ({
+  id,
+  name: String(template.name).trim(),
+  description: template.description || '',
+  params: (template.params || []).map(param => ({
+    name: param.name,
+    type: param.type || 'string',
+    description: param.description || '',
+    required: param.required !== false && param.default === undefined,
+    default: param.default,
+    options: param.type === 'enum' ? param.options : undefined,
+  })),
+  messages: template.messages.map(({ role, content }) => ({ role, content })),
+  toolIds: Array.isArray(template.toolIds) ? template.toolIds : [],
+  settings: template.settings && typeof template.settings === 'object' ? template.settings : {},
+  createdAt: existing?.createdAt || new Date().toISOString(),
+  updatedAt: new Date().toISOString(),
+})
+
+export const createSavedPrompt = template => {
+  const errors = validatePromptTemplate(template)
+  if (errors.length) throw new PromptTemplateError('Invalid prompt template', errors)
+  const id = randomUUID()
+  return writeRecord(COLLECTION, id, toStoredTemplate(id, template))
+}
+
+export const updateSavedPrompt = (id, template) => {
+  const existing = readRecord(COLLECTION, id)
+  if (!existing) return null
+  const errors = validatePromptTemplate(template)
+  if (errors.length) throw new PromptTemplateError('Invalid prompt template', errors)
+  return writeRecord(COLLECTION, id, toStoredTemplate(id, template, existing))
+}
+
+export const getSavedPrompt = id => readRecord(COLLECTION, id)
+
+export const deleteSavedPrompt = id => deleteRecord(COLLECTION, id)
+
+export const listSavedPrompts = () =>
+  listRecords(COLLECTION).sort((a, b) => String(a.name).localeCompare(String(b.name)))
+
+const coerceParam = (param, raw) => {
+  switch (param.type) {
+    case 'number': {
+      const value = typeof raw === 'number' ? raw : Number(raw)
+      if (!Number.isFinite(value)) throw new Error(`"${param.name}" must be a number`)
+      return value
+    }
+    case 'boolean':
+      if (typeof raw === 'boolean') return raw
+      if (raw === 'true' || raw === 'false') return raw === 'true'
+      throw new Error(`"${param.name}" must be a boolean`)
+    case 'enum':
+      if (!param.options.includes(raw)) {
+        throw new Error(`"${param.name}" must be one of: ${param.options.join(', ')}`)
+      }
+      return raw
+    default:
+      if (typeof raw === 'object') throw new Error(`"${param.name}" must be a string`)
+      return String(raw)
+  }
+}
+
+/**
+ * Resolve parameter values against the template's slots (defaults, required checks, types)
+ */
+export const resolvePromptParams = (template, values = {}) => {
+  const resolved = {}
+  const errors = []
+  for (const param of template.params || []) {
+    const raw = values[param.name] ?? param.default
+    if (raw === undefined || raw === null || raw === '') {
+      if (param.required) errors.push(`Missing required parameter "${param.name}"`)
+      else resolved[param.name] = ''
+      continue
+    }
+    try {
+      resolved[param.name] = coerceParam(param, raw)
+    } catch (error) {
+      errors.push(error.message)
+    }
+  }
+  if (errors.length) throw new PromptTemplateError('Invalid prompt parameters', errors)
+  return resolved
+}
+
+/**
+ * Expand a template into messages (plus tool settings) for the chat pipeline
+ * @returns {{ messages: Array, toolIds: string[], settings: Object }}
+ */
+export const expandSavedPrompt = (template, values) => {
+  const resolved = resolvePromptParams(template, values)
+  return {
+    messages: template.messages.map(message => ({
+      role: message.role,
+      content: message.content.replace(SLOT_PATTERN, (_, slot) => String(resolved[slot] ?? '')),
+    })),
+    toolIds: template.toolIds || [],
+    settings: template.settings || {},
+  }
+}
@@ -0,0 +1,182 @@
+/**
+ * Screenshot service
+ * Captures rendered screenshots of pages whose text is hard to extract (charts, canvas, heavy
+ * scripting) so vision-capable models can read them and users can check citations visually.
+ * Rendering goes through SCREENSHOT_SERVICE_URL (a headless renderer answering
+ * GET ?url=... with image bytes) when set, otherwise through the Jina reader's screenshot mode.
+ * Images live next to their metadata records in the "screenshots" data collection.
+ */
+
+import crypto from 'crypto'
+import fs from 'fs'
+import path from 'path'
+import {
+  deleteRecord,
+  listRecords,
+  readRecord,
+  resolveCollectionDir,
+  writeRecord,
+} from '../utils/dataStore.js'
+import { getResearchRun, saveResearchRun } from './researchRunStore.js'
+
+const COLLECTION = 'screenshots'
+const REQUEST_TIMEOUT_MS = 60000
+const MAX_IMAGE_BYTES = 8 * 1024 * 1024
+const IMAGE_EXTENSIONS = { 'image/png': 'png', 'image/jpeg': 'jpg', 'image/webp': 'webp' }
+
+const withTimeout = signal => {
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  return signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal
+}
+
+const jinaHeaders = () =>
+  process.env.JINA_API_KEY ? { Authorization: `Bearer ${process.env.JINA_API_KEY}` } : {}
+
+// Jina renders the page and answers with a link to the stored screenshot
+const resolveJinaScreenshotUrl = async (url, signal) => {
+  const response = await fetch(`https://r.jina.ai/${url}`, {
+    headers: { Accept: 'application/json', 'X-Return-Format': 'screenshot', ...jinaHeaders() },
+    signal: withTimeout(signal),
+  })
+  if (!response.ok) throw new Error(`Screenshot render failed: HTTP ${response.status}`)
+  const body = await response.text()
+  let data = null
+  try {
+    data = JSON.parse(body)?.data
+  } catch {
+    // Plain-text answer: take the first image link
+  }
+  const imageUrl =
+    data?.screenshotUrl || data?.screenshot || body.match(/https?:\/\/\S+?\.png/)?.[0]
+  if (!imageUrl) throw new Error('Screenshot render returned no image')
+  return imageUrl
+}
+
+const downloadImage = async (imageUrl, signal) => {
+  const response = await fetch(imageUrl, { headers: jinaHeaders(), signal: withTimeout(signal) })
+  if (!response.ok) throw new Error(`Screenshot download failed: HTTP ${response.status}`)
+  const mimeType = (response.headers.get('content-type') || 'image/png').split(';')[0].trim()
+  if (!IMAGE_EXTENSIONS[mimeType]) throw new Error(`Unsupported screenshot type: ${mimeType}`)
+  const buffer = Buffer.from(await response.arrayBuffer())
+  if (buffer.length > MAX_IMAGE_BYTES) throw new Error('Screenshot exceeds the size limit')
+  return { buffer, mimeType }
+}
+
+const renderScreenshot = async (url, signal) => {
+  const serviceUrl = process.env.SCREENSHOT_SERVICE_URL
+  if (serviceUrl) {
+    const target = new URL(serviceUrl)
+    target.searchParams.set('url', url)
+    return downloadImage(target.toString(), signal)
+  }
+  return downloadImage(await resolveJinaScreenshotUrl(url, signal), signal)
+}
+
+const imagePath = record => path.join(resolveCollectionDir(COLLECTION), record.file)
+
+export const getScreenshot = id => readRecord(COLLECTION, id)
+
+export const listScreenshots = () => listRecords(COLLECTION)
+
+/**
+ * Delete a screenshot and its image; research runs keep their reference, whose image then 404s
+ */
+export const deleteScreenshot = id => {
+  const record = getScreenshot(id)
+  if (!record) return false
+  if (record.file) fs.rmSync(imagePath(record), { force: true })
+  return deleteRecord(COLLECTION, id)
+}
+
+/**
+ * Stored image bytes of a screenshot
+ * @returns {{ buffer: Buffer, mimeType: string }|null}
+ */
+export const readScreenshotImage = id => {
+  const record = getScreenshot(id)
+  if (!record?.file || !fs.existsSync(imagePath(record))) return null
+  return { buffer: fs.readFileSync(imagePath(record)), mimeType: record.mime_type }
+}
+
+/**
+ * Public shape of a screenshot for tool results and API responses
+ */
+export const toScreenshotRef = record => ({
+  id: record.id,
+  url: record.url,
+  captured_at: record.captured_at,
+  image_url: `/api/screenshots/${record.id}/image`,
+})
+
+/**
+ * Render a page, store the image, and optionally attach it to a research run's source
+ * @param {string} url
+ * @param {Object} [options]
+ * @param {string} [options.runId] Research run whose source with this URL gets the screenshot
+ * @returns {Promise<Object>} Screenshot record
+ */
+export const captureScreenshot = async (url, { runId, signal } = {}) => {
+  const target = new URL(String(url || '').trim())
+  if (!/^https?:$/.test(target.protocol)) throw new Error(`Unsupported URL: ${url}`)
+  if (runId) findRunSourceIndex(runId, target.href)
+  const { buffer, mimeType } = await renderScreenshot(target.href, signal)
+  const id = crypto.randomUUID()
+  const file = `${id}.${IMAGE_EXTENSIONS[mimeType]}`
+  const record = {
+    id,
+    url: target.href,
+    file,
+    mime_type: mimeType,
+    bytes: buffer.length,
+    captured_at: new Date().toISOString(),
+  }
+  fs.writeFileSync(imagePath(record), buffer)
+  writeRecord(COLLECTION, id, record)
+  if (runId) attachToRun(runId, record)
+  return record
+}
+
+const sameUrl = (value, href) => {
+  try {
+    return new URL(value).href === href
+  } catch {
+    return false
+  }
+}
+
+// Checked before rendering so a bad run id does not leave an orphan image behind
+const findRunSourceIndex = (runId, url) => {
+  const run = getResearchRun(runId)
+  if (!run) throw new Error(`Research run not found: ${runId}`)
+  const index = (run.sources || []).findIndex(source => sameUrl(source?.url || source?.uri, url))
+  if (index === -1) throw new Error(`Source not found in run ${runId}: ${url}`)
+  return index
+}
+
+const attachToRun = (runId, record) => {
+  const index = findRunSourceIndex(runId, record.url)
+  const run = getResearchRun(runId)
+  const sources = [...run.sources]
+  sources[index] = { ...sources[index], screenshot: toScreenshotRef(record) }
+  saveResearchRun({ ...run, sources })
+}
+
+/**
+ * User message carrying captured screenshots as image parts, for vision-capable models
+ * @param {Array<{ id: string, url: string }>} screenshots
+ * @returns {Object|null}
+ */
+export const buildScreenshotMessage = screenshots => {
+  const parts = screenshots.flatMap(screenshot => {
+    const image = readScreenshotImage(screenshot.id)
+    if (!image) return []
+    return [
+      { type: 'text', text: `Screenshot of ${screenshot.url}:` },
+      {
+        type: 'image_url',
+        image_url: { url: `data:${image.mimeType};base64,${image.buffer.toString('base64')}` },
+      },
+    ]
+  })
+  return parts.length ? { role: 'user', content: parts } : null
+}
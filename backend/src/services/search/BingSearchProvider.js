@@ -0,0 +1,40 @@
+/**
+ * Bing Search Provider
+ * GET {baseUrl}/search on the Bing Web Search v7 API with the Ocp-Apim-Subscription-Key header
+ */
+
+import { SearchProvider, toSearchError } from './SearchProvider.js'
+
+export class BingSearchProvider extends SearchProvider {
+  constructor() {
+    super('bing')
+  }
+
+  get defaultBaseUrl() {
+    return 'https://api.bing.microsoft.com/v7.0'
+  }
+
+  get maxResults() {
+    return 50
+  }
+
+  async searchRequest(query, { apiKey, baseUrl, maxResults, signal }) {
+    const url = new URL(`${baseUrl}/search`)
+    url.searchParams.set('q', query)
+    url.searchParams.set('count', String(maxResults))
+    url.searchParams.set('textDecorations', 'false')
+    const response = await fetch(url, {
+      headers: { Accept: 'application/json', 'Ocp-Apim-Subscription-Key': apiKey },
+      signal,
+    })
+    if (!response.ok) throw await toSearchError(response, 'Bing')
+    const data = await response.json()
+    return {
+      results: (data?.webPages?.value || []).map(item => ({
+        title: item.name,
+        url: item.url,
+        content: item.snippet,
+      })),
+    }
+  }
+}
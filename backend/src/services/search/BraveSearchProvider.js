@@ -0,0 +1,36 @@
+/**
+ * Brave Search Provider
+ * GET {baseUrl}/web/search with the X-Subscription-Token header
+ */
+
+import { SearchProvider, stripHtml, toSearchError } from './SearchProvider.js'
+
+export class BraveSearchProvider extends SearchProvider {
+  constructor() {
+    super('brave')
+  }
+
+  get defaultBaseUrl() {
+    return 'https://api.search.brave.com/res/v1'
+  }
+
+  async searchRequest(query, { apiKey, baseUrl, maxResults, signal }) {
+    const url = new URL(`${baseUrl}/web/search`)
+    url.searchParams.set('q', query)
+    url.searchParams.set('count', String(maxResults))
+    url.searchParams.set('extra_snippets', 'true')
+    const response = await fetch(url, {
+      headers: { Accept: 'application/json', 'X-Subscription-Token': apiKey },
+      signal,
+    })
+    if (!response.ok) throw await toSearchError(response, 'Brave')
+    const data = await response.json()
+    return {
+      results: (data?.web?.results || []).map(item => ({
+        title: stripHtml(item.title),
+        url: item.url,
+        content: [item.description, ...(item.extra_snippets || [])].map(stripHtml).join('\n'),
+      })),
+    }
+  }
+}
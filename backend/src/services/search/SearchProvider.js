@@ -0,0 +1,151 @@
+/**
+ * Base Search Provider
+ * Every provider returns Tavily-shaped output ({ answer, results: [{ title, url, content }] }) so
+ * web search results keep flowing into sources and citations unchanged.
+ */
+
+const REQUEST_TIMEOUT_MS = 30000
+// Search APIs reject very long queries; domain filters are added as site: terms up to this length
+const MAX_QUERY_CHARS = 380
+
+/**
+ * Error carrying the HTTP status of a failed search request, so utils/errors can classify it
+ */
+export const toSearchError = async (response, provider) => {
+  const body = await response.text().catch(() => '')
+  let message = body || response.statusText
+  try {
+    const data = JSON.parse(body)
+    message = data?.error?.message || data?.message || data?.detail || data?.error || message
+  } catch {
+    // Plain-text error body
+  }
+  return Object.assign(
+    new Error(`${provider} search failed (HTTP ${response.status}): ${message}`.trim()),
+    { status: response.status, headers: response.headers },
+  )
+}
+
+export const stripHtml = text =>
+  String(text || '')
+    .replace(/<[^>]+>/g, '')
+    .replace(/&(amp|lt|gt|quot|#39|nbsp);/g, (match, entity) => {
+      const entities = { amp: '&', lt: '<', gt: '>', quot: '"', '#39': "'", nbsp: ' ' }
+      return entities[entity]
+    })
+    .trim()
+
+const hostOf = url => {
+  try {
+    return new URL(url).hostname.toLowerCase()
+  } catch {
+    return ''
+  }
+}
+
+// "example.com" also matches "www.example.com"; entries may carry a path ("ncbi.nlm.nih.gov/pmc")
+const matchesDomain = (url, domain) => {
+  const [domainHost, ...pathParts] = domain.toLowerCase().split('/')
+  const host = hostOf(url)
+  if (host !== domainHost && !host.endsWith(`.${domainHost}`)) return false
+  if (!pathParts.length) return true
+  try {
+    return new URL(url).pathname.toLowerCase().startsWith(`/${pathParts.join('/')}`)
+  } catch {
+    return false
+  }
+}
+
+export class SearchProvider {
+  constructor(name) {
+    this.name = name
+  }
+
+  get defaultBaseUrl() {
+    return ''
+  }
+
+  // Largest result count accepted by one request
+  get maxResults() {
+    return 20
+  }
+
+  // Providers that filter by domain server-side (Tavily include_domains) override this
+  get supportsDomainFilter() {
+    return false
+  }
+
+  get requiresApiKey() {
+    return true
+  }
+
+  /**
+   * Run one search request
+   * @returns {Promise<{ answer?: string, results: Array<{ title, url, content, score? }> }>}
+   * @abstract
+   */
+  async searchRequest(_query, _options) {
+    throw new Error(`${this.name}: searchRequest not implemented`)
+  }
+
+  /**
+   * Search the web
+   * @param {string} query
+   * @param {Object} options
+   * @param {string} [options.apiKey]
+   * @param {string} [options.baseUrl]
+   * @param {number} [options.maxResults] Default 5
+   * @param {string[]} [options.includeDomains] Restrict results to these domains
+   * @param {'basic'|'advanced'} [options.depth]
+   * @param {AbortSignal} [options.signal]
+   * @returns {Promise<{ answer, results: Array<{ title, url, content, score }>, provider }>}
+   */
+  async search(query, options = {}) {
+    if (this.requiresApiKey && !options.apiKey) {
+      throw new Error(`${this.name} search API key not configured`)
+    }
+    const maxResults = Math.min(Math.max(1, options.maxResults || 5), this.maxResults)
+    const baseUrl = String(options.baseUrl || this.defaultBaseUrl).replace(/\/$/, '')
+    if (!baseUrl) throw new Error(`${this.name} search base URL not configured`)
+    const domains = options.includeDomains?.length ? options.includeDomains : null
+    const filterLocally = domains && !this.supportsDomainFilter
+
+    let searchQuery = query
+    if (filterLocally) {
+      // Best effort: as many site: terms as fit, then drop results outside the domain list
+      const sites = []
+      for (const domain of domains) {
+        const next = [...sites, `site:${domain}`].join(' OR ')
+        if (query.length + next.length + 3 > MAX_QUERY_CHARS) break
+        sites.push(`site:${domain}`)
+      }
+      if (sites.length) searchQuery = `${query} (${sites.join(' OR ')})`
+    }
+
+    const signal = options.signal
+      ? AbortSignal.any([options.signal, AbortSignal.timeout(REQUEST_TIMEOUT_MS)])
+      : AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+    const output = await this.searchRequest(searchQuery, {
+      ...options,
+      baseUrl,
+      includeDomains: domains,
+      maxResults: filterLocally ? this.maxResults : maxResults,
+      signal,
+    })
+
+    let results = (output.results || []).filter(result => result?.url)
+    if (filterLocally) {
+      results = results.filter(result => domains.some(domain => matchesDomain(result.url, domain)))
+    }
+    return {
+      answer: output.answer || null,
+      results: results.slice(0, maxResults).map(result => ({
+        title: result.title || result.url,
+        url: result.url,
+        content: result.content || '',
+        score: result.score ?? null,
+      })),
+      provider: this.name,
+    }
+  }
+}
@@ -0,0 +1,48 @@
+/**
+ * SearXNG Search Provider
+ * GET {baseUrl}/search?format=json on a self-hosted instance ("json" must be listed under
+ * search.formats in its settings.yml). No API key is needed.
+ */
+
+import { SearchProvider, stripHtml, toSearchError } from './SearchProvider.js'
+
+// Older instances list answers as strings, newer ones as { answer, url }
+const firstAnswer = answers => {
+  const answer = Array.isArray(answers) ? answers[0] : null
+  return answer ? stripHtml(typeof answer === 'string' ? answer : answer.answer) : null
+}
+
+export class SearxngSearchProvider extends SearchProvider {
+  constructor() {
+    super('searxng')
+  }
+
+  get requiresApiKey() {
+    return false
+  }
+
+  async searchRequest(query, { apiKey, baseUrl, signal }) {
+    const url = new URL(`${baseUrl}/search`)
+    url.searchParams.set('q', query)
+    url.searchParams.set('format', 'json')
+    const response = await fetch(url, {
+      headers: {
+        Accept: 'application/json',
+        // Instances behind an authenticating proxy
+        ...(apiKey ? { Authorization: `Bearer ${apiKey}` } : {}),
+      },
+      signal,
+    })
+    if (!response.ok) throw await toSearchError(response, 'SearXNG')
+    const data = await response.json()
+    return {
+      answer: firstAnswer(data?.answers),
+      results: (data?.results || []).map(item => ({
+        title: item.title,
+        url: item.url,
+        content: stripHtml(item.content),
+        score: item.score,
+      })),
+    }
+  }
+}
@@ -0,0 +1,39 @@
+/**
+ * Serper Search Provider
+ * POST {baseUrl}/search (Google results) with the X-API-KEY header
+ */
+
+import { SearchProvider, toSearchError } from './SearchProvider.js'
+
+export class SerperSearchProvider extends SearchProvider {
+  constructor() {
+    super('serper')
+  }
+
+  get defaultBaseUrl() {
+    return 'https://google.serper.dev'
+  }
+
+  get maxResults() {
+    return 100
+  }
+
+  async searchRequest(query, { apiKey, baseUrl, maxResults, signal }) {
+    const response = await fetch(`${baseUrl}/search`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', 'X-API-KEY': apiKey },
+      body: JSON.stringify({ q: query, num: maxResults }),
+      signal,
+    })
+    if (!response.ok) throw await toSearchError(response, 'Serper')
+    const data = await response.json()
+    return {
+      answer: data?.answerBox?.answer || data?.answerBox?.snippet || null,
+      results: (data?.organic || []).map(item => ({
+        title: item.title,
+        url: item.link,
+        content: item.snippet,
+      })),
+    }
+  }
+}
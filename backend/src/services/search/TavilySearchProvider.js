@@ -0,0 +1,47 @@
+/**
+ * Tavily Search Provider
+ * POST {baseUrl}/search with server-side domain filtering and a generated answer
+ */
+
+import { SearchProvider, toSearchError } from './SearchProvider.js'
+
+export class TavilySearchProvider extends SearchProvider {
+  constructor() {
+    super('tavily')
+  }
+
+  get defaultBaseUrl() {
+    return 'https://api.tavily.com'
+  }
+
+  get supportsDomainFilter() {
+    return true
+  }
+
+  async searchRequest(query, { apiKey, baseUrl, maxResults, includeDomains, depth, signal }) {
+    const response = await fetch(`${baseUrl}/search`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({
+        api_key: apiKey,
+        query,
+        search_depth: depth || 'basic',
+        ...(includeDomains ? { include_domains: includeDomains } : {}),
+        include_answer: true,
+        max_results: maxResults,
+      }),
+      signal,
+    })
+    if (!response.ok) throw await toSearchError(response, 'Tavily')
+    const data = await response.json()
+    return {
+      answer: data?.answer,
+      results: (data?.results || []).map(item => ({
+        title: item.title,
+        url: item.url,
+        content: item.content,
+        score: item.score,
+      })),
+    }
+  }
+}
@@ -0,0 +1,110 @@
+/**
+ * Web search providers
+ * The web/academic/standards/legal search tools run on the provider selected by the request's
+ * searchProvider (or SEARCH_PROVIDER), defaulting to Tavily
+ */
+
+import { BingSearchProvider } from './BingSearchProvider.js'
+import { BraveSearchProvider } from './BraveSearchProvider.js'
+import { SearxngSearchProvider } from './SearxngSearchProvider.js'
+import { SerperSearchProvider } from './SerperSearchProvider.js'
+import { TavilySearchProvider } from './TavilySearchProvider.js'
+
+export const SEARCH_PROVIDERS = ['tavily', 'searxng', 'brave', 'serper', 'bing']
+const DEFAULT_SEARCH_PROVIDER = 'tavily'
+
+// Server-side fallbacks when the request carries no key / base URL
+const ENV_API_KEYS = {
+  tavily: ['TAVILY_API_KEY', 'PUBLIC_TAVILY_API_KEY'],
+  searxng: ['SEARXNG_API_KEY'],
+  brave: ['BRAVE_SEARCH_API_KEY'],
+  serper: ['SERPER_API_KEY'],
+  bing: ['BING_SEARCH_API_KEY'],
+}
+const ENV_BASE_URLS = {
+  searxng: 'SEARXNG_BASE_URL',
+  bing: 'BING_SEARCH_BASE_URL',
+}
+
+const providerCache = new Map()
+
+/**
+ * Get search provider instance
+ * @param {string} provider - Provider name
+ * @returns {SearchProvider}
+ */
+export function getSearchProvider(provider) {
+  if (providerCache.has(provider)) return providerCache.get(provider)
+
+  let instance
+  switch (provider) {
+    case 'tavily':
+      instance = new TavilySearchProvider()
+      break
+    case 'searxng':
+      instance = new SearxngSearchProvider()
+      break
+    case 'brave':
+      instance = new BraveSearchProvider()
+      break
+    case 'serper':
+      instance = new SerperSearchProvider()
+      break
+    case 'bing':
+      instance = new BingSearchProvider()
+      break
+    default:
+      throw new Error(
+        `Unsupported search provider: ${provider}. Supported: ${SEARCH_PROVIDERS.join(', ')}`,
+      )
+  }
+
+  providerCache.set(provider, instance)
+  return instance
+}
+
+export const isSearchProviderSupported = provider => SEARCH_PROVIDERS.includes(provider)
+
+/**
+ * Resolve provider, key and base URL from a tool config
+ * Priority: request (searchApiKey / tavilyApiKey, searchBaseUrl) > environment variables
+ * @param {Object} [toolConfig] - { searchProvider, searchApiKey, searchBaseUrl, tavilyApiKey }
+ */
+export const resolveSearchConfig = (toolConfig = {}) => {
+  const provider = String(
+    toolConfig?.searchProvider || process.env.SEARCH_PROVIDER || DEFAULT_SEARCH_PROVIDER,
+  ).toLowerCase()
+  const requestKey =
+    provider === 'tavily'
+      ? toolConfig?.tavilyApiKey || toolConfig?.searchApiKey
+      : toolConfig?.searchApiKey
+  const envKey = ENV_API_KEYS[provider]?.map(name => process.env[name]).find(Boolean)
+  return {
+    provider,
+    apiKey: requestKey || envKey || '',
+    baseUrl: toolConfig?.searchBaseUrl || process.env[ENV_BASE_URLS[provider]] || undefined,
+  }
+}
+
+/**
+ * Search the web with the configured provider
+ * @param {string} query
+ * @param {Object} [options] - { maxResults, includeDomains, depth, signal }
+ * @param {Object} [toolConfig] - See resolveSearchConfig
+ * @returns {Promise<{ answer, results: Array<{ title, url, content, score }>, provider }>}
+ */
+export const webSearch = async (query, options = {}, toolConfig = {}) => {
+  const { provider, apiKey, baseUrl } = resolveSearchConfig(toolConfig)
+  const searchProvider = getSearchProvider(provider)
+  if (searchProvider.requiresApiKey && !apiKey) {
+    const envName = ENV_API_KEYS[provider][0]
+    throw new Error(
+      `${provider === 'tavily' ? 'Tavily' : provider} API key not configured. ` +
+        `Set ${envName} or add it in settings.`,
+    )
+  }
+  if (!baseUrl && !searchProvider.defaultBaseUrl) {
+    throw new Error(`${provider} base URL not configured. Set ${ENV_BASE_URLS[provider]}.`)
+  }
+  return searchProvider.search(query, { ...options, apiKey, baseUrl })
+}
@@ -0,0 +1,76 @@
+/**
+ * Search saturation service
+ * Skips repeated search queries and ends a step's searching once successive searches stop
+ * turning up new URLs, so research steps don't burn tokens and API calls on known sources.
+ */
+
+const DEFAULT_NOVELTY_THRESHOLD = 0.2
+const DEFAULT_PATIENCE = 2
+
+export const SATURATION_NOTE =
+  '_Saturation reached: further searches for this step were skipped because recent searches returned mostly already-seen sources._'
+
+/**
+ * Minimum share of new URLs a search must return to count as novel; 0 disables the stop rule
+ */
+export const resolveNoveltyThreshold = value => {
+  const threshold = Number.parseFloat(value ?? process.env.SEARCH_NOVELTY_THRESHOLD)
+  return Number.isFinite(threshold) && threshold >= 0 && threshold <= 1
+    ? threshold
+    : DEFAULT_NOVELTY_THRESHOLD
+}
+
+// Case, punctuation and word order don't make a query new
+export const normalizeQuery = query =>
+  Array.from(new Set(String(query || '').toLowerCase().match(/[\p{L}\p{N}]+/gu) || []))
+    .sort()
+    .join(' ')
+
+/**
+ * Per-step saturation tracker
+ * @param {Object} args
+ * @param {number} args.threshold Novelty threshold (see resolveNoveltyThreshold)
+ * @param {Set<string>} args.seenQueries Normalized queries already issued in this run (shared)
+ * @param {number} [args.patience] Consecutive low-novelty searches before the step saturates
+ */
+export const createSaturationTracker = ({
+  threshold = resolveNoveltyThreshold(),
+  seenQueries = new Set(),
+  patience = DEFAULT_PATIENCE,
+} = {}) => {
+  let lowNoveltyStreak = 0
+  let saturated = false
+
+  return {
+    get saturated() {
+      return saturated
+    },
+    /**
+     * Reason to skip a search before it runs, or null to run it
+     */
+    checkQuery(query) {
+      if (saturated) {
+        return 'Search saturation reached for this step: recent searches returned mostly already-seen sources. Write the finding from the sources already collected.'
+      }
+      const normalized = normalizeQuery(query)
+      if (normalized && seenQueries.has(normalized)) {
+        return 'Duplicate query: this search was already run during this research. Use its earlier results or try a substantially different query.'
+      }
+      return null
+    },
+    /**
+     * Record a finished search; call before its URLs are added to the known sources
+     * @returns {number|null} Share of result URLs not seen before, null for empty results
+     */
+    recordResult(query, urls, knownUrls) {
+      const normalized = normalizeQuery(query)
+      if (normalized) seenQueries.add(normalized)
+      const unique = Array.from(new Set((urls || []).filter(Boolean)))
+      if (!unique.length) return null
+      const novelty = unique.filter(url => !knownUrls.has(url)).length / unique.length
+      lowNoveltyStreak = novelty < threshold ? lowNoveltyStreak + 1 : 0
+      if (threshold > 0 && lowNoveltyStreak >= patience) saturated = true
+      return novelty
+    },
+  }
+}
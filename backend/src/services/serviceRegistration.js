@@ -0,0 +1,191 @@
+/**
+ * OS service registration helpers
+ * Builds launchd / systemd definitions and a Windows logon Scheduled Task that run the backend in
+ * background mode.
+ */
+
+import os from 'os'
+import path from 'path'
+import { fileURLToPath } from 'url'
+
+export const SERVICE_LABEL = 'com.qurio.backend'
+export const WINDOWS_TASK_NAME = 'Qurio Backend'
+
+const BACKEND_ROOT = path.resolve(path.dirname(fileURLToPath(import.meta.url)), '..', '..')
+
+export const resolvePlatform = platform => {
+  const value = String(platform || process.platform).toLowerCase()
+  if (value === 'darwin' || value === 'launchd' || value === 'macos') return 'launchd'
+  if (value === 'win32' || value === 'windows') return 'windows'
+  if (value === 'linux' || value === 'systemd') return 'systemd'
+  throw new Error(`Unsupported service platform: ${platform}`)
+}
+
+const resolveOptions = (options = {}) => ({
+  nodePath: options.nodePath || process.execPath,
+  workingDirectory: options.workingDirectory || BACKEND_ROOT,
+  entry: options.entry || path.join(options.workingDirectory || BACKEND_ROOT, 'src', 'server.js'),
+  logDirectory: options.logDirectory || path.join(os.homedir(), '.qurio', 'logs'),
+  env: { BACKGROUND_MODE: '1', ...(options.env || {}) },
+})
+
+const escapeXml = value =>
+  String(value)
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;')
+
+export const buildLaunchdPlist = options => {
+  const { nodePath, workingDirectory, entry, logDirectory, env } = resolveOptions(options)
+  const envEntries = Object.entries(env)
+    .map(
+      ([key, value]) =>
+        `      <key>${escapeXml(key)}</key>\n      <string>${escapeXml(value)}</string>`,
+    )
+    .join('\n')
+
+  return `<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+  <dict>
+    <key>Label</key>
+    <string>${SERVICE_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+      <string>${escapeXml(nodePath)}</string>
+      <string>${escapeXml(entry)}</string>
+    </array>
+    <key>WorkingDirectory</key>
+    <string>${escapeXml(workingDirectory)}</string>
+    <key>EnvironmentVariables</key>
+    <dict>
+${envEntries}
+    </dict>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardOutPath</key>
+    <string>${escapeXml(path.join(logDirectory, 'backend.out.log'))}</string>
+    <key>StandardErrorPath</key>
+    <string>${escapeXml(path.join(logDirectory, 'backend.err.log'))}</string>
+  </dict>
+</plist>
+`
+}
+
+export const buildSystemdUnit = options => {
+  const { nodePath, workingDirectory, entry, env } = resolveOptions(options)
+  const envLines = Object.entries(env)
+    .map(([key, value]) => `Environment=${key}=${value}`)
+    .join('\n')
+
+  return `[Unit]
+Description=Qurio backend (background mode)
+After=network-online.target
+
+[Service]
+Type=simple
+WorkingDirectory=${workingDirectory}
+ExecStart=${nodePath} ${entry}
+${envLines}
+Restart=on-failure
+RestartSec=5
+
+[Install]
+WantedBy=default.target
+`
+}
+
+const escapeVbs = value => String(value).replace(/"/g, '""')
+
+/**
+ * Windows launcher script, run by a Scheduled Task at logon: node is not a Windows service
+ * binary (the Service Control Manager would fail to start it with error 1053), so the task runs
+ * this script, which sets the environment and starts the backend without a console window.
+ */
+export const buildWindowsLauncher = options => {
+  const { nodePath, workingDirectory, entry, logDirectory, env } = resolveOptions(options)
+  const envLines = Object.entries(env)
+    .map(([key, value]) => `environment("${escapeVbs(key)}") = "${escapeVbs(value)}"`)
+    .join('\r\n')
+  const outLog = path.join(logDirectory, 'backend.out.log')
+  const errLog = path.join(logDirectory, 'backend.err.log')
+  const command =
+    `cmd.exe /c "if not exist "${logDirectory}" mkdir "${logDirectory}" & ` +
+    `"${nodePath}" "${entry}" >> "${outLog}" 2>> "${errLog}""`
+
+  return [
+    'Set shell = CreateObject("WScript.Shell")',
+    'Set environment = shell.Environment("PROCESS")',
+    envLines,
+    `shell.CurrentDirectory = "${escapeVbs(workingDirectory)}"`,
+    `shell.Run "${escapeVbs(command)}", 0, False`,
+    '',
+  ].join('\r\n')
+}
+
+export const buildWindowsTaskCommands = launcherPath => ({
+  install: [
+    `schtasks /Create /TN "${WINDOWS_TASK_NAME}" /TR "wscript.exe //B \\"${launcherPath}\\"" /SC ONLOGON /RL LIMITED /F`,
+    `schtasks /Run /TN "${WINDOWS_TASK_NAME}"`,
+  ],
+  uninstall: [
+    `schtasks /End /TN "${WINDOWS_TASK_NAME}"`,
+    `schtasks /Delete /TN "${WINDOWS_TASK_NAME}" /F`,
+  ],
+})
+
+export const getServiceDefinitionPath = platform => {
+  const resolved = resolvePlatform(platform)
+  if (resolved === 'launchd') {
+    return path.join(os.homedir(), 'Library', 'LaunchAgents', `${SERVICE_LABEL}.plist`)
+  }
+  if (resolved === 'systemd') {
+    return path.join(os.homedir(), '.config', 'systemd', 'user', 'qurio-backend.service')
+  }
+  return path.join(
+    process.env.LOCALAPPDATA || path.join(os.homedir(), 'AppData', 'Local'),
+    'Qurio',
+    'qurio-backend.vbs',
+  )
+}
+
+/**
+ * Build the full service definition for a platform
+ * @returns {{ platform: string, path: string|null, content: string|null, install: string[], uninstall: string[] }}
+ */
+export const buildServiceDefinition = (platform, options = {}) => {
+  const resolved = resolvePlatform(platform)
+  const definitionPath = getServiceDefinitionPath(resolved)
+
+  if (resolved === 'launchd') {
+    return {
+      platform: resolved,
+      path: definitionPath,
+      content: buildLaunchdPlist(options),
+      install: [`launchctl load -w "${definitionPath}"`],
+      uninstall: [`launchctl unload -w "${definitionPath}"`],
+    }
+  }
+
+  if (resolved === 'systemd') {
+    return {
+      platform: resolved,
+      path: definitionPath,
+      content: buildSystemdUnit(options),
+      install: ['systemctl --user daemon-reload', 'systemctl --user enable --now qurio-backend'],
+      uninstall: ['systemctl --user disable --now qurio-backend'],
+    }
+  }
+
+  const commands = buildWindowsTaskCommands(definitionPath)
+  return {
+    platform: resolved,
+    path: definitionPath,
+    content: buildWindowsLauncher(options),
+    install: commands.install,
+    uninstall: commands.uninstall,
+  }
+}
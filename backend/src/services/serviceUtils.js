@@ -44,6 +44,10 @@ export const normalizeParts = content => {
         if (!url) return null
         return { type: 'image_url', image_url: { url } }
       }
+      // Inline documents from attachmentService (only for providers with supportsDocuments)
+      if (part?.type === 'media' && part.data) {
+        return { type: 'media', mimeType: part.mimeType, data: part.data }
+      }
       if (part?.text) return { type: 'text', text: part.text }
       return null
     })
@@ -79,3 +83,21 @@ export const normalizeGeminiMessages = messages => {
   if (systemMessages.length === 0) return messages
   return [...systemMessages, ...nonSystemMessages]
 }
+
+/**
+ * Plain text of the last user message (string or multimodal parts)
+ */
+export const getLastUserText = messages => {
+  const lastUser = (messages || [])
+    .slice()
+    .reverse()
+    .find(m => m?.role === 'user')
+  if (!lastUser) return ''
+  if (typeof lastUser.content === 'string') return lastUser.content
+  if (Array.isArray(lastUser.content)) {
+    return lastUser.content
+      .map(part => (typeof part === 'string' ? part : part?.text || ''))
+      .join('\n')
+  }
+  return ''
+}
@@ -0,0 +1,69 @@
+/**
+ * Session registry
+ * Issues session ids to client windows. A window sends its id with every stream it starts
+ * (session_id), so closing the window can cancel exactly its own streams while other windows keep
+ * running. Sessions live in memory: ids from before a restart are still accepted and simply have
+ * no streams.
+ */
+
+import { randomUUID } from 'crypto'
+import { normalizeSessionId, streamRegistry } from './streamRegistry.js'
+
+const MAX_LABEL_LENGTH = 200
+
+class SessionRegistry {
+  constructor() {
+    // Issued sessions by id: { label, createdAt }
+    this.sessions = new Map()
+  }
+
+  describe(sessionId) {
+    const id = normalizeSessionId(sessionId)
+    const entry = this.sessions.get(id)
+    return {
+      session_id: id,
+      label: entry?.label ?? null,
+      created_at: entry ? new Date(entry.createdAt).toISOString() : null,
+      active_streams: streamRegistry.list({ sessionId: id }).length,
+    }
+  }
+
+  /**
+   * Issue a session id
+   * @param {Object} [args]
+   * @param {string} [args.label] Window label, e.g. "main" or "quick-ask"
+   */
+  create({ label } = {}) {
+    const id = randomUUID()
+    this.sessions.set(id, {
+      label: label ? String(label).slice(0, MAX_LABEL_LENGTH) : null,
+      createdAt: Date.now(),
+    })
+    return this.describe(id)
+  }
+
+  /**
+   * Streams of a session that are still running
+   */
+  active(sessionId) {
+    return streamRegistry.list({ sessionId })
+  }
+
+  /**
+   * Cancel the session's streams; with forget, also drop the session (its window closed)
+   * @returns {{ cancelled: string[], existed: boolean }}
+   */
+  close(sessionId, { forget = false } = {}) {
+    const id = normalizeSessionId(sessionId)
+    const existed = this.sessions.has(id)
+    const cancelled = streamRegistry.cancelSession(id)
+    if (forget) this.sessions.delete(id)
+    return { cancelled, existed }
+  }
+
+  list() {
+    return Array.from(this.sessions.keys()).map(id => this.describe(id))
+  }
+}
+
+export const sessionRegistry = new SessionRegistry()
@@ -0,0 +1,335 @@
+/**
+ * Site explorer service
+ * Breadth-first crawl of one site from a seed URL, following internal links up to a depth limit
+ * (robots.txt respected, bounded concurrency and page count), summarized as a structured site map.
+ * Sites on private or local addresses are refused (see utils/publicFetch.js).
+ */
+
+import { assertPublicUrl, fetchPublicUrl } from '../utils/publicFetch.js'
+
+const USER_AGENT = 'QurioBot/1.0 (+https://github.com/havingautism/Qurio)'
+const REQUEST_TIMEOUT_MS = 10000
+const MAX_HTML_BYTES = 2 * 1024 * 1024
+const MAX_EXCERPT_CHARS = 600
+const MAX_HEADINGS = 12
+const MAX_CRAWL_DELAY_MS = 5000
+const LIMITS = {
+  depth: { default: 2, max: 4 },
+  pages: { default: 20, max: 60 },
+  concurrency: { default: 4, max: 8 },
+}
+const SKIPPED_EXTENSIONS =
+  /\.(?:png|jpe?g|gif|svg|webp|ico|pdf|zip|gz|tgz|tar|dmg|exe|msi|mp[34]|mov|avi|woff2?|ttf|css|js|json|xml|rss)$/i
+
+const clamp = (value, { default: fallback, max }) => {
+  const number = Number.parseInt(value, 10)
+  return Number.isFinite(number) && number > 0 ? Math.min(number, max) : fallback
+}
+
+const decodeEntities = text =>
+  text
+    .replace(/&lt;/g, '<')
+    .replace(/&gt;/g, '>')
+    .replace(/&quot;/g, '"')
+    .replace(/&#39;|&apos;/g, "'")
+    .replace(/&nbsp;/g, ' ')
+    .replace(/&#(\d+);/g, (_, code) => String.fromCharCode(Number(code)))
+    .replace(/&amp;/g, '&')
+
+const cleanText = html =>
+  decodeEntities(String(html || '').replace(/<[^>]+>/g, ' '))
+    .replace(/\s+/g, ' ')
+    .trim()
+
+/**
+ * Rules from robots.txt for our user agent (falls back to the "*" group)
+ * @returns {{ allow: string[], disallow: string[], crawlDelayMs: number }}
+ */
+export const parseRobots = (text, agent = 'qurio') => {
+  const groups = []
+  let current = null
+  let lastWasAgent = false
+  for (const rawLine of String(text || '').split(/\r?\n/)) {
+    const line = rawLine.replace(/#.*$/, '').trim()
+    const match = line.match(/^([A-Za-z-]+)\s*:\s*(.*)$/)
+    if (!match) continue
+    const field = match[1].toLowerCase()
+    const value = match[2].trim()
+    if (field === 'user-agent') {
+      if (!lastWasAgent) {
+        current = { agents: [], allow: [], disallow: [], crawlDelayMs: 0 }
+        groups.push(current)
+      }
+      current.agents.push(value.toLowerCase())
+      lastWasAgent = true
+      continue
+    }
+    lastWasAgent = false
+    if (!current) continue
+    if (field === 'allow' && value) current.allow.push(value)
+    else if (field === 'disallow' && value) current.disallow.push(value)
+    else if (field === 'crawl-delay') {
+      const seconds = Number.parseFloat(value)
+      if (Number.isFinite(seconds) && seconds > 0) current.crawlDelayMs = seconds * 1000
+    }
+  }
+  const group =
+    groups.find(item => item.agents.some(name => name !== '*' && agent.includes(name))) ||
+    groups.find(item => item.agents.includes('*'))
+  return group
+    ? { allow: group.allow, disallow: group.disallow, crawlDelayMs: group.crawlDelayMs }
+    : { allow: [], disallow: [], crawlDelayMs: 0 }
+}
+
+const patternToRegExp = pattern => {
+  const anchored = pattern.endsWith('$')
+  const body = (anchored ? pattern.slice(0, -1) : pattern)
+    .split('*')
+    .map(part => part.replace(/[.+?^${}()|[\]\\]/g, '\\$&'))
+    .join('.*')
+  return new RegExp(`^${body}${anchored ? '$' : ''}`)
+}
+
+/**
+ * Longest matching rule wins; Allow wins ties (RFC 9309)
+ */
+export const isPathAllowed = (rules, pathWithQuery) => {
+  let best = { length: -1, allowed: true }
+  for (const [patterns, allowed] of [
+    [rules.disallow, false],
+    [rules.allow, true],
+  ]) {
+    for (const pattern of patterns) {
+      if (!patternToRegExp(pattern).test(pathWithQuery)) continue
+      if (pattern.length > best.length || (pattern.length === best.length && allowed)) {
+        best = { length: pattern.length, allowed }
+      }
+    }
+  }
+  return best.allowed
+}
+
+export const fetchWithTimeout = (url, signal) => {
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  return fetchPublicUrl(url, {
+    headers: { 'User-Agent': USER_AGENT, Accept: 'text/html,application/xhtml+xml' },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+}
+
+export const loadRobots = async (origin, signal) => {
+  try {
+    const response = await fetchWithTimeout(`${origin}/robots.txt`, signal)
+    // Missing robots.txt (4xx) means no restrictions
+    if (!response.ok) return { allow: [], disallow: [], crawlDelayMs: 0 }
+    return parseRobots(await response.text())
+  } catch (error) {
+    if (signal?.aborted) throw error
+    return { allow: [], disallow: [], crawlDelayMs: 0 }
+  }
+}
+
+const normalizeLink = (href, baseUrl) => {
+  try {
+    const url = new URL(decodeEntities(href), baseUrl)
+    if (url.protocol !== 'http:' && url.protocol !== 'https:') return null
+    url.hash = ''
+    return url
+  } catch {
+    return null
+  }
+}
+
+const stripScripts = html =>
+  String(html || '')
+    .replace(/<script[\s\S]*?<\/script>/gi, ' ')
+    .replace(/<style[\s\S]*?<\/style>/gi, ' ')
+    .replace(/<noscript[\s\S]*?<\/noscript>/gi, ' ')
+
+const extractTitle = body => cleanText(body.match(/<title[^>]*>([\s\S]*?)<\/title>/i)?.[1])
+
+// Prefer <main>, drop navigation chrome
+const extractMainHtml = body =>
+  (body.match(/<main[\s\S]*?<\/main>/i)?.[0] || body.match(/<body[\s\S]*$/i)?.[0] || body).replace(
+    /<(nav|header|footer|aside)[\s\S]*?<\/\1>/gi,
+    ' ',
+  )
+
+/**
+ * Readable text of an HTML page with paragraph breaks kept
+ * @returns {{ title: string, text: string }}
+ */
+export const extractPageText = html => {
+  const body = stripScripts(html)
+  const text = decodeEntities(
+    extractMainHtml(body)
+      .replace(/<br\s*\/?>|<\/(?:p|div|li|h[1-6]|pre|tr|section|article|blockquote)>/gi, '\n\n')
+      .replace(/<[^>]+>/g, ' '),
+  )
+    .replace(/[ \t]+/g, ' ')
+    .replace(/\s*\n\s*\n\s*/g, '\n\n')
+    .trim()
+  return { title: extractTitle(body), text }
+}
+
+/**
+ * Title, description, headings, excerpt and outgoing links of an HTML page
+ */
+export const parsePage = (html, pageUrl) => {
+  const body = stripScripts(html)
+  const title = extractTitle(body)
+  const description = decodeEntities(
+    body.match(/<meta[^>]+name=["']description["'][^>]*content=["']([^"']*)["']/i)?.[1] ||
+      body.match(/<meta[^>]+content=["']([^"']*)["'][^>]*name=["']description["']/i)?.[1] ||
+      '',
+  ).trim()
+  const headings = Array.from(body.matchAll(/<h([12])[^>]*>([\s\S]*?)<\/h\1>/gi))
+    .map(match => cleanText(match[2]))
+    .filter(Boolean)
+    .slice(0, MAX_HEADINGS)
+  const text = cleanText(extractMainHtml(body))
+  const links = Array.from(body.matchAll(/<a\s[^>]*href=["']([^"'#][^"']*)["']/gi))
+    .map(match => normalizeLink(match[1], pageUrl))
+    .filter(Boolean)
+  return {
+    title,
+    description,
+    headings,
+    excerpt: text.length > MAX_EXCERPT_CHARS ? `${text.slice(0, MAX_EXCERPT_CHARS)}…` : text,
+    links,
+  }
+}
+
+const readHtml = async response => {
+  const type = response.headers.get('content-type') || ''
+  if (!/html/i.test(type)) return null
+  const html = await response.text()
+  return html.length > MAX_HTML_BYTES ? html.slice(0, MAX_HTML_BYTES) : html
+}
+
+const sleep = (ms, signal) =>
+  new Promise((resolve, reject) => {
+    const timer = setTimeout(resolve, ms)
+    signal?.addEventListener(
+      'abort',
+      () => {
+        clearTimeout(timer)
+        reject(new Error('Request aborted'))
+      },
+      { once: true },
+    )
+  })
+
+// Group pages by their first path segment so the summary reads like a table of contents
+const buildSections = pages => {
+  const sections = new Map()
+  for (const page of pages) {
+    const segment = new URL(page.url).pathname.split('/').filter(Boolean)[0] || ''
+    const path = `/${segment}`
+    if (!sections.has(path)) sections.set(path, { path, pages: 0, titles: [] })
+    const section = sections.get(path)
+    section.pages += 1
+    if (page.title && section.titles.length < 5) section.titles.push(page.title)
+  }
+  return Array.from(sections.values()).sort((a, b) => b.pages - a.pages)
+}
+
+/**
+ * Crawl a site from a seed URL
+ * @param {Object} args
+ * @param {string} args.url Seed URL; only links on the same host are followed
+ * @param {number} [args.maxDepth] Link hops from the seed (default 2, max 4)
+ * @param {number} [args.maxPages] Pages to fetch (default 20, max 60)
+ * @param {number} [args.concurrency] Parallel requests (default 4, max 8; 1 when robots.txt sets
+ *   a crawl delay)
+ * @param {string} [args.pathPrefix] Only follow links under this path (e.g. "/docs")
+ * @returns {Promise<Object>} { seed, pages, sections, skipped, truncated, results }
+ */
+export const exploreSite = async ({
+  url,
+  maxDepth,
+  maxPages,
+  concurrency,
+  pathPrefix,
+  signal,
+}) => {
+  const seed = normalizeLink(String(url || '').trim(), undefined)
+  if (!seed) throw new Error(`Invalid seed URL: ${url}`)
+  // Fail the crawl up front rather than reporting every page of a private site as an error
+  await assertPublicUrl(seed)
+  const depthLimit = clamp(maxDepth, LIMITS.depth)
+  const pageLimit = clamp(maxPages, LIMITS.pages)
+  const robots = await loadRobots(seed.origin, signal)
+  const crawlDelayMs = Math.min(robots.crawlDelayMs, MAX_CRAWL_DELAY_MS)
+  const workers = crawlDelayMs > 0 ? 1 : clamp(concurrency, LIMITS.concurrency)
+  const prefix = pathPrefix ? `/${String(pathPrefix).replace(/^\/+/, '')}` : ''
+
+  const seen = new Set([seed.href])
+  const queue = [{ url: seed, depth: 0 }]
+  const pages = []
+  const skipped = { robots: [], errors: [] }
+  let truncated = false
+
+  const enqueue = (links, depth) => {
+    for (const link of links) {
+      if (link.host !== seed.host || seen.has(link.href)) continue
+      if (SKIPPED_EXTENSIONS.test(link.pathname)) continue
+      if (prefix && !link.pathname.startsWith(prefix)) continue
+      seen.add(link.href)
+      queue.push({ url: link, depth })
+    }
+  }
+
+  const crawlOne = async ({ url: pageUrl, depth }) => {
+    if (!isPathAllowed(robots, `${pageUrl.pathname}${pageUrl.search}`)) {
+      skipped.robots.push(pageUrl.href)
+      return
+    }
+    try {
+      const response = await fetchWithTimeout(pageUrl.href, signal)
+      if (!response.ok) throw new Error(`HTTP ${response.status}`)
+      const html = await readHtml(response)
+      if (html === null) return
+      const page = parsePage(html, response.url || pageUrl.href)
+      const internal = page.links.filter(link => link.host === seed.host)
+      pages.push({
+        url: pageUrl.href,
+        depth,
+        title: page.title,
+        description: page.description,
+        headings: page.headings,
+        excerpt: page.excerpt,
+        internal_links: new Set(internal.map(link => link.href)).size,
+      })
+      if (depth < depthLimit) enqueue(internal, depth + 1)
+    } catch (error) {
+      if (signal?.aborted) throw error
+      skipped.errors.push({ url: pageUrl.href, error: error.message })
+    }
+  }
+
+  // Breadth-first: each batch drains the current queue front, never exceeding the page budget
+  while (queue.length && pages.length + skipped.errors.length < pageLimit) {
+    const budget = pageLimit - pages.length - skipped.errors.length
+    const batch = queue.splice(0, Math.min(workers, budget))
+    await Promise.all(batch.map(crawlOne))
+    if (crawlDelayMs && queue.length) await sleep(crawlDelayMs, signal)
+  }
+  if (queue.length) truncated = true
+
+  pages.sort((a, b) => a.depth - b.depth)
+  return {
+    seed: seed.href,
+    max_depth: depthLimit,
+    page_count: pages.length,
+    truncated,
+    sections: buildSections(pages),
+    pages,
+    skipped,
+    results: pages.map(page => ({
+      title: page.title || page.url,
+      url: page.url,
+      content: page.description || page.excerpt,
+    })),
+  }
+}
@@ -0,0 +1,253 @@
+/**
+ * Site ingestion service
+ * Pulls a documentation site into the RAG store through its sitemap in one call. Re-ingesting
+ * only refetches pages whose sitemap lastmod changed, only re-embeds pages whose text changed,
+ * and drops documents for pages that left the sitemap.
+ */
+
+import { listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import {
+  deleteDocument,
+  documentIdFor,
+  getDocument,
+  hashContent,
+  saveDocument,
+} from './ragStore.js'
+import { taskQueue } from './taskQueue.js'
+import {
+  extractPageText,
+  fetchWithTimeout,
+  isPathAllowed,
+  loadRobots,
+} from './siteExplorerService.js'
+
+const SITES = 'rag-sites'
+const DEFAULT_MAX_PAGES = 200
+const MAX_PAGES_LIMIT = 2000
+const MAX_SITEMAPS = 20
+const CONCURRENCY = 4
+const MIN_PAGE_CHARS = 80
+
+const siteIdFor = (origin, prefix) => documentIdFor(`site:${origin}${prefix}`)
+
+const decodeXml = text =>
+  text
+    .replace(/<!\[CDATA\[([\s\S]*?)\]\]>/g, '$1')
+    .replace(/&lt;/g, '<')
+    .replace(/&gt;/g, '>')
+    .replace(/&quot;/g, '"')
+    .replace(/&apos;/g, "'")
+    .replace(/&amp;/g, '&')
+    .trim()
+
+/**
+ * Entries of a sitemap or sitemap index
+ * @returns {{ sitemaps: string[], urls: Array<{ url: string, lastmod: string|null }> }}
+ */
+export const parseSitemap = xml => {
+  const text = String(xml || '')
+  // Tags may carry a namespace prefix (e.g. <sm:loc>)
+  const element = (tag, flags) =>
+    new RegExp(`<(?:\\w+:)?${tag}(?:\\s[^>]*)?>([\\s\\S]*?)</(?:\\w+:)?${tag}>`, flags)
+  const readTag = (block, tag) => {
+    const match = block.match(element(tag))
+    return match ? decodeXml(match[1]) : null
+  }
+  const blocks = tag => Array.from(text.matchAll(element(tag, 'g'))).map(match => match[0])
+  return {
+    sitemaps: blocks('sitemap')
+      .map(block => readTag(block, 'loc'))
+      .filter(Boolean),
+    urls: blocks('url')
+      .map(block => ({ url: readTag(block, 'loc'), lastmod: readTag(block, 'lastmod') }))
+      .filter(entry => entry.url),
+  }
+}
+
+// robots.txt Sitemap: lines, else the conventional /sitemap.xml
+const discoverSitemaps = async (origin, signal) => {
+  try {
+    const response = await fetchWithTimeout(`${origin}/robots.txt`, signal)
+    if (response.ok) {
+      const listed = Array.from((await response.text()).matchAll(/^\s*sitemap\s*:\s*(\S+)/gim))
+        .map(match => match[1])
+      if (listed.length) return listed
+    }
+  } catch (error) {
+    if (signal?.aborted) throw error
+  }
+  return [`${origin}/sitemap.xml`]
+}
+
+/**
+ * Page URLs from the sitemap(s), following sitemap indexes breadth-first
+ */
+const collectSitemapUrls = async (sitemapUrls, { host, pathPrefix, maxPages, signal }) => {
+  const queue = [...sitemapUrls]
+  const visited = new Set()
+  const pages = new Map()
+  while (queue.length && visited.size < MAX_SITEMAPS && pages.size < maxPages) {
+    const sitemapUrl = queue.shift()
+    if (visited.has(sitemapUrl)) continue
+    visited.add(sitemapUrl)
+    const response = await fetchWithTimeout(sitemapUrl, signal)
+    if (!response.ok) {
+      if (visited.size === 1 && !queue.length) {
+        throw new Error(`Sitemap not found: ${sitemapUrl} (HTTP ${response.status})`)
+      }
+      continue
+    }
+    const { sitemaps, urls } = parseSitemap(await response.text())
+    queue.push(...sitemaps)
+    for (const entry of urls) {
+      let url
+      try {
+        url = new URL(entry.url)
+      } catch {
+        continue
+      }
+      url.hash = ''
+      if (url.host !== host || (pathPrefix && !url.pathname.startsWith(pathPrefix))) continue
+      if (!pages.has(url.href)) pages.set(url.href, { url: url.href, lastmod: entry.lastmod })
+      if (pages.size >= maxPages) break
+    }
+  }
+  return Array.from(pages.values())
+}
+
+export const listSites = () =>
+  listRecords(SITES)
+    .map(({ pages, ...site }) => ({ ...site, page_count: Object.keys(pages || {}).length }))
+    .sort((a, b) => String(b.ingested_at).localeCompare(String(a.ingested_at)))
+
+export const getSite = id => readRecord(SITES, id)
+
+/**
+ * Ingest (or re-ingest) a site into the RAG store via its sitemap
+ * @param {Object} args
+ * @param {string} args.url Site URL; its origin plus pathPrefix identify the site
+ * @param {string} [args.sitemapUrl] Explicit sitemap (default: robots.txt Sitemap lines or
+ *   /sitemap.xml)
+ * @param {string} [args.pathPrefix] Only ingest pages under this path (e.g. "/docs")
+ * @param {number} [args.maxPages] Page cap (default 200, max 2000)
+ * @param {boolean} [args.force] Refetch and re-embed every page
+ * @param {Object} [args.embedding] Embedding overrides (see ragStore.saveDocument)
+ * @param {Function} [args.onProgress] Called with { current, total, message } per page batch
+ * @returns {Promise<Object>} { site_id, origin, pages, added, updated, unchanged, removed, failed }
+ */
+export const ingestSite = async ({
+  url,
+  sitemapUrl,
+  pathPrefix,
+  maxPages,
+  force = false,
+  embedding,
+  signal,
+  onProgress,
+}) => {
+  let seed
+  try {
+    seed = new URL(String(url || '').trim())
+  } catch {
+    throw new Error(`Invalid site URL: ${url}`)
+  }
+  const prefix = pathPrefix ? `/${String(pathPrefix).replace(/^\/+/, '')}` : ''
+  const siteId = siteIdFor(seed.origin, prefix)
+  const previous = getSite(siteId)
+  const limit = Math.min(Number.parseInt(maxPages, 10) || DEFAULT_MAX_PAGES, MAX_PAGES_LIMIT)
+
+  const robots = await loadRobots(seed.origin, signal)
+  const sitemaps = sitemapUrl ? [sitemapUrl] : await discoverSitemaps(seed.origin, signal)
+  const entries = (
+    await collectSitemapUrls(sitemaps, {
+      host: seed.host,
+      pathPrefix: prefix,
+      maxPages: limit,
+      signal,
+    })
+  ).filter(entry => {
+    const target = new URL(entry.url)
+    return isPathAllowed(robots, `${target.pathname}${target.search}`)
+  })
+
+  const summary = { added: 0, updated: 0, unchanged: 0, removed: 0, failed: [] }
+  const pages = {}
+
+  const ingestPage = async entry => {
+    const known = previous?.pages?.[entry.url]
+    const documentId = documentIdFor(`${siteId}:${entry.url}`)
+    // Same lastmod as last time: trust the stored copy without refetching
+    if (!force && known && entry.lastmod && known.lastmod === entry.lastmod) {
+      if (getDocument(documentId)) {
+        pages[entry.url] = known
+        summary.unchanged += 1
+        return
+      }
+    }
+    try {
+      const response = await fetchWithTimeout(entry.url, signal)
+      if (!response.ok) throw new Error(`HTTP ${response.status}`)
+      if (!/html/i.test(response.headers.get('content-type') || '')) {
+        throw new Error('Not an HTML page')
+      }
+      const { title, text } = extractPageText(await response.text())
+      if (text.length < MIN_PAGE_CHARS) throw new Error('No readable content')
+      const hash = hashContent(text)
+      if (!force && known?.hash === hash && getDocument(documentId)) {
+        summary.unchanged += 1
+      } else {
+        await saveDocument({
+          id: documentId,
+          title,
+          url: entry.url,
+          text,
+          source: { type: 'site', siteId },
+          embedding,
+          signal,
+        })
+        summary[known ? 'updated' : 'added'] += 1
+      }
+      pages[entry.url] = { document_id: documentId, hash, lastmod: entry.lastmod }
+    } catch (error) {
+      if (signal?.aborted) throw error
+      summary.failed.push({ url: entry.url, error: error.message })
+      // Keep the previous copy when a refresh fails
+      if (known) pages[entry.url] = known
+    }
+  }
+
+  for (let offset = 0; offset < entries.length; offset += CONCURRENCY) {
+    onProgress?.({ current: offset, total: entries.length, message: 'Fetching pages' })
+    await Promise.all(entries.slice(offset, offset + CONCURRENCY).map(ingestPage))
+  }
+  onProgress?.({ current: entries.length, total: entries.length, message: 'Saving site' })
+
+  for (const [pageUrl, page] of Object.entries(previous?.pages || {})) {
+    if (pages[pageUrl]) continue
+    deleteDocument(page.document_id)
+    summary.removed += 1
+  }
+
+  const ingestedAt = new Date().toISOString()
+  writeRecord(SITES, siteId, {
+    id: siteId,
+    origin: seed.origin,
+    path_prefix: prefix || null,
+    sitemaps,
+    created_at: previous?.created_at || ingestedAt,
+    ingested_at: ingestedAt,
+    pages,
+  })
+  return {
+    site_id: siteId,
+    origin: seed.origin,
+    pages: Object.keys(pages).length,
+    ...summary,
+    ingested_at: ingestedAt,
+  }
+}
+
+// Background ingestion (POST /api/rag/ingest-site with "background": true)
+taskQueue.registerHandler('rag.ingest-site', (args, { signal, progress }) =>
+  ingestSite({ ...args, signal, onProgress: progress }),
+)
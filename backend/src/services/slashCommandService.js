@@ -0,0 +1,147 @@
+/**
+ * Slash command service
+ * Parses "/command ..." messages server-side and routes them to the matching subsystem,
+ * so every frontend gets the same command behavior.
+ */
+
+import { streamDeepResearch } from './deepResearchAgentService.js'
+import { normalizeTextContent } from './serviceUtils.js'
+import { streamChat } from './streamChatService.js'
+
+const COMMAND_PATTERN = /^\/([a-zA-Z][\w-]*)(?:[ \t]+|\n|$)([\s\S]*)$/
+
+/**
+ * Accepts "to French: text", "to French text", and "to \"Brazilian Portuguese\" text"
+ */
+const parseTranslateArgs = rest => {
+  const match = rest.match(/^to\s+([\s\S]*)$/i)
+  if (!match) return { args: {}, payload: rest }
+  const body = match[1]
+  const quoted = body.match(/^"([^"]+)"\s*([\s\S]*)$/)
+  if (quoted) return { args: { language: quoted[1].trim() }, payload: quoted[2].trim() }
+  const delimited = body.match(/^([^:\n]{1,40})[:\n]\s*([\s\S]*)$/)
+  if (delimited) return { args: { language: delimited[1].trim() }, payload: delimited[2].trim() }
+  const [language, ...words] = body.split(/\s+/)
+  return { args: { language }, payload: words.join(' ').trim() }
+}
+
+const messageText = message => normalizeTextContent(message?.content)
+
+const formatHistoryForSummary = history =>
+  history
+    .filter(message => message?.role === 'user' || message?.role === 'assistant')
+    .map(message => `${message.role === 'user' ? 'User' : 'Assistant'}: ${messageText(message)}`)
+    .join('\n\n')
+
+const getLastAssistantText = history =>
+  messageText(
+    history
+      .slice()
+      .reverse()
+      .find(message => message?.role === 'assistant'),
+  )
+
+export const SLASH_COMMANDS = {
+  research: {
+    usage: '/research <question>',
+    description: 'Run deep research on the question',
+    validate: ({ payload }) => Boolean(payload),
+    run: (params, { payload, history }) =>
+      streamDeepResearch({
+        ...params,
+        messages: history,
+        question: payload,
+        researchType: params.researchType || 'general',
+      }),
+  },
+  summarize: {
+    usage: '/summarize [text]',
+    description: 'Summarize the given text, or the conversation so far when no text is given',
+    validate: ({ payload }, history) => Boolean(payload || history.length),
+    run: (params, { payload, history }) =>
+      streamChat({
+        ...params,
+        toolIds: [],
+        tools: [],
+        messages: [
+          {
+            role: 'system',
+            content:
+              'Summarize the content below. Start with a one-sentence gist, then list the key points as bullets. Keep the original language and do not add facts.',
+          },
+          { role: 'user', content: payload || formatHistoryForSummary(history) },
+        ],
+      }),
+  },
+  translate: {
+    usage: '/translate to <language> [text]',
+    description: 'Translate text (or the last answer when no text is given) into a language',
+    parseArgs: parseTranslateArgs,
+    validate: ({ args, payload }, history) =>
+      Boolean(args.language && (payload || getLastAssistantText(history))),
+    run: (params, { args, payload, history }) =>
+      streamChat({
+        ...params,
+        toolIds: [],
+        tools: [],
+        applyPreferences: false,
+        messages: [
+          {
+            role: 'system',
+            content: `Translate the user's text into ${args.language}. Preserve meaning, tone, formatting, and markdown. Output only the translation.`,
+          },
+          { role: 'user', content: payload || getLastAssistantText(history) },
+        ],
+      }),
+  },
+}
+
+/**
+ * Parse a slash command from message text
+ * @returns {{ name: string, args: Object, payload: string } | null} null for plain text and
+ *   unknown commands
+ */
+export const parseSlashCommand = text => {
+  const match = String(text || '')
+    .trim()
+    .match(COMMAND_PATTERN)
+  if (!match) return null
+  const name = match[1].toLowerCase()
+  const command = SLASH_COMMANDS[name]
+  if (!command) return null
+  const rest = match[2].trim()
+  const { args, payload } = command.parseArgs
+    ? command.parseArgs(rest)
+    : { args: {}, payload: rest }
+  return { name, args, payload }
+}
+
+/**
+ * Detect a command in the latest user message
+ */
+export const detectSlashCommand = messages => {
+  const last = Array.isArray(messages) ? messages[messages.length - 1] : null
+  if (last?.role !== 'user' || typeof last.content !== 'string') return null
+  return parseSlashCommand(last.content)
+}
+
+export const listSlashCommands = () =>
+  Object.entries(SLASH_COMMANDS).map(([name, { usage, description }]) => ({
+    name,
+    usage,
+    description,
+  }))
+
+/**
+ * Stream a parsed command; emits a "command" event, then the subsystem's own events
+ */
+export const streamSlashCommand = async function* (command, params) {
+  const definition = SLASH_COMMANDS[command.name]
+  const history = (params.messages || []).slice(0, -1)
+  yield { type: 'command', command: command.name, args: command.args }
+  if (definition.validate && !definition.validate(command, history)) {
+    yield { type: 'error', error: `Usage: ${definition.usage}` }
+    return
+  }
+  yield* definition.run(params, { ...command, history })
+}
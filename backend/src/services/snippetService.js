@@ -0,0 +1,162 @@
+/**
+ * Snippet service
+ * A clipboard of reusable answer snippets (with source message link and tags) that can be
+ * searched and inserted into later prompts as context blocks.
+ */
+
+import { randomUUID } from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+
+const COLLECTION = 'snippets'
+const MAX_SNIPPET_CHARS = 20000
+const MAX_TAGS = 20
+const MAX_CONTEXT_SNIPPETS = 10
+const DEFAULT_SEARCH_LIMIT = 20
+
+export class SnippetError extends Error {
+  constructor(message, details = []) {
+    super(message)
+    this.name = 'SnippetError'
+    this.details = details
+  }
+}
+
+export const normalizeTags = tags =>
+  Array.from(
+    new Set(
+      (Array.isArray(tags) ? tags : typeof tags === 'string' ? tags.split(',') : [])
+        .map(tag => String(tag).trim().toLowerCase())
+        .filter(Boolean),
+    ),
+  ).slice(0, MAX_TAGS)
+
+const normalizeSource = source => {
+  if (!source || typeof source !== 'object') return null
+  const normalized = {
+    conversationId: source.conversationId || source.conversation_id || null,
+    messageId: source.messageId || source.message_id || null,
+    url: source.url || null,
+  }
+  return Object.values(normalized).some(Boolean) ? normalized : null
+}
+
+const validateSnippet = (snippet, { partial = false } = {}) => {
+  const errors = []
+  if (!snippet || typeof snippet !== 'object') return ['Snippet must be an object']
+  if (!partial || snippet.content !== undefined) {
+    if (typeof snippet.content !== 'string' || !snippet.content.trim()) {
+      errors.push('content is required')
+    } else if (snippet.content.length > MAX_SNIPPET_CHARS) {
+      errors.push(`content must be at most ${MAX_SNIPPET_CHARS} characters`)
+    }
+  }
+  if (snippet.title !== undefined && typeof snippet.title !== 'string') {
+    errors.push('title must be a string')
+  }
+  return errors
+}
+
+export const createSnippet = snippet => {
+  const errors = validateSnippet(snippet)
+  if (errors.length) throw new SnippetError('Invalid snippet', errors)
+  const id = randomUUID()
+  const now = new Date().toISOString()
+  return writeRecord(COLLECTION, id, {
+    id,
+    title: String(snippet.title || '').trim() || snippet.content.trim().split('\n')[0].slice(0, 80),
+    content: snippet.content,
+    tags: normalizeTags(snippet.tags),
+    source: normalizeSource(snippet.source),
+    createdAt: now,
+    updatedAt: now,
+  })
+}
+
+export const updateSnippet = (id, patch) => {
+  const existing = readRecord(COLLECTION, id)
+  if (!existing) return null
+  const errors = validateSnippet(patch, { partial: true })
+  if (errors.length) throw new SnippetError('Invalid snippet', errors)
+  return writeRecord(COLLECTION, id, {
+    ...existing,
+    title: patch.title !== undefined ? patch.title.trim() : existing.title,
+    content: patch.content !== undefined ? patch.content : existing.content,
+    tags: patch.tags !== undefined ? normalizeTags(patch.tags) : existing.tags,
+    source: patch.source !== undefined ? normalizeSource(patch.source) : existing.source,
+    updatedAt: new Date().toISOString(),
+  })
+}
+
+export const getSnippet = id => readRecord(COLLECTION, id)
+
+export const deleteSnippet = id => deleteRecord(COLLECTION, id)
+
+const tokenize = text =>
+  String(text || '')
+    .toLowerCase()
+    .match(/[\p{L}\p{N}_]+/gu) || []
+
+const scoreSnippet = (snippet, terms) => {
+  const title = String(snippet.title || '').toLowerCase()
+  const content = String(snippet.content || '').toLowerCase()
+  let score = 0
+  for (const term of terms) {
+    if (snippet.tags?.includes(term)) score += 3
+    if (title.includes(term)) score += 2
+    if (content.includes(term)) score += 1
+  }
+  return score
+}
+
+/**
+ * Search snippets by free text and/or tags (all given tags must match)
+ * @returns {Array} Newest first when no query is given, otherwise by relevance
+ */
+export const searchSnippets = ({ query, tags, limit = DEFAULT_SEARCH_LIMIT } = {}) => {
+  const requiredTags = normalizeTags(tags)
+  const terms = tokenize(query)
+  const candidates = listRecords(COLLECTION).filter(snippet =>
+    requiredTags.every(tag => snippet.tags?.includes(tag)),
+  )
+  const ranked = terms.length
+    ? candidates
+        .map(snippet => ({ snippet, score: scoreSnippet(snippet, terms) }))
+        .filter(item => item.score > 0)
+        .sort((a, b) => b.score - a.score)
+        .map(item => item.snippet)
+    : candidates.sort((a, b) => String(b.updatedAt).localeCompare(String(a.updatedAt)))
+  return ranked.slice(0, Math.max(1, Number(limit) || DEFAULT_SEARCH_LIMIT))
+}
+
+/**
+ * Format snippets as a context block for a system prompt
+ */
+export const formatSnippetsAsContext = snippets => {
+  if (!snippets?.length) return ''
+  const blocks = snippets.map(
+    (snippet, index) =>
+      `<snippet index="${index + 1}" title="${String(snippet.title || '').replace(/"/g, "'")}"${
+        snippet.tags?.length ? ` tags="${snippet.tags.join(', ')}"` : ''
+      }>\n${snippet.content}\n</snippet>`,
+  )
+  const intro = 'Saved snippets the user attached as context (use them where relevant):'
+  return `${intro}\n${blocks.join('\n')}`
+}
+
+/**
+ * Insert the given snippets as a system context block ahead of the conversation
+ */
+export const applySnippetsToMessages = (messages, snippetIds) => {
+  if (!Array.isArray(snippetIds) || snippetIds.length === 0) return messages
+  const snippets = snippetIds
+    .slice(0, MAX_CONTEXT_SNIPPETS)
+    .map(id => getSnippet(String(id)))
+    .filter(Boolean)
+  const context = formatSnippetsAsContext(snippets)
+  if (!context) return messages
+  const [first, ...rest] = messages
+  if (first?.role === 'system' && typeof first.content === 'string') {
+    return [{ ...first, content: `${first.content}\n\n${context}` }, ...rest]
+  }
+  return [{ role: 'system', content: context }, ...messages]
+}
@@ -0,0 +1,238 @@
+/**
+ * Source ownership and bias dataset
+ * Bundled, approximate metadata for major news outlets used by the source diversity pass.
+ * bias: left | lean_left | center | lean_right | right (editorial slant, US-centric scale)
+ * type: wire | public | state | commercial | nonprofit
+ * Ratings are coarse and contestable; they describe outlets, not individual articles.
+ */
+
+export const SOURCE_BIAS = {
+  // Wire services
+  'reuters.com': { name: 'Reuters', owner: 'Thomson Reuters', bias: 'center', type: 'wire' },
+  'apnews.com': {
+    name: 'Associated Press',
+    owner: 'AP (cooperative)',
+    bias: 'center',
+    type: 'wire',
+  },
+  'afp.com': { name: 'AFP', owner: 'Agence France-Presse', bias: 'center', type: 'wire' },
+  'bloomberg.com': {
+    name: 'Bloomberg',
+    owner: 'Bloomberg L.P.',
+    bias: 'center',
+    type: 'commercial',
+  },
+
+  // Public broadcasters
+  'bbc.com': { name: 'BBC', owner: 'BBC (UK public)', bias: 'center', type: 'public' },
+  'bbc.co.uk': { name: 'BBC', owner: 'BBC (UK public)', bias: 'center', type: 'public' },
+  'npr.org': { name: 'NPR', owner: 'NPR (US public)', bias: 'lean_left', type: 'public' },
+  'pbs.org': { name: 'PBS', owner: 'PBS (US public)', bias: 'center', type: 'public' },
+  'dw.com': {
+    name: 'Deutsche Welle',
+    owner: 'German federal government',
+    bias: 'center',
+    type: 'public',
+  },
+  'abc.net.au': { name: 'ABC Australia', owner: 'ABC (AU public)', bias: 'center', type: 'public' },
+  'cbc.ca': { name: 'CBC', owner: 'CBC (CA public)', bias: 'lean_left', type: 'public' },
+  'france24.com': {
+    name: 'France 24',
+    owner: 'France Médias Monde',
+    bias: 'center',
+    type: 'public',
+  },
+
+  // State-controlled media
+  'rt.com': { name: 'RT', owner: 'Russian state', bias: 'right', type: 'state' },
+  'tass.com': { name: 'TASS', owner: 'Russian state', bias: 'right', type: 'state' },
+  'xinhuanet.com': { name: 'Xinhua', owner: 'Chinese state', bias: 'left', type: 'state' },
+  'news.cn': { name: 'Xinhua', owner: 'Chinese state', bias: 'left', type: 'state' },
+  'globaltimes.cn': {
+    name: 'Global Times',
+    owner: "People's Daily (Chinese state)",
+    bias: 'left',
+    type: 'state',
+  },
+  'cgtn.com': { name: 'CGTN', owner: 'Chinese state', bias: 'left', type: 'state' },
+  'aljazeera.com': { name: 'Al Jazeera', owner: 'Qatari state', bias: 'lean_left', type: 'state' },
+  'presstv.ir': { name: 'Press TV', owner: 'Iranian state', bias: 'right', type: 'state' },
+
+  // US national outlets
+  'nytimes.com': {
+    name: 'The New York Times',
+    owner: 'The New York Times Company',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'washingtonpost.com': {
+    name: 'The Washington Post',
+    owner: 'Nash Holdings (Jeff Bezos)',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'wsj.com': {
+    name: 'The Wall Street Journal',
+    owner: 'News Corp',
+    bias: 'center',
+    type: 'commercial',
+  },
+  'foxnews.com': { name: 'Fox News', owner: 'Fox Corporation', bias: 'right', type: 'commercial' },
+  'nypost.com': {
+    name: 'New York Post',
+    owner: 'News Corp',
+    bias: 'lean_right',
+    type: 'commercial',
+  },
+  'cnn.com': {
+    name: 'CNN',
+    owner: 'Warner Bros. Discovery',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'msnbc.com': { name: 'MSNBC', owner: 'Versant', bias: 'left', type: 'commercial' },
+  'nbcnews.com': { name: 'NBC News', owner: 'Comcast', bias: 'lean_left', type: 'commercial' },
+  'cbsnews.com': {
+    name: 'CBS News',
+    owner: 'Paramount Skydance',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'abcnews.go.com': { name: 'ABC News', owner: 'Disney', bias: 'lean_left', type: 'commercial' },
+  'usatoday.com': { name: 'USA Today', owner: 'Gannett', bias: 'lean_left', type: 'commercial' },
+  'politico.com': {
+    name: 'Politico',
+    owner: 'Axel Springer',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'axios.com': { name: 'Axios', owner: 'Cox Enterprises', bias: 'lean_left', type: 'commercial' },
+  'thehill.com': {
+    name: 'The Hill',
+    owner: 'Nexstar Media Group',
+    bias: 'center',
+    type: 'commercial',
+  },
+  'newsmax.com': { name: 'Newsmax', owner: 'Newsmax Media', bias: 'right', type: 'commercial' },
+  'breitbart.com': {
+    name: 'Breitbart',
+    owner: 'Breitbart News Network',
+    bias: 'right',
+    type: 'commercial',
+  },
+  'dailywire.com': {
+    name: 'The Daily Wire',
+    owner: 'Daily Wire',
+    bias: 'right',
+    type: 'commercial',
+  },
+  'washingtonexaminer.com': {
+    name: 'Washington Examiner',
+    owner: 'Clarity Media Group',
+    bias: 'lean_right',
+    type: 'commercial',
+  },
+  'nationalreview.com': {
+    name: 'National Review',
+    owner: 'National Review Institute',
+    bias: 'right',
+    type: 'nonprofit',
+  },
+  'theatlantic.com': {
+    name: 'The Atlantic',
+    owner: 'Emerson Collective',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'vox.com': { name: 'Vox', owner: 'Vox Media', bias: 'left', type: 'commercial' },
+  'huffpost.com': { name: 'HuffPost', owner: 'BuzzFeed', bias: 'left', type: 'commercial' },
+  'motherjones.com': {
+    name: 'Mother Jones',
+    owner: 'Foundation for National Progress',
+    bias: 'left',
+    type: 'nonprofit',
+  },
+  'propublica.org': {
+    name: 'ProPublica',
+    owner: 'ProPublica (nonprofit)',
+    bias: 'lean_left',
+    type: 'nonprofit',
+  },
+  'reason.com': {
+    name: 'Reason',
+    owner: 'Reason Foundation',
+    bias: 'lean_right',
+    type: 'nonprofit',
+  },
+  'forbes.com': {
+    name: 'Forbes',
+    owner: 'Integrated Whale Media',
+    bias: 'center',
+    type: 'commercial',
+  },
+  'cnbc.com': { name: 'CNBC', owner: 'Versant', bias: 'center', type: 'commercial' },
+
+  // UK and international
+  'theguardian.com': {
+    name: 'The Guardian',
+    owner: 'Scott Trust',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'telegraph.co.uk': {
+    name: 'The Telegraph',
+    owner: 'Telegraph Media Group',
+    bias: 'lean_right',
+    type: 'commercial',
+  },
+  'thetimes.co.uk': {
+    name: 'The Times',
+    owner: 'News Corp',
+    bias: 'lean_right',
+    type: 'commercial',
+  },
+  'dailymail.co.uk': { name: 'Daily Mail', owner: 'DMG Media', bias: 'right', type: 'commercial' },
+  'independent.co.uk': {
+    name: 'The Independent',
+    owner: 'Independent Digital News & Media',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'ft.com': { name: 'Financial Times', owner: 'Nikkei', bias: 'center', type: 'commercial' },
+  'economist.com': {
+    name: 'The Economist',
+    owner: 'The Economist Group',
+    bias: 'center',
+    type: 'commercial',
+  },
+  'spiegel.de': {
+    name: 'Der Spiegel',
+    owner: 'Spiegel-Verlag',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'lemonde.fr': {
+    name: 'Le Monde',
+    owner: 'Groupe Le Monde',
+    bias: 'lean_left',
+    type: 'commercial',
+  },
+  'scmp.com': {
+    name: 'South China Morning Post',
+    owner: 'Alibaba Group',
+    bias: 'center',
+    type: 'commercial',
+  },
+  'timesofindia.indiatimes.com': {
+    name: 'The Times of India',
+    owner: 'Bennett, Coleman & Co.',
+    bias: 'center',
+    type: 'commercial',
+  },
+  'japantimes.co.jp': {
+    name: 'The Japan Times',
+    owner: 'News2u Holdings',
+    bias: 'center',
+    type: 'commercial',
+  },
+}
@@ -0,0 +1,102 @@
+/**
+ * Source bias service
+ * Labels sources with ownership/bias metadata from the bundled SOURCE_BIAS dataset and
+ * summarizes the mix into a source diversity note for the report.
+ */
+
+import { SOURCE_BIAS } from './sourceBiasData.js'
+
+export const BIAS_LEVELS = ['left', 'lean_left', 'center', 'lean_right', 'right']
+
+// A single owner or side holding more than this share of labeled sources is flagged
+const CONCENTRATION_THRESHOLD = 0.5
+
+const getHostname = url => {
+  try {
+    return new URL(url).hostname.toLowerCase().replace(/^www\./, '')
+  } catch {
+    return ''
+  }
+}
+
+/**
+ * Dataset entry for a URL, matching subdomains (e.g. edition.cnn.com -> cnn.com)
+ */
+export const lookupSourceBias = url => {
+  const labels = getHostname(url).split('.')
+  for (let i = 0; i < labels.length - 1; i += 1) {
+    const domain = labels.slice(i).join('.')
+    if (SOURCE_BIAS[domain]) return { domain, ...SOURCE_BIAS[domain] }
+  }
+  return null
+}
+
+/**
+ * Attach `outlet` metadata to each source; unknown outlets get outlet: null
+ */
+export const annotateSources = (sources = []) =>
+  sources.map(source => ({ ...source, outlet: lookupSourceBias(source.url) }))
+
+/**
+ * Count sources by bias, type, and owner, and flag one-sided or concentrated mixes
+ */
+export const summarizeSourceDiversity = (annotated = []) => {
+  const labeled = annotated.filter(source => source.outlet)
+  const byBias = Object.fromEntries(BIAS_LEVELS.map(level => [level, 0]))
+  const byType = {}
+  const byOwner = {}
+  for (const { outlet } of labeled) {
+    byBias[outlet.bias] += 1
+    byType[outlet.type] = (byType[outlet.type] || 0) + 1
+    byOwner[outlet.owner] = (byOwner[outlet.owner] || 0) + 1
+  }
+
+  const warnings = []
+  if (labeled.length >= 3) {
+    const left = byBias.left + byBias.lean_left
+    const right = byBias.right + byBias.lean_right
+    if (left / labeled.length > CONCENTRATION_THRESHOLD && right === 0) {
+      warnings.push('Labeled news sources lean left with no right-leaning counterweight.')
+    }
+    if (right / labeled.length > CONCENTRATION_THRESHOLD && left === 0) {
+      warnings.push('Labeled news sources lean right with no left-leaning counterweight.')
+    }
+    const [topOwner, topCount] = Object.entries(byOwner).sort((a, b) => b[1] - a[1])[0]
+    if (topCount / labeled.length > CONCENTRATION_THRESHOLD) {
+      warnings.push(
+        `${topCount} of ${labeled.length} labeled sources share one owner (${topOwner}).`,
+      )
+    }
+  }
+  if (byType.state) {
+    warnings.push(`${byType.state} source(s) are state-controlled media.`)
+  }
+
+  return {
+    total: annotated.length,
+    labeled: labeled.length,
+    by_bias: byBias,
+    by_type: byType,
+    by_owner: byOwner,
+    warnings,
+  }
+}
+
+/**
+ * Report prompt section: per-source outlet labels plus the aggregate mix
+ */
+export const formatDiversityForPrompt = (annotated, diversity) => {
+  const rows = annotated
+    .map((source, index) =>
+      source.outlet
+        ? `[${index + 1}] ${source.outlet.name} | owner: ${source.outlet.owner} | bias: ${source.outlet.bias} | type: ${source.outlet.type}`
+        : null,
+    )
+    .filter(Boolean)
+  const mix = BIAS_LEVELS.map(level => `${level}: ${diversity.by_bias[level]}`).join(', ')
+  return [
+    ...rows,
+    `Mix of ${diversity.labeled} labeled / ${diversity.total} total sources: ${mix}`,
+    ...diversity.warnings.map(warning => `Warning: ${warning}`),
+  ].join('\n')
+}
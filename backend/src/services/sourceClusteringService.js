@@ -0,0 +1,197 @@
+/**
+ * Source clustering service
+ * Groups collected sources into sub-topics (embedding k-means) and names each group, so the
+ * report can organize its findings by topic and the UI can render a source map.
+ * Requires embeddings (EMBEDDING_API_KEY / EMBEDDING_MODEL); without them clustering is skipped.
+ */
+
+import { cosineSimilarity, embedTexts, resolveEmbeddingConfig } from './embeddingClient.js'
+import { completeJson } from './modelCompletion.js'
+
+const DEFAULT_MIN_SOURCES = 8
+const MAX_CLUSTERS = 8
+const MAX_ITERATIONS = 25
+const STOP_WORDS = new Set(
+  'and are for from has how its not that the this vs what when why with'.split(' '),
+)
+
+export const resolveClusterMinSources = () => {
+  const value = Number.parseInt(process.env.SOURCE_CLUSTER_MIN_SOURCES, 10)
+  return Number.isFinite(value) && value > 1 ? value : DEFAULT_MIN_SOURCES
+}
+
+const sourceText = source =>
+  [source.title, source.snippet].filter(Boolean).join('\n') || source.url || ''
+
+const meanVector = vectors => {
+  const mean = new Array(vectors[0].length).fill(0)
+  for (const vector of vectors) {
+    for (let i = 0; i < vector.length; i += 1) mean[i] += vector[i] / vectors.length
+  }
+  return mean
+}
+
+/**
+ * k-means on cosine similarity with deterministic farthest-point initialization
+ * @returns {number[]} Cluster index per vector
+ */
+export const kMeans = (vectors, k) => {
+  const centroids = [vectors[0]]
+  while (centroids.length < k) {
+    let farthest = 0
+    let farthestScore = Infinity
+    vectors.forEach((vector, index) => {
+      const closest = Math.max(...centroids.map(centroid => cosineSimilarity(vector, centroid)))
+      if (closest < farthestScore) {
+        farthestScore = closest
+        farthest = index
+      }
+    })
+    centroids.push(vectors[farthest])
+  }
+
+  let assignments = []
+  for (let iteration = 0; iteration < MAX_ITERATIONS; iteration += 1) {
+    const next = vectors.map(vector => {
+      let best = 0
+      centroids.forEach((centroid, index) => {
+        if (cosineSimilarity(vector, centroid) > cosineSimilarity(vector, centroids[best])) {
+          best = index
+        }
+      })
+      return best
+    })
+    const changed = next.some((cluster, index) => cluster !== assignments[index])
+    assignments = next
+    if (!changed) break
+    centroids.forEach((_, index) => {
+      const members = vectors.filter((_, i) => assignments[i] === index)
+      if (members.length) centroids[index] = meanVector(members)
+    })
+  }
+  return assignments
+}
+
+// Roughly one cluster per four sources, between 2 and MAX_CLUSTERS
+const chooseClusterCount = count => Math.min(MAX_CLUSTERS, Math.max(2, Math.round(count / 4)))
+
+// Fallback label: the most frequent non-trivial title words in the cluster
+const keywordLabel = titles => {
+  const counts = new Map()
+  for (const title of titles) {
+    const words = new Set(String(title).toLowerCase().match(/[\p{L}\p{N}][\p{L}\p{N}-]{2,}/gu))
+    for (const word of words) {
+      if (!STOP_WORDS.has(word)) counts.set(word, (counts.get(word) || 0) + 1)
+    }
+  }
+  const top = Array.from(counts.entries())
+    .sort((a, b) => b[1] - a[1])
+    .slice(0, 3)
+    .map(([word]) => word)
+  return top.length ? top.join(', ') : 'Other'
+}
+
+const buildLabelMessages = ({ question, clusters, sources }) => [
+  {
+    role: 'system',
+    content: `You name groups of research sources by their shared sub-topic.
+Give each group a short label (2-6 words) that distinguishes it from the other groups, and a one-sentence summary.
+Return ONLY JSON: {"clusters": [{"id": 1, "label": "...", "summary": "..."}]}`,
+  },
+  {
+    role: 'user',
+    content: `Question: ${question || '(none)'}
+
+${clusters
+  .map(
+    cluster =>
+      `Group ${cluster.id}:\n${cluster.sources
+        .map(index => `- ${sources[index - 1].title || sources[index - 1].url}`)
+        .join('\n')}`,
+  )
+  .join('\n\n')}`,
+  },
+]
+
+/**
+ * Cluster sources by sub-topic
+ * @param {Object} args
+ * @param {Array<{title, url, snippet}>} args.sources In citation order (index 1 = sources[0])
+ * @returns {Promise<{clusters: Array<{id, label, summary, sources}>, embedding_model}|null>}
+ *   null when there are too few sources, embeddings are not configured, or embedding fails
+ */
+export const clusterSources = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  question,
+  sources = [],
+  minSources = resolveClusterMinSources(),
+  signal,
+}) => {
+  if (sources.length < minSources) return null
+  const embeddingConfig = resolveEmbeddingConfig()
+  if (!embeddingConfig) {
+    console.warn('[SourceClusters] Embeddings not configured; skipping source clustering')
+    return null
+  }
+
+  let vectors
+  try {
+    vectors = await embedTexts(sources.map(sourceText), embeddingConfig, { signal })
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[SourceClusters] Embedding failed:', error.message)
+    return null
+  }
+
+  const assignments = kMeans(vectors, chooseClusterCount(sources.length))
+  const clusters = Array.from(new Set(assignments))
+    .map(cluster =>
+      assignments.flatMap((assigned, index) => (assigned === cluster ? [index + 1] : [])),
+    )
+    .sort((a, b) => b.length - a.length || a[0] - b[0])
+    .map((members, index) => ({
+      id: index + 1,
+      label: keywordLabel(members.map(member => sources[member - 1].title)),
+      summary: '',
+      sources: members,
+    }))
+
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: buildLabelMessages({ question, clusters, sources }),
+      temperature: 0,
+      signal,
+    })
+    const named = Array.isArray(parsed?.clusters) ? parsed.clusters : []
+    const labels = new Map(named.map(item => [Number(item?.id), item]))
+    clusters.forEach(cluster => {
+      const item = labels.get(cluster.id)
+      if (typeof item?.label === 'string' && item.label.trim()) cluster.label = item.label.trim()
+      if (typeof item?.summary === 'string') cluster.summary = item.summary.trim()
+    })
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[SourceClusters] Cluster labeling failed, using keyword labels:', error.message)
+  }
+
+  return { clusters, embedding_model: embeddingConfig.model }
+}
+
+/**
+ * Report prompt section: one line per cluster with its source indexes
+ */
+export const formatClustersForPrompt = result =>
+  result.clusters
+    .map(cluster => {
+      const summary = cluster.summary ? ` (${cluster.summary})` : ''
+      const citations = cluster.sources.map(index => `[${index}]`).join('')
+      return `${cluster.id}. ${cluster.label}${summary}: ${citations}`
+    })
+    .join('\n')
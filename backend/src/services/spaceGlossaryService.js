@@ -0,0 +1,209 @@
+/**
+ * Space glossary service
+ * Per-space terminology: preferred terms (with translation/definition and variants to avoid) and
+ * banned terms. The glossary is injected into chat and report prompts for requests that name the
+ * space, and final answers are checked for avoided/banned wording so teams with strict terminology
+ * can see every deviation.
+ */
+
+import { deleteRecord, readRecord, writeRecord } from '../utils/dataStore.js'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+
+const COLLECTION = 'space-glossaries'
+const MAX_ENTRIES = 500
+const MAX_TERM_CHARS = 100
+const MAX_DEFINITION_CHARS = 500
+const MAX_PROMPT_ENTRIES = 200
+const MAX_VIOLATION_SAMPLES = 3
+
+const asText = value => (typeof value === 'string' ? value.trim() : '')
+
+const asList = value =>
+  Array.from(
+    new Set(
+      (Array.isArray(value) ? value : typeof value === 'string' ? value.split(',') : [])
+        .map(asText)
+        .filter(Boolean),
+    ),
+  )
+
+const normalizeTermEntry = entry => ({
+  term: asText(entry?.term),
+  preferred: asText(entry?.preferred ?? entry?.translation) || null,
+  definition: asText(entry?.definition) || null,
+  avoid: asList(entry?.avoid),
+})
+
+const normalizeBannedEntry = entry =>
+  typeof entry === 'string'
+    ? { term: entry.trim(), reason: null, replacement: null }
+    : {
+        term: asText(entry?.term),
+        reason: asText(entry?.reason) || null,
+        replacement: asText(entry?.replacement) || null,
+      }
+
+/**
+ * Validate and normalize a glossary body
+ * @returns {{ glossary: { terms, banned }, errors: string[] }}
+ */
+export const normalizeGlossary = ({ terms, banned } = {}) => {
+  const errors = []
+  if (terms !== undefined && !Array.isArray(terms)) errors.push('terms must be an array')
+  if (banned !== undefined && !Array.isArray(banned)) errors.push('banned must be an array')
+  const glossary = {
+    terms: (Array.isArray(terms) ? terms : []).map(normalizeTermEntry),
+    banned: (Array.isArray(banned) ? banned : []).map(normalizeBannedEntry),
+  }
+  if (glossary.terms.length + glossary.banned.length > MAX_ENTRIES) {
+    errors.push(`A glossary holds at most ${MAX_ENTRIES} entries`)
+  }
+  glossary.terms.forEach((entry, index) => {
+    if (!entry.term) errors.push(`terms[${index}].term is required`)
+    if ([entry.term, entry.preferred, ...entry.avoid].some(t => t?.length > MAX_TERM_CHARS)) {
+      errors.push(`terms[${index}]: terms must be at most ${MAX_TERM_CHARS} characters`)
+    }
+    if (entry.definition?.length > MAX_DEFINITION_CHARS) {
+      errors.push(`terms[${index}].definition must be at most ${MAX_DEFINITION_CHARS} characters`)
+    }
+  })
+  glossary.banned.forEach((entry, index) => {
+    if (!entry.term) errors.push(`banned[${index}].term is required`)
+    else if (entry.term.length > MAX_TERM_CHARS) {
+      errors.push(`banned[${index}].term must be at most ${MAX_TERM_CHARS} characters`)
+    }
+  })
+  return { glossary, errors }
+}
+
+export const getSpaceGlossary = spaceId =>
+  spaceId ? readRecord(COLLECTION, String(spaceId)) : null
+
+/**
+ * Replace the glossary of a space
+ * @throws {QurioError} invalid_request with details when the body is invalid
+ */
+export const setSpaceGlossary = (spaceId, body) => {
+  const { glossary, errors } = normalizeGlossary(body)
+  if (errors.length) {
+    throw new QurioError(ErrorCode.InvalidRequest, 'Invalid glossary', { details: errors })
+  }
+  return writeRecord(COLLECTION, String(spaceId), {
+    space_id: String(spaceId),
+    ...glossary,
+    updated_at: new Date().toISOString(),
+  })
+}
+
+export const deleteSpaceGlossary = spaceId => deleteRecord(COLLECTION, String(spaceId))
+
+const hasEntries = glossary => Boolean(glossary?.terms?.length || glossary?.banned?.length)
+
+/**
+ * System prompt block listing the glossary rules
+ */
+export const buildGlossaryPrompt = glossary => {
+  if (!hasEntries(glossary)) return ''
+  const lines = glossary.terms.slice(0, MAX_PROMPT_ENTRIES).map(entry => {
+    let line = `- "${entry.term}"`
+    if (entry.preferred) line += `: write "${entry.preferred}"`
+    if (entry.definition) line += ` (${entry.definition})`
+    if (entry.avoid.length) line += `; never write ${entry.avoid.map(t => `"${t}"`).join(', ')}`
+    return line
+  })
+  const banned = glossary.banned.slice(0, MAX_PROMPT_ENTRIES).map(entry => {
+    let line = `- "${entry.term}"`
+    if (entry.replacement) line += `: use "${entry.replacement}" instead`
+    if (entry.reason) line += ` (${entry.reason})`
+    return line
+  })
+  return [
+    'Terminology rules for this workspace. Follow them exactly, also when translating:',
+    ...(lines.length ? ['Required terms:', ...lines] : []),
+    ...(banned.length ? ['Banned terms (never use them):', ...banned] : []),
+  ].join('\n')
+}
+
+/**
+ * Append the glossary rules to the leading system message (or add one)
+ */
+export const applyGlossaryToMessages = (messages, glossary) => {
+  const prompt = buildGlossaryPrompt(glossary)
+  if (!prompt) return messages
+  const [first, ...rest] = messages
+  if (first?.role === 'system' && typeof first.content === 'string') {
+    return [{ ...first, content: `${first.content}\n\n${prompt}` }, ...rest]
+  }
+  return [{ role: 'system', content: prompt }, ...messages]
+}
+
+const escapeRegExp = text => text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')
+
+// Word boundaries only where the term itself starts/ends with a word character, so CJK terms
+// (written without spaces) still match inside running text
+const buildTermPattern = term => {
+  const start = /^[\p{L}\p{N}_]/u.test(term) && !/^\p{Script=Han}/u.test(term)
+  const end = /[\p{L}\p{N}_]$/u.test(term) && !/\p{Script=Han}$/u.test(term)
+  const before = start ? '(?<![\\p{L}\\p{N}_])' : ''
+  const after = end ? '(?![\\p{L}\\p{N}_])' : ''
+  return new RegExp(`${before}${escapeRegExp(term)}${after}`, 'giu')
+}
+
+// Code is quoted verbatim, so identifiers there are not terminology violations
+const maskCode = text =>
+  text.replace(/```[\s\S]*?```|`[^`\n]*`/g, match => match.replace(/[^\n]/g, ' '))
+
+const findTerm = (text, term) =>
+  Array.from(text.matchAll(buildTermPattern(term)), match => ({
+    index: match.index,
+    text: match[0],
+  }))
+
+/**
+ * Find avoided and banned wording in a text
+ * @returns {{ violations: Array<{ rule, term, expected, reason, count, matches }> }}
+ *   rule: 'avoid' | 'banned'; matches: up to 3 { index, text } occurrences per term
+ */
+export const checkTerminology = (text, glossary) => {
+  if (!hasEntries(glossary) || !text) return { violations: [] }
+  const source = maskCode(String(text))
+  const violations = []
+  const report = (rule, term, expected, reason) => {
+    const matches = findTerm(source, term)
+    if (!matches.length) return
+    violations.push({
+      rule,
+      term,
+      expected,
+      reason,
+      count: matches.length,
+      matches: matches.slice(0, MAX_VIOLATION_SAMPLES),
+    })
+  }
+  for (const entry of glossary.terms) {
+    for (const variant of entry.avoid) report('avoid', variant, entry.preferred || entry.term, null)
+  }
+  for (const entry of glossary.banned) {
+    report('banned', entry.term, entry.replacement, entry.reason)
+  }
+  return { violations: violations.sort((a, b) => a.matches[0].index - b.matches[0].index) }
+}
+
+/**
+ * Pass an event stream through, checking the final answer against the glossary
+ * Deviations are announced as a "terminology_violations" event ahead of done, and done carries
+ * "terminology_check".
+ */
+export const checkTerminologyInStream = async function* (stream, glossary) {
+  for await (const event of stream) {
+    if (!hasEntries(glossary) || event?.type !== 'done' || !event.content) {
+      yield event
+      continue
+    }
+    const check = checkTerminology(event.content, glossary)
+    if (check.violations.length) {
+      yield { type: 'terminology_violations', violations: check.violations }
+    }
+    yield { ...event, terminology_check: check }
+  }
+}
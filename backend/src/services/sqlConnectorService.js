@@ -0,0 +1,278 @@
+/**
+ * SQL connector service
+ * Read-only access to user-configured Postgres/MySQL/SQLite databases: schema inspection and
+ * SELECT queries with row/size limits and a system-table denylist.
+ *
+ * Drivers are loaded on demand (pg, mysql2, better-sqlite3); install the one you need.
+ */
+
+import { randomUUID } from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import { resolveSandboxedDir } from '../utils/pathSandbox.js'
+import { decryptSecret, encryptSecret, maskSecret } from './keyVault.js'
+
+const COLLECTION = 'sql-connections'
+const DATABASE_TYPES = ['postgres', 'mysql', 'sqlite']
+const DEFAULT_MAX_ROWS = 200
+const MAX_ROWS_LIMIT = 1000
+const MAX_RESULT_BYTES = 256 * 1024
+const MAX_CELL_CHARS = 2000
+const QUERY_TIMEOUT_MS = 15000
+
+// Data-modifying statements, including those nested in CTEs or SELECT ... INTO
+const WRITE_KEYWORDS =
+  /\b(?:insert|update|delete|merge|upsert|drop|alter|create|truncate|grant|revoke|copy|call|exec|execute|lock|vacuum|attach|detach|pragma|into|outfile|dumpfile)\b/i
+
+// Catalog schemas, engine-internal tables, and file/timing functions
+const SYSTEM_OBJECTS =
+  /\b(?:pg_\w+|information_schema|performance_schema|mysql\s*\.|sys\s*\.|sqlite_\w+|lo_\w+|dblink\w*)\b|\b(?:load_file|benchmark|sleep)\s*\(/i
+
+export class SqlConnectorError extends Error {
+  constructor(message, status = 400) {
+    super(message)
+    this.name = 'SqlConnectorError'
+    this.status = status
+  }
+}
+
+const toPublicConnection = record => ({
+  id: record.id,
+  name: record.name,
+  type: record.type,
+  target: record.type === 'sqlite' ? record.file : record.connectionMasked,
+  maxRows: record.maxRows,
+  createdAt: record.createdAt,
+})
+
+/**
+ * Register a connection; connection strings are encrypted at rest
+ */
+export const createSqlConnection = ({ name, type, connectionString, file, maxRows }) => {
+  if (!String(name || '').trim()) throw new SqlConnectorError('name is required')
+  if (!DATABASE_TYPES.includes(type)) {
+    throw new SqlConnectorError(`type must be one of: ${DATABASE_TYPES.join(', ')}`)
+  }
+  if (type === 'sqlite' && !file) throw new SqlConnectorError('file is required for sqlite')
+  if (type !== 'sqlite' && !connectionString) {
+    throw new SqlConnectorError(`connectionString is required for ${type}`)
+  }
+  const id = randomUUID()
+  const record = {
+    id,
+    name: String(name).trim(),
+    type,
+    file: type === 'sqlite' ? resolveSandboxedDir(file) : null,
+    connectionEncrypted: type === 'sqlite' ? null : encryptSecret(connectionString),
+    connectionMasked: type === 'sqlite' ? null : maskSecret(connectionString),
+    maxRows: Math.min(Number(maxRows) || DEFAULT_MAX_ROWS, MAX_ROWS_LIMIT),
+    createdAt: new Date().toISOString(),
+  }
+  writeRecord(COLLECTION, id, record)
+  return toPublicConnection(record)
+}
+
+export const listSqlConnections = () => listRecords(COLLECTION).map(toPublicConnection)
+
+export const deleteSqlConnection = id => deleteRecord(COLLECTION, id)
+
+/**
+ * Look up a connection by id or (case-insensitive) name
+ */
+const findConnection = idOrName => {
+  const byId = readRecord(COLLECTION, String(idOrName || ''))
+  if (byId) return byId
+  const name = String(idOrName || '').toLowerCase()
+  const byName = listRecords(COLLECTION).find(record => record.name.toLowerCase() === name)
+  if (!byName) throw new SqlConnectorError(`Unknown SQL connection: ${idOrName}`, 404)
+  return byName
+}
+
+// Comments and string/identifier literals of each dialect, matched left to right so a quote
+// inside a comment (or "--" inside a string) is read the way the database reads it. "#" starts
+// a comment only in MySQL (it is XOR in Postgres); backslashes escape quotes in MySQL strings
+// and Postgres E'' strings.
+const LITERAL_PATTERNS = {
+  postgres:
+    /--[^\n]*|\/\*[\s\S]*?\*\/|(?<!\w)[eE]'(?:[^'\\]|\\[\s\S]|'')*'|'(?:[^']|'')*'|"(?:[^"]|"")*"/g,
+  mysql:
+    /--(?=\s|$)[^\n]*|#[^\n]*|\/\*[\s\S]*?\*\/|'(?:[^'\\]|\\[\s\S]|'')*'|"(?:[^"\\]|\\[\s\S]|"")*"|`(?:[^`]|``)*`/g,
+  sqlite: /--[^\n]*|\/\*[\s\S]*?\*\/|'(?:[^']|'')*'|"(?:[^"]|"")*"|`(?:[^`]|``)*`|\[[^\]]*\]/g,
+}
+
+const isComment = text => /^(?:--|#|\/\*)/.test(text)
+
+// Blank out comments and literals so keyword checks only see SQL structure
+const stripLiterals = (sql, type) =>
+  sql.replace(LITERAL_PATTERNS[type] || LITERAL_PATTERNS.postgres, match =>
+    isComment(match) ? ' ' : "''",
+  )
+
+/**
+ * Reject anything that is not a single SELECT/WITH statement over user tables
+ * @param {string} sql
+ * @param {string} type - Database type, for its comment and quoting rules
+ * @returns {string} The statement without a trailing semicolon
+ */
+export const validateReadOnlyQuery = (sql, type) => {
+  const statement = String(sql || '')
+    .trim()
+    .replace(/;\s*$/, '')
+  if (!statement) throw new SqlConnectorError('sql is required')
+  const structure = stripLiterals(statement, type)
+  if (structure.includes(';')) throw new SqlConnectorError('Only a single statement is allowed')
+  if (!/^\s*(?:select|with)\b/i.test(structure)) {
+    throw new SqlConnectorError('Only SELECT queries are allowed')
+  }
+  const write = structure.match(WRITE_KEYWORDS)
+  if (write) throw new SqlConnectorError(`Keyword not allowed in read-only mode: ${write[0]}`)
+  const system = structure.match(SYSTEM_OBJECTS)
+  if (system) throw new SqlConnectorError(`System objects are not accessible: ${system[0]}`)
+  return statement
+}
+
+const loadDriver = async (specifier, type) => {
+  try {
+    return await import(specifier)
+  } catch (error) {
+    if (error.code === 'ERR_MODULE_NOT_FOUND') {
+      throw new SqlConnectorError(
+        `The "${specifier.split('/')[0]}" package is required for ${type} connections`,
+        501,
+      )
+    }
+    throw error
+  }
+}
+
+const runPostgres = async (record, sql, params = []) => {
+  const { default: pg } = await loadDriver('pg', 'postgres')
+  const client = new pg.Client({
+    connectionString: decryptSecret(record.connectionEncrypted),
+    statement_timeout: QUERY_TIMEOUT_MS,
+  })
+  await client.connect()
+  try {
+    await client.query('BEGIN READ ONLY')
+    // A named query goes through the extended protocol, which runs exactly one statement; the
+    // simple protocol would run a smuggled "; COMMIT; ..." after the SELECT
+    const result = await client.query({ name: 'qurio_read_only', text: sql, values: params })
+    return { columns: result.fields.map(field => field.name), rows: result.rows }
+  } finally {
+    await client.query('ROLLBACK').catch(() => {})
+    await client.end().catch(() => {})
+  }
+}
+
+const runMysql = async (record, sql, params = []) => {
+  const mysql = await loadDriver('mysql2/promise', 'mysql')
+  const connection = await mysql.createConnection({
+    uri: decryptSecret(record.connectionEncrypted),
+    connectTimeout: QUERY_TIMEOUT_MS,
+  })
+  try {
+    await connection.query('START TRANSACTION READ ONLY')
+    const [rows, fields] = await connection.query({ sql, timeout: QUERY_TIMEOUT_MS }, params)
+    return { columns: (fields || []).map(field => field.name), rows }
+  } finally {
+    await connection.query('ROLLBACK').catch(() => {})
+    await connection.end().catch(() => {})
+  }
+}
+
+const runSqlite = async (record, sql, params = []) => {
+  const { default: Database } = await loadDriver('better-sqlite3', 'sqlite')
+  const db = new Database(resolveSandboxedDir(record.file), {
+    readonly: true,
+    fileMustExist: true,
+  })
+  try {
+    const statement = db.prepare(sql)
+    if (!statement.reader) throw new SqlConnectorError('Only SELECT queries are allowed')
+    return {
+      columns: statement.columns().map(column => column.name),
+      rows: statement.all(...params),
+    }
+  } finally {
+    db.close()
+  }
+}
+
+const RUNNERS = { postgres: runPostgres, mysql: runMysql, sqlite: runSqlite }
+
+const truncateCell = value => {
+  if (typeof value === 'string' && value.length > MAX_CELL_CHARS) {
+    return `${value.slice(0, MAX_CELL_CHARS)}…`
+  }
+  if (typeof value === 'bigint') return value.toString()
+  if (Buffer.isBuffer(value)) return `<${value.length} bytes>`
+  return value
+}
+
+/**
+ * Run a validated SELECT with a row cap; large results are cut to the size budget
+ * @returns {Promise<{columns: string[], rows: Array<Object>, rowCount: number,
+ *   truncated: boolean}>}
+ */
+export const runReadOnlyQuery = async (idOrName, sql, { maxRows } = {}) => {
+  const record = findConnection(idOrName)
+  const statement = validateReadOnlyQuery(sql, record.type)
+  const limit = Math.min(Number(maxRows) || record.maxRows, record.maxRows)
+  // Newlines keep a trailing line comment in the statement from swallowing the wrapper
+  const wrapped = `SELECT * FROM (\n${statement}\n) AS qurio_q LIMIT ${limit + 1}`
+  const { columns, rows } = await RUNNERS[record.type](record, wrapped)
+
+  let truncated = rows.length > limit
+  const output = []
+  let bytes = 0
+  for (const row of rows.slice(0, limit)) {
+    const cleaned = Object.fromEntries(
+      Object.entries(row).map(([key, value]) => [key, truncateCell(value)]),
+    )
+    bytes += Buffer.byteLength(JSON.stringify(cleaned))
+    if (bytes > MAX_RESULT_BYTES) {
+      truncated = true
+      break
+    }
+    output.push(cleaned)
+  }
+  return { columns, rows: output, rowCount: output.length, truncated }
+}
+
+const SCHEMA_QUERIES = {
+  postgres: `SELECT table_schema AS schema, table_name AS table, column_name AS column,
+      data_type AS type, is_nullable AS nullable
+    FROM information_schema.columns
+    WHERE table_schema NOT IN ('pg_catalog', 'information_schema')
+      AND table_schema NOT LIKE 'pg_toast%'
+    ORDER BY table_schema, table_name, ordinal_position`,
+  mysql: `SELECT table_schema AS \`schema\`, table_name AS \`table\`, column_name AS \`column\`,
+      data_type AS type, is_nullable AS nullable
+    FROM information_schema.columns
+    WHERE table_schema = DATABASE()
+    ORDER BY table_name, ordinal_position`,
+  sqlite: `SELECT 'main' AS schema, m.name AS "table", p.name AS "column", p.type AS type,
+      CASE p."notnull" WHEN 1 THEN 'NO' ELSE 'YES' END AS nullable
+    FROM sqlite_master m JOIN pragma_table_info(m.name) p
+    WHERE m.type IN ('table', 'view') AND m.name NOT LIKE 'sqlite_%'
+    ORDER BY m.name, p.cid`,
+}
+
+/**
+ * Tables and columns of user schemas (system catalogs excluded)
+ * @returns {Promise<{type: string, tables: Array<{schema, name, columns: Array}>}>}
+ */
+export const getSqlSchema = async idOrName => {
+  const record = findConnection(idOrName)
+  const { rows } = await RUNNERS[record.type](record, SCHEMA_QUERIES[record.type])
+  const tables = new Map()
+  for (const row of rows) {
+    const key = `${row.schema}.${row.table}`
+    if (!tables.has(key)) tables.set(key, { schema: row.schema, name: row.table, columns: [] })
+    tables.get(key).columns.push({
+      name: row.column,
+      type: row.type,
+      nullable: String(row.nullable).toUpperCase() === 'YES',
+    })
+  }
+  return { type: record.type, tables: Array.from(tables.values()) }
+}
@@ -0,0 +1,123 @@
+/**
+ * StackExchange service
+ * Question search across StackExchange sites with the best answer for each question.
+ * STACKEXCHANGE_KEY is optional and only raises the daily quota.
+ */
+
+const API_BASE = 'https://api.stackexchange.com/2.3'
+const REQUEST_TIMEOUT_MS = 15000
+const MAX_ANSWER_CHARS = 3000
+const MAX_QUESTION_CHARS = 1000
+
+const decodeEntities = text =>
+  text
+    .replace(/&lt;/g, '<')
+    .replace(/&gt;/g, '>')
+    .replace(/&quot;/g, '"')
+    .replace(/&#39;/g, "'")
+    .replace(/&#(\d+);/g, (_, code) => String.fromCharCode(Number(code)))
+    .replace(/&amp;/g, '&')
+
+// Keep code blocks readable; drop the rest of the markup
+const htmlToText = (html, max) => {
+  const text = decodeEntities(
+    String(html || '')
+      .replace(/<pre[^>]*><code[^>]*>([\s\S]*?)<\/code><\/pre>/gi, '\n```\n$1\n```\n')
+      .replace(/<code>([\s\S]*?)<\/code>/gi, '`$1`')
+      .replace(/<br\s*\/?>|<\/p>|<\/li>/gi, '\n')
+      .replace(/<[^>]+>/g, ''),
+  )
+    .replace(/\n{3,}/g, '\n\n')
+    .trim()
+  return text.length > max ? `${text.slice(0, max)}…` : text
+}
+
+const stackExchangeFetch = async (path, params, { signal } = {}) => {
+  const query = new URLSearchParams(params)
+  if (process.env.STACKEXCHANGE_KEY) query.set('key', process.env.STACKEXCHANGE_KEY)
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(`${API_BASE}${path}?${query}`, {
+    headers: { Accept: 'application/json' },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  const data = await response.json().catch(() => ({}))
+  if (!response.ok || data.error_id) {
+    throw new Error(`StackExchange API error: ${data.error_message || `HTTP ${response.status}`}`)
+  }
+  return data
+}
+
+/**
+ * Search questions and attach the accepted (or highest-voted) answer
+ * @param {Object} args
+ * @param {string} args.query
+ * @param {string} [args.site='stackoverflow']
+ * @param {string[]} [args.tags]
+ * @param {boolean} [args.acceptedOnly=false] Only questions with an accepted answer
+ * @param {number} [args.limit=5]
+ */
+export const searchStackExchange = async ({
+  query,
+  site = 'stackoverflow',
+  tags = [],
+  acceptedOnly = false,
+  limit = 5,
+  signal,
+}) => {
+  const pageSize = Math.min(Math.max(Number(limit) || 5, 1), 20)
+  const search = await stackExchangeFetch(
+    '/search/advanced',
+    {
+      q: query,
+      site,
+      order: 'desc',
+      sort: 'relevance',
+      pagesize: String(pageSize),
+      filter: 'withbody',
+      ...(tags.length ? { tagged: tags.join(';') } : {}),
+      ...(acceptedOnly ? { accepted: 'True' } : {}),
+    },
+    { signal },
+  )
+  const questions = search.items || []
+  if (!questions.length) return { site, results: [] }
+
+  const answers = await stackExchangeFetch(
+    `/questions/${questions.map(question => question.question_id).join(';')}/answers`,
+    { site, order: 'desc', sort: 'votes', pagesize: '100', filter: 'withbody' },
+    { signal },
+  )
+  const bestAnswers = new Map()
+  for (const answer of answers.items || []) {
+    const current = bestAnswers.get(answer.question_id)
+    // Accepted answer wins; otherwise keep the first (highest-voted) one
+    if (!current || (answer.is_accepted && !current.is_accepted)) {
+      bestAnswers.set(answer.question_id, answer)
+    }
+  }
+
+  return {
+    site,
+    quota_remaining: search.quota_remaining,
+    results: questions.map(question => {
+      const answer = bestAnswers.get(question.question_id)
+      const questionText = htmlToText(question.body, MAX_QUESTION_CHARS)
+      const answerText = answer ? htmlToText(answer.body, MAX_ANSWER_CHARS) : ''
+      return {
+        title: decodeEntities(question.title),
+        url: question.link,
+        content: answer ? `Q: ${questionText}\n\nA: ${answerText}` : `Q: ${questionText}`,
+        score: question.score,
+        tags: question.tags,
+        is_answered: question.is_answered,
+        answer: answer
+          ? {
+              score: answer.score,
+              accepted: answer.is_accepted,
+              url: `${new URL(question.link).origin}/a/${answer.answer_id}`,
+            }
+          : null,
+      }
+    }),
+  }
+}
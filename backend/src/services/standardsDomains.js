@@ -0,0 +1,58 @@
+/**
+ * Standards and regulatory domains configuration
+ * Official publishers of standards, RFCs, and legislation for the Tavily_standards_search tool
+ */
+
+export const STANDARDS_DOMAINS = [
+  // Internet standards
+  'rfc-editor.org',
+  'datatracker.ietf.org',
+  'ietf.org',
+  'w3.org',
+  'whatwg.org',
+
+  // International standards bodies
+  'iso.org',
+  'iec.ch',
+  'itu.int',
+  'ieee.org',
+  'standards.ieee.org',
+  'etsi.org',
+  'cen.eu',
+  'cencenelec.eu',
+
+  // National standards and metrology
+  'nist.gov',
+  'csrc.nist.gov',
+  'ansi.org',
+  'bsigroup.com',
+  'din.de',
+
+  // EU legislation
+  'eur-lex.europa.eu',
+  'europa.eu',
+  'edpb.europa.eu',
+  'eba.europa.eu',
+  'esma.europa.eu',
+
+  // US federal register and code
+  'federalregister.gov',
+  'ecfr.gov',
+  'govinfo.gov',
+  'regulations.gov',
+  'congress.gov',
+  'uscode.house.gov',
+
+  // UK and other registers
+  'legislation.gov.uk',
+  'gov.uk',
+  'laws-lois.justice.gc.ca',
+  'legislation.gov.au',
+
+  // Sector regulators
+  'fda.gov',
+  'sec.gov',
+  'ftc.gov',
+  'fcc.gov',
+  'ema.europa.eu',
+]
@@ -0,0 +1,234 @@
+/**
+ * Storage service
+ * Disk usage of the local data directory by category, and the maintenance operations long-term
+ * users need to keep it small: clearing caches, compacting the data store (leftover temp files,
+ * orphaned attachment files, expired uploads), and bulk-deleting old attachments.
+ * Collections not listed in STORAGE_CATEGORIES are reported under "other".
+ */
+
+import fs from 'fs'
+import path from 'path'
+import { deleteRecord, getDataDir, listRecords } from '../utils/dataStore.js'
+import { clearModelCache } from './modelCatalogService.js'
+import { clearResponseCache } from './responseCache.js'
+import { deleteScreenshot, listScreenshots } from './screenshotService.js'
+import { deleteUpload, listUploads, pruneExpiredUploads } from './uploadService.js'
+
+export const STORAGE_CATEGORIES = {
+  conversations: ['conversations', 'conversation-messages', 'spaces', 'agents', 'title-proposals'],
+  research: ['research-runs', 'watchlist', 'watchlist-reports'],
+  attachments: ['uploads', 'screenshots'],
+  rag_indexes: ['rag-documents', 'code-index'],
+  caches: ['source-checks'],
+}
+
+export const ATTACHMENT_KINDS = ['uploads', 'screenshots']
+
+// Temp files of an atomic write younger than this may still be in flight
+const STALE_TEMP_MS = 60 * 1000
+
+const CATEGORY_BY_COLLECTION = new Map(
+  Object.entries(STORAGE_CATEGORIES).flatMap(([category, collections]) =>
+    collections.map(collection => [collection, category]),
+  ),
+)
+
+// Bytes and file count below a path
+const measure = target => {
+  const stat = fs.statSync(target, { throwIfNoEntry: false })
+  if (!stat) return { bytes: 0, files: 0 }
+  if (!stat.isDirectory()) return { bytes: stat.size, files: 1 }
+  return fs.readdirSync(target).reduce(
+    (total, name) => {
+      const { bytes, files } = measure(path.join(target, name))
+      return { bytes: total.bytes + bytes, files: total.files + files }
+    },
+    { bytes: 0, files: 0 },
+  )
+}
+
+const listCollectionDirs = () => {
+  const dataDir = getDataDir()
+  if (!fs.existsSync(dataDir)) return []
+  return fs
+    .readdirSync(dataDir, { withFileTypes: true })
+    .filter(entry => entry.isDirectory())
+    .map(entry => entry.name)
+}
+
+/**
+ * Disk usage by category and collection
+ * @returns {{ data_dir: string, total_bytes: number, total_files: number, categories: Array }}
+ */
+export const getStorageUsage = () => {
+  const dataDir = getDataDir()
+  const categories = new Map(
+    [...Object.keys(STORAGE_CATEGORIES), 'other'].map(category => [
+      category,
+      { category, bytes: 0, files: 0, collections: [] },
+    ]),
+  )
+  for (const collection of listCollectionDirs()) {
+    const usage = measure(path.join(dataDir, collection))
+    const entry = categories.get(CATEGORY_BY_COLLECTION.get(collection) || 'other')
+    entry.bytes += usage.bytes
+    entry.files += usage.files
+    entry.collections.push({ name: collection, ...usage })
+  }
+  // Files directly in the data directory (e.g. the key vault's master key)
+  const other = categories.get('other')
+  if (fs.existsSync(dataDir)) {
+    for (const entry of fs.readdirSync(dataDir, { withFileTypes: true })) {
+      if (!entry.isFile()) continue
+      other.bytes += measure(path.join(dataDir, entry.name)).bytes
+      other.files += 1
+    }
+  }
+  const list = Array.from(categories.values())
+  for (const entry of list) entry.collections.sort((a, b) => b.bytes - a.bytes)
+  return {
+    data_dir: dataDir,
+    total_bytes: list.reduce((sum, entry) => sum + entry.bytes, 0),
+    total_files: list.reduce((sum, entry) => sum + entry.files, 0),
+    categories: list,
+  }
+}
+
+/**
+ * Drop cached data that is rebuilt on demand: link check results (re-checked by the next
+ * link-check run) and the in-memory model catalog
+ */
+export const pruneCaches = () => {
+  const dataDir = getDataDir()
+  let freedBytes = 0
+  let removedRecords = 0
+  for (const collection of STORAGE_CATEGORIES.caches) {
+    const before = measure(path.join(dataDir, collection)).bytes
+    for (const record of listRecords(collection)) {
+      if (record.id && deleteRecord(collection, record.id)) removedRecords += 1
+    }
+    freedBytes += before - measure(path.join(dataDir, collection)).bytes
+  }
+  clearModelCache()
+  clearResponseCache()
+  return {
+    cleared: [...STORAGE_CATEGORIES.caches, 'model-catalog', 'responses'],
+    removed_records: removedRecords,
+    freed_bytes: freedBytes,
+  }
+}
+
+const removePath = target => {
+  const { bytes, files } = measure(target)
+  fs.rmSync(target, { recursive: true, force: true })
+  return { bytes, files }
+}
+
+// Attachment files whose metadata record is gone
+const findOrphanFiles = collection => {
+  const dir = path.join(getDataDir(), collection)
+  if (!fs.existsSync(dir)) return []
+  const names = fs.readdirSync(dir)
+  const recordIds = new Set(
+    names.filter(name => name.endsWith('.json')).map(name => name.slice(0, -'.json'.length)),
+  )
+  return names
+    .filter(name => !name.endsWith('.json') && !name.endsWith('.tmp'))
+    .filter(name => !recordIds.has(name.replace(/\.(parts|bin|png|jpg|webp)$/, '')))
+    .map(name => path.join(dir, name))
+}
+
+/**
+ * Compact the data store: remove temp files left by interrupted writes, attachment files without
+ * a record, expired unfinished uploads, and empty collection directories. Records that no longer
+ * parse are reported, not deleted.
+ */
+export const vacuumDataStore = (now = Date.now()) => {
+  const dataDir = getDataDir()
+  const expiredUploads = pruneExpiredUploads(now)
+  let freedBytes = 0
+  let removedFiles = 0
+  const corruptRecords = []
+  const remove = target => {
+    const { bytes, files } = removePath(target)
+    freedBytes += bytes
+    removedFiles += files
+  }
+
+  for (const collection of listCollectionDirs()) {
+    const dir = path.join(dataDir, collection)
+    for (const name of fs.readdirSync(dir)) {
+      const target = path.join(dir, name)
+      if (name.endsWith('.tmp')) {
+        if (now - fs.statSync(target).mtimeMs > STALE_TEMP_MS) remove(target)
+      } else if (name.endsWith('.json')) {
+        try {
+          JSON.parse(fs.readFileSync(target, 'utf8'))
+        } catch {
+          corruptRecords.push(`${collection}/${name}`)
+        }
+      }
+    }
+    if (STORAGE_CATEGORIES.attachments.includes(collection)) {
+      for (const orphan of findOrphanFiles(collection)) remove(orphan)
+    }
+    if (!fs.readdirSync(dir).length) fs.rmdirSync(dir)
+  }
+
+  return {
+    removed_files: removedFiles,
+    freed_bytes: freedBytes,
+    expired_uploads: expiredUploads,
+    corrupt_records: corruptRecords,
+  }
+}
+
+const listAttachments = kinds => [
+  ...(kinds.includes('uploads')
+    ? listUploads().map(upload => ({
+        kind: 'uploads',
+        id: upload.id,
+        name: upload.filename,
+        bytes: upload.size,
+        created_at: upload.created_at,
+      }))
+    : []),
+  ...(kinds.includes('screenshots')
+    ? listScreenshots().map(screenshot => ({
+        kind: 'screenshots',
+        id: screenshot.id,
+        name: screenshot.url,
+        bytes: screenshot.bytes || 0,
+        created_at: screenshot.captured_at,
+      }))
+    : []),
+]
+
+/**
+ * Delete attachments created before a cutoff
+ * @param {Object} options
+ * @param {number} options.olderThanDays
+ * @param {string[]} [options.kinds] - Subset of ATTACHMENT_KINDS (default all)
+ * @param {boolean} [options.dryRun] - Only list what would be deleted
+ */
+export const deleteOldAttachments = ({
+  olderThanDays,
+  kinds = ATTACHMENT_KINDS,
+  dryRun = false,
+  now = Date.now(),
+}) => {
+  const cutoff = now - olderThanDays * 24 * 3600 * 1000
+  const deleted = listAttachments(kinds)
+    .filter(attachment => Date.parse(attachment.created_at) < cutoff)
+    .filter(attachment => {
+      if (dryRun) return true
+      const remove = attachment.kind === 'uploads' ? deleteUpload : deleteScreenshot
+      return remove(attachment.id)
+    })
+  return {
+    cutoff: new Date(cutoff).toISOString(),
+    dry_run: dryRun,
+    deleted,
+    freed_bytes: deleted.reduce((sum, attachment) => sum + attachment.bytes, 0),
+  }
+}
@@ -0,0 +1,377 @@
+/**
+ * Stream chat runner
+ * The transport-independent part of a chat stream: request validation and the event pipeline
+ * (slash commands / smart mode / chat, answer constraints, terminology checks, message journal,
+ * plain text). POST /api/stream-chat writes the events as SSE and GET /api/ws/chat as WebSocket
+ * frames; both hand runStreamChat a ChatEventSink.
+ */
+
+import { resolveRequestAgent } from './agentPresetService.js'
+import {
+  enforceAnswerConstraints,
+  normalizeAnswerConstraints,
+  validateAnswerConstraints,
+} from './answerConstraintsService.js'
+import { resolveMessageAttachments } from './attachmentService.js'
+import { validateMemoryTokenBudget } from './conversationMemoryService.js'
+import { listMessages } from './conversationStore.js'
+import { IMAGE_PROVIDERS } from './images/index.js'
+import { journalStreamedMessage } from './messageJournal.js'
+import { plainTextStream } from './plainTextStream.js'
+import { validateProbedCapabilities } from './providers/capabilityProbe.js'
+import { isValidContextCacheId, normalizeContextCache } from './providers/kimiFiles.js'
+import {
+  AUDIO_FORMATS,
+  normalizeAudioOutput,
+  speakAnswerInStream,
+} from './providers/minimaxSpeech.js'
+import { requiresApiKey, supportsCapability } from './providers/providerConfig.js'
+import { streamDecomposedChat } from './questionDecompositionService.js'
+import { isSearchProviderSupported, SEARCH_PROVIDERS } from './search/index.js'
+import { detectSlashCommand, streamSlashCommand } from './slashCommandService.js'
+import { checkTerminologyInStream, getSpaceGlossary } from './spaceGlossaryService.js'
+import { streamChat } from './streamChatService.js'
+import { streamRegistry } from './streamRegistry.js'
+import { addSpanEvent, getActiveTraceId } from './tracing.js'
+import { validateMaxTurns } from './turnLimits.js'
+import { recordUsage } from './usageLedger.js'
+import { toErrorEvent } from '../utils/errors.js'
+
+/**
+ * Where a chat stream's events go (utils/sse.js createSseStream, utils/webSocket.js
+ * createWebSocketSink)
+ * @typedef {Object} ChatEventSink
+ * @property {(event: Object) => void} sendEvent - Deliver one event (sinks apply event_filter)
+ * @property {() => void} close - End the stream after the last event
+ */
+
+const SUPPORTED_PROVIDERS = [
+  'gemini',
+  'openai',
+  'openai_compatibility',
+  'siliconflow',
+  'glm',
+  'modelscope',
+  'kimi',
+  'nvidia',
+  'minimax',
+]
+
+/**
+ * Read a StreamChatRequest body (snake_case aliases included) into runStreamChat params
+ */
+const readRequest = body => {
+  const {
+    provider,
+    apiKey,
+    baseUrl,
+    model,
+    messages,
+    tools,
+    toolChoice,
+    responseFormat,
+    thinking,
+    temperature,
+    top_k,
+    top_p,
+    frequency_penalty,
+    presence_penalty,
+    contextMessageLimit,
+    memoryTokenBudget = body.memory_token_budget, // Summarize older turns past this many tokens
+    memoryModel = body.memory_model, // Model that writes the conversation memory
+    toolIds,
+    searchProvider = body.search_provider, // Web search backend (default SEARCH_PROVIDER)
+    searchApiKey = body.search_api_key,
+    searchBaseUrl = body.search_base_url,
+    tavilyApiKey,
+    imageProvider = body.image_provider, // generate_image backend
+    imageApiKey = body.image_api_key,
+    imageBaseUrl = body.image_base_url,
+    imageModel = body.image_model,
+    userTools,
+    smartMode,
+    userId, // Selects stored preferences (defaults to the shared profile)
+    stream_id: streamId, // Client-chosen id for POST /api/streams/:id/cancel
+    sessionId = body.session_id, // Window session, for per-window cancellation
+    conversationId = body.conversation_id, // Books token usage (GET /api/usage)
+    persistMessage = body.persist_message, // Save the answer into the conversation
+    messageId = body.message_id, // Id of the stored answer (with persistMessage)
+    snippetIds, // Saved snippets to insert as context blocks
+    mcpServers = body.mcp_servers, // Loaded MCP servers to expose as tools
+    maxWords = body.max_words, // Strict answer word budget
+    format, // 'bullets' | 'table' | 'short' | 'long'
+    spaceId = body.space_id, // Space whose terminology glossary applies
+    maxTurns = body.max_turns, // Tool-loop turn limit (see turnLimits)
+    plainText = body.plain_text, // Strip markdown/emoji/citations for screen readers
+    contextCache = body.context_cache, // Kimi context cache to put ahead of the conversation
+    audio, // Spoken answer (providers with supportsAudioOutput)
+  } = body
+  return {
+    provider,
+    apiKey,
+    baseUrl,
+    model,
+    messages,
+    tools,
+    toolChoice,
+    responseFormat,
+    thinking,
+    temperature,
+    top_k,
+    top_p,
+    frequency_penalty,
+    presence_penalty,
+    contextMessageLimit,
+    memoryTokenBudget:
+      memoryTokenBudget === undefined || memoryTokenBudget === null
+        ? undefined
+        : Number(memoryTokenBudget),
+    memoryModel,
+    toolIds,
+    searchProvider,
+    searchApiKey,
+    searchBaseUrl,
+    tavilyApiKey,
+    imageProvider,
+    imageApiKey,
+    imageBaseUrl,
+    imageModel,
+    userTools,
+    smartMode,
+    userId,
+    streamId,
+    sessionId,
+    conversationId,
+    persistMessage,
+    messageId,
+    snippetIds,
+    mcpServers,
+    maxWords,
+    format,
+    spaceId,
+    maxTurns,
+    plainText,
+    contextCache,
+    audio: normalizeAudioOutput(audio),
+  }
+}
+
+/**
+ * Validate a StreamChatRequest body and resolve its agent and attachments
+ * @param {Object} body - After space credentials are applied
+ * @returns {Promise<{ rejection: { status: number, error: string, details?: string[] } } |
+ *   { request: Object }>} rejection is answered before any event is sent (HTTP status for SSE)
+ */
+export const prepareStreamChat = async body => {
+  // The agent fills in what the request leaves unset, so it is resolved before anything is read
+  const resolved = resolveRequestAgent(body || {})
+  if (resolved.rejection) return resolved
+  const request = readRequest(resolved.body)
+  const { provider, apiKey, baseUrl, model, messages, tools, responseFormat } = request
+  const reject = (error, details, status = 400) => ({
+    rejection: details ? { status, error, details } : { status, error },
+  })
+
+  if (process.env.DEBUG_TOOLS === '1') {
+    console.log(
+      '[API] streamChat toolIds:',
+      Array.isArray(request.toolIds) ? request.toolIds : [],
+    )
+  }
+
+  if (!provider) return reject('Missing required field: provider')
+  if (!apiKey && requiresApiKey(provider)) return reject('Missing required field: apiKey')
+  if (!messages || !Array.isArray(messages)) return reject('Missing required field: messages')
+  const turnsError = validateMaxTurns(request.maxTurns, 'chat')
+  if (turnsError) return reject(turnsError)
+  const memoryError = validateMemoryTokenBudget(request.memoryTokenBudget)
+  if (memoryError) return reject(memoryError)
+  if (request.searchProvider && !isSearchProviderSupported(request.searchProvider)) {
+    return reject(
+      `Unsupported search provider: ${request.searchProvider}. Supported: ${SEARCH_PROVIDERS.join(', ')}`,
+    )
+  }
+  if (request.imageProvider && !IMAGE_PROVIDERS.includes(request.imageProvider)) {
+    return reject(
+      `Unsupported image provider: ${request.imageProvider}. Supported: ${IMAGE_PROVIDERS.join(', ')}`,
+    )
+  }
+  if (request.contextCache !== undefined && request.contextCache !== null) {
+    if (provider !== 'kimi') return reject('contextCache is only supported by the kimi provider')
+    if (!isValidContextCacheId(normalizeContextCache(request.contextCache)?.id)) {
+      return reject('Invalid contextCache: expected a cache id or { id, reset_ttl }')
+    }
+  }
+  if (request.audio) {
+    if (!supportsCapability(provider, 'supportsAudioOutput')) {
+      return reject(`audio is not supported by the ${provider} provider`)
+    }
+    if (!AUDIO_FORMATS.includes(request.audio.format)) {
+      return reject(
+        `Unsupported audio format: ${request.audio.format}. Supported: ${AUDIO_FORMATS.join(', ')}`,
+      )
+    }
+  }
+  const constraintErrors = validateAnswerConstraints(request)
+  if (constraintErrors.length) return reject('Invalid answer constraints', constraintErrors)
+  const chatMessages = await resolveMessageAttachments(messages, { provider })
+  const capabilityErrors = validateProbedCapabilities({
+    provider,
+    baseUrl,
+    model,
+    messages: chatMessages,
+    tools,
+    responseFormat,
+  })
+  if (capabilityErrors.length) {
+    return reject('Model does not support this request', capabilityErrors)
+  }
+
+  if (!SUPPORTED_PROVIDERS.includes(provider)) {
+    return reject(
+      `Unsupported provider: ${provider}. Supported: ${SUPPORTED_PROVIDERS.join(', ')}`,
+    )
+  }
+
+  const { persistMessage, messageId, conversationId, streamId } = request
+  if (persistMessage && messageId && conversationId) {
+    if (listMessages(conversationId).some(message => message.id === String(messageId))) {
+      return reject(`Message already exists: ${messageId}`, null, 409)
+    }
+  }
+  if (streamId && streamRegistry.has(streamId)) {
+    return reject(`Stream already running: ${streamId}`, null, 409)
+  }
+
+  return {
+    request: {
+      ...request,
+      messages: chatMessages,
+      answerConstraints: normalizeAnswerConstraints(request),
+      spaceGlossary: getSpaceGlossary(request.spaceId),
+    },
+  }
+}
+
+/**
+ * Run a prepared chat stream into a sink, from stream_started to done / cancelled / error
+ * The stream is registered for POST /api/streams/:id/cancel while it runs; the sink is closed
+ * when it ends, whichever way.
+ * @param {Object} request - From prepareStreamChat()
+ * @param {ChatEventSink} sink
+ * @param {Object} options
+ * @param {AbortController} options.controller - Aborted when the client goes away
+ */
+export const runStreamChat = async (request, sink, { controller }) => {
+  const { provider, apiKey, baseUrl, model, conversationId, answerConstraints, spaceGlossary } =
+    request
+  let activeStreamId
+  try {
+    activeStreamId = streamRegistry.register({
+      streamId: request.streamId,
+      controller,
+      kind: 'chat',
+      sessionId: request.sessionId,
+    })
+    sink.sendEvent({
+      type: 'stream_started',
+      stream_id: activeStreamId,
+      trace_id: getActiveTraceId(),
+    })
+
+    const command = detectSlashCommand(request.messages)
+    const streamFn = command
+      ? params => streamSlashCommand(command, params)
+      : request.smartMode
+        ? streamDecomposedChat
+        : streamChat
+    const events = streamFn({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: request.messages,
+      tools: request.tools,
+      toolChoice: request.toolChoice,
+      responseFormat: request.responseFormat,
+      thinking: request.thinking,
+      temperature: request.temperature,
+      top_k: request.top_k,
+      top_p: request.top_p,
+      frequency_penalty: request.frequency_penalty,
+      presence_penalty: request.presence_penalty,
+      contextMessageLimit: request.contextMessageLimit,
+      conversationId,
+      memoryTokenBudget: request.memoryTokenBudget,
+      memoryModel: request.memoryModel,
+      toolIds: request.toolIds,
+      searchProvider: request.searchProvider,
+      searchApiKey: request.searchApiKey,
+      searchBaseUrl: request.searchBaseUrl,
+      tavilyApiKey: request.tavilyApiKey,
+      imageProvider: request.imageProvider,
+      imageApiKey: request.imageApiKey,
+      imageBaseUrl: request.imageBaseUrl,
+      imageModel: request.imageModel,
+      maxTurns: request.maxTurns,
+      contextCache: request.contextCache,
+      userTools: request.userTools,
+      userId: request.userId,
+      snippetIds: request.snippetIds,
+      mcpServers: request.mcpServers,
+      answerConstraints,
+      spaceGlossary,
+      signal: controller.signal,
+    })
+    const checked = enforceAnswerConstraints(events, answerConstraints, {
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      signal: controller.signal,
+    })
+    const checkedTerms = checkTerminologyInStream(checked, spaceGlossary)
+    const output = request.persistMessage
+      ? journalStreamedMessage(checkedTerms, {
+          conversationId,
+          messageId: request.messageId,
+          streamId: activeStreamId,
+          provider,
+          model,
+          signal: controller.signal,
+        })
+      : checkedTerms
+    // After journaling, so the stored message keeps its markdown
+    const readable = request.plainText ? plainTextStream(output) : output
+    const spoken = request.audio
+      ? speakAnswerInStream(readable, {
+          apiKey,
+          baseUrl,
+          audio: request.audio,
+          signal: controller.signal,
+        })
+      : readable
+    for await (const chunk of spoken) {
+      if (chunk?.type === 'done') {
+        recordUsage({ conversationId, kind: 'chat', provider, model, usage: chunk.usage })
+      }
+      sink.sendEvent(chunk)
+    }
+
+    if (streamRegistry.isCancelled(activeStreamId)) {
+      sink.sendEvent({ type: 'cancelled', stream_id: activeStreamId })
+    }
+  } catch (error) {
+    if (activeStreamId && streamRegistry.isCancelled(activeStreamId)) {
+      sink.sendEvent({ type: 'cancelled', stream_id: activeStreamId })
+    } else {
+      console.error('[API] streamChat error:', error)
+      const event = toErrorEvent(error)
+      addSpanEvent('error', event)
+      sink.sendEvent(event)
+    }
+  } finally {
+    if (activeStreamId) streamRegistry.unregister(activeStreamId)
+    sink.close()
+  }
+}
@@ -3,10 +3,35 @@
  * Clean architecture with provider adapter pattern
  */
 
+import { applyAnswerConstraintsToMessages } from './answerConstraintsService.js'
+import {
+  compactConversation,
+  createModelSummarizer,
+  getConversationMemoryConfig,
+} from './conversationMemoryService.js'
+import { IMAGE_PROVIDERS } from './images/index.js'
+import { applyPreferencesToMessages } from './preferencesService.js'
 import { getProviderAdapter } from './providers/adapterFactory.js'
+import { isVisionModel } from './providers/BaseProviderAdapter.js'
+import { GLM_NATIVE_SEARCH_TOOL_ID } from './providers/GLMAdapter.js'
+import { supportsCapability } from './providers/providerConfig.js'
+import { primeStream, withProviderRetry } from './providers/retry.js'
 import { normalizeTextContent, safeJsonParse } from './serviceUtils.js'
+import { addUsage, emptyUsage, extractUsage, toUsagePayload } from './researchTelemetry.js'
+import { buildScreenshotMessage } from './screenshotService.js'
+import { applySnippetsToMessages } from './snippetService.js'
+import { applyGlossaryToMessages } from './spaceGlossaryService.js'
 import { TIME_KEYWORDS_REGEX } from './regexConstants.js'
-import { executeToolByName, getToolDefinitionsByIds, isLocalToolName } from './toolsService.js'
+import { buildTurnLimitEvent, resolveMaxTurns } from './turnLimits.js'
+import { ErrorCode } from '../utils/errors.js'
+import { createTaggedTextParser } from '../utils/taggedText.js'
+import { getToolCallFragments, ToolCallDeltaTracker } from '../utils/toolCallDeltas.js'
+import {
+  executeToolByName,
+  getToolDefinitionsByIds,
+  isLocalToolName,
+  isSourceToolName,
+} from './toolsService.js'
 import { executeCustomTool } from './customToolExecutor.js'
 
 // Debug flags
@@ -24,83 +49,6 @@ const applyContextLimit = (messages, limit) => {
   return [...systemMessages, ...recent]
 }
 
-/**
- * Factory for handleTaggedText function
- */
-/**
- * Factory for handleTaggedText function
- */
-const handleTaggedTextFactory = ({ emitText, emitThought, enableTags = true }) => {
-  let inThoughtBlock = false
-  return text => {
-    // If tag parsing is disabled, just emit everything as text
-    if (!enableTags) {
-      emitText(text)
-      return
-    }
-
-    let remaining = text
-    while (remaining) {
-      if (!inThoughtBlock) {
-        const matchIndex = remaining.search(/<think>|<thought>/i)
-        if (matchIndex === -1) {
-          emitText(remaining)
-          return
-        }
-        emitText(remaining.slice(0, matchIndex))
-        remaining = remaining.slice(matchIndex)
-        const openMatch = remaining.match(/^<(think|thought)>/i)
-        if (openMatch) {
-          remaining = remaining.slice(openMatch[0].length)
-          inThoughtBlock = true
-        } else {
-          emitText(remaining)
-          return
-        }
-      } else {
-        const matchIndex = remaining.search(/<\/think>|<\/thought>/i)
-        if (matchIndex === -1) {
-          emitThought(remaining)
-          return
-        }
-        emitThought(remaining.slice(0, matchIndex))
-        remaining = remaining.slice(matchIndex)
-        const closeMatch = remaining.match(/^<\/(think|thought)>/i)
-        if (closeMatch) {
-          remaining = remaining.slice(closeMatch[0].length)
-          inThoughtBlock = false
-        } else {
-          emitThought(remaining)
-          return
-        }
-      }
-    }
-  }
-}
-
-// NOTE: Legacy provider-specific source collectors (GLM/Kimi) are unused in the refactor.
-// Keeping them commented to avoid dead code until adapters emit provider-native sources again.
-/*
-const collectGLMSources = (webSearch, sourcesMap) => {
-  if (!webSearch) return
-  const results = webSearch.results || webSearch
-  if (!Array.isArray(results)) return
-
-  for (const item of results) {
-    const refer = item?.refer || item?.id || item?.link
-    if (!refer || sourcesMap.has(refer)) continue
-    sourcesMap.set(refer, {
-      id: refer,
-      title: item?.title || refer,
-      url: item?.link || '',
-      snippet: item?.content?.substring(0, 200) || item?.snippet || '',
-      icon: item?.icon || '',
-      media: item?.media || '',
-    })
-  }
-}
-*/
-
 const collectKimiSources = (toolOutput, sourcesMap) => {
   if (!toolOutput) return
   const parsed = typeof toolOutput === 'string' ? safeJsonParse(toolOutput) : toolOutput
@@ -142,25 +90,32 @@ const isSearchToolName = name =>
   name === 'Tavily_academic_search' ||
   name === 'web_search' ||
   name === 'academic_search' ||
-  name === 'search' // Kimi native search tool
-
-// NOTE: Gemini grounding sources are not wired into the adapter path yet.
-// Keeping commented until adapter exposes groundingMetadata.
-/*
-const collectGeminiSources = (groundingMetadata, geminiSources) => {
-  const chunks = groundingMetadata?.groundingChunks
-  if (!Array.isArray(chunks)) return
-  if (!Array.isArray(geminiSources)) return
-  if (geminiSources.length === chunks.length && geminiSources.length > 0) return
-  geminiSources.length = 0
-  for (const chunk of chunks) {
-    const web = chunk?.web
-    const url = web?.uri
-    if (!url) continue
-    geminiSources.push({ url, title: web?.title || url })
+  name === 'Tavily_standards_search' ||
+  name === 'Tavily_legal_search' ||
+  name === 'search' || // Kimi native search tool
+  isSourceToolName(name)
+
+/**
+ * Collect sources found by a provider's built-in search (Gemini grounding, GLM web search)
+ */
+const collectProviderSources = (adapter, messageChunk, sourcesMap) => {
+  for (const source of adapter.extractSources(messageChunk)) {
+    if (!sourcesMap.has(source.url)) {
+      sourcesMap.set(source.url, { title: source.title, uri: source.url })
+    }
   }
 }
-*/
+
+/**
+ * Assistant turn that made tool calls; reasoning_content carries the turn's thinking for
+ * providers that need it back (see supportsInterleavedThinking)
+ */
+const buildAssistantToolCallMessage = (toolCalls, thought) => ({
+  role: 'assistant',
+  content: '',
+  tool_calls: toolCalls,
+  ...(thought ? { reasoning_content: thought } : {}),
+})
 
 /**
  * Build tool call event
@@ -187,8 +142,23 @@ const buildToolResultEvent = (toolCall, error, durationMs, output) => ({
   duration_ms: typeof durationMs === 'number' ? durationMs : undefined,
   output: typeof output !== 'undefined' ? output : undefined,
   error: error ? String(error.message || error) : undefined,
+  code: error ? ErrorCode.ToolFailure : undefined,
 })
 
+/**
+ * Build image events for the images a generate_image call stored
+ */
+const buildImageEvents = (toolCall, result) =>
+  (result?.images || []).map(image => ({
+    type: 'image',
+    tool_call_id: toolCall?.id || null,
+    id: image.id,
+    url: image.url,
+    mime_type: image.mime_type,
+    prompt: image.prompt,
+    revised_prompt: image.revised_prompt,
+  }))
+
 /**
  * Helper: Extract tool call name
  */
@@ -294,20 +264,71 @@ export const streamChat = async function* (params) {
     stream = true,
     signal,
     toolIds = [],
-    searchProvider,
+    searchProvider, // 'tavily' | 'searxng' | 'brave' | 'serper' | 'bing' (see services/search)
+    searchApiKey,
+    searchBaseUrl,
     userId,
     tavilyApiKey,
+    imageProvider, // generate_image backend (default: the chat provider, when it makes images)
+    imageApiKey,
+    imageBaseUrl,
+    imageModel,
     userTimezone,
     userLocale,
+    applyPreferences = true,
+    snippetIds,
+    mcpServers, // Loaded MCP server names (or true for all) whose tools the model may call
+    answerConstraints, // Normalized length/format constraints (see answerConstraintsService)
+    spaceGlossary, // Terminology rules of the request's space (see spaceGlossaryService)
+    maxTurns, // Model turns of the tool loop (default MAX_STREAM_TURNS, see turnLimits)
+    contextCache, // Kimi context cache referenced ahead of the messages (see kimiFiles)
+    conversationId, // Keys the cached conversation memory
+    memoryTokenBudget, // History token budget before older turns are summarized (0 = off)
+    memoryModel, // Model that writes the conversation memory (see conversationMemoryService)
   } = params
 
-  const toolConfig = { searchProvider, tavilyApiKey }
-
-  // Apply context limit
-  const trimmedMessages = applyContextLimit(messages, contextMessageLimit)
+  // generate_image falls back to the chat's own credentials
+  const image = imageProvider
+    ? { provider: imageProvider, apiKey: imageApiKey, baseUrl: imageBaseUrl, model: imageModel }
+    : IMAGE_PROVIDERS.includes(provider)
+      ? { provider, apiKey, baseUrl, model: imageModel }
+      : null
+  const toolConfig = {
+    searchProvider,
+    searchApiKey,
+    searchBaseUrl,
+    tavilyApiKey,
+    image,
+    proofread: { provider, apiKey, baseUrl, model },
+  }
 
   const preExecutionEvents = []
 
+  // Over the memory budget, older turns are summarized into a memory note instead of dropped
+  let contextMessages = messages
+  const memoryBudget = memoryTokenBudget ?? getConversationMemoryConfig().tokenBudget
+  if (memoryBudget > 0) {
+    try {
+      const compacted = await compactConversation(messages, {
+        tokenBudget: memoryBudget,
+        keepMessages: contextMessageLimit,
+        conversationId,
+        summarize: createModelSummarizer({ provider, apiKey, baseUrl, model, memoryModel, signal }),
+      })
+      contextMessages = compacted.messages
+      if (compacted.memory) {
+        preExecutionEvents.push({ type: 'conversation_memory', ...compacted.memory })
+      }
+    } catch (error) {
+      if (signal?.aborted) throw error
+      console.warn('[streamChat] Conversation memory failed:', error.message)
+      preExecutionEvents.push({ type: 'conversation_memory', error: error.message })
+    }
+  }
+
+  // Apply context limit
+  const trimmedMessages = applyContextLimit(contextMessages, contextMessageLimit)
+
   // Check for time-related keywords in the last user message
   const lastUserMessage = trimmedMessages
     .slice()
@@ -362,7 +383,16 @@ export const streamChat = async function* (params) {
   // Inject interactive_form guidance if tool is available
   // Inject interactive_form guidance (GLOBAL TOOL)
 
-  let currentMessages = trimmedMessages
+  // Inject persistent user preferences (language, units, verbosity, citation style)
+  let currentMessages = applyPreferences
+    ? applyPreferencesToMessages(trimmedMessages, userId)
+    : trimmedMessages
+  // Insert saved snippets the user attached as context blocks
+  currentMessages = applySnippetsToMessages(currentMessages, snippetIds)
+  // Requested answer length/format; the route post-checks the final answer against it
+  currentMessages = applyAnswerConstraintsToMessages(currentMessages, answerConstraints)
+  // Space terminology; the route flags avoided/banned terms in the final answer
+  currentMessages = applyGlossaryToMessages(currentMessages, spaceGlossary)
 
   // Get provider adapter
   const adapter = getProviderAdapter(provider)
@@ -440,6 +470,19 @@ export const streamChat = async function* (params) {
     }
   }
 
+  // Expose tools of MCP servers loaded on the backend (POST /api/mcp-tools/servers)
+  if (mcpServers) {
+    const { mcpToolManager } = await import('./mcpToolManager.js')
+    const serverTools = mcpToolManager.listToolsForServers(
+      Array.isArray(mcpServers) ? mcpServers : null,
+    )
+    for (const tool of serverTools) {
+      if (userToolsMap.has(tool.name)) continue
+      userTools = [...userTools, tool]
+      userToolsMap.set(tool.name, tool)
+    }
+  }
+
   // Convert all user tools (HTTP + MCP) to tool definitions
   const userToolDefinitions = userTools.map(tool => {
     const parameters =
@@ -465,7 +508,12 @@ export const streamChat = async function* (params) {
 
   // Prepare tool definitions
   // Always include interactive_form as it is a global tool
-  const agentToolDefinitions = provider === 'gemini' ? [] : getToolDefinitionsByIds(toolIds)
+  // GLM's built-in web search replaces the Tavily web search tool
+  const localToolIds =
+    provider === 'glm' && Array.isArray(toolIds) && toolIds.includes(GLM_NATIVE_SEARCH_TOOL_ID)
+      ? toolIds.filter(id => id !== 'web_search' && id !== 'Tavily_web_search')
+      : toolIds
+  const agentToolDefinitions = provider === 'gemini' ? [] : getToolDefinitionsByIds(localToolIds)
   const combinedTools = [
     ...(Array.isArray(tools) ? tools : []),
     ...agentToolDefinitions,
@@ -544,7 +592,13 @@ If you need to collect information, design ONE comprehensive form that gathers a
   // This ensures they are available across loops and execution types
   let fullContent = ''
   let fullThought = ''
+  // Thought of the current model turn, sent back with its tool calls when the provider keeps
+  // reasoning across tool calls (interleaved thinking)
+  let turnThought = ''
+  const keepsTurnThought = supportsCapability(provider, 'supportsInterleavedThinking')
   const chunks = []
+  // Token usage summed over every model call in the tool loop
+  const usage = emptyUsage()
 
   // Emit helpers
   const emitText = text => {
@@ -555,12 +609,13 @@ If you need to collect information, design ONE comprehensive form that gathers a
   const emitThought = text => {
     if (!text) return
     fullThought += text
+    turnThought += text
     chunks.push({ type: 'thought', content: text })
   }
   // For SiliconFlow/DeepSeek, we rely on native reasoning_content field.
   // We disable tag parsing to avoid confusion if the model outputs tags in the content
   const enableTagParsing = provider !== 'siliconflow'
-  const handleTaggedText = handleTaggedTextFactory({
+  const handleTaggedText = createTaggedTextParser({
     emitText,
     emitThought,
     enableTags: enableTagParsing,
@@ -568,7 +623,7 @@ If you need to collect information, design ONE comprehensive form that gathers a
 
   // Tool calling loop
   let loops = 0
-  const maxLoops = 10
+  const maxLoops = resolveMaxTurns(maxTurns, 'chat')
 
   // Yield pre-execution events (e.g. forced local_time)
   for (const event of preExecutionEvents) {
@@ -577,32 +632,40 @@ If you need to collect information, design ONE comprehensive form that gathers a
 
   while (loops < maxLoops) {
     loops += 1
+    turnThought = ''
 
     if (debugStream()) {
       console.log(`[streamChat] Loop ${loops}, messages count:`, currentMessages.length)
     }
 
-    // Execute via adapter
-    const execution = await adapter.execute(currentMessages, {
-      apiKey,
-      baseUrl,
-      model,
-      temperature,
-      top_k,
-      top_p,
-      frequency_penalty,
-      presence_penalty,
-      tools: normalizedTools,
-      toolChoice: effectiveToolChoice,
-      responseFormat,
-      thinking,
-      stream,
-      signal,
-    })
+    // Execute via adapter (transient provider failures are retried with backoff)
+    const execution = yield* withProviderRetry(
+      () =>
+        adapter.execute(currentMessages, {
+          apiKey,
+          baseUrl,
+          model,
+          temperature,
+          top_k,
+          top_p,
+          frequency_penalty,
+          presence_penalty,
+          tools: normalizedTools,
+          toolChoice: effectiveToolChoice,
+          toolIds, // Gemini and GLM map their native tool ids to built-in tools
+          responseFormat,
+          thinking,
+          contextCache,
+          stream,
+          signal,
+        }),
+      { provider, signal },
+    )
 
     // Handle tool calls (non-streaming)
     if (execution.type === 'tool_calls') {
       const { toolCalls, thought } = execution
+      addUsage(usage, execution.usage)
 
       // Emit thought if present (from non-streaming adapter execution)
       if (thought) {
@@ -619,10 +682,11 @@ If you need to collect information, design ONE comprehensive form that gathers a
       // Add assistant message with tool_calls
       currentMessages = [
         ...currentMessages,
-        { role: 'assistant', content: '', tool_calls: toolCalls },
+        buildAssistantToolCallMessage(toolCalls, keepsTurnThought && turnThought),
       ]
 
       // Execute each tool
+      const screenshots = []
       for (const toolCall of toolCalls) {
         const rawArgs = getToolCallArguments(toolCall)
         // ... rest of loop handled by existing code ...
@@ -675,7 +739,9 @@ If you need to collect information, design ONE comprehensive form that gathers a
             name: toolName,
             content: JSON.stringify(result),
           })
+          if (result?.screenshot?.id) screenshots.push(result.screenshot)
           yield buildToolResultEvent(toolCall, null, Date.now() - startedAt, result)
+          if (toolName === 'generate_image') yield* buildImageEvents(toolCall, result)
         } catch (error) {
           console.error(`Tool execution error (${toolName}):`, error)
           currentMessages.push({
@@ -688,6 +754,10 @@ If you need to collect information, design ONE comprehensive form that gathers a
         }
       }
 
+      // Vision models get captured page screenshots as images after the tool results
+      const screenshotMessage = isVisionModel(model) ? buildScreenshotMessage(screenshots) : null
+      if (screenshotMessage) currentMessages.push(screenshotMessage)
+
       // Continue loop with tool results
       continue
     }
@@ -695,6 +765,8 @@ If you need to collect information, design ONE comprehensive form that gathers a
     // Handle non-streaming response (final answer from provider that forced non-streaming)
     if (execution.type === 'response' || execution.type === 'no_tool_calls') {
       const response = execution.response
+      addUsage(usage, execution.usage ?? extractUsage(response))
+      collectProviderSources(adapter, response, sourcesMap)
       const content = adapter.getResponseContent
         ? adapter.getResponseContent(response)
         : response?.content || ''
@@ -709,6 +781,7 @@ If you need to collect information, design ONE comprehensive form that gathers a
 
       if (content) {
         handleTaggedText(content)
+        handleTaggedText.flush()
       }
 
       // Flush chunks
@@ -724,6 +797,7 @@ If you need to collect information, design ONE comprehensive form that gathers a
         content: fullContent,
         thought: fullThought || undefined,
         sources: sourcesMap.size ? Array.from(sourcesMap.values()) : undefined,
+        usage: toUsagePayload(usage),
       }
       return
     }
@@ -732,22 +806,27 @@ If you need to collect information, design ONE comprehensive form that gathers a
     if (execution.type === 'stream') {
       const { modelInstance, messages: executionMessages } = execution
 
-      const streamIterator = await adapter.createStreamIterator(
-        modelInstance,
-        currentMessages,
-        signal,
+      const streamIterator = yield* withProviderRetry(
+        () => primeStream(adapter.createStreamIterator(modelInstance, currentMessages, signal)),
+        { provider, signal },
       )
 
       // Restart tool accumulation for this new stream
       const toolCallsMap = new Map()
       const toolCallsByIndex = []
+      const deltaTracker = new ToolCallDeltaTracker()
 
       let lastFinishReason = null
+      // Providers report usage on the final chunk (cumulative), so keep the latest one
+      let streamUsage = null
+      // Provider-executed tool call awaiting its result (Gemini code execution)
+      let nativeToolCall = null
 
       // Process streaming chunks
       for await (const chunk of streamIterator) {
         const messageChunk = chunk?.message ?? chunk
         const contentValue = messageChunk?.content ?? chunk?.content
+        streamUsage = extractUsage(messageChunk) || streamUsage
 
         // 1. Process reasoning/thinking content using adapter
         const reasoning = adapter.extractThinkingContent(messageChunk)
@@ -768,6 +847,33 @@ If you need to collect information, design ONE comprehensive form that gathers a
           handleTaggedText(chunkText)
         }
 
+        // 2b. Provider-side search and tools: grounding sources, code run by the provider
+        collectProviderSources(adapter, messageChunk, sourcesMap)
+        for (const nativeEvent of adapter.extractNativeToolEvents(messageChunk)) {
+          const { name, arguments: args, output } = nativeEvent
+          if (nativeEvent.kind === 'call') {
+            nativeToolCall = {
+              id: `${name}-${Date.now()}`,
+              function: { name, arguments: JSON.stringify(args) },
+              textIndex: fullContent.length,
+              startedAt: Date.now(),
+            }
+            chunks.push(buildToolCallEvent(nativeToolCall, args))
+            continue
+          }
+          const toolCall = nativeToolCall || { id: null, function: { name } }
+          const failed = output?.outcome && output.outcome !== 'OUTCOME_OK'
+          chunks.push(
+            buildToolResultEvent(
+              toolCall,
+              failed ? new Error(output.output || output.outcome) : null,
+              toolCall.startedAt ? Date.now() - toolCall.startedAt : undefined,
+              output,
+            ),
+          )
+          nativeToolCall = null
+        }
+
         // 3. Collect tool_calls from streaming chunks
         const toolCalls =
           messageChunk?.tool_calls ||
@@ -788,6 +894,13 @@ If you need to collect information, design ONE comprehensive form that gathers a
           updateToolCallsMap(toolCallsMap, rawToolCalls)
         }
 
+        // 5. Announce argument fragments as they arrive (the full tool_call follows on finish)
+        chunks.push(
+          ...deltaTracker.push(getToolCallFragments(messageChunk), {
+            textIndex: fullContent.length,
+          }),
+        )
+
         // Yield accumulated chunks and track thought content
         while (chunks.length > 0) {
           yield chunks.shift()
@@ -803,8 +916,10 @@ If you need to collect information, design ONE comprehensive form that gathers a
         }
       }
 
+      addUsage(usage, streamUsage)
+
       // Flush any buffered content
-      handleTaggedText('')
+      handleTaggedText.flush()
       while (chunks.length > 0) {
         yield chunks.shift()
       }
@@ -829,13 +944,15 @@ If you need to collect information, design ONE comprehensive form that gathers a
         if (assistantToolCalls.length > 0) {
           // Add assistant message with tool_calls
           // Note: content should be empty when tool_calls are present
-          // to avoid sending thinking content back to the model
+          // to avoid sending thinking content back to the model (providers with interleaved
+          // thinking get it through reasoning_content instead)
           currentMessages = [
             ...currentMessages,
-            { role: 'assistant', content: '', tool_calls: assistantToolCalls },
+            buildAssistantToolCallMessage(assistantToolCalls, keepsTurnThought && turnThought),
           ]
 
           // Execute tools
+          const screenshots = []
           for (const toolCall of assistantToolCalls) {
             const rawArgs = getToolCallArguments(toolCall)
             const parsedArgs = typeof rawArgs === 'string' ? safeJsonParse(rawArgs) : rawArgs || {}
@@ -886,7 +1003,9 @@ If you need to collect information, design ONE comprehensive form that gathers a
                 name: toolName,
                 content: JSON.stringify(result),
               })
+              if (result?.screenshot?.id) screenshots.push(result.screenshot)
               yield buildToolResultEvent(toolCall, null, Date.now() - startedAt, result)
+              if (toolName === 'generate_image') yield* buildImageEvents(toolCall, result)
             } catch (error) {
               console.error(`Tool execution error (${toolName}):`, error)
               currentMessages.push({
@@ -899,6 +1018,11 @@ If you need to collect information, design ONE comprehensive form that gathers a
             }
           }
 
+          const screenshotMessage = isVisionModel(model)
+            ? buildScreenshotMessage(screenshots)
+            : null
+          if (screenshotMessage) currentMessages.push(screenshotMessage)
+
           // Continue loop with tool results
           continue
         }
@@ -910,6 +1034,7 @@ If you need to collect information, design ONE comprehensive form that gathers a
         content: fullContent,
         thought: fullThought || undefined,
         sources: sourcesMap.size ? Array.from(sourcesMap.values()) : undefined,
+        usage: toUsagePayload(usage),
       }
       return
     }
@@ -918,10 +1043,13 @@ If you need to collect information, design ONE comprehensive form that gathers a
     break
   }
 
-  // Max loops reached
+  // Max loops reached: tell the client why the tool chain stopped, keep what was written so far
+  if (loops >= maxLoops) yield buildTurnLimitEvent(maxLoops)
   yield {
     type: 'done',
-    content: '',
+    content: fullContent,
+    thought: fullThought || undefined,
     sources: sourcesMap.size ? Array.from(sourcesMap.values()) : undefined,
+    usage: toUsagePayload(usage),
   }
 }
@@ -0,0 +1,99 @@
+/**
+ * Stream registry
+ * Tracks in-flight SSE streams (chat and deep research) by stream id so a client can cancel a
+ * running generation through the API instead of relying on the connection being closed. Streams
+ * started with a session id (one per desktop window, see sessionRegistry) can be listed and
+ * cancelled per session.
+ */
+
+import { randomUUID } from 'crypto'
+
+const MAX_STREAM_ID_LENGTH = 128
+
+const normalizeStreamId = streamId =>
+  streamId ? String(streamId).slice(0, MAX_STREAM_ID_LENGTH) : ''
+
+export const normalizeSessionId = normalizeStreamId
+
+class StreamRegistry {
+  constructor() {
+    // Running streams by id: { controller, kind, sessionId, startedAt, cancelled }
+    this.streams = new Map()
+  }
+
+  has(streamId) {
+    return this.streams.has(normalizeStreamId(streamId))
+  }
+
+  /**
+   * Register a stream's abort controller
+   * @param {Object} args
+   * @param {string} [args.streamId] Client-chosen id; generated when omitted
+   * @param {AbortController} args.controller Aborted on cancel
+   * @param {string} args.kind 'chat' | 'deep_research'
+   * @param {string} [args.sessionId] Session (window) that started the stream
+   * @returns {string} The stream id
+   */
+  register({ streamId, controller, kind, sessionId }) {
+    const id = normalizeStreamId(streamId) || randomUUID()
+    this.streams.set(id, {
+      controller,
+      kind,
+      sessionId: normalizeSessionId(sessionId) || null,
+      startedAt: Date.now(),
+      cancelled: false,
+    })
+    return id
+  }
+
+  unregister(streamId) {
+    this.streams.delete(streamId)
+  }
+
+  /**
+   * Abort a running stream
+   * @returns {boolean} false when no stream with this id is running
+   */
+  cancel(streamId) {
+    const entry = this.streams.get(normalizeStreamId(streamId))
+    if (!entry) return false
+    entry.cancelled = true
+    entry.controller.abort()
+    return true
+  }
+
+  /**
+   * Abort every running stream of a session
+   * @returns {string[]} Ids of the streams cancelled
+   */
+  cancelSession(sessionId) {
+    const cancelled = this.list({ sessionId })
+      .filter(stream => !stream.cancelled)
+      .map(stream => stream.stream_id)
+    cancelled.forEach(id => this.cancel(id))
+    return cancelled
+  }
+
+  isCancelled(streamId) {
+    return Boolean(this.streams.get(streamId)?.cancelled)
+  }
+
+  /**
+   * @param {Object} [filter]
+   * @param {string} [filter.sessionId] Only streams of this session
+   */
+  list({ sessionId } = {}) {
+    const session = sessionId ? normalizeSessionId(sessionId) : null
+    return Array.from(this.streams.entries())
+      .filter(([, entry]) => !session || entry.sessionId === session)
+      .map(([id, entry]) => ({
+        stream_id: id,
+        kind: entry.kind,
+        session_id: entry.sessionId,
+        started_at: new Date(entry.startedAt).toISOString(),
+        cancelled: entry.cancelled,
+      }))
+  }
+}
+
+export const streamRegistry = new StreamRegistry()
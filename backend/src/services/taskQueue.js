@@ -0,0 +1,224 @@
+/**
+ * Task queue
+ * One-off background work (site ingestion, watchlist runs, upload assembly) that outlives the
+ * request starting it. Owning services register a handler per task kind at import time and
+ * enqueue tasks; /api/tasks lists them with their progress and cancels them.
+ * Task records are persisted in the "tasks" data collection so finished work stays visible across
+ * restarts. Payloads (which may carry API keys) only live in memory, so tasks that were queued or
+ * running when the process stopped are marked "interrupted" instead of being resumed.
+ */
+
+import { randomUUID } from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+
+const COLLECTION = 'tasks'
+const DEFAULT_CONCURRENCY = 2
+const MAX_FINISHED_TASKS = 200
+// Progress updates are kept in memory and written at most this often
+const PROGRESS_WRITE_INTERVAL_MS = 1000
+
+const ACTIVE_STATUSES = new Set(['queued', 'running'])
+
+export const getTaskQueueConfig = () => {
+  const concurrency = Number.parseInt(process.env.TASK_CONCURRENCY, 10)
+  return { concurrency: concurrency > 0 ? concurrency : DEFAULT_CONCURRENCY }
+}
+
+const now = () => new Date().toISOString()
+
+class TaskQueue {
+  constructor() {
+    // Handlers by task kind: async (payload, { signal, progress }) => result
+    this.handlers = new Map()
+
+    // Task records by id (queued, running, and recently loaded finished tasks)
+    this.tasks = new Map()
+
+    // In-memory state of unfinished tasks by id: { payload, controller, waiters, lastWriteAt }
+    this.runtime = new Map()
+
+    this.pending = []
+    this.active = 0
+    this.loaded = false
+  }
+
+  /**
+   * Register the handler of a task kind
+   * @param {string} kind
+   * @param {Function} handler - async (payload, { signal, progress }) => result; progress takes
+   *   { current, total, message } (any subset)
+   */
+  registerHandler(kind, handler) {
+    if (!kind || typeof handler !== 'function') {
+      throw new Error('Task handler requires a kind and a function')
+    }
+    this.handlers.set(kind, handler)
+  }
+
+  // Records of a previous process: unfinished ones can no longer run
+  load() {
+    if (this.loaded) return
+    this.loaded = true
+    for (const task of listRecords(COLLECTION)) {
+      if (ACTIVE_STATUSES.has(task.status)) {
+        Object.assign(task, { status: 'interrupted', finished_at: task.finished_at || now() })
+        writeRecord(COLLECTION, task.id, task)
+      }
+      this.tasks.set(task.id, task)
+    }
+  }
+
+  save(task) {
+    writeRecord(COLLECTION, task.id, task)
+    const state = this.runtime.get(task.id)
+    if (state) state.lastWriteAt = Date.now()
+  }
+
+  /**
+   * Queue a task
+   * @param {string} kind - A registered task kind
+   * @param {Object} [payload] - Passed to the handler; never persisted
+   * @param {Object} [options]
+   * @param {string} [options.title] - Shown in task lists
+   * @param {string} [options.dedupeKey] - Return the unfinished task with this key instead of
+   *   queueing a second one
+   * @returns {Object} Task record
+   */
+  enqueue(kind, payload = {}, { title, dedupeKey } = {}) {
+    this.load()
+    if (!this.handlers.has(kind)) throw new Error(`Unknown task kind: ${kind}`)
+    if (dedupeKey) {
+      const existing = Array.from(this.tasks.values()).find(
+        task => task.dedupe_key === dedupeKey && ACTIVE_STATUSES.has(task.status),
+      )
+      if (existing) return existing
+    }
+    const task = {
+      id: randomUUID(),
+      kind,
+      title: title || kind,
+      dedupe_key: dedupeKey || null,
+      status: 'queued',
+      progress: null,
+      result: null,
+      error: null,
+      created_at: now(),
+      started_at: null,
+      finished_at: null,
+    }
+    this.tasks.set(task.id, task)
+    this.runtime.set(task.id, {
+      payload,
+      controller: new AbortController(),
+      waiters: [],
+      lastWriteAt: 0,
+    })
+    this.save(task)
+    this.pending.push(task.id)
+    this.prune()
+    this.drain()
+    return task
+  }
+
+  drain() {
+    const { concurrency } = getTaskQueueConfig()
+    while (this.active < concurrency && this.pending.length) {
+      const task = this.tasks.get(this.pending.shift())
+      if (task?.status !== 'queued') continue
+      this.active += 1
+      this.run(task).finally(() => {
+        this.active -= 1
+        this.drain()
+      })
+    }
+  }
+
+  async run(task) {
+    const state = this.runtime.get(task.id)
+    const { signal } = state.controller
+    Object.assign(task, { status: 'running', started_at: now() })
+    this.save(task)
+
+    const progress = update => {
+      if (task.status !== 'running') return
+      task.progress = { ...task.progress, ...update, updated_at: now() }
+      if (Date.now() - state.lastWriteAt >= PROGRESS_WRITE_INTERVAL_MS) this.save(task)
+    }
+
+    try {
+      const result = await this.handlers.get(task.kind)(state.payload, { signal, progress })
+      if (signal.aborted) throw new Error('Task cancelled')
+      Object.assign(task, { status: 'done', result: result ?? null })
+    } catch (error) {
+      Object.assign(task, {
+        status: signal.aborted ? 'cancelled' : 'error',
+        error: signal.aborted ? null : error.message,
+      })
+      if (!signal.aborted) console.warn(`[Tasks] ${task.kind} ${task.id} failed:`, error.message)
+    } finally {
+      task.finished_at = now()
+      this.save(task)
+      this.runtime.delete(task.id)
+      for (const resolve of state.waiters) resolve(task)
+    }
+  }
+
+  get(id) {
+    this.load()
+    return this.tasks.get(id) || readRecord(COLLECTION, id)
+  }
+
+  /**
+   * Tasks, newest first
+   * @param {Object} [filters]
+   * @param {string} [filters.status]
+   * @param {string} [filters.kind]
+   */
+  list({ status, kind } = {}) {
+    this.load()
+    return Array.from(this.tasks.values())
+      .filter(task => (!status || task.status === status) && (!kind || task.kind === kind))
+      .sort((a, b) => String(b.created_at).localeCompare(String(a.created_at)))
+  }
+
+  /**
+   * Cancel a queued or running task; running handlers see their signal aborted
+   * @returns {Object|null} The task, or null when it does not exist
+   */
+  cancel(id) {
+    const task = this.get(id)
+    if (!task || !ACTIVE_STATUSES.has(task.status)) return task || null
+    const state = this.runtime.get(id)
+    if (task.status === 'queued') {
+      Object.assign(task, { status: 'cancelled', finished_at: now() })
+      this.save(task)
+      this.runtime.delete(id)
+      for (const resolve of state?.waiters || []) resolve(task)
+      return task
+    }
+    task.cancel_requested = true
+    state?.controller.abort()
+    return task
+  }
+
+  /**
+   * Resolve with the task once it finished
+   */
+  wait(id) {
+    const task = this.get(id)
+    const state = this.runtime.get(id)
+    if (!task || !state) return Promise.resolve(task || null)
+    return new Promise(resolve => state.waiters.push(resolve))
+  }
+
+  // Keep the newest finished tasks only
+  prune() {
+    const finished = this.list().filter(task => !ACTIVE_STATUSES.has(task.status))
+    for (const task of finished.slice(MAX_FINISHED_TASKS)) {
+      this.tasks.delete(task.id)
+      deleteRecord(COLLECTION, task.id)
+    }
+  }
+}
+
+export const taskQueue = new TaskQueue()
@@ -0,0 +1,69 @@
+/**
+ * Text edit service
+ * Streams a model revision of user-provided text as tracked-change diff operations.
+ */
+
+import { StreamingDiff } from '../utils/streamingDiff.js'
+import { streamChat } from './streamChatService.js'
+
+const buildEditMessages = ({ text, instruction }) => [
+  {
+    role: 'system',
+    content: `You are a careful editor. Apply the user's instruction to the text.
+Rules:
+- Output ONLY the full revised text, with no preamble, quotes, or code fences.
+- Change only what the instruction requires; keep every other character (including whitespace and line breaks) exactly as it is.
+- Keep the original language unless the instruction says otherwise.`,
+  },
+  {
+    role: 'user',
+    content: `Instruction: ${instruction || 'Improve clarity and fix grammar.'}
+
+Text:
+${text}`,
+  },
+]
+
+/**
+ * Stream an edit as diff events
+ * Yields: {type:"diff", ops:[{op:"equal"|"insert"|"delete", text, position}]}, then done/error
+ */
+export const streamTextEdit = async function* (params) {
+  const { text, instruction } = params
+  const diff = new StreamingDiff(text)
+  const counts = { insert: 0, delete: 0 }
+  const countOps = ops => {
+    for (const op of ops) {
+      if (op.op !== 'equal') counts[op.op] += 1
+    }
+    return ops
+  }
+
+  for await (const event of streamChat({
+    ...params,
+    messages: buildEditMessages({ text, instruction }),
+    toolIds: [],
+    tools: [],
+    applyPreferences: false,
+    thinking:
+      params.provider === 'glm' || params.provider === 'modelscope'
+        ? { type: 'disabled' }
+        : params.thinking,
+  })) {
+    if (event.type === 'text') {
+      const ops = countOps(diff.push(event.content || ''))
+      if (ops.length) yield { type: 'diff', ops }
+    } else if (event.type === 'done') {
+      const ops = countOps(diff.finish())
+      if (ops.length) yield { type: 'diff', ops }
+      yield {
+        type: 'done',
+        content: diff.revised,
+        original: text,
+        changes: counts,
+      }
+    } else if (event.type === 'error') {
+      yield event
+    }
+  }
+}
@@ -0,0 +1,118 @@
+/**
+ * Timeline service
+ * Extracts dated events from research findings so history answers can be rendered as a timeline.
+ */
+
+import { completeJson } from './modelCompletion.js'
+
+const MAX_TIMELINE_EVENTS = 40
+const MAX_FINDINGS_CHARS = 24000
+
+export const isTimelineQuestion = planMeta => planMeta?.question_type === 'history'
+
+/**
+ * Sortable key for partial dates: "1969", "1969-07", "1969-07-20", "-500" / "500 BC"
+ */
+export const toDateSortKey = value => {
+  const text = String(value ?? '').trim()
+  const match = text.match(/^(-?\d{1,6})(?:-(\d{1,2}))?(?:-(\d{1,2}))?/)
+  if (!match) return null
+  let year = Number.parseInt(match[1], 10)
+  if (/\b(BC|BCE)\b/i.test(text) && year > 0) year = -year
+  const month = match[2] ? Number.parseInt(match[2], 10) : 0
+  const day = match[3] ? Number.parseInt(match[3], 10) : 0
+  return year * 10000 + month * 100 + day
+}
+
+const buildTimelineMessages = ({ question, findings, sourcesList }) => [
+  {
+    role: 'system',
+    content: `You extract timelines from research notes.
+Rules:
+- List the key dated events relevant to the question, at most ${MAX_TIMELINE_EVENTS}.
+- "date" is machine-readable: "YYYY", "YYYY-MM", or "YYYY-MM-DD"; use a negative year for BCE (e.g. "-44").
+- "date_label" is how a reader would write the date (e.g. "March 44 BC", "Early 1990s").
+- "title" is a short headline; "description" is one sentence, in the notes' language.
+- "sources" lists numbers from the source list that support the event. Never invent numbers.
+- Only include events stated in the notes; do not add outside knowledge.
+
+Return ONLY JSON: {"events": [{"date": "...", "date_label": "...", "title": "...", "description": "...", "sources": [1]}]}`,
+  },
+  {
+    role: 'user',
+    content: `Question: ${question || '(none)'}
+
+Source list:
+${sourcesList.length ? sourcesList.join('\n') : 'No sources available.'}
+
+Research notes:
+${findings.join('\n\n').slice(0, MAX_FINDINGS_CHARS)}`,
+  },
+]
+
+export const normalizeTimeline = (parsed, sourceCount) => {
+  const list = Array.isArray(parsed) ? parsed : parsed?.events || parsed?.timeline
+  if (!Array.isArray(list)) return []
+  return list
+    .filter(item => item && typeof item.title === 'string' && item.title.trim())
+    .map(item => {
+      const date = String(item.date ?? '').trim()
+      return {
+        date,
+        date_label: String(item.date_label || date).trim(),
+        title: item.title.trim(),
+        description: typeof item.description === 'string' ? item.description.trim() : '',
+        sources: (Array.isArray(item.sources) ? item.sources : [])
+          .map(value => Number.parseInt(value, 10))
+          .filter(value => Number.isFinite(value) && value >= 1 && value <= sourceCount),
+        sort_key: toDateSortKey(date),
+      }
+    })
+    .filter(item => item.sort_key !== null)
+    .sort((a, b) => a.sort_key - b.sort_key)
+    .slice(0, MAX_TIMELINE_EVENTS)
+}
+
+/**
+ * Extract timeline events from findings
+ * @returns {Promise<Array<{date, date_label, title, description, sources, sort_key}>>}
+ */
+export const extractTimeline = async ({
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  question,
+  findings = [],
+  sourcesList = [],
+  signal,
+}) => {
+  if (!findings.length) return []
+  try {
+    const parsed = await completeJson({
+      provider,
+      apiKey,
+      baseUrl,
+      model,
+      messages: buildTimelineMessages({ question, findings, sourcesList }),
+      temperature: 0,
+      signal,
+    })
+    return normalizeTimeline(parsed, sourcesList.length)
+  } catch (error) {
+    if (signal?.aborted) throw error
+    console.warn('[Timeline] Timeline extraction failed:', error.message)
+    return []
+  }
+}
+
+/**
+ * Compact timeline for the report prompt, so prose and timeline agree
+ */
+export const formatTimelineForPrompt = events =>
+  events
+    .map(event => {
+      const citations = event.sources.map(index => `[${index}]`).join('')
+      return `- ${event.date_label}: ${event.title}${citations ? ` ${citations}` : ''}`
+    })
+    .join('\n')
@@ -4,12 +4,18 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { ChatGoogleGenerativeAI } from '@langchain/google-genai'
+import { renderSystemPrompt } from '../prompts/promptStore.js'
 import {
   normalizeGeminiMessages,
   normalizeTextContent,
   safeJsonParse,
   toLangChainMessages,
 } from './serviceUtils.js'
+import {
+  buildEmojiInstructions,
+  finalizeTitle,
+  parseTitleEmojis,
+} from './titleEmojiService.js'
 
 // Default base URLs
 const OPENAI_DEFAULT_BASE = 'https://api.openai.com/v1'
@@ -452,6 +458,7 @@ const requestOpenAICompat = async ({
 
 /**
  * Generate title and select space
+ * options.emojis: false asks for no emoji and returns an empty emojis array
  */
 export const generateTitleAndSpace = async (
   provider,
@@ -460,19 +467,16 @@ export const generateTitleAndSpace = async (
   apiKey,
   baseUrl,
   model,
+  { emojis: withEmojis = true } = {},
 ) => {
   const spaceLabels = (spaces || []).map(s => s.label).join(', ')
   const promptMessages = [
     {
       role: 'system',
-      content: `You are a helpful assistant.
-## Task
-1. Generate a short, concise title (max 5 words) for this conversation based on the user's first message.
-2. Select the most appropriate space from the following list: [${spaceLabels}]. If none fit well, return null.
-3. Select 1 emoji that best matches the conversation.
-
-## Output
-Return the result as a JSON object with keys "title", "spaceLabel", and "emojis".`,
+      content: renderSystemPrompt('title_and_space', {
+        spaces: spaceLabels,
+        emoji_instructions: buildEmojiInstructions(withEmojis),
+      }),
     },
     { role: 'user', content: firstMessage },
   ]
@@ -521,14 +525,9 @@ Return the result as a JSON object with keys "title", "spaceLabel", and "emojis"
   }
   const parsed = safeJsonParse(content) || {}
   const rawTitle = typeof content === 'string' ? content.trim() : ''
-  const title = parsed.title || rawTitle || 'New Conversation'
+  const title = finalizeTitle(parsed.title || rawTitle, withEmojis) || 'New Conversation'
   const spaceLabel = parsed.spaceLabel
   const selectedSpace = (spaces || []).find(s => s.label === spaceLabel) || null
-  const emojis = Array.isArray(parsed.emojis)
-    ? parsed.emojis
-        .map(item => String(item || '').trim())
-        .filter(Boolean)
-        .slice(0, 1)
-    : []
+  const emojis = parseTitleEmojis(parsed.emojis, withEmojis)
   return { title, space: selectedSpace, emojis }
 }
@@ -0,0 +1,46 @@
+/**
+ * Title emoji service
+ * Whether the title endpoints pick an emoji: the request's "emojis" flag, else the titleEmojis
+ * preference (on unless set to "off"). With emoji off the prompts ask for none, and whatever the
+ * model returns anyway is dropped, so clients always get an empty emojis array.
+ */
+
+import { getPreferences } from './preferencesService.js'
+import { EMOJI_PATTERN } from './regexConstants.js'
+
+const EMOJI_ON =
+  'Select 1 emoji that best matches the conversation. "emojis" must be an array with 1 emoji character.'
+const EMOJI_OFF =
+  'Do not use emoji: "emojis" must be an empty array and the title must not contain emoji.'
+
+/**
+ * @param {boolean|undefined} override - Per-request flag; undefined falls back to the preference
+ * @param {string} [userId]
+ * @returns {boolean}
+ */
+export const resolveTitleEmojis = (override, userId) =>
+  typeof override === 'boolean' ? override : getPreferences(userId).titleEmojis !== 'off'
+
+/**
+ * Value of the {{emoji_instructions}} prompt variable
+ */
+export const buildEmojiInstructions = enabled => (enabled ? EMOJI_ON : EMOJI_OFF)
+
+/**
+ * The model's "emojis" value as at most one emoji, or none when emoji are off
+ */
+export const parseTitleEmojis = (value, enabled) => {
+  if (!enabled || !Array.isArray(value)) return []
+  return value
+    .map(item => String(item || '').trim())
+    .filter(Boolean)
+    .slice(0, 1)
+}
+
+/**
+ * Drop emoji the model put in the title despite the instructions
+ */
+export const finalizeTitle = (title, enabled) => {
+  if (enabled || typeof title !== 'string') return title
+  return title.replace(EMOJI_PATTERN, '').replace(/\s{2,}/g, ' ').trim()
+}
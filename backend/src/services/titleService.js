@@ -4,12 +4,18 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { ChatGoogleGenerativeAI } from '@langchain/google-genai'
+import { renderSystemPrompt } from '../prompts/promptStore.js'
 import {
   normalizeGeminiMessages,
   normalizeTextContent,
   toLangChainMessages,
   safeJsonParse,
 } from './serviceUtils.js'
+import {
+  buildEmojiInstructions,
+  finalizeTitle,
+  parseTitleEmojis,
+} from './titleEmojiService.js'
 
 // Default base URLs
 const OPENAI_DEFAULT_BASE = 'https://api.openai.com/v1'
@@ -457,18 +463,24 @@ const requestOpenAICompat = async ({
  * @param {string} apiKey - API key for authentication
  * @param {string} baseUrl - Custom base URL
  * @param {string} model - Model name/ID
+ * @param {Object} [options]
+ * @param {boolean} [options.emojis=true] - Pick an emoji; false returns an empty emojis array
  * @returns {Promise<string>} - Generated conversation title
  */
-export const generateTitle = async (provider, firstMessage, apiKey, baseUrl, model) => {
+export const generateTitle = async (
+  provider,
+  firstMessage,
+  apiKey,
+  baseUrl,
+  model,
+  { emojis: withEmojis = true } = {},
+) => {
   const promptMessages = [
     {
       role: 'system',
-      content: `## Task
-Generate a short, concise title (max 5 words) for this conversation based on the user's first message. Do not use quotes.
-Select 1 emoji that best matches the conversation.
-
-## Output
-Return JSON with keys "title" and "emojis". "emojis" must be an array with 1 emoji character.`,
+      content: renderSystemPrompt('title', {
+        emoji_instructions: buildEmojiInstructions(withEmojis),
+      }),
     },
     { role: 'user', content: firstMessage },
   ]
@@ -519,14 +531,9 @@ Return JSON with keys "title" and "emojis". "emojis" must be an array with 1 emo
   const rawTitle = typeof content === 'string' ? content.trim() : ''
   const title =
     typeof parsed.title === 'string' && parsed.title.trim() ? parsed.title.trim() : rawTitle
-  const emojis = Array.isArray(parsed.emojis)
-    ? parsed.emojis
-        .map(item => String(item || '').trim())
-        .filter(Boolean)
-        .slice(0, 1)
-    : []
+  const emojis = parseTitleEmojis(parsed.emojis, withEmojis)
   return {
-    title: title || 'New Conversation',
+    title: finalizeTitle(title, withEmojis) || 'New Conversation',
     emojis,
   }
 }
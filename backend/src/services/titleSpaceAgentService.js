@@ -5,12 +5,18 @@
 
 import { ChatOpenAI } from '@langchain/openai'
 import { ChatGoogleGenerativeAI } from '@langchain/google-genai'
+import { renderSystemPrompt } from '../prompts/promptStore.js'
 import {
   normalizeGeminiMessages,
   normalizeTextContent,
   safeJsonParse,
   toLangChainMessages,
 } from './serviceUtils.js'
+import {
+  buildEmojiInstructions,
+  finalizeTitle,
+  parseTitleEmojis,
+} from './titleEmojiService.js'
 
 // Default base URLs
 const OPENAI_DEFAULT_BASE = 'https://api.openai.com/v1'
@@ -468,6 +474,7 @@ const requestOpenAICompat = async ({
 /**
  * Generate title, select space, and optionally select agent
  * Direct port from frontend generateTitleSpaceAndAgent
+ * options.emojis: false asks for no emoji and returns an empty emojis array
  */
 export const generateTitleSpaceAndAgent = async (
   provider,
@@ -476,6 +483,7 @@ export const generateTitleSpaceAndAgent = async (
   apiKey,
   baseUrl,
   model,
+  { emojis: withEmojis = true } = {},
 ) => {
   const spaceLines = (spacesWithAgents || []).map(space => {
     const agentEntries = (space.agents || []).map(agent => {
@@ -506,15 +514,9 @@ export const generateTitleSpaceAndAgent = async (
   const promptMessages = [
     {
       role: 'system',
-      content: `You are a helpful assistant.
-## Task
-1. Generate a short, concise title (max 5 words) for this conversation based on the user's first message.
-2. Select the most appropriate space from the list below and return its spaceLabel (the space name only, without the description).
-3. If the chosen space has agents, select the best matching agent by agentName (agent name only). Otherwise return null.
-4. Select 1 emoji that best matches the conversation.
-
-## Output
-Return the result as JSON with keys "title", "spaceLabel", "agentName", and "emojis". "emojis" must be an array with 1 emoji character.`,
+      content: renderSystemPrompt('title_space_agent', {
+        emoji_instructions: buildEmojiInstructions(withEmojis),
+      }),
     },
     {
       role: 'user',
@@ -568,14 +570,9 @@ Return the result as JSON with keys "title", "spaceLabel", "agentName", and "emo
 
   const parsed = safeJsonParse(content) || {}
   const rawTitle = typeof content === 'string' ? content.trim() : ''
-  const emojis = Array.isArray(parsed.emojis)
-    ? parsed.emojis
-        .map(item => String(item || '').trim())
-        .filter(Boolean)
-        .slice(0, 1)
-    : []
+  const emojis = parseTitleEmojis(parsed.emojis, withEmojis)
   return {
-    title: parsed.title || rawTitle || 'New Conversation',
+    title: finalizeTitle(parsed.title || rawTitle, withEmojis) || 'New Conversation',
     spaceLabel: parsed.spaceLabel || null,
     agentName: parsed.agentName || null,
     emojis,
@@ -2,6 +2,30 @@ import { jsonrepair } from 'jsonrepair'
 import { all, create } from 'mathjs'
 import { z } from 'zod'
 import { ACADEMIC_DOMAINS } from './academicDomains.js'
+import { ARXIV_SORT_ORDERS, searchArxiv, searchSemanticScholar } from './academicSearchService.js'
+import { searchCode } from './codeIndexService.js'
+import { GITHUB_ACTIONS, queryGithub } from './githubService.js'
+import { performHttpRequest } from './httpRequestService.js'
+import { generateImages, IMAGE_PROVIDERS, MAX_IMAGES_PER_REQUEST } from './images/index.js'
+import { lookupPackage, PACKAGE_ECOSYSTEMS } from './packageRegistryService.js'
+import { searchPatents } from './patentService.js'
+import { PUBMED_STUDY_FILTERS, searchPubmed } from './pubmedService.js'
+import { searchDocuments } from './ragStore.js'
+import { LEGAL_DOMAINS } from './legalDomains.js'
+import { proofreadText } from './proofreadService.js'
+import { fetchRfc } from './rfcService.js'
+import { queryRepository, REPO_ACTIONS } from './repoContextService.js'
+import { getUnavailableToolIds } from './runtimeCapabilities.js'
+import { captureScreenshot, toScreenshotRef } from './screenshotService.js'
+import { webSearch } from './search/index.js'
+import { exploreSite } from './siteExplorerService.js'
+import { createSnippet, searchSnippets } from './snippetService.js'
+import { searchStackExchange } from './stackExchangeService.js'
+import { STANDARDS_DOMAINS } from './standardsDomains.js'
+import { getSqlSchema, runReadOnlyQuery } from './sqlConnectorService.js'
+import { sanitizeTraceValue, withSpan } from './tracing.js'
+import { readArchivedPage } from './waybackService.js'
+import { readWebpage } from './webpageReaderService.js'
 
 const math = create(all, {})
 
@@ -12,15 +36,34 @@ const TOOL_ALIASES = {
 
 const resolveToolName = toolName => TOOL_ALIASES[toolName] || toolName
 
-const resolveTavilyApiKey = toolConfig => {
-  // Priority: User settings (Supabase) > Environment variables
-  if (toolConfig?.tavilyApiKey) return toolConfig.tavilyApiKey
-  if (toolConfig?.searchProvider === 'tavily' && toolConfig?.searchApiKey) {
-    return toolConfig.searchApiKey
+// Lookup tools whose `results: [{title, url, content}]` are collected as citable sources
+const SOURCE_TOOL_NAMES = new Set([
+  'package_lookup',
+  'github',
+  'stackexchange_search',
+  'rfc_fetch',
+  'patent_search',
+  'pubmed_search',
+  'arxiv_search',
+  'semantic_scholar_search',
+  'site_explorer',
+  'webpage_reader',
+  'wayback_reader',
+  'knowledge_search',
+])
+
+// Advanced search restricted to a domain pack (standards, legal, ...)
+const searchDomains = async (params, toolConfig, { domains, queryType, label }) => {
+  try {
+    const { answer, results } = await webSearch(
+      params.query,
+      { maxResults: params.max_results || 5, includeDomains: domains, depth: 'advanced' },
+      toolConfig,
+    )
+    return { answer, results, query_type: queryType }
+  } catch (error) {
+    throw new Error(`${label} search failed: ${error.message}`, { cause: error })
   }
-  const envKey = process.env.TAVILY_API_KEY || process.env.PUBLIC_TAVILY_API_KEY
-  if (envKey) return envKey
-  return ''
 }
 
 const GLOBAL_TOOLS = [
@@ -28,7 +71,7 @@ const GLOBAL_TOOLS = [
     id: 'Tavily_web_search',
     name: 'Tavily_web_search',
     category: 'search',
-    description: 'Search the web for current information using Tavily API.',
+    description: 'Search the web for current information.',
     parameters: {
       type: 'object',
       required: ['query'],
@@ -146,11 +189,367 @@ const AGENT_TOOLS = [
       },
     },
   },
+  {
+    id: 'proofread_text',
+    name: 'proofread_text',
+    category: 'text',
+    description:
+      'Proofread text: grammar, spelling and clarity plus style rules (passive voice, sentence length, banned terms). Returns issues with character spans and suggested fixes.',
+    parameters: {
+      type: 'object',
+      required: ['text'],
+      properties: {
+        text: {
+          type: 'string',
+          description: 'Text to check.',
+        },
+        passive_voice: {
+          type: 'boolean',
+          description: 'Flag passive voice (default true).',
+        },
+        max_sentence_words: {
+          type: 'integer',
+          description: 'Flag sentences longer than this many words (default 35).',
+        },
+        banned_terms: {
+          type: 'array',
+          items: { type: 'string' },
+          description: 'Terms that must not appear.',
+        },
+      },
+    },
+  },
+  {
+    id: 'save_snippet',
+    name: 'save_snippet',
+    category: 'memory',
+    description:
+      "Save a reusable snippet (code, fact, definition, or passage) to the user's snippet clipboard with optional tags.",
+    parameters: {
+      type: 'object',
+      required: ['content'],
+      properties: {
+        content: {
+          type: 'string',
+          description: 'Snippet text to save.',
+        },
+        title: {
+          type: 'string',
+          description: 'Short title (defaults to the first line).',
+        },
+        tags: {
+          type: 'array',
+          items: { type: 'string' },
+          description: 'Tags for later lookup.',
+        },
+      },
+    },
+  },
+  {
+    id: 'search_snippets',
+    name: 'search_snippets',
+    category: 'memory',
+    description: "Search the user's saved snippets by keywords and/or tags.",
+    parameters: {
+      type: 'object',
+      properties: {
+        query: {
+          type: 'string',
+          description: 'Keywords to match against titles, contents, and tags.',
+        },
+        tags: {
+          type: 'array',
+          items: { type: 'string' },
+          description: 'Only return snippets that have all of these tags.',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum number of snippets to return (default 5).',
+        },
+      },
+    },
+  },
+  {
+    id: 'knowledge_search',
+    name: 'knowledge_search',
+    category: 'memory',
+    description:
+      "Search the user's knowledge base (uploaded documents and ingested documentation sites) for passages relevant to a question. Cite the returned passages when answering from them.",
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description: 'What to look for, as a question or keywords.',
+        },
+        document_ids: {
+          type: 'array',
+          items: { type: 'string' },
+          description: 'Only search these documents.',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum number of passages to return (default 6).',
+        },
+      },
+    },
+  },
+  {
+    id: 'repo_context',
+    name: 'repo_context',
+    category: 'code',
+    description:
+      'Inspect a local git repository (must be inside the sandbox allowlist): list files, read a file, grep for a pattern, or show recent commits.',
+    parameters: {
+      type: 'object',
+      required: ['repo_path', 'action'],
+      properties: {
+        repo_path: {
+          type: 'string',
+          description: 'Absolute path of the repository.',
+        },
+        action: {
+          type: 'string',
+          enum: REPO_ACTIONS,
+          description: 'Operation to run.',
+        },
+        path: {
+          type: 'string',
+          description:
+            'File to read (read_file), or a path to limit list_files/grep/recent_commits to.',
+        },
+        pattern: {
+          type: 'string',
+          description: 'Extended regular expression (grep).',
+        },
+        ignore_case: {
+          type: 'boolean',
+          description: 'Case-insensitive grep.',
+        },
+        start_line: {
+          type: 'integer',
+          description: 'First line to read, 1-based (read_file).',
+        },
+        end_line: {
+          type: 'integer',
+          description: 'Last line to read (read_file).',
+        },
+        limit: {
+          type: 'integer',
+          description: 'Maximum files, matches, or commits to return.',
+        },
+      },
+    },
+  },
+  {
+    id: 'code_search',
+    name: 'code_search',
+    category: 'code',
+    description:
+      'Search an indexed local repository (inside the sandbox allowlist) for relevant functions, classes, and code snippets. Returns ranked results with file and line references; the repository is indexed on first use.',
+    parameters: {
+      type: 'object',
+      required: ['repo_path', 'query'],
+      properties: {
+        repo_path: {
+          type: 'string',
+          description: 'Absolute path of the repository.',
+        },
+        query: {
+          type: 'string',
+          description: 'What to look for, in words or identifiers.',
+        },
+        kind: {
+          type: 'string',
+          description: 'Only return this symbol kind (function, class, method, struct, file, ...).',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum number of results (default 8).',
+        },
+      },
+    },
+  },
+  {
+    id: 'sql_query',
+    name: 'sql_query',
+    category: 'data',
+    description:
+      'Query a user-configured database in read-only mode. Use action "schema" to list tables and columns, then "query" with a single SELECT statement. Results are row-limited.',
+    parameters: {
+      type: 'object',
+      required: ['connection', 'action'],
+      properties: {
+        connection: {
+          type: 'string',
+          description: 'Connection name or id.',
+        },
+        action: {
+          type: 'string',
+          enum: ['schema', 'query'],
+          description: 'schema: list tables/columns; query: run a SELECT.',
+        },
+        sql: {
+          type: 'string',
+          description: 'A single SELECT (or WITH ... SELECT) statement (query action).',
+        },
+        max_rows: {
+          type: 'integer',
+          description: 'Maximum rows to return (capped by the connection setting).',
+        },
+      },
+    },
+  },
+  {
+    id: 'http_request',
+    name: 'http_request',
+    category: 'web',
+    description:
+      'Call an HTTP API on a user-allowlisted domain (GET or POST with JSON). Reference stored credentials as {{secret:NAME}} in headers, query, or body; they are filled in server-side.',
+    parameters: {
+      type: 'object',
+      required: ['url'],
+      properties: {
+        method: {
+          type: 'string',
+          enum: ['GET', 'POST'],
+          description: 'HTTP method (default GET).',
+        },
+        url: {
+          type: 'string',
+          description: 'Full URL on an allowlisted domain.',
+        },
+        headers: {
+          type: 'object',
+          description: 'Request headers, e.g. {"Authorization": "Bearer {{secret:MY_API_KEY}}"}.',
+        },
+        query: {
+          type: 'object',
+          description: 'Query string parameters.',
+        },
+        body: {
+          type: 'object',
+          description: 'JSON body (POST only).',
+        },
+      },
+    },
+  },
+  {
+    id: 'package_lookup',
+    name: 'package_lookup',
+    category: 'web',
+    description:
+      'Look up a package on npm, crates.io, or PyPI: latest version, deprecation/yank status, minimum runtime (Node engines, Rust MSRV, requires_python), and dependencies. Use instead of guessing versions.',
+    parameters: {
+      type: 'object',
+      required: ['ecosystem', 'name'],
+      properties: {
+        ecosystem: {
+          type: 'string',
+          enum: PACKAGE_ECOSYSTEMS,
+          description: 'Package registry: npm, crates (crates.io), or pypi.',
+        },
+        name: {
+          type: 'string',
+          description: 'Package name, e.g. "serde", "@tanstack/react-query", "requests".',
+        },
+        version: {
+          type: 'string',
+          description: 'Specific version (default: latest stable).',
+        },
+        include_dependencies: {
+          type: 'boolean',
+          description: 'Include the dependency list (default true).',
+        },
+      },
+    },
+  },
+  {
+    id: 'github',
+    name: 'github',
+    category: 'web',
+    description:
+      'Search GitHub repositories and issues/pull requests, or fetch a full issue/PR thread with comments. Useful for grounding software answers in real bug reports and discussions.',
+    parameters: {
+      type: 'object',
+      required: ['action'],
+      properties: {
+        action: {
+          type: 'string',
+          enum: GITHUB_ACTIONS,
+          description:
+            'search_repos: find repositories; search_issues: find issues/PRs; get_issue: read one thread.',
+        },
+        query: {
+          type: 'string',
+          description: 'Search text; GitHub qualifiers like "language:rust" are supported.',
+        },
+        repo: {
+          type: 'string',
+          description: 'Repository as "owner/name" (limits search_issues; required for get_issue).',
+        },
+        number: {
+          type: 'integer',
+          description: 'Issue or PR number (get_issue).',
+        },
+        type: {
+          type: 'string',
+          enum: ['issue', 'pr', 'any'],
+          description: 'Restrict search_issues to issues or pull requests.',
+        },
+        state: {
+          type: 'string',
+          enum: ['open', 'closed', 'any'],
+          description: 'Restrict search_issues by state.',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum results (default 10, max 30).',
+        },
+      },
+    },
+  },
+  {
+    id: 'stackexchange_search',
+    name: 'stackexchange_search',
+    category: 'web',
+    description:
+      'Search StackExchange Q&A (Stack Overflow by default) and return questions with their accepted or top-voted answer, scores, and links.',
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description: 'Search text.',
+        },
+        site: {
+          type: 'string',
+          description: 'Site id, e.g. stackoverflow, serverfault, superuser, math, unix.',
+        },
+        tags: {
+          type: 'array',
+          items: { type: 'string' },
+          description: 'Only questions with all of these tags.',
+        },
+        accepted_only: {
+          type: 'boolean',
+          description: 'Only questions that have an accepted answer.',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum questions to return (default 5, max 20).',
+        },
+      },
+    },
+  },
   {
     id: 'webpage_reader',
     name: 'webpage_reader',
     category: 'web',
-    description: 'Fetch webpage content and return JSON.',
+    description:
+      'Read a webpage: returns its main content as Markdown (navigation and ads removed) with the title, author, and published date. Long pages are split into pages; when has_more is true, call again with the next page number.',
     parameters: {
       type: 'object',
       required: ['url'],
@@ -159,6 +558,65 @@ const AGENT_TOOLS = [
           type: 'string',
           description: 'Target webpage URL (e.g., https://example.com).',
         },
+        page: {
+          type: 'integer',
+          description: 'Page of the extracted text to return (default 1; see total_pages).',
+        },
+        screenshot: {
+          type: 'boolean',
+          description:
+            'Also capture a rendered screenshot (for charts, tables drawn as images, or pages whose text extraction is poor).',
+        },
+      },
+    },
+  },
+  {
+    id: 'wayback_reader',
+    name: 'wayback_reader',
+    category: 'web',
+    description:
+      'Read an archived copy of a webpage from the Internet Archive Wayback Machine. Use when a page is gone, moved, or blocked; the result is tagged with the snapshot date.',
+    parameters: {
+      type: 'object',
+      required: ['url'],
+      properties: {
+        url: {
+          type: 'string',
+          description: 'Original webpage URL (e.g., https://example.com/article).',
+        },
+        date: {
+          type: 'string',
+          description: 'Prefer the snapshot closest to this date (YYYY-MM-DD). Default: latest.',
+        },
+      },
+    },
+  },
+  {
+    id: 'site_explorer',
+    name: 'site_explorer',
+    category: 'web',
+    description:
+      'Explore a website from a seed URL by following its internal links (respects robots.txt). Returns a site map with page titles, headings and excerpts grouped by section; useful for surveying a product documentation site.',
+    parameters: {
+      type: 'object',
+      required: ['url'],
+      properties: {
+        url: {
+          type: 'string',
+          description: 'Seed URL (e.g., https://docs.example.com/).',
+        },
+        max_depth: {
+          type: 'integer',
+          description: 'Link hops to follow from the seed (default 2, max 4).',
+        },
+        max_pages: {
+          type: 'integer',
+          description: 'Maximum pages to fetch (default 20, max 60).',
+        },
+        path_prefix: {
+          type: 'string',
+          description: 'Only follow links under this path (e.g., "/docs").',
+        },
       },
     },
   },
@@ -167,7 +625,7 @@ const AGENT_TOOLS = [
     name: 'Tavily_academic_search',
     category: 'search',
     description:
-      'Search academic journals, papers, and scholarly resources using Tavily API with advanced search depth. Results are limited to peer-reviewed sources, preprint servers, and trusted academic databases.',
+      'Search academic journals, papers, and scholarly resources with advanced search depth. Results are limited to peer-reviewed sources, preprint servers, and trusted academic databases.',
     parameters: {
       type: 'object',
       required: ['query'],
@@ -183,6 +641,234 @@ const AGENT_TOOLS = [
       },
     },
   },
+  {
+    id: 'Tavily_standards_search',
+    name: 'Tavily_standards_search',
+    category: 'search',
+    description:
+      'Search standards and regulations with advanced search depth. Results are limited to official publishers: IETF/RFC Editor, W3C, ISO/IEC, NIST, EUR-Lex, federal registers, and national legislation sites.',
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description:
+            'Standards/regulatory search query (e.g., "GDPR Article 17 right to erasure", "ISO 27001 Annex A").',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum number of results to return (default 5).',
+        },
+      },
+    },
+  },
+  {
+    id: 'Tavily_legal_search',
+    name: 'Tavily_legal_search',
+    category: 'search',
+    description:
+      'Search case law and legislation with advanced search depth. Results are limited to court, legislation, and legal information sites (e.g., CourtListener, Cornell LII, BAILII, CanLII, AustLII, EUR-Lex, CURIA).',
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description:
+            'Legal search query; include the jurisdiction (e.g., "California non-compete enforceability Bus. & Prof. Code 16600").',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum number of results to return (default 5).',
+        },
+      },
+    },
+  },
+  {
+    id: 'rfc_fetch',
+    name: 'rfc_fetch',
+    category: 'web',
+    description:
+      'Fetch an IETF RFC by number from the RFC Editor, with status and obsoleted/updated-by metadata. Pass a section number to get just that clause.',
+    parameters: {
+      type: 'object',
+      required: ['number'],
+      properties: {
+        number: {
+          type: 'integer',
+          description: 'RFC number (e.g., 9110).',
+        },
+        section: {
+          type: 'string',
+          description: 'Section to extract (e.g., "15.5.4" or "Appendix A").',
+        },
+      },
+    },
+  },
+  {
+    id: 'patent_search',
+    name: 'patent_search',
+    category: 'search',
+    description:
+      'Search granted US patents (PatentsView) by keywords, assignee, and grant date. Returns patent numbers, titles, assignees, filing/grant dates, abstracts, and independent claims.',
+    parameters: {
+      type: 'object',
+      properties: {
+        query: {
+          type: 'string',
+          description: 'Keywords that must all appear in the title or abstract.',
+        },
+        assignee: {
+          type: 'string',
+          description: 'Assignee organization (substring match, e.g. "Siemens").',
+        },
+        date_from: {
+          type: 'string',
+          description: 'Earliest grant date (YYYY-MM-DD).',
+        },
+        date_to: {
+          type: 'string',
+          description: 'Latest grant date (YYYY-MM-DD).',
+        },
+        include_claims: {
+          type: 'boolean',
+          description: 'Include independent claims (default true).',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum patents to return (default 10, max 25).',
+        },
+      },
+    },
+  },
+  {
+    id: 'pubmed_search',
+    name: 'pubmed_search',
+    category: 'search',
+    description:
+      'Search PubMed (NCBI E-utilities) for biomedical literature. Returns PMID, title, journal, year, DOI, study design and evidence level, sample size, population, outcomes, and abstract. Supports PubMed query syntax (MeSH terms, [tiab], AND/OR).',
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description: 'PubMed query, e.g. "metformin AND type 2 diabetes[mh] AND cardiovascular".',
+        },
+        study_type: {
+          type: 'string',
+          enum: Object.keys(PUBMED_STUDY_FILTERS),
+          description: 'Restrict to a study design.',
+        },
+        date_from: {
+          type: 'string',
+          description: 'Earliest publication date (YYYY or YYYY/MM/DD).',
+        },
+        date_to: {
+          type: 'string',
+          description: 'Latest publication date (YYYY or YYYY/MM/DD).',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum articles to return (default 10, max 30).',
+        },
+      },
+    },
+  },
+  {
+    id: 'arxiv_search',
+    name: 'arxiv_search',
+    category: 'search',
+    description:
+      'Search arXiv preprints (physics, mathematics, computer science, quantitative biology, statistics, economics). Returns title, authors, year, DOI, categories, PDF link, and abstract. Supports arXiv query syntax (ti:, au:, abs:, cat:, AND/OR/ANDNOT).',
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description: 'Search words, or an arXiv query such as "ti:transformer AND au:vaswani".',
+        },
+        category: {
+          type: 'string',
+          description: 'Restrict to an arXiv category, e.g. "cs.CL", "math.PR", "q-bio.NC".',
+        },
+        sort: {
+          type: 'string',
+          enum: Object.keys(ARXIV_SORT_ORDERS),
+          description: 'Result order (default relevance; "submitted" for the newest preprints).',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum papers to return (default 10, max 30).',
+        },
+      },
+    },
+  },
+  {
+    id: 'semantic_scholar_search',
+    name: 'semantic_scholar_search',
+    category: 'search',
+    description:
+      'Search Semantic Scholar for papers across all fields of study. Returns title, authors, year, DOI, venue, citation count, open-access PDF link, and abstract.',
+    parameters: {
+      type: 'object',
+      required: ['query'],
+      properties: {
+        query: {
+          type: 'string',
+          description: 'Plain-text search words (no boolean syntax).',
+        },
+        year: {
+          type: 'string',
+          description: 'Publication year or range, e.g. "2021", "2018-2022", "2020-".',
+        },
+        fields_of_study: {
+          type: 'array',
+          items: { type: 'string' },
+          description: 'Restrict to fields such as "Computer Science", "Medicine", "Economics".',
+        },
+        open_access_only: {
+          type: 'boolean',
+          description: 'Only return papers with a free PDF.',
+        },
+        min_citations: {
+          type: 'integer',
+          description: 'Only return papers cited at least this many times.',
+        },
+        max_results: {
+          type: 'integer',
+          description: 'Maximum papers to return (default 10, max 30).',
+        },
+      },
+    },
+  },
+  {
+    id: 'generate_image',
+    name: 'generate_image',
+    category: 'image',
+    description:
+      'Generate an image from a text description. The image is shown to the user; describe it briefly in the answer instead of linking it.',
+    parameters: {
+      type: 'object',
+      required: ['prompt'],
+      properties: {
+        prompt: {
+          type: 'string',
+          description: 'Detailed description of the image: subject, style, composition, colors.',
+        },
+        size: {
+          type: 'string',
+          description: 'Image size such as "1024x1024", "1536x1024" or "1024x1536".',
+        },
+        n: {
+          type: 'integer',
+          description: 'Number of images (default 1, max 4).',
+        },
+      },
+    },
+  },
   {
     id: 'interactive_form',
     name: 'interactive_form',
@@ -237,6 +923,32 @@ const AGENT_TOOLS = [
   },
 ]
 
+// Built-in tools of a provider: listed for configuration, but enabled through the provider's
+// adapter (see GeminiAdapter, GLMAdapter) instead of executed here
+const PROVIDER_NATIVE_TOOLS = [
+  {
+    id: 'google_search_grounding',
+    name: 'google_search_grounding',
+    category: 'search',
+    provider: 'gemini',
+    description: 'Ground Gemini answers in Google Search results; cited pages become sources.',
+  },
+  {
+    id: 'gemini_code_execution',
+    name: 'gemini_code_execution',
+    category: 'code',
+    provider: 'gemini',
+    description: 'Let Gemini write and run Python code in a sandbox hosted by Google.',
+  },
+  {
+    id: 'glm_native_search',
+    name: 'glm_native_search',
+    category: 'search',
+    provider: 'glm',
+    description: "Search the web with GLM's built-in search instead of Tavily; pages become sources.",
+  },
+]
+
 // Combined list for execution and validation
 const ALL_TOOLS = [...GLOBAL_TOOLS, ...AGENT_TOOLS]
 
@@ -261,8 +973,92 @@ const toolSchemas = {
   json_repair: z.object({
     text: z.string().min(1, 'text is required'),
   }),
+  proofread_text: z.object({
+    text: z.string().min(1, 'text is required'),
+    passive_voice: z.boolean().optional(),
+    max_sentence_words: z.number().int().positive().optional(),
+    banned_terms: z.array(z.string()).optional(),
+  }),
+  save_snippet: z.object({
+    content: z.string().min(1, 'content is required'),
+    title: z.string().optional(),
+    tags: z.array(z.string()).optional(),
+  }),
+  search_snippets: z.object({
+    query: z.string().optional(),
+    tags: z.array(z.string()).optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  knowledge_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    document_ids: z.array(z.string()).optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  repo_context: z.object({
+    repo_path: z.string().min(1, 'repo_path is required'),
+    action: z.enum(REPO_ACTIONS),
+    path: z.string().optional(),
+    pattern: z.string().optional(),
+    ignore_case: z.boolean().optional(),
+    start_line: z.number().int().positive().optional(),
+    end_line: z.number().int().positive().optional(),
+    limit: z.number().int().positive().optional(),
+  }),
+  code_search: z.object({
+    repo_path: z.string().min(1, 'repo_path is required'),
+    query: z.string().min(1, 'query is required'),
+    kind: z.string().optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  sql_query: z.object({
+    connection: z.string().min(1, 'connection is required'),
+    action: z.enum(['schema', 'query']),
+    sql: z.string().optional(),
+    max_rows: z.number().int().positive().optional(),
+  }),
+  http_request: z.object({
+    method: z.enum(['GET', 'POST']).optional(),
+    url: z.string().min(1, 'url is required'),
+    headers: z.record(z.string()).optional(),
+    query: z.record(z.union([z.string(), z.number(), z.boolean()])).optional(),
+    body: z.any().optional(),
+  }),
+  package_lookup: z.object({
+    ecosystem: z.enum(PACKAGE_ECOSYSTEMS),
+    name: z.string().min(1, 'name is required'),
+    version: z.string().optional(),
+    include_dependencies: z.boolean().optional(),
+  }),
+  github: z.object({
+    action: z.enum(GITHUB_ACTIONS),
+    query: z.string().optional(),
+    repo: z.string().optional(),
+    number: z.number().int().positive().optional(),
+    type: z.enum(['issue', 'pr', 'any']).optional(),
+    state: z.enum(['open', 'closed', 'any']).optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  stackexchange_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    site: z.string().optional(),
+    tags: z.array(z.string()).optional(),
+    accepted_only: z.boolean().optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
   webpage_reader: z.object({
     url: z.string().min(1, 'url is required'),
+    page: z.number().int().positive().optional(),
+    screenshot: z.boolean().optional(),
+  }),
+  wayback_reader: z.object({
+    url: z.string().min(1, 'url is required'),
+    date: z.string().optional(),
+  }),
+  site_explorer: z.object({
+    url: z.string().min(1, 'url is required'),
+    max_depth: z.number().int().positive().optional(),
+    max_pages: z.number().int().positive().optional(),
+    path_prefix: z.string().optional(),
   }),
   Tavily_web_search: z.object({
     query: z.string().min(1, 'query is required'),
@@ -272,6 +1068,52 @@ const toolSchemas = {
     query: z.string().min(1, 'query is required'),
     max_results: z.number().int().positive().optional(),
   }),
+  Tavily_standards_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    max_results: z.number().int().positive().optional(),
+  }),
+  Tavily_legal_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    max_results: z.number().int().positive().optional(),
+  }),
+  rfc_fetch: z.object({
+    number: z.number().int().positive(),
+    section: z.string().optional(),
+  }),
+  patent_search: z.object({
+    query: z.string().optional(),
+    assignee: z.string().optional(),
+    date_from: z.string().optional(),
+    date_to: z.string().optional(),
+    include_claims: z.boolean().optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  pubmed_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    study_type: z.enum(Object.keys(PUBMED_STUDY_FILTERS)).optional(),
+    date_from: z.string().optional(),
+    date_to: z.string().optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  arxiv_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    category: z.string().optional(),
+    sort: z.enum(Object.keys(ARXIV_SORT_ORDERS)).optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  semantic_scholar_search: z.object({
+    query: z.string().min(1, 'query is required'),
+    year: z.union([z.string(), z.number()]).optional(),
+    fields_of_study: z.array(z.string()).optional(),
+    open_access_only: z.boolean().optional(),
+    min_citations: z.number().int().nonnegative().optional(),
+    max_results: z.number().int().positive().optional(),
+  }),
+  generate_image: z.object({
+    prompt: z.string().min(1, 'prompt is required'),
+    size: z.string().optional(),
+    n: z.number().int().positive().max(MAX_IMAGES_PER_REQUEST).optional(),
+  }),
   interactive_form: z.object({
     id: z.string().min(1, 'id is required'),
     title: z.string().min(1, 'title is required'),
@@ -297,14 +1139,28 @@ const safeEvaluate = expression => {
 }
 
 // Only expose Agent Tools to the configuration UI
-export const listTools = () =>
-  AGENT_TOOLS.map(tool => ({
-    id: tool.id,
-    name: tool.name,
-    category: tool.category,
-    description: tool.description,
-    parameters: tool.parameters,
-  }))
+export const listTools = () => {
+  const unavailableToolIds = getUnavailableToolIds()
+  return [
+    ...AGENT_TOOLS.map(tool => ({
+      id: tool.id,
+      name: tool.name,
+      category: tool.category,
+      description: tool.description,
+      parameters: tool.parameters,
+      // Needs a program this machine lacks (see runtimeCapabilities.js)
+      ...(unavailableToolIds.has(tool.id) ? { available: false } : {}),
+    })),
+    // Only effective with their provider
+    ...PROVIDER_NATIVE_TOOLS.map(({ id, name, category, provider, description }) => ({
+      id,
+      name,
+      category,
+      provider,
+      description,
+    })),
+  ]
+}
 
 export const getToolDefinitionsByIds = toolIds => {
   if (!Array.isArray(toolIds) || toolIds.length === 0) return []
@@ -323,7 +1179,9 @@ export const getToolDefinitionsByIds = toolIds => {
 export const isLocalToolName = toolName =>
   ALL_TOOLS.some(tool => tool.name === resolveToolName(toolName) || tool.id === toolName)
 
-export const executeToolByName = async (toolName, args = {}, toolConfig = {}) => {
+export const isSourceToolName = toolName => SOURCE_TOOL_NAMES.has(resolveToolName(toolName))
+
+const runToolByName = async (toolName, args = {}, toolConfig = {}) => {
   const resolvedToolName = resolveToolName(toolName)
   const schema = toolSchemas[resolvedToolName]
   if (!schema) {
@@ -394,120 +1252,212 @@ export const executeToolByName = async (toolName, args = {}, toolConfig = {}) =>
         }
       }
     }
+    case 'proofread_text': {
+      // The grammar pass runs on the caller's model; without credentials only the rules run
+      const { issues, llm } = await proofreadText({
+        ...toolConfig.proofread,
+        text: params.text,
+        rules: {
+          passiveVoice: params.passive_voice,
+          maxSentenceWords: params.max_sentence_words,
+          bannedTerms: params.banned_terms,
+        },
+      })
+      return { issues, count: issues.length, llm }
+    }
+    case 'save_snippet': {
+      const snippet = createSnippet({
+        content: params.content,
+        title: params.title,
+        tags: params.tags,
+      })
+      return { id: snippet.id, title: snippet.title, tags: snippet.tags }
+    }
+    case 'search_snippets': {
+      const snippets = searchSnippets({
+        query: params.query,
+        tags: params.tags,
+        limit: params.max_results || 5,
+      })
+      return {
+        snippets: snippets.map(({ id, title, content, tags }) => ({ id, title, content, tags })),
+      }
+    }
+    case 'knowledge_search': {
+      const passages = await searchDocuments({
+        query: params.query,
+        documentIds: params.document_ids,
+        limit: Math.min(params.max_results || 6, 20),
+      })
+      return {
+        results: passages.map(passage => ({
+          title: passage.title,
+          url: passage.url || `qurio://documents/${passage.document_id}#chunk-${passage.chunk}`,
+          content: passage.text,
+          document_id: passage.document_id,
+          score: passage.score,
+        })),
+      }
+    }
+    case 'repo_context': {
+      return queryRepository(params)
+    }
+    case 'code_search': {
+      const results = await searchCode({
+        repoPath: params.repo_path,
+        query: params.query,
+        kind: params.kind,
+        limit: params.max_results || 8,
+      })
+      return { results }
+    }
+    case 'sql_query': {
+      if (params.action === 'schema') return getSqlSchema(params.connection)
+      return runReadOnlyQuery(params.connection, params.sql, { maxRows: params.max_rows })
+    }
+    case 'http_request': {
+      return performHttpRequest(params)
+    }
+    case 'package_lookup': {
+      return lookupPackage({
+        ecosystem: params.ecosystem,
+        name: params.name,
+        version: params.version,
+        includeDependencies: params.include_dependencies !== false,
+      })
+    }
+    case 'github': {
+      return queryGithub({ ...params, limit: params.max_results })
+    }
+    case 'stackexchange_search': {
+      return searchStackExchange({
+        query: params.query,
+        site: params.site,
+        tags: params.tags,
+        acceptedOnly: params.accepted_only,
+        limit: params.max_results,
+      })
+    }
     case 'webpage_reader': {
-      const inputUrl = params.url.trim()
-      const normalized = inputUrl.replace(/^https?:\/\/r\.jina\.ai\//i, '')
-      const requestUrl = `https://r.jina.ai/${normalized}`
-
+      const page = await readWebpage(params.url, { page: params.page })
+      if (!params.screenshot) return page
       try {
-        const response = await fetch(requestUrl, {
-          headers: {
-            Accept: 'text/plain',
-          },
-        })
-
-        if (!response.ok) {
-          throw new Error(`Jina AI reader error: ${response.statusText}`)
-        }
-
-        const content = await response.text()
-        return {
-          url: normalized,
-          content,
-          source: 'jina.ai',
-        }
+        return { ...page, screenshot: toScreenshotRef(await captureScreenshot(page.url)) }
       } catch (error) {
-        throw new Error(`Webpage read failed: ${error.message}`)
+        return { ...page, screenshot_error: error.message }
       }
     }
-    case 'Tavily_web_search': {
-      const query = params.query
-      const maxResults = params.max_results || 5
-      const apiKey = resolveTavilyApiKey(toolConfig)
-
-      if (!apiKey) {
-        throw new Error('Tavily API key not configured. Set TAVILY_API_KEY or add it in settings.')
+    case 'wayback_reader': {
+      const { content, ...page } = await readArchivedPage(params.url.trim(), {
+        near: params.date,
+      })
+      const archivedOn = page.archived_at?.slice(0, 10)
+      return {
+        ...page,
+        results: [
+          {
+            title: archivedOn ? `${page.title} (archived ${archivedOn})` : page.title,
+            url: page.archived_url,
+            content,
+          },
+        ],
       }
-
+    }
+    case 'site_explorer': {
+      return exploreSite({
+        url: params.url,
+        maxDepth: params.max_depth,
+        maxPages: params.max_pages,
+        pathPrefix: params.path_prefix,
+      })
+    }
+    case 'Tavily_web_search': {
       try {
-        const response = await fetch('https://api.tavily.com/search', {
-          method: 'POST',
-          headers: {
-            'Content-Type': 'application/json',
-          },
-          body: JSON.stringify({
-            api_key: apiKey,
-            query,
-            search_depth: 'basic',
-            include_answer: true,
-            max_results: maxResults,
-          }),
-        })
-
-        if (!response.ok) {
-          throw new Error(`Tavily API error: ${response.statusText}`)
-        }
-
-        const data = await response.json()
-
-        // Return structured results
-        return {
-          answer: data.answer,
-          results: data.results.map(r => ({
-            title: r.title,
-            url: r.url,
-            content: r.content,
-          })),
-        }
+        const { answer, results } = await webSearch(
+          params.query,
+          { maxResults: params.max_results || 5 },
+          toolConfig,
+        )
+        return { answer, results }
       } catch (error) {
-        throw new Error(`Search failed: ${error.message}`)
+        throw new Error(`Search failed: ${error.message}`, { cause: error })
       }
     }
     case 'Tavily_academic_search': {
-      const query = params.query
-      const maxResults = params.max_results || 5
-      const apiKey = resolveTavilyApiKey(toolConfig)
-
-      if (!apiKey) {
-        throw new Error('Tavily API key not configured. Set TAVILY_API_KEY or add it in settings.')
-      }
-
-      try {
-        const response = await fetch('https://api.tavily.com/search', {
-          method: 'POST',
-          headers: {
-            'Content-Type': 'application/json',
-          },
-          body: JSON.stringify({
-            api_key: apiKey,
-            query,
-            search_depth: 'advanced', // Use advanced search for academic queries
-            include_domains: ACADEMIC_DOMAINS,
-            include_answer: true,
-            max_results: maxResults,
-          }),
-        })
-
-        if (!response.ok) {
-          throw new Error(`Tavily API error: ${response.statusText}`)
-        }
-
-        const data = await response.json()
-
-        // Return structured academic results
-        return {
-          answer: data.answer,
-          results: data.results.map(r => ({
-            title: r.title,
-            url: r.url,
-            content: r.content,
-            score: r.score || null, // Relevance score if available
-          })),
-          query_type: 'academic',
-        }
-      } catch (error) {
-        throw new Error(`Academic search failed: ${error.message}`)
+      return searchDomains(params, toolConfig, {
+        domains: ACADEMIC_DOMAINS,
+        queryType: 'academic',
+        label: 'Academic',
+      })
+    }
+    case 'Tavily_standards_search': {
+      return searchDomains(params, toolConfig, {
+        domains: STANDARDS_DOMAINS,
+        queryType: 'standards',
+        label: 'Standards',
+      })
+    }
+    case 'Tavily_legal_search': {
+      return searchDomains(params, toolConfig, {
+        domains: LEGAL_DOMAINS,
+        queryType: 'legal',
+        label: 'Legal',
+      })
+    }
+    case 'rfc_fetch': {
+      return fetchRfc(params)
+    }
+    case 'patent_search': {
+      return searchPatents({
+        query: params.query,
+        assignee: params.assignee,
+        dateFrom: params.date_from,
+        dateTo: params.date_to,
+        includeClaims: params.include_claims !== false,
+        limit: params.max_results,
+      })
+    }
+    case 'pubmed_search': {
+      return searchPubmed({
+        query: params.query,
+        studyType: params.study_type,
+        dateFrom: params.date_from,
+        dateTo: params.date_to,
+        limit: params.max_results,
+      })
+    }
+    case 'arxiv_search': {
+      return searchArxiv({
+        query: params.query,
+        category: params.category,
+        sort: params.sort,
+        limit: params.max_results,
+      })
+    }
+    case 'semantic_scholar_search': {
+      return searchSemanticScholar({
+        query: params.query,
+        year: params.year,
+        fieldsOfStudy: params.fields_of_study,
+        openAccessOnly: params.open_access_only,
+        minCitations: params.min_citations,
+        limit: params.max_results,
+      })
+    }
+    case 'generate_image': {
+      const image = toolConfig.image
+      if (!image?.provider || !image?.apiKey) {
+        throw new Error(
+          `Image generation needs an image provider (${IMAGE_PROVIDERS.join(', ')}) and its key`,
+        )
       }
+      const { provider, model, images } = await generateImages({
+        ...image,
+        prompt: params.prompt,
+        size: params.size,
+        n: params.n,
+      })
+      return { provider, model, images }
     }
     case 'interactive_form': {
       // This is a client-side interaction tool
@@ -521,3 +1471,13 @@ export const executeToolByName = async (toolName, args = {}, toolConfig = {}) =>
       throw new Error(`Unknown tool: ${toolName}`)
   }
 }
+
+/**
+ * Run a local tool by name (or id); traced as a "tool" span with its arguments and result
+ */
+export const executeToolByName = (toolName, args = {}, toolConfig = {}) =>
+  withSpan('tool', { tool: toolName, arguments: args }, async span => {
+    const result = await runToolByName(toolName, args, toolConfig)
+    if (span) span.attributes.result = sanitizeTraceValue(result)
+    return result
+  })
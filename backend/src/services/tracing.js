@@ -0,0 +1,305 @@
+/**
+ * Request tracing
+ * Each traced request (chat, deep research, the other LLM routes) gets a trace id and a tree of
+ * spans: the request itself, model calls with their prompts and output, tool calls, research
+ * steps. The active span travels with the request through AsyncLocalStorage, so services only
+ * call withSpan() / addSpanEvent() and need no trace parameter.
+ *
+ * Finished traces are stored in the data dir ("traces", newest TRACE_RETENTION kept, default 200)
+ * for GET /api/traces/:id, and every finished span is appended to the rolling log
+ * logs/qurio.log (TRACE_LOG_MAX_MB per file, default 10; TRACE_LOG_FILES files, default 5).
+ * TRACING=off disables both. Values that look like credentials are never recorded, and long
+ * strings are cut at TRACE_MAX_FIELD_CHARS (default 8000).
+ */
+
+import { AsyncLocalStorage } from 'async_hooks'
+import crypto from 'crypto'
+import fs from 'fs'
+import path from 'path'
+import { performance } from 'perf_hooks'
+import { readRecord, resolveCollectionDir, writeRecord } from '../utils/dataStore.js'
+import { createRollingLog } from '../utils/rollingLog.js'
+
+const COLLECTION = 'traces'
+const DEFAULT_RETENTION = 200
+const DEFAULT_MAX_FIELD_CHARS = 8000
+const DEFAULT_LOG_MAX_MB = 10
+const DEFAULT_LOG_FILES = 5
+const MAX_DEPTH = 8
+const SECRET_KEY_PATTERN = /api_?key|token|secret|password|authorization|cookie/i
+const TRACE_ID_PATTERN = /^[A-Za-z0-9_-]{8,64}$/
+
+const storage = new AsyncLocalStorage()
+// Traces still running, so GET /api/traces/:id can show a hung run
+const activeTraces = new Map()
+let spanLog = null
+
+const readNumber = (name, fallback) => {
+  const value = Number.parseFloat(process.env[name])
+  return Number.isFinite(value) && value >= 0 ? value : fallback
+}
+
+export const isTracingEnabled = () => process.env.TRACING !== 'off'
+
+export const isValidTraceId = id => TRACE_ID_PATTERN.test(String(id || ''))
+
+const newId = () => crypto.randomBytes(8).toString('hex')
+
+const getSpanLog = () => {
+  if (!spanLog) {
+    spanLog = createRollingLog({
+      dir: resolveCollectionDir('logs'),
+      name: 'qurio.log',
+      maxBytes: readNumber('TRACE_LOG_MAX_MB', DEFAULT_LOG_MAX_MB) * 1024 * 1024,
+      maxFiles: readNumber('TRACE_LOG_FILES', DEFAULT_LOG_FILES),
+    })
+  }
+  return spanLog
+}
+
+/**
+ * Copy a value for a trace: credentials redacted, long strings cut, depth bounded
+ */
+export const sanitizeTraceValue = (
+  value,
+  maxChars = readNumber('TRACE_MAX_FIELD_CHARS', DEFAULT_MAX_FIELD_CHARS),
+  depth = 0,
+) => {
+  if (typeof value === 'string') {
+    return value.length > maxChars
+      ? `${value.slice(0, maxChars)}…[${value.length - maxChars} more chars]`
+      : value
+  }
+  if (value === null || typeof value !== 'object') {
+    return typeof value === 'function' || typeof value === 'symbol' ? undefined : value
+  }
+  if (value instanceof Error) return { name: value.name, message: value.message }
+  if (depth >= MAX_DEPTH) return '[nested too deep]'
+  if (Array.isArray(value)) return value.map(item => sanitizeTraceValue(item, maxChars, depth + 1))
+  return Object.fromEntries(
+    Object.entries(value)
+      .filter(([, item]) => item !== undefined && !(item instanceof AbortSignal))
+      .map(([key, item]) => [
+        key,
+        SECRET_KEY_PATTERN.test(key) && item
+          ? '[redacted]'
+          : sanitizeTraceValue(item, maxChars, depth + 1),
+      ]),
+  )
+}
+
+class Trace {
+  constructor({ id, name, kind }) {
+    this.id = id
+    this.name = name
+    this.kind = kind
+    this.startedAt = new Date().toISOString()
+    this.spans = []
+  }
+
+  startSpan(name, attributes, parentId = null) {
+    const span = {
+      id: newId(),
+      parent_id: parentId,
+      name,
+      started_at: new Date().toISOString(),
+      duration_ms: null,
+      status: 'running',
+      attributes: sanitizeTraceValue(attributes || {}),
+      events: [],
+      start: performance.now(),
+    }
+    this.spans.push(span)
+    return span
+  }
+}
+
+/**
+ * Close a span; error marks it failed
+ */
+export const endSpan = (trace, span, error) => {
+  if (!span || span.status !== 'running') return
+  span.duration_ms = Math.round((performance.now() - span.start) * 10) / 10
+  span.status = error ? 'error' : 'ok'
+  if (error) span.error = String(error?.message || error)
+  try {
+    getSpanLog().write({
+      time: new Date().toISOString(),
+      trace_id: trace.id,
+      span_id: span.id,
+      parent_id: span.parent_id,
+      name: span.name,
+      status: span.status,
+      duration_ms: span.duration_ms,
+      error: span.error,
+    })
+  } catch (logError) {
+    console.warn('[Tracing] Failed to write span log:', logError.message)
+  }
+}
+
+/**
+ * Begin a trace; run the traced work inside runInTrace(context, fn) and end it with finishTrace()
+ * @param {Object} options
+ * @param {string} options.name - e.g. "POST /api/stream-chat"
+ * @param {string} [options.id] - Client-supplied id (X-Trace-Id); generated when invalid
+ * @param {string} [options.kind] - "chat" | "research" | "request"
+ * @param {Object} [options.attributes] - Recorded on the root span (sanitized)
+ * @returns {{ trace: Trace, context: Object }|null} null when tracing is off
+ */
+export const startTrace = ({ name, id, kind = 'request', attributes } = {}) => {
+  if (!isTracingEnabled()) return null
+  const traceId = isValidTraceId(id) && !activeTraces.has(id) ? id : newId()
+  const trace = new Trace({ id: traceId, name, kind })
+  const root = trace.startSpan(name, attributes)
+  activeTraces.set(trace.id, trace)
+  return { trace, context: { trace, span: root } }
+}
+
+export const runInTrace = (context, fn) => (context ? storage.run(context, fn) : fn())
+
+/**
+ * Run fn inside a new trace and finish it when fn settles
+ */
+export const withTrace = async (options, fn) => {
+  const started = startTrace(options)
+  try {
+    const result = await runInTrace(started?.context, fn)
+    if (started) finishTrace(started.trace)
+    return result
+  } catch (error) {
+    if (started) finishTrace(started.trace, { error })
+    throw error
+  }
+}
+
+const prunePersisted = () => {
+  const retention = readNumber('TRACE_RETENTION', DEFAULT_RETENTION)
+  const dir = resolveCollectionDir(COLLECTION)
+  const files = fs
+    .readdirSync(dir)
+    .filter(name => name.endsWith('.json'))
+    .map(name => ({ name, mtime: fs.statSync(path.join(dir, name)).mtimeMs }))
+    .sort((a, b) => b.mtime - a.mtime)
+  for (const { name } of files.slice(retention)) {
+    fs.rmSync(path.join(dir, name), { force: true })
+  }
+}
+
+const toRecord = trace => {
+  const root = trace.spans[0]
+  return {
+    id: trace.id,
+    name: trace.name,
+    kind: trace.kind,
+    status: root?.status || 'running',
+    started_at: trace.startedAt,
+    duration_ms: root?.duration_ms ?? null,
+    spans: trace.spans.map(({ start, ...span }) => span),
+  }
+}
+
+/**
+ * End a trace's root span (and any span left open) and store it
+ * @param {Trace} trace
+ * @param {Object} [options]
+ * @param {*} [options.error]
+ * @param {Object} [options.attributes] - Added to the root span
+ */
+export const finishTrace = (trace, { error, attributes } = {}) => {
+  if (!trace || !activeTraces.has(trace.id)) return
+  activeTraces.delete(trace.id)
+  const [root, ...children] = trace.spans
+  // Spans of work abandoned by a cancelled or failed request
+  for (const span of children) endSpan(trace, span, 'unfinished')
+  if (attributes) Object.assign(root.attributes, sanitizeTraceValue(attributes))
+  endSpan(trace, root, error)
+  try {
+    writeRecord(COLLECTION, trace.id, toRecord(trace))
+    prunePersisted()
+  } catch (storeError) {
+    console.warn('[Tracing] Failed to store trace:', storeError.message)
+  }
+}
+
+export const getActiveTraceId = () => storage.getStore()?.trace.id
+
+/** The current trace context, for work that outlives the call stack (see modelTracing.js) */
+export const getTraceContext = () => storage.getStore() || null
+
+/**
+ * Run fn in a child span of the current span; without an active trace fn just runs
+ * @param {string} name
+ * @param {Object} [attributes]
+ * @param {(span: Object|null) => Promise<*>} fn
+ */
+export const withSpan = async (name, attributes, fn) => {
+  const context = storage.getStore()
+  if (!context) return fn(null)
+  const span = context.trace.startSpan(name, attributes, context.span.id)
+  try {
+    const result = await storage.run({ trace: context.trace, span }, () => fn(span))
+    endSpan(context.trace, span)
+    return result
+  } catch (error) {
+    endSpan(context.trace, span, error)
+    throw error
+  }
+}
+
+/**
+ * Start a span by hand (for callbacks that report start and end separately)
+ * @returns {{ span: Object, end: (error?: *, attributes?: Object) => void }|null}
+ */
+export const startSpan = (context, name, attributes, parentId = context?.span.id) => {
+  if (!context || !activeTraces.has(context.trace.id)) return null
+  const span = context.trace.startSpan(name, attributes, parentId)
+  return {
+    span,
+    end: (error, endAttributes) => {
+      if (endAttributes) Object.assign(span.attributes, sanitizeTraceValue(endAttributes))
+      endSpan(context.trace, span, error)
+    },
+  }
+}
+
+/**
+ * Record a point-in-time event (a rate limit wait, a retry) on the current span
+ */
+export const addSpanEvent = (name, data) => {
+  const span = storage.getStore()?.span
+  if (!span) return
+  span.events.push({ name, time: new Date().toISOString(), data: sanitizeTraceValue(data) })
+}
+
+/**
+ * Add attributes to the current span
+ */
+export const setSpanAttributes = attributes => {
+  const span = storage.getStore()?.span
+  if (span) Object.assign(span.attributes, sanitizeTraceValue(attributes))
+}
+
+const toSpanTree = spans => {
+  const nodes = new Map(spans.map(span => [span.id, { ...span, children: [] }]))
+  let root = null
+  for (const node of nodes.values()) {
+    const parent = node.parent_id && nodes.get(node.parent_id)
+    if (parent) parent.children.push(node)
+    else root ??= node
+  }
+  return root
+}
+
+/**
+ * A trace with its spans as a tree (root span with nested children)
+ * @returns {Object|null} { id, name, kind, status, started_at, duration_ms, root }
+ */
+export const getTrace = id => {
+  if (!isValidTraceId(id)) return null
+  const active = activeTraces.get(id)
+  const record = active ? toRecord(active) : readRecord(COLLECTION, id)
+  if (!record) return null
+  const { spans, ...summary } = record
+  return { ...summary, root: toSpanTree(spans || []) }
+}
@@ -0,0 +1,47 @@
+/**
+ * Turn limits
+ * How many model turns a tool-calling loop may take before it is cut short: a chat answer
+ * (MAX_STREAM_TURNS, default 10) and each deep research step (MAX_RESEARCH_STEP_TURNS, default 4).
+ * Requests can lower or raise their own limit with "max_turns" up to a fixed ceiling.
+ */
+
+const LIMITS = {
+  chat: { env: 'MAX_STREAM_TURNS', fallback: 10, ceiling: 50 },
+  research_step: { env: 'MAX_RESEARCH_STEP_TURNS', fallback: 4, ceiling: 20 },
+}
+
+const parseTurns = value => {
+  const turns = Number(value)
+  return Number.isInteger(turns) && turns > 0 ? turns : null
+}
+
+/**
+ * Server-side default for a loop kind, clamped to its ceiling
+ * @param {'chat'|'research_step'} kind
+ */
+export const getDefaultMaxTurns = kind => {
+  const { env, fallback, ceiling } = LIMITS[kind]
+  return Math.min(parseTurns(process.env[env]) ?? fallback, ceiling)
+}
+
+/**
+ * @returns {string|null} Error message for an invalid per-request value
+ */
+export const validateMaxTurns = (value, kind) => {
+  if (value === undefined || value === null) return null
+  const { ceiling } = LIMITS[kind]
+  const turns = parseTurns(value)
+  return turns && turns <= ceiling ? null : `max_turns must be an integer between 1 and ${ceiling}`
+}
+
+export const resolveMaxTurns = (value, kind) =>
+  Math.min(parseTurns(value) ?? getDefaultMaxTurns(kind), LIMITS[kind].ceiling)
+
+/**
+ * SSE event announcing that a loop stopped at its turn limit with tool calls still pending
+ */
+export const buildTurnLimitEvent = (maxTurns, meta = {}) => ({
+  type: 'turn_limit_reached',
+  max_turns: maxTurns,
+  ...meta,
+})
@@ -0,0 +1,302 @@
+/**
+ * Upload service
+ * Chunked, resumable uploads for attachments and knowledge-base files that are too large for one
+ * request body. A client declares the file (init), sends numbered parts in any order and retries
+ * failed ones, then completes the upload; the parts are concatenated in a background task (see
+ * taskQueue) and the result is checked against the declared SHA-256 before it can be used.
+ * Parts and assembled files live next to their metadata records in the "uploads" data collection.
+ */
+
+import crypto from 'crypto'
+import fs from 'fs'
+import path from 'path'
+import { pipeline } from 'stream/promises'
+import {
+  deleteRecord,
+  listRecords,
+  readRecord,
+  resolveCollectionDir,
+  writeRecord,
+} from '../utils/dataStore.js'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+import { backgroundJobManager } from './backgroundService.js'
+import { taskQueue } from './taskQueue.js'
+
+const COLLECTION = 'uploads'
+const DEFAULT_PART_MB = 5
+const MAX_PART_MB = 16
+const DEFAULT_MAX_UPLOAD_MB = 200
+const DEFAULT_TTL_HOURS = 24
+const SHA256_PATTERN = /^[a-f0-9]{64}$/
+
+const MB = 1024 * 1024
+
+const readPositive = (value, fallback) => {
+  const number = Number.parseFloat(value)
+  return Number.isFinite(number) && number > 0 ? number : fallback
+}
+
+export const getUploadConfig = () => ({
+  maxBytes: Math.round(readPositive(process.env.UPLOAD_MAX_MB, DEFAULT_MAX_UPLOAD_MB) * MB),
+  maxPartBytes: MAX_PART_MB * MB,
+  ttlMs: readPositive(process.env.UPLOAD_TTL_HOURS, DEFAULT_TTL_HOURS) * 3600 * 1000,
+})
+
+const invalid = (message, details) =>
+  new QurioError(ErrorCode.InvalidRequest, message, details ? { details } : undefined)
+
+const notFound = id =>
+  new QurioError(ErrorCode.InvalidRequest, `Upload not found: ${id}`, { status: 404 })
+
+const partsDir = id => path.join(resolveCollectionDir(COLLECTION), `${id}.parts`)
+const filePath = id => path.join(resolveCollectionDir(COLLECTION), `${id}.bin`)
+const partPath = (id, index) => path.join(partsDir(id), String(index))
+
+const sha256 = buffer => crypto.createHash('sha256').update(buffer).digest('hex')
+
+const receivedParts = id => {
+  const dir = partsDir(id)
+  if (!fs.existsSync(dir)) return []
+  return fs
+    .readdirSync(dir)
+    .filter(name => /^\d+$/.test(name))
+    .map(Number)
+    .sort((a, b) => a - b)
+}
+
+/**
+ * Public shape of an upload; "missing_parts" is what a resuming client still has to send
+ */
+const toPublicUpload = record => {
+  const received = record.status === 'pending' ? receivedParts(record.id) : []
+  return {
+    id: record.id,
+    filename: record.filename,
+    mime_type: record.mime_type,
+    size: record.size,
+    sha256: record.sha256,
+    part_size: record.part_size,
+    total_parts: record.total_parts,
+    received_parts: record.status === 'pending' ? received : undefined,
+    missing_parts:
+      record.status === 'pending'
+        ? Array.from({ length: record.total_parts }, (_, index) => index).filter(
+            index => !received.includes(index),
+          )
+        : undefined,
+    status: record.status,
+    error: record.error || null,
+    created_at: record.created_at,
+    completed_at: record.completed_at || null,
+    expires_at: record.expires_at || null,
+  }
+}
+
+export const getUpload = id => {
+  const record = readRecord(COLLECTION, id)
+  return record ? toPublicUpload(record) : null
+}
+
+export const listUploads = () =>
+  listRecords(COLLECTION)
+    .sort((a, b) => String(b.created_at).localeCompare(String(a.created_at)))
+    .map(toPublicUpload)
+
+/**
+ * Declare a file to upload
+ * @param {Object} args
+ * @param {string} args.filename
+ * @param {number} args.size - Total bytes
+ * @param {string} args.sha256 - Hex SHA-256 of the whole file
+ * @param {string} [args.mimeType]
+ * @param {number} [args.partSize] - Bytes per part (default 5 MB, at most 16 MB)
+ */
+export const initUpload = ({ filename, size, sha256: checksum, mimeType, partSize }) => {
+  const { maxBytes, maxPartBytes, ttlMs } = getUploadConfig()
+  const errors = []
+  const bytes = Number(size)
+  const chunk = partSize === undefined ? DEFAULT_PART_MB * MB : Number(partSize)
+  if (!filename || typeof filename !== 'string') errors.push('filename is required')
+  if (!Number.isInteger(bytes) || bytes <= 0) errors.push('size must be a positive integer')
+  else if (bytes > maxBytes) errors.push(`size exceeds the upload limit of ${maxBytes} bytes`)
+  if (!SHA256_PATTERN.test(String(checksum || '').toLowerCase())) {
+    errors.push('sha256 must be the hex SHA-256 of the file')
+  }
+  if (!Number.isInteger(chunk) || chunk < 64 * 1024 || chunk > maxPartBytes) {
+    errors.push(`partSize must be between 65536 and ${maxPartBytes} bytes`)
+  }
+  if (errors.length) throw invalid('Invalid upload', errors)
+
+  pruneExpiredUploads()
+  const id = crypto.randomUUID()
+  const now = Date.now()
+  fs.mkdirSync(partsDir(id), { recursive: true })
+  return toPublicUpload(
+    writeRecord(COLLECTION, id, {
+      id,
+      filename: path.basename(filename),
+      mime_type: mimeType || 'application/octet-stream',
+      size: bytes,
+      sha256: String(checksum).toLowerCase(),
+      part_size: chunk,
+      total_parts: Math.ceil(bytes / chunk),
+      status: 'pending',
+      created_at: new Date(now).toISOString(),
+      expires_at: new Date(now + ttlMs).toISOString(),
+    }),
+  )
+}
+
+/**
+ * Store one part; re-sending a part replaces it, so failed parts can simply be retried
+ * @param {string} id
+ * @param {number} index - Zero-based part number
+ * @param {Buffer} buffer
+ * @param {Object} [options]
+ * @param {string} [options.sha256] - Hex SHA-256 of this part, verified when given
+ */
+export const writeUploadPart = async (id, index, buffer, { sha256: checksum } = {}) => {
+  const record = readRecord(COLLECTION, id)
+  if (!record) throw notFound(id)
+  if (record.status !== 'pending') {
+    throw invalid(`Upload is already ${record.status}: ${id}`)
+  }
+  const partIndex = Number(index)
+  if (!Number.isInteger(partIndex) || partIndex < 0 || partIndex >= record.total_parts) {
+    throw invalid(`Part number must be between 0 and ${record.total_parts - 1}`)
+  }
+  const isLast = partIndex === record.total_parts - 1
+  const expected = isLast ? record.size - record.part_size * partIndex : record.part_size
+  if (!Buffer.isBuffer(buffer) || buffer.length !== expected) {
+    throw invalid(`Part ${partIndex} must be ${expected} bytes, got ${buffer?.length || 0}`)
+  }
+  const digest = sha256(buffer)
+  if (checksum && String(checksum).toLowerCase() !== digest) {
+    throw invalid(`Checksum mismatch for part ${partIndex}`)
+  }
+  // Temp file + rename, so a half-written part is never counted as received
+  const target = partPath(id, partIndex)
+  const tempPath = `${target}.${crypto.randomUUID()}.tmp`
+  await fs.promises.writeFile(tempPath, buffer)
+  await fs.promises.rename(tempPath, target)
+  return { part: partIndex, bytes: buffer.length, sha256: digest, upload: getUpload(id) }
+}
+
+const assemble = async record => {
+  const hash = crypto.createHash('sha256')
+  const tempPath = `${filePath(record.id)}.${process.pid}.tmp`
+  const output = fs.createWriteStream(tempPath)
+  try {
+    for (let index = 0; index < record.total_parts; index += 1) {
+      const input = fs.createReadStream(partPath(record.id, index))
+      input.on('data', data => hash.update(data))
+      await pipeline(input, output, { end: false })
+    }
+    await new Promise((resolve, reject) => output.end(error => (error ? reject(error) : resolve())))
+    const digest = hash.digest('hex')
+    if (digest !== record.sha256) {
+      throw new Error(`Checksum mismatch: expected ${record.sha256}, got ${digest}`)
+    }
+    await fs.promises.rename(tempPath, filePath(record.id))
+    await fs.promises.rm(partsDir(record.id), { recursive: true, force: true })
+    writeRecord(COLLECTION, record.id, {
+      ...record,
+      status: 'complete',
+      completed_at: new Date().toISOString(),
+      expires_at: null,
+    })
+  } catch (error) {
+    output.destroy()
+    await fs.promises.rm(tempPath, { force: true })
+    console.warn(`[Uploads] Assembly of ${record.id} failed:`, error.message)
+    // Back to pending so the client can re-send parts and complete again
+    writeRecord(COLLECTION, record.id, { ...record, status: 'pending', error: error.message })
+  }
+}
+
+/**
+ * Finish an upload once every part is stored; assembly runs as a background task
+ * (status "assembling", then "complete" or back to "pending" with an error)
+ * @returns {{ upload: Object, task: Object|null, done: Promise<Object> }} done resolves with the
+ *   final upload state
+ */
+export const completeUpload = id => {
+  const record = readRecord(COLLECTION, id)
+  if (!record) throw notFound(id)
+  if (record.status === 'complete') {
+    return { upload: toPublicUpload(record), task: null, done: Promise.resolve(getUpload(id)) }
+  }
+  const missing = toPublicUpload(record).missing_parts
+  if (record.status === 'pending' && missing.length) {
+    throw invalid(`Upload is missing ${missing.length} part(s)`, { missing_parts: missing })
+  }
+  const assembling =
+    record.status === 'assembling'
+      ? record
+      : writeRecord(COLLECTION, id, { ...record, status: 'assembling', error: null })
+  const task = taskQueue.enqueue(
+    'upload.assemble',
+    { id },
+    { title: `Assemble ${record.filename}`, dedupeKey: `upload:${id}` },
+  )
+  const done = taskQueue.wait(task.id).then(() => getUpload(id))
+  return { upload: toPublicUpload(assembling), task, done }
+}
+
+taskQueue.registerHandler('upload.assemble', async ({ id }, { progress }) => {
+  const record = readRecord(COLLECTION, id)
+  if (record?.status !== 'assembling') return getUpload(id)
+  progress({ current: 0, total: record.total_parts, message: 'Assembling parts' })
+  await assemble(record)
+  const upload = getUpload(id)
+  if (upload?.status !== 'complete') throw new Error(upload?.error || 'Assembly failed')
+  return { upload_id: id, size: upload.size, sha256: upload.sha256 }
+})
+
+export const deleteUpload = id => {
+  if (!readRecord(COLLECTION, id)) return false
+  fs.rmSync(partsDir(id), { recursive: true, force: true })
+  fs.rmSync(filePath(id), { force: true })
+  return deleteRecord(COLLECTION, id)
+}
+
+/**
+ * Bytes of a completed upload
+ * @returns {{ buffer: Buffer, filename: string, mimeType: string }}
+ * @throws {QurioError} when the upload does not exist or is not complete
+ */
+export const readUploadFile = id => {
+  const record = id ? readRecord(COLLECTION, String(id)) : null
+  if (!record) throw notFound(id)
+  if (record.status !== 'complete' || !fs.existsSync(filePath(record.id))) {
+    throw invalid(`Upload is not complete: ${id}`)
+  }
+  return {
+    buffer: fs.readFileSync(filePath(record.id)),
+    filename: record.filename,
+    mimeType: record.mime_type,
+  }
+}
+
+/**
+ * Drop unfinished uploads past their expiry (completed uploads stay until deleted)
+ */
+export const pruneExpiredUploads = (now = Date.now()) => {
+  let removed = 0
+  for (const record of listRecords(COLLECTION)) {
+    if (record.status === 'complete' || !record.expires_at) continue
+    if (Date.parse(record.expires_at) > now) continue
+    if (deleteUpload(record.id)) removed += 1
+  }
+  return removed
+}
+
+backgroundJobManager.registerJob({
+  name: 'upload-cleanup',
+  description: 'Remove unfinished chunked uploads past their expiry',
+  intervalMs: 3600 * 1000,
+  handler: async () => {
+    const removed = pruneExpiredUploads()
+    if (removed) console.log(`[Uploads] Removed ${removed} expired upload(s)`)
+  },
+})
@@ -0,0 +1,111 @@
+/**
+ * Usage ledger
+ * Records token usage from finished chat, research plan, and deep research streams per
+ * conversation, and aggregates tokens and estimated cost by provider/model.
+ */
+
+import { listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import { estimateCost } from './modelPricing.js'
+
+const COLLECTION = 'usage-ledger'
+// Streams sent without a conversation id are booked here
+const UNASSIGNED = '_unassigned'
+
+const emptyTotals = () => ({
+  requests: 0,
+  prompt_tokens: 0,
+  completion_tokens: 0,
+  total_tokens: 0,
+  estimated_cost_usd: 0,
+  unpriced_requests: 0,
+})
+
+const addEntry = (totals, entry) => {
+  totals.requests += 1
+  totals.prompt_tokens += entry.prompt_tokens
+  totals.completion_tokens += entry.completion_tokens
+  totals.total_tokens += entry.total_tokens
+  if (entry.estimated_cost_usd === null) totals.unpriced_requests += 1
+  else totals.estimated_cost_usd += entry.estimated_cost_usd
+  return totals
+}
+
+const roundCost = totals => ({
+  ...totals,
+  estimated_cost_usd: Number(totals.estimated_cost_usd.toFixed(6)),
+})
+
+/**
+ * Append one finished request to the ledger; never throws (accounting must not break streams)
+ * @param {Object} args
+ * @param {string} [args.conversationId]
+ * @param {string} args.kind 'chat' | 'research_plan' | 'deep_research'
+ * @param {Object} args.usage { prompt_tokens, completion_tokens, total_tokens }
+ */
+export const recordUsage = ({ conversationId, kind, provider, model, usage }) => {
+  if (!usage?.total_tokens) return null
+  try {
+    const id = conversationId ? String(conversationId) : UNASSIGNED
+    const record = readRecord(COLLECTION, id) || { conversation_id: id, entries: [] }
+    const entry = {
+      at: new Date().toISOString(),
+      kind,
+      provider,
+      model: model || null,
+      prompt_tokens: usage.prompt_tokens || 0,
+      completion_tokens: usage.completion_tokens || 0,
+      total_tokens: usage.total_tokens,
+      estimated_cost_usd: estimateCost({ provider, model, usage }),
+    }
+    record.entries.push(entry)
+    writeRecord(COLLECTION, id, record)
+    return entry
+  } catch (error) {
+    console.warn('[UsageLedger] Failed to record usage:', error.message)
+    return null
+  }
+}
+
+/**
+ * Aggregate usage, optionally for one conversation and/or a time window
+ * @returns {{ totals, by_model: Array, by_conversation: Array }}
+ */
+export const summarizeUsage = ({ conversationId, from, to } = {}) => {
+  const fromTime = from ? Date.parse(from) : null
+  const toTime = to ? Date.parse(to) : null
+  const records = conversationId
+    ? [readRecord(COLLECTION, String(conversationId))].filter(Boolean)
+    : listRecords(COLLECTION)
+
+  const totals = emptyTotals()
+  const byModel = new Map()
+  const byConversation = []
+  for (const record of records) {
+    const conversationTotals = emptyTotals()
+    for (const entry of record.entries || []) {
+      const time = Date.parse(entry.at)
+      if ((fromTime && time < fromTime) || (toTime && time > toTime)) continue
+      const key = `${entry.provider}|${entry.model || ''}`
+      if (!byModel.has(key)) {
+        byModel.set(key, { provider: entry.provider, model: entry.model, ...emptyTotals() })
+      }
+      addEntry(byModel.get(key), entry)
+      addEntry(conversationTotals, entry)
+      addEntry(totals, entry)
+    }
+    if (conversationTotals.requests) {
+      byConversation.push({
+        conversation_id: record.conversation_id === UNASSIGNED ? null : record.conversation_id,
+        ...roundCost(conversationTotals),
+      })
+    }
+  }
+
+  return {
+    totals: roundCost(totals),
+    by_model: Array.from(byModel.values())
+      .map(roundCost)
+      .sort((a, b) => b.total_tokens - a.total_tokens),
+    by_conversation: byConversation.sort((a, b) => b.total_tokens - a.total_tokens),
+  }
+}
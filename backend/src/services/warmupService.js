@@ -0,0 +1,198 @@
+/**
+ * Warmup service
+ * Pre-resolves DNS and opens TLS connections to provider endpoints, and optionally sends
+ * a 1-token ping to the default model, so the first real message does not pay that latency.
+ * Startup warmup is opt-in (WARMUP_ENABLED=1), as it contacts every provider in WARMUP_PROVIDERS
+ * (default all of them).
+ */
+
+import dns from 'dns'
+import { DEFAULT_MODELS, PROVIDER_BASE_URLS } from './providers/providerConfig.js'
+
+const DEFAULT_TIMEOUT_MS = 5000
+
+const parseList = value =>
+  String(value || '')
+    .split(',')
+    .map(item => item.trim())
+    .filter(Boolean)
+
+export const getWarmupConfig = () => {
+  const timeoutMs = Number.parseInt(process.env.WARMUP_TIMEOUT_MS, 10)
+  const providers = parseList(process.env.WARMUP_PROVIDERS)
+  return {
+    enabled: process.env.WARMUP_ENABLED === '1',
+    providers: providers.length ? providers : Object.keys(PROVIDER_BASE_URLS),
+    timeoutMs: Number.isFinite(timeoutMs) && timeoutMs > 0 ? timeoutMs : DEFAULT_TIMEOUT_MS,
+    ping: process.env.WARMUP_PING === '1',
+    pingProvider: process.env.WARMUP_PING_PROVIDER || '',
+    pingApiKey: process.env.WARMUP_PING_API_KEY || '',
+    pingModel: process.env.WARMUP_PING_MODEL || '',
+    pingBaseUrl: process.env.WARMUP_PING_BASE_URL || '',
+  }
+}
+
+const GEMINI_BASE = 'https://generativelanguage.googleapis.com'
+
+const resolveEndpoint = (provider, baseUrl) => {
+  if (baseUrl) return baseUrl
+  if (provider === 'gemini') return GEMINI_BASE
+  return PROVIDER_BASE_URLS[provider] || null
+}
+
+const withTimeout = (timeoutMs, signal) => {
+  const controller = new AbortController()
+  const timer = setTimeout(() => controller.abort(), timeoutMs)
+  signal?.addEventListener?.('abort', () => controller.abort(), { once: true })
+  return { signal: controller.signal, clear: () => clearTimeout(timer) }
+}
+
+/**
+ * Resolve DNS and complete a TLS handshake against one endpoint
+ */
+const warmEndpoint = async (provider, endpoint, timeoutMs) => {
+  const result = { provider, endpoint, dnsMs: null, connectMs: null, error: null }
+  try {
+    const { hostname } = new URL(endpoint)
+
+    const dnsStartedAt = Date.now()
+    await dns.promises.lookup(hostname)
+    result.dnsMs = Date.now() - dnsStartedAt
+
+    // Any HTTP status means the TLS session is up; the pooled keep-alive socket gets reused
+    const connectStartedAt = Date.now()
+    const timeout = withTimeout(timeoutMs)
+    try {
+      const response = await fetch(endpoint, { method: 'HEAD', signal: timeout.signal })
+      await response.arrayBuffer().catch(() => {})
+    } finally {
+      timeout.clear()
+    }
+    result.connectMs = Date.now() - connectStartedAt
+  } catch (error) {
+    result.error = error.name === 'AbortError' ? `Timed out after ${timeoutMs}ms` : error.message
+  }
+  return result
+}
+
+/**
+ * Send a 1-token completion so the provider has the model loaded before the first real request
+ */
+export const pingModel = async ({ provider, apiKey, baseUrl, model, timeoutMs, signal }) => {
+  const startedAt = Date.now()
+  const resolvedModel = model || DEFAULT_MODELS[provider] || DEFAULT_MODELS.openai
+  const timeout = withTimeout(timeoutMs || DEFAULT_TIMEOUT_MS * 2, signal)
+
+  try {
+    let response
+    if (provider === 'gemini') {
+      response = await fetch(
+        `${GEMINI_BASE}/v1beta/models/${encodeURIComponent(resolvedModel)}:generateContent`,
+        {
+          method: 'POST',
+          headers: { 'Content-Type': 'application/json', 'x-goog-api-key': apiKey },
+          body: JSON.stringify({
+            contents: [{ role: 'user', parts: [{ text: 'ping' }] }],
+            generationConfig: { maxOutputTokens: 1 },
+          }),
+          signal: timeout.signal,
+        },
+      )
+    } else {
+      const endpoint = (resolveEndpoint(provider, baseUrl) || PROVIDER_BASE_URLS.openai).replace(
+        /\/+$/,
+        '',
+      )
+      response = await fetch(`${endpoint}/chat/completions`, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json', Authorization: `Bearer ${apiKey}` },
+        body: JSON.stringify({
+          model: resolvedModel,
+          messages: [{ role: 'user', content: 'ping' }],
+          max_tokens: 1,
+          stream: false,
+        }),
+        signal: timeout.signal,
+      })
+    }
+    await response.arrayBuffer().catch(() => {})
+    return {
+      provider,
+      model: resolvedModel,
+      ok: response.ok,
+      status: response.status,
+      latencyMs: Date.now() - startedAt,
+    }
+  } catch (error) {
+    return {
+      provider,
+      model: resolvedModel,
+      ok: false,
+      error: error.name === 'AbortError' ? 'Ping timed out' : error.message,
+      latencyMs: Date.now() - startedAt,
+    }
+  } finally {
+    timeout.clear()
+  }
+}
+
+let lastReport = null
+
+export const getLastWarmupReport = () => lastReport
+
+/**
+ * Run warmup for the configured providers
+ * @param {Object} overrides - { providers, ping: { provider, apiKey, baseUrl, model } }
+ */
+export const runWarmup = async (overrides = {}) => {
+  const config = getWarmupConfig()
+  const providers =
+    Array.isArray(overrides.providers) && overrides.providers.length
+      ? overrides.providers
+      : config.providers
+  const startedAt = Date.now()
+
+  const endpoints = providers
+    .map(provider => {
+      const baseUrl = provider === overrides.ping?.provider ? overrides.ping?.baseUrl : ''
+      return { provider, endpoint: resolveEndpoint(provider, baseUrl) }
+    })
+    .filter(item => item.endpoint)
+
+  const connections = await Promise.all(
+    endpoints.map(({ provider, endpoint }) => warmEndpoint(provider, endpoint, config.timeoutMs)),
+  )
+
+  const pingParams = overrides.ping?.apiKey
+    ? overrides.ping
+    : config.ping && config.pingProvider && config.pingApiKey
+      ? {
+          provider: config.pingProvider,
+          apiKey: config.pingApiKey,
+          baseUrl: config.pingBaseUrl,
+          model: config.pingModel,
+        }
+      : null
+  const ping = pingParams
+    ? await pingModel({ ...pingParams, timeoutMs: config.timeoutMs * 2 })
+    : null
+
+  lastReport = {
+    startedAt: new Date(startedAt).toISOString(),
+    durationMs: Date.now() - startedAt,
+    connections,
+    ping,
+  }
+
+  const warmed = connections.filter(item => !item.error).length
+  console.log(
+    `[Warmup] ${warmed}/${connections.length} endpoints warmed in ${lastReport.durationMs}ms`,
+  )
+  if (ping) {
+    console.log(
+      `[Warmup] Ping ${ping.provider}/${ping.model}:`,
+      ping.ok ? `${ping.latencyMs}ms` : ping.error || `HTTP ${ping.status}`,
+    )
+  }
+  return lastReport
+}
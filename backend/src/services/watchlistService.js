@@ -0,0 +1,428 @@
+/**
+ * Watchlist service
+ * Saved research questions ("watch items") with their deep research settings. Each item can be
+ * re-run on demand or on a schedule; every run stores a report in the item's history together
+ * with a diff against the previous report (paragraphs added/removed, sources gained/lost), so
+ * users can follow how the answer to a question changes over time.
+ * Scheduled items run from the "watchlist" background job (see backgroundService).
+ */
+
+import crypto from 'crypto'
+import { deleteRecord, listRecords, readRecord, writeRecord } from '../utils/dataStore.js'
+import { ErrorCode, QurioError } from '../utils/errors.js'
+import { backgroundJobManager } from './backgroundService.js'
+import { streamDeepResearch } from './deepResearchAgentService.js'
+import { decryptSecret, encryptSecret, maskSecret, resolveSpaceCredentials } from './keyVault.js'
+import { requiresApiKey } from './providers/providerConfig.js'
+import { taskQueue } from './taskQueue.js'
+import { recordUsage } from './usageLedger.js'
+
+const COLLECTION = 'watchlist'
+const REPORTS_COLLECTION = 'watchlist-reports'
+const DEFAULT_TICK_MS = 15 * 60 * 1000
+const MAX_HISTORY = 20
+const MIN_INTERVAL_HOURS = 1
+const MAX_QUESTION_CHARS = 2000
+
+// Deep research options a watch item may pin (see POST /api/stream-deep-research)
+const SETTING_KEYS = [
+  'researchType',
+  'reportStyle',
+  'decompose',
+  'glossary',
+  'timeline',
+  'numericCheck',
+  'stalenessDays',
+  'sourceBias',
+  'sourceClusters',
+  'noveltyThreshold',
+  'similarityCheck',
+  'citationCheck',
+  'proofread',
+  'concurrentExecution',
+  'maxParallelSteps',
+  'entities',
+  'criteria',
+  'toolIds',
+  'searchProvider',
+  'searchBaseUrl',
+  'temperature',
+  'userId',
+]
+
+const running = new Set()
+
+const now = () => new Date().toISOString()
+
+const invalid = message => new QurioError(ErrorCode.InvalidRequest, message)
+
+export const getWatchlistConfig = () => {
+  const tickMs = Number.parseInt(process.env.WATCHLIST_TICK_MS, 10)
+  return { tickMs: Number.isFinite(tickMs) && tickMs > 0 ? tickMs : DEFAULT_TICK_MS }
+}
+
+const pickSettings = settings =>
+  Object.fromEntries(
+    SETTING_KEYS.filter(key => settings?.[key] !== undefined).map(key => [key, settings[key]]),
+  )
+
+const normalizeInterval = value => {
+  if (value === undefined || value === null || value === '') return null
+  const hours = Number(value)
+  if (!Number.isFinite(hours) || hours < MIN_INTERVAL_HOURS) {
+    throw invalid(`interval_hours must be at least ${MIN_INTERVAL_HOURS} (or null for on demand)`)
+  }
+  return hours
+}
+
+const nextRunAt = (intervalHours, from = Date.now()) =>
+  intervalHours ? new Date(from + intervalHours * 60 * 60 * 1000).toISOString() : null
+
+const toPublicItem = ({ api_key_encrypted: encrypted, tavily_key_encrypted: tavily, ...item }) => ({
+  ...item,
+  api_key_masked: encrypted ? maskSecret(decryptSecret(encrypted)) : null,
+  has_tavily_key: Boolean(tavily),
+  running: running.has(item.id),
+})
+
+export const getWatchItem = id => {
+  const item = readRecord(COLLECTION, id)
+  return item ? toPublicItem(item) : null
+}
+
+export const listWatchItems = () =>
+  listRecords(COLLECTION)
+    .map(toPublicItem)
+    .sort((a, b) => String(b.updated_at).localeCompare(String(a.updated_at)))
+
+/**
+ * Save a research question to re-run later
+ * @param {Object} args
+ * @param {string} args.question
+ * @param {string} args.provider
+ * @param {string} [args.apiKey] Stored encrypted; not needed with a space that pins credentials
+ * @param {string} [args.spaceId] Space whose pinned credentials are used at run time
+ * @param {string} [args.tavilyApiKey] Stored encrypted, for the web search tool
+ * @param {number|null} [args.intervalHours] Re-run schedule; null for on demand only
+ * @param {Object} [args.settings] Deep research options (researchType, reportStyle, ...)
+ */
+export const createWatchItem = ({
+  question,
+  provider,
+  apiKey,
+  baseUrl,
+  model,
+  spaceId,
+  tavilyApiKey,
+  intervalHours,
+  settings,
+}) => {
+  const text = String(question || '').trim()
+  if (!text) throw invalid('Missing required field: question')
+  if (text.length > MAX_QUESTION_CHARS) {
+    throw invalid(`question must be at most ${MAX_QUESTION_CHARS} characters`)
+  }
+  if (!provider && !spaceId) throw invalid('Missing required field: provider')
+  if (!apiKey && !spaceId && requiresApiKey(provider)) {
+    throw invalid('Missing required field: apiKey')
+  }
+  const interval = normalizeInterval(intervalHours)
+  const timestamp = now()
+  const item = {
+    id: crypto.randomUUID(),
+    question: text,
+    provider: provider || null,
+    base_url: baseUrl || null,
+    model: model || null,
+    space_id: spaceId || null,
+    api_key_encrypted: apiKey ? encryptSecret(apiKey) : null,
+    tavily_key_encrypted: tavilyApiKey ? encryptSecret(tavilyApiKey) : null,
+    settings: pickSettings(settings),
+    interval_hours: interval,
+    next_run_at: nextRunAt(interval),
+    last_run_at: null,
+    last_status: null,
+    last_error: null,
+    report_count: 0,
+    created_at: timestamp,
+    updated_at: timestamp,
+  }
+  writeRecord(COLLECTION, item.id, item)
+  return toPublicItem(item)
+}
+
+/**
+ * Update question, credentials, schedule or settings of a watch item
+ * @returns {Object|null} null when the item does not exist
+ */
+export const updateWatchItem = (id, patch = {}) => {
+  const item = readRecord(COLLECTION, id)
+  if (!item) return null
+  const next = { ...item, updated_at: now() }
+  if (patch.question !== undefined) {
+    const text = String(patch.question || '').trim()
+    if (!text) throw invalid('question must not be empty')
+    next.question = text
+  }
+  if (patch.provider !== undefined) next.provider = patch.provider || null
+  if (patch.baseUrl !== undefined) next.base_url = patch.baseUrl || null
+  if (patch.model !== undefined) next.model = patch.model || null
+  if (patch.spaceId !== undefined) next.space_id = patch.spaceId || null
+  if (patch.apiKey !== undefined) {
+    next.api_key_encrypted = patch.apiKey ? encryptSecret(patch.apiKey) : null
+  }
+  if (patch.tavilyApiKey !== undefined) {
+    next.tavily_key_encrypted = patch.tavilyApiKey ? encryptSecret(patch.tavilyApiKey) : null
+  }
+  if (patch.settings !== undefined) next.settings = pickSettings(patch.settings)
+  if (patch.intervalHours !== undefined) {
+    next.interval_hours = normalizeInterval(patch.intervalHours)
+    next.next_run_at = nextRunAt(next.interval_hours)
+  }
+  writeRecord(COLLECTION, id, next)
+  return toPublicItem(next)
+}
+
+export const deleteWatchItem = id => {
+  deleteRecord(REPORTS_COLLECTION, id)
+  return deleteRecord(COLLECTION, id)
+}
+
+const readHistory = id => readRecord(REPORTS_COLLECTION, id) || { item_id: id, reports: [] }
+
+const toReportSummary = ({ content, sources, diff, ...report }) => ({
+  ...report,
+  source_count: sources?.length || 0,
+  diff: diff?.summary || null,
+})
+
+/**
+ * Report history of a watch item, newest first, without report bodies
+ */
+export const listWatchReports = id =>
+  readHistory(id).reports.map(toReportSummary).reverse()
+
+export const getWatchReport = (id, reportId) =>
+  readHistory(id).reports.find(report => report.id === reportId) || null
+
+// --- Diffing -------------------------------------------------------------------
+
+const splitParagraphs = text =>
+  String(text || '')
+    .split(/\n\s*\n/)
+    .map(paragraph => paragraph.replace(/\s+/g, ' ').trim())
+    .filter(Boolean)
+
+// Longest common subsequence of paragraphs; reports are at most a few hundred paragraphs
+const diffParagraphs = (before, after) => {
+  const rows = before.length + 1
+  const cols = after.length + 1
+  const lengths = Array.from({ length: rows }, () => new Uint16Array(cols))
+  for (let i = before.length - 1; i >= 0; i -= 1) {
+    for (let j = after.length - 1; j >= 0; j -= 1) {
+      lengths[i][j] =
+        before[i] === after[j]
+          ? lengths[i + 1][j + 1] + 1
+          : Math.max(lengths[i + 1][j], lengths[i][j + 1])
+    }
+  }
+  const added = []
+  const removed = []
+  let i = 0
+  let j = 0
+  while (i < before.length && j < after.length) {
+    if (before[i] === after[j]) {
+      i += 1
+      j += 1
+    } else if (lengths[i + 1][j] >= lengths[i][j + 1]) {
+      removed.push(before[i])
+      i += 1
+    } else {
+      added.push(after[j])
+      j += 1
+    }
+  }
+  removed.push(...before.slice(i))
+  added.push(...after.slice(j))
+  return { added, removed, unchanged: lengths[0][0] }
+}
+
+const sourceUrls = report =>
+  new Set((report?.sources || []).map(source => source?.url).filter(Boolean))
+
+/**
+ * Compare two reports of a watch item
+ * @returns {{ summary, added: string[], removed: string[], sources_added, sources_removed }}
+ */
+export const diffReports = (previous, current) => {
+  const { added, removed, unchanged } = diffParagraphs(
+    splitParagraphs(previous?.content),
+    splitParagraphs(current?.content),
+  )
+  const before = sourceUrls(previous)
+  const after = sourceUrls(current)
+  const sourcesAdded = Array.from(after).filter(url => !before.has(url))
+  const sourcesRemoved = Array.from(before).filter(url => !after.has(url))
+  return {
+    summary: {
+      compared_to: previous?.id || null,
+      paragraphs_added: added.length,
+      paragraphs_removed: removed.length,
+      paragraphs_unchanged: unchanged,
+      sources_added: sourcesAdded.length,
+      sources_removed: sourcesRemoved.length,
+      changed: Boolean(
+        added.length || removed.length || sourcesAdded.length || sourcesRemoved.length,
+      ),
+    },
+    added,
+    removed,
+    sources_added: sourcesAdded,
+    sources_removed: sourcesRemoved,
+  }
+}
+
+// --- Running -------------------------------------------------------------------
+
+const resolveCredentials = item => {
+  const pinned = resolveSpaceCredentials(item.space_id)
+  if (pinned) return pinned
+  return {
+    provider: item.provider,
+    apiKey: item.api_key_encrypted ? decryptSecret(item.api_key_encrypted) : undefined,
+    baseUrl: item.base_url || undefined,
+    model: item.model || undefined,
+  }
+}
+
+const finishRun = (id, fields) => {
+  const item = readRecord(COLLECTION, id)
+  if (!item) return null
+  const finishedAt = Date.now()
+  const next = {
+    ...item,
+    ...fields,
+    last_run_at: new Date(finishedAt).toISOString(),
+    next_run_at: nextRunAt(item.interval_hours, finishedAt),
+  }
+  writeRecord(COLLECTION, id, next)
+  return next
+}
+
+/**
+ * Run a watch item's research now and append the report to its history
+ * @param {string} id
+ * @param {Object} [options]
+ * @param {AbortSignal} [options.signal]
+ * @param {Function} [options.onProgress] Called with { current, total, message } per research step
+ * @returns {Promise<Object>} The stored report (with its diff)
+ */
+export const runWatchItem = async (id, { signal, onProgress } = {}) => {
+  const item = readRecord(COLLECTION, id)
+  if (!item) {
+    throw new QurioError(ErrorCode.InvalidRequest, `Watch item not found: ${id}`, { status: 404 })
+  }
+  if (running.has(id)) throw invalid(`Watch item is already running: ${id}`)
+  running.add(id)
+  try {
+    const credentials = resolveCredentials(item)
+    const tavilyApiKey = item.tavily_key_encrypted
+      ? decryptSecret(item.tavily_key_encrypted)
+      : undefined
+    let done = null
+    for await (const event of streamDeepResearch({
+      ...item.settings,
+      ...credentials,
+      messages: [{ role: 'user', content: item.question }],
+      question: item.question,
+      tavilyApiKey,
+      signal,
+    })) {
+      if (event?.type === 'done') done = event
+      if (event?.type === 'research_step' && event.status === 'running') {
+        onProgress?.({ current: event.step - 1, total: event.total, message: event.title })
+      }
+    }
+    if (!done?.content) throw new Error('Research finished without a report')
+    recordUsage({
+      kind: 'deep_research',
+      provider: credentials.provider,
+      model: credentials.model,
+      usage: done.usage,
+    })
+
+    const history = readHistory(id)
+    const previous = history.reports[history.reports.length - 1]
+    const report = {
+      id: crypto.randomUUID(),
+      run_id: done.runId || null,
+      created_at: now(),
+      content: done.content,
+      sources: (done.sources || []).map(source => ({
+        title: source?.title || null,
+        url: source?.url || source?.uri || null,
+      })),
+    }
+    report.diff = previous ? diffReports(previous, report) : null
+    const reports = [...history.reports, report].slice(-MAX_HISTORY)
+    writeRecord(REPORTS_COLLECTION, id, { ...history, reports })
+    finishRun(id, { last_status: 'done', last_error: null, report_count: reports.length })
+    return report
+  } catch (error) {
+    finishRun(id, { last_status: signal?.aborted ? 'aborted' : 'error', last_error: error.message })
+    throw error
+  } finally {
+    running.delete(id)
+  }
+}
+
+/**
+ * Run every scheduled item whose next run is due, one at a time
+ */
+export const runDueWatchItems = async ({ signal } = {}) => {
+  const summary = { ran: 0, failed: 0 }
+  const due = listRecords(COLLECTION).filter(
+    item => item.next_run_at && item.next_run_at <= now() && !running.has(item.id),
+  )
+  for (const item of due) {
+    if (signal?.aborted) break
+    try {
+      await runWatchItem(item.id, { signal })
+      summary.ran += 1
+    } catch (error) {
+      summary.failed += 1
+      console.warn(`[Watchlist] Run failed for ${item.id}:`, error.message)
+    }
+  }
+  return summary
+}
+
+/**
+ * Queue a run as a background task (one per item at a time)
+ * @returns {Object} Task record (see taskQueue)
+ */
+export const enqueueWatchItemRun = id => {
+  const item = readRecord(COLLECTION, id)
+  return taskQueue.enqueue(
+    'watchlist.run',
+    { id },
+    { title: `Watchlist: ${item?.question || id}`, dedupeKey: `watchlist:${id}` },
+  )
+}
+
+taskQueue.registerHandler('watchlist.run', async ({ id }, { signal, progress }) => {
+  const report = await runWatchItem(id, { signal, onProgress: progress })
+  return { item_id: id, report_id: report.id, diff: report.diff?.summary || null }
+})
+
+backgroundJobManager.registerJob({
+  name: 'watchlist',
+  description: 'Re-run scheduled watchlist research questions and diff their reports',
+  intervalMs: getWatchlistConfig().tickMs,
+  handler: async () => {
+    const summary = await runDueWatchItems()
+    if (summary.ran || summary.failed) {
+      console.log('[Watchlist] Pass finished:', JSON.stringify(summary))
+    }
+  },
+})
@@ -0,0 +1,80 @@
+/**
+ * Wayback Machine service
+ * Looks up archived snapshots of a URL through the Internet Archive availability API and reads
+ * their content (used when the live page is gone or blocked).
+ */
+
+import { extractPageText, fetchWithTimeout } from './siteExplorerService.js'
+
+const AVAILABILITY_API = 'https://archive.org/wayback/available'
+const REQUEST_TIMEOUT_MS = 15000
+const MAX_CONTENT_CHARS = 50000
+
+// Wayback timestamps are YYYYMMDDhhmmss
+const toWaybackTimestamp = value => {
+  const date = value ? new Date(value) : null
+  return date && !Number.isNaN(date.getTime())
+    ? date.toISOString().replace(/[-:T]/g, '').slice(0, 14)
+    : undefined
+}
+
+const fromWaybackTimestamp = value => {
+  const match = String(value || '').match(/^(\d{4})(\d{2})(\d{2})(\d{2})?(\d{2})?(\d{2})?/)
+  if (!match) return null
+  const [, year, month, day, hour = '00', minute = '00', second = '00'] = match
+  return `${year}-${month}-${day}T${hour}:${minute}:${second}Z`
+}
+
+/**
+ * Closest archived snapshot of a URL
+ * @param {string} url
+ * @param {Object} [options]
+ * @param {string|Date} [options.near] Prefer the snapshot closest to this date
+ * @returns {Promise<{ url: string, archived_at: string, timestamp: string }|null>}
+ */
+export const findSnapshot = async (url, { near, signal } = {}) => {
+  const query = new URLSearchParams({ url })
+  const timestamp = toWaybackTimestamp(near)
+  if (timestamp) query.set('timestamp', timestamp)
+  const timeoutSignal = AbortSignal.timeout(REQUEST_TIMEOUT_MS)
+  const response = await fetch(`${AVAILABILITY_API}?${query}`, {
+    headers: { Accept: 'application/json' },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+  if (!response.ok) throw new Error(`Wayback availability request failed: HTTP ${response.status}`)
+  const data = await response.json()
+  const closest = data?.archived_snapshots?.closest
+  if (!closest?.available || !closest.url || String(closest.status || '200') !== '200') return null
+  return {
+    url: closest.url.replace(/^http:/, 'https:'),
+    archived_at: fromWaybackTimestamp(closest.timestamp),
+    timestamp: closest.timestamp,
+  }
+}
+
+// "id_" serves the page as originally captured, without the Wayback toolbar and link rewriting
+const rawSnapshotUrl = (timestamp, url) => `https://web.archive.org/web/${timestamp}id_/${url}`
+
+/**
+ * Read the closest archived copy of a URL
+ * @param {string} url
+ * @param {Object} [options]
+ * @param {string|Date} [options.near] Prefer the snapshot closest to this date
+ * @returns {Promise<{ url, archived_url, archived_at, title, content }>}
+ */
+export const readArchivedPage = async (url, { near, signal } = {}) => {
+  const snapshot = await findSnapshot(url, { near, signal })
+  if (!snapshot) throw new Error(`No archived snapshot found for ${url}`)
+  const response = await fetchWithTimeout(rawSnapshotUrl(snapshot.timestamp, url), signal)
+  if (!response.ok) throw new Error(`Wayback snapshot request failed: HTTP ${response.status}`)
+  const body = await response.text()
+  const isHtml = /html/i.test(response.headers.get('content-type') || '')
+  const { title, text } = isHtml ? extractPageText(body) : { title: '', text: body }
+  return {
+    url,
+    archived_url: snapshot.url,
+    archived_at: snapshot.archived_at,
+    title: title || url,
+    content: text.slice(0, MAX_CONTENT_CHARS),
+  }
+}
@@ -0,0 +1,309 @@
+/**
+ * Webpage reader
+ * Backs the webpage_reader tool: fetches a page directly (with a timeout and a size cap), extracts
+ * its main content as Markdown with its metadata (see htmlReadability.js), and returns it one
+ * page of text at a time. Pages that are not HTML (PDF, ...) or that render client-side go
+ * through the Jina reader; a page that cannot be fetched falls back to its latest Wayback Machine
+ * copy. Extracted documents are cached briefly so reading the next page does not refetch. Pages
+ * on private or local addresses are refused (see utils/publicFetch.js).
+ *
+ * WEBPAGE_READER_TIMEOUT_MS (default 15000), WEBPAGE_READER_MAX_MB (default 5), and
+ * WEBPAGE_READER_PAGE_CHARS (default 20000) configure it.
+ */
+
+import { fetchPublicUrl, UnsafeUrlError } from '../utils/publicFetch.js'
+import { extractReadableContent } from './htmlReadability.js'
+import { readArchivedPage } from './waybackService.js'
+
+const USER_AGENT = 'QurioBot/1.0 (+https://github.com/havingautism/Qurio)'
+const JINA_READER_URL = 'https://r.jina.ai/'
+const DEFAULT_TIMEOUT_MS = 15000
+const DEFAULT_MAX_MB = 5
+const DEFAULT_PAGE_CHARS = 20000
+const MIN_PAGE_CHARS = 1000
+// Less extracted text than this usually means the page renders its content with JavaScript
+const MIN_READABLE_CHARS = 200
+const CACHE_TTL_MS = 10 * 60 * 1000
+const MAX_CACHED_DOCUMENTS = 50
+
+const readPositiveNumber = (name, fallback) => {
+  const value = Number.parseFloat(process.env[name])
+  return Number.isFinite(value) && value > 0 ? value : fallback
+}
+
+export const getWebpageReaderConfig = () => ({
+  timeoutMs: readPositiveNumber('WEBPAGE_READER_TIMEOUT_MS', DEFAULT_TIMEOUT_MS),
+  maxBytes: readPositiveNumber('WEBPAGE_READER_MAX_MB', DEFAULT_MAX_MB) * 1024 * 1024,
+  pageChars: Math.max(
+    MIN_PAGE_CHARS,
+    Math.floor(readPositiveNumber('WEBPAGE_READER_PAGE_CHARS', DEFAULT_PAGE_CHARS)),
+  ),
+})
+
+// Map iteration order is insertion order: the first key is the oldest entry
+const documents = new Map()
+
+const readCachedDocument = url => {
+  const entry = documents.get(url)
+  if (!entry) return null
+  if (entry.expiresAt > Date.now()) return entry.document
+  documents.delete(url)
+  return null
+}
+
+const cacheDocument = (url, document) => {
+  documents.delete(url)
+  documents.set(url, { document, expiresAt: Date.now() + CACHE_TTL_MS })
+  while (documents.size > MAX_CACHED_DOCUMENTS) documents.delete(documents.keys().next().value)
+}
+
+/**
+ * The http(s) URL to read (a scheme-less URL is read over https; an r.jina.ai prefix is dropped)
+ */
+export const normalizeWebpageUrl = value => {
+  const input = String(value || '')
+    .trim()
+    .replace(/^https?:\/\/r\.jina\.ai\//i, '')
+  let url
+  try {
+    url = new URL(/^[a-z][a-z\d+.-]*:/i.test(input) ? input : `https://${input}`)
+  } catch {
+    throw new Error(`Invalid URL: ${value}`)
+  }
+  if (url.protocol !== 'http:' && url.protocol !== 'https:') {
+    throw new Error(`Unsupported URL scheme: ${url.protocol}`)
+  }
+  url.hash = ''
+  return url.href
+}
+
+/**
+ * Split Markdown into pages of at most pageChars characters
+ * Pages break after a blank line where possible, then after a line, then after a space, so
+ * joining the pages gives back the text unchanged.
+ * @returns {string[]} At least one page
+ */
+export const paginateMarkdown = (markdown, pageChars) => {
+  const text = String(markdown || '')
+  const limit = Math.max(1, Math.floor(pageChars))
+  const pages = []
+  let start = 0
+  while (text.length - start > limit) {
+    const window = text.slice(start, start + limit)
+    const minimum = limit / 2
+    const paragraph = window.lastIndexOf('\n\n')
+    const line = window.lastIndexOf('\n')
+    const space = window.lastIndexOf(' ')
+    let end = start + limit
+    if (paragraph > minimum) end = start + paragraph + 2
+    else if (line > minimum) end = start + line + 1
+    else if (space > minimum) end = start + space + 1
+    else if (/[\uD800-\uDBFF]/.test(text[end - 1]) && end - 1 > start) end -= 1
+    pages.push(text.slice(start, end))
+    start = end
+  }
+  pages.push(text.slice(start))
+  return pages
+}
+
+const fetchWithLimits = (url, { headers, signal, timeoutMs }) => {
+  const timeoutSignal = AbortSignal.timeout(timeoutMs)
+  return fetchPublicUrl(url, {
+    headers: { 'User-Agent': USER_AGENT, ...headers },
+    signal: signal ? AbortSignal.any([signal, timeoutSignal]) : timeoutSignal,
+  })
+}
+
+// Read at most maxBytes of the body; the rest of a larger page is not downloaded
+const readBody = async (response, maxBytes) => {
+  const chunks = []
+  let size = 0
+  let truncated = false
+  if (response.body) {
+    for await (const chunk of response.body) {
+      const remaining = maxBytes - size
+      if (chunk.byteLength > remaining) {
+        chunks.push(chunk.subarray(0, remaining))
+        truncated = true
+        break
+      }
+      chunks.push(chunk)
+      size += chunk.byteLength
+    }
+  }
+  return { body: Buffer.concat(chunks), truncated }
+}
+
+// Charset from the Content-Type header, else from a <meta charset> near the top of the page
+const decodeBody = (body, contentType) => {
+  const charset =
+    contentType.match(/charset=["']?([\w:.-]+)/i)?.[1] ||
+    body
+      .subarray(0, 4096)
+      .toString('latin1')
+      .match(/<meta[^>]+charset=["']?([\w:.-]+)/i)?.[1]
+  try {
+    return new TextDecoder(charset || 'utf-8').decode(body)
+  } catch {
+    return new TextDecoder().decode(body)
+  }
+}
+
+const jinaHeaders = () => ({
+  Accept: 'text/plain',
+  ...(process.env.JINA_API_KEY ? { Authorization: `Bearer ${process.env.JINA_API_KEY}` } : {}),
+})
+
+/**
+ * Read a page through the Jina reader (renders JavaScript and converts PDFs)
+ * Its plain-text response starts with "Title:", "URL Source:" and "Published Time:" lines
+ * followed by "Markdown Content:".
+ */
+const readThroughJina = async (url, { signal, config }) => {
+  const response = await fetchWithLimits(`${JINA_READER_URL}${url}`, {
+    headers: jinaHeaders(),
+    signal,
+    timeoutMs: config.timeoutMs,
+  })
+  if (!response.ok) throw new Error(`Jina reader error: HTTP ${response.status}`)
+  const { body, truncated } = await readBody(response, config.maxBytes)
+  const text = body.toString('utf8')
+  const marker = text.indexOf('Markdown Content:')
+  const header = marker === -1 ? '' : text.slice(0, marker)
+  const field = name => header.match(new RegExp(`^${name}:\\s*(.+)$`, 'm'))?.[1].trim() || null
+  return {
+    title: field('Title'),
+    published_at: field('Published Time'),
+    final_url: field('URL Source') || url,
+    markdown: (marker === -1 ? text : text.slice(marker + 'Markdown Content:'.length)).trim(),
+    source: 'jina.ai',
+    truncated,
+  }
+}
+
+const readLivePage = async (url, { signal, config }) => {
+  const response = await fetchWithLimits(url, {
+    headers: { Accept: 'text/html,application/xhtml+xml,text/plain;q=0.9,*/*;q=0.8' },
+    signal,
+    timeoutMs: config.timeoutMs,
+  })
+  if (!response.ok) {
+    await response.body?.cancel().catch(() => {})
+    throw new Error(`HTTP ${response.status}`)
+  }
+  const contentType = response.headers.get('content-type') || ''
+  const finalUrl = response.url || url
+
+  if (!/html/i.test(contentType) && !/^text\/|json|xml/i.test(contentType)) {
+    // PDFs, office documents, ...: let the Jina reader convert them
+    await response.body?.cancel().catch(() => {})
+    return readThroughJina(finalUrl, { signal, config })
+  }
+
+  const { body, truncated } = await readBody(response, config.maxBytes)
+  const text = decodeBody(body, contentType)
+  if (!/html/i.test(contentType)) {
+    return { final_url: finalUrl, markdown: text.trim(), source: 'direct', truncated }
+  }
+
+  const { metadata, markdown } = extractReadableContent(text, finalUrl)
+  const page = { ...metadata, final_url: finalUrl, markdown, source: 'direct', truncated }
+  if (markdown.length >= MIN_READABLE_CHARS) return page
+  const rendered = await readThroughJina(finalUrl, { signal, config }).catch(() => null)
+  if (rendered && rendered.markdown.length > markdown.length) {
+    return { ...page, ...rendered, title: page.title || rendered.title }
+  }
+  if (!markdown) throw new Error('No readable content found')
+  return page
+}
+
+const readArchivedCopy = async (url, liveError, signal) => {
+  try {
+    const archived = await readArchivedPage(url, { signal })
+    return {
+      title: archived.title,
+      final_url: archived.archived_url,
+      markdown: archived.content,
+      source: 'wayback',
+      archived_url: archived.archived_url,
+      archived_at: archived.archived_at,
+      live_error: liveError.message,
+      truncated: false,
+    }
+  } catch (archiveError) {
+    throw new Error(
+      `Webpage read failed: ${liveError.message} (archive fallback: ${archiveError.message})`,
+    )
+  }
+}
+
+const loadDocument = async (url, { signal, config }) => {
+  const cached = readCachedDocument(url)
+  if (cached) return cached
+  let document
+  try {
+    document = await readLivePage(url, { signal, config })
+  } catch (error) {
+    // Live page unavailable: fall back to the latest archived copy
+    if (signal?.aborted || error instanceof UnsafeUrlError) throw error
+    document = await readArchivedCopy(url, error, signal)
+  }
+  cacheDocument(url, document)
+  return document
+}
+
+/**
+ * Read one page of a webpage's main content as Markdown
+ * @param {string} url
+ * @param {Object} [options]
+ * @param {number} [options.page] - 1-based page of the extracted text (default 1)
+ * @param {AbortSignal} [options.signal]
+ * @returns {Promise<Object>} Metadata (title, author, published_at, site_name, ...), paging
+ *   (page, total_pages, has_more, next_page_url), and the page text as
+ *   results: [{ title, url, content }]
+ */
+export const readWebpage = async (url, { page = 1, signal } = {}) => {
+  const config = getWebpageReaderConfig()
+  const target = normalizeWebpageUrl(url)
+  const { markdown, ...document } = await loadDocument(target, { signal, config })
+  const pages = paginateMarkdown(markdown, config.pageChars)
+  const pageNumber = Math.floor(Number(page)) || 1
+  if (pageNumber < 1 || pageNumber > pages.length) {
+    throw new Error(`Page ${page} is out of range (the text has ${pages.length} pages)`)
+  }
+  const title = document.title || target
+  return {
+    url: target,
+    final_url: document.final_url || target,
+    title,
+    author: document.author || null,
+    published_at: document.published_at || null,
+    modified_at: document.modified_at || null,
+    site_name: document.site_name || null,
+    description: document.description || null,
+    lang: document.lang || null,
+    canonical_url: document.canonical_url || null,
+    source: document.source,
+    ...(document.archived_url
+      ? {
+          archived_url: document.archived_url,
+          archived_at: document.archived_at,
+          live_error: document.live_error,
+        }
+      : {}),
+    format: 'markdown',
+    page: pageNumber,
+    total_pages: pages.length,
+    has_more: pageNumber < pages.length,
+    // The site's own next page (rel="next"), for articles split across several URLs
+    next_page_url: document.next_page_url || null,
+    truncated: Boolean(document.truncated),
+    results: [
+      {
+        title: pages.length > 1 ? `${title} (page ${pageNumber}/${pages.length})` : title,
+        url: document.final_url || target,
+        content: pages[pageNumber - 1],
+      },
+    ],
+  }
+}
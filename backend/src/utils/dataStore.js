@@ -0,0 +1,66 @@
+/**
+ * Local JSON data store
+ * Small file-backed persistence for backend-owned records (research runs, settings, logs).
+ */
+
+import fs from 'fs'
+import os from 'os'
+import path from 'path'
+
+export const getDataDir = () =>
+  path.resolve(process.env.QURIO_DATA_DIR || path.join(os.homedir(), '.qurio', 'data'))
+
+export const resolveCollectionDir = collection => {
+  const dir = path.join(getDataDir(), collection)
+  fs.mkdirSync(dir, { recursive: true })
+  return dir
+}
+
+const sanitizeId = id => String(id).replace(/[^a-zA-Z0-9_.-]/g, '_')
+
+const resolveRecordPath = (collection, id) =>
+  path.join(resolveCollectionDir(collection), `${sanitizeId(id)}.json`)
+
+export const readRecord = (collection, id) => {
+  const filePath = resolveRecordPath(collection, id)
+  if (!fs.existsSync(filePath)) return null
+  try {
+    return JSON.parse(fs.readFileSync(filePath, 'utf8'))
+  } catch (error) {
+    console.warn(`[DataStore] Failed to read ${collection}/${id}:`, error.message)
+    return null
+  }
+}
+
+/**
+ * Write a record atomically (temp file + rename) so a crash never leaves half-written JSON
+ */
+export const writeRecord = (collection, id, record) => {
+  const filePath = resolveRecordPath(collection, id)
+  const tempPath = `${filePath}.${process.pid}.tmp`
+  fs.writeFileSync(tempPath, JSON.stringify(record, null, 2))
+  fs.renameSync(tempPath, filePath)
+  return record
+}
+
+export const deleteRecord = (collection, id) => {
+  const filePath = resolveRecordPath(collection, id)
+  if (!fs.existsSync(filePath)) return false
+  fs.unlinkSync(filePath)
+  return true
+}
+
+export const listRecords = collection => {
+  const dir = resolveCollectionDir(collection)
+  return fs
+    .readdirSync(dir)
+    .filter(name => name.endsWith('.json'))
+    .map(name => {
+      try {
+        return JSON.parse(fs.readFileSync(path.join(dir, name), 'utf8'))
+      } catch {
+        return null
+      }
+    })
+    .filter(Boolean)
+}
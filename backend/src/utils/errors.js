@@ -0,0 +1,139 @@
+/**
+ * API errors
+ * One error type with machine-readable codes, so the frontend can show actionable messages
+ * ("check your API key", "rate limited, try again in 20s") instead of opaque strings.
+ * Routes answer failures with sendError(); SSE streams end with toErrorEvent(). Errors thrown by
+ * provider SDKs (OpenAI, Gemini, LangChain wrappers) and fetch are mapped by their HTTP status,
+ * error code, or message.
+ */
+
+import { getRetryAfterMs } from '../services/providers/retry.js'
+
+export const ErrorCode = {
+  ProviderAuth: 'provider_auth',
+  ProviderRateLimit: 'provider_rate_limit',
+  InvalidRequest: 'invalid_request',
+  Upstream: 'upstream',
+  ToolFailure: 'tool_failure',
+  RuntimeUnavailable: 'runtime_unavailable',
+  Internal: 'internal',
+}
+
+const HTTP_STATUS = {
+  [ErrorCode.ProviderAuth]: 401,
+  [ErrorCode.ProviderRateLimit]: 429,
+  [ErrorCode.InvalidRequest]: 400,
+  [ErrorCode.Upstream]: 502,
+  [ErrorCode.ToolFailure]: 502,
+  [ErrorCode.RuntimeUnavailable]: 501,
+  [ErrorCode.Internal]: 500,
+}
+
+export class QurioError extends Error {
+  /**
+   * @param {string} code - One of ErrorCode
+   * @param {string} message
+   * @param {Object} [options]
+   * @param {number} [options.status] - HTTP status (defaults by code)
+   * @param {number} [options.retryAfterMs] - Provider-requested wait (rate limits)
+   * @param {*} [options.details]
+   * @param {Error} [options.cause]
+   */
+  constructor(code, message, { status, retryAfterMs, details, cause } = {}) {
+    super(message, cause ? { cause } : undefined)
+    this.name = 'QurioError'
+    this.code = code
+    this.status = status || HTTP_STATUS[code] || 500
+    this.retryAfterMs = retryAfterMs
+    this.details = details
+  }
+}
+
+const NETWORK_CODES = new Set([
+  'ECONNRESET',
+  'ECONNREFUSED',
+  'ENOTFOUND',
+  'ETIMEDOUT',
+  'EAI_AGAIN',
+  'UND_ERR_SOCKET',
+  'UND_ERR_CONNECT_TIMEOUT',
+])
+const AUTH_MESSAGE_PATTERN =
+  /invalid.{0,20}api.?key|api.?key not valid|incorrect api key|unauthori[sz]ed|authenticat/i
+const RATE_LIMIT_MESSAGE_PATTERN = /\b429\b|rate.?limit|too many requests|quota/i
+const UPSTREAM_MESSAGE_PATTERN = /\b50[0234]\b|overloaded|temporarily unavailable|fetch failed/i
+
+const getStatus = error =>
+  Number(
+    error?.status ?? error?.response?.status ?? error?.statusCode ?? error?.cause?.status ?? 0,
+  ) || null
+
+/**
+ * Map any thrown value onto a QurioError
+ * Errors that are already QurioErrors pass through unchanged.
+ */
+export const toQurioError = error => {
+  if (error instanceof QurioError) return error
+  const message = String(error?.message || error || 'Unknown error')
+  const options = { cause: error instanceof Error ? error : undefined }
+  const status = getStatus(error)
+
+  // http-errors from Express middleware (malformed JSON, oversized body) are the client's fault
+  if (error?.expose && status >= 400 && status < 500) {
+    return new QurioError(ErrorCode.InvalidRequest, message, { ...options, status })
+  }
+  if (status === 401 || status === 403 || (!status && AUTH_MESSAGE_PATTERN.test(message))) {
+    return new QurioError(ErrorCode.ProviderAuth, message, options)
+  }
+  if (status === 429 || (!status && RATE_LIMIT_MESSAGE_PATTERN.test(message))) {
+    return new QurioError(ErrorCode.ProviderRateLimit, message, {
+      ...options,
+      retryAfterMs: getRetryAfterMs(error) ?? undefined,
+    })
+  }
+  // A status on a thrown error comes from an upstream API, never from our own validation
+  if (status >= 400) return new QurioError(ErrorCode.Upstream, message, options)
+  const code = error?.code || error?.cause?.code
+  if (NETWORK_CODES.has(code) || UPSTREAM_MESSAGE_PATTERN.test(message)) {
+    return new QurioError(ErrorCode.Upstream, message, options)
+  }
+  return new QurioError(ErrorCode.Internal, message, options)
+}
+
+/**
+ * JSON body for a failed request; keeps the { error, message } shape and adds "code"
+ */
+export const toErrorBody = (error, fallbackMessage) => {
+  const mapped = toQurioError(error)
+  return {
+    error: fallbackMessage || mapped.message,
+    code: mapped.code,
+    message: mapped.message,
+    retry_after_ms: mapped.retryAfterMs,
+    details: mapped.details,
+  }
+}
+
+/**
+ * Answer a failed request with the mapped status and error body
+ * @param {import('express').Response} res
+ * @param {*} error
+ * @param {string} [fallbackMessage] - Summary such as "Failed to list models"
+ */
+export const sendError = (res, error, fallbackMessage) => {
+  const mapped = toQurioError(error)
+  return res.status(mapped.status).json(toErrorBody(mapped, fallbackMessage))
+}
+
+/**
+ * Final SSE event for a stream that failed after headers were sent
+ */
+export const toErrorEvent = error => {
+  const mapped = toQurioError(error)
+  return {
+    type: 'error',
+    error: mapped.message,
+    code: mapped.code,
+    retry_after_ms: mapped.retryAfterMs,
+  }
+}
@@ -0,0 +1,37 @@
+/**
+ * Event queue
+ * Turns work that reports events through a callback (parallel sub-answers, concurrent research
+ * steps, tool-call deltas) into an async iterator, so a generator can yield the events of all
+ * its running tasks as they happen.
+ */
+
+/**
+ * Yield every event the task pushes while it runs, then return (or throw) the task's result
+ * @param {(push: (event: Object) => void) => Promise<*>} run
+ * @returns {AsyncGenerator<Object, *>} Use as `const result = yield* yieldWhileRunning(...)`
+ */
+export const yieldWhileRunning = async function* (run) {
+  const pending = []
+  let wake = null
+  let settled = false
+  const task = run(event => {
+    pending.push(event)
+    wake?.()
+  })
+  task
+    .catch(() => {})
+    .then(() => {
+      settled = true
+      wake?.()
+    })
+  while (!settled || pending.length) {
+    if (pending.length) {
+      yield pending.shift()
+      continue
+    }
+    await new Promise(resolve => {
+      wake = resolve
+    })
+  }
+  return task
+}
@@ -0,0 +1,98 @@
+/**
+ * Bounded line reader
+ * Splits a byte stream into lines for the provider stream parsers (SSE, NDJSON). Line breaks are
+ * found in the raw bytes (CR and LF never occur inside multi-byte UTF-8 sequences), so complete
+ * lines are decoded straight from the chunk they arrive in. Only a line split across chunks is
+ * copied, into one reusable buffer that is drained after each line and never grows past
+ * maxLineBytes: a malformed stream without line breaks fails instead of buffering forever.
+ */
+
+import { ErrorCode, QurioError } from './errors.js'
+
+const CR = 0x0d
+const LF = 0x0a
+const INITIAL_CAPACITY = 4 * 1024
+const DEFAULT_MAX_LINE_BYTES = 4 * 1024 * 1024
+
+export const getMaxLineBytes = () => {
+  const value = Number.parseInt(process.env.PROVIDER_STREAM_MAX_LINE_BYTES, 10)
+  return Number.isFinite(value) && value > 0 ? value : DEFAULT_MAX_LINE_BYTES
+}
+
+export const lineTooLongError = maxLineBytes =>
+  new QurioError(
+    ErrorCode.Upstream,
+    `Provider stream sent a line or event over ${maxLineBytes} bytes; the stream looks malformed`,
+  )
+
+/**
+ * Split a byte stream into lines (CR, LF, and CRLF all end a line; a CRLF split across chunks
+ * counts once). Lines come in one batch per chunk, which keeps consumers to one await per chunk
+ * rather than per line. A last line without a line break is still delivered.
+ * @param {AsyncIterable<Uint8Array|string>} body
+ * @param {Object} [options]
+ * @param {number} [options.maxLineBytes] - Default PROVIDER_STREAM_MAX_LINE_BYTES or 4 MiB
+ * @returns {AsyncGenerator<string[]>}
+ * @throws {QurioError} upstream, when a line split across chunks exceeds maxLineBytes
+ */
+export async function* readLineBatches(body, { maxLineBytes = getMaxLineBytes() } = {}) {
+  const decoder = new TextDecoder()
+  const encoder = new TextEncoder()
+  let pending = new Uint8Array(0)
+  let pendingLength = 0
+  // The previous chunk ended in CR: an LF at the start of this one belongs to it
+  let skipLeadingLf = false
+
+  const append = bytes => {
+    const required = pendingLength + bytes.length
+    if (required > maxLineBytes) throw lineTooLongError(maxLineBytes)
+    if (required > pending.length) {
+      let capacity = Math.max(pending.length, INITIAL_CAPACITY)
+      while (capacity < required) capacity *= 2
+      const grown = new Uint8Array(Math.min(capacity, maxLineBytes))
+      grown.set(pending.subarray(0, pendingLength))
+      pending = grown
+    }
+    pending.set(bytes, pendingLength)
+    pendingLength = required
+  }
+
+  // Decode the pending head plus `tail` as one line and drain the buffer
+  const takeLine = tail => {
+    if (!pendingLength) return decoder.decode(tail)
+    append(tail)
+    const line = decoder.decode(pending.subarray(0, pendingLength))
+    pendingLength = 0
+    // Give back the memory of an unusually long line
+    if (pending.length > INITIAL_CAPACITY * 16) pending = new Uint8Array(0)
+    return line
+  }
+
+  for await (const chunk of body) {
+    const bytes = typeof chunk === 'string' ? encoder.encode(chunk) : chunk
+    const lines = []
+    let start = 0
+    if (skipLeadingLf && bytes.length) {
+      if (bytes[0] === LF) start = 1
+      skipLeadingLf = false
+    }
+    // Native searches; a stream without CRs pays for one failed CR search per chunk
+    let nextLf = bytes.indexOf(LF, start)
+    let nextCr = bytes.indexOf(CR, start)
+    while (nextLf !== -1 || nextCr !== -1) {
+      const isCr = nextCr !== -1 && (nextLf === -1 || nextCr < nextLf)
+      const index = isCr ? nextCr : nextLf
+      lines.push(takeLine(bytes.subarray(start, index)))
+      start = index + 1
+      if (isCr) {
+        if (index + 1 === bytes.length) skipLeadingLf = true
+        else if (bytes[index + 1] === LF) start += 1
+        nextCr = bytes.indexOf(CR, start)
+      }
+      if (nextLf !== -1 && nextLf < start) nextLf = bytes.indexOf(LF, start)
+    }
+    if (start < bytes.length) append(bytes.subarray(start))
+    if (lines.length) yield lines
+  }
+  if (pendingLength) yield [takeLine(new Uint8Array(0))]
+}
@@ -0,0 +1,69 @@
+/**
+ * Path sandbox
+ * Restricts filesystem access to directories listed in QURIO_SANDBOX_DIRS (separated by the
+ * platform path delimiter, ":" or ";"), so endpoints and tools never touch arbitrary paths.
+ */
+
+import fs from 'fs'
+import path from 'path'
+
+export class SandboxError extends Error {
+  constructor(message) {
+    super(message)
+    this.name = 'SandboxError'
+  }
+}
+
+// Resolve symlinks through the nearest existing ancestor so not-yet-created paths are checked too
+const realpathOrResolve = target => {
+  const resolved = path.resolve(target)
+  try {
+    return fs.realpathSync(resolved)
+  } catch {
+    const parent = path.dirname(resolved)
+    if (parent === resolved) return resolved
+    return path.join(realpathOrResolve(parent), path.basename(resolved))
+  }
+}
+
+export const getSandboxRoots = () =>
+  String(process.env.QURIO_SANDBOX_DIRS || '')
+    .split(path.delimiter)
+    .map(item => item.trim())
+    .filter(Boolean)
+    .map(realpathOrResolve)
+
+const isInside = (root, target) => {
+  const relative = path.relative(root, target)
+  return relative === '' || (!relative.startsWith('..') && !path.isAbsolute(relative))
+}
+
+/**
+ * Resolve a directory and ensure it lies within an allowlisted root (symlinks resolved)
+ * @throws {SandboxError}
+ */
+export const resolveSandboxedDir = dir => {
+  if (!dir || typeof dir !== 'string') throw new SandboxError('A directory path is required')
+  const roots = getSandboxRoots()
+  if (!roots.length) {
+    throw new SandboxError('No sandbox directories configured (set QURIO_SANDBOX_DIRS)')
+  }
+  const resolved = realpathOrResolve(dir)
+  if (!roots.some(root => isInside(root, resolved))) {
+    throw new SandboxError(`Path is outside the sandbox allowlist: ${dir}`)
+  }
+  return resolved
+}
+
+/**
+ * Resolve a relative path under a sandboxed base directory, rejecting escapes
+ * @throws {SandboxError}
+ */
+export const resolveSandboxedPath = (baseDir, relativePath) => {
+  const base = resolveSandboxedDir(baseDir)
+  const target = path.resolve(base, String(relativePath || ''))
+  if (!isInside(base, target) || !isInside(base, realpathOrResolve(target))) {
+    throw new SandboxError(`Path escapes the base directory: ${relativePath}`)
+  }
+  return target
+}
@@ -0,0 +1,111 @@
+/**
+ * Public fetch
+ * Fetches URLs chosen by a model or a user without exposing the server's network: the host must
+ * resolve only to public addresses (not loopback, private, link-local, cloud metadata, or this
+ * machine's own interfaces, where Qurio's API listens), and every redirect hop is checked the
+ * same way before it is followed.
+ */
+
+import dns from 'dns/promises'
+import net from 'net'
+import os from 'os'
+
+const MAX_REDIRECTS = 5
+
+// IPv4 rules also match IPv4-mapped IPv6 addresses (::ffff:127.0.0.1)
+const NON_PUBLIC_SUBNETS = [
+  ['0.0.0.0', 8, 'ipv4'],
+  ['10.0.0.0', 8, 'ipv4'],
+  ['100.64.0.0', 10, 'ipv4'],
+  ['127.0.0.0', 8, 'ipv4'],
+  ['169.254.0.0', 16, 'ipv4'],
+  ['172.16.0.0', 12, 'ipv4'],
+  ['192.0.0.0', 24, 'ipv4'],
+  ['192.168.0.0', 16, 'ipv4'],
+  ['198.18.0.0', 15, 'ipv4'],
+  ['224.0.0.0', 3, 'ipv4'],
+  ['::', 128, 'ipv6'],
+  ['::1', 128, 'ipv6'],
+  ['fc00::', 7, 'ipv6'],
+  ['fe80::', 10, 'ipv6'],
+  ['ff00::', 8, 'ipv6'],
+]
+
+export class UnsafeUrlError extends Error {
+  constructor(message) {
+    super(message)
+    this.name = 'UnsafeUrlError'
+  }
+}
+
+let blockList = null
+
+const getBlockList = () => {
+  if (blockList) return blockList
+  blockList = new net.BlockList()
+  for (const [network, prefix, type] of NON_PUBLIC_SUBNETS) {
+    blockList.addSubnet(network, prefix, type)
+  }
+  for (const addresses of Object.values(os.networkInterfaces())) {
+    for (const { address, family } of addresses || []) {
+      blockList.addAddress(address.split('%')[0], family === 'IPv6' ? 'ipv6' : 'ipv4')
+    }
+  }
+  return blockList
+}
+
+/**
+ * Whether an IP address is reachable on the public internet and not this machine
+ */
+export const isPublicAddress = address => {
+  const version = net.isIP(address)
+  if (!version) return false
+  return !getBlockList().check(address, version === 6 ? 'ipv6' : 'ipv4')
+}
+
+/**
+ * Reject a URL that is not http(s) or whose host resolves to any non-public address
+ * @param {string|URL} url
+ * @returns {Promise<URL>}
+ */
+export const assertPublicUrl = async url => {
+  let parsed
+  try {
+    parsed = new URL(url)
+  } catch {
+    throw new UnsafeUrlError(`Invalid URL: ${url}`)
+  }
+  if (parsed.protocol !== 'http:' && parsed.protocol !== 'https:') {
+    throw new UnsafeUrlError(`Only http and https URLs can be fetched: ${parsed.protocol}`)
+  }
+  const host = parsed.hostname.replace(/^\[|\]$/g, '')
+  let addresses
+  try {
+    addresses = await dns.lookup(host, { all: true, verbatim: true })
+  } catch (error) {
+    throw new Error(`Cannot resolve ${host}: ${error.code || error.message}`)
+  }
+  const blocked = addresses.find(({ address }) => !isPublicAddress(address))
+  if (!addresses.length || blocked) {
+    throw new UnsafeUrlError(`${host} resolves to a non-public address`)
+  }
+  return parsed
+}
+
+/**
+ * fetch() of a public URL, following redirects only to other public URLs
+ * The response's url is that of the last hop.
+ * @param {string|URL} url
+ * @param {RequestInit} [init] - Any redirect option is replaced by manual handling
+ */
+export const fetchPublicUrl = async (url, init = {}) => {
+  let target = await assertPublicUrl(url)
+  for (let redirects = 0; ; redirects += 1) {
+    const response = await fetch(target, { ...init, redirect: 'manual' })
+    const location = response.headers.get('location')
+    if (response.status < 300 || response.status >= 400 || !location) return response
+    await response.body?.cancel().catch(() => {})
+    if (redirects >= MAX_REDIRECTS) throw new Error('Too many redirects')
+    target = await assertPublicUrl(new URL(location, target))
+  }
+}
@@ -0,0 +1,46 @@
+/**
+ * Rolling log file
+ * Appends JSON lines to <dir>/<name>; when the file would pass maxBytes it becomes <name>.1
+ * (older files shift up to <name>.<maxFiles - 1>, the oldest is dropped).
+ */
+
+import fs from 'fs'
+import path from 'path'
+
+/**
+ * @param {Object} options
+ * @param {string} options.dir
+ * @param {string} options.name - e.g. "qurio.log"
+ * @param {number} options.maxBytes - Size of one file before it rolls over
+ * @param {number} options.maxFiles - Files kept, the current one included
+ * @returns {{ write: (entry: Object) => void, path: string }}
+ */
+export const createRollingLog = ({ dir, name, maxBytes, maxFiles }) => {
+  const filePath = path.join(dir, name)
+  let size = fs.existsSync(filePath) ? fs.statSync(filePath).size : 0
+
+  const rotate = () => {
+    const last = `${filePath}.${Math.max(1, maxFiles - 1)}`
+    if (maxFiles <= 1) {
+      fs.rmSync(filePath, { force: true })
+    } else {
+      fs.rmSync(last, { force: true })
+      for (let index = maxFiles - 2; index >= 1; index -= 1) {
+        const from = `${filePath}.${index}`
+        if (fs.existsSync(from)) fs.renameSync(from, `${filePath}.${index + 1}`)
+      }
+      if (fs.existsSync(filePath)) fs.renameSync(filePath, `${filePath}.1`)
+    }
+    size = 0
+  }
+
+  const write = entry => {
+    const line = `${JSON.stringify(entry)}\n`
+    const bytes = Buffer.byteLength(line)
+    if (size > 0 && size + bytes > maxBytes) rotate()
+    fs.appendFileSync(filePath, line)
+    size += bytes
+  }
+
+  return { write, path: filePath }
+}
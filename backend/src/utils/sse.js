@@ -1,3 +1,5 @@
+import { getMaxLineBytes, lineTooLongError, readLineBatches } from './lineReader.js'
+
 const DEFAULT_FLUSH_MS = 50
 const DEFAULT_HEARTBEAT_MS = 15000
 
@@ -10,7 +12,38 @@ export const getSseConfig = () => {
   }
 }
 
+// Terminal events are never filtered so clients always learn how the stream ended
+const UNFILTERED_EVENT_TYPES = new Set(['done', 'error', 'cancelled'])
+
+const toTypeList = value =>
+  (Array.isArray(value) ? value : String(value || '').split(','))
+    .map(item => String(item).trim())
+    .filter(Boolean)
+
+/**
+ * Build a predicate from a request's event_filter field
+ * - ["thought", "tool_call"] or "thought,tool_call": drop these event types
+ * - { "exclude": [...] }: same as above
+ * - { "include": ["text"] }: send only these event types
+ * done / error / cancelled are always sent.
+ * @returns {((event: Object) => boolean)|null} null when nothing is filtered
+ */
+export const createEventFilter = value => {
+  if (!value) return null
+  const isRules = typeof value === 'object' && !Array.isArray(value)
+  const include = isRules && value.include ? new Set(toTypeList(value.include)) : null
+  const exclude = new Set(toTypeList(isRules ? value.exclude : value))
+  if (!include && !exclude.size) return null
+  return event => {
+    const type = event?.type
+    if (!type || UNFILTERED_EVENT_TYPES.has(type)) return true
+    if (include && !include.has(type)) return false
+    return !exclude.has(type)
+  }
+}
+
 export const createSseStream = (res, config = {}) => {
+  const eventFilter = createEventFilter(config.eventFilter)
   const flushMs = Number.isFinite(config.flushMs) ? config.flushMs : DEFAULT_FLUSH_MS
   const heartbeatMs = Number.isFinite(config.heartbeatMs)
     ? config.heartbeatMs
@@ -59,6 +92,7 @@ export const createSseStream = (res, config = {}) => {
   }
 
   const sendEvent = data => {
+    if (eventFilter && !eventFilter(data)) return
     writeRaw(`data: ${JSON.stringify(data)}\n\n`)
   }
 
@@ -88,3 +122,54 @@ export const createSseStream = (res, config = {}) => {
     close,
   }
 }
+
+/**
+ * Decode a Server-Sent Events byte stream (e.g. an upstream fetch body) into messages
+ * Chunks may split lines, CRLF pairs, and multi-byte UTF-8 characters anywhere. Comment lines
+ * are skipped, and a last message missing its blank line is still delivered when the body ends.
+ * A line, or the data of one message, over maxLineBytes fails the stream (see lineReader.js).
+ * @param {AsyncIterable<Uint8Array|string>} body
+ * @param {Object} [options]
+ * @param {number} [options.maxLineBytes]
+ * @returns {AsyncGenerator<{ event: string, data: string, id: string|undefined }>}
+ */
+export async function* readServerSentEvents(body, { maxLineBytes = getMaxLineBytes() } = {}) {
+  let event = ''
+  let data = []
+  let dataLength = 0
+  let id
+
+  const takeMessage = () => {
+    const message = data.length ? { event: event || 'message', data: data.join('\n'), id } : null
+    event = ''
+    data = []
+    dataLength = 0
+    return message
+  }
+
+  // Returns a message when the line ends one
+  const readLine = line => {
+    if (!line) return takeMessage()
+    if (line.startsWith(':')) return null
+    const colon = line.indexOf(':')
+    const field = colon === -1 ? line : line.slice(0, colon)
+    const value = colon === -1 ? '' : line.slice(colon + 1).replace(/^ /, '')
+    if (field === 'event') event = value
+    else if (field === 'data') {
+      // Many data lines without a blank line would otherwise grow one message without bound
+      dataLength += value.length + 1
+      if (dataLength > maxLineBytes) throw lineTooLongError(maxLineBytes)
+      data.push(value)
+    } else if (field === 'id') id = value
+    return null
+  }
+
+  for await (const lines of readLineBatches(body, { maxLineBytes })) {
+    for (const line of lines) {
+      const message = readLine(line)
+      if (message) yield message
+    }
+  }
+  const last = takeMessage()
+  if (last) yield last
+}
@@ -0,0 +1,165 @@
+/**
+ * Streaming diff
+ * Aligns a revision against the original text while the revision is still streaming and emits
+ * equal/insert/delete operations with positions in the original text.
+ */
+
+const TOKEN_PATTERN = /\s+|[\p{L}\p{N}_]+|[^\s\p{L}\p{N}_]/gu
+
+// Number of consecutive matching tokens required before re-synchronizing after an edit
+const DEFAULT_SYNC_RUN = 3
+// How far ahead in the original to look for a re-synchronization point
+const DEFAULT_LOOKAHEAD = 200
+
+export const tokenizeForDiff = text => {
+  const tokens = []
+  for (const match of String(text || '').matchAll(TOKEN_PATTERN)) {
+    tokens.push({ text: match[0], start: match.index })
+  }
+  return tokens
+}
+
+/**
+ * @example
+ * const diff = new StreamingDiff(original)
+ * for (const chunk of chunks) ops.push(...diff.push(chunk))
+ * ops.push(...diff.finish())
+ */
+export class StreamingDiff {
+  constructor(original, { syncRun = DEFAULT_SYNC_RUN, lookahead = DEFAULT_LOOKAHEAD } = {}) {
+    this.original = String(original || '')
+    this.originalTokens = tokenizeForDiff(this.original)
+    this.syncRun = syncRun
+    this.lookahead = lookahead
+    this.origIndex = 0
+    this.revisedBuffer = ''
+    this.pending = []
+    this.revised = ''
+  }
+
+  get originalOffset() {
+    const token = this.originalTokens[this.origIndex]
+    return token ? token.start : this.original.length
+  }
+
+  /**
+   * Feed a chunk of the revision; returns operations that are now stable
+   */
+  push(chunk) {
+    this.revised += chunk
+    this.revisedBuffer += chunk
+    const tokens = tokenizeForDiff(this.revisedBuffer)
+    // The last token may still grow with the next chunk
+    const complete = tokens.length > 1 ? tokens.slice(0, -1) : []
+    if (complete.length) {
+      const last = tokens[tokens.length - 1]
+      this.revisedBuffer = this.revisedBuffer.slice(last.start)
+      this.pending.push(...complete.map(token => token.text))
+    }
+    return this.drain(false)
+  }
+
+  /**
+   * Flush everything once the revision is complete
+   */
+  finish() {
+    if (this.revisedBuffer) {
+      this.pending.push(...tokenizeForDiff(this.revisedBuffer).map(token => token.text))
+      this.revisedBuffer = ''
+    }
+    const ops = this.drain(true)
+    if (this.pending.length) {
+      ops.push(this.buildOp('insert', this.pending.join('')))
+      this.pending = []
+    }
+    if (this.origIndex < this.originalTokens.length) {
+      ops.push(this.deleteUntil(this.originalTokens.length))
+    }
+    return mergeOps(ops)
+  }
+
+  buildOp(op, text) {
+    return { op, text, position: this.originalOffset }
+  }
+
+  deleteUntil(index) {
+    const start = this.originalOffset
+    const end =
+      index < this.originalTokens.length ? this.originalTokens[index].start : this.original.length
+    this.origIndex = index
+    return { op: 'delete', text: this.original.slice(start, end), position: start }
+  }
+
+  equalCount(originalIndex, pendingIndex, limit) {
+    let count = 0
+    while (
+      count < limit &&
+      pendingIndex + count < this.pending.length &&
+      originalIndex + count < this.originalTokens.length &&
+      this.pending[pendingIndex + count] === this.originalTokens[originalIndex + count].text
+    ) {
+      count += 1
+    }
+    return count
+  }
+
+  /**
+   * Find the earliest (pendingIndex, originalIndex) where syncRun tokens match
+   */
+  findSync(final) {
+    const maxOriginal = Math.min(this.originalTokens.length, this.origIndex + this.lookahead)
+    for (let p = 0; p < this.pending.length; p += 1) {
+      for (let o = this.origIndex; o < maxOriginal; o += 1) {
+        if (this.pending[p] !== this.originalTokens[o].text) continue
+        const run = this.equalCount(o, p, this.syncRun)
+        const reachesEnd = o + run === this.originalTokens.length || p + run === this.pending.length
+        if (run >= this.syncRun || (final && reachesEnd)) return { p, o }
+      }
+    }
+    return null
+  }
+
+  drain(final) {
+    const ops = []
+    while (this.pending.length) {
+      // Fast path: next token matches the original
+      if (
+        this.origIndex < this.originalTokens.length &&
+        this.pending[0] === this.originalTokens[this.origIndex].text
+      ) {
+        ops.push(this.buildOp('equal', this.pending.shift()))
+        this.origIndex += 1
+        continue
+      }
+      const sync = this.findSync(final)
+      if (!sync) break
+      if (sync.o > this.origIndex) ops.push(this.deleteUntil(sync.o))
+      if (sync.p > 0) ops.push(this.buildOp('insert', this.pending.splice(0, sync.p).join('')))
+    }
+    return mergeOps(ops)
+  }
+}
+
+/**
+ * Merge adjacent operations of the same kind
+ */
+export const mergeOps = ops =>
+  ops.reduce((merged, op) => {
+    const last = merged[merged.length - 1]
+    const contiguous = op.op !== 'delete' || last?.position + last?.text.length === op.position
+    if (last && last.op === op.op && contiguous) {
+      last.text += op.text
+    } else if (op.text) {
+      merged.push({ ...op })
+    }
+    return merged
+  }, [])
+
+/**
+ * Apply operations to the original text (useful for verifying a diff)
+ */
+export const applyOps = ops =>
+  ops
+    .filter(op => op.op !== 'delete')
+    .map(op => op.text)
+    .join('')
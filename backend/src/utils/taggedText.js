@@ -0,0 +1,70 @@
+/**
+ * Tagged text parser
+ * Splits streamed model text into answer text and thoughts wrapped in <think>...</think> or
+ * <thought>...</thought> (any case). Chunks may split a tag anywhere: a chunk ending in what could
+ * be the start of a tag ("<thi") holds that tail back until the next chunk decides it.
+ */
+
+const OPEN_TAG = /<(think|thought)>/i
+const CLOSE_TAG = /<\/(think|thought)>/i
+const OPEN_TAGS = ['<think>', '<thought>']
+const CLOSE_TAGS = ['</think>', '</thought>']
+
+// Length of the longest tail of text that is a proper prefix of one of the tags
+const partialTagLength = (text, tags) => {
+  const tail = text.slice(-Math.max(...tags.map(tag => tag.length)) + 1).toLowerCase()
+  for (let length = tail.length; length > 0; length -= 1) {
+    const suffix = tail.slice(-length)
+    if (tags.some(tag => tag.startsWith(suffix))) return length
+  }
+  return 0
+}
+
+/**
+ * Create a parser; call it with each chunk, then call its flush() at the end of a response to
+ * emit a held-back tail as-is
+ * @param {Object} handlers
+ * @param {(text: string) => void} handlers.emitText
+ * @param {(text: string) => void} handlers.emitThought
+ * @param {boolean} [handlers.enableTags] - false passes everything through as text
+ * @returns {((text: string) => void) & { flush: () => void }}
+ */
+export const createTaggedTextParser = ({ emitText, emitThought, enableTags = true }) => {
+  let inThoughtBlock = false
+  let pending = ''
+
+  const emit = text => {
+    if (text) (inThoughtBlock ? emitThought : emitText)(text)
+  }
+
+  const handle = text => {
+    if (!text) return
+    // If tag parsing is disabled, just emit everything as text
+    if (!enableTags) {
+      emitText(text)
+      return
+    }
+
+    let remaining = pending + text
+    pending = ''
+    while (remaining) {
+      const match = remaining.match(inThoughtBlock ? CLOSE_TAG : OPEN_TAG)
+      if (!match) {
+        const held = partialTagLength(remaining, inThoughtBlock ? CLOSE_TAGS : OPEN_TAGS)
+        emit(remaining.slice(0, remaining.length - held))
+        pending = remaining.slice(remaining.length - held)
+        return
+      }
+      emit(remaining.slice(0, match.index))
+      remaining = remaining.slice(match.index + match[0].length)
+      inThoughtBlock = !inThoughtBlock
+    }
+  }
+
+  const flush = () => {
+    emit(pending)
+    pending = ''
+  }
+
+  return Object.assign(handle, { flush })
+}
@@ -0,0 +1,105 @@
+/**
+ * Tool call deltas
+ * Accumulates streamed tool call fragments by index and turns each fragment into a
+ * "tool_call_delta" event, so clients can render a search query while the model is still writing
+ * its arguments. The consolidated "tool_call" event is still emitted before the tool runs.
+ */
+
+import { jsonrepair } from 'jsonrepair'
+
+// Best-effort parse of incomplete JSON ('{"query": "rust asy' -> { query: 'rust asy' })
+export const parsePartialArguments = text => {
+  if (!text) return null
+  try {
+    return JSON.parse(text)
+  } catch {
+    // Still streaming
+  }
+  try {
+    const parsed = JSON.parse(jsonrepair(text))
+    return parsed && typeof parsed === 'object' ? parsed : null
+  } catch {
+    return null
+  }
+}
+
+// LangChain chunks ({ index, id, name, args }) and raw OpenAI deltas
+// ({ index, id, function: { name, arguments } }) carry the same fragments
+const readFragment = fragment => ({
+  index: typeof fragment?.index === 'number' ? fragment.index : null,
+  id: fragment?.id || null,
+  name: fragment?.function?.name || fragment?.name || null,
+  args: fragment?.function?.arguments ?? (typeof fragment?.args === 'string' ? fragment.args : ''),
+})
+
+/**
+ * Fragments of one streamed message chunk; LangChain's tool_call_chunks are preferred and the raw
+ * provider delta is used when the adapter does not expose them
+ */
+export const getToolCallFragments = messageChunk => {
+  const chunks = messageChunk?.tool_call_chunks
+  if (Array.isArray(chunks) && chunks.length) return chunks
+  const raw = messageChunk?.additional_kwargs?.__raw_response?.choices?.[0]?.delta?.tool_calls
+  return Array.isArray(raw) ? raw : []
+}
+
+/**
+ * @example
+ * const tracker = new ToolCallDeltaTracker()
+ * for await (const chunk of stream) events.push(...tracker.push(getToolCallFragments(chunk)))
+ * const toolCalls = tracker.toolCalls()
+ */
+export class ToolCallDeltaTracker {
+  constructor() {
+    this.calls = []
+  }
+
+  /**
+   * Merge fragments and return one delta event per fragment that changed a call
+   * @param {Array} fragments
+   * @param {Object} [extra] - Fields added to every event (e.g. step, total, textIndex)
+   * @returns {Array<{ type: 'tool_call_delta', index, id, name, arguments_delta, arguments,
+   *   partial_arguments }>}
+   */
+  push(fragments, extra = {}) {
+    const events = []
+    for (const raw of fragments || []) {
+      const fragment = readFragment(raw)
+      const index = fragment.index ?? this.calls.length
+      const call = this.calls[index] || (this.calls[index] = { id: null, name: null, args: '' })
+      const isNew = !call.id && !call.name && !call.args
+      if (fragment.id) call.id = fragment.id
+      if (fragment.name) call.name = fragment.name
+      if (!fragment.args && !isNew) continue
+      call.args += fragment.args
+      events.push({
+        type: 'tool_call_delta',
+        index,
+        id: call.id,
+        name: call.name,
+        arguments_delta: fragment.args,
+        arguments: call.args,
+        partial_arguments: parsePartialArguments(call.args),
+        ...extra,
+      })
+    }
+    return events
+  }
+
+  get size() {
+    return this.calls.filter(Boolean).length
+  }
+
+  /**
+   * Completed calls in OpenAI message format
+   */
+  toolCalls() {
+    return this.calls
+      .filter(call => call?.id && call.name)
+      .map(call => ({
+        id: call.id,
+        type: 'function',
+        function: { name: call.name, arguments: call.args || '{}' },
+      }))
+  }
+}
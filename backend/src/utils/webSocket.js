@@ -0,0 +1,44 @@
+/**
+ * WebSocket helpers
+ * Proxies and webviews that buffer SSE pass WebSocket frames through, so streaming routes can
+ * also write their events to a socket (see routes/wsChat.js).
+ */
+
+import { WebSocket } from 'ws'
+import { createEventFilter, getSseConfig } from './sse.js'
+
+// RFC 6455 close codes
+export const CLOSE_NORMAL = 1000
+export const CLOSE_INVALID_PAYLOAD = 1007
+export const CLOSE_POLICY_VIOLATION = 1008
+
+/**
+ * Chat event sink over a WebSocket: one JSON text frame per event (the same objects SSE sends
+ * as data lines), with pings instead of keep-alive comments
+ * @param {import('ws').WebSocket} socket
+ * @param {Object} [config]
+ * @param {*} [config.eventFilter] - A request's event_filter (see createEventFilter)
+ * @param {number} [config.heartbeatMs] - Ping interval (default SSE_HEARTBEAT_MS or 15s)
+ * @returns {import('../services/streamChatRunner.js').ChatEventSink}
+ */
+export const createWebSocketSink = (socket, config = {}) => {
+  const eventFilter = createEventFilter(config.eventFilter)
+  const heartbeatMs = Number.isFinite(config.heartbeatMs)
+    ? config.heartbeatMs
+    : getSseConfig().heartbeatMs
+  const isOpen = () => socket.readyState === WebSocket.OPEN
+  const heartbeatTimer =
+    heartbeatMs > 0 ? setInterval(() => isOpen() && socket.ping(), heartbeatMs) : null
+
+  const sendEvent = data => {
+    if (!isOpen() || (eventFilter && !eventFilter(data))) return
+    socket.send(JSON.stringify(data))
+  }
+
+  const close = (code = CLOSE_NORMAL, reason) => {
+    if (heartbeatTimer) clearInterval(heartbeatTimer)
+    if (isOpen()) socket.close(code, reason)
+  }
+
+  return { sendEvent, close }
+}
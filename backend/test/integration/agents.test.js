@@ -0,0 +1,142 @@
+/**
+ * /api/agents and agent_id on /api/stream-chat: a chat naming an agent is sent the agent's
+ * preamble, model, tools, and sampling parameters, with the request's own values taking
+ * precedence, and agents bound to spaces are refused elsewhere
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('agents', () => {
+  let mock
+  let app
+
+  const createAgent = async fields => {
+    const response = await app.request('POST', '/api/agents', fields)
+    assert.equal(response.status, 201)
+    return (await response.json()).agent
+  }
+
+  const chatBody = extra => ({
+    apiKey: 'test-key',
+    baseUrl: mock.baseUrl,
+    messages: [{ role: 'user', content: 'Review this diff' }],
+    ...extra,
+  })
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('validates agents and keeps fields an update leaves out', async () => {
+    const invalid = await app.request('POST', '/api/agents', {
+      temperature: 3,
+      tool_ids: 'calculator',
+      base_tone: 'loud',
+    })
+    assert.equal(invalid.status, 400)
+    assert.equal((await invalid.json()).details.length, 4)
+
+    const agent = await createAgent({ name: 'Writer', prompt: 'Write clearly.', top_p: 0.5 })
+    const updated = await app.request('PUT', `/api/agents/${agent.id}`, { description: 'Docs' })
+    const body = await updated.json()
+    assert.equal(body.agent.description, 'Docs')
+    assert.equal(body.agent.top_p, 0.5)
+
+    const fetched = await (await app.request('GET', `/api/agents/${agent.id}`)).json()
+    assert.equal(fetched.preamble, '## Agent Prompt\nWrite clearly.')
+
+    assert.equal((await app.request('DELETE', `/api/agents/${agent.id}`)).status, 200)
+    assert.equal((await app.request('GET', `/api/agents/${agent.id}`)).status, 404)
+  })
+
+  it('applies the same rules to /api/local/agents', async () => {
+    const invalid = await app.request('PUT', '/api/local/agents/local-1', { temperature: 3 })
+    assert.equal(invalid.status, 400)
+    assert.equal((await invalid.json()).details.length, 2)
+
+    const created = await app.request('PUT', '/api/local/agents/local-1', {
+      name: 'Default',
+      is_default: true,
+    })
+    assert.equal((await created.json()).agent.id, 'local-1')
+    const updated = await app.request('PUT', '/api/local/agents/local-1', { top_p: 2 })
+    assert.equal(updated.status, 400)
+
+    assert.equal((await app.request('DELETE', '/api/local/agents/local-1')).status, 409)
+    assert.equal((await app.request('GET', '/api/agents/local-1')).status, 200)
+  })
+
+  it('resolves agent_id into the preamble, model, tools, and parameters', async () => {
+    const agent = await createAgent({
+      name: 'Reviewer',
+      prompt: 'You review pull requests.',
+      provider: 'openai',
+      default_model: 'agent-model',
+      tool_ids: ['calculator'],
+      temperature: 0.1,
+      base_tone: 'technical',
+      response_language: 'English',
+    })
+    mock.enqueue({ content: 'Looks good.' })
+
+    const { status, events } = await app.postSse(
+      '/api/stream-chat',
+      chatBody({ agent_id: agent.id }),
+    )
+    assert.equal(status, 200)
+    assert.equal(events.find(event => event.type === 'done')?.content, 'Looks good.')
+
+    const sent = mock.chatRequests().at(-1).body
+    assert.equal(sent.model, 'agent-model')
+    assert.equal(sent.temperature, 0.1)
+    assert.ok(sent.tools.some(tool => tool.function?.name === 'calculator'))
+    assert.equal(sent.messages[0].role, 'system')
+    assert.equal(
+      sent.messages[0].content,
+      [
+        '## Agent Prompt\nYou review pull requests.',
+        '## Response Style\n- Use a technical, precise tone suitable for developers.',
+        '## Language\nReply in English.',
+      ].join('\n\n'),
+    )
+
+    mock.enqueue({ content: 'Override.' })
+    await app.postSse(
+      '/api/stream-chat',
+      chatBody({ agent_id: agent.id, provider: 'openai', model: 'mock-model', temperature: 0.7 }),
+    )
+    const overridden = mock.chatRequests().at(-1).body
+    assert.equal(overridden.model, 'mock-model')
+    assert.equal(overridden.temperature, 0.7)
+  })
+
+  it('limits agents bound to spaces to those spaces', async () => {
+    const agent = await createAgent({ name: 'Legal', provider: 'openai', space_ids: ['legal'] })
+
+    const listed = await (await app.request('GET', '/api/agents?space_id=support')).json()
+    assert.ok(!listed.agents.some(item => item.id === agent.id))
+
+    const refused = await app.postSse(
+      '/api/stream-chat',
+      chatBody({ agent_id: agent.id, space_id: 'support' }),
+    )
+    assert.equal(refused.status, 400)
+    assert.match(refused.body.error, /not available in space support/)
+
+    const spaceless = await app.postSse('/api/stream-chat', chatBody({ agent_id: agent.id }))
+    assert.equal(spaceless.status, 400)
+    assert.match(spaceless.body.error, /only available in its spaces/)
+
+    const missing = await app.postSse('/api/stream-chat', chatBody({ agent_id: 'missing' }))
+    assert.equal(missing.status, 404)
+  })
+})
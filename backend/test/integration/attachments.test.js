@@ -0,0 +1,90 @@
+/**
+ * POST /api/stream-chat with file parts: inline images, sandboxed local files, and the limits
+ */
+
+import assert from 'node:assert/strict'
+import fs from 'fs'
+import os from 'os'
+import path from 'path'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+// 1x1 transparent PNG
+const PNG_BASE64 =
+  'iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mP4z8DwHwAFAAIBpauL0QAAAABJRU5ErkJggg=='
+
+describe('chat attachments', () => {
+  let mock
+  let app
+  let sandboxDir
+
+  const chatBody = content => ({
+    provider: 'openai',
+    apiKey: 'test-key',
+    baseUrl: mock.baseUrl,
+    model: 'mock-model',
+    messages: [{ role: 'user', content }],
+  })
+
+  const lastUserContent = () =>
+    mock
+      .chatRequests()
+      .at(-1)
+      .body.messages.findLast(message => message.role === 'user').content
+
+  before(async () => {
+    sandboxDir = fs.mkdtempSync(path.join(os.tmpdir(), 'qurio-attachments-'))
+    fs.writeFileSync(path.join(sandboxDir, 'notes.md'), '# Notes\n\nThe launch is on Friday.')
+    fs.writeFileSync(path.join(sandboxDir, 'pixel.png'), Buffer.from(PNG_BASE64, 'base64'))
+    mock = await startMockLlmServer()
+    app = await startTestApp({ env: { QURIO_SANDBOX_DIRS: sandboxDir } })
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+    fs.rmSync(sandboxDir, { recursive: true, force: true })
+  })
+
+  it('sends base64 and file:// images as data URLs and local documents as text', async () => {
+    mock.enqueue({ content: 'Seen.' })
+    const { status } = await app.postSse(
+      '/api/stream-chat',
+      chatBody([
+        { type: 'text', text: 'What is in these?' },
+        { type: 'image_base64', data: PNG_BASE64 },
+        { type: 'image_url', image_url: { url: `file://${path.join(sandboxDir, 'pixel.png')}` } },
+        { type: 'file', path: path.join(sandboxDir, 'notes.md') },
+      ]),
+    )
+    assert.equal(status, 200)
+
+    const content = lastUserContent()
+    const images = content.filter(part => part.type === 'image_url')
+    assert.equal(images.length, 2)
+    for (const image of images) {
+      assert.equal(image.image_url.url, `data:image/png;base64,${PNG_BASE64}`)
+    }
+    const text = content.find(part => part.type === 'text' && part.text.includes('notes.md'))
+    assert.match(text.text, /launch is on Friday/)
+  })
+
+  it('rejects paths outside the sandbox', async () => {
+    const { status, body } = await app.postSse(
+      '/api/stream-chat',
+      chatBody([{ type: 'file', path: path.join(os.tmpdir(), 'elsewhere.txt') }]),
+    )
+    assert.equal(status, 403)
+    assert.match(body.message, /outside the sandbox/)
+  })
+
+  it('rejects data that is not an image', async () => {
+    const { status, body } = await app.postSse(
+      '/api/stream-chat',
+      chatBody([{ type: 'image_base64', data: Buffer.from('hello').toString('base64') }]),
+    )
+    assert.equal(status, 400)
+    assert.match(body.message, /Unsupported image type/)
+  })
+})
@@ -0,0 +1,82 @@
+/**
+ * POST /api/providers/:name/probe against the mock server: checks are judged from the model's
+ * answers, conclusive results are cached, and stream-chat refuses what a model was probed to lack
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('capability probe', () => {
+  let mock
+  let app
+
+  const probe = body =>
+    app.postJson('/api/providers/openai/probe', {
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      ...body,
+    })
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('detects JSON mode and answers repeat probes from the cache', async () => {
+    mock.enqueue({ content: '{"ok": true}' })
+    const first = await probe({ checks: ['json_mode'] })
+    assert.equal(first.status, 200)
+    assert.equal(first.body.capabilities.json_mode.supported, true)
+    assert.equal(first.body.cached, false)
+    assert.deepEqual(mock.chatRequests().at(-1).body.response_format, { type: 'json_object' })
+
+    const requestCount = mock.chatRequests().length
+    const second = await probe({ checks: ['json_mode'] })
+    assert.equal(second.body.cached, true)
+    assert.equal(mock.chatRequests().length, requestCount)
+  })
+
+  it('detects tool calls', async () => {
+    mock.enqueue({ toolCalls: [{ name: 'get_probe_value', arguments: {} }] })
+    const { body } = await probe({ checks: ['tool_calls'] })
+    assert.equal(body.capabilities.tool_calls.supported, true)
+  })
+
+  it('lists probed capabilities and rejects images for a model without vision', async () => {
+    mock.enqueue({ content: 'I cannot see images.' })
+    const { body } = await probe({ checks: ['vision'] })
+    assert.equal(body.capabilities.vision.supported, false)
+
+    const chat = await app.postSse('/api/stream-chat', {
+      provider: 'openai',
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      messages: [
+        {
+          role: 'user',
+          content: [
+            { type: 'text', text: 'What is this?' },
+            { type: 'image_url', image_url: { url: 'https://example.com/cat.png' } },
+          ],
+        },
+      ],
+    })
+    assert.equal(chat.status, 400)
+    assert.deepEqual(chat.body.details, ['mock-model does not accept images'])
+  })
+
+  it('rejects unknown checks', async () => {
+    const { status, body } = await probe({ checks: ['telepathy'] })
+    assert.equal(status, 400)
+    assert.match(body.error, /checks must be a list of/)
+  })
+})
@@ -0,0 +1,269 @@
+/**
+ * POST /api/stream-deep-research against the mock LLM server: the plan, step, and report
+ * phases, run persistence, and resuming a run whose report failed
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, beforeEach, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+const QUESTION = 'How does the mock protocol negotiate versions?'
+
+const PLAN = {
+  research_type: 'general',
+  goal: 'Explain version negotiation in the mock protocol',
+  complexity: 'simple',
+  question_type: 'explanation',
+  assumptions: [],
+  plan: [
+    {
+      step: 1,
+      thought: 'Start from the handshake',
+      action: 'Describe the handshake messages',
+      expected_output: 'A paragraph',
+      deliverable_format: 'paragraph',
+      acceptance_criteria: [],
+      depth: 'low',
+      requires_search: false,
+    },
+    {
+      step: 2,
+      thought: 'Then the fallback rules',
+      action: 'Explain the version fallback rules',
+      expected_output: 'A bullet list',
+      deliverable_format: 'bullet_list',
+      acceptance_criteria: [],
+      depth: 'low',
+      requires_search: false,
+    },
+  ],
+  risks: [],
+  success_criteria: [],
+}
+
+const planFixture = () => ({ when: 'You are a task planner', content: JSON.stringify(PLAN) })
+const stepFixture = content => ({ when: 'structured research plan step', content })
+const reportFixture = content => ({ when: 'deep research writer', content })
+const repairFixture = plan => ({
+  when: 'You repair malformed research plans',
+  content: JSON.stringify(plan),
+})
+
+describe('POST /api/stream-deep-research', () => {
+  let mock
+  let app
+
+  const researchBody = (extra = {}) => ({
+    provider: 'openai',
+    apiKey: 'test-key',
+    baseUrl: mock.baseUrl,
+    model: 'mock-model',
+    messages: [{ role: 'user', content: QUESTION }],
+    question: QUESTION,
+    toolIds: [],
+    ...extra,
+  })
+
+  const getRun = async runId => {
+    const response = await app.request('GET', `/api/research-runs/${runId}`)
+    return (await response.json()).run
+  }
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  beforeEach(() => {
+    assert.deepEqual(mock.pending(), [], 'a previous test left fixtures unconsumed')
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('plans, runs each step, writes the report, and persists the run', async () => {
+    mock.enqueue(
+      planFixture(),
+      stepFixture('Finding one: a hello message carries the versions.'),
+      stepFixture('Finding two: the highest shared version wins.'),
+      reportFixture('# Version negotiation\n\nPeers exchange versions and pick the highest.'),
+    )
+
+    const { status, events } = await app.postSse('/api/stream-deep-research', researchBody())
+
+    assert.equal(status, 200)
+    const stepsDone = events.filter(
+      event => event.type === 'research_step' && event.status === 'done',
+    )
+    assert.deepEqual(
+      stepsDone.map(event => event.step),
+      [1, 2],
+    )
+    const done = events.find(event => event.type === 'done')
+    assert.ok(done, 'done event')
+    assert.match(done.content, /pick the highest/)
+    assert.ok(done.runId, 'runId on the done event')
+    assert.ok(
+      !events.some(event => event.type === 'research_checkpoint'),
+      'checkpoints stay internal',
+    )
+
+    const run = await getRun(done.runId)
+    assert.equal(run.status, 'done')
+    assert.equal(run.plan.plan.length, 2)
+    assert.equal(Object.keys(run.completed_steps).length, 2)
+    assert.equal(run.request.apiKey, undefined, 'credentials are not stored')
+  })
+
+  it('re-prompts with validation errors and reports a plan that stays invalid', async () => {
+    const withoutAction = {
+      ...PLAN,
+      plan: PLAN.plan.map(({ action, ...step }, index) => (index ? { action, ...step } : step)),
+    }
+    mock.enqueue(
+      { when: 'You are a task planner', content: JSON.stringify(withoutAction) },
+      repairFixture(withoutAction),
+      repairFixture(withoutAction),
+      stepFixture('Finding one.'),
+      stepFixture('Finding two.'),
+      reportFixture('Report.'),
+    )
+
+    const { events } = await app.postSse('/api/stream-deep-research', researchBody())
+
+    const invalid = events.find(event => event.type === 'plan_invalid')
+    assert.ok(invalid, 'plan_invalid event')
+    assert.deepEqual(invalid.errors, ['plan[0].action: Required'])
+    assert.equal(invalid.attempts, 2)
+    assert.equal(invalid.fallback, false)
+    const repairs = mock
+      .chatRequests()
+      .filter(request => JSON.stringify(request.body).includes('You repair malformed'))
+    assert.equal(repairs.length, 2)
+    assert.match(JSON.stringify(repairs[0].body), /plan\[0\]\.action: Required/)
+    assert.ok(events.some(event => event.type === 'done'), 'research continues')
+  })
+
+  it('accepts a plan the model repairs', async () => {
+    const { goal, ...withoutGoal } = PLAN
+    mock.enqueue(
+      { when: 'You are a task planner', content: JSON.stringify(withoutGoal) },
+      repairFixture({ ...withoutGoal, goal }),
+      stepFixture('Finding one.'),
+      stepFixture('Finding two.'),
+      reportFixture('Report.'),
+    )
+
+    const { events } = await app.postSse('/api/stream-deep-research', researchBody())
+
+    assert.ok(!events.some(event => event.type === 'plan_invalid'))
+    assert.ok(events.some(event => event.type === 'done'))
+  })
+
+  it('resumes a failed run without re-planning or re-running finished steps', async () => {
+    mock.enqueue(
+      planFixture(),
+      stepFixture('Finding one.'),
+      stepFixture('Finding two.'),
+      {
+        when: 'deep research writer',
+        status: 401,
+        error: { message: 'Incorrect API key provided', type: 'invalid_request_error' },
+      },
+    )
+    const failed = await app.postSse('/api/stream-deep-research', researchBody())
+    assert.ok(failed.events.some(event => event.type === 'error'), 'error event')
+
+    const missing = await app.postJson('/api/research-runs/none/resume', { apiKey: 'test-key' })
+    assert.equal(missing.status, 404)
+
+    const runs = (await (await app.request('GET', '/api/research-runs')).json()).runs
+    const stored = runs.find(run => run.status === 'error')
+    assert.ok(stored, 'failed run listed')
+    assert.equal(stored.completedSteps, 2)
+
+    const seen = mock.chatRequests().length
+    mock.enqueue(reportFixture('Resumed report.'))
+    const resumed = await app.postSse(`/api/research-runs/${stored.id}/resume`, {
+      apiKey: 'test-key',
+    })
+
+    const resumedEvent = resumed.events.find(event => event.type === 'research_resumed')
+    assert.equal(resumedEvent?.completed_steps, 2)
+    assert.ok(
+      resumed.events
+        .filter(event => event.type === 'research_step')
+        .every(event => event.status === 'done' && event.resumed),
+      'finished steps are reported as resumed',
+    )
+    assert.match(resumed.events.find(event => event.type === 'done')?.content, /Resumed report/)
+    assert.equal(mock.chatRequests().length - seen, 1, 'only the report was requested')
+    assert.equal((await getRun(stored.id)).status, 'done')
+
+    const again = await app.postJson(`/api/research-runs/${stored.id}/resume`, {
+      apiKey: 'test-key',
+    })
+    assert.equal(again.status, 409)
+  })
+
+  it('follows up a finished run with a plan for the new question only', async () => {
+    mock.enqueue(
+      planFixture(),
+      stepFixture('Finding one: a hello message carries the versions.'),
+      stepFixture('Finding two: the highest shared version wins.'),
+      reportFixture('First report.'),
+    )
+    const first = await app.postSse('/api/stream-deep-research', researchBody())
+    const parentId = first.events.find(event => event.type === 'done')?.runId
+    assert.ok(parentId, 'first run finished')
+
+    const followupPath = `/api/deep-research/${parentId}/followup`
+    assert.equal((await app.postJson(followupPath, { apiKey: 'test-key' })).status, 400)
+    const missing = await app.postJson('/api/deep-research/none/followup', {
+      apiKey: 'test-key',
+      question: 'More?',
+    })
+    assert.equal(missing.status, 404)
+
+    const seen = mock.chatRequests().length
+    mock.enqueue(
+      planFixture(),
+      stepFixture('Finding three: downgrades are signalled.'),
+      stepFixture('Finding four: old peers ignore the signal.'),
+      reportFixture('Follow-up report.'),
+    )
+    const { events } = await app.postSse(followupPath, {
+      apiKey: 'test-key',
+      question: 'Go deeper on the fallback rules',
+    })
+
+    const started = events.find(event => event.type === 'research_followup')
+    assert.equal(started?.parent_run_id, parentId)
+    const done = events.find(event => event.type === 'done')
+    assert.match(done?.content, /Follow-up report/)
+    assert.equal(done.runId, started.runId)
+    assert.notEqual(done.runId, parentId)
+
+    const requests = mock
+      .chatRequests()
+      .slice(seen)
+      .map(request => JSON.stringify(request.body))
+    const planRequest = requests.find(body => body.includes('You are a task planner'))
+    assert.match(planRequest, /Follow-up question: Go deeper on the fallback rules/)
+    assert.match(planRequest, /Finding two: the highest shared version wins/)
+    assert.ok(
+      requests
+        .filter(body => !body.includes('You are a task planner'))
+        .every(body => body.includes('EARLIER RESEARCH') && body.includes('Finding one')),
+      'steps and report see the earlier findings',
+    )
+
+    const run = await getRun(done.runId)
+    assert.equal(run.parentRunId, parentId)
+    assert.equal(run.question, 'Go deeper on the fallback rules')
+    assert.equal(run.plan.followup.findings.length, 2)
+  })
+})
@@ -0,0 +1,77 @@
+/**
+ * Error paths of the streaming routes: request validation before the stream starts, and
+ * provider failures reported as SSE error events
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('streaming error paths', () => {
+  let mock
+  let app
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('rejects a request without a provider', async () => {
+    const { status, body } = await app.postSse('/api/stream-chat', {
+      messages: [{ role: 'user', content: 'hi' }],
+    })
+    assert.equal(status, 400)
+    assert.equal(body.error, 'Missing required field: provider')
+  })
+
+  it('rejects an unsupported provider', async () => {
+    const { status, body } = await app.postSse('/api/stream-chat', {
+      provider: 'nope',
+      apiKey: 'test-key',
+      messages: [{ role: 'user', content: 'hi' }],
+    })
+    assert.equal(status, 400)
+    assert.match(body.error, /Unsupported provider: nope/)
+  })
+
+  it('rejects an out-of-range turn limit', async () => {
+    const { status } = await app.postSse('/api/stream-deep-research', {
+      provider: 'openai',
+      apiKey: 'test-key',
+      messages: [{ role: 'user', content: 'hi' }],
+      max_turns: 0,
+    })
+    assert.equal(status, 400)
+  })
+
+  it('reports a rejected API key as a provider_auth error event', async () => {
+    mock.enqueue({
+      status: 401,
+      error: { message: 'Incorrect API key provided', type: 'invalid_request_error' },
+    })
+
+    const { status, events } = await app.postSse('/api/stream-chat', {
+      provider: 'openai',
+      apiKey: 'bad-key',
+      baseUrl: mock.baseUrl,
+      messages: [{ role: 'user', content: 'hi' }],
+    })
+
+    assert.equal(status, 200)
+    const error = events.find(event => event.type === 'error')
+    assert.ok(error, 'error event')
+    assert.equal(error.code, 'provider_auth')
+    assert.ok(!events.some(event => event.type === 'done'), 'no done event after an error')
+  })
+
+  it('returns 404 for an unknown route', async () => {
+    const response = await app.request('GET', '/api/does-not-exist')
+    assert.equal(response.status, 404)
+  })
+})
@@ -0,0 +1,120 @@
+/**
+ * Image generation against the mock server: POST /api/images/generate stores base64 and URL
+ * answers alike, and the generate_image tool emits image events in stream-chat
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+const PNG_SIGNATURE = '89504e470d0a1a0a'
+
+describe('image generation', () => {
+  let mock
+  let app
+
+  const generate = body =>
+    app.postJson('/api/images/generate', {
+      provider: 'openai',
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      ...body,
+    })
+
+  const fetchImage = async url => {
+    const response = await app.request('GET', url)
+    assert.equal(response.status, 200)
+    assert.equal(response.headers.get('content-type'), 'image/png')
+    return Buffer.from(await response.arrayBuffer())
+  }
+
+  const lastImageRequest = () =>
+    mock.requests.filter(request => request.path === '/v1/images/generations').at(-1).body
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('asks DALL·E for base64 and returns it with the stored file', async () => {
+    const { status, body } = await generate({
+      prompt: 'A lighthouse at dusk',
+      model: 'dall-e-3',
+      n: 2,
+      response_format: 'b64_json',
+    })
+
+    assert.equal(status, 200)
+    assert.equal(lastImageRequest().response_format, 'b64_json')
+    assert.equal(body.model, 'dall-e-3')
+    assert.equal(body.images.length, 2)
+    const [image] = body.images
+    assert.equal(image.mime_type, 'image/png')
+    assert.equal(image.revised_prompt, 'Revised: A lighthouse at dusk')
+    assert.match(image.url, /^\/api\/images\/[\w-]+\/file$/)
+    const stored = await fetchImage(image.url)
+    assert.equal(stored.subarray(0, 8).toString('hex'), PNG_SIGNATURE)
+    assert.equal(stored.toString('base64'), image.b64_json)
+  })
+
+  it('downloads URL answers so the image outlives the provider link', async () => {
+    const { status, body } = await generate({ prompt: 'A red bicycle' })
+
+    assert.equal(status, 200)
+    assert.equal(body.model, 'gpt-image-1')
+    assert.equal(lastImageRequest().response_format, undefined)
+    const [image] = body.images
+    assert.match(image.source_url, /\/files\/generated\.png$/)
+    assert.equal(image.b64_json, undefined)
+    const stored = await fetchImage(image.url)
+    assert.equal(stored.subarray(0, 8).toString('hex'), PNG_SIGNATURE)
+
+    const metadata = await (await app.request('GET', `/api/images/${image.id}`)).json()
+    assert.equal(metadata.prompt, 'A red bicycle')
+    assert.equal(metadata.bytes, stored.length)
+  })
+
+  it('rejects a missing prompt and an out-of-range n', async () => {
+    const missing = await generate({})
+    assert.equal(missing.status, 400)
+    assert.equal(missing.body.error, 'Missing required field: prompt')
+
+    const tooMany = await generate({ prompt: 'Cats', n: 9 })
+    assert.equal(tooMany.status, 400)
+    assert.match(tooMany.body.message, /n must be 1-4/)
+  })
+
+  it('emits an image event when the model calls generate_image', async () => {
+    mock.enqueue(
+      { toolCalls: [{ name: 'generate_image', arguments: { prompt: 'A paper crane' } }] },
+      {
+        when: body => body.messages.some(message => message.role === 'tool'),
+        content: 'Here is your paper crane.',
+      },
+    )
+
+    const { events } = await app.postSse('/api/stream-chat', {
+      provider: 'openai',
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      toolIds: ['generate_image'],
+      messages: [{ role: 'user', content: 'Draw a paper crane' }],
+    })
+
+    const types = events.map(event => event.type)
+    const image = events.find(event => event.type === 'image')
+    assert.ok(image, 'image event')
+    assert.equal(types.indexOf('image'), types.indexOf('tool_result') + 1)
+    assert.equal(image.prompt, 'A paper crane')
+    assert.equal(image.tool_call_id, events.find(event => event.type === 'tool_call').id)
+    await fetchImage(image.url)
+    assert.equal(events.at(-1).content, 'Here is your paper crane.')
+  })
+})
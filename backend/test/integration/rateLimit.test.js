@@ -0,0 +1,99 @@
+/**
+ * Provider rate limiting: requests over a key's per-minute limit are rejected with 429 and
+ * Retry-After in reject mode, other keys are unaffected, non-chat provider routes share the
+ * limit, and queue mode delays instead
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { createRateLimiter } from '../../src/services/providers/rateLimiter.js'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('provider rate limiting', () => {
+  let mock
+  let app
+
+  const requestTitle = apiKey =>
+    app.request('POST', '/api/title', {
+      provider: 'openai',
+      apiKey,
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      message: `Question for ${apiKey}`,
+    })
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp({ env: { RATE_LIMIT_MODE: 'reject', RATE_LIMIT_OPENAI_RPM: '1' } })
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('rejects a second request on the same key and meters keys separately', async () => {
+    mock.enqueue(
+      { content: '{"title":"First","emojis":[]}' },
+      { content: '{"title":"Other key","emojis":[]}' },
+    )
+    assert.equal((await requestTitle('key-a')).status, 200)
+
+    const limited = await requestTitle('key-a')
+    const body = await limited.json()
+    assert.equal(limited.status, 429)
+    assert.equal(body.code, 'provider_rate_limit')
+    assert.ok(Number(limited.headers.get('retry-after')) > 0)
+    assert.ok(body.retry_after_ms > 0)
+
+    assert.equal((await requestTitle('key-b')).status, 200)
+    assert.equal(mock.chatRequests().length, 2)
+  })
+
+  it('meters embeddings by the same key', async () => {
+    const requestEmbeddings = () =>
+      app.request('POST', '/api/embeddings', {
+        provider: 'openai',
+        apiKey: 'key-c',
+        baseUrl: mock.baseUrl,
+        input: 'text',
+      })
+    await requestEmbeddings()
+    const limited = await requestEmbeddings()
+    assert.equal(limited.status, 429)
+    assert.equal((await limited.json()).code, 'provider_rate_limit')
+  })
+
+  it('queues requests over the limit in arrival order', async () => {
+    // 6000 tokens per minute refill at 100 per second
+    const limiter = createRateLimiter({
+      mode: 'queue',
+      env: { RATE_LIMIT_OPENAI_RPM: '0', RATE_LIMIT_OPENAI_TPM: '6000' },
+    })
+    const request = { provider: 'openai', apiKey: 'key' }
+    assert.equal((await limiter.acquire({ ...request, tokens: 6000 })).waitedMs, 0)
+
+    const started = Date.now()
+    const order = []
+    await Promise.all([
+      limiter.acquire({ ...request, tokens: 20 }).then(() => order.push('first')),
+      limiter.acquire({ ...request, tokens: 10 }).then(() => order.push('second')),
+    ])
+    assert.deepEqual(order, ['first', 'second'])
+    assert.ok(Date.now() - started >= 250)
+  })
+
+  it('rejects in queue mode once the wait would exceed the maximum', async () => {
+    const limiter = createRateLimiter({
+      mode: 'queue',
+      maxWaitMs: 1000,
+      env: { RATE_LIMIT_OPENAI_TPM: '6000' },
+    })
+    const request = { provider: 'openai', apiKey: 'key' }
+    await limiter.acquire({ ...request, tokens: 6000 })
+    await assert.rejects(limiter.acquire({ ...request, tokens: 3000 }), {
+      code: 'provider_rate_limit',
+    })
+  })
+})
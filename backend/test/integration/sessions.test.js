@@ -0,0 +1,86 @@
+/**
+ * Per-window sessions: streams are tied to the session that started them, and cancelling one
+ * session's streams leaves the other windows' streams running
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { parseSseEvents, startTestApp } from '../support/testApp.js'
+
+describe('sessions', () => {
+  let mock
+  let app
+
+  const createSession = async label => {
+    const response = await app.request('POST', '/api/sessions', { label })
+    assert.equal(response.status, 201)
+    return (await response.json()).session.session_id
+  }
+
+  // Start a chat stream without waiting for it; resolves to its events once it ends
+  const startStream = (sessionId, question) =>
+    app
+      .request('POST', '/api/stream-chat', {
+        provider: 'openai',
+        apiKey: 'test-key',
+        baseUrl: mock.baseUrl,
+        model: 'mock-model',
+        session_id: sessionId,
+        messages: [{ role: 'user', content: question }],
+      })
+      .then(async response => parseSseEvents(await response.text()))
+
+  const waitForActive = async (sessionId, count) => {
+    for (let attempt = 0; attempt < 100; attempt += 1) {
+      const response = await app.request('GET', `/api/sessions/${sessionId}/active`)
+      const { streams } = await response.json()
+      if (streams.length === count) return streams
+      await new Promise(resolve => setTimeout(resolve, 20))
+    }
+    throw new Error(`session ${sessionId} never had ${count} active streams`)
+  }
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it("cancels only the closing window's streams", async () => {
+    const mainWindow = await createSession('main')
+    const otherWindow = await createSession('quick-ask')
+    mock.enqueue(
+      { when: 'main window question', content: 'Main answer.', holdMs: 1500 },
+      { when: 'other window question', content: 'Other answer.', holdMs: 300 },
+    )
+
+    const mainStream = startStream(mainWindow, 'main window question')
+    const otherStream = startStream(otherWindow, 'other window question')
+    const [active] = await waitForActive(mainWindow, 1)
+    await waitForActive(otherWindow, 1)
+    assert.equal(active.session_id, mainWindow)
+
+    const closed = await (await app.request('DELETE', `/api/sessions/${mainWindow}`)).json()
+    assert.deepEqual(closed.cancelled, [active.stream_id])
+
+    const mainEvents = await mainStream
+    assert.equal(mainEvents.at(-1).type, 'cancelled')
+    const otherEvents = await otherStream
+    assert.equal(otherEvents.at(-1).type, 'done')
+    assert.equal(otherEvents.at(-1).content, 'Other answer.')
+
+    await waitForActive(otherWindow, 0)
+    const { sessions } = await (await app.request('GET', '/api/sessions')).json()
+    assert.deepEqual(sessions.map(session => session.label), ['quick-ask'])
+  })
+
+  it('answers 404 for a session that was never issued', async () => {
+    const response = await app.request('DELETE', '/api/sessions/unknown-session')
+    assert.equal(response.status, 404)
+  })
+})
@@ -0,0 +1,105 @@
+/**
+ * POST /api/stream-chat against the mock LLM server: plain answers and the tool-calling loop
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, beforeEach, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('POST /api/stream-chat', () => {
+  let mock
+  let app
+
+  const chatBody = (content, extra = {}) => ({
+    provider: 'openai',
+    apiKey: 'test-key',
+    baseUrl: mock.baseUrl,
+    model: 'mock-model',
+    messages: [{ role: 'user', content }],
+    ...extra,
+  })
+
+  const textOf = events =>
+    events
+      .filter(event => event.type === 'text')
+      .map(event => event.content)
+      .join('')
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  beforeEach(() => {
+    assert.deepEqual(mock.pending(), [], 'a previous test left fixtures unconsumed')
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('streams the answer and finishes with a done event', async () => {
+    mock.enqueue({ content: 'Hello from the mock model.' })
+
+    const { status, events } = await app.postSse('/api/stream-chat', chatBody('Say hello'))
+
+    assert.equal(status, 200)
+    assert.equal(events[0].type, 'stream_started')
+    assert.equal(textOf(events), 'Hello from the mock model.')
+    const done = events.find(event => event.type === 'done')
+    assert.ok(done, 'done event')
+    assert.equal(done.content, 'Hello from the mock model.')
+    assert.equal(events.at(-1).type, 'done')
+  })
+
+  it('runs a tool call and feeds its result back to the model', async () => {
+    mock.enqueue(
+      { toolCalls: [{ name: 'calculator', arguments: { expression: '2+3' } }] },
+      {
+        when: body => body.messages.some(message => message.role === 'tool'),
+        content: 'The answer is 5.',
+      },
+    )
+    const seen = mock.chatRequests().length
+
+    const { events } = await app.postSse(
+      '/api/stream-chat',
+      chatBody('What is 2+3?', { toolIds: ['calculator'] }),
+    )
+
+    const toolCall = events.find(event => event.type === 'tool_call')
+    assert.equal(toolCall?.name, 'calculator')
+    const toolResult = events.find(event => event.type === 'tool_result')
+    assert.match(JSON.stringify(toolResult), /5/)
+    assert.equal(events.find(event => event.type === 'done')?.content, 'The answer is 5.')
+
+    const [first, second] = mock.chatRequests().slice(seen)
+    assert.ok(
+      first.body.tools.some(tool => tool.function?.name === 'calculator'),
+      'calculator offered to the model',
+    )
+    const toolMessage = second.body.messages.find(message => message.role === 'tool')
+    assert.match(String(toolMessage.content), /5/)
+  })
+
+  it('persists the streamed answer into a stored conversation', async () => {
+    const created = await app.postJson('/api/conversations', { title: 'Integration' })
+    const conversationId = created.body.conversation.id
+    mock.enqueue({ content: 'Stored answer.' })
+
+    const { events } = await app.postSse(
+      '/api/stream-chat',
+      chatBody('Answer and store', { conversation_id: conversationId, persist_message: true }),
+    )
+
+    const createdEvent = events.find(event => event.type === 'message_created')
+    assert.ok(createdEvent, 'message_created event')
+    const response = await app.request('GET', `/api/conversations/${conversationId}/messages`)
+    const { messages } = await response.json()
+    const stored = messages.find(message => message.id === createdEvent.message_id)
+    assert.equal(stored.status, 'complete')
+    assert.equal(stored.content, 'Stored answer.')
+  })
+})
@@ -0,0 +1,89 @@
+/**
+ * GET/PUT/DELETE /api/prompts/:name: overriding a system prompt changes what the model is sent,
+ * and saved prompt ids still reach the saved prompt routes
+ */
+
+import assert from 'node:assert/strict'
+import fs from 'fs'
+import path from 'path'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('system prompts', () => {
+  let mock
+  let app
+
+  const requestTitle = message =>
+    app.postJson('/api/title', {
+      provider: 'openai',
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      message,
+    })
+
+  const lastSystemPrompt = () => {
+    const { messages } = mock.chatRequests().at(-1).body
+    return messages.find(message => message.role === 'system').content
+  }
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('lists the prompts and serves the built-in template', async () => {
+    const list = await (await app.request('GET', '/api/prompts/system')).json()
+    assert.ok(list.prompts.some(prompt => prompt.name === 'research_plan'))
+
+    const { prompt } = await (await app.request('GET', '/api/prompts/title_and_space')).json()
+    assert.equal(prompt.customized, false)
+    assert.equal(prompt.template, prompt.default_template)
+    assert.deepEqual(prompt.variables.map(variable => variable.name), [
+      'spaces',
+      'emoji_instructions',
+    ])
+  })
+
+  it('rejects templates with unknown or missing variables', async () => {
+    const response = await app.request('PUT', '/api/prompts/title_and_space', {
+      template: 'Pick one of {{space_list}}',
+    })
+    const body = await response.json()
+    assert.equal(response.status, 400)
+    assert.equal(body.details.length, 2)
+  })
+
+  it('sends the override to the model and restores the default on delete', async () => {
+    const template = 'Custom title rules. Reply as JSON with "title" and "emojis".'
+    const saved = await app.request('PUT', '/api/prompts/title', { template })
+    assert.equal(saved.status, 200)
+    assert.ok(fs.existsSync(path.join(app.dataDir, 'prompts', 'title.md')))
+
+    mock.enqueue({ content: '{"title":"Custom","emojis":["✨"]}' })
+    const custom = await requestTitle('First question')
+    assert.equal(custom.body.title, 'Custom')
+    assert.equal(lastSystemPrompt(), template)
+
+    const reset = await (await app.request('DELETE', '/api/prompts/title')).json()
+    assert.equal(reset.reset, true)
+    assert.equal(reset.prompt.customized, false)
+
+    mock.enqueue({ content: '{"title":"Default","emojis":["📝"]}' })
+    await requestTitle('Second question')
+    assert.match(lastSystemPrompt(), /^## Task/)
+  })
+
+  it('leaves other ids to the saved prompt routes', async () => {
+    const response = await app.request('GET', '/api/prompts/00000000-0000-0000-0000-000000000000')
+    const body = await response.json()
+    assert.equal(response.status, 404)
+    assert.match(body.error, /Prompt not found/)
+  })
+})
@@ -0,0 +1,85 @@
+/**
+ * Title emoji opt-out: the titleEmojis preference and the per-request "emojis" flag change the
+ * title prompt, and the endpoints return an empty emojis array when emoji are off
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('title emojis', () => {
+  let mock
+  let app
+
+  const requestTitle = (path, body) =>
+    app.postJson(path, {
+      provider: 'openai',
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      ...body,
+    })
+
+  const lastSystemPrompt = () => {
+    const { messages } = mock.chatRequests().at(-1).body
+    return messages.find(message => message.role === 'system').content
+  }
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('asks for one emoji by default', async () => {
+    mock.enqueue({ content: '{"title":"Trip planning","emojis":["🧳"]}' })
+    const { status, body } = await requestTitle('/api/title', { message: 'Plan a trip' })
+
+    assert.equal(status, 200)
+    assert.deepEqual(body.emojis, ['🧳'])
+    assert.match(lastSystemPrompt(), /Select 1 emoji/)
+  })
+
+  it('drops emoji when the request turns them off, even if the model sends some', async () => {
+    mock.enqueue({ content: '{"title":"🚀 Rocket launch","emojis":["🚀"]}' })
+    const { body } = await requestTitle('/api/title', { message: 'Rockets', emojis: false })
+
+    assert.equal(body.title, 'Rocket launch')
+    assert.deepEqual(body.emojis, [])
+    assert.match(lastSystemPrompt(), /Do not use emoji/)
+  })
+
+  it('follows the titleEmojis preference unless the request overrides it', async () => {
+    await app.request('PUT', '/api/preferences', { userId: 'plain', titleEmojis: 'off' })
+
+    mock.enqueue({ content: '{"title":"Garden","spaceLabel":"Home","emojis":["🌱"]}' })
+    const off = await requestTitle('/api/title-and-space', {
+      message: 'Grow tomatoes',
+      spaces: [{ label: 'Home' }],
+      userId: 'plain',
+    })
+    assert.deepEqual(off.body.emojis, [])
+    assert.equal(off.body.space.label, 'Home')
+
+    mock.enqueue({
+      content: '{"title":"Garden","spaceLabel":null,"agentName":null,"emojis":["🌱"]}',
+    })
+    const on = await requestTitle('/api/title-space-agent', {
+      message: 'Grow tomatoes',
+      userId: 'plain',
+      emojis: true,
+    })
+    assert.deepEqual(on.body.emojis, ['🌱'])
+  })
+
+  it('rejects a non-boolean emojis flag', async () => {
+    const { status, body } = await requestTitle('/api/title', { message: 'Hi', emojis: 'no' })
+    assert.equal(status, 400)
+    assert.equal(body.error, 'emojis must be true or false')
+  })
+})
@@ -0,0 +1,84 @@
+/**
+ * Request tracing: a chat with a tool call is recorded as a span tree (model calls with their
+ * prompts, the tool call) under the id from stream_started, with credentials redacted
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+const flatten = span => [span, ...span.children.flatMap(flatten)]
+
+describe('GET /api/traces/:id', () => {
+  let mock
+  let app
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('records model and tool spans of a chat', async () => {
+    mock.enqueue(
+      { toolCalls: [{ name: 'calculator', arguments: { expression: '2+3' } }] },
+      {
+        when: body => body.messages.some(message => message.role === 'tool'),
+        content: 'The answer is 5.',
+      },
+    )
+
+    const { events } = await app.postSse('/api/stream-chat', {
+      provider: 'openai',
+      apiKey: 'test-key',
+      baseUrl: mock.baseUrl,
+      model: 'mock-model',
+      messages: [{ role: 'user', content: 'What is 2+3?' }],
+      toolIds: ['calculator'],
+    })
+    const traceId = events.find(event => event.type === 'stream_started')?.trace_id
+    assert.ok(traceId, 'trace_id on stream_started')
+
+    const response = await app.request('GET', `/api/traces/${traceId}`)
+    assert.equal(response.status, 200)
+    const { trace } = await response.json()
+    assert.equal(trace.kind, 'chat')
+    assert.equal(trace.root.attributes.request.apiKey, '[redacted]')
+
+    const spans = flatten(trace.root)
+    const llmSpans = spans.filter(span => span.name === 'llm')
+    assert.equal(llmSpans.length, 2)
+    assert.match(JSON.stringify(llmSpans[0].attributes.messages), /What is 2\+3\?/)
+    assert.equal(llmSpans[1].attributes.output, 'The answer is 5.')
+    const toolSpan = spans.find(span => span.name === 'tool')
+    assert.equal(toolSpan.attributes.tool, 'calculator')
+    assert.deepEqual(toolSpan.attributes.result, { result: 5 })
+  })
+
+  it('uses a client-supplied X-Trace-Id', async () => {
+    mock.enqueue({ content: '{"title":"Traced","emojis":[]}' })
+    const response = await fetch(`${app.baseUrl}/api/title`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', 'X-Trace-Id': 'bug-report-0001' },
+      body: JSON.stringify({
+        provider: 'openai',
+        apiKey: 'test-key',
+        baseUrl: mock.baseUrl,
+        model: 'mock-model',
+        message: 'Trace this title',
+      }),
+    })
+    assert.equal(response.headers.get('x-trace-id'), 'bug-report-0001')
+    await response.json()
+    assert.equal((await app.request('GET', '/api/traces/bug-report-0001')).status, 200)
+  })
+
+  it('answers 404 for unknown traces', async () => {
+    assert.equal((await app.request('GET', '/api/traces/does-not-exist')).status, 404)
+  })
+})
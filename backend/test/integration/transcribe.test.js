@@ -0,0 +1,101 @@
+/**
+ * POST /api/transcribe against the mock server: raw audio bodies, segment timestamps, and
+ * format checks
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+// Header of a 16 kHz mono PCM WAV with no samples
+const wavHeader = () => {
+  const buffer = Buffer.alloc(44)
+  buffer.write('RIFF', 0, 'latin1')
+  buffer.writeUInt32LE(36, 4)
+  buffer.write('WAVEfmt ', 8, 'latin1')
+  buffer.writeUInt32LE(16, 16)
+  buffer.writeUInt16LE(1, 20)
+  buffer.writeUInt16LE(1, 22)
+  buffer.writeUInt32LE(16000, 24)
+  buffer.writeUInt32LE(32000, 28)
+  buffer.writeUInt16LE(2, 32)
+  buffer.writeUInt16LE(16, 34)
+  buffer.write('data', 36, 'latin1')
+  return buffer
+}
+
+describe('POST /api/transcribe', () => {
+  let mock
+  let app
+
+  const transcribe = async (audio, query = {}, { contentType = 'audio/wav' } = {}) => {
+    const params = new URLSearchParams({ provider: 'openai', baseUrl: mock.baseUrl, ...query })
+    const response = await fetch(`${app.baseUrl}/api/transcribe?${params}`, {
+      method: 'POST',
+      headers: { 'Content-Type': contentType, 'X-Api-Key': 'test-key' },
+      body: audio,
+    })
+    return { status: response.status, body: await response.json() }
+  }
+
+  const lastForm = () =>
+    mock.requests.filter(request => request.path === '/v1/audio/transcriptions').at(-1).fields
+
+  before(async () => {
+    mock = await startMockLlmServer({ transcript: 'Remind me to call the dentist.' })
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('returns the text with timestamped segments from whisper', async () => {
+    const { status, body } = await transcribe(wavHeader(), { filename: 'memo', language: 'en' })
+
+    assert.equal(status, 200)
+    assert.equal(body.text, 'Remind me to call the dentist.')
+    assert.equal(body.model, 'whisper-1')
+    assert.deepEqual(
+      body.segments.map(segment => [segment.start, segment.end]),
+      [
+        [0, 1.2],
+        [1.2, 2.5],
+      ],
+    )
+    assert.equal(body.segments[0].text, 'Remind me to')
+
+    const form = lastForm()
+    assert.equal(form.response_format, 'verbose_json')
+    assert.equal(form.language, 'en')
+    assert.equal(form.file.name, 'memo.wav')
+  })
+
+  it('asks gpt-4o-transcribe for plain json and returns no segments', async () => {
+    const { status, body } = await transcribe(wavHeader(), { model: 'gpt-4o-transcribe' })
+
+    assert.equal(status, 200)
+    assert.equal(body.text, 'Remind me to call the dentist.')
+    assert.deepEqual(body.segments, [])
+    assert.equal(lastForm().response_format, 'json')
+  })
+
+  it('rejects audio it cannot recognise', async () => {
+    const { status, body } = await transcribe(Buffer.from('not audio'), {}, {
+      contentType: 'application/octet-stream',
+    })
+    assert.equal(status, 415)
+    assert.match(body.message, /Unsupported audio type/)
+  })
+
+  it('requires an API key', async () => {
+    const response = await fetch(`${app.baseUrl}/api/transcribe?provider=openai`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'audio/wav' },
+      body: wavHeader(),
+    })
+    assert.equal(response.status, 400)
+  })
+})
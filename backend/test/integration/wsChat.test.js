@@ -0,0 +1,81 @@
+/**
+ * GET /api/ws/chat against the mock LLM server: the same events as POST /api/stream-chat arrive
+ * as frames, and bad requests are refused with an error frame and a close code
+ */
+
+import assert from 'node:assert/strict'
+import { after, before, describe, it } from 'node:test'
+import { startMockLlmServer } from '../support/mockLlmServer.js'
+import { startTestApp } from '../support/testApp.js'
+
+describe('GET /api/ws/chat', () => {
+  let mock
+  let app
+
+  const chatBody = (content, extra = {}) => ({
+    provider: 'openai',
+    apiKey: 'test-key',
+    baseUrl: mock.baseUrl,
+    model: 'mock-model',
+    messages: [{ role: 'user', content }],
+    ...extra,
+  })
+
+  const withoutIds = events =>
+    events.map(event => ({ ...event, stream_id: undefined, trace_id: undefined }))
+
+  before(async () => {
+    mock = await startMockLlmServer()
+    app = await startTestApp()
+  })
+
+  after(async () => {
+    await app?.close()
+    await mock?.close()
+  })
+
+  it('streams the same events as the SSE route', async () => {
+    mock.enqueue({ content: 'Hello over SSE.' }, { content: 'Hello over SSE.' })
+    const sse = await app.postSse('/api/stream-chat', chatBody('Say hello'))
+    const { events, closeCode } = await app.chatOverWebSocket(chatBody('Say hello'))
+
+    assert.equal(closeCode, 1000)
+    assert.equal(events[0].type, 'stream_started')
+    assert.equal(events.at(-1).type, 'done')
+    assert.deepEqual(withoutIds(events), withoutIds(sse.events))
+  })
+
+  it('applies event_filter', async () => {
+    mock.enqueue({ content: 'Filtered.' })
+    const { events } = await app.chatOverWebSocket(
+      chatBody('Say hello', { event_filter: { include: ['text'] } }),
+    )
+    assert.ok(events.some(event => event.type === 'text'))
+    assert.ok(events.every(event => event.type === 'text' || event.type === 'done'))
+    assert.equal(events.at(-1).type, 'done')
+  })
+
+  it('refuses invalid requests with an error frame', async () => {
+    const missing = await app.chatOverWebSocket({ provider: 'openai', apiKey: 'test-key' })
+    assert.equal(missing.closeCode, 1008)
+    assert.deepEqual(missing.events, [
+      {
+        type: 'error',
+        error: 'Missing required field: messages',
+        code: 'invalid_request',
+        status: 400,
+      },
+    ])
+
+    const garbage = await app.chatOverWebSocket('not json')
+    assert.equal(garbage.closeCode, 1007)
+    assert.equal(garbage.events[0].code, 'invalid_request')
+  })
+
+  it('rejects upgrades from origins outside FRONTEND_URLS', async () => {
+    await assert.rejects(
+      app.chatOverWebSocket(chatBody('Hi'), { headers: { Origin: 'https://evil.example' } }),
+      /403/,
+    )
+  })
+})
@@ -0,0 +1,89 @@
+/**
+ * Property tests for academic search DOIs: however a result writes its DOI (bare, "doi:", or a
+ * doi.org URL, in any case), it normalizes to the same key, so one paper found through arXiv,
+ * Semantic Scholar, and PubMed is collected as one source; arXiv titles decode every entity
+ * they may contain, and leave out-of-range character references as written
+ */
+
+import assert from 'node:assert/strict'
+import { afterEach, describe, it } from 'node:test'
+import {
+  normalizeDoi,
+  searchArxiv,
+  toCitationMetadata,
+} from '../../src/services/academicSearchService.js'
+import { forAll } from '../support/property.js'
+
+const SUFFIX_CHARS = 'abcXYZ0189.-_;()/:'
+
+// [as written in arXiv's Atom feed, decoded]
+const TITLE_PARTS = [
+  ['Graph', 'Graph'],
+  ['&amp;', '&'],
+  ['&lt;b&gt;', '<b>'],
+  ['&#233;', 'é'],
+  ['&#x1F600;', '😀'],
+  ['&#99999999;', '&#99999999;'],
+  ['&#x110000;', '&#x110000;'],
+  ['<![CDATA[Q&A]]>', 'Q&A'],
+]
+
+const atomFeed = title =>
+  `<feed><entry><id>http://arxiv.org/abs/2401.01234v1</id><title>${title}</title></entry></feed>`
+
+const generateDoi = random => {
+  const prefix = `10.${random.int(1000, 999999)}`
+  const suffix = Array.from({ length: random.int(1, 20) }, () => random.pick([...SUFFIX_CHARS]))
+  return `${prefix}/${suffix.join('')}`
+}
+
+describe('academic search DOIs', () => {
+  it('normalizes every way of writing a DOI to one key', async () => {
+    await forAll(
+      random => {
+        const doi = generateDoi(random)
+        const written = random.pick([
+          doi,
+          `doi:${doi}`,
+          `doi: ${doi}`,
+          `https://doi.org/${doi}`,
+          `http://dx.doi.org/${doi}`,
+          `https://doi.org/${encodeURIComponent(doi)}`,
+        ])
+        const casing = [...written].map(char => (random.bool() ? char.toUpperCase() : char))
+        return { doi, written: casing.join('') }
+      },
+      ({ doi, written }) => {
+        const key = normalizeDoi(written)
+        assert.equal(key, doi.toLowerCase())
+        assert.equal(normalizeDoi(key), key)
+        assert.equal(toCitationMetadata({ doi: written }).doi, key)
+      },
+    )
+  })
+
+  it('rejects text that is not a DOI', () => {
+    for (const value of ['', null, 'not a doi', '10.12/too-short-prefix', 'https://example.com']) {
+      assert.equal(normalizeDoi(value), null)
+    }
+  })
+})
+
+describe('arXiv entry decoding', () => {
+  const originalFetch = globalThis.fetch
+  afterEach(() => {
+    globalThis.fetch = originalFetch
+  })
+
+  it('decodes titles and keeps out-of-range character references', async () => {
+    await forAll(
+      random => Array.from({ length: random.int(1, 6) }, () => random.pick(TITLE_PARTS)),
+      async parts => {
+        const feed = atomFeed(parts.map(([written]) => written).join(' '))
+        globalThis.fetch = async () => new Response(feed)
+        const { results } = await searchArxiv({ query: 'graphs' })
+        assert.equal(results[0].title, parts.map(([, decoded]) => decoded).join(' '))
+      },
+    )
+  })
+})
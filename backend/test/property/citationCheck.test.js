@@ -0,0 +1,109 @@
+/**
+ * Property tests for the citation check: every citation of a report is checked once per
+ * sentence, citations of sources that do not exist always fail, and a sentence made of its
+ * source's own words always passes
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { checkCitations, extractCitations } from '../../src/services/citationCheckService.js'
+import { forAll } from '../support/property.js'
+
+const WORDS = [
+  'protocol',
+  'handshake',
+  'negotiates',
+  'version',
+  'fallback',
+  'server',
+  'client',
+  'latency',
+  'encryption',
+  'packets',
+  'throughput',
+  'congestion',
+]
+
+const sentence = (random, words) =>
+  Array.from({ length: random.int(3, 8) }, () => random.pick(words)).join(' ')
+
+// Sources have disjoint vocabularies; each report sentence cites one or two sources, possibly
+// past the end of the list
+const generateCase = random => {
+  const sourceCount = random.int(0, 4)
+  const vocabularies = Array.from({ length: sourceCount }, (_, index) =>
+    WORDS.map(word => `${word}${index}`),
+  )
+  const sources = vocabularies.map((_, index) => ({
+    url: `https://example.com/${index + 1}`,
+    title: `Source ${index + 1}`,
+  }))
+  const sourceTexts = new Map(
+    vocabularies.map((words, index) => [sources[index].url, words.join(' ')]),
+  )
+  const sentences = Array.from({ length: random.int(1, 6) }, () => {
+    const cited = [random.int(1, sourceCount + 2)]
+    if (random.bool(0.3)) cited.push(random.int(1, sourceCount + 2))
+    // Words of the first cited source when it exists
+    const words = vocabularies[cited[0] - 1] || WORDS
+    const text = `${sentence(random, words)} [${cited.join(', ')}].`
+    return { cited: [...new Set(cited)], text }
+  })
+  return { sources, sourceTexts, sentences }
+}
+
+describe('citation check', () => {
+  it('fails missing sources and passes claims taken from their source', async () => {
+    await forAll(generateCase, async ({ sources, sourceTexts, sentences }) => {
+      const report = sentences.map(item => item.text).join('\n\n')
+      const check = await checkCitations({ report, sources, sourceTexts })
+      assert.deepEqual(
+        check.citations.map(citation => citation.index),
+        sentences.flatMap(item => item.cited),
+      )
+      sentences
+        .flatMap(item => item.cited.map((index, position) => ({ index, own: position === 0 })))
+        .forEach(({ index, own }, position) => {
+          const citation = check.citations[position]
+          if (index > sources.length) {
+            assert.equal(citation.status, 'fail')
+            assert.equal(citation.reason, 'missing_source')
+          } else if (own) {
+            assert.equal(citation.status, 'pass')
+            assert.equal(citation.overlap, 1)
+          }
+        })
+      assert.equal(check.passed + check.failed + check.unverified, check.citations.length)
+    })
+  })
+
+  it('expands lists and ranges and skips reference lists', () => {
+    const report = [
+      '# Findings',
+      '',
+      'Handshakes negotiate the version [1, 3-4].',
+      '',
+      '## References',
+      '',
+      '[1] Protocol notes https://example.com/1',
+    ].join('\n')
+    const citations = extractCitations(report)
+    assert.deepEqual(
+      citations.map(citation => citation.index),
+      [1, 3, 4],
+    )
+    assert.equal(citations[0].claim, 'Handshakes negotiate the version.')
+  })
+
+  it('compares against re-fetched pages when asked', async () => {
+    const check = await checkCitations({
+      report: 'The fallback version is two [1].',
+      sources: [{ url: 'https://example.com/1', title: 'Notes' }],
+      sourceTexts: new Map([['https://example.com/1', 'Unrelated collected text']]),
+      fetchPage: async () => 'When negotiation fails the fallback version is two.',
+    })
+    assert.equal(check.refetched, 1)
+    assert.equal(check.citations[0].status, 'pass')
+    assert.equal(check.citations[0].evidence, 'page')
+  })
+})
@@ -0,0 +1,95 @@
+/**
+ * Property tests for citation formatting: every source gets exactly one reference under its [n]
+ * number in each style, BibTeX entries stay parseable (unique keys, balanced braces) whatever
+ * the titles and names contain, and replacing a report's reference list leaves the rest intact
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import {
+  CITATION_STYLES,
+  formatReferences,
+  formatReferencesMarkdown,
+  replaceReferencesSection,
+  toCslItem,
+} from '../../src/services/citationFormatService.js'
+import { forAll } from '../support/property.js'
+
+const TITLE_PARTS = ['Study', 'of', 'R&D', '50%', 'C#', '{braces}', 'a_b', '$x$', '*bold*', '中文']
+const NAMES = ['Ada Lovelace', 'Lovelace, Ada', 'Smith JM', 'WHO Working Group', 'José Núñez']
+const URLS = [
+  'https://doi.org/10.1000/abc.1',
+  'https://arxiv.org/abs/2401.01234v2',
+  'https://www.tandfonline.com/doi/full/10.1080/123.456',
+  'https://example.com/post?id=1',
+  'https://news.example.org/a_b',
+]
+
+const ACCESSED = new Date('2026-10-15T00:00:00Z')
+
+const generateSources = random =>
+  Array.from({ length: random.int(1, 8) }, () => ({
+    title: Array.from({ length: random.int(1, 5) }, () => random.pick(TITLE_PARTS)).join(' '),
+    url: random.pick(URLS),
+    ...(random.bool()
+      ? { authors: Array.from({ length: random.int(0, 8) }, () => random.pick(NAMES)) }
+      : {}),
+    ...(random.bool() ? { year: random.int(1990, 2026) } : {}),
+    ...(random.bool() ? { venue: random.pick(['Nature', 'arXiv', 'J. Things & Stuff']) } : {}),
+  }))
+
+const isBalanced = text => {
+  let depth = 0
+  for (let index = 0; index < text.length; index += 1) {
+    if (text[index] === '\\') index += 1
+    else if (text[index] === '{') depth += 1
+    else if (text[index] === '}' && --depth < 0) return false
+  }
+  return depth === 0
+}
+
+describe('citation formatting', () => {
+  it('gives every source one reference under its number in every style', async () => {
+    await forAll(generateSources, sources => {
+      const items = sources.map((source, index) => toCslItem(source, index, ACCESSED))
+      for (const style of CITATION_STYLES) {
+        const references = formatReferences(items, style)
+        assert.deepEqual(
+          references.map(reference => reference.index),
+          sources.map((_, index) => index + 1),
+        )
+        const markdown = formatReferencesMarkdown(references, style)
+        const labels =
+          style === 'bibtex'
+            ? markdown.match(/^% \[\d+\]$/gm)
+            : style === 'ieee'
+              ? markdown.match(/^\[\d+\] /gm)
+              : markdown.match(/^\d+\. /gm)
+        assert.equal(labels.length, sources.length, `${style} labels`)
+      }
+
+      const bibtex = formatReferences(items, 'bibtex')
+      const keys = bibtex.map(({ text }) => text.match(/^@\w+\{([^,]+),/)[1])
+      assert.equal(new Set(keys).size, keys.length)
+      for (const { text } of bibtex) assert.ok(isBalanced(text), text)
+    })
+  })
+
+  it('replaces only the reference list of a report', async () => {
+    await forAll(
+      random => ({
+        heading: random.pick(['## References', '## 7. REFERENCES', '### Sources', '## 参考文献']),
+        before: random.pick(['# Report\n\nText [1].', '# Report\n\n## Findings\n\nA [2].']),
+        after: random.pick(['', '\n\n## Citation check\n\nok', '\n\n## Glossary\n\n- term']),
+      }),
+      ({ heading, before, after }) => {
+        const report = `${before}\n\n${heading}\n\n[1] Old entry\n[2] Another${after}\n`
+        const revised = replaceReferencesSection(report, 'NEW LIST')
+        assert.ok(revised.startsWith(`${before}\n\n${heading}\n\nNEW LIST\n`))
+        assert.ok(!revised.includes('Old entry'))
+        if (after) assert.ok(revised.includes(after.trim()))
+        assert.equal(replaceReferencesSection(revised, 'NEW LIST'), revised)
+      },
+    )
+  })
+})
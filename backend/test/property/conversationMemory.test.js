@@ -0,0 +1,154 @@
+/**
+ * Property tests for conversation memory: a history over its token budget keeps its system
+ * messages and recent turns verbatim behind one memory note, every older turn is summarized
+ * exactly once as the conversation grows, and editing an old turn rebuilds the memory
+ */
+
+import assert from 'node:assert/strict'
+import { beforeEach, describe, it } from 'node:test'
+import {
+  clearConversationMemories,
+  compactConversation,
+  estimateMessageTokens,
+} from '../../src/services/conversationMemoryService.js'
+import { forAll } from '../support/property.js'
+
+const WORDS = ['tide', 'harbor', 'ledger', 'quartz', 'signal', 'meadow', 'copper', 'orbit']
+
+const sentence = (random, maxWords) =>
+  Array.from({ length: random.int(1, maxWords) }, () => random.pick(WORDS)).join(' ')
+
+// User turns, each answered directly or through a tool call and its result
+const generateHistory = (random, turns) => {
+  const history = []
+  for (let turn = 0; turn < turns; turn += 1) {
+    history.push({ role: 'user', content: `${turn}: ${sentence(random, 60)}` })
+    if (random.bool(0.3)) {
+      history.push({
+        role: 'assistant',
+        content: '',
+        tool_calls: [{ id: `call_${turn}`, function: { name: 'calculator', arguments: '{}' } }],
+      })
+      history.push({ role: 'tool', tool_call_id: `call_${turn}`, content: sentence(random, 40) })
+    }
+    history.push({ role: 'assistant', content: sentence(random, 80) })
+  }
+  return history
+}
+
+// Records every turn it is given; the memory lists the turns folded so far
+const createRecordingSummarizer = () => {
+  const folded = []
+  const summarize = async (memory, turns) => {
+    folded.push(...turns)
+    return [memory, ...turns.map(turn => `- ${turn.role}`)].filter(Boolean).join('\n')
+  }
+  return { folded, summarize }
+}
+
+const tokensOf = messages =>
+  messages.reduce((sum, message) => sum + estimateMessageTokens(message), 0)
+
+describe('conversation memory', () => {
+  beforeEach(() => clearConversationMemories())
+
+  it('keeps system messages and recent turns behind one memory note', async () => {
+    await forAll(
+      random => ({
+        history: generateHistory(random, random.int(1, 12)),
+        tokenBudget: random.int(500, 3000),
+        keepMessages: random.bool() ? random.int(1, 10) : undefined,
+      }),
+      async ({ history, tokenBudget, keepMessages }) => {
+        const system = { role: 'system', content: 'You are helpful.' }
+        const messages = [system, ...history]
+        const { summarize } = createRecordingSummarizer()
+        const result = await compactConversation(messages, {
+          tokenBudget,
+          keepMessages,
+          summarize,
+        })
+
+        if (!result.memory) {
+          assert.equal(result.messages, messages)
+          return
+        }
+        const [first, note, ...recent] = result.messages
+        assert.equal(first, system)
+        assert.equal(note.role, 'system')
+        assert.match(note.content, /^Conversation memory/)
+        assert.equal(result.memory.summarized_messages + recent.length, history.length)
+        assert.deepEqual(recent, history.slice(result.memory.summarized_messages))
+        assert.equal(recent[0].role, 'user')
+        const lastUser = history.findLastIndex(message => message.role === 'user')
+        assert.ok(result.memory.summarized_messages <= lastUser)
+        // Beyond the last user turn, the verbatim turns stay within the budget and the limit
+        if (result.memory.summarized_messages < lastUser) {
+          assert.ok(tokensOf(recent) <= tokenBudget)
+          if (keepMessages) assert.ok(recent.length <= keepMessages)
+        }
+      },
+    )
+  })
+
+  it('summarizes every older turn once as the conversation grows', async () => {
+    await forAll(
+      random => ({
+        history: generateHistory(random, random.int(4, 16)),
+        tokenBudget: random.int(500, 1500),
+        steps: random.int(1, 4),
+      }),
+      async ({ history, tokenBudget, steps }) => {
+        clearConversationMemories()
+        const { folded, summarize } = createRecordingSummarizer()
+        const options = { tokenBudget, conversationId: 'conversation-1', summarize }
+        // Each request ends with the user's newest turn; some requests are skipped
+        const ends = history
+          .map((message, index) => (message.role === 'user' ? index + 1 : 0))
+          .filter(Boolean)
+          .filter((end, turn) => turn % steps === 0 || end === history.length)
+        let summarized = 0
+        let last = []
+        for (const end of ends) {
+          last = history.slice(0, end)
+          const { memory } = await compactConversation(last, options)
+          if (!memory) continue
+          assert.ok(memory.summarized_messages >= summarized)
+          assert.equal(memory.cached, memory.summarized_messages === summarized)
+          summarized = memory.summarized_messages
+          assert.deepEqual(folded, history.slice(0, summarized))
+        }
+
+        // Asking again with the same history summarizes nothing new
+        const again = await compactConversation(last, options)
+        assert.equal(again.memory?.cached ?? true, true)
+        assert.deepEqual(folded, history.slice(0, summarized))
+      },
+    )
+  })
+
+  it('rebuilds the memory when an older turn changes', async () => {
+    await forAll(
+      random => ({ history: generateHistory(random, random.int(8, 16)) }),
+      async ({ history }) => {
+        clearConversationMemories()
+        const options = { tokenBudget: 500, conversationId: 'conversation-2' }
+        const first = createRecordingSummarizer()
+        const { memory } = await compactConversation(history, {
+          ...options,
+          summarize: first.summarize,
+        })
+        assert.ok(memory)
+
+        const edited = [{ ...history[0], content: 'edited' }, ...history.slice(1)]
+        const second = createRecordingSummarizer()
+        const rebuilt = await compactConversation(edited, {
+          ...options,
+          summarize: second.summarize,
+        })
+        assert.equal(rebuilt.memory.cached, false)
+        assert.deepEqual(second.folded, edited.slice(0, rebuilt.memory.summarized_messages))
+      },
+    )
+  })
+})
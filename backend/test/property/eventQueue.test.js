@@ -0,0 +1,58 @@
+/**
+ * Property tests for the event queue: events pushed by concurrently running tasks are yielded
+ * once each, in push order, before the task's result is returned or its error thrown
+ */
+
+import assert from 'node:assert/strict'
+import { setTimeout as sleep } from 'node:timers/promises'
+import { describe, it } from 'node:test'
+import { yieldWhileRunning } from '../../src/utils/eventQueue.js'
+import { forAll } from '../support/property.js'
+
+// Workers that each push a few events, some after a delay
+const generateWorkers = random =>
+  Array.from({ length: random.int(1, 4) }, (_, worker) =>
+    Array.from({ length: random.int(0, 4) }, (_, index) => ({
+      id: `${worker}.${index}`,
+      delayMs: random.pick([0, 0, 1, 2]),
+    })),
+  )
+
+const collect = async (generator, events) => {
+  for (;;) {
+    const { value, done } = await generator.next()
+    if (done) return value
+    events.push(value)
+  }
+}
+
+describe('event queue', () => {
+  it('yields every pushed event in order and returns the result', async () => {
+    await forAll(
+      random => ({ workers: generateWorkers(random), fail: random.bool(0.3) }),
+      async ({ workers, fail }) => {
+        const pushed = []
+        const run = push =>
+          Promise.all(
+            workers.map(async steps => {
+              for (const { id, delayMs } of steps) {
+                if (delayMs) await sleep(delayMs)
+                pushed.push(id)
+                push({ id })
+              }
+            }),
+          ).then(() => {
+            if (fail) throw new Error('task failed')
+            return 'result'
+          })
+
+        const events = []
+        const outcome = collect(yieldWhileRunning(run), events)
+        if (fail) await assert.rejects(outcome, /task failed/)
+        else assert.equal(await outcome, 'result')
+        assert.deepEqual(events.map(event => event.id), pushed)
+      },
+      { runs: 100 },
+    )
+  })
+})
@@ -0,0 +1,69 @@
+/**
+ * Property tests for the bounded line reader behind the provider stream parsers: any chunking
+ * yields the same lines, and the partial-line buffer never exceeds its bound
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { readLineBatches } from '../../src/utils/lineReader.js'
+import { readServerSentEvents } from '../../src/utils/sse.js'
+import { createRandom, forAll, randomChunks } from '../support/property.js'
+
+const LINE_PIECES = ['{"done":false}', 'data: x', 'é', '中文', '😀', ' ', 'word']
+const LINE_ENDINGS = ['\n', '\r\n', '\r']
+
+async function* toBody(chunks) {
+  for (const chunk of chunks) yield chunk
+}
+
+const readAll = async (chunks, options) => {
+  const lines = []
+  for await (const batch of readLineBatches(toBody(chunks), options)) lines.push(...batch)
+  return lines
+}
+
+const generateLines = random => {
+  const lines = Array.from({ length: random.int(0, 8) }, () =>
+    Array.from({ length: random.int(0, 5) }, () => random.pick(LINE_PIECES)).join(''),
+  )
+  // One ending style per stream (a CR followed by an LF ending would read as one CRLF); the last
+  // line may have none
+  const ending = random.pick(LINE_ENDINGS)
+  const lastEnded = random.bool()
+  const text = lines
+    .map((line, index) => (index < lines.length - 1 || lastEnded ? `${line}${ending}` : line))
+    .join('')
+  // A last empty line without a line break is not a line
+  const expected = lines.length && !lastEnded && !lines.at(-1) ? lines.slice(0, -1) : lines
+  const bytes = new TextEncoder().encode(text)
+  return { expected, chunks: randomChunks(random, bytes, 9) }
+}
+
+describe('readLineBatches', () => {
+  it('yields the same lines whatever the byte boundaries', async () => {
+    await forAll(generateLines, async ({ expected, chunks }) => {
+      assert.deepEqual(await readAll(chunks), expected)
+    })
+  })
+
+  it('accepts a long line split across chunks up to the bound', async () => {
+    const line = 'x'.repeat(5000)
+    const chunks = randomChunks(createRandom(1), `${line}\nnext\n`, 700)
+    assert.deepEqual(await readAll(chunks, { maxLineBytes: 5000 }), [line, 'next'])
+  })
+
+  it('fails a stream whose partial line outgrows the bound', async () => {
+    const chunks = Array.from({ length: 20 }, () => 'y'.repeat(100))
+    await assert.rejects(readAll(chunks, { maxLineBytes: 1024 }), { code: 'upstream' })
+  })
+
+  it('fails an SSE message whose data lines outgrow the bound', async () => {
+    const chunks = Array.from({ length: 50 }, () => 'data: zzzzzzzzzz\n')
+    const read = async () => {
+      for await (const message of readServerSentEvents(toBody(chunks), { maxLineBytes: 200 })) {
+        assert.fail(`unexpected message ${message.data}`)
+      }
+    }
+    await assert.rejects(read(), { code: 'upstream' })
+  })
+})
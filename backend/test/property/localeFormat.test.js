@@ -0,0 +1,84 @@
+/**
+ * Property tests for report locale formatting: a report written with one locale's separators
+ * comes out exactly as if it had been written for the other, and code and citations are untouched
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { formatForLocale } from '../../src/services/localeFormatService.js'
+import { forAll } from '../support/property.js'
+
+const WORDS = ['revenue', 'rose', 'to', 'about', 'while', 'costs', 'were', 'and', '—', '(', ')']
+
+const decimal = random => {
+  const integer = String(random.int(0, 10 ** random.int(1, 9)))
+  const fraction = String(random.int(0, 999)).padStart(random.int(1, 3), '0')
+  return `${integer}.${fraction}`
+}
+
+// The same report rendered for both locales; the first number always has a group separator and
+// a decimal part, so the report's convention is never in doubt
+const generateReport = random => {
+  const numbers = [`${random.int(1000, 99999)}.${random.int(10, 99)}`]
+  for (let index = random.int(0, 8); index > 0; index -= 1) numbers.push(decimal(random))
+  const words = numbers.map(() => random.pick(WORDS))
+  const render = locale =>
+    numbers
+      .map((value, index) => {
+        const digits = value.split('.')[1].length
+        const formatted = new Intl.NumberFormat(locale, {
+          minimumFractionDigits: digits,
+          maximumFractionDigits: digits,
+        }).format(value)
+        return `${words[index]} ${formatted}`
+      })
+      .join(' ')
+  return { english: render('en-US'), german: render('de-DE') }
+}
+
+describe('report locale formatting', () => {
+  it('converts between en-US and de-DE separators both ways', async () => {
+    await forAll(generateReport, ({ english, german }) => {
+      assert.equal(formatForLocale(english, 'de-DE').content, german)
+      assert.equal(formatForLocale(german, 'en-US').content, english)
+    })
+  })
+
+  it('never touches code, citations, links or outline numbers', async () => {
+    await forAll(
+      random => {
+        const value = decimal(random)
+        return random.pick([
+          `\`x = ${value}\``,
+          `\n\`\`\`\ny = ${value}\n\`\`\``,
+          `[${random.int(1, 9)},${random.int(1, 9)}]`,
+          `[source](https://example.com/v${value})`,
+          `\n## ${random.int(1, 9)}.${random.int(1, 9)} Background`,
+          `version ${value}`,
+        ])
+      },
+      input => {
+        const { content } = formatForLocale(`Total 1,234.56 and ${input}`, 'de-DE')
+        assert.equal(content, `Total 1.234,56 and ${input}`)
+      },
+    )
+  })
+
+  it('rewrites numeric dates and unit spacing', () => {
+    const report = 'Launched 2024-03-05, reviewed 15/03/2024; up 12.5% at 20°C.'
+    assert.equal(
+      formatForLocale(report, 'de-DE').content,
+      // de-DE puts a no-break space before %
+      'Launched 05.03.2024, reviewed 15.03.2024; up 12,5\u00a0% at 20 °C.',
+    )
+    assert.equal(
+      formatForLocale('Released 05.03.2024 at 20 °C', 'en-US').content,
+      'Released 03/05/2024 at 20°C',
+    )
+  })
+
+  it('leaves ambiguous numbers and dates alone', () => {
+    const report = 'Between 03/04/2024 and 04/03/2024 we sold 1,234 units.'
+    assert.deepEqual(formatForLocale(report, 'de-DE'), { content: report, changes: 0 })
+  })
+})
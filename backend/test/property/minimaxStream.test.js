@@ -0,0 +1,102 @@
+/**
+ * Property tests for MiniMax stream normalization: however the text is chunked, tool calls
+ * written as <minimax:tool_call> markup are parsed out of it and the rest is shown unchanged,
+ * and reasoning_details that repeat the reasoning so far come out as increments
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { MinimaxAdapter } from '../../src/services/providers/MinimaxAdapter.js'
+import { forAll, randomChunks } from '../support/property.js'
+
+const TEXT = ['Sure', ' ', 'a < b', '<', '<minimax:', 'tool', '\n', '中文', '</invoke>', 'done.']
+
+const toolCallMarkup = calls =>
+  '<minimax:tool_call>\n' +
+  calls
+    .map(
+      ({ name, query, limit }) =>
+        `<invoke name="${name}">` +
+        `<parameter name="query">${query}</parameter>` +
+        `<parameter name="limit">${limit}</parameter></invoke>\n`,
+    )
+    .join('') +
+  '</minimax:tool_call>'
+
+const generateCase = random => {
+  const text = Array.from({ length: random.int(0, 8) }, () => random.pick(TEXT)).join('')
+  const calls = random.bool(0.7)
+    ? Array.from({ length: random.int(1, 3) }, (_, index) => ({
+        name: random.pick(['web_search', 'calculator']),
+        query: `query ${index}`,
+        limit: random.int(1, 10),
+      }))
+    : []
+  const after = random.bool(0.3) ? random.pick(TEXT) : ''
+  const reasoning = Array.from({ length: random.int(0, 5) }, () => random.pick(TEXT)).join('')
+  const content = `${text}${calls.length ? toolCallMarkup(calls) : ''}${after}`
+  return {
+    calls,
+    visible: `${text}${after}`,
+    reasoning,
+    reasoningChunks: randomChunks(random, reasoning, 4),
+    contentChunks: randomChunks(random, content, 12),
+  }
+}
+
+// OpenAI-compatible stream chunks: reasoning_details carry the reasoning so far
+const toChunks = ({ reasoningChunks, contentChunks }) => {
+  let reasoningSoFar = ''
+  const reasoningPart = reasoningChunks.map(piece => {
+    reasoningSoFar += piece
+    const details = [{ type: 'reasoning.text', text: reasoningSoFar }]
+    const delta = { content: '', reasoning_details: details }
+    return { content: '', additional_kwargs: { __raw_response: { choices: [{ delta }] } } }
+  })
+  const contentPart = contentChunks.map(content => ({
+    content,
+    additional_kwargs: { __raw_response: { choices: [{ delta: { content } }] } },
+  }))
+  return [...reasoningPart, ...contentPart]
+}
+
+const runStream = async chunks => {
+  const adapter = new MinimaxAdapter()
+  const model = {
+    stream: async () =>
+      (async function* () {
+        yield* chunks
+      })(),
+  }
+  let text = ''
+  let thought = ''
+  let toolCalls = []
+  let finishReason = null
+  for await (const chunk of await adapter.createStreamIterator(model, [])) {
+    const choice = chunk.additional_kwargs?.__raw_response?.choices?.[0]
+    text += chunk.content || ''
+    thought += adapter.extractThinkingContent(chunk) || ''
+    if (choice?.delta?.tool_calls) toolCalls = [...toolCalls, ...choice.delta.tool_calls]
+    finishReason = choice?.finish_reason || finishReason
+  }
+  return { text, thought, toolCalls, finishReason }
+}
+
+describe('MiniMax stream normalization', () => {
+  it('parses markup tool calls and turns repeated reasoning into increments', async () => {
+    await forAll(generateCase, async testCase => {
+      const { text, thought, toolCalls, finishReason } = await runStream(toChunks(testCase))
+      assert.equal(text, testCase.visible)
+      assert.equal(thought, testCase.reasoning)
+      assert.deepEqual(
+        toolCalls.map(call => [
+          call.index,
+          call.function.name,
+          JSON.parse(call.function.arguments),
+        ]),
+        testCase.calls.map(({ name, query, limit }, index) => [index, name, { query, limit }]),
+      )
+      assert.equal(finishReason, testCase.calls.length ? 'tool_calls' : null)
+    })
+  })
+})
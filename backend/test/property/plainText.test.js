@@ -0,0 +1,94 @@
+/**
+ * Property tests for the plain text converter: chunking never changes the output, and no emoji
+ * or citation bracket reaches a screen reader
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { createPlainTextConverter, toPlainText } from '../../src/services/plainTextStream.js'
+import { forAll, randomChunks } from '../support/property.js'
+
+const FRAGMENTS = [
+  '# ',
+  '## ',
+  '- ',
+  '> ',
+  '1. ',
+  '**',
+  '*',
+  '_',
+  '~~',
+  '`',
+  '[1]',
+  '[2, 3]',
+  '[^4]',
+  '【5】',
+  '[link](https://example.com)',
+  '![alt](img.png)',
+  '| a | b |',
+  '|---|---|',
+  '```',
+  '\n',
+  '\n\n',
+  ' ',
+  'word',
+  'snake_case',
+  'é',
+  '中文',
+  '😀',
+  '👍🏽',
+  '🇩🇪',
+  '👨‍👩‍👧',
+]
+
+// Lines stay under the converter's early-flush length, so only chunking varies
+const generateMarkdown = random =>
+  Array.from({ length: random.int(0, 40) }, () =>
+    random.bool(0.7) ? random.pick(FRAGMENTS) : String.fromCharCode(random.int(32, 126)),
+  ).join('')
+
+const convert = chunks => {
+  const converter = createPlainTextConverter()
+  return chunks.map(chunk => converter.push(chunk)).join('') + converter.flush()
+}
+
+const EMOJI = /\p{Extended_Pictographic}|\p{Regional_Indicator}/u
+
+describe('plain text conversion', () => {
+  it('produces the same text for any chunking', async () => {
+    await forAll(
+      random => {
+        const input = generateMarkdown(random)
+        return { input, chunks: randomChunks(random, input, 12) }
+      },
+      ({ input, chunks }) => {
+        assert.equal(convert(chunks), convert([input]))
+      },
+    )
+  })
+
+  it('removes every emoji and citation outside code', async () => {
+    await forAll(
+      // Without backticks and ~~~ there is no code to keep verbatim
+      random => generateMarkdown(random).replace(/`/g, '').replace(/~{3,}/g, ''),
+      input => {
+        const { text } = toPlainText(input)
+        assert.doesNotMatch(text, EMOJI)
+        assert.doesNotMatch(text, /\[\^?\d+\]|【\d+】/)
+      },
+    )
+  })
+
+  it('moves citations to a list in order of first use', () => {
+    const markdown = '**Rust** is fast [2] and safe [1, 2].\n\n- Go [3-4] 🚀'
+    const { text, citations } = toPlainText(markdown)
+    assert.equal(text, 'Rust is fast and safe.\n\nGo')
+    assert.deepEqual(citations, [2, 1, 3, 4])
+  })
+
+  it('keeps code blocks and inline code verbatim', () => {
+    const { text, citations } = toPlainText('Use `arr[1]`:\n```js\nconst x = a[2] // **no**\n```')
+    assert.equal(text, 'Use arr[1]:\nconst x = a[2] // **no**')
+    assert.deepEqual(citations, [])
+  })
+})
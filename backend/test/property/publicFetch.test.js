@@ -0,0 +1,78 @@
+/**
+ * Property tests for the public fetch guard: loopback, private, link-local, and metadata
+ * addresses are refused however they are written (IPv4, IPv4-mapped IPv6, bracketed URL hosts),
+ * as are this machine's own addresses, while public addresses pass
+ */
+
+import assert from 'node:assert/strict'
+import os from 'node:os'
+import { describe, it } from 'node:test'
+import { assertPublicUrl, isPublicAddress } from '../../src/utils/publicFetch.js'
+import { forAll } from '../support/property.js'
+
+// [first octets, random octets to fill]
+const PRIVATE_V4 = [
+  [[127], 3],
+  [[10], 3],
+  [[169, 254], 2],
+  [[192, 168], 2],
+  [[0], 3],
+]
+const PRIVATE_V6 = ['::1', '::', 'fd00:ec2::254', 'fe80::1', 'fc00::abcd']
+
+const privateV4 = random => {
+  const [prefix, fill] = random.pick(PRIVATE_V4)
+  const octets = [...prefix, ...Array.from({ length: fill }, () => random.int(0, 255))]
+  if (random.bool(0.2)) octets.splice(0, 2, 172, random.int(16, 31))
+  return octets.join('.')
+}
+
+const toMappedHex = v4 => {
+  const [a, b, c, d] = v4.split('.').map(Number)
+  return `::ffff:${((a << 8) | b).toString(16)}:${((c << 8) | d).toString(16)}`
+}
+
+// Addresses written the ways a URL or a resolver may hand them over
+const privateAddress = random => {
+  const v4 = privateV4(random)
+  return random.pick([v4, `::ffff:${v4}`, toMappedHex(v4), random.pick(PRIVATE_V6)])
+}
+
+const publicAddress = random => {
+  if (random.bool()) {
+    return [random.int(1, 9), random.int(0, 255), random.int(0, 255), random.int(1, 254)].join('.')
+  }
+  const hex = (min, max) => random.int(min, max).toString(16)
+  return `2606:4700:${hex(0, 0xffff)}::${hex(1, 0xffff)}`
+}
+
+describe('public fetch guard', () => {
+  it('refuses non-public addresses in any notation', async () => {
+    await forAll(privateAddress, async address => {
+      assert.equal(isPublicAddress(address), false)
+      const host = address.includes(':') ? `[${address}]` : address
+      await assert.rejects(assertPublicUrl(`http://${host}:3001/api/health`), {
+        name: 'UnsafeUrlError',
+      })
+    })
+  })
+
+  it("refuses the cloud metadata endpoint, localhost, and this machine's addresses", async () => {
+    await assert.rejects(assertPublicUrl('http://169.254.169.254/latest/meta-data/'), {
+      name: 'UnsafeUrlError',
+    })
+    await assert.rejects(assertPublicUrl('http://localhost:3001/api/settings'), {
+      name: 'UnsafeUrlError',
+    })
+    await assert.rejects(assertPublicUrl('file:///etc/passwd'), { name: 'UnsafeUrlError' })
+    for (const { address } of Object.values(os.networkInterfaces()).flat()) {
+      assert.equal(isPublicAddress(address.split('%')[0]), false)
+    }
+  })
+
+  it('allows public addresses', async () => {
+    await forAll(publicAddress, address => {
+      assert.equal(isPublicAddress(address), true)
+    })
+  })
+})
@@ -0,0 +1,62 @@
+/**
+ * Property tests for question decomposition: however the sub-answers number and share their
+ * sources, every citation in the renumbered answers points at the same source in the merged list
+ * as it did in its own sub-answer, and the merged list follows sub-question order
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { mergeSubAnswerSources } from '../../src/services/questionDecompositionService.js'
+import { forAll } from '../support/property.js'
+
+const URLS = Array.from({ length: 8 }, (_, index) => `https://example.com/${index}`)
+
+// Sub-answers citing their own sources, some of which other sub-answers also found
+const generateSubAnswers = random =>
+  Array.from({ length: random.int(2, 5) }, (_, index) => {
+    const urls = [...new Set(Array.from({ length: random.int(0, 4) }, () => random.pick(URLS)))]
+    const cite = () => `[${random.int(1, urls.length)}]`
+    const parts = urls.length
+      ? Array.from({ length: random.int(1, 5) }, () =>
+          random.pick([cite(), `${cite().slice(0, -1)}, ${random.int(1, urls.length)}]`, 'text']),
+        )
+      : ['no sources']
+    return {
+      question: `Part ${index + 1}`,
+      answer: parts.join(' '),
+      sources: urls.map(url => ({ url, title: url })),
+    }
+  })
+
+const citedUrls = (answer, sources) =>
+  Array.from(answer.matchAll(/\[(\d+(?:\s*,\s*\d+)*)\]/g)).flatMap(([, list]) =>
+    list.split(',').map(number => sources[Number(number) - 1]?.url),
+  )
+
+describe('question decomposition sources', () => {
+  it('keeps every citation on its source after merging', async () => {
+    await forAll(generateSubAnswers, subAnswers => {
+      const merged = mergeSubAnswerSources(subAnswers)
+      const expectedOrder = [...new Set(subAnswers.flatMap(item => item.sources.map(s => s.url)))]
+      assert.deepEqual(merged.sources.map(source => source.url), expectedOrder)
+      subAnswers.forEach((original, index) => {
+        assert.deepEqual(
+          citedUrls(merged.subAnswers[index].answer, merged.sources),
+          citedUrls(original.answer, original.sources),
+        )
+      })
+    })
+  })
+
+  it('leaves Markdown links alone', () => {
+    const { subAnswers } = mergeSubAnswerSources([
+      { question: 'a', answer: 'A [1]', sources: [{ url: 'https://a.example' }] },
+      {
+        question: 'b',
+        answer: 'B [1] and [1](https://b.example)',
+        sources: [{ url: 'https://b.example' }],
+      },
+    ])
+    assert.equal(subAnswers[1].answer, 'B [2] and [1](https://b.example)')
+  })
+})
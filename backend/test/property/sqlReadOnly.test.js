@@ -0,0 +1,59 @@
+/**
+ * Property tests for the SQL connector's read-only guard: a write hidden behind a comment or
+ * literal that the database reads differently (Postgres "#" XOR, MySQL backslash escapes and
+ * "--" without a space, "--" inside a string) is rejected, while keywords that really are inside
+ * comments and literals are allowed
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { validateReadOnlyQuery } from '../../src/services/sqlConnectorService.js'
+import { forAll } from '../support/property.js'
+
+const TYPES = ['postgres', 'mysql', 'sqlite']
+const WRITES = ['DROP TABLE users', 'DELETE FROM users', 'COMMIT', "UPDATE users SET name = 'x'"]
+
+// Prefixes after which the database sees the rest as SQL, not a comment or a literal
+const SMUGGLERS = {
+  postgres: ['SELECT 1 # 1) q; ', "SELECT E'\\''; ", "SELECT '--'; ", "SELECT '/*'; "],
+  mysql: ["SELECT '\\''; ", 'SELECT 1 --1; ', "SELECT '#'; ", 'SELECT "\\""; '],
+  sqlite: ["SELECT '--'; ", "SELECT 'it''s'; ", 'SELECT "/*"; '],
+}
+
+// Comments and literals the database reads as such in every dialect
+const HIDERS = [
+  write => `SELECT '${write.replaceAll("'", "''")}' AS note`,
+  write => `SELECT 1 -- ${write}`,
+  write => `SELECT 1 /* ${write}; */ AS one`,
+]
+
+describe('sql read-only guard', () => {
+  it('rejects the Postgres "#" bypass', () => {
+    const sql = 'SELECT 1 # 1) q; COMMIT; DROP TABLE users; SELECT * FROM (SELECT 1'
+    assert.throws(() => validateReadOnlyQuery(sql, 'postgres'), /single statement/)
+  })
+
+  it('rejects writes smuggled past comment and literal rules', async () => {
+    await forAll(
+      random => {
+        const type = random.pick(TYPES)
+        return { type, sql: `${random.pick(SMUGGLERS[type])}${random.pick(WRITES)}` }
+      },
+      ({ type, sql }) => {
+        assert.throws(() => validateReadOnlyQuery(sql, type), { name: 'SqlConnectorError' })
+      },
+    )
+  })
+
+  it('allows write keywords inside comments and literals', async () => {
+    await forAll(
+      random => ({
+        type: random.pick(TYPES),
+        sql: random.pick(HIDERS)(random.pick(WRITES)),
+      }),
+      ({ type, sql }) => {
+        assert.equal(validateReadOnlyQuery(`${sql};`, type), sql)
+      },
+    )
+  })
+})
@@ -0,0 +1,75 @@
+/**
+ * Property tests for the upstream SSE decoder: random byte boundaries (splitting lines, CRLF
+ * pairs, and multi-byte UTF-8 characters), comments, and line-ending styles never lose or alter
+ * a message
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { readServerSentEvents } from '../../src/utils/sse.js'
+import { forAll, randomChunks } from '../support/property.js'
+
+const VALUE_PIECES = ['{"type":"delta"}', ' ', ':', 'data:', 'é', '中文', '😀', ' ', 'x', '[DONE]']
+const LINE_ENDINGS = ['\n', '\r\n', '\r']
+
+const generateValue = random =>
+  Array.from({ length: random.int(0, 6) }, () => random.pick(VALUE_PIECES)).join('')
+
+const generateStream = random => {
+  const ending = random.pick(LINE_ENDINGS)
+  const messages = Array.from({ length: random.int(0, 6) }, () => ({
+    event: random.bool() ? random.pick(['message_start', 'content_block_delta', 'ping']) : null,
+    lines: Array.from({ length: random.int(1, 3) }, () => generateValue(random)),
+  }))
+  let text = random.bool(0.3) ? `: keep-alive${ending}${ending}` : ''
+  messages.forEach((message, index) => {
+    if (random.bool(0.2)) text += `: comment${ending}`
+    if (message.event) text += `event: ${message.event}${ending}`
+    for (const line of message.lines) text += `data: ${line}${ending}`
+    // The last message may end without its blank line
+    if (index < messages.length - 1 || random.bool()) text += ending
+  })
+  const bytes = new TextEncoder().encode(text)
+  return { messages, chunks: randomChunks(random, bytes, 7) }
+}
+
+async function* toBody(chunks) {
+  for (const chunk of chunks) yield chunk
+}
+
+const decode = async chunks => {
+  const decoded = []
+  for await (const message of readServerSentEvents(toBody(chunks))) decoded.push(message)
+  return decoded
+}
+
+describe('readServerSentEvents', () => {
+  it('decodes every message whatever the byte boundaries', async () => {
+    await forAll(generateStream, async ({ messages, chunks }) => {
+      const decoded = await decode(chunks)
+      assert.deepEqual(
+        decoded.map(({ event, data }) => ({ event, data })),
+        messages.map(message => ({
+          event: message.event || 'message',
+          data: message.lines.join('\n'),
+        })),
+      )
+    })
+  })
+
+  it('strips exactly one space after the field colon', async () => {
+    const decoded = await decode(['data:  two spaces\n', 'data:none\n\n'])
+    assert.deepEqual(decoded, [{ event: 'message', data: ' two spaces\nnone', id: undefined }])
+  })
+
+  it('keeps a multi-byte character split across chunks', async () => {
+    const bytes = new TextEncoder().encode('data: 😀\n\n')
+    const decoded = await decode([...bytes].map(byte => Uint8Array.of(byte)))
+    assert.equal(decoded[0].data, '😀')
+  })
+
+  it('accepts string chunks and reports the event id', async () => {
+    const decoded = await decode(['id: 7\r', '\nevent: ping\r\ndata: {}\r\n\r\n'])
+    assert.deepEqual(decoded, [{ event: 'ping', data: '{}', id: '7' }])
+  })
+})
@@ -0,0 +1,149 @@
+/**
+ * Property tests for the <think> tag parser: however provider text is chunked, and wherever
+ * (partial, nested, mixed-case) tags land, nothing is lost or misclassified
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { createTaggedTextParser } from '../../src/utils/taggedText.js'
+import { forAll, randomChunks } from '../support/property.js'
+
+const OPEN_TAG = /<(think|thought)>/i
+const CLOSE_TAG = /<\/(think|thought)>/i
+
+const FRAGMENTS = [
+  '<think>',
+  '</think>',
+  '<THINK>',
+  '</Think>',
+  '<thought>',
+  '</thought>',
+  '<thinking>',
+  '<th',
+  'ink>',
+  '</',
+  '<',
+  '>',
+  '<<',
+  'think',
+  'Hello',
+  ' ',
+  '\n',
+  'é',
+  '中文',
+  '😀',
+  '`<think>`',
+]
+
+const generateText = random =>
+  Array.from({ length: random.int(0, 30) }, () =>
+    random.bool(0.6) ? random.pick(FRAGMENTS) : String.fromCharCode(random.int(32, 126)),
+  ).join('')
+
+// Adjacent pieces of the same kind are merged, so outputs compare independent of chunking
+const parse = (chunks, options = {}) => {
+  const segments = []
+  const push = type => text => {
+    assert.equal(typeof text, 'string')
+    assert.ok(text.length > 0, 'no empty emits')
+    const last = segments.at(-1)
+    if (last?.type === type) last.text += text
+    else segments.push({ type, text })
+  }
+  const handle = createTaggedTextParser({
+    emitText: push('text'),
+    emitThought: push('thought'),
+    ...options,
+  })
+  for (const chunk of chunks) handle(chunk)
+  handle.flush()
+  return segments
+}
+
+// Reference semantics on the whole string: tags toggle between text and thought
+const expectedSegments = input => {
+  const segments = []
+  let inThought = false
+  let rest = input
+  const push = text => {
+    const type = inThought ? 'thought' : 'text'
+    if (!text) return
+    const last = segments.at(-1)
+    if (last?.type === type) last.text += text
+    else segments.push({ type, text })
+  }
+  for (;;) {
+    const match = rest.match(inThought ? CLOSE_TAG : OPEN_TAG)
+    if (!match) {
+      push(rest)
+      return segments
+    }
+    push(rest.slice(0, match.index))
+    rest = rest.slice(match.index + match[0].length)
+    inThought = !inThought
+  }
+}
+
+describe('createTaggedTextParser', () => {
+  it('splits text and thoughts the same way for any chunking', async () => {
+    await forAll(
+      random => {
+        const input = generateText(random)
+        return { input, chunks: randomChunks(random, input, 10) }
+      },
+      ({ input, chunks }) => {
+        assert.deepEqual(parse(chunks), expectedSegments(input))
+      },
+    )
+  })
+
+  it('never emits an opening tag as answer text', async () => {
+    await forAll(
+      random => randomChunks(random, generateText(random), 6),
+      chunks => {
+        const handle = createTaggedTextParser({
+          emitText: text => assert.doesNotMatch(text, OPEN_TAG),
+          emitThought: () => {},
+        })
+        for (const chunk of chunks) handle(chunk)
+        handle.flush()
+      },
+    )
+  })
+
+  it('keeps every character that is not part of a tag', async () => {
+    await forAll(
+      random => randomChunks(random, generateText(random), 4),
+      chunks => {
+        const input = chunks.join('')
+        const emitted = parse(chunks)
+          .map(segment => segment.text)
+          .join('')
+        const tagged = input.replace(/<\/?(think|thought)>/gi, '')
+        assert.ok(emitted.length <= input.length)
+        // Tags inside a block of the other kind stay as content, so emitted can exceed tagged
+        assert.ok(emitted.length >= tagged.length)
+      },
+    )
+  })
+
+  it('emits a held-back partial tag on flush', () => {
+    assert.deepEqual(parse(['Answer <th']), [{ type: 'text', text: 'Answer <th' }])
+    assert.deepEqual(parse(['<think>still thinking </thi']), [
+      { type: 'thought', text: 'still thinking </thi' },
+    ])
+  })
+
+  it('passes everything through as text when tags are disabled', async () => {
+    await forAll(
+      random => randomChunks(random, generateText(random), 8),
+      chunks => {
+        const input = chunks.join('')
+        assert.deepEqual(
+          parse(chunks, { enableTags: false }),
+          input ? [{ type: 'text', text: input }] : [],
+        )
+      },
+    )
+  })
+})
@@ -0,0 +1,122 @@
+/**
+ * Property tests for the webpage reader: pages of extracted text always join back to the whole
+ * text within the page size, and main-content extraction keeps the article while dropping the
+ * navigation, sidebar, and footer around it
+ */
+
+import assert from 'node:assert/strict'
+import { describe, it } from 'node:test'
+import { extractReadableContent } from '../../src/services/htmlReadability.js'
+import { paginateMarkdown } from '../../src/services/webpageReaderService.js'
+import { forAll } from '../support/property.js'
+
+const TEXT = ['word', ' ', ', ', '.', '\n', '\n\n', '中文', '😀', '# Heading', '- item', '```']
+const WORDS = ['river', 'delta', 'sediment', 'survey', 'flood', 'basin', 'coastal', 'model']
+
+const sentence = random =>
+  Array.from({ length: random.int(6, 14) }, () => random.pick(WORDS)).join(' ') + '.'
+
+const generateArticle = random => ({
+  title: `Study ${random.int(1, 1000)}`,
+  author: random.pick(['Ada Lovelace', 'Grace Hopper', 'Katherine Johnson']),
+  published: `2024-0${random.int(1, 9)}-1${random.int(0, 9)}T08:00:00.000Z`,
+  paragraphs: Array.from({ length: random.int(3, 8) }, () =>
+    Array.from({ length: random.int(2, 5) }, () => sentence(random)).join(', '),
+  ),
+  navLinks: Array.from({ length: random.int(3, 8) }, (_, index) => `Section ${index}`),
+  related: Array.from({ length: random.int(2, 5) }, (_, index) => `Related story ${index}`),
+})
+
+// A link inside the paragraph, after its first comma
+const withLink = (text, link) => text.replace(', ', `, ${link} `)
+
+const toHtml = article => `<!DOCTYPE html>
+<html lang="en"><head>
+<title>${article.title} | Example News</title>
+<meta property="og:title" content="${article.title}">
+<meta name="author" content="${article.author}">
+<meta property="article:published_time" content="${article.published}">
+<link rel="canonical" href="/news/study">
+<script>var tracking = "Section 0";</script>
+</head><body>
+<nav class="menu">${article.navLinks.map(link => `<a href="/${link}">${link}</a>`).join(' ')}</nav>
+<div id="page"><article class="post-content">
+<h1>${article.title}</h1>
+${article.paragraphs.map(text => `<p>${withLink(text, '<a href="/ref">ref</a>')}</p>`).join('\n')}
+</article>
+<div class="sidebar"><ul>${article.related
+  .map(title => `<li><a href="/r">${title}</a></li>`)
+  .join('')}</ul></div></div>
+<footer>Copyright Example News</footer>
+</body></html>`
+
+describe('webpage reader pagination', () => {
+  it('splits text into pages within the size that join back to the text', async () => {
+    await forAll(
+      random => ({
+        text: Array.from({ length: random.int(0, 200) }, () => random.pick(TEXT)).join(''),
+        pageChars: random.int(1, 120),
+      }),
+      ({ text, pageChars }) => {
+        const pages = paginateMarkdown(text, pageChars)
+        assert.equal(pages.join(''), text)
+        assert.ok(pages.length >= 1)
+        pages.forEach((page, index) => {
+          assert.ok(page.length <= pageChars, `page ${index} has ${page.length} characters`)
+          if (pages.length > 1) assert.ok(page.length > 0)
+        })
+      },
+    )
+  })
+})
+
+describe('webpage reader extraction', () => {
+  it('keeps the article as Markdown and drops the page chrome', async () => {
+    await forAll(generateArticle, article => {
+      const { metadata, markdown } = extractReadableContent(
+        toHtml(article),
+        'https://news.example.com/news/study?id=1',
+      )
+      assert.equal(metadata.title, article.title)
+      assert.equal(metadata.author, article.author)
+      assert.equal(metadata.published_at, article.published)
+      assert.equal(metadata.lang, 'en')
+      assert.equal(metadata.canonical_url, 'https://news.example.com/news/study')
+      for (const text of article.paragraphs) {
+        assert.ok(
+          markdown.includes(withLink(text, '[ref](https://news.example.com/ref)')),
+          `missing paragraph: ${text}`,
+        )
+      }
+      for (const chrome of [...article.navLinks, ...article.related, 'Copyright']) {
+        assert.ok(!markdown.includes(chrome), `kept page chrome: ${chrome}`)
+      }
+      assert.ok(!markdown.startsWith(`# ${article.title}`))
+    })
+  })
+
+  it('converts headings, lists, code, tables, and emphasis', () => {
+    const html = `<html><body><main>
+      <h2>Setup</h2>
+      <p>Install the <strong>package</strong> with <em>care</em>, then run it, as usual.</p>
+      <ol><li>First step</li><li>Second step<ul><li>Nested</li></ul></li></ol>
+      <pre><code class="language-js">const a = 1 &lt; 2\nconsole.log(a)</code></pre>
+      <table><tr><th>Name</th><th>Value</th></tr><tr><td>a</td><td>1</td></tr></table>
+      <blockquote><p>Quoted text, with a comma, and more words to score.</p></blockquote>
+      <p><img src="/img.png" alt="Chart"> See <a href="#top">above</a>.</p>
+    </main></body></html>`
+    const { markdown } = extractReadableContent(html, 'https://example.com/docs/')
+    assert.equal(
+      markdown,
+      [
+        '## Setup',
+        'Install the **package** with _care_, then run it, as usual.',
+        '1. First step\n2. Second step\n\n   - Nested',
+        '```js\nconst a = 1 < 2\nconsole.log(a)\n```',
+        '| Name | Value |\n| --- | --- |\n| a | 1 |',
+        '> Quoted text, with a comma, and more words to score.',
+        '![Chart](https://example.com/img.png) See above.',
+      ].join('\n\n'),
+    )
+  })
+})
@@ -0,0 +1,289 @@
+/**
+ * Mock OpenAI-compatible server for the integration tests
+ * Serves POST /v1/chat/completions (JSON or SSE, depending on "stream"), GET /v1/models, a
+ * SearXNG-style GET /search from fixtures, POST /v1/audio/transcriptions (the transcript
+ * option, as whisper verbose_json or plain json), and POST /v1/images/generations (a 1x1 PNG,
+ * base64 when response_format is b64_json, otherwise a URL served from GET /files/generated.png),
+ * and records every request for assertions.
+ *
+ * A chat fixture is consumed by the first request it matches:
+ * {
+ *   when: 'task planner' | (body, text) => boolean (optional; a string must occur in the
+ *     request's messages, text is all message contents joined),
+ *   content: '...' (optional, streamed in chunks of chunkSize characters),
+ *   reasoning: '...' (optional, sent as reasoning_content),
+ *   toolCalls: [{ name: 'calculator', arguments: { expression: '2+3' } }] (optional),
+ *   usage: { prompt_tokens, completion_tokens, total_tokens } (optional),
+ *   status: 401, error: { message, type } (optional, answers with an HTTP error instead),
+ *   chunkSize: 8 (optional),
+ *   holdMs: 2000 (optional, streams only: wait this long after the first chunk, so a test can
+ *     act on a running stream)
+ * }
+ * Requests that no fixture matches get a 500, so a missing fixture fails the test loudly, unless
+ * a defaultFixture is set (it answers every unmatched request; used by the benchmarks).
+ */
+
+import http from 'http'
+
+const DEFAULT_USAGE = { prompt_tokens: 10, completion_tokens: 5, total_tokens: 15 }
+
+const messageText = message =>
+  typeof message?.content === 'string'
+    ? message.content
+    : (Array.isArray(message?.content) ? message.content : [])
+        .map(part => part?.text || '')
+        .join('')
+
+const readJson = req =>
+  new Promise((resolve, reject) => {
+    let raw = ''
+    req.on('data', chunk => {
+      raw += chunk
+    })
+    req.on('end', () => {
+      try {
+        resolve(raw ? JSON.parse(raw) : {})
+      } catch (error) {
+        reject(error)
+      }
+    })
+    req.on('error', reject)
+  })
+
+const splitChunks = (text, size) => {
+  const chunks = []
+  for (let i = 0; i < text.length; i += size) chunks.push(text.slice(i, i + size))
+  return chunks
+}
+
+const toToolCalls = (toolCalls = []) =>
+  toolCalls.map((call, index) => ({
+    index,
+    id: call.id || `call_${index + 1}`,
+    type: 'function',
+    function: {
+      name: call.name,
+      arguments:
+        typeof call.arguments === 'string' ? call.arguments : JSON.stringify(call.arguments || {}),
+    },
+  }))
+
+const completionJson = (fixture, model) => {
+  const toolCalls = toToolCalls(fixture.toolCalls)
+  return {
+    id: 'chatcmpl-mock',
+    object: 'chat.completion',
+    created: Math.floor(Date.now() / 1000),
+    model,
+    choices: [
+      {
+        index: 0,
+        message: {
+          role: 'assistant',
+          content: fixture.content ?? (toolCalls.length ? null : ''),
+          ...(fixture.reasoning ? { reasoning_content: fixture.reasoning } : {}),
+          ...(toolCalls.length ? { tool_calls: toolCalls.map(({ index, ...call }) => call) } : {}),
+        },
+        finish_reason: toolCalls.length ? 'tool_calls' : 'stop',
+      },
+    ],
+    usage: fixture.usage || DEFAULT_USAGE,
+  }
+}
+
+const writeCompletionStream = async (res, fixture, model) => {
+  const chunk = (delta, finishReason = null, extra = {}) =>
+    res.write(
+      `data: ${JSON.stringify({
+        id: 'chatcmpl-mock',
+        object: 'chat.completion.chunk',
+        created: Math.floor(Date.now() / 1000),
+        model,
+        choices: [{ index: 0, delta, finish_reason: finishReason }],
+        ...extra,
+      })}\n\n`,
+    )
+  const size = fixture.chunkSize || 8
+  const toolCalls = toToolCalls(fixture.toolCalls)
+
+  res.writeHead(200, { 'Content-Type': 'text/event-stream', 'Cache-Control': 'no-cache' })
+  chunk({ role: 'assistant', content: '' })
+  if (fixture.holdMs) {
+    await new Promise(resolve => setTimeout(resolve, fixture.holdMs))
+    // The client gave up (e.g. the stream was cancelled) while we were holding
+    if (res.destroyed) return
+  }
+  for (const piece of splitChunks(fixture.reasoning || '', size)) {
+    chunk({ reasoning_content: piece })
+  }
+  for (const piece of splitChunks(fixture.content || '', size)) {
+    chunk({ content: piece })
+  }
+  // Tool calls arrive as a header chunk (id, name) followed by argument fragments
+  for (const call of toolCalls) {
+    const { name } = call.function
+    chunk({
+      tool_calls: [{ index: call.index, id: call.id, type: 'function', function: { name } }],
+    })
+    for (const piece of splitChunks(call.function.arguments, size)) {
+      chunk({ tool_calls: [{ index: call.index, function: { arguments: piece } }] })
+    }
+  }
+  chunk({}, toolCalls.length ? 'tool_calls' : 'stop', { usage: fixture.usage || DEFAULT_USAGE })
+  res.write('data: [DONE]\n\n')
+  res.end()
+}
+
+// Multipart form fields; files are recorded as { name, type, size }
+const readForm = async req => {
+  const chunks = []
+  for await (const chunk of req) chunks.push(chunk)
+  const form = await new Response(Buffer.concat(chunks), {
+    headers: { 'Content-Type': req.headers['content-type'] || '' },
+  }).formData()
+  const fields = {}
+  for (const [name, value] of form) {
+    fields[name] =
+      typeof value === 'string' ? value : { name: value.name, type: value.type, size: value.size }
+  }
+  return fields
+}
+
+const transcriptionJson = (transcript, fields) => {
+  if (fields.response_format !== 'verbose_json') return { text: transcript }
+  const words = transcript.split(' ')
+  const half = Math.ceil(words.length / 2)
+  return {
+    text: transcript,
+    language: 'english',
+    duration: 2.5,
+    segments: [
+      { id: 0, start: 0, end: 1.2, text: ` ${words.slice(0, half).join(' ')}` },
+      { id: 1, start: 1.2, end: 2.5, text: ` ${words.slice(half).join(' ')}` },
+    ],
+  }
+}
+
+// 1x1 transparent PNG
+const GENERATED_PNG = Buffer.from(
+  'iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==',
+  'base64',
+)
+
+const imagesJson = (body, root) => ({
+  created: Math.floor(Date.now() / 1000),
+  data: Array.from({ length: body.n || 1 }, () =>
+    body.response_format === 'b64_json'
+      ? { b64_json: GENERATED_PNG.toString('base64'), revised_prompt: `Revised: ${body.prompt}` }
+      : { url: `${root}/files/generated.png` },
+  ),
+})
+
+const sendJson = (res, status, body) => {
+  res.writeHead(status, { 'Content-Type': 'application/json' })
+  res.end(JSON.stringify(body))
+}
+
+/**
+ * Start the mock server on an ephemeral localhost port
+ * @param {Object} [options]
+ * @param {Array<Object>} [options.fixtures] - Chat fixtures (see above)
+ * @param {Array<Object>} [options.searchResults] - Results of every GET /search
+ * @param {Object} [options.defaultFixture] - Answers requests no queued fixture matches
+ * @param {boolean} [options.record] - false keeps chat request bodies out of memory (benchmarks)
+ * @param {string} [options.transcript] - Text of every audio transcription
+ * @returns {Promise<Object>} { baseUrl, url, requests, chatRequests, enqueue, pending, close };
+ *   baseUrl ends in /v1 (the provider baseUrl), url is the server root (a SearXNG searchBaseUrl)
+ */
+export const startMockLlmServer = async ({
+  fixtures = [],
+  searchResults = [],
+  defaultFixture = null,
+  record = true,
+  transcript = 'Mock transcript of the recording.',
+} = {}) => {
+  const queue = [...fixtures]
+  const requests = []
+
+  const takeFixture = body => {
+    const text = (body.messages || []).map(messageText).join('\n')
+    const index = queue.findIndex(fixture =>
+      typeof fixture.when === 'function'
+        ? fixture.when(body, text)
+        : !fixture.when || text.includes(fixture.when),
+    )
+    return index === -1 ? defaultFixture : queue.splice(index, 1)[0]
+  }
+
+  const server = http.createServer(async (req, res) => {
+    const url = new URL(req.url, 'http://localhost')
+    try {
+      if (req.method === 'GET' && url.pathname === '/v1/models') {
+        requests.push({ method: req.method, path: url.pathname })
+        return sendJson(res, 200, {
+          object: 'list',
+          data: [{ id: 'mock-model', object: 'model', owned_by: 'mock' }],
+        })
+      }
+      if (req.method === 'GET' && url.pathname === '/search') {
+        requests.push({ method: req.method, path: url.pathname, query: url.searchParams.get('q') })
+        return sendJson(res, 200, { query: url.searchParams.get('q'), results: searchResults })
+      }
+      if (req.method === 'POST' && url.pathname === '/v1/chat/completions') {
+        const body = await readJson(req)
+        if (record) requests.push({ method: req.method, path: url.pathname, body })
+        const fixture = takeFixture(body)
+        if (!fixture) {
+          return sendJson(res, 500, {
+            error: { message: 'No mock fixture matches this request', type: 'mock_error' },
+          })
+        }
+        if (fixture.status) {
+          return sendJson(res, fixture.status, {
+            error: fixture.error || { message: `Mock error ${fixture.status}`, type: 'mock_error' },
+          })
+        }
+        const model = body.model || 'mock-model'
+        return body.stream
+          ? await writeCompletionStream(res, fixture, model)
+          : sendJson(res, 200, completionJson(fixture, model))
+      }
+      if (req.method === 'POST' && url.pathname === '/v1/audio/transcriptions') {
+        const fields = await readForm(req)
+        requests.push({ method: req.method, path: url.pathname, fields })
+        return sendJson(res, 200, transcriptionJson(transcript, fields))
+      }
+      if (req.method === 'POST' && url.pathname === '/v1/images/generations') {
+        const body = await readJson(req)
+        requests.push({ method: req.method, path: url.pathname, body })
+        return sendJson(res, 200, imagesJson(body, `http://${req.headers.host}`))
+      }
+      if (req.method === 'GET' && url.pathname === '/files/generated.png') {
+        requests.push({ method: req.method, path: url.pathname })
+        res.writeHead(200, { 'Content-Type': 'image/png' })
+        return res.end(GENERATED_PNG)
+      }
+      sendJson(res, 404, { error: { message: `Not found: ${url.pathname}` } })
+    } catch (error) {
+      sendJson(res, 500, { error: { message: error.message, type: 'mock_error' } })
+    }
+  })
+
+  await new Promise(resolve => server.listen(0, '127.0.0.1', resolve))
+  const root = `http://127.0.0.1:${server.address().port}`
+  return {
+    url: root,
+    baseUrl: `${root}/v1`,
+    requests,
+    /** Chat completion requests received so far */
+    chatRequests: () => requests.filter(request => request.path === '/v1/chat/completions'),
+    enqueue: (...more) => queue.push(...more),
+    /** Fixtures not consumed yet */
+    pending: () => [...queue],
+    close: () =>
+      new Promise(resolve => {
+        server.closeAllConnections?.()
+        server.close(() => resolve())
+      }),
+  }
+}
@@ -0,0 +1,63 @@
+/**
+ * Minimal property-based testing helpers for node:test
+ * Cases come from a seeded PRNG, so a failure can be replayed: it reports its seed, and
+ * QURIO_PROPERTY_SEED / QURIO_PROPERTY_RUNS override the seed and the number of cases.
+ */
+
+// mulberry32
+export const createRandom = seed => {
+  let state = seed >>> 0
+  const next = () => {
+    state = (state + 0x6d2b79f5) >>> 0
+    let t = state
+    t = Math.imul(t ^ (t >>> 15), t | 1)
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61)
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296
+  }
+  const int = (min, max) => min + Math.floor(next() * (max - min + 1))
+  return {
+    next,
+    int,
+    bool: (probability = 0.5) => next() < probability,
+    pick: items => items[int(0, items.length - 1)],
+  }
+}
+
+/**
+ * Split a string or byte array into random consecutive pieces (empty pieces included)
+ * @returns {Array} Pieces whose concatenation is the input
+ */
+export const randomChunks = (random, input, maxSize = 8) => {
+  const pieces = []
+  let offset = 0
+  while (offset < input.length) {
+    const size = random.int(0, maxSize)
+    pieces.push(input.slice(offset, offset + size))
+    offset += size
+  }
+  return pieces
+}
+
+/**
+ * Check a property on many generated cases; throws with the failing seed and case
+ * @param {(random: Object) => *} generate
+ * @param {(value: *) => void | Promise<void>} check - Throws (e.g. assert) when the property fails
+ * @param {Object} [options]
+ * @param {number} [options.runs] - Default 200
+ * @param {number} [options.seed] - Seed of the first case; case n uses seed + n
+ */
+export const forAll = async (generate, check, { runs = 200, seed = Date.now() } = {}) => {
+  const totalRuns = Number.parseInt(process.env.QURIO_PROPERTY_RUNS, 10) || runs
+  const firstSeed = Number.parseInt(process.env.QURIO_PROPERTY_SEED, 10) || seed
+  for (let run = 0; run < totalRuns; run += 1) {
+    const caseSeed = firstSeed + run
+    const value = generate(createRandom(caseSeed))
+    try {
+      await check(value)
+    } catch (error) {
+      const replay = `QURIO_PROPERTY_SEED=${caseSeed} QURIO_PROPERTY_RUNS=1`
+      error.message = `${error.message}\nProperty failed (${replay}) for ${JSON.stringify(value)}`
+      throw error
+    }
+  }
+}
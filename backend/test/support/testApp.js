@@ -0,0 +1,105 @@
+/**
+ * Integration test app
+ * Mounts the real Express app (src/app.js) on an ephemeral localhost port with its data
+ * directory in a fresh temp dir, and collects SSE responses into event arrays.
+ */
+
+import fs from 'fs'
+import os from 'os'
+import path from 'path'
+import { WebSocket } from 'ws'
+
+/**
+ * Parse an SSE body into its JSON events (comments and heartbeats are skipped)
+ * @param {string} text
+ * @returns {Array<Object>}
+ */
+export const parseSseEvents = text =>
+  text
+    .split(/\r?\n\r?\n/)
+    .map(block =>
+      block
+        .split(/\r?\n/)
+        .filter(line => line.startsWith('data:'))
+        .map(line => line.slice(5).trimStart())
+        .join('\n'),
+    )
+    .filter(data => data && data !== '[DONE]')
+    .map(data => JSON.parse(data))
+
+/**
+ * Start the app
+ * @param {Object} [options]
+ * @param {Object} [options.env] - Environment overrides, applied before the app is imported
+ * @returns {Promise<Object>} { baseUrl, dataDir, request, postJson, postSse, chatOverWebSocket,
+ *   close }
+ */
+export const startTestApp = async ({ env = {} } = {}) => {
+  const dataDir = fs.mkdtempSync(path.join(os.tmpdir(), 'qurio-test-'))
+  Object.assign(process.env, {
+    QURIO_DATA_DIR: dataDir,
+    // Flush SSE writes immediately so event order matches the service's
+    SSE_FLUSH_MS: '0',
+    ...env,
+  })
+
+  const { createApp } = await import('../../src/app.js')
+  const { attachChatWebSocket } = await import('../../src/routes/wsChat.js')
+  const app = createApp()
+  const server = app.listen(0, '127.0.0.1')
+  attachChatWebSocket(server, app)
+  await new Promise((resolve, reject) => {
+    server.once('listening', resolve)
+    server.once('error', reject)
+  })
+  const baseUrl = `http://127.0.0.1:${server.address().port}`
+
+  const request = (method, urlPath, body) =>
+    fetch(`${baseUrl}${urlPath}`, {
+      method,
+      headers: body === undefined ? {} : { 'Content-Type': 'application/json' },
+      body: body === undefined ? undefined : JSON.stringify(body),
+    })
+
+  return {
+    baseUrl,
+    dataDir,
+    request,
+    /** POST a JSON body and read a JSON response: { status, body } */
+    postJson: async (urlPath, body) => {
+      const response = await request('POST', urlPath, body)
+      return { status: response.status, body: await response.json() }
+    },
+    /** POST a JSON body to a streaming route and read the whole stream: { status, events } */
+    postSse: async (urlPath, body) => {
+      const response = await request('POST', urlPath, body)
+      const text = await response.text()
+      const isStream = response.headers.get('content-type')?.includes('text/event-stream')
+      return {
+        status: response.status,
+        events: isStream ? parseSseEvents(text) : [],
+        body: isStream ? null : JSON.parse(text || 'null'),
+      }
+    },
+    /**
+     * Send a request as the first frame of GET /api/ws/chat and read frames until the server
+     * closes: { events, closeCode }
+     */
+    chatOverWebSocket: (body, { headers } = {}) =>
+      new Promise((resolve, reject) => {
+        const socket = new WebSocket(`${baseUrl.replace(/^http/, 'ws')}/api/ws/chat`, { headers })
+        const events = []
+        socket.on('open', () => socket.send(typeof body === 'string' ? body : JSON.stringify(body)))
+        socket.on('message', data => events.push(JSON.parse(data.toString())))
+        socket.on('close', closeCode => resolve({ events, closeCode }))
+        socket.on('error', reject)
+      }),
+    close: async () => {
+      await new Promise(resolve => {
+        server.closeAllConnections?.()
+        server.close(() => resolve())
+      })
+      fs.rmSync(dataDir, { recursive: true, force: true })
+    },
+  }
+}